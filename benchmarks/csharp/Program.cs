@@ -49,6 +49,15 @@ public static class MainClass
 
         [Option('m', "minimal", HelpText = "Should use a minimal number of actions")]
         public bool Minimal { get; set; } = false;
+
+        [Option("noTcpNoDelay", HelpText = "Should enable Nagle's algorithm (disable TCP_NODELAY) in the glide client")]
+        public bool NoTcpNoDelay { get; set; } = false;
+
+        [Option("connectionsPerNode", Required = false, HelpText = "Number of connections to every node in the glide client")]
+        public uint ConnectionsPerNode { get; set; } = 1;
+
+        [Option("largeDataSize", Required = false, HelpText = "The size of the data in bytes of a few keys written before the run, which a share of the GET commands read, so GET replies mix small and large values. Disabled if 0.")]
+        public int LargeDataSize { get; set; } = 0;
     }
 
     private static string GetAddress(string host, ushort port) => $"{host}:{port}";
@@ -60,6 +69,8 @@ public static class MainClass
     private const double PROB_GET_EXISTING_KEY = 0.8;
     private const int SIZE_GET_KEYSPACE = 3750000; // 3.75 million
     private const int SIZE_SET_KEYSPACE = 3000000; // 3 million
+    private const double PROB_GET_LARGE_KEY = 0.05;
+    private const int LARGE_KEY_COUNT = 10;
 
     private static readonly Random Randomizer = new();
     private static long s_started_tasks_counter = 0;
@@ -70,6 +81,13 @@ public static class MainClass
     private static string GenerateKeySet() => (Randomizer.Next(SIZE_SET_KEYSPACE) + 1).ToString();
     private static string GenerateKeyGet() => (Randomizer.Next(SIZE_SET_KEYSPACE, SIZE_GET_KEYSPACE) + 1).ToString();
 
+    private static string GenerateKeyLarge(int index) => $"large-{index}";
+
+    private static string ChooseKeyGetExisting(bool readLargeKeys) =>
+        readLargeKeys && Randomizer.NextDouble() < PROB_GET_LARGE_KEY
+            ? GenerateKeyLarge(Randomizer.Next(LARGE_KEY_COUNT))
+            : GenerateKeySet();
+
     private static ChosenAction ChooseAction() =>
         Randomizer.NextDouble() > PROB_GET
             ? ChosenAction.SET
@@ -109,6 +127,7 @@ public static class MainClass
         ClientWrapper[] clients,
         long total_commands,
         string data,
+        bool read_large_keys,
         Dictionary<ChosenAction, ConcurrentBag<double>> action_latencies)
     {
         Stopwatch stopwatch = new();
@@ -122,7 +141,7 @@ public static class MainClass
             switch (action)
             {
                 case ChosenAction.GET_EXISTING:
-                    _ = await client.Get(GenerateKeySet());
+                    _ = await client.Get(ChooseKeyGetExisting(read_large_keys));
                     break;
                 case ChosenAction.GET_NON_EXISTING:
                     _ = await client.Get(GenerateKeyGet());
@@ -143,6 +162,7 @@ public static class MainClass
         ClientWrapper[] clients,
         int total_commands,
         string data,
+        bool read_large_keys,
         int num_of_concurrent_tasks,
         Dictionary<ChosenAction, ConcurrentBag<double>> action_latencies
     )
@@ -153,7 +173,7 @@ public static class MainClass
         for (int i = 0; i < num_of_concurrent_tasks; i++)
         {
             running_tasks.Add(
-                RedisBenchmark(clients, total_commands, data, action_latencies)
+                RedisBenchmark(clients, total_commands, data, read_large_keys, action_latencies)
             );
         }
         await Task.WhenAll(running_tasks);
@@ -179,6 +199,8 @@ public static class MainClass
         bool isCluster,
         int total_commands,
         int data_size,
+        int large_data_size,
+        uint connections_per_node,
         int num_of_concurrent_tasks
     )
     {
@@ -189,10 +211,20 @@ public static class MainClass
             {ChosenAction.SET, new()},
         };
         string data = GenerateValue(data_size);
+        bool read_large_keys = large_data_size > 0;
+        if (read_large_keys)
+        {
+            string large_data = GenerateValue(large_data_size);
+            for (int i = 0; i < LARGE_KEY_COUNT; i++)
+            {
+                await clients[0].Set(GenerateKeyLarge(i), large_data);
+            }
+        }
         long elapsed_milliseconds = await CreateBenchTasks(
             clients,
             total_commands,
             data,
+            read_large_keys,
             num_of_concurrent_tasks,
             action_latencies
         );
@@ -212,6 +244,8 @@ public static class MainClass
             {"client", client_name},
             {"num_of_tasks", num_of_concurrent_tasks},
             {"data_size", data_size},
+            {"large_data_size", large_data_size},
+            {"connections_per_node", connections_per_node},
             {"tps", tps},
             {"client_count", clients.Length},
             {"is_cluster", isCluster}
@@ -262,7 +296,10 @@ public static class MainClass
         ushort port,
         int clientCount,
         bool useTLS,
-        bool isCluster)
+        bool isCluster,
+        bool tcpNoDelay,
+        uint connectionsPerNode,
+        int largeDataSize)
     {
         if (clientsToRun is "all" or "glide")
         {
@@ -272,13 +309,15 @@ public static class MainClass
                 if (!isCluster)
                 {
                     StandaloneClientConfiguration config = new StandaloneClientConfigurationBuilder()
-                        .WithAddress(host, port).WithTls(useTLS).Build();
+                        .WithAddress(host, port).WithTls(useTLS).WithTcpNoDelay(tcpNoDelay)
+                        .WithConnectionsPerNode(connectionsPerNode).Build();
                     glideClient = new GlideClient(config);
                 }
                 else
                 {
                     ClusterClientConfiguration config = new ClusterClientConfigurationBuilder()
-                        .WithAddress(host, port).WithTls(useTLS).Build();
+                        .WithAddress(host, port).WithTls(useTLS).WithTcpNoDelay(tcpNoDelay)
+                        .WithConnectionsPerNode(connectionsPerNode).Build();
                     glideClient = new GlideClusterClient(config);
                 }
                 return Task.FromResult<(Func<string, Task<string?>>, Func<string, string, Task>, Action)>(
@@ -293,6 +332,8 @@ public static class MainClass
                 isCluster,
                 total_commands,
                 data_size,
+                largeDataSize,
+                connectionsPerNode,
                 num_of_concurrent_tasks
             );
         }
@@ -314,6 +355,8 @@ public static class MainClass
                 isCluster,
                 total_commands,
                 data_size,
+                largeDataSize,
+                1,
                 num_of_concurrent_tasks
             );
 
@@ -338,7 +381,7 @@ public static class MainClass
         foreach ((int concurrentTasks, int dataSize, int clientCount) in product)
         {
             int iterations = options.Minimal ? 1000 : NumberOfIterations(concurrentTasks);
-            await RunWithParameters(iterations, dataSize, concurrentTasks, options.ClientsToRun, options.Host, options.Port, clientCount, options.Tls, options.ClusterMode);
+            await RunWithParameters(iterations, dataSize, concurrentTasks, options.ClientsToRun, options.Host, options.Port, clientCount, options.Tls, options.ClusterMode, !options.NoTcpNoDelay, options.ConnectionsPerNode, options.LargeDataSize);
         }
 
         PrintResults(options.ResultsFile);
@@ -0,0 +1,113 @@
+// Copyright Valkey GLIDE Project Contributors - SPDX Identifier: Apache-2.0
+
+//! Compares the throughput of `GET` commands whose replies mix small and large values, by a single connection
+//! and by several connections to the node, see `connections_per_node`.
+//! The server is a mock which runs the commands one at a time like Valkey, so only the connections differ.
+
+use std::{
+    io::{BufRead, BufReader, Write},
+    net::{TcpListener, TcpStream},
+    sync::{Arc, Mutex},
+};
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use glide_rs::benchmarks::Connections;
+
+const SMALL_VALUE_SIZE: usize = 100;
+const LARGE_VALUE_SIZE: usize = 1_000_000;
+/// Commands sent concurrently per iteration, of which every 20th reads a large value, like 5% of the `GET` commands
+/// of the C# benchmark with `--largeDataSize`.
+const COMMANDS: usize = 200;
+const LARGE_EVERY: usize = 20;
+
+/// Start a server on a port of the loopback interface, which replies to `GET` of `large-*` keys by a large value,
+/// to other `GET` commands by a small value, to `INFO` by the role of a primary, and to every other command by `OK`.
+fn start_server() -> u16 {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let port = listener.local_addr().unwrap().port();
+    // Commands of all connections are run one at a time, the replies are written concurrently.
+    let execution = Arc::new(Mutex::new(()));
+    std::thread::spawn(move || {
+        for stream in listener.incoming() {
+            let stream = stream.unwrap();
+            let execution = execution.clone();
+            std::thread::spawn(move || serve(stream, &execution));
+        }
+    });
+    port
+}
+
+fn serve(stream: TcpStream, execution: &Mutex<()>) {
+    let small = bulk_string(SMALL_VALUE_SIZE);
+    let large = bulk_string(LARGE_VALUE_SIZE);
+    let mut writer = stream.try_clone().unwrap();
+    let mut reader = BufReader::new(stream);
+    while let Some(args) = read_command(&mut reader) {
+        let reply = {
+            let _running = execution.lock().unwrap();
+            match (args[0].to_ascii_uppercase().as_slice(), args.get(1)) {
+                (b"GET", Some(key)) if key.starts_with(b"large-") => large.clone(),
+                (b"GET", _) => small.clone(),
+                (b"INFO", _) => b"$11\r\nrole:master\r\n".to_vec(),
+                _ => b"+OK\r\n".to_vec(),
+            }
+        };
+        if writer.write_all(&reply).is_err() {
+            return;
+        }
+    }
+}
+
+fn bulk_string(size: usize) -> Vec<u8> {
+    let mut reply = format!("${size}\r\n").into_bytes();
+    reply.resize(reply.len() + size, b'0');
+    reply.extend_from_slice(b"\r\n");
+    reply
+}
+
+/// Read a command sent as an array of bulk strings, `None` once the connection is closed.
+fn read_command(reader: &mut impl BufRead) -> Option<Vec<Vec<u8>>> {
+    let count = read_number(reader, b'*')?;
+    (0..count)
+        .map(|_| {
+            let len = read_number(reader, b'$')?;
+            let mut arg = vec![0; len + 2];
+            reader.read_exact(&mut arg).ok()?;
+            arg.truncate(len);
+            Some(arg)
+        })
+        .collect()
+}
+
+fn read_number(reader: &mut impl BufRead, prefix: u8) -> Option<usize> {
+    let mut line = String::new();
+    reader.read_line(&mut line).ok().filter(|read| *read > 0)?;
+    assert_eq!(line.as_bytes()[0], prefix, "Unexpected RESP type");
+    line[1..].trim_end().parse().ok()
+}
+
+fn benchmark_connections_per_node(c: &mut Criterion) {
+    let port = start_server();
+    let keys: Vec<String> = (0..COMMANDS)
+        // The large values move by one position per block of commands, so they are spread over the connections,
+        // which the commands are assigned to in turn
+        .map(|index| match (index / LARGE_EVERY + index) % LARGE_EVERY {
+            0 => format!("large-{index}"),
+            _ => format!("small-{index}"),
+        })
+        .collect();
+    let mut group = c.benchmark_group("mixed replies");
+    group.sample_size(30);
+    for connections_per_node in [1, 4] {
+        let connections =
+            Connections::connect("127.0.0.1", port, connections_per_node, false).unwrap();
+        group.bench_function(
+            format!("connections_per_node {connections_per_node}"),
+            |b| b.iter(|| connections.get_all(&keys).unwrap()),
+        );
+    }
+    group.finish();
+}
+
+criterion_group!(benches, benchmark_connections_per_node);
+criterion_main!(benches);
@@ -0,0 +1,61 @@
+// Copyright Valkey GLIDE Project Contributors - SPDX Identifier: Apache-2.0
+
+//! Compares routing keyless commands of a client with a key prefix by looking up their keys, to routing them as is
+//! when the caller declares them `keyless`, see the `keyless` argument of `command`.
+
+use criterion::{black_box, criterion_group, criterion_main, BatchSize, Criterion};
+use glide_rs::benchmarks::KeyPrefix;
+use redis::{Cmd, Value};
+
+/// A response of `COMMAND` by the legacy command info: name, arity, flags, first key, last key and step.
+fn commands() -> Value {
+    let info = |name: &str, arity: i64, first_key: i64, last_key: i64, step: i64| {
+        Value::Array(vec![
+            Value::BulkString(name.as_bytes().to_vec()),
+            Value::Int(arity),
+            Value::Array(vec![]),
+            Value::Int(first_key),
+            Value::Int(last_key),
+            Value::Int(step),
+        ])
+    };
+    Value::Array(vec![
+        info("get", 2, 1, 1, 1),
+        info("set", -3, 1, 1, 1),
+        info("mget", -2, 1, -1, 1),
+        info("ping", -1, 0, 0, 0),
+        info("publish", 3, 0, 0, 0),
+        info("dbsize", 1, 0, 0, 0),
+    ])
+}
+
+fn keyless_commands() -> [(&'static str, Cmd); 3] {
+    [
+        ("ping", redis::cmd("PING")),
+        ("dbsize", redis::cmd("DBSIZE")),
+        (
+            "publish",
+            redis::cmd("PUBLISH").arg("channel").arg("message").clone(),
+        ),
+    ]
+}
+
+fn benchmark_routes(c: &mut Criterion) {
+    let key_prefix = KeyPrefix::new("tenant:", commands()).unwrap();
+    for (route_name, keyless) in [("prefixed", false), ("keyless", true)] {
+        let mut group = c.benchmark_group(format!("route {route_name}"));
+        for (command_name, cmd) in keyless_commands() {
+            group.bench_function(command_name, |b| {
+                b.iter_batched(
+                    || cmd.clone(),
+                    |cmd| black_box(key_prefix.route(cmd, keyless)),
+                    BatchSize::SmallInput,
+                )
+            });
+        }
+        group.finish();
+    }
+}
+
+criterion_group!(benches, benchmark_routes);
+criterion_main!(benches);
@@ -0,0 +1,72 @@
+// Copyright Valkey GLIDE Project Contributors - SPDX Identifier: Apache-2.0
+
+//! Compares allocating a response per reply to reusing pooled allocations, see `response_pool_capacity`.
+//! Besides the timings, the allocations per response are counted and printed, since the pool is meant to reduce them.
+
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use glide_rs::benchmarks::ResponsePool;
+use redis::Value;
+
+struct CountingAllocator;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        unsafe { System.alloc(layout) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(ptr, layout) }
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+const RESPONSES: usize = 100_000;
+
+fn replies() -> [(&'static str, Value); 2] {
+    [
+        ("int", Value::Int(42)),
+        ("bulk string", Value::BulkString(b"value".to_vec())),
+    ]
+}
+
+/// Print the allocations per response of every reply, once the pool is warm.
+fn count_allocations(pool: &ResponsePool, name: &str, value: &Value) {
+    pool.respond(value.clone());
+    let before = ALLOCATIONS.load(Ordering::Relaxed);
+    for _ in 0..RESPONSES {
+        // The clone of the reply is allocated in both cases, since glide-core hands out an owned value
+        pool.respond(value.clone());
+    }
+    let allocations = ALLOCATIONS.load(Ordering::Relaxed) - before;
+    println!(
+        "{name}: {:.2} allocations per response",
+        allocations as f64 / RESPONSES as f64
+    );
+}
+
+fn benchmark_responses(c: &mut Criterion) {
+    for (pool_name, capacity) in [("unpooled", 0), ("pooled", 1024)] {
+        let pool = ResponsePool::new(capacity);
+        let mut group = c.benchmark_group(format!("response {pool_name}"));
+        for (reply_name, value) in replies() {
+            count_allocations(&pool, &format!("{pool_name} {reply_name}"), &value);
+            group.bench_function(reply_name, |b| {
+                b.iter(|| pool.respond(black_box(value.clone())))
+            });
+        }
+        group.finish();
+    }
+}
+
+criterion_group!(benches, benchmark_responses);
+criterion_main!(benches);
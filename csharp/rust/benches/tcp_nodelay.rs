@@ -0,0 +1,102 @@
+// Copyright Valkey GLIDE Project Contributors - SPDX Identifier: Apache-2.0
+
+//! Compares the latency of small `GET` commands with Nagle's algorithm enabled and disabled on the connections of the client,
+//! see `tcp_nodelay`. The server is a mock which disables Nagle's algorithm itself, so only the client differs.
+//! It delays its replies like a remote server, since over the loopback interface the acknowledgements arrive before
+//! the next command is written, so Nagle's algorithm wouldn't delay the commands.
+
+use std::{
+    io::{BufRead, BufReader, Write},
+    net::{TcpListener, TcpStream},
+    sync::mpsc::channel,
+    time::{Duration, Instant},
+};
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use glide_rs::benchmarks::Connections;
+
+/// Commands sent concurrently per iteration of the concurrent benchmark, which are written by separate writes
+/// while the previous ones aren't acknowledged yet, so Nagle's algorithm may delay them.
+const CONCURRENT_COMMANDS: usize = 10;
+
+/// Delay of every reply after its command is read, like the round trip to a server in the same zone.
+const ROUND_TRIP: Duration = Duration::from_micros(500);
+
+/// Start a server on a port of the loopback interface, which replies to `GET` by a small value, to `INFO` by the role of
+/// a primary, and to every other command by `OK`. Every reply is written [`ROUND_TRIP`] after its command is read.
+fn start_server() -> u16 {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let port = listener.local_addr().unwrap().port();
+    std::thread::spawn(move || {
+        for stream in listener.incoming() {
+            let stream = stream.unwrap();
+            stream.set_nodelay(true).unwrap();
+            std::thread::spawn(move || serve(stream));
+        }
+    });
+    port
+}
+
+fn serve(stream: TcpStream) {
+    let mut writer = stream.try_clone().unwrap();
+    let (replies, delayed_replies) = channel::<(Instant, &'static [u8])>();
+    std::thread::spawn(move || {
+        for (due, reply) in delayed_replies {
+            std::thread::sleep(due.saturating_duration_since(Instant::now()));
+            if writer.write_all(reply).is_err() {
+                return;
+            }
+        }
+    });
+    let mut reader = BufReader::new(stream);
+    while let Some(args) = read_command(&mut reader) {
+        let reply: &[u8] = match args[0].to_ascii_uppercase().as_slice() {
+            b"GET" => b"$5\r\nvalue\r\n",
+            b"INFO" => b"$11\r\nrole:master\r\n",
+            _ => b"+OK\r\n",
+        };
+        if replies.send((Instant::now() + ROUND_TRIP, reply)).is_err() {
+            return;
+        }
+    }
+}
+
+/// Read a command sent as an array of bulk strings, `None` once the connection is closed.
+fn read_command(reader: &mut impl BufRead) -> Option<Vec<Vec<u8>>> {
+    let count = read_number(reader, b'*')?;
+    (0..count)
+        .map(|_| {
+            let len = read_number(reader, b'$')?;
+            let mut arg = vec![0; len + 2];
+            reader.read_exact(&mut arg).ok()?;
+            arg.truncate(len);
+            Some(arg)
+        })
+        .collect()
+}
+
+fn read_number(reader: &mut impl BufRead, prefix: u8) -> Option<usize> {
+    let mut line = String::new();
+    reader.read_line(&mut line).ok().filter(|read| *read > 0)?;
+    assert_eq!(line.as_bytes()[0], prefix, "Unexpected RESP type");
+    line[1..].trim_end().parse().ok()
+}
+
+fn benchmark_tcp_nodelay(c: &mut Criterion) {
+    let port = start_server();
+    let keys: Vec<String> = (0..CONCURRENT_COMMANDS)
+        .map(|index| format!("key-{index}"))
+        .collect();
+    for tcp_nodelay in [true, false] {
+        let connections = Connections::connect("127.0.0.1", port, 1, tcp_nodelay).unwrap();
+        let mut group = c.benchmark_group(format!("tcp_nodelay {tcp_nodelay}"));
+        group.bench_function("GET", |b| b.iter(|| connections.get("key").unwrap()));
+        group.bench_function(format!("{CONCURRENT_COMMANDS} concurrent GET"), |b| {
+            b.iter(|| connections.get_all(&keys).unwrap())
+        });
+        group.finish();
+    }
+}
+
+criterion_group!(benches, benchmark_tcp_nodelay);
+criterion_main!(benches);
@@ -0,0 +1,128 @@
+// Copyright Valkey GLIDE Project Contributors - SPDX Identifier: Apache-2.0
+
+//! Entry points of the benchmarks in `benches`, to internals which aren't part of the FFI, available only with the `testing` feature.
+
+use std::sync::Arc;
+
+use glide_core::client::{Client as GlideClient, ConnectionRequest, NodeAddress};
+use redis::{cluster_routing::RoutingInfo, Cmd, ProtocolVersion, RedisResult, Value};
+use tokio::{
+    runtime::{Builder, Runtime},
+    task::JoinSet,
+};
+
+use crate::{
+    client_pool::ClientPool,
+    ffi::{ResponseValue, RouteInfo, RouteType, SlotType},
+    free_respose, key_prefix, response_pool, route_command,
+};
+
+/// The pool of the responses of a client, see [`ConnectionConfig::response_pool_capacity`](crate::ffi::ConnectionConfig::response_pool_capacity).
+pub struct ResponsePool(Option<Arc<response_pool::ResponsePool>>);
+
+impl ResponsePool {
+    /// Create a pool holding up to `capacity` free allocations, `0` disables pooling.
+    pub fn new(capacity: usize) -> Self {
+        ResponsePool(response_pool::ResponsePool::new(capacity))
+    }
+
+    /// Allocate the response of `value` like the success callback does, and free it like the caller does by [`free_respose`].
+    pub fn respond(&self, value: Value) {
+        let response = response_pool::into_raw(ResponseValue::from_value(value), self.0.as_ref());
+        unsafe { free_respose(response) };
+    }
+}
+
+/// Connections to a standalone server, see [`ConnectionConfig::connections_per_node`](crate::ffi::ConnectionConfig::connections_per_node).
+pub struct Connections {
+    runtime: Runtime,
+    pool: Arc<ClientPool>,
+}
+
+impl Connections {
+    /// Connect to the server at `host:port` by RESP2, with `connections_per_node` connections,
+    /// and with Nagle's algorithm disabled if `tcp_nodelay` is set, see [`ConnectionConfig::tcp_nodelay`](crate::ffi::ConnectionConfig::tcp_nodelay).
+    pub fn connect(
+        host: &str,
+        port: u16,
+        connections_per_node: u32,
+        tcp_nodelay: bool,
+    ) -> RedisResult<Self> {
+        let runtime = Builder::new_multi_thread().enable_all().build()?;
+        let request = ConnectionRequest {
+            addresses: vec![NodeAddress {
+                host: host.to_string(),
+                port,
+            }],
+            protocol: Some(ProtocolVersion::RESP2),
+            tcp_nodelay,
+            ..Default::default()
+        };
+        let pool = runtime.block_on(async {
+            let client = GlideClient::new(request.clone(), None)
+                .await
+                .map_err(|err| {
+                    redis::RedisError::from((
+                        redis::ErrorKind::ClientError,
+                        "Failed to connect",
+                        err.to_string(),
+                    ))
+                })?;
+            ClientPool::connect(client, &request, None, connections_per_node).await
+        })?;
+        Ok(Connections {
+            runtime,
+            pool: Arc::new(pool),
+        })
+    }
+
+    /// Send `GET` of `key` and wait for the reply.
+    pub fn get(&self, key: &str) -> RedisResult<()> {
+        self.runtime.block_on(async {
+            let mut cmd = redis::cmd("GET");
+            cmd.arg(key);
+            self.pool.next().send_command(&cmd, None).await.map(drop)
+        })
+    }
+
+    /// Send `GET` of all keys concurrently, spread across the connections like the commands of a client, and wait for the replies.
+    pub fn get_all(&self, keys: &[String]) -> RedisResult<()> {
+        self.runtime.block_on(async {
+            let mut gets = JoinSet::new();
+            for key in keys {
+                let mut client = self.pool.next();
+                let mut cmd = redis::cmd("GET");
+                cmd.arg(key);
+                gets.spawn(async move { client.send_command(&cmd, None).await });
+            }
+            while let Some(get) = gets.join_next().await {
+                get.expect("GET panicked")?;
+            }
+            Ok(())
+        })
+    }
+}
+
+/// The prefixing of the keys of a client, see [`ConnectionConfig::key_prefix`](crate::ffi::ConnectionConfig::key_prefix).
+pub struct KeyPrefix(key_prefix::KeyPrefix);
+
+impl KeyPrefix {
+    /// Create the prefixing of keys by `prefix`, from a response of the `COMMAND` command.
+    pub fn new(prefix: &str, commands: Value) -> RedisResult<Self> {
+        key_prefix::KeyPrefix::new(prefix.as_bytes().to_vec(), false, commands).map(KeyPrefix)
+    }
+
+    /// Prefix the keys of `cmd` and route it to a random node, like [`command`](crate::command) does with the given `keyless`.
+    pub fn route(&self, cmd: Cmd, keyless: bool) -> (Cmd, Option<RoutingInfo>) {
+        let route_info = RouteInfo {
+            route_type: RouteType::Random,
+            slot_id: 0,
+            slot_key: std::ptr::null(),
+            slot_type: SlotType::Primary,
+            hostname: std::ptr::null(),
+            port: 0,
+        };
+        let (cmd, route, _) = unsafe { route_command(Some(&self.0), cmd, &route_info, keyless) };
+        (cmd, route)
+    }
+}
@@ -0,0 +1,83 @@
+// Copyright Valkey GLIDE Project Contributors - SPDX Identifier: Apache-2.0
+
+use redis::{ErrorKind, RedisError, RedisResult, Value};
+
+/// Check that a `BITFIELD` encoding fits the 64 bit integers of the server's replies:
+/// signed fields (`i<bits>`) are up to 64 bits wide and unsigned fields (`u<bits>`) up to 63 bits wide.
+pub(crate) fn bitfield_encoding(signed: bool, bits: u8) -> RedisResult<String> {
+    let max_bits = if signed { 64 } else { 63 };
+    if bits == 0 || bits > max_bits {
+        return Err(RedisError::from((
+            ErrorKind::ClientError,
+            "Invalid BITFIELD field width",
+            format!(
+                "{} fields must be 1 to {max_bits} bits wide",
+                if signed { "Signed" } else { "Unsigned" }
+            ),
+        )));
+    }
+    Ok(format!("{}{bits}", if signed { 'i' } else { 'u' }))
+}
+
+/// Align a `BITFIELD` response to the operations, by inserting nulls for the `OVERFLOW` operations,
+/// which the server doesn't reply to. `overflow_positions` are the ascending indices of the `OVERFLOW` operations.
+pub(crate) fn align_bitfield_response(
+    value: Value,
+    overflow_positions: &[usize],
+) -> RedisResult<Value> {
+    let Value::Array(results) = value else {
+        return Err(RedisError::from((
+            ErrorKind::TypeError,
+            "Unexpected BITFIELD response",
+            "response is not an array".to_string(),
+        )));
+    };
+    let mut aligned = Vec::with_capacity(results.len() + overflow_positions.len());
+    let mut results = results.into_iter();
+    for position in overflow_positions {
+        while aligned.len() < *position {
+            aligned.push(results.next().unwrap_or(Value::Nil));
+        }
+        aligned.push(Value::Nil);
+    }
+    aligned.extend(results);
+    Ok(Value::Array(aligned))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bitfield_encoding_widths() {
+        assert_eq!(bitfield_encoding(true, 64).unwrap(), "i64");
+        assert_eq!(bitfield_encoding(false, 63).unwrap(), "u63");
+        assert_eq!(bitfield_encoding(false, 1).unwrap(), "u1");
+        assert!(bitfield_encoding(false, 64).is_err());
+        assert!(bitfield_encoding(true, 65).is_err());
+        assert!(bitfield_encoding(true, 0).is_err());
+    }
+
+    #[test]
+    fn test_bitfield_extreme_values_round_trip() {
+        // The widest unsigned field holds up to `2^63 - 1`, so all values fit the integers of the response.
+        assert_eq!((1u64 << 63) - 1, i64::MAX as u64);
+        let value = Value::Array(vec![Value::Int(i64::MIN), Value::Int(i64::MAX)]);
+        assert_eq!(align_bitfield_response(value.clone(), &[]).unwrap(), value);
+    }
+
+    #[test]
+    fn test_align_bitfield_response() {
+        let value = Value::Array(vec![Value::Int(1), Value::Int(2), Value::Nil]);
+        assert_eq!(
+            align_bitfield_response(value, &[0, 2]).unwrap(),
+            Value::Array(vec![
+                Value::Nil,
+                Value::Int(1),
+                Value::Nil,
+                Value::Int(2),
+                Value::Nil,
+            ])
+        );
+    }
+}
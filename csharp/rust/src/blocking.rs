@@ -0,0 +1,528 @@
+// Copyright Valkey GLIDE Project Contributors - SPDX Identifier: Apache-2.0
+
+//! Blocking pops (`BLPOP`, `BRPOP`, `BLMOVE`, `BZPOPMIN` and `BZPOPMAX`), which can be cancelled by the caller.
+//!
+//! A blocked connection doesn't serve other commands until the server replies, so blocking pops aren't sent on the
+//! shared multiplexed connections. Each runs on a dedicated connection, which is reused once the server replied.
+//! If the pop is cancelled or its deadline passes, the connection is closed instead, which unblocks it on the server.
+
+use std::{
+    ffi::c_void,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+};
+
+use glide_core::client::Client as GlideClient;
+use redis::{Cmd, RedisError, RedisResult};
+use tokio::sync::Notify;
+
+use crate::{
+    commands::ptr_to_bytes,
+    deadline_to_instant,
+    errors::{error_type, RequestErrorType, CANCELLED_CODE},
+    ffi::{convert_double_pointer_to_vec, create_route, RouteInfo},
+    lease::LeasePool,
+    open_client_from_ptr, timeout_error,
+};
+
+/// Maximal number of idle dedicated connections kept for later blocking pops.
+const MAX_IDLE_CONNECTIONS: usize = 8;
+
+/// Dedicated connections of blocking pops which aren't in use.
+#[derive(Default)]
+pub(crate) struct BlockingConnections {
+    idle: Mutex<Vec<GlideClient>>,
+}
+
+impl BlockingConnections {
+    async fn acquire(&self, leases: &LeasePool) -> RedisResult<GlideClient> {
+        let idle = self.idle.lock().unwrap().pop();
+        match idle {
+            Some(connection) => Ok(connection),
+            None => leases.connect().await,
+        }
+    }
+
+    /// Return a connection once the server replied to a blocking pop on it.
+    /// Connections which failed or timed out may still be blocked, so they are closed.
+    fn release(&self, connection: GlideClient, result: &RedisResult<redis::Value>) {
+        if let Err(err) = result {
+            if matches!(
+                error_type(err),
+                RequestErrorType::Disconnect | RequestErrorType::Timeout
+            ) {
+                return;
+            }
+        }
+        let mut idle = self.idle.lock().unwrap();
+        if idle.len() < MAX_IDLE_CONNECTIONS {
+            idle.push(connection);
+        }
+    }
+}
+
+/// A handle which cancels the blocking pops it was passed to, created by [`create_cancellation_handle`].
+#[derive(Default)]
+struct CancellationHandle {
+    cancelled: AtomicBool,
+    notify: Notify,
+}
+
+impl CancellationHandle {
+    fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Release);
+        self.notify.notify_waiters();
+    }
+
+    /// Complete once the handle is cancelled, right away if it already was.
+    async fn cancelled(&self) {
+        // The future is registered for `notify_waiters` once created, so a cancellation after the check isn't missed.
+        let notified = self.notify.notified();
+        if self.cancelled.load(Ordering::Acquire) {
+            return;
+        }
+        notified.await;
+    }
+}
+
+fn cancelled_error() -> RedisError {
+    redis::make_extension_error(
+        CANCELLED_CODE.to_string(),
+        Some("The blocking command was cancelled".to_string()),
+    )
+}
+
+/// Create a handle to cancel blocking pops, e.g. when the caller gives up waiting.
+/// A handle may be passed to any number of pops, and cancelling it cancels all of them, including pops submitted later.
+/// The handle must be freed by [`free_cancellation_handle`].
+#[no_mangle]
+pub extern "C" fn create_cancellation_handle() -> *const c_void {
+    Arc::into_raw(Arc::new(CancellationHandle::default())) as *const c_void
+}
+
+/// Cancel the blocking pops of a handle. They fail with [`RequestErrorType::Cancelled`], and their connections are closed.
+/// Pops which the server already replied to aren't affected.
+///
+/// # Safety
+/// * `handle` must be a pointer returned by [`create_cancellation_handle`], which wasn't freed yet.
+#[no_mangle]
+pub unsafe extern "C" fn cancel_handle(handle: *const c_void) {
+    let handle = unsafe { &*(handle as *const CancellationHandle) };
+    handle.cancel();
+}
+
+/// Free a handle created by [`create_cancellation_handle`]. Pops submitted with the handle before can't be cancelled anymore.
+///
+/// # Safety
+/// * `handle` must be a pointer returned by [`create_cancellation_handle`], which wasn't freed yet.
+#[no_mangle]
+pub unsafe extern "C" fn free_cancellation_handle(handle: *const c_void) {
+    drop(unsafe { Arc::from_raw(handle as *const CancellationHandle) });
+}
+
+/// Restore a reference to a cancellation handle, `None` if `handle` is `null`.
+///
+/// # Safety
+/// * `handle` could be `null`, but if it is not `null`, it must be a pointer returned by [`create_cancellation_handle`], which wasn't freed yet.
+unsafe fn cancellation_from_ptr(handle: *const c_void) -> Option<Arc<CancellationHandle>> {
+    if handle.is_null() {
+        return None;
+    }
+    let handle = handle as *const CancellationHandle;
+    unsafe {
+        Arc::increment_strong_count(handle);
+        Some(Arc::from_raw(handle))
+    }
+}
+
+/// Submit a blocking pop on a dedicated connection.
+/// The pop fails with [`RequestErrorType::Cancelled`] once `cancellation` is cancelled,
+/// and with a timeout error once the deadline passes.
+///
+/// # Safety
+/// * `client_ptr` must be a valid pointer created by [`create_client`](crate::create_client), before [`close_client`](crate::close_client) was called.
+/// * `route_info` could be `null`, but if it is not `null`, it must be a valid [`RouteInfo`] pointer. See the safety documentation of [`create_route`].
+/// * `cancellation` could be `null`, but if it is not `null`, it must be a pointer returned by [`create_cancellation_handle`], which wasn't freed yet.
+unsafe fn submit_blocking(
+    client_ptr: *const c_void,
+    callback_index: usize,
+    cmd: Cmd,
+    route_info: *const RouteInfo,
+    cancellation: *const c_void,
+    deadline_unix_ms: u64,
+) {
+    let Some(client) = (unsafe { open_client_from_ptr(client_ptr, callback_index) }) else {
+        return;
+    };
+    let core = client.core.clone();
+    let cancellation = unsafe { cancellation_from_ptr(cancellation) };
+    let route = unsafe { create_route(route_info, &cmd) };
+    let reserved = core
+        .check_command_allowed(&cmd)
+        .and_then(|_| deadline_to_instant(deadline_unix_ms))
+        .and_then(|deadline| Ok((deadline, core.reserve_pending_task()?)));
+    let (deadline, pending_guard) = match reserved {
+        Ok(reserved) => reserved,
+        Err(err) => {
+            unsafe { core.report_error(callback_index, err) };
+            return;
+        }
+    };
+    client.runtime.spawn(async move {
+        // Dropping `send` on cancellation or on the deadline closes its connection.
+        let send = async {
+            let mut connection = core.blocking_connections.acquire(&core.leases).await?;
+            let result = connection.send_command(&cmd, route).await;
+            core.blocking_connections.release(connection, &result);
+            result
+        };
+        let cancelled = async {
+            match &cancellation {
+                Some(cancellation) => cancellation.cancelled().await,
+                None => std::future::pending::<()>().await,
+            }
+        };
+        let timed_out = async {
+            match deadline {
+                Some(deadline) => tokio::time::sleep_until(deadline).await,
+                None => std::future::pending::<()>().await,
+            }
+        };
+        let result = tokio::select! {
+            result = send => result,
+            _ = cancelled => Err(cancelled_error()),
+            _ = timed_out => Err(timeout_error()),
+        };
+        core.track_authentication(&result);
+        unsafe { core.report_result(callback_index, core.normalize_reply(result)) };
+        drop(pending_guard);
+    });
+}
+
+/// Build a pop from the lists or the sorted sets of `keys`, which blocks for up to `timeout_secs` seconds.
+fn blocking_pop_command(name: &str, keys: Vec<&[u8]>, timeout_secs: f64) -> Cmd {
+    let mut cmd = redis::cmd(name);
+    for key in keys {
+        cmd.arg(key);
+    }
+    cmd.arg(timeout_secs);
+    cmd
+}
+
+/// Submit a pop from the first non-empty list or sorted set of `keys`, like [`blpop`].
+///
+/// # Safety
+/// See the safety documentation of [`blpop`].
+#[allow(clippy::too_many_arguments)]
+unsafe fn submit_blocking_pop(
+    name: &str,
+    client_ptr: *const c_void,
+    callback_index: usize,
+    keys: *const *const u8,
+    keys_len: *const u32,
+    key_count: u32,
+    timeout_secs: f64,
+    route_info: *const RouteInfo,
+    cancellation: *const c_void,
+    deadline_unix_ms: u64,
+) {
+    let keys =
+        unsafe { convert_double_pointer_to_vec(keys as *const *const c_void, key_count, keys_len) };
+    let cmd = blocking_pop_command(name, keys, timeout_secs);
+    unsafe {
+        submit_blocking(
+            client_ptr,
+            callback_index,
+            cmd,
+            route_info,
+            cancellation,
+            deadline_unix_ms,
+        )
+    };
+}
+
+/// Execute a `BLPOP` command, popping the first element of the first non-empty list of `keys`.
+/// The server blocks for up to `timeout_secs` seconds until one of the lists isn't empty, `0` blocks indefinitely.
+///
+/// The response is an array of the key and the popped element, or null if the server-side timeout elapsed.
+/// The command is routed by the slot of the first key, so in cluster mode all keys must map to the same slot.
+///
+/// The pop fails with [`RequestErrorType::Cancelled`] once `cancellation` is cancelled, see [`create_cancellation_handle`].
+/// `deadline_unix_ms` is an absolute deadline in milliseconds since the unix epoch, `0` means no deadline.
+/// A pop which outlives the deadline fails with a timeout error. In both cases the blocked connection is closed.
+///
+/// # Safety
+/// * `client_ptr` must be a valid pointer created by [`create_client`](crate::create_client), before [`close_client`](crate::close_client) was called.
+/// * `keys` must point to `key_count` consecutive buffer pointers and `keys_len` must point to their lengths.
+///   See the safety documentation of [`convert_double_pointer_to_vec`].
+/// * `route_info` could be `null`, but if it is not `null`, it must be a valid [`RouteInfo`] pointer.
+/// * `cancellation` could be `null`, but if it is not `null`, it must be a pointer returned by [`create_cancellation_handle`], which wasn't freed yet.
+#[allow(clippy::too_many_arguments)]
+#[no_mangle]
+pub unsafe extern "C" fn blpop(
+    client_ptr: *const c_void,
+    callback_index: usize,
+    keys: *const *const u8,
+    keys_len: *const u32,
+    key_count: u32,
+    timeout_secs: f64,
+    route_info: *const RouteInfo,
+    cancellation: *const c_void,
+    deadline_unix_ms: u64,
+) {
+    unsafe {
+        submit_blocking_pop(
+            "BLPOP",
+            client_ptr,
+            callback_index,
+            keys,
+            keys_len,
+            key_count,
+            timeout_secs,
+            route_info,
+            cancellation,
+            deadline_unix_ms,
+        )
+    };
+}
+
+/// Execute a `BRPOP` command, popping the last element of the first non-empty list of `keys`.
+/// See [`blpop`] for the timeout, the cancellation and the response.
+///
+/// # Safety
+/// See the safety documentation of [`blpop`].
+#[allow(clippy::too_many_arguments)]
+#[no_mangle]
+pub unsafe extern "C" fn brpop(
+    client_ptr: *const c_void,
+    callback_index: usize,
+    keys: *const *const u8,
+    keys_len: *const u32,
+    key_count: u32,
+    timeout_secs: f64,
+    route_info: *const RouteInfo,
+    cancellation: *const c_void,
+    deadline_unix_ms: u64,
+) {
+    unsafe {
+        submit_blocking_pop(
+            "BRPOP",
+            client_ptr,
+            callback_index,
+            keys,
+            keys_len,
+            key_count,
+            timeout_secs,
+            route_info,
+            cancellation,
+            deadline_unix_ms,
+        )
+    };
+}
+
+/// Execute a `BZPOPMIN` command, popping the member with the lowest score of the first non-empty sorted set of `keys`.
+/// The response is an array of the key, the member and its score, or null if the server-side timeout elapsed.
+/// See [`blpop`] for the timeout and the cancellation.
+///
+/// # Safety
+/// See the safety documentation of [`blpop`].
+#[allow(clippy::too_many_arguments)]
+#[no_mangle]
+pub unsafe extern "C" fn bzpopmin(
+    client_ptr: *const c_void,
+    callback_index: usize,
+    keys: *const *const u8,
+    keys_len: *const u32,
+    key_count: u32,
+    timeout_secs: f64,
+    route_info: *const RouteInfo,
+    cancellation: *const c_void,
+    deadline_unix_ms: u64,
+) {
+    unsafe {
+        submit_blocking_pop(
+            "BZPOPMIN",
+            client_ptr,
+            callback_index,
+            keys,
+            keys_len,
+            key_count,
+            timeout_secs,
+            route_info,
+            cancellation,
+            deadline_unix_ms,
+        )
+    };
+}
+
+/// Execute a `BZPOPMAX` command, popping the member with the highest score of the first non-empty sorted set of `keys`.
+/// See [`bzpopmin`] for the response, and [`blpop`] for the timeout and the cancellation.
+///
+/// # Safety
+/// See the safety documentation of [`blpop`].
+#[allow(clippy::too_many_arguments)]
+#[no_mangle]
+pub unsafe extern "C" fn bzpopmax(
+    client_ptr: *const c_void,
+    callback_index: usize,
+    keys: *const *const u8,
+    keys_len: *const u32,
+    key_count: u32,
+    timeout_secs: f64,
+    route_info: *const RouteInfo,
+    cancellation: *const c_void,
+    deadline_unix_ms: u64,
+) {
+    unsafe {
+        submit_blocking_pop(
+            "BZPOPMAX",
+            client_ptr,
+            callback_index,
+            keys,
+            keys_len,
+            key_count,
+            timeout_secs,
+            route_info,
+            cancellation,
+            deadline_unix_ms,
+        )
+    };
+}
+
+/// The end of a list to pop from or to push to.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ListDirection {
+    Left = 0,
+    Right = 1,
+}
+
+impl ListDirection {
+    fn as_arg(self) -> &'static str {
+        match self {
+            ListDirection::Left => "LEFT",
+            ListDirection::Right => "RIGHT",
+        }
+    }
+}
+
+fn blmove_command(
+    source: &[u8],
+    destination: &[u8],
+    where_from: ListDirection,
+    where_to: ListDirection,
+    timeout_secs: f64,
+) -> Cmd {
+    let mut cmd = redis::cmd("BLMOVE");
+    cmd.arg(source)
+        .arg(destination)
+        .arg(where_from.as_arg())
+        .arg(where_to.as_arg())
+        .arg(timeout_secs);
+    cmd
+}
+
+/// Execute a `BLMOVE` command, popping an element from the `where_from` end of `source` and pushing it to the `where_to` end
+/// of `destination`. The server blocks for up to `timeout_secs` seconds until `source` isn't empty, `0` blocks indefinitely.
+///
+/// The response is the moved element, or null if the server-side timeout elapsed.
+/// See [`blpop`] for the cancellation and the deadline.
+///
+/// # Safety
+/// * `client_ptr` must be a valid pointer created by [`create_client`](crate::create_client), before [`close_client`](crate::close_client) was called.
+/// * `source` and `destination` must point to `source_len` and `destination_len` consecutive bytes respectively.
+///   See the safety documentation of [`ptr_to_bytes`].
+/// * `route_info` could be `null`, but if it is not `null`, it must be a valid [`RouteInfo`] pointer.
+/// * `cancellation` could be `null`, but if it is not `null`, it must be a pointer returned by [`create_cancellation_handle`], which wasn't freed yet.
+#[allow(clippy::too_many_arguments)]
+#[no_mangle]
+pub unsafe extern "C" fn blmove(
+    client_ptr: *const c_void,
+    callback_index: usize,
+    source: *const u8,
+    source_len: u32,
+    destination: *const u8,
+    destination_len: u32,
+    where_from: ListDirection,
+    where_to: ListDirection,
+    timeout_secs: f64,
+    route_info: *const RouteInfo,
+    cancellation: *const c_void,
+    deadline_unix_ms: u64,
+) {
+    let cmd = blmove_command(
+        unsafe { ptr_to_bytes(source, source_len) },
+        unsafe { ptr_to_bytes(destination, destination_len) },
+        where_from,
+        where_to,
+        timeout_secs,
+    );
+    unsafe {
+        submit_blocking(
+            client_ptr,
+            callback_index,
+            cmd,
+            route_info,
+            cancellation,
+            deadline_unix_ms,
+        )
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_blocking_pop_command() {
+        let cmd = blocking_pop_command("BZPOPMIN", vec![b"first", b"second"], 0.5);
+        assert_eq!(
+            cmd.get_packed_command(),
+            redis::cmd("BZPOPMIN")
+                .arg("first")
+                .arg("second")
+                .arg("0.5")
+                .get_packed_command()
+        );
+    }
+
+    #[test]
+    fn test_blmove_command() {
+        let cmd = blmove_command(
+            b"source",
+            b"",
+            ListDirection::Right,
+            ListDirection::Left,
+            0.0,
+        );
+        assert_eq!(
+            cmd.get_packed_command(),
+            redis::cmd("BLMOVE")
+                .arg("source")
+                .arg("")
+                .arg("RIGHT")
+                .arg("LEFT")
+                .arg("0.0")
+                .get_packed_command()
+        );
+    }
+
+    #[test]
+    fn test_cancellation_completes_current_and_later_waiters() {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .build()
+            .unwrap();
+        let handle = Arc::new(CancellationHandle::default());
+        let waiter = runtime.spawn({
+            let handle = handle.clone();
+            async move { handle.cancelled().await }
+        });
+        handle.cancel();
+        runtime.block_on(waiter).unwrap();
+        // Pops submitted after the handle was cancelled are cancelled right away.
+        runtime.block_on(handle.cancelled());
+        assert_eq!(error_type(&cancelled_error()), RequestErrorType::Cancelled);
+    }
+}
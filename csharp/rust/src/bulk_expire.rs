@@ -0,0 +1,215 @@
+// Copyright Valkey GLIDE Project Contributors - SPDX Identifier: Apache-2.0
+
+//! Bulk expiries of many keys, e.g. to set TTLs during a cleanup, see [`bulk_expire`].
+
+use std::ffi::c_void;
+
+use redis::{
+    cluster_routing::{Route, RoutingInfo, SingleNodeRoutingInfo, SlotAddr},
+    cluster_topology::get_slot,
+    Pipeline, PipelineRetryStrategy, RedisResult, Value,
+};
+use tokio::task::JoinSet;
+
+use crate::{
+    bulk_set::{group_keys, MAX_PIPELINE_SIZE},
+    commands::{expire_command, unsupported_expire_condition, ExpireCondition},
+    errors::{error_type, RequestErrorType},
+    ffi::{convert_double_pointer_to_vec, create_route, RouteInfo},
+    open_client_from_ptr,
+};
+
+/// Add the results of the keys of a chunk to `results`: `1` if the expiry was set, `0` if it wasn't, and null if the `EXPIRE`
+/// failed. If the whole pipeline failed, e.g. because the node disconnected, all of its keys failed.
+/// Fails if the server doesn't support the condition, since the `EXPIRE` of every key fails then.
+fn collect_results(
+    keys: Vec<Vec<u8>>,
+    result: RedisResult<Value>,
+    has_condition: bool,
+    results: &mut Vec<(Value, Value)>,
+) -> RedisResult<()> {
+    let replies = match result {
+        Ok(Value::Array(replies)) if replies.len() == keys.len() => replies,
+        _ => vec![Value::Nil; keys.len()],
+    };
+    for (key, reply) in keys.into_iter().zip(replies) {
+        let reply = match reply {
+            Value::Int(applied) => Value::Int(applied),
+            Value::Boolean(applied) => Value::Int(applied.into()),
+            Value::ServerError(err) if has_condition => {
+                let err = unsupported_expire_condition("EXPIRE", err.into());
+                if error_type(&err) == RequestErrorType::UnsupportedByServer {
+                    return Err(err);
+                }
+                Value::Nil
+            }
+            _ => Value::Nil,
+        };
+        results.push((Value::BulkString(key), reply));
+    }
+    Ok(())
+}
+
+/// Set the expiry of `key_count` keys to `seconds` from now, if `condition` is met, like an `EXPIRE` of every key,
+/// e.g. to set TTLs during a cleanup. Unlike separate `EXPIRE` commands, the keys are sent by pipelines.
+///
+/// In cluster mode the keys are grouped by the primaries of their slots, according to the topology known to the client,
+/// and each group is sent by pipelines of up to 1000 keys to its node, like [`bulk_set`](crate::bulk_set::bulk_set).
+/// The pipelines are sent concurrently, and keys redirected by the server (e.g. after a slot migration) are retried.
+/// Keys whose pipeline failed on a connection error aren't retried, since their `EXPIRE` might have been applied already,
+/// which a retry with a condition would report as not applied. If `route_info` is given, all keys are sent to its node,
+/// and they are only split to pipelines by size, like in standalone mode.
+///
+/// The response is a map of every key to `1` if the expiry was set, `0` if the key doesn't exist or the condition isn't met,
+/// or null if its `EXPIRE` failed. Conditions require servers 7.0 and later, older servers fail the request with an
+/// [`UnsupportedByServer`](crate::errors::RequestErrorType::UnsupportedByServer) error. Otherwise the request fails only
+/// if the client can't submit it.
+///
+/// # Safety
+/// * `client_ptr` must be a valid pointer created by [`create_client`](crate::create_client), before [`close_client`](crate::close_client) was called.
+/// * `keys` must point to `key_count` consecutive buffer pointers, and `keys_len` must point to their lengths.
+///   See the safety documentation of [`convert_double_pointer_to_vec`].
+/// * `route_info` could be `null`, but if it is not `null`, it must be a valid [`RouteInfo`] pointer.
+#[allow(clippy::too_many_arguments)]
+#[no_mangle]
+pub unsafe extern "C" fn bulk_expire(
+    client_ptr: *const c_void,
+    callback_index: usize,
+    keys: *const *const u8,
+    keys_len: *const u32,
+    key_count: u32,
+    seconds: i64,
+    condition: ExpireCondition,
+    route_info: *const RouteInfo,
+) {
+    let Some(client) = (unsafe { open_client_from_ptr(client_ptr, callback_index) }) else {
+        return;
+    };
+    let core = client.core.clone();
+    let pending_guard = match core
+        .check_command_allowed(&redis::cmd("EXPIRE"))
+        .and_then(|_| core.reserve_pending_task())
+    {
+        Ok(pending_guard) => pending_guard,
+        Err(err) => {
+            unsafe { core.report_error(callback_index, err) };
+            return;
+        }
+    };
+    let keys =
+        unsafe { convert_double_pointer_to_vec(keys as *const *const c_void, key_count, keys_len) };
+
+    // Build the pipelines outside of the task to ensure that the keys passed are still valid
+    let route = match keys.first() {
+        Some(key) => unsafe {
+            create_route(
+                route_info,
+                &expire_command("EXPIRE", key, seconds, condition),
+            )
+        },
+        None => None,
+    };
+    let topology = (route.is_none() && core.standalone_address.is_none())
+        .then(|| core.topology.read().unwrap().clone());
+    let chunks: Vec<_> = group_keys(&keys, topology.as_deref(), MAX_PIPELINE_SIZE)
+        .into_iter()
+        .map(|indices| {
+            let mut pipeline = Pipeline::new();
+            for &index in &indices {
+                pipeline.add_command(expire_command("EXPIRE", keys[index], seconds, condition));
+            }
+            // In cluster mode the pipeline is routed to the primary of its first key, unless a route is given.
+            let route = route.clone().or_else(|| {
+                topology.as_ref().map(|_| {
+                    RoutingInfo::SingleNode(SingleNodeRoutingInfo::SpecificNode(Route::new(
+                        get_slot(keys[indices[0]]),
+                        SlotAddr::Master,
+                    )))
+                })
+            });
+            let chunk_keys: Vec<Vec<u8>> =
+                indices.iter().map(|&index| keys[index].to_vec()).collect();
+            (pipeline, route, chunk_keys)
+        })
+        .collect();
+    let has_condition = condition != ExpireCondition::None;
+
+    client.runtime.spawn(async move {
+        let mut tasks = JoinSet::new();
+        for (pipeline, route, chunk_keys) in chunks {
+            let mut client = core.client_pool.next();
+            tasks.spawn(async move {
+                let result = client
+                    .send_pipeline(
+                        &pipeline,
+                        route,
+                        false,
+                        None,
+                        PipelineRetryStrategy::new(true, false),
+                    )
+                    .await;
+                (chunk_keys, result)
+            });
+        }
+        let mut results = Vec::new();
+        let mut outcome = Ok(());
+        while let Some(joined) = tasks.join_next().await {
+            if let Ok((chunk_keys, result)) = joined {
+                core.track_authentication(&result);
+                if outcome.is_ok() {
+                    outcome = collect_results(chunk_keys, result, has_condition, &mut results);
+                }
+            }
+        }
+        let response = outcome.map(|_| Value::Map(results));
+        unsafe { core.report_result(callback_index, response) };
+        drop(pending_guard);
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn server_error(reply: &str) -> Value {
+        redis::parse_redis_value(reply.as_bytes()).unwrap()
+    }
+
+    fn keys(names: &[&str]) -> Vec<Vec<u8>> {
+        names.iter().map(|name| name.as_bytes().to_vec()).collect()
+    }
+
+    #[test]
+    fn test_collect_results_of_every_key() {
+        let mut results = Vec::new();
+        let replies = Value::Array(vec![
+            Value::Int(1),
+            Value::Boolean(false),
+            server_error("-WRONGTYPE Operation against a key holding the wrong kind of value\r\n"),
+        ]);
+        collect_results(keys(&["a", "b", "c"]), Ok(replies), true, &mut results).unwrap();
+        let err =
+            redis::RedisError::from(std::io::Error::from(std::io::ErrorKind::ConnectionReset));
+        collect_results(keys(&["d"]), Err(err), true, &mut results).unwrap();
+        assert_eq!(
+            results,
+            vec![
+                (Value::BulkString(b"a".to_vec()), Value::Int(1)),
+                (Value::BulkString(b"b".to_vec()), Value::Int(0)),
+                (Value::BulkString(b"c".to_vec()), Value::Nil),
+                (Value::BulkString(b"d".to_vec()), Value::Nil),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_unsupported_condition_fails_the_request() {
+        let replies = Value::Array(vec![server_error(
+            "-ERR wrong number of arguments for 'expire' command\r\n",
+        )]);
+        let err =
+            collect_results(keys(&["a"]), Ok(replies.clone()), true, &mut Vec::new()).unwrap_err();
+        assert_eq!(error_type(&err), RequestErrorType::UnsupportedByServer);
+        assert!(collect_results(keys(&["a"]), Ok(replies), false, &mut Vec::new()).is_ok());
+    }
+}
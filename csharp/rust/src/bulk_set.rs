@@ -0,0 +1,307 @@
+// Copyright Valkey GLIDE Project Contributors - SPDX Identifier: Apache-2.0
+
+//! Bulk writes of key/value pairs with per-key TTLs, e.g. to warm a cache, see [`bulk_set`].
+
+use std::{collections::HashMap, ffi::c_void};
+
+use redis::{
+    cluster_routing::{Route, RoutingInfo, SingleNodeRoutingInfo, SlotAddr},
+    cluster_topology::get_slot,
+    Cmd, Pipeline, PipelineRetryStrategy, RedisResult, Value,
+};
+use tokio::task::JoinSet;
+
+use crate::{ffi::convert_double_pointer_to_vec, open_client_from_ptr, topology::Topology};
+
+/// Maximal number of commands in a pipeline, so a huge bulk is split to pipelines which are sent concurrently.
+pub(crate) const MAX_PIPELINE_SIZE: usize = 1000;
+
+/// The node a group of keys is sent to: the primary of their slots if the topology knows it, otherwise the slot itself.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Group<'a> {
+    Node(&'a str),
+    Slot(u16),
+}
+
+/// Split the indices of `keys` to chunks of at most `max_size` keys, whose keys are served by the same primary.
+/// Without a topology (`None`), e.g. in standalone mode, keys are only split by size.
+pub(crate) fn group_keys(
+    keys: &[&[u8]],
+    topology: Option<&Topology>,
+    max_size: usize,
+) -> Vec<Vec<usize>> {
+    let Some(topology) = topology else {
+        return (0..keys.len())
+            .collect::<Vec<_>>()
+            .chunks(max_size)
+            .map(<[usize]>::to_vec)
+            .collect();
+    };
+    let mut groups: HashMap<Group, Vec<usize>> = HashMap::new();
+    for (index, key) in keys.iter().enumerate() {
+        let slot = get_slot(key);
+        let group = topology
+            .primary_for_slot(slot)
+            .map_or(Group::Slot(slot), Group::Node);
+        groups.entry(group).or_default().push(index);
+    }
+    groups
+        .into_values()
+        .flat_map(|indices| {
+            indices
+                .chunks(max_size)
+                .map(<[usize]>::to_vec)
+                .collect::<Vec<_>>()
+        })
+        .collect()
+}
+
+fn set_command(key: &[u8], value: &[u8], ttl_secs: u64) -> Cmd {
+    let mut cmd = redis::cmd("SET");
+    cmd.arg(key).arg(value);
+    if ttl_secs > 0 {
+        cmd.arg("EX").arg(ttl_secs);
+    }
+    cmd
+}
+
+/// Count the entries of a chunk which were set, and collect the keys of the entries which failed.
+/// If the whole pipeline failed, e.g. because the node disconnected, all of its keys failed.
+fn collect_results(
+    keys: Vec<Vec<u8>>,
+    result: RedisResult<Value>,
+    succeeded: &mut i64,
+    failed: &mut Vec<Value>,
+) {
+    match result {
+        Ok(Value::Array(replies)) if replies.len() == keys.len() => {
+            for (key, reply) in keys.into_iter().zip(replies) {
+                match reply {
+                    Value::ServerError(_) => failed.push(Value::BulkString(key)),
+                    _ => *succeeded += 1,
+                }
+            }
+        }
+        _ => failed.extend(keys.into_iter().map(Value::BulkString)),
+    }
+}
+
+/// Set `entry_count` key/value pairs, each with its own TTL, e.g. to warm a cache.
+/// Unlike `MSET`, each key has its own TTL, and unlike separate `SET` commands, the entries are sent by pipelines.
+///
+/// In cluster mode the entries are grouped by the primaries of their slots, according to the topology known to the client,
+/// and each group is sent by pipelines of up to 1000 entries to its node. In standalone mode the entries are only split to
+/// such pipelines. The pipelines are sent concurrently, and entries redirected by the server (e.g. after a slot migration) are retried.
+///
+/// `ttls_secs` contains the TTL of every entry in seconds (`SET ... EX`), where `0` means no TTL. It could be `null`, which means no TTLs.
+///
+/// The response is a map of `succeeded` - the number of entries which were set, and `failed` - an array of the keys
+/// of the entries which failed. The request itself fails only if the client can't submit it.
+///
+/// # Safety
+/// * `client_ptr` must be a valid pointer created by [`create_client`](crate::create_client), before [`close_client`](crate::close_client) was called.
+/// * `keys` and `values` must point to `entry_count` consecutive buffer pointers, and `keys_len` and `values_len` must point to their lengths.
+///   See the safety documentation of [`convert_double_pointer_to_vec`].
+/// * `ttls_secs` could be `null`, but if it is not `null`, it must point to `entry_count` consecutive integers.
+#[allow(clippy::too_many_arguments)]
+#[no_mangle]
+pub unsafe extern "C" fn bulk_set(
+    client_ptr: *const c_void,
+    callback_index: usize,
+    keys: *const *const u8,
+    keys_len: *const u32,
+    values: *const *const u8,
+    values_len: *const u32,
+    ttls_secs: *const u64,
+    entry_count: u32,
+) {
+    let Some(client) = (unsafe { open_client_from_ptr(client_ptr, callback_index) }) else {
+        return;
+    };
+    let core = client.core.clone();
+    let pending_guard = match core
+        .check_command_allowed(&redis::cmd("SET"))
+        .and_then(|_| core.reserve_pending_task())
+    {
+        Ok(pending_guard) => pending_guard,
+        Err(err) => {
+            unsafe { core.report_error(callback_index, err) };
+            return;
+        }
+    };
+    let keys = unsafe {
+        convert_double_pointer_to_vec(keys as *const *const c_void, entry_count, keys_len)
+    };
+    let values = unsafe {
+        convert_double_pointer_to_vec(values as *const *const c_void, entry_count, values_len)
+    };
+    let ttls_secs = if ttls_secs.is_null() {
+        &[]
+    } else {
+        unsafe { std::slice::from_raw_parts(ttls_secs, entry_count as usize) }
+    };
+
+    // Build the pipelines outside of the task to ensure that the entries passed are still valid
+    let topology = core
+        .standalone_address
+        .is_none()
+        .then(|| core.topology.read().unwrap().clone());
+    let chunks: Vec<_> = group_keys(&keys, topology.as_deref(), MAX_PIPELINE_SIZE)
+        .into_iter()
+        .map(|indices| {
+            let mut pipeline = Pipeline::new();
+            for &index in &indices {
+                let ttl_secs = ttls_secs.get(index).copied().unwrap_or_default();
+                pipeline.add_command(set_command(keys[index], values[index], ttl_secs));
+            }
+            // In cluster mode the pipeline is routed to the primary of its first key.
+            let route = topology.as_ref().map(|_| {
+                RoutingInfo::SingleNode(SingleNodeRoutingInfo::SpecificNode(Route::new(
+                    get_slot(keys[indices[0]]),
+                    SlotAddr::Master,
+                )))
+            });
+            let chunk_keys: Vec<Vec<u8>> =
+                indices.iter().map(|&index| keys[index].to_vec()).collect();
+            (pipeline, route, chunk_keys)
+        })
+        .collect();
+
+    client.runtime.spawn(async move {
+        let mut tasks = JoinSet::new();
+        for (pipeline, route, chunk_keys) in chunks {
+            let mut client = core.client_pool.next();
+            tasks.spawn(async move {
+                // `SET` is idempotent, so entries are retried after redirections and connection errors.
+                let result = client
+                    .send_pipeline(
+                        &pipeline,
+                        route,
+                        false,
+                        None,
+                        PipelineRetryStrategy::new(true, true),
+                    )
+                    .await;
+                (chunk_keys, result)
+            });
+        }
+        let mut succeeded = 0;
+        let mut failed = Vec::new();
+        while let Some(joined) = tasks.join_next().await {
+            if let Ok((chunk_keys, result)) = joined {
+                core.track_authentication(&result);
+                collect_results(chunk_keys, result, &mut succeeded, &mut failed);
+            }
+        }
+        let response = Value::Map(vec![
+            (
+                Value::SimpleString("succeeded".to_string()),
+                Value::Int(succeeded),
+            ),
+            (
+                Value::SimpleString("failed".to_string()),
+                Value::Array(failed),
+            ),
+        ]);
+        unsafe { core.report_result(callback_index, Ok(response)) };
+        drop(pending_guard);
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::topology::SlotRange;
+
+    #[test]
+    fn test_keys_are_grouped_by_primary_and_size() {
+        let topology = Topology {
+            ranges: vec![
+                SlotRange {
+                    start: 0,
+                    end: 8191,
+                    primary: "first:6379".to_string(),
+                    replicas: vec![],
+                },
+                SlotRange {
+                    start: 8192,
+                    end: 16383,
+                    primary: "second:6379".to_string(),
+                    replicas: vec![],
+                },
+            ],
+        };
+        let keys: Vec<Vec<u8>> = (0..100).map(|i| format!("key{i}").into_bytes()).collect();
+        let keys: Vec<&[u8]> = keys.iter().map(Vec::as_slice).collect();
+        let node = |index: usize| topology.primary_for_slot(get_slot(keys[index]));
+
+        let chunks = group_keys(&keys, Some(&topology), 10);
+        let mut indices: Vec<usize> = chunks.iter().flatten().copied().collect();
+        indices.sort();
+        assert_eq!(indices, (0..100).collect::<Vec<_>>());
+        for chunk in &chunks {
+            assert!(!chunk.is_empty() && chunk.len() <= 10);
+            // Keys of different slots are grouped together, as long as they are served by the same node.
+            assert!(chunk.iter().all(|&index| node(index) == node(chunk[0])));
+        }
+        assert!(chunks.iter().any(|chunk| {
+            chunk
+                .iter()
+                .any(|&index| get_slot(keys[index]) != get_slot(keys[chunk[0]]))
+        }));
+
+        let chunks = group_keys(&keys, None, 30);
+        assert_eq!(
+            chunks.iter().map(Vec::len).collect::<Vec<_>>(),
+            vec![30, 30, 30, 10]
+        );
+    }
+
+    #[test]
+    fn test_failed_keys_are_collected() {
+        let error = redis::parse_redis_value(b"-OOM command not allowed\r\n").unwrap();
+        let mut succeeded = 0;
+        let mut failed = Vec::new();
+        collect_results(
+            vec![b"first".to_vec(), b"second".to_vec()],
+            Ok(Value::Array(vec![Value::Okay, error])),
+            &mut succeeded,
+            &mut failed,
+        );
+        let disconnect = std::io::Error::from(std::io::ErrorKind::BrokenPipe).into();
+        collect_results(
+            vec![b"third".to_vec()],
+            Err(disconnect),
+            &mut succeeded,
+            &mut failed,
+        );
+        assert_eq!(succeeded, 1);
+        assert_eq!(
+            failed,
+            vec![
+                Value::BulkString(b"second".to_vec()),
+                Value::BulkString(b"third".to_vec())
+            ]
+        );
+    }
+
+    #[test]
+    fn test_set_command() {
+        assert_eq!(
+            set_command(b"key", b"value", 60).get_packed_command(),
+            redis::cmd("SET")
+                .arg("key")
+                .arg("value")
+                .arg("EX")
+                .arg(60)
+                .get_packed_command()
+        );
+        assert_eq!(
+            set_command(b"key", b"value", 0).get_packed_command(),
+            redis::cmd("SET")
+                .arg("key")
+                .arg("value")
+                .get_packed_command()
+        );
+    }
+}
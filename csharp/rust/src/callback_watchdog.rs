@@ -0,0 +1,73 @@
+// Copyright Valkey GLIDE Project Contributors - SPDX Identifier: Apache-2.0
+
+use std::time::{Duration, Instant};
+
+use tokio::runtime::Handle;
+
+/// Watches the time spent in the success and failure callbacks, and optionally calls them on the blocking pool of the runtime,
+/// see [`ConnectionConfig::slow_callback_threshold_ms`](crate::ffi::ConnectionConfig::slow_callback_threshold_ms)
+/// and [`ConnectionConfig::offload_callbacks`](crate::ffi::ConnectionConfig::offload_callbacks).
+#[derive(Debug, Clone)]
+pub(crate) struct CallbackWatchdog {
+    /// Time in a callback above which a warning is logged, `None` if callbacks aren't timed.
+    threshold: Option<Duration>,
+    /// The runtime whose blocking pool callbacks are called on, `None` to call them on the reporting thread.
+    offload: Option<Handle>,
+}
+
+impl CallbackWatchdog {
+    pub(crate) fn new(threshold: Option<Duration>, offload: Option<Handle>) -> Self {
+        CallbackWatchdog { threshold, offload }
+    }
+
+    /// Call a callback of the request of `callback_index` by `call`, timing it if a threshold is configured.
+    pub(crate) fn call(&self, callback_index: usize, call: impl FnOnce() + Send + 'static) {
+        let threshold = self.threshold;
+        let timed_call = move || {
+            let started = Instant::now();
+            call();
+            let elapsed = started.elapsed();
+            if threshold.is_some_and(|threshold| elapsed > threshold) {
+                logger_core::log_warn(
+                    "callback watchdog",
+                    format!(
+                        "The callback of request {callback_index} took {}ms. Callbacks should return immediately, \
+                        since slow callbacks starve the threads of the client",
+                        elapsed.as_millis()
+                    ),
+                );
+            }
+        };
+        match &self.offload {
+            Some(runtime) => drop(runtime.spawn_blocking(timed_call)),
+            None => timed_call(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::mpsc;
+
+    #[test]
+    fn test_offloads_callbacks_to_the_blocking_pool() {
+        let runtime = tokio::runtime::Builder::new_multi_thread()
+            .worker_threads(1)
+            .build()
+            .unwrap();
+        let (sender, receiver) = mpsc::channel();
+
+        let watchdog = CallbackWatchdog::new(Some(Duration::from_millis(1)), None);
+        let called = sender.clone();
+        watchdog.call(1, move || called.send(std::thread::current().id()).unwrap());
+        assert_eq!(receiver.recv().unwrap(), std::thread::current().id());
+
+        let watchdog = CallbackWatchdog::new(None, Some(runtime.handle().clone()));
+        watchdog.call(2, move || sender.send(std::thread::current().id()).unwrap());
+        assert_ne!(
+            receiver.recv_timeout(Duration::from_secs(5)).unwrap(),
+            std::thread::current().id()
+        );
+    }
+}
@@ -0,0 +1,146 @@
+// Copyright Valkey GLIDE Project Contributors - SPDX Identifier: Apache-2.0
+
+use std::{
+    sync::atomic::{AtomicBool, AtomicU32, AtomicU64, Ordering},
+    time::Duration,
+};
+
+use redis::{RedisError, RedisResult};
+
+use crate::errors::{error_type, RequestErrorType};
+
+/// Delay between opening the breaker and the first topology refresh which could close it.
+const INITIAL_BACKOFF: Duration = Duration::from_millis(500);
+
+/// Maximal delay between opening the breaker and refreshing the topology, or between failed refreshes.
+pub(crate) const MAX_BACKOFF: Duration = Duration::from_secs(10);
+
+/// Stops dispatching commands after consecutive `CLUSTERDOWN` errors, so the client doesn't keep hammering a recovering cluster,
+/// see [`ConnectionConfig::circuit_breaker_threshold`](crate::ffi::ConnectionConfig::circuit_breaker_threshold).
+/// While the breaker is open, commands fail fast with a [`RequestErrorType::ClusterDown`] error. It is closed once a topology refresh
+/// succeeds after a backoff, which doubles every time the breaker opens again before a command succeeded in between.
+#[derive(Debug)]
+pub(crate) struct CircuitBreaker {
+    threshold: u32,
+    consecutive_cluster_down: AtomicU32,
+    open: AtomicBool,
+    backoff_ms: AtomicU64,
+}
+
+impl CircuitBreaker {
+    /// Create a breaker which opens after `threshold` consecutive `CLUSTERDOWN` errors, `None` if `threshold` is `0`.
+    pub(crate) fn new(threshold: u32) -> Option<Self> {
+        (threshold > 0).then(|| CircuitBreaker {
+            threshold,
+            consecutive_cluster_down: AtomicU32::new(0),
+            open: AtomicBool::new(false),
+            backoff_ms: AtomicU64::new(INITIAL_BACKOFF.as_millis() as u64),
+        })
+    }
+
+    /// Fail with a [`RequestErrorType::ClusterDown`] error if the breaker is open.
+    pub(crate) fn check(&self) -> RedisResult<()> {
+        if self.open.load(Ordering::Acquire) {
+            return Err(RedisError::from((
+                redis::ErrorKind::ClusterDown,
+                "The circuit breaker is open after repeated CLUSTERDOWN errors",
+            )));
+        }
+        Ok(())
+    }
+
+    /// Record the result of a command. Returns the backoff before refreshing the topology if the result opened the breaker.
+    pub(crate) fn record<T>(&self, result: &RedisResult<T>) -> Option<Duration> {
+        match result {
+            Err(err) if error_type(err) == RequestErrorType::ClusterDown => {
+                let count = self.consecutive_cluster_down.fetch_add(1, Ordering::AcqRel) + 1;
+                if count < self.threshold || self.open.swap(true, Ordering::AcqRel) {
+                    return None;
+                }
+                Some(Duration::from_millis(
+                    self.backoff_ms.load(Ordering::Acquire),
+                ))
+            }
+            result => {
+                // Loaded first, so commands don't write to the shared counters while the cluster is healthy
+                if self.consecutive_cluster_down.load(Ordering::Acquire) > 0 {
+                    self.consecutive_cluster_down.store(0, Ordering::Release);
+                }
+                let initial_backoff_ms = INITIAL_BACKOFF.as_millis() as u64;
+                if result.is_ok() && self.backoff_ms.load(Ordering::Acquire) != initial_backoff_ms {
+                    self.backoff_ms.store(initial_backoff_ms, Ordering::Release);
+                }
+                None
+            }
+        }
+    }
+
+    /// Close the breaker after a successful topology refresh. If it opens again before a command succeeded, the backoff is doubled.
+    pub(crate) fn close(&self) {
+        self.consecutive_cluster_down.store(0, Ordering::Release);
+        let backoff_ms = self.backoff_ms.load(Ordering::Acquire);
+        self.backoff_ms.store(
+            (backoff_ms * 2).min(MAX_BACKOFF.as_millis() as u64),
+            Ordering::Release,
+        );
+        self.open.store(false, Ordering::Release);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cluster_down() -> RedisResult<()> {
+        Err(RedisError::from((
+            redis::ErrorKind::ClusterDown,
+            "The cluster is down",
+        )))
+    }
+
+    #[test]
+    fn test_opens_after_consecutive_cluster_down_errors() {
+        let circuit_breaker = CircuitBreaker::new(3).unwrap();
+        assert_eq!(circuit_breaker.record(&cluster_down()), None);
+        assert_eq!(circuit_breaker.record(&cluster_down()), None);
+        // A success in between resets the count
+        assert_eq!(circuit_breaker.record(&Ok(())), None);
+        assert_eq!(circuit_breaker.record(&cluster_down()), None);
+        assert_eq!(circuit_breaker.record(&cluster_down()), None);
+        assert!(circuit_breaker.check().is_ok());
+
+        assert_eq!(
+            circuit_breaker.record(&cluster_down()),
+            Some(INITIAL_BACKOFF)
+        );
+        let err = circuit_breaker.check().unwrap_err();
+        assert_eq!(error_type(&err), RequestErrorType::ClusterDown);
+        // Commands which were in flight while it opened don't open it again
+        assert_eq!(circuit_breaker.record(&cluster_down()), None);
+
+        assert!(CircuitBreaker::new(0).is_none());
+    }
+
+    #[test]
+    fn test_closes_after_refresh_with_growing_backoff() {
+        let circuit_breaker = CircuitBreaker::new(1).unwrap();
+        assert_eq!(
+            circuit_breaker.record(&cluster_down()),
+            Some(INITIAL_BACKOFF)
+        );
+        circuit_breaker.close();
+        assert!(circuit_breaker.check().is_ok());
+
+        // Opened again before any command succeeded
+        assert_eq!(
+            circuit_breaker.record(&cluster_down()),
+            Some(INITIAL_BACKOFF * 2)
+        );
+        circuit_breaker.close();
+        assert_eq!(circuit_breaker.record(&Ok(())), None);
+        assert_eq!(
+            circuit_breaker.record(&cluster_down()),
+            Some(INITIAL_BACKOFF)
+        );
+    }
+}
@@ -0,0 +1,87 @@
+// Copyright Valkey GLIDE Project Contributors - SPDX Identifier: Apache-2.0
+
+//! Multiple connections per node, see [`ConnectionConfig::connections_per_node`](crate::ffi::ConnectionConfig::connections_per_node).
+//! glide-core multiplexes all commands to a node on a single connection, so a large reply delays the replies queued behind it.
+//! The pool holds several glide-core clients, each with its own connections, and spreads the commands across them.
+
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+use glide_core::client::{Client as GlideClient, ConnectionRequest};
+use redis::{PushInfo, RedisError, RedisResult};
+use tokio::{sync::mpsc::UnboundedSender, task::JoinSet};
+
+/// Clients with separate connections to all nodes, which commands are sent by in round-robin.
+pub(crate) struct ClientPool {
+    clients: Vec<GlideClient>,
+    next: AtomicUsize,
+    /// Whether every client was picked for a command since the last [`ClientPool::take_idle`].
+    used: Vec<AtomicBool>,
+}
+
+impl ClientPool {
+    /// Create a pool of `client` and `size - 1` additional clients of `request`, which are connected concurrently.
+    /// A `size` of `0` or `1` means that the pool holds `client` only.
+    /// Push notifications of all clients are sent to `push_sender`, so that client tracking covers every connection.
+    pub(crate) async fn connect(
+        client: GlideClient,
+        request: &ConnectionRequest,
+        push_sender: Option<UnboundedSender<PushInfo>>,
+        size: u32,
+    ) -> RedisResult<Self> {
+        let mut connects = JoinSet::new();
+        for _ in 1..size {
+            connects.spawn(GlideClient::new(request.clone(), push_sender.clone()));
+        }
+        let mut clients = vec![client];
+        while let Some(connected) = connects.join_next().await {
+            match connected {
+                Ok(Ok(client)) => clients.push(client),
+                Ok(Err(err)) => return Err(connect_error(err.to_string())),
+                Err(err) => return Err(connect_error(err.to_string())),
+            }
+        }
+        Ok(ClientPool::new(clients))
+    }
+
+    fn new(clients: Vec<GlideClient>) -> Self {
+        ClientPool {
+            used: clients.iter().map(|_| AtomicBool::new(false)).collect(),
+            clients,
+            next: AtomicUsize::new(0),
+        }
+    }
+
+    /// The client to send the next command by.
+    pub(crate) fn next(&self) -> GlideClient {
+        let index = self.next.fetch_add(1, Ordering::Relaxed) % self.clients.len();
+        self.used[index].store(true, Ordering::Relaxed);
+        self.clients[index].clone()
+    }
+
+    /// The client to send the commands with the given affinity token by, so they share a connection, e.g. for the cache locality
+    /// of a session. The same token always maps to the same client, since the size of the pool doesn't change.
+    pub(crate) fn for_token(&self, affinity_token: u64) -> GlideClient {
+        let index = (affinity_token % self.clients.len() as u64) as usize;
+        self.used[index].store(true, Ordering::Relaxed);
+        self.clients[index].clone()
+    }
+
+    /// The clients which weren't picked for a command since the previous call, e.g. to keep their connections alive.
+    pub(crate) fn take_idle(&self) -> Vec<GlideClient> {
+        self.clients
+            .iter()
+            .zip(&self.used)
+            .filter(|(_, used)| !used.swap(false, Ordering::Relaxed))
+            .map(|(client, _)| client.clone())
+            .collect()
+    }
+
+    /// All clients, to apply a setting to the connections of every client, e.g. re-authentication.
+    pub(crate) fn all(&self) -> &[GlideClient] {
+        &self.clients
+    }
+}
+
+fn connect_error(detail: String) -> RedisError {
+    RedisError::from((redis::ErrorKind::ClientError, "Failed to connect", detail))
+}
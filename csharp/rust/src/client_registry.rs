@@ -0,0 +1,171 @@
+// Copyright Valkey GLIDE Project Contributors - SPDX Identifier: Apache-2.0
+
+//! The clients created by [`create_client`](crate::create_client), by pointer.
+//! A closed client is deallocated once its requests are done, so a pointer is looked up here under the lock of the registry
+//! before it is dereferenced, and requests submitted with the pointer of a closed client fail without touching the client.
+//!
+//! Every request looks up its client, so the registry is split into shards by address, each behind a read-write lock,
+//! and lookups of different clients rarely touch the same lock. Only the last [`CLOSED_PER_SHARD`] closed clients of a shard
+//! are remembered, so short-lived clients don't grow the registry. A pointer which isn't registered, e.g. of a client
+//! which was closed long ago, is logged and ignored.
+
+use std::{
+    collections::{HashMap, VecDeque},
+    ffi::c_void,
+    sync::{atomic::Ordering, Arc, LazyLock, RwLock},
+};
+
+use crate::{Client, FailureCallback};
+
+/// Number of shards of the registry, a power of two.
+const SHARDS: usize = 16;
+/// Closed clients remembered per shard, whose requests fail with [`RequestErrorType::ClientClosed`](crate::errors::RequestErrorType::ClientClosed).
+const CLOSED_PER_SHARD: usize = 64;
+
+enum Registration {
+    Open,
+    /// The client was closed. Its failure callback is kept to fail the requests submitted with the pointer afterwards.
+    /// The registration is replaced once a new client is allocated at the same address, or once it's evicted by newer closed clients.
+    Closed(FailureCallback),
+}
+
+#[derive(Default)]
+struct Shard {
+    registrations: HashMap<usize, Registration>,
+    /// Addresses of the closed registrations, from the oldest to the newest.
+    closed: VecDeque<usize>,
+}
+
+impl Shard {
+    fn register(&mut self, address: usize) {
+        self.closed.retain(|closed| *closed != address);
+        self.registrations.insert(address, Registration::Open);
+    }
+
+    /// Mark the registration as closed, and evict the oldest closed registration if there are too many.
+    fn close(&mut self, address: usize, failure_callback: FailureCallback) {
+        self.registrations
+            .insert(address, Registration::Closed(failure_callback));
+        self.closed.push_back(address);
+        if self.closed.len() > CLOSED_PER_SHARD {
+            if let Some(evicted) = self.closed.pop_front() {
+                self.registrations.remove(&evicted);
+            }
+        }
+    }
+}
+
+static CLIENTS: LazyLock<[RwLock<Shard>; SHARDS]> = LazyLock::new(Default::default);
+
+fn shard(client_ptr: *const c_void) -> &'static RwLock<Shard> {
+    // The low bits of an allocation are mostly the same due to alignment
+    &CLIENTS[(client_ptr as usize >> 4) % SHARDS]
+}
+
+fn log_unknown(operation: &str, client_ptr: *const c_void) {
+    logger_core::log_error(
+        "client registry",
+        format!("{operation} with the pointer {client_ptr:?}, which isn't a client created by create_client, or was closed long ago"),
+    );
+}
+
+/// Move the client to the heap and register it, returns the pointer handed to the caller,
+/// which holds a strong reference to the client until [`close`] is called with it.
+pub(crate) fn register(client: Client) -> *const c_void {
+    let client_ptr = Arc::into_raw(Arc::new(client)) as *const c_void;
+    shard(client_ptr)
+        .write()
+        .unwrap()
+        .register(client_ptr as usize);
+    client_ptr
+}
+
+/// Get an owned reference to the client, or its failure callback if it was closed.
+/// `Err(None)` is returned, and logged, if the pointer isn't registered.
+pub(crate) fn get(client_ptr: *const c_void) -> Result<Arc<Client>, Option<FailureCallback>> {
+    let shard = shard(client_ptr).read().unwrap();
+    match shard.registrations.get(&(client_ptr as usize)) {
+        // The client can't be deallocated while the registry is locked, since the reference of the caller is released by `close`.
+        Some(Registration::Open) => unsafe {
+            Arc::increment_strong_count(client_ptr as *const Client);
+            Ok(Arc::from_raw(client_ptr as *const Client))
+        },
+        Some(Registration::Closed(failure_callback)) => Err(Some(*failure_callback)),
+        None => {
+            log_unknown("Request", client_ptr);
+            Err(None)
+        }
+    }
+}
+
+/// Mark the client as closed, and return the strong reference of the caller to the client,
+/// which deallocates the client once it's dropped and all requests are done.
+/// Returns `None` if the client was closed before, or if the pointer isn't registered, which is logged.
+pub(crate) fn close(client_ptr: *const c_void) -> Option<Arc<Client>> {
+    let address = client_ptr as usize;
+    let mut shard = shard(client_ptr).write().unwrap();
+    match shard.registrations.get(&address) {
+        Some(Registration::Open) => {}
+        Some(Registration::Closed(_)) => return None,
+        None => {
+            log_unknown("Close", client_ptr);
+            return None;
+        }
+    }
+    let client = unsafe { Arc::from_raw(client_ptr as *const Client) };
+    client.closed.store(true, Ordering::Release);
+    shard.close(address, client.core.failure_callback);
+    Some(client)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::errors::RequestErrorType;
+    use std::ffi::c_char;
+
+    unsafe extern "C" fn failure_callback(_: usize, _: RequestErrorType, _: *const c_char) {}
+
+    #[test]
+    fn test_closed_registrations_are_evicted() {
+        let mut shard = Shard::default();
+        let addresses: Vec<usize> = (1..=CLOSED_PER_SHARD + 2)
+            .map(|address| address * 16)
+            .collect();
+        for &address in &addresses {
+            shard.register(address);
+        }
+        for &address in &addresses {
+            shard.close(address, failure_callback);
+        }
+        // The two oldest closed registrations were evicted
+        assert_eq!(shard.registrations.len(), CLOSED_PER_SHARD);
+        assert!(!shard.registrations.contains_key(&addresses[0]));
+        assert!(!shard.registrations.contains_key(&addresses[1]));
+        assert!(matches!(
+            shard.registrations.get(&addresses[2]),
+            Some(Registration::Closed(_))
+        ));
+
+        // A new client allocated at the address of a closed one isn't evicted with the closed registration
+        shard.register(addresses[2]);
+        shard.register(addresses[0]);
+        shard.close(addresses[0], failure_callback);
+        shard.register(addresses[1]);
+        shard.close(addresses[1], failure_callback);
+        assert!(!shard.registrations.contains_key(&addresses[3]));
+        assert!(matches!(
+            shard.registrations.get(&addresses[2]),
+            Some(Registration::Open)
+        ));
+        assert_eq!(shard.closed.len(), CLOSED_PER_SHARD);
+    }
+
+    #[test]
+    fn test_unknown_pointers_are_ignored() {
+        let unknown = Box::new(0u64);
+        let client_ptr = &*unknown as *const u64 as *const c_void;
+        assert!(matches!(get(client_ptr), Err(None)));
+        assert!(close(client_ptr).is_none());
+    }
+}
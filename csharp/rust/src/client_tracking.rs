@@ -0,0 +1,125 @@
+// Copyright Valkey GLIDE Project Contributors - SPDX Identifier: Apache-2.0
+
+use redis::{Cmd, PushInfo, PushKind, Value};
+
+/// Mode of server-assisted client-side caching (`CLIENT TRACKING`), see [`ConnectionConfig::client_tracking_mode`](crate::ffi::ConnectionConfig::client_tracking_mode).
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ClientTrackingMode {
+    /// Tracking is disabled.
+    Disabled = 0,
+    /// The server tracks the keys read by the connection, and invalidates them once they are modified.
+    Default = 1,
+    /// The server invalidates every modified key which starts with one of the prefixes (`BCAST`), whether it was read or not.
+    Broadcast = 2,
+    /// The server tracks only keys read by a command following `CLIENT CACHING yes` (`OPTIN`).
+    OptIn = 3,
+    /// The server tracks all keys read, except by a command following `CLIENT CACHING no` (`OPTOUT`).
+    OptOut = 4,
+}
+
+/// Invalidation callback that is called when keys tracked by the client were modified, see [`ClientTrackingMode`].
+///
+/// The arguments are the invalidated keys, their lengths and their count. The keys are only valid until the callback returns,
+/// so they should be copied synchronously. If the count is `0`, all keys are invalidated, e.g. after `FLUSHALL`.
+pub type InvalidationCallback = unsafe extern "C" fn(*const *const u8, *const u32, u32) -> ();
+
+/// Tracking configuration of the connections of a client.
+#[derive(Debug)]
+pub(crate) struct ClientTracking {
+    mode: ClientTrackingMode,
+    /// Prefixes of the keys to invalidate in [`ClientTrackingMode::Broadcast`], all keys if empty.
+    prefixes: Vec<String>,
+}
+
+impl ClientTracking {
+    /// Create the tracking configuration, `None` if it is disabled.
+    pub(crate) fn new(mode: ClientTrackingMode, prefixes: Vec<String>) -> Option<Self> {
+        (mode != ClientTrackingMode::Disabled).then_some(ClientTracking { mode, prefixes })
+    }
+
+    /// The command which enables tracking on a connection.
+    /// It is sent when the client is created, and again whenever a connection is re-established,
+    /// since tracking is a state of the connection which the server drops once it disconnects.
+    pub(crate) fn command(&self) -> Cmd {
+        let mut cmd = redis::cmd("CLIENT");
+        cmd.arg("TRACKING").arg("ON");
+        match self.mode {
+            ClientTrackingMode::Broadcast => {
+                cmd.arg("BCAST");
+                for prefix in &self.prefixes {
+                    cmd.arg("PREFIX").arg(prefix);
+                }
+            }
+            ClientTrackingMode::OptIn => {
+                cmd.arg("OPTIN");
+            }
+            ClientTrackingMode::OptOut => {
+                cmd.arg("OPTOUT");
+            }
+            ClientTrackingMode::Disabled | ClientTrackingMode::Default => {}
+        }
+        cmd
+    }
+}
+
+/// Pass the keys of an `invalidate` push to the invalidation callback. Other pushes are ignored.
+///
+/// # Safety
+///
+/// * `callback` must be safe to call with the keys of any invalidation.
+pub(crate) unsafe fn report_invalidation(callback: InvalidationCallback, push: &PushInfo) {
+    if push.kind != PushKind::Invalidate {
+        return;
+    }
+    let keys: Vec<&[u8]> = match push.data.first() {
+        Some(Value::Array(keys)) => keys
+            .iter()
+            .filter_map(|key| match key {
+                Value::BulkString(key) => Some(key.as_slice()),
+                Value::SimpleString(key) => Some(key.as_bytes()),
+                _ => None,
+            })
+            .collect(),
+        // A null invalidates all keys.
+        _ => vec![],
+    };
+    let pointers: Vec<*const u8> = keys.iter().map(|key| key.as_ptr()).collect();
+    let lengths: Vec<u32> = keys.iter().map(|key| key.len() as u32).collect();
+    unsafe { callback(pointers.as_ptr(), lengths.as_ptr(), keys.len() as u32) };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(cmd: &Cmd) -> Vec<String> {
+        cmd.args_iter()
+            .map(|arg| match arg {
+                redis::Arg::Simple(arg) => String::from_utf8_lossy(arg).to_string(),
+                redis::Arg::Cursor => "CURSOR".to_string(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_tracking_command() {
+        let tracking = ClientTracking::new(ClientTrackingMode::Default, vec![]).unwrap();
+        assert_eq!(args(&tracking.command()), ["CLIENT", "TRACKING", "ON"]);
+        let tracking = ClientTracking::new(
+            ClientTrackingMode::Broadcast,
+            vec!["user:".to_string(), "session:".to_string()],
+        )
+        .unwrap();
+        assert_eq!(
+            args(&tracking.command()),
+            ["CLIENT", "TRACKING", "ON", "BCAST", "PREFIX", "user:", "PREFIX", "session:"]
+        );
+        let tracking = ClientTracking::new(ClientTrackingMode::OptIn, vec![]).unwrap();
+        assert_eq!(
+            args(&tracking.command()),
+            ["CLIENT", "TRACKING", "ON", "OPTIN"]
+        );
+        assert!(ClientTracking::new(ClientTrackingMode::Disabled, vec![]).is_none());
+    }
+}
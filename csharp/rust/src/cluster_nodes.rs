@@ -0,0 +1,401 @@
+// Copyright Valkey GLIDE Project Contributors - SPDX Identifier: Apache-2.0
+
+//! Parsing of `CLUSTER NODES` and `CLUSTER SHARDS` to a common structure of nodes, see [`convert_cluster_nodes`] and [`convert_cluster_shards`].
+
+use redis::{ErrorKind, RedisError, RedisResult, Value};
+
+use crate::info::bulk;
+
+/// Build a map value with the given string keys.
+fn map(entries: Vec<(&str, Value)>) -> Value {
+    Value::Map(
+        entries
+            .into_iter()
+            .map(|(key, value)| (bulk(key), value))
+            .collect(),
+    )
+}
+
+/// Get the text of a string reply.
+fn text(value: Value) -> RedisResult<String> {
+    match value {
+        Value::BulkString(text) => Ok(String::from_utf8_lossy(&text).into_owned()),
+        Value::SimpleString(text) | Value::VerbatimString { text, .. } => Ok(text),
+        value => Err(invalid_response(format!(
+            "expected a string, got {value:?}"
+        ))),
+    }
+}
+
+/// Convert an inclusive range of slots to a map with `start` and `end` integers.
+fn slot_range(start: i64, end: i64) -> Value {
+    map(vec![("start", Value::Int(start)), ("end", Value::Int(end))])
+}
+
+/// Name a role the way the rest of the client does, `primary` or `replica`, instead of the server's `master` or `slave`.
+fn role(role: &str) -> &str {
+    match role {
+        "master" => "primary",
+        "slave" => "replica",
+        role => role,
+    }
+}
+
+/// Parse a line of `CLUSTER NODES`:
+/// `<id> <ip:port@cport[,hostname]> <flags> <primary> <ping-sent> <pong-recv> <config-epoch> <link-state> <slot> ...`,
+/// where every slot is a `start-end` range, a single slot, or a slot being migrated, `[slot->-id]`, or imported, `[slot-<-id]`.
+fn parse_node_line(line: &str) -> RedisResult<Value> {
+    let fields: Vec<&str> = line.split_whitespace().collect();
+    let [id, address, flags, primary, _ping, _pong, config_epoch, link_state, slots @ ..] =
+        fields.as_slice()
+    else {
+        return Err(invalid_response(format!("incomplete node line: {line}")));
+    };
+    let (address, hostname) = match address.split_once(',') {
+        Some((address, hostname)) => (address, Some(hostname)),
+        None => (*address, None),
+    };
+    let address = address
+        .split_once('@')
+        .map_or(address, |(address, _)| address);
+    let flags: Vec<&str> = flags.split(',').collect();
+    let role = match flags
+        .iter()
+        .find(|flag| **flag == "master" || **flag == "slave")
+    {
+        Some(flag) => self::role(flag),
+        None => "unknown",
+    };
+    let health = if flags.contains(&"fail") {
+        "failed"
+    } else if flags.contains(&"fail?") {
+        "suspected"
+    } else if *link_state == "disconnected" {
+        "disconnected"
+    } else {
+        "online"
+    };
+    let parse_slot = |slot: &str| {
+        slot.parse::<i64>()
+            .map_err(|_| invalid_response(format!("invalid slot: {slot}")))
+    };
+    let mut ranges = vec![];
+    let mut migrating = vec![];
+    let mut importing = vec![];
+    for slot in slots {
+        if let Some(state) = slot
+            .strip_prefix('[')
+            .and_then(|slot| slot.strip_suffix(']'))
+        {
+            let (slot, states, node_id) = if let Some((slot, node_id)) = state.split_once("->-") {
+                (slot, &mut migrating, node_id)
+            } else if let Some((slot, node_id)) = state.split_once("-<-") {
+                (slot, &mut importing, node_id)
+            } else {
+                return Err(invalid_response(format!("invalid slot state: {slot}")));
+            };
+            states.push(map(vec![
+                ("slot", Value::Int(parse_slot(slot)?)),
+                ("node_id", bulk(node_id)),
+            ]));
+            continue;
+        }
+        let (start, end) = slot.split_once('-').unwrap_or((slot, slot));
+        ranges.push(slot_range(parse_slot(start)?, parse_slot(end)?));
+    }
+    let config_epoch = config_epoch
+        .parse::<i64>()
+        .map_err(|_| invalid_response(format!("invalid config epoch: {config_epoch}")))?;
+    Ok(map(vec![
+        ("id", bulk(id)),
+        ("address", bulk(address)),
+        (
+            "hostname",
+            hostname
+                .filter(|hostname| !hostname.is_empty())
+                .map_or(Value::Nil, bulk),
+        ),
+        ("role", bulk(role)),
+        (
+            "primary_id",
+            if *primary == "-" {
+                Value::Nil
+            } else {
+                bulk(primary)
+            },
+        ),
+        ("myself", Value::Boolean(flags.contains(&"myself"))),
+        ("flags", Value::Array(flags.into_iter().map(bulk).collect())),
+        ("health", bulk(health)),
+        ("config_epoch", Value::Int(config_epoch)),
+        ("slots", Value::Array(ranges)),
+        ("migrating", Value::Array(migrating)),
+        ("importing", Value::Array(importing)),
+    ]))
+}
+
+/// Convert a `CLUSTER NODES` response, whose lines describe a node each, to an array with a map for every node:
+/// * `id` - the node id.
+/// * `address` - the `host:port` address of the node, `host` is empty if the node's address is unknown.
+/// * `hostname` - the announced hostname, or null if none is announced.
+/// * `role` - `primary`, `replica`, or `unknown` during a handshake.
+/// * `primary_id` - the id of the primary of a replica, or null for primaries.
+/// * `myself` - whether this is the node which replied.
+/// * `flags` - the raw flags of the node, e.g. `myself`, `master`, `fail?` or `nofailover`.
+/// * `health` - `failed` or `suspected` if the node is flagged as failing by the cluster or by the replying node,
+///   `disconnected` if the replying node has no link to it, `online` otherwise.
+/// * `config_epoch` - the configuration epoch of the node.
+/// * `slots` - an array of maps of inclusive `start` and `end` slots served by the node.
+/// * `migrating` and `importing` - arrays of maps of a `slot` being migrated to, or imported from, the node of `node_id`.
+///   These are only reported by the node which replied, for its own slots.
+pub(crate) fn convert_cluster_nodes(value: Value) -> RedisResult<Value> {
+    text(value)?
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(parse_node_line)
+        .collect::<RedisResult<_>>()
+        .map(Value::Array)
+}
+
+/// Get the fields of a `CLUSTER SHARDS` entry, which is a map, or an array of fields and values with RESP2.
+fn fields(value: Value) -> RedisResult<Vec<(String, Value)>> {
+    let fields = match value {
+        Value::Map(fields) => fields,
+        Value::Array(fields) if fields.len() % 2 == 0 => {
+            let mut fields = fields.into_iter();
+            std::iter::from_fn(|| Some((fields.next()?, fields.next()?))).collect()
+        }
+        value => return Err(invalid_response(format!("expected a map, got {value:?}"))),
+    };
+    fields
+        .into_iter()
+        .map(|(name, value)| Ok((text(name)?, value)))
+        .collect()
+}
+
+/// Convert a node of `CLUSTER SHARDS` to the structure of [`convert_cluster_nodes`], as far as `CLUSTER SHARDS` reports it.
+fn convert_shard_node(node: Value) -> RedisResult<Value> {
+    let mut id = Value::Nil;
+    let mut host = None;
+    let mut port = None;
+    let mut hostname = Value::Nil;
+    let mut role = Value::Nil;
+    let mut replication_offset = Value::Nil;
+    let mut health = Value::Nil;
+    for (name, value) in fields(node)? {
+        match name.as_str() {
+            "id" => id = bulk(&text(value)?),
+            // The endpoint is the preferred address of the node for clients, it may be a hostname or `?` if it is unknown
+            "endpoint" => host = Some(text(value)?),
+            "ip" if host.is_none() => host = Some(text(value)?),
+            "port" | "tls-port" => port = Some(value),
+            "hostname" => hostname = bulk(&text(value)?),
+            "role" => role = bulk(self::role(&text(value)?)),
+            "replication-offset" => replication_offset = value,
+            "health" => health = bulk(&text(value)?),
+            _ => {}
+        }
+    }
+    let address = match (host, port) {
+        (Some(host), Some(Value::Int(port))) => bulk(&format!("{host}:{port}")),
+        _ => Value::Nil,
+    };
+    Ok(map(vec![
+        ("id", id),
+        ("address", address),
+        ("hostname", hostname),
+        ("role", role),
+        ("health", health),
+        ("replication_offset", replication_offset),
+    ]))
+}
+
+/// Convert a `CLUSTER SHARDS` response to an array with a map for every shard, a primary and its replicas:
+/// * `slots` - an array of maps of inclusive `start` and `end` slots served by the shard.
+/// * `nodes` - an array with a map for every node of the shard: its `id`, `address` (`host:port`, or null if unknown), `hostname`
+///   (null if none is announced), `role` (`primary` or `replica`), `health` (`online`, `failed` or `loading`) and `replication_offset`.
+pub(crate) fn convert_cluster_shards(value: Value) -> RedisResult<Value> {
+    let Value::Array(shards) = value else {
+        return Err(invalid_response(format!(
+            "expected an array, got {value:?}"
+        )));
+    };
+    shards
+        .into_iter()
+        .map(|shard| {
+            let mut slots = vec![];
+            let mut nodes = vec![];
+            for (name, value) in fields(shard)? {
+                match (name.as_str(), value) {
+                    ("slots", Value::Array(bounds)) => {
+                        for range in bounds.chunks_exact(2) {
+                            let [Value::Int(start), Value::Int(end)] = range else {
+                                return Err(invalid_response(
+                                    "slot bounds are not integers".into(),
+                                ));
+                            };
+                            slots.push(slot_range(*start, *end));
+                        }
+                    }
+                    ("nodes", Value::Array(shard_nodes)) => {
+                        nodes = shard_nodes
+                            .into_iter()
+                            .map(convert_shard_node)
+                            .collect::<RedisResult<_>>()?;
+                    }
+                    _ => {}
+                }
+            }
+            Ok(map(vec![
+                ("slots", Value::Array(slots)),
+                ("nodes", Value::Array(nodes)),
+            ]))
+        })
+        .collect::<RedisResult<_>>()
+        .map(Value::Array)
+}
+
+fn invalid_response(detail: String) -> RedisError {
+    RedisError::from((ErrorKind::TypeError, "Unexpected CLUSTER response", detail))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn get<'a>(node: &'a Value, name: &str) -> &'a Value {
+        let Value::Map(fields) = node else {
+            panic!("not a map: {node:?}");
+        };
+        &fields.iter().find(|(key, _)| *key == bulk(name)).unwrap().1
+    }
+
+    #[test]
+    fn test_convert_cluster_nodes() {
+        let nodes = "\
+07c37dfeb235213a872192d90877d0cd55635b91 127.0.0.1:30004@31004,node4.example slave e7d1eecce10fd6bb5eb35b9f99a514335d9ba9ca 0 1426238317239 4 connected
+67ed2db8d677e59ec4a4cefb06858cf2a1a89fa1 127.0.0.1:30002@31002 master,fail? - 0 1426238316232 2 connected 5461-10922
+e7d1eecce10fd6bb5eb35b9f99a514335d9ba9ca 127.0.0.1:30001@31001 myself,master - 0 0 1 connected 0-5460 [93->-67ed2db8d677e59ec4a4cefb06858cf2a1a89fa1] [77-<-292f8b365bb7edb5e285caf0b7e6ddc7265d2f4f]
+6ec23923021cf3ffec47632106199cb7f496ce01 127.0.0.1:30005@31005 slave 67ed2db8d677e59ec4a4cefb06858cf2a1a89fa1 0 1426238316232 5 disconnected
+";
+        let Value::Array(nodes) = convert_cluster_nodes(Value::VerbatimString {
+            format: redis::VerbatimFormat::Text,
+            text: nodes.to_string(),
+        })
+        .unwrap() else {
+            panic!("not an array");
+        };
+        assert_eq!(nodes.len(), 4);
+
+        let replica = &nodes[0];
+        assert_eq!(get(replica, "address"), &bulk("127.0.0.1:30004"));
+        assert_eq!(get(replica, "hostname"), &bulk("node4.example"));
+        assert_eq!(get(replica, "role"), &bulk("replica"));
+        assert_eq!(
+            get(replica, "primary_id"),
+            &bulk("e7d1eecce10fd6bb5eb35b9f99a514335d9ba9ca")
+        );
+        assert_eq!(get(replica, "slots"), &Value::Array(vec![]));
+
+        assert_eq!(get(&nodes[1], "health"), &bulk("suspected"));
+        assert_eq!(
+            get(&nodes[1], "slots"),
+            &Value::Array(vec![slot_range(5461, 10922)])
+        );
+
+        let myself = &nodes[2];
+        assert_eq!(get(myself, "myself"), &Value::Boolean(true));
+        assert_eq!(get(myself, "primary_id"), &Value::Nil);
+        assert_eq!(get(myself, "hostname"), &Value::Nil);
+        assert_eq!(
+            get(myself, "slots"),
+            &Value::Array(vec![slot_range(0, 5460)])
+        );
+        assert_eq!(
+            get(myself, "migrating"),
+            &Value::Array(vec![map(vec![
+                ("slot", Value::Int(93)),
+                ("node_id", bulk("67ed2db8d677e59ec4a4cefb06858cf2a1a89fa1")),
+            ])])
+        );
+        assert_eq!(
+            get(myself, "importing"),
+            &Value::Array(vec![map(vec![
+                ("slot", Value::Int(77)),
+                ("node_id", bulk("292f8b365bb7edb5e285caf0b7e6ddc7265d2f4f")),
+            ])])
+        );
+
+        // The replica of the primary of slots 5461-10922, which the replying node has no link to
+        assert_eq!(get(&nodes[3], "health"), &bulk("disconnected"));
+        assert_eq!(get(&nodes[3], "primary_id"), get(&nodes[1], "id"),);
+
+        assert!(convert_cluster_nodes(bulk("abc 127.0.0.1:30001@31001 master")).is_err());
+    }
+
+    fn shard_node(id: &str, port: i64, role: &str, health: &str) -> Value {
+        // RESP2 replies nodes as arrays of fields and values
+        Value::Array(vec![
+            bulk("id"),
+            bulk(id),
+            bulk("port"),
+            Value::Int(port),
+            bulk("ip"),
+            bulk("127.0.0.1"),
+            bulk("endpoint"),
+            bulk("127.0.0.1"),
+            bulk("role"),
+            bulk(role),
+            bulk("replication-offset"),
+            Value::Int(72156),
+            bulk("health"),
+            bulk(health),
+        ])
+    }
+
+    #[test]
+    fn test_convert_cluster_shards() {
+        let shards = Value::Array(vec![Value::Map(vec![
+            (
+                bulk("slots"),
+                Value::Array(vec![
+                    Value::Int(0),
+                    Value::Int(5460),
+                    Value::Int(10923),
+                    Value::Int(10923),
+                ]),
+            ),
+            (
+                bulk("nodes"),
+                Value::Array(vec![
+                    shard_node("a", 30001, "master", "online"),
+                    shard_node("b", 30004, "replica", "online"),
+                    shard_node("c", 30007, "replica", "loading"),
+                ]),
+            ),
+        ])]);
+        let Value::Array(shards) = convert_cluster_shards(shards).unwrap() else {
+            panic!("not an array");
+        };
+        assert_eq!(
+            get(&shards[0], "slots"),
+            &Value::Array(vec![slot_range(0, 5460), slot_range(10923, 10923)])
+        );
+        let Value::Array(nodes) = get(&shards[0], "nodes") else {
+            panic!("not an array");
+        };
+        assert_eq!(
+            nodes[0],
+            map(vec![
+                ("id", bulk("a")),
+                ("address", bulk("127.0.0.1:30001")),
+                ("hostname", Value::Nil),
+                ("role", bulk("primary")),
+                ("health", bulk("online")),
+                ("replication_offset", Value::Int(72156)),
+            ])
+        );
+        assert_eq!(get(&nodes[1], "role"), &bulk("replica"));
+        assert_eq!(get(&nodes[2], "health"), &bulk("loading"));
+    }
+}
@@ -0,0 +1,104 @@
+// Copyright Valkey GLIDE Project Contributors - SPDX Identifier: Apache-2.0
+
+use std::{
+    collections::BTreeMap,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        RwLock,
+    },
+};
+
+use glide_core::request_type::RequestType;
+use redis::Value;
+
+/// Counts the commands submitted by [`command`](crate::command) per request type, e.g. to find which commands dominate the traffic.
+/// A request type is added once, the first time it is submitted, so afterwards commands only take the read lock.
+#[derive(Debug, Default)]
+pub(crate) struct CommandCounts {
+    /// The request types, by their numeric values, and the number of commands submitted with them.
+    counts: RwLock<BTreeMap<u32, (RequestType, AtomicU64)>>,
+}
+
+impl CommandCounts {
+    pub(crate) fn increment(&self, request_type: RequestType) {
+        let key = request_type as u32;
+        if let Some((_, count)) = self.counts.read().unwrap().get(&key) {
+            count.fetch_add(1, Ordering::Relaxed);
+            return;
+        }
+        self.counts
+            .write()
+            .unwrap()
+            .entry(key)
+            .or_insert_with(|| (request_type, AtomicU64::new(0)))
+            .1
+            .fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Get the counts as a map of the request types, as their numeric values, to the number of commands submitted with them.
+    /// Request types which weren't submitted since the client was created or the counts were reset are omitted.
+    pub(crate) fn to_value(&self) -> Value {
+        let counts = self.counts.read().unwrap();
+        Value::Map(
+            counts
+                .iter()
+                .map(|(request_type, (_, count))| (request_type, count.load(Ordering::Relaxed)))
+                .filter(|(_, count)| *count > 0)
+                .map(|(request_type, count)| {
+                    (Value::Int((*request_type).into()), Value::Int(count as i64))
+                })
+                .collect(),
+        )
+    }
+
+    /// Get the counts by the names of the request types, e.g. `Get`, like [`to_value`](Self::to_value).
+    pub(crate) fn named_counts(&self) -> Vec<(String, u64)> {
+        let counts = self.counts.read().unwrap();
+        counts
+            .values()
+            .map(|(request_type, count)| {
+                (format!("{request_type:?}"), count.load(Ordering::Relaxed))
+            })
+            .filter(|(_, count)| *count > 0)
+            .collect()
+    }
+
+    pub(crate) fn reset(&self) {
+        for (_, count) in self.counts.read().unwrap().values() {
+            count.store(0, Ordering::Relaxed);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_counts_per_request_type() {
+        let command_counts = CommandCounts::default();
+        for _ in 0..3 {
+            command_counts.increment(RequestType::Get);
+        }
+        command_counts.increment(RequestType::Set);
+        assert_eq!(
+            command_counts.to_value(),
+            Value::Map(vec![
+                (Value::Int(RequestType::Get as i64), Value::Int(3)),
+                (Value::Int(RequestType::Set as i64), Value::Int(1)),
+            ])
+        );
+
+        assert_eq!(
+            command_counts.named_counts(),
+            [("Get".to_string(), 3), ("Set".to_string(), 1)]
+        );
+
+        command_counts.reset();
+        command_counts.increment(RequestType::Set);
+        assert_eq!(
+            command_counts.to_value(),
+            Value::Map(vec![(Value::Int(RequestType::Set as i64), Value::Int(1))])
+        );
+    }
+}
@@ -0,0 +1,130 @@
+// Copyright Valkey GLIDE Project Contributors - SPDX Identifier: Apache-2.0
+
+use std::collections::HashSet;
+
+use glide_core::request_type::RequestType;
+use redis::{Cmd, RedisResult};
+
+/// Error code of commands rejected by a [`CommandFilter`], classified as
+/// [`CommandNotAllowed`](crate::errors::RequestErrorType::CommandNotAllowed).
+pub(crate) const COMMAND_NOT_ALLOWED_CODE: &str = "NOTALLOWED";
+
+/// Whether the commands of a command filter are the only allowed commands, or denied.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CommandFilterMode {
+    /// All commands are allowed.
+    Disabled = 0,
+    /// Only the listed commands are allowed.
+    Allow = 1,
+    /// The listed commands are denied.
+    Deny = 2,
+}
+
+/// Allow or deny list of commands, enforced on the client side before commands are dispatched,
+/// as defense in depth alongside the ACLs of the server.
+///
+/// Commands are matched by name rather than by request type, so custom commands can't bypass the filter:
+/// e.g. a denied [`RequestType::FlushAll`] also denies a custom `FLUSHALL` command.
+/// Container commands are matched with their subcommand, e.g. [`RequestType::ConfigSet`] matches `CONFIG SET`.
+#[derive(Debug)]
+pub(crate) struct CommandFilter {
+    mode: CommandFilterMode,
+    /// Upper-case names of the listed commands, with the subcommand separated by a space for container commands.
+    names: HashSet<Vec<u8>>,
+}
+
+impl CommandFilter {
+    /// Create a filter of the given request types, `None` if it is disabled.
+    pub(crate) fn new(mode: CommandFilterMode, request_types: &[RequestType]) -> Option<Self> {
+        if mode == CommandFilterMode::Disabled {
+            return None;
+        }
+        let names = request_types
+            .iter()
+            .filter_map(|request_type| request_type.get_command())
+            .map(|cmd| command_name(&cmd, true))
+            .collect();
+        Some(CommandFilter { mode, names })
+    }
+
+    /// Fail with a [`COMMAND_NOT_ALLOWED_CODE`] error if the command isn't allowed.
+    pub(crate) fn check(&self, cmd: &Cmd) -> RedisResult<()> {
+        let listed = self.names.contains(&command_name(cmd, false))
+            || self.names.contains(&command_name(cmd, true));
+        if listed == (self.mode == CommandFilterMode::Allow) {
+            return Ok(());
+        }
+        Err(redis::make_extension_error(
+            COMMAND_NOT_ALLOWED_CODE.to_string(),
+            Some(format!(
+                "Command `{}` isn't allowed by the client configuration",
+                String::from_utf8_lossy(&command_name(cmd, false))
+            )),
+        ))
+    }
+}
+
+/// Get the upper-case name of a command, followed by its first argument if `with_subcommand` is set.
+fn command_name(cmd: &Cmd, with_subcommand: bool) -> Vec<u8> {
+    let mut args = cmd.args_iter().filter_map(|arg| match arg {
+        redis::Arg::Simple(arg) => Some(arg.to_ascii_uppercase()),
+        redis::Arg::Cursor => None,
+    });
+    let mut name = args.next().unwrap_or_default();
+    if with_subcommand {
+        if let Some(subcommand) = args.next() {
+            name.push(b' ');
+            name.extend(subcommand);
+        }
+    }
+    name
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cmd(args: &[&str]) -> Cmd {
+        let mut cmd = redis::cmd(args[0]);
+        for arg in &args[1..] {
+            cmd.arg(*arg);
+        }
+        cmd
+    }
+
+    #[test]
+    fn test_deny_list_rejects_custom_commands() {
+        let filter = CommandFilter::new(
+            CommandFilterMode::Deny,
+            &[RequestType::FlushAll, RequestType::ConfigSet],
+        )
+        .unwrap();
+        let err = filter.check(&cmd(&["flushall"])).unwrap_err();
+        assert_eq!(err.code(), Some(COMMAND_NOT_ALLOWED_CODE));
+        assert!(filter
+            .check(&cmd(&["CONFIG", "set", "maxmemory", "0"]))
+            .is_err());
+        assert!(filter.check(&cmd(&["CONFIG", "GET", "maxmemory"])).is_ok());
+        assert!(filter.check(&cmd(&["GET", "flushall"])).is_ok());
+    }
+
+    #[test]
+    fn test_allow_list_rejects_other_commands() {
+        let filter = CommandFilter::new(
+            CommandFilterMode::Allow,
+            &[RequestType::Get, RequestType::Set],
+        )
+        .unwrap();
+        assert!(filter.check(&cmd(&["GET", "key"])).is_ok());
+        assert!(filter.check(&cmd(&["SET", "key", "value"])).is_ok());
+        assert!(filter.check(&cmd(&["KEYS", "*"])).is_err());
+    }
+
+    #[test]
+    fn test_disabled_filter() {
+        assert!(
+            CommandFilter::new(CommandFilterMode::Disabled, &[RequestType::FlushAll]).is_none()
+        );
+    }
+}
@@ -0,0 +1,291 @@
+// Copyright Valkey GLIDE Project Contributors - SPDX Identifier: Apache-2.0
+
+use redis::{ErrorKind, RedisError, RedisResult, Value};
+
+/// Names of the elements of a `COMMAND INFO` entry, by their position. Older servers send only a prefix of them.
+const COMMAND_INFO_FIELDS: [&str; 10] = [
+    "name",
+    "arity",
+    "flags",
+    "first_key",
+    "last_key",
+    "step",
+    "acl_categories",
+    "tips",
+    "key_specs",
+    "subcommands",
+];
+
+fn bulk(value: &str) -> Value {
+    Value::BulkString(value.as_bytes().to_vec())
+}
+
+fn is_field(key: &Value, name: &str) -> bool {
+    match key {
+        Value::BulkString(key) => key == name.as_bytes(),
+        Value::SimpleString(key) => key == name,
+        _ => false,
+    }
+}
+
+/// Convert a RESP2 flat array of alternating fields and values to a map. RESP3 maps are returned as is.
+fn to_map(value: Value) -> RedisResult<Vec<(Value, Value)>> {
+    match value {
+        Value::Map(entries) => Ok(entries),
+        Value::Array(items) if items.len() % 2 == 0 => {
+            let mut items = items.into_iter();
+            let mut entries = Vec::with_capacity(items.len() / 2);
+            while let (Some(field), Some(value)) = (items.next(), items.next()) {
+                entries.push((field, value));
+            }
+            Ok(entries)
+        }
+        _ => Err(invalid_response("expected a map")),
+    }
+}
+
+/// Convert the values of the given fields of a map with `convert`.
+fn convert_fields(
+    entries: Vec<(Value, Value)>,
+    convert: impl Fn(&Value, Value) -> RedisResult<Value>,
+) -> RedisResult<Value> {
+    entries
+        .into_iter()
+        .map(|(field, value)| {
+            let value = convert(&field, value)?;
+            Ok((field, value))
+        })
+        .collect::<RedisResult<_>>()
+        .map(Value::Map)
+}
+
+/// Convert a key specification to a map, including its `begin_search` and `find_keys` parts and their `spec`.
+fn convert_key_spec(spec: Value) -> RedisResult<Value> {
+    convert_fields(to_map(spec)?, |field, value| {
+        if is_field(field, "begin_search") || is_field(field, "find_keys") {
+            convert_fields(to_map(value)?, |field, value| {
+                if is_field(field, "spec") {
+                    to_map(value).map(Value::Map)
+                } else {
+                    Ok(value)
+                }
+            })
+        } else {
+            Ok(value)
+        }
+    })
+}
+
+fn convert_array(value: Value, convert: fn(Value) -> RedisResult<Value>) -> RedisResult<Value> {
+    match value {
+        Value::Array(items) => items
+            .into_iter()
+            .map(convert)
+            .collect::<RedisResult<_>>()
+            .map(Value::Array),
+        _ => Err(invalid_response("expected an array")),
+    }
+}
+
+/// Convert a `COMMAND INFO` entry to a map of its named elements, see [`COMMAND_INFO_FIELDS`].
+fn convert_command_info_entry(entry: Value) -> RedisResult<(Value, Value)> {
+    let Value::Array(items) = entry else {
+        return Err(invalid_response("command info is not an array"));
+    };
+    let name = items
+        .first()
+        .cloned()
+        .ok_or_else(|| invalid_response("command info is empty"))?;
+    let fields = COMMAND_INFO_FIELDS
+        .iter()
+        .zip(items)
+        .map(|(&field, value)| {
+            let value = match field {
+                "key_specs" => convert_array(value, convert_key_spec)?,
+                "subcommands" => convert_array(value, |subcommand| {
+                    convert_command_info_entry(subcommand).map(|(_, info)| info)
+                })?,
+                _ => value,
+            };
+            Ok((bulk(field), value))
+        })
+        .collect::<RedisResult<_>>()?;
+    Ok((name, Value::Map(fields)))
+}
+
+/// Convert a `COMMAND INFO` response to a map of command names to maps of their `arity`, `flags`, `first_key`, `last_key`,
+/// `step`, `acl_categories`, `tips`, `key_specs` and `subcommands`, depending on the version of the server.
+/// Unknown commands, which the server replies with a null for, are omitted.
+pub(crate) fn convert_command_info(value: Value) -> RedisResult<Value> {
+    let Value::Array(entries) = value else {
+        return Err(invalid_response("COMMAND INFO response is not an array"));
+    };
+    entries
+        .into_iter()
+        .filter(|entry| *entry != Value::Nil)
+        .map(convert_command_info_entry)
+        .collect::<RedisResult<_>>()
+        .map(Value::Map)
+}
+
+/// Convert the documentation of a command to a map, including its `arguments` and `subcommands`, for both protocols.
+fn convert_command_doc(doc: Value) -> RedisResult<Value> {
+    convert_fields(to_map(doc)?, |field, value| {
+        if is_field(field, "arguments") {
+            convert_array(value, convert_argument)
+        } else if is_field(field, "subcommands") {
+            convert_command_docs(value)
+        } else {
+            Ok(value)
+        }
+    })
+}
+
+/// Convert an argument of a command to a map, including its nested arguments.
+fn convert_argument(argument: Value) -> RedisResult<Value> {
+    convert_fields(to_map(argument)?, |field, value| {
+        if is_field(field, "arguments") {
+            convert_array(value, convert_argument)
+        } else {
+            Ok(value)
+        }
+    })
+}
+
+/// Convert a `COMMAND DOCS` response to a map of command names to maps of their documentation, regardless of the protocol.
+/// Unknown commands are omitted by the server.
+pub(crate) fn convert_command_docs(value: Value) -> RedisResult<Value> {
+    convert_fields(to_map(value)?, |_, doc| convert_command_doc(doc))
+}
+
+fn invalid_response(detail: &str) -> RedisError {
+    RedisError::from((
+        ErrorKind::TypeError,
+        "Unexpected COMMAND response",
+        detail.to_string(),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn map(entries: Vec<(&str, Value)>) -> Value {
+        Value::Map(
+            entries
+                .into_iter()
+                .map(|(key, value)| (bulk(key), value))
+                .collect(),
+        )
+    }
+
+    fn array(items: Vec<Value>) -> Value {
+        Value::Array(items)
+    }
+
+    #[test]
+    fn test_convert_command_info() {
+        let key_spec = array(vec![
+            bulk("flags"),
+            array(vec![Value::SimpleString("RO".to_string())]),
+            bulk("begin_search"),
+            array(vec![
+                bulk("type"),
+                bulk("index"),
+                bulk("spec"),
+                array(vec![bulk("index"), Value::Int(1)]),
+            ]),
+        ]);
+        let get = array(vec![
+            bulk("get"),
+            Value::Int(2),
+            array(vec![Value::SimpleString("readonly".to_string())]),
+            Value::Int(1),
+            Value::Int(1),
+            Value::Int(1),
+            array(vec![bulk("@read")]),
+            array(vec![]),
+            array(vec![key_spec]),
+            array(vec![]),
+        ]);
+        assert_eq!(
+            convert_command_info(array(vec![get, Value::Nil])).unwrap(),
+            map(vec![(
+                "get",
+                map(vec![
+                    ("name", bulk("get")),
+                    ("arity", Value::Int(2)),
+                    (
+                        "flags",
+                        array(vec![Value::SimpleString("readonly".to_string())])
+                    ),
+                    ("first_key", Value::Int(1)),
+                    ("last_key", Value::Int(1)),
+                    ("step", Value::Int(1)),
+                    ("acl_categories", array(vec![bulk("@read")])),
+                    ("tips", array(vec![])),
+                    (
+                        "key_specs",
+                        array(vec![map(vec![
+                            ("flags", array(vec![Value::SimpleString("RO".to_string())])),
+                            (
+                                "begin_search",
+                                map(vec![
+                                    ("type", bulk("index")),
+                                    ("spec", map(vec![("index", Value::Int(1))])),
+                                ])
+                            ),
+                        ])])
+                    ),
+                    ("subcommands", array(vec![])),
+                ])
+            )])
+        );
+    }
+
+    #[test]
+    fn test_convert_command_docs_of_both_protocols() {
+        let argument = |key_spec_index| {
+            vec![
+                (bulk("name"), bulk("key")),
+                (bulk("type"), bulk("key")),
+                (bulk("key_spec_index"), Value::Int(key_spec_index)),
+            ]
+        };
+        let flatten = |entries: Vec<(Value, Value)>| {
+            array(
+                entries
+                    .into_iter()
+                    .flat_map(|(field, value)| [field, value])
+                    .collect(),
+            )
+        };
+        let expected = map(vec![(
+            "get",
+            map(vec![
+                ("summary", bulk("Returns the string value of a key.")),
+                ("arguments", array(vec![Value::Map(argument(0))])),
+            ]),
+        )]);
+
+        let resp2 = array(vec![
+            bulk("get"),
+            array(vec![
+                bulk("summary"),
+                bulk("Returns the string value of a key."),
+                bulk("arguments"),
+                array(vec![flatten(argument(0))]),
+            ]),
+        ]);
+        assert_eq!(convert_command_docs(resp2).unwrap(), expected);
+
+        let resp3 = map(vec![(
+            "get",
+            map(vec![
+                ("summary", bulk("Returns the string value of a key.")),
+                ("arguments", array(vec![Value::Map(argument(0))])),
+            ]),
+        )]);
+        assert_eq!(convert_command_docs(resp3).unwrap(), expected);
+    }
+}
@@ -0,0 +1,219 @@
+// Copyright Valkey GLIDE Project Contributors - SPDX Identifier: Apache-2.0
+
+use std::{
+    collections::{hash_map::DefaultHasher, VecDeque},
+    hash::{Hash, Hasher},
+    sync::Mutex,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use redis::{cluster_routing::RoutingInfo, Arg, Cmd, RedisResult, Value};
+
+use crate::{errors::error_type, info::bulk};
+
+/// The arguments of a traced command.
+enum TracedArgs {
+    /// A hash of the arguments, which identifies identical commands without keeping their data.
+    Hash(u64),
+    Full(Vec<Vec<u8>>),
+}
+
+/// A command recorded by a [`CommandTrace`].
+struct TraceEntry {
+    /// Time at which the command completed.
+    completed_at: SystemTime,
+    name: Vec<u8>,
+    args: TracedArgs,
+    route: Option<RoutingInfo>,
+    node: Option<String>,
+    latency: Duration,
+    /// The [`RequestErrorType`](crate::errors::RequestErrorType) of the error, `None` if the command succeeded.
+    error: Option<String>,
+}
+
+/// Records the last commands of the client in a ring buffer, see [`ConnectionConfig::command_trace_capacity`](crate::ffi::ConnectionConfig::command_trace_capacity).
+/// Entries are only formatted when the trace is read, so recording costs a hash of the arguments, or their copy, and a short lock.
+pub(crate) struct CommandTrace {
+    entries: Mutex<VecDeque<TraceEntry>>,
+    capacity: usize,
+    full_args: bool,
+}
+
+impl CommandTrace {
+    /// Create a trace of the last `capacity` commands, `None` if `capacity` is `0`.
+    pub(crate) fn new(capacity: u32, full_args: bool) -> Option<Self> {
+        (capacity > 0).then(|| CommandTrace {
+            entries: Mutex::new(VecDeque::with_capacity(capacity as usize)),
+            capacity: capacity as usize,
+            full_args,
+        })
+    }
+
+    /// Record a completed command, dropping the oldest command if the trace is full.
+    pub(crate) fn record(
+        &self,
+        cmd: &Cmd,
+        route: Option<&RoutingInfo>,
+        node: Option<&str>,
+        latency: Duration,
+        result: &RedisResult<Value>,
+    ) {
+        let mut args = cmd.args_iter().map(|arg| match arg {
+            Arg::Simple(arg) => arg,
+            Arg::Cursor => b"CURSOR",
+        });
+        let name = args.next().unwrap_or_default().to_vec();
+        let args = match self.full_args {
+            true => TracedArgs::Full(args.map(<[u8]>::to_vec).collect()),
+            false => {
+                let mut hasher = DefaultHasher::new();
+                args.for_each(|arg| arg.hash(&mut hasher));
+                TracedArgs::Hash(hasher.finish())
+            }
+        };
+        let entry = TraceEntry {
+            completed_at: SystemTime::now(),
+            name,
+            args,
+            route: route.cloned(),
+            node: node.map(str::to_string),
+            latency,
+            error: result
+                .as_ref()
+                .err()
+                .map(|err| format!("{:?}", error_type(err))),
+        };
+        let mut entries = self.entries.lock().unwrap();
+        if entries.len() == self.capacity {
+            entries.pop_front();
+        }
+        entries.push_back(entry);
+    }
+
+    /// Get the recorded commands, from the oldest to the newest, as an array of maps of:
+    /// * `timestamp_ms` - the unix time in milliseconds at which the command completed.
+    /// * `command` - the name of the command, e.g. `SET`.
+    /// * `args_hash` - a hash of the arguments as an integer, or `args` - an array of the arguments, if full arguments are recorded.
+    /// * `route` - a description of the route the command was submitted with, or null if it was routed by its keys.
+    /// * `node` - the address of the node, if the command was sent to a single known node, or null.
+    /// * `latency_us` - the latency in microseconds.
+    /// * `result` - `Ok`, or the type of the error, e.g. `Timeout`.
+    pub(crate) fn to_value(&self) -> Value {
+        let optional = |text: Option<String>| text.map_or(Value::Nil, |text| bulk(&text));
+        let entries = self.entries.lock().unwrap();
+        Value::Array(
+            entries
+                .iter()
+                .map(|entry| {
+                    let args = match &entry.args {
+                        TracedArgs::Hash(hash) => (bulk("args_hash"), Value::Int(*hash as i64)),
+                        TracedArgs::Full(args) => (
+                            bulk("args"),
+                            Value::Array(args.iter().cloned().map(Value::BulkString).collect()),
+                        ),
+                    };
+                    let timestamp = entry
+                        .completed_at
+                        .duration_since(UNIX_EPOCH)
+                        .unwrap_or_default();
+                    Value::Map(vec![
+                        (
+                            bulk("timestamp_ms"),
+                            Value::Int(timestamp.as_millis() as i64),
+                        ),
+                        (bulk("command"), Value::BulkString(entry.name.clone())),
+                        args,
+                        (
+                            bulk("route"),
+                            optional(entry.route.as_ref().map(|route| format!("{route:?}"))),
+                        ),
+                        (bulk("node"), optional(entry.node.clone())),
+                        (
+                            bulk("latency_us"),
+                            Value::Int(entry.latency.as_micros() as i64),
+                        ),
+                        (bulk("result"), bulk(entry.error.as_deref().unwrap_or("Ok"))),
+                    ])
+                })
+                .collect(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use redis::cluster_routing::SingleNodeRoutingInfo;
+
+    fn field<'a>(entry: &'a Value, name: &str) -> &'a Value {
+        let Value::Map(fields) = entry else {
+            panic!("Expected a map, got {entry:?}");
+        };
+        fields
+            .iter()
+            .find(|(key, _)| *key == bulk(name))
+            .map(|(_, value)| value)
+            .unwrap_or_else(|| panic!("Missing field {name}"))
+    }
+
+    fn entries(trace: &CommandTrace) -> Vec<Value> {
+        match trace.to_value() {
+            Value::Array(entries) => entries,
+            value => panic!("Expected an array, got {value:?}"),
+        }
+    }
+
+    #[test]
+    fn test_disabled_without_capacity() {
+        assert!(CommandTrace::new(0, false).is_none());
+    }
+
+    #[test]
+    fn test_keeps_last_commands() {
+        let trace = CommandTrace::new(2, false).unwrap();
+        for key in ["a", "b", "c"] {
+            trace.record(
+                &redis::cmd("GET").arg(key).clone(),
+                None,
+                Some("127.0.0.1:6379"),
+                Duration::from_micros(10),
+                &Ok(Value::Nil),
+            );
+        }
+        let entries = entries(&trace);
+        assert_eq!(entries.len(), 2);
+        let hash = |key: &str| {
+            let mut hasher = DefaultHasher::new();
+            key.as_bytes().hash(&mut hasher);
+            Value::Int(hasher.finish() as i64)
+        };
+        assert_eq!(field(&entries[0], "args_hash"), &hash("b"));
+        assert_eq!(field(&entries[1], "args_hash"), &hash("c"));
+        assert_eq!(field(&entries[1], "command"), &bulk("GET"));
+        assert_eq!(field(&entries[1], "node"), &bulk("127.0.0.1:6379"));
+        assert_eq!(field(&entries[1], "latency_us"), &Value::Int(10));
+        assert_eq!(field(&entries[1], "result"), &bulk("Ok"));
+        assert_eq!(field(&entries[1], "route"), &Value::Nil);
+    }
+
+    #[test]
+    fn test_records_full_args_and_errors() {
+        let trace = CommandTrace::new(1, true).unwrap();
+        let err = redis::RedisError::from(std::io::Error::from(std::io::ErrorKind::TimedOut));
+        trace.record(
+            &redis::cmd("SET").arg("key").arg("value").clone(),
+            Some(&RoutingInfo::SingleNode(SingleNodeRoutingInfo::Random)),
+            None,
+            Duration::ZERO,
+            &Err(err),
+        );
+        let entries = entries(&trace);
+        assert_eq!(
+            field(&entries[0], "args"),
+            &Value::Array(vec![bulk("key"), bulk("value")])
+        );
+        assert_eq!(field(&entries[0], "result"), &bulk("Timeout"));
+        assert_eq!(field(&entries[0], "node"), &Value::Nil);
+        assert!(matches!(field(&entries[0], "route"), Value::BulkString(_)));
+    }
+}
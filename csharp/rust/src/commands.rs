@@ -0,0 +1,4310 @@
+// Copyright Valkey GLIDE Project Contributors - SPDX Identifier: Apache-2.0
+
+//! Helpers for commands which have typed arguments, so the caller doesn't have to compose them as strings.
+
+use std::{ffi::c_void, slice::from_raw_parts};
+
+use redis::{
+    cluster_routing::{MultipleNodeRoutingInfo, RoutingInfo, SingleNodeRoutingInfo},
+    Cmd, ErrorKind, RedisError, RedisResult, Value,
+};
+
+use crate::{
+    bitmap::{align_bitfield_response, bitfield_encoding},
+    cluster_nodes::{convert_cluster_nodes, convert_cluster_shards},
+    command_info::{convert_command_docs, convert_command_info},
+    compression::{compress, decompress},
+    deadline_to_instant, dispatch_command,
+    errors::{error_type, RequestErrorType, UNSUPPORTED_BY_SERVER_CODE},
+    execute_command,
+    ffi::{convert_double_pointer_to_vec, create_route, RouteInfo},
+    geo::{convert_geopos, convert_geosearch},
+    info::{bulk, convert_info},
+    lcs::convert_lcs,
+    memory_stats::convert_memory_stats,
+    open_client_from_ptr,
+    random_members::convert_random_members,
+    server_version::version_at_least,
+    stream_info::{convert_xinfo_groups, convert_xinfo_stream},
+    ResponseConverter,
+};
+
+/// Convert a raw buffer to a byte slice, without any UTF-8 validation.
+///
+/// # Safety
+///
+/// * `data` could be `null` only if `len` is `0`.
+/// * `data` must point to `len` consecutive bytes. See the safety documentation of [`from_raw_parts`].
+pub(crate) unsafe fn ptr_to_bytes<'a>(data: *const u8, len: u32) -> &'a [u8] {
+    if len == 0 {
+        &[]
+    } else {
+        unsafe { from_raw_parts(data, len as usize) }
+    }
+}
+
+/// Submit a command built by a helper, routed by `route_info` if given.
+///
+/// # Safety
+///
+/// * `client_ptr` must be a valid pointer created by [`create_client`](crate::create_client), before [`close_client`](crate::close_client) was called.
+/// * `route_info` could be `null`, but if it is not `null`, it must be a valid [`RouteInfo`] pointer. See the safety documentation of [`create_route`].
+pub(crate) unsafe fn submit(
+    client_ptr: *const c_void,
+    callback_index: usize,
+    cmd: Cmd,
+    route_info: *const RouteInfo,
+) {
+    unsafe { submit_with_conversion(client_ptr, callback_index, cmd, route_info, None) };
+}
+
+/// Submit a command built by a helper, like [`submit`], and convert the response before passing it to the callback.
+///
+/// # Safety
+///
+/// See the safety documentation of [`submit`].
+unsafe fn submit_with_conversion(
+    client_ptr: *const c_void,
+    callback_index: usize,
+    cmd: Cmd,
+    route_info: *const RouteInfo,
+    convert: Option<ResponseConverter>,
+) {
+    let Some(client) = (unsafe { open_client_from_ptr(client_ptr, callback_index) }) else {
+        return;
+    };
+    let route = unsafe { create_route(route_info, &cmd) };
+    unsafe { execute_command(&client, callback_index, cmd, route, None, convert) };
+}
+
+/// Options of the `RESTORE` command.
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct RestoreOptions {
+    /// Replace the key if it already exists (`REPLACE`).
+    pub replace: bool,
+    /// The TTL is an absolute unix timestamp in milliseconds (`ABSTTL`).
+    pub abs_ttl: bool,
+    pub has_idle_time: bool,
+    /// Idle time of the key in seconds (`IDLETIME`).
+    pub idle_time: i64,
+    pub has_frequency: bool,
+    /// LFU access frequency of the key (`FREQ`).
+    pub frequency: i64,
+}
+
+/// Execute a `DUMP` command.
+/// The response is the serialized value as a binary bulk string, or null if the key doesn't exist.
+///
+/// # Safety
+/// * `client_ptr` must be a valid pointer created by [`create_client`](crate::create_client), before [`close_client`](crate::close_client) was called.
+/// * `key` must point to `key_len` consecutive bytes. See the safety documentation of [`ptr_to_bytes`].
+/// * `route_info` could be `null`, but if it is not `null`, it must be a valid [`RouteInfo`] pointer.
+#[no_mangle]
+pub unsafe extern "C" fn dump(
+    client_ptr: *const c_void,
+    callback_index: usize,
+    key: *const u8,
+    key_len: u32,
+    route_info: *const RouteInfo,
+) {
+    let mut cmd = redis::cmd("DUMP");
+    cmd.arg(unsafe { ptr_to_bytes(key, key_len) });
+    unsafe { submit(client_ptr, callback_index, cmd, route_info) };
+}
+
+/// Execute a `RESTORE` command with a value serialized by [`dump`].
+/// The serialized value is passed to the server as is, it is never converted to a string.
+///
+/// # Safety
+/// * `client_ptr` must be a valid pointer created by [`create_client`](crate::create_client), before [`close_client`](crate::close_client) was called.
+/// * `key` must point to `key_len` consecutive bytes and `serialized_value` must point to `serialized_value_len` consecutive bytes.
+///   See the safety documentation of [`ptr_to_bytes`].
+/// * `options` could be `null`, but if it is not `null`, it must be a valid [`RestoreOptions`] pointer.
+/// * `route_info` could be `null`, but if it is not `null`, it must be a valid [`RouteInfo`] pointer.
+#[allow(clippy::too_many_arguments)]
+#[no_mangle]
+pub unsafe extern "C" fn restore(
+    client_ptr: *const c_void,
+    callback_index: usize,
+    key: *const u8,
+    key_len: u32,
+    ttl: i64,
+    serialized_value: *const u8,
+    serialized_value_len: u32,
+    options: *const RestoreOptions,
+    route_info: *const RouteInfo,
+) {
+    let mut cmd = redis::cmd("RESTORE");
+    cmd.arg(unsafe { ptr_to_bytes(key, key_len) })
+        .arg(ttl)
+        .arg(unsafe { ptr_to_bytes(serialized_value, serialized_value_len) });
+    if let Some(options) = unsafe { options.as_ref() } {
+        if options.replace {
+            cmd.arg("REPLACE");
+        }
+        if options.abs_ttl {
+            cmd.arg("ABSTTL");
+        }
+        if options.has_idle_time {
+            cmd.arg("IDLETIME").arg(options.idle_time);
+        }
+        if options.has_frequency {
+            cmd.arg("FREQ").arg(options.frequency);
+        }
+    }
+    unsafe { submit(client_ptr, callback_index, cmd, route_info) };
+}
+
+/// Build a `COPY` command of `source` to `destination`, to the database `db` if `has_db` is set.
+fn copy_command(source: &[u8], destination: &[u8], has_db: bool, db: u32, replace: bool) -> Cmd {
+    let mut cmd = redis::cmd("COPY");
+    cmd.arg(source).arg(destination);
+    if has_db {
+        cmd.arg("DB").arg(db);
+    }
+    if replace {
+        cmd.arg("REPLACE");
+    }
+    cmd
+}
+
+/// Execute a `COPY` command, copying the value at `source` to `destination`, e.g. to duplicate keys during a migration.
+/// * `has_db` and `db` - the database of the destination, without it the destination is in the database of the client.
+/// * `replace` - overwrite the destination if it exists. Without it nothing is copied if the destination exists.
+///
+/// The response is `1` if the value was copied and `0` otherwise, as an integer.
+/// In cluster mode both keys must map to the same slot, e.g. by sharing a hash tag, otherwise the request fails with a client error,
+/// without being sent. Without `route_info` the command is routed by the shared slot.
+///
+/// # Safety
+/// * `client_ptr` must be a valid pointer created by [`create_client`](crate::create_client), before [`close_client`](crate::close_client) was called.
+/// * `source` must point to `source_len` consecutive bytes and `destination` must point to `destination_len` consecutive bytes.
+///   See the safety documentation of [`ptr_to_bytes`].
+/// * `route_info` could be `null`, but if it is not `null`, it must be a valid [`RouteInfo`] pointer.
+#[allow(clippy::too_many_arguments)]
+#[no_mangle]
+pub unsafe extern "C" fn copy(
+    client_ptr: *const c_void,
+    callback_index: usize,
+    source: *const u8,
+    source_len: u32,
+    destination: *const u8,
+    destination_len: u32,
+    has_db: bool,
+    db: u32,
+    replace: bool,
+    route_info: *const RouteInfo,
+) {
+    let Some(client) = (unsafe { open_client_from_ptr(client_ptr, callback_index) }) else {
+        return;
+    };
+    let source = unsafe { ptr_to_bytes(source, source_len) };
+    let destination = unsafe { ptr_to_bytes(destination, destination_len) };
+    if client.core.standalone_address.is_none()
+        && redis::cluster_topology::get_slot(source)
+            != redis::cluster_topology::get_slot(destination)
+    {
+        let err = RedisError::from((
+            ErrorKind::ClientError,
+            "COPY source and destination must map to the same slot in cluster mode",
+        ));
+        unsafe { client.core.report_error(callback_index, err) };
+        return;
+    }
+    let cmd = copy_command(source, destination, has_db, db, replace);
+    let route = unsafe { create_route(route_info, &cmd) };
+    unsafe { execute_command(&client, callback_index, cmd, route, None, None) };
+}
+
+/// Execute a `SET` command, compressing the value with LZ4 if it is at least the configured compression threshold in size.
+/// Compressed values are stored with a prefix, which allows [`get_compressed`] to tell them apart from values which weren't compressed.
+/// The compression happens in Rust, so the value is copied over the FFI boundary only once.
+///
+/// # Safety
+/// * `client_ptr` must be a valid pointer created by [`create_client`](crate::create_client), before [`close_client`](crate::close_client) was called.
+/// * `key` must point to `key_len` consecutive bytes and `value` must point to `value_len` consecutive bytes.
+///   See the safety documentation of [`ptr_to_bytes`].
+#[no_mangle]
+pub unsafe extern "C" fn set_compressed(
+    client_ptr: *const c_void,
+    callback_index: usize,
+    key: *const u8,
+    key_len: u32,
+    value: *const u8,
+    value_len: u32,
+) {
+    let Some(client) = (unsafe { open_client_from_ptr(client_ptr, callback_index) }) else {
+        return;
+    };
+    let value = compress(
+        unsafe { ptr_to_bytes(value, value_len) },
+        client.core.compression_threshold,
+    );
+    let mut cmd = redis::cmd("SET");
+    cmd.arg(unsafe { ptr_to_bytes(key, key_len) }).arg(value);
+    unsafe { execute_command(&client, callback_index, cmd, None, None, None) };
+}
+
+/// Execute a `GET` command, decompressing the value if it was compressed by [`set_compressed`].
+/// Values which weren't compressed are returned as is.
+///
+/// # Safety
+/// * `client_ptr` must be a valid pointer created by [`create_client`](crate::create_client), before [`close_client`](crate::close_client) was called.
+/// * `key` must point to `key_len` consecutive bytes. See the safety documentation of [`ptr_to_bytes`].
+#[no_mangle]
+pub unsafe extern "C" fn get_compressed(
+    client_ptr: *const c_void,
+    callback_index: usize,
+    key: *const u8,
+    key_len: u32,
+) {
+    let mut cmd = redis::cmd("GET");
+    cmd.arg(unsafe { ptr_to_bytes(key, key_len) });
+    unsafe {
+        submit_with_conversion(
+            client_ptr,
+            callback_index,
+            cmd,
+            std::ptr::null(),
+            Some(Box::new(decompress)),
+        )
+    };
+}
+
+/// Build a `SETRANGE` command, with the key and the value passed as raw bytes.
+fn setrange_command(key: &[u8], offset: u64, value: &[u8]) -> Cmd {
+    let mut cmd = redis::cmd("SETRANGE");
+    cmd.arg(key).arg(offset).arg(value);
+    cmd
+}
+
+/// Execute a `SETRANGE` command, overwriting the string at `key` from `offset` with `value`, e.g. to patch a binary blob in place.
+/// The string is padded with zero bytes if it is shorter than `offset`. The value is passed to the server as is, it is never converted to a string.
+/// The response is the length of the string after it was modified, as an integer.
+///
+/// # Safety
+/// * `client_ptr` must be a valid pointer created by [`create_client`](crate::create_client), before [`close_client`](crate::close_client) was called.
+/// * `key` must point to `key_len` consecutive bytes and `value` must point to `value_len` consecutive bytes.
+///   See the safety documentation of [`ptr_to_bytes`].
+/// * `route_info` could be `null`, but if it is not `null`, it must be a valid [`RouteInfo`] pointer.
+#[allow(clippy::too_many_arguments)]
+#[no_mangle]
+pub unsafe extern "C" fn setrange(
+    client_ptr: *const c_void,
+    callback_index: usize,
+    key: *const u8,
+    key_len: u32,
+    offset: u64,
+    value: *const u8,
+    value_len: u32,
+    route_info: *const RouteInfo,
+) {
+    let cmd = unsafe {
+        setrange_command(
+            ptr_to_bytes(key, key_len),
+            offset,
+            ptr_to_bytes(value, value_len),
+        )
+    };
+    unsafe { submit(client_ptr, callback_index, cmd, route_info) };
+}
+
+/// Build an `APPEND` command, with the key and the value passed as raw bytes.
+fn append_command(key: &[u8], value: &[u8]) -> Cmd {
+    let mut cmd = redis::cmd("APPEND");
+    cmd.arg(key).arg(value);
+    cmd
+}
+
+/// Execute an `APPEND` command, appending `value` to the string at `key`, e.g. to build up a binary blob in chunks.
+/// The key is created as an empty string if it doesn't exist. The value is passed to the server as is, it is never converted to a string.
+/// The response is the length of the string after the append, as an integer. Without `route_info` the command is routed by the slot of the key.
+///
+/// # Safety
+/// * `client_ptr` must be a valid pointer created by [`create_client`](crate::create_client), before [`close_client`](crate::close_client) was called.
+/// * `key` must point to `key_len` consecutive bytes and `value` must point to `value_len` consecutive bytes.
+///   See the safety documentation of [`ptr_to_bytes`].
+/// * `route_info` could be `null`, but if it is not `null`, it must be a valid [`RouteInfo`] pointer.
+#[no_mangle]
+pub unsafe extern "C" fn append(
+    client_ptr: *const c_void,
+    callback_index: usize,
+    key: *const u8,
+    key_len: u32,
+    value: *const u8,
+    value_len: u32,
+    route_info: *const RouteInfo,
+) {
+    let cmd = unsafe { append_command(ptr_to_bytes(key, key_len), ptr_to_bytes(value, value_len)) };
+    unsafe { submit(client_ptr, callback_index, cmd, route_info) };
+}
+
+/// Execute a `GETRANGE` command, getting the substring of the string at `key` between the inclusive `start` and `end` offsets.
+/// Negative offsets count from the end of the string.
+/// The response is the substring as a binary bulk string, which is empty if the key doesn't exist or the range is out of the string.
+///
+/// # Safety
+/// * `client_ptr` must be a valid pointer created by [`create_client`](crate::create_client), before [`close_client`](crate::close_client) was called.
+/// * `key` must point to `key_len` consecutive bytes. See the safety documentation of [`ptr_to_bytes`].
+/// * `route_info` could be `null`, but if it is not `null`, it must be a valid [`RouteInfo`] pointer.
+#[no_mangle]
+pub unsafe extern "C" fn getrange(
+    client_ptr: *const c_void,
+    callback_index: usize,
+    key: *const u8,
+    key_len: u32,
+    start: i64,
+    end: i64,
+    route_info: *const RouteInfo,
+) {
+    let mut cmd = redis::cmd("GETRANGE");
+    cmd.arg(unsafe { ptr_to_bytes(key, key_len) })
+        .arg(start)
+        .arg(end);
+    unsafe { submit(client_ptr, callback_index, cmd, route_info) };
+}
+
+/// Convert the reply of `INCRBYFLOAT` or `HINCRBYFLOAT`, the new value as a string, to a map of the `value` as a double,
+/// parsed in Rust so it doesn't depend on the locale of the caller, and the `raw` string replied by the server,
+/// for values whose precision exceeds a double.
+fn convert_float_reply(value: Value) -> RedisResult<Value> {
+    let raw = match value {
+        Value::BulkString(raw) => raw,
+        Value::SimpleString(raw) => raw.into_bytes(),
+        Value::Double(value) => value.to_string().into_bytes(),
+        value => {
+            return Err(RedisError::from((
+                ErrorKind::TypeError,
+                "Unexpected float response",
+                format!("{value:?}"),
+            )))
+        }
+    };
+    let Some(parsed) = std::str::from_utf8(&raw)
+        .ok()
+        .and_then(|text| text.parse::<f64>().ok())
+    else {
+        return Err(RedisError::from((
+            ErrorKind::TypeError,
+            "Response is not a float",
+            String::from_utf8_lossy(&raw).into_owned(),
+        )));
+    };
+    Ok(Value::Map(vec![
+        (bulk("value"), Value::Double(parsed)),
+        (bulk("raw"), Value::BulkString(raw)),
+    ]))
+}
+
+/// Execute an `INCRBYFLOAT` command, incrementing the float stored at `key` by `increment`, which may be negative.
+/// A missing key is treated as `0`. The response is a map of the new `value` as a double, and the `raw` new value
+/// as a bulk string, as replied by the server. Without `route_info` the command is routed by the slot of the key.
+///
+/// # Safety
+/// * `client_ptr` must be a valid pointer created by [`create_client`](crate::create_client), before [`close_client`](crate::close_client) was called.
+/// * `key` must point to `key_len` consecutive bytes. See the safety documentation of [`ptr_to_bytes`].
+/// * `route_info` could be `null`, but if it is not `null`, it must be a valid [`RouteInfo`] pointer.
+#[no_mangle]
+pub unsafe extern "C" fn incrbyfloat(
+    client_ptr: *const c_void,
+    callback_index: usize,
+    key: *const u8,
+    key_len: u32,
+    increment: f64,
+    route_info: *const RouteInfo,
+) {
+    let mut cmd = redis::cmd("INCRBYFLOAT");
+    cmd.arg(unsafe { ptr_to_bytes(key, key_len) })
+        .arg(increment);
+    unsafe {
+        submit_with_conversion(
+            client_ptr,
+            callback_index,
+            cmd,
+            route_info,
+            Some(Box::new(convert_float_reply)),
+        )
+    };
+}
+
+/// Execute an `HINCRBYFLOAT` command, incrementing the float stored in `field` of the hash at `key` by `increment`,
+/// like [`incrbyfloat`], with the same response.
+///
+/// # Safety
+/// * `client_ptr` must be a valid pointer created by [`create_client`](crate::create_client), before [`close_client`](crate::close_client) was called.
+/// * `key` must point to `key_len` consecutive bytes and `field` must point to `field_len` consecutive bytes.
+///   See the safety documentation of [`ptr_to_bytes`].
+/// * `route_info` could be `null`, but if it is not `null`, it must be a valid [`RouteInfo`] pointer.
+#[allow(clippy::too_many_arguments)]
+#[no_mangle]
+pub unsafe extern "C" fn hincrbyfloat(
+    client_ptr: *const c_void,
+    callback_index: usize,
+    key: *const u8,
+    key_len: u32,
+    field: *const u8,
+    field_len: u32,
+    increment: f64,
+    route_info: *const RouteInfo,
+) {
+    let mut cmd = redis::cmd("HINCRBYFLOAT");
+    cmd.arg(unsafe { ptr_to_bytes(key, key_len) })
+        .arg(unsafe { ptr_to_bytes(field, field_len) })
+        .arg(increment);
+    unsafe {
+        submit_with_conversion(
+            client_ptr,
+            callback_index,
+            cmd,
+            route_info,
+            Some(Box::new(convert_float_reply)),
+        )
+    };
+}
+
+/// Expiry of the key set by a `SET` command.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SetExpiry {
+    /// Remove the expiry of the key, unless [`SetOptions::keep_ttl`] is set.
+    None = 0,
+    /// Expire after the given seconds (`EX`).
+    Seconds = 1,
+    /// Expire after the given milliseconds (`PX`).
+    Milliseconds = 2,
+    /// Expire at the given unix time in seconds (`EXAT`).
+    UnixSeconds = 3,
+    /// Expire at the given unix time in milliseconds (`PXAT`).
+    UnixMilliseconds = 4,
+}
+
+/// Options of the `SET` command.
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct SetOptions {
+    /// Only set the key if it doesn't exist (`NX`). Can't be combined with `only_if_exists`.
+    pub only_if_not_exists: bool,
+    /// Only set the key if it already exists (`XX`).
+    pub only_if_exists: bool,
+    pub expiry_type: SetExpiry,
+    /// The expiry in the unit of `expiry_type`, must be positive. Ignored if `expiry_type` is [`SetExpiry::None`].
+    pub expiry: i64,
+    /// Keep the current expiry of the key (`KEEPTTL`). Can't be combined with an `expiry_type`.
+    pub keep_ttl: bool,
+    /// Return the old value of the key (`GET`).
+    pub get: bool,
+}
+
+/// The option of an expiry of the given type, e.g. `EX`, `None` if there's no expiry.
+fn expiry_unit(expiry_type: SetExpiry) -> Option<&'static str> {
+    match expiry_type {
+        SetExpiry::None => None,
+        SetExpiry::Seconds => Some("EX"),
+        SetExpiry::Milliseconds => Some("PX"),
+        SetExpiry::UnixSeconds => Some("EXAT"),
+        SetExpiry::UnixMilliseconds => Some("PXAT"),
+    }
+}
+
+/// Build a `SET` command, rejecting combinations of options which the server would reject.
+fn set_command(key: &[u8], value: &[u8], options: &SetOptions) -> RedisResult<Cmd> {
+    let invalid = |detail: &'static str| Err(RedisError::from((ErrorKind::ClientError, detail)));
+    if options.only_if_not_exists && options.only_if_exists {
+        return invalid("SET NX and XX can't be combined");
+    }
+    if options.keep_ttl && options.expiry_type != SetExpiry::None {
+        return invalid("SET KEEPTTL can't be combined with an expiry");
+    }
+    if options.expiry_type != SetExpiry::None && options.expiry <= 0 {
+        return invalid("SET expiry must be positive");
+    }
+    let mut cmd = redis::cmd("SET");
+    cmd.arg(key).arg(value);
+    if options.only_if_not_exists {
+        cmd.arg("NX");
+    }
+    if options.only_if_exists {
+        cmd.arg("XX");
+    }
+    if let Some(unit) = expiry_unit(options.expiry_type) {
+        cmd.arg(unit).arg(options.expiry);
+    }
+    if options.keep_ttl {
+        cmd.arg("KEEPTTL");
+    }
+    if options.get {
+        cmd.arg("GET");
+    }
+    Ok(cmd)
+}
+
+/// Execute a `SET` command with its options. The value is passed to the server as is, it is never converted to a string.
+/// The response is `OK` if the key was set, or null if it wasn't set because of the condition.
+/// With the `get` option the response is the old value as a binary bulk string, or null if the key didn't exist.
+/// Invalid combinations of options fail the request with a client error, without sending it.
+/// Without `route_info` the command is routed by the slot of the key.
+///
+/// # Safety
+/// * `client_ptr` must be a valid pointer created by [`create_client`](crate::create_client), before [`close_client`](crate::close_client) was called.
+/// * `key` must point to `key_len` consecutive bytes and `value` must point to `value_len` consecutive bytes.
+///   See the safety documentation of [`ptr_to_bytes`].
+/// * `options` could be `null`, but if it is not `null`, it must be a valid [`SetOptions`] pointer.
+/// * `route_info` could be `null`, but if it is not `null`, it must be a valid [`RouteInfo`] pointer.
+#[allow(clippy::too_many_arguments)]
+#[no_mangle]
+pub unsafe extern "C" fn set(
+    client_ptr: *const c_void,
+    callback_index: usize,
+    key: *const u8,
+    key_len: u32,
+    value: *const u8,
+    value_len: u32,
+    options: *const SetOptions,
+    route_info: *const RouteInfo,
+) {
+    let Some(client) = (unsafe { open_client_from_ptr(client_ptr, callback_index) }) else {
+        return;
+    };
+    let options = unsafe { options.as_ref() }.copied().unwrap_or(SetOptions {
+        only_if_not_exists: false,
+        only_if_exists: false,
+        expiry_type: SetExpiry::None,
+        expiry: 0,
+        keep_ttl: false,
+        get: false,
+    });
+    let cmd = match unsafe {
+        set_command(
+            ptr_to_bytes(key, key_len),
+            ptr_to_bytes(value, value_len),
+            &options,
+        )
+    } {
+        Ok(cmd) => cmd,
+        Err(err) => {
+            unsafe { client.core.report_error(callback_index, err) };
+            return;
+        }
+    };
+    let route = unsafe { create_route(route_info, &cmd) };
+    unsafe { execute_command(&client, callback_index, cmd, route, None, None) };
+}
+
+/// Build the command which sets `key` to `value` and gets its previous value: `SET key value GET` if `set_with_get`,
+/// otherwise the deprecated `GETSET key value`, for servers older than 6.2.
+fn getset_command(key: &[u8], value: &[u8], set_with_get: bool) -> Cmd {
+    let mut cmd = match set_with_get {
+        true => redis::cmd("SET"),
+        false => redis::cmd("GETSET"),
+    };
+    cmd.arg(key).arg(value);
+    if set_with_get {
+        cmd.arg("GET");
+    }
+    cmd
+}
+
+/// Set `key` to `value` and get its previous value, by `SET` with the `GET` option if the server supports it (6.2 and later),
+/// and by the deprecated `GETSET` otherwise. The version of the server is served from the cache of [`get_server_version`](crate::get_server_version),
+/// or fetched once per node. The response is the previous value as a binary bulk string, or null if the key didn't exist.
+/// Without `route_info` the command is routed by the slot of the key.
+///
+/// # Safety
+/// * `client_ptr` must be a valid pointer created by [`create_client`](crate::create_client), before [`close_client`](crate::close_client) was called.
+/// * `key` must point to `key_len` consecutive bytes and `value` must point to `value_len` consecutive bytes.
+///   See the safety documentation of [`ptr_to_bytes`].
+/// * `route_info` could be `null`, but if it is not `null`, it must be a valid [`RouteInfo`] pointer.
+#[no_mangle]
+pub unsafe extern "C" fn getset(
+    client_ptr: *const c_void,
+    callback_index: usize,
+    key: *const u8,
+    key_len: u32,
+    value: *const u8,
+    value_len: u32,
+    route_info: *const RouteInfo,
+) {
+    let Some(client) = (unsafe { open_client_from_ptr(client_ptr, callback_index) }) else {
+        return;
+    };
+    let core = client.core.clone();
+    let key = unsafe { ptr_to_bytes(key, key_len) }.to_vec();
+    let value = unsafe { ptr_to_bytes(value, value_len) }.to_vec();
+    let set = getset_command(&key, &value, true);
+    let pending_guard = match core
+        .check_command_allowed(&set)
+        .and_then(|_| core.check_command_allowed(&getset_command(&key, &value, false)))
+        .and_then(|_| core.reserve_pending_task())
+    {
+        Ok(pending_guard) => pending_guard,
+        Err(err) => {
+            unsafe { core.report_error(callback_index, err) };
+            return;
+        }
+    };
+    let route = unsafe { create_route(route_info, &set) }.or_else(|| {
+        core.standalone_address
+            .is_none()
+            .then(|| RoutingInfo::for_routable(&set))
+            .flatten()
+    });
+
+    client.runtime.spawn(async move {
+        let result = async {
+            // A route to multiple nodes has a version per node, so it falls back to GETSET, which all servers support
+            let set_with_get = match core.server_version(route.clone()).await? {
+                Value::BulkString(version) => {
+                    version_at_least(&String::from_utf8_lossy(&version), 6, 2)
+                }
+                _ => false,
+            };
+            let cmd = getset_command(&key, &value, set_with_get);
+            core.client_pool.next().send_command(&cmd, route).await
+        }
+        .await;
+        core.track_authentication(&result);
+        unsafe { core.report_result(callback_index, result) };
+        drop(pending_guard);
+    });
+}
+
+/// Whether [`take`] deletes the key, or extends its expiry.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TakeMode {
+    /// Get the value and delete the key (`GETDEL`), e.g. to release a lock.
+    Delete = 0,
+    /// Get the value and set the expiry of the key (`GETEX`), e.g. to extend a lease.
+    Extend = 1,
+}
+
+/// Build the command of [`take`], rejecting an extension without an expiry.
+fn take_command(
+    key: &[u8],
+    mode: TakeMode,
+    expiry_type: SetExpiry,
+    expiry: i64,
+) -> RedisResult<Cmd> {
+    match (mode, expiry_unit(expiry_type)) {
+        (TakeMode::Delete, _) => {
+            let mut cmd = redis::cmd("GETDEL");
+            cmd.arg(key);
+            Ok(cmd)
+        }
+        (TakeMode::Extend, Some(unit)) if expiry > 0 => {
+            let mut cmd = redis::cmd("GETEX");
+            cmd.arg(key).arg(unit).arg(expiry);
+            Ok(cmd)
+        }
+        (TakeMode::Extend, _) => Err(RedisError::from((
+            ErrorKind::ClientError,
+            "Extending a key requires a positive expiry",
+        ))),
+    }
+}
+
+/// Atomically get the value of `key` and either delete the key (`GETDEL`), or set its expiry (`GETEX`), according to `mode`.
+/// `expiry_type` and `expiry` are the new expiry of the key, in the unit of `expiry_type`, which must be set with [`TakeMode::Extend`]
+/// and are ignored with [`TakeMode::Delete`]. The response is the value as a binary bulk string, or null if the key didn't exist,
+/// in which case nothing is deleted or extended. Errors, e.g. `WRONGTYPE` if the key doesn't hold a string, fail the request.
+/// Without `route_info` the command is routed by the slot of the key.
+///
+/// # Safety
+/// * `client_ptr` must be a valid pointer created by [`create_client`](crate::create_client), before [`close_client`](crate::close_client) was called.
+/// * `key` must point to `key_len` consecutive bytes. See the safety documentation of [`ptr_to_bytes`].
+/// * `route_info` could be `null`, but if it is not `null`, it must be a valid [`RouteInfo`] pointer.
+#[allow(clippy::too_many_arguments)]
+#[no_mangle]
+pub unsafe extern "C" fn take(
+    client_ptr: *const c_void,
+    callback_index: usize,
+    key: *const u8,
+    key_len: u32,
+    mode: TakeMode,
+    expiry_type: SetExpiry,
+    expiry: i64,
+    route_info: *const RouteInfo,
+) {
+    let Some(client) = (unsafe { open_client_from_ptr(client_ptr, callback_index) }) else {
+        return;
+    };
+    let cmd = match take_command(
+        unsafe { ptr_to_bytes(key, key_len) },
+        mode,
+        expiry_type,
+        expiry,
+    ) {
+        Ok(cmd) => cmd,
+        Err(err) => {
+            unsafe { client.core.report_error(callback_index, err) };
+            return;
+        }
+    };
+    let route = unsafe { create_route(route_info, &cmd) };
+    unsafe { execute_command(&client, callback_index, cmd, route, None, None) };
+}
+
+/// Trimming strategy of a stream.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum StreamTrimStrategy {
+    None = 0,
+    /// Evict entries as long as the stream's length exceeds the threshold (`MAXLEN`).
+    MaxLen = 1,
+    /// Evict entries with IDs lower than the threshold (`MINID`).
+    MinId = 2,
+}
+
+/// Options of the `XADD` command.
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct XAddOptions {
+    /// Don't create the stream if it doesn't exist (`NOMKSTREAM`).
+    pub no_make_stream: bool,
+    pub trim_strategy: StreamTrimStrategy,
+    /// Trim exactly to the threshold (`=`), otherwise the server may trim less entries for efficiency (`~`).
+    pub trim_exact: bool,
+    /// The maximal length or the minimal ID of the stream, depending on the trimming strategy.
+    pub trim_threshold: *const u8,
+    pub trim_threshold_len: u32,
+    /// Maximal number of entries to evict, `0` means the server default. Only allowed with inexact trimming.
+    pub trim_limit: i64,
+}
+
+/// Execute an `XADD` command.
+/// `fields_and_values` contains `field_count * 2` buffers - alternating fields and values.
+/// If `id` is empty, the ID of the entry is generated by the server (`*`).
+/// The response is the ID of the added entry, or null if `NOMKSTREAM` is set and the stream doesn't exist.
+///
+/// # Safety
+/// * `client_ptr` must be a valid pointer created by [`create_client`](crate::create_client), before [`close_client`](crate::close_client) was called.
+/// * `key` must point to `key_len` consecutive bytes and `id` must point to `id_len` consecutive bytes.
+///   See the safety documentation of [`ptr_to_bytes`].
+/// * `fields_and_values` must point to `field_count * 2` consecutive buffer pointers and `fields_and_values_len` must point to their lengths.
+///   See the safety documentation of [`convert_double_pointer_to_vec`].
+/// * `options` could be `null`, but if it is not `null`, it must be a valid [`XAddOptions`] pointer,
+///   whose `trim_threshold` points to `trim_threshold_len` consecutive bytes.
+/// * `route_info` could be `null`, but if it is not `null`, it must be a valid [`RouteInfo`] pointer.
+#[allow(clippy::too_many_arguments)]
+#[no_mangle]
+pub unsafe extern "C" fn xadd(
+    client_ptr: *const c_void,
+    callback_index: usize,
+    key: *const u8,
+    key_len: u32,
+    id: *const u8,
+    id_len: u32,
+    fields_and_values: *const *const u8,
+    fields_and_values_len: *const u32,
+    field_count: u32,
+    options: *const XAddOptions,
+    route_info: *const RouteInfo,
+) {
+    let mut cmd = redis::cmd("XADD");
+    cmd.arg(unsafe { ptr_to_bytes(key, key_len) });
+    if let Some(options) = unsafe { options.as_ref() } {
+        if options.no_make_stream {
+            cmd.arg("NOMKSTREAM");
+        }
+        let strategy = match options.trim_strategy {
+            StreamTrimStrategy::None => None,
+            StreamTrimStrategy::MaxLen => Some("MAXLEN"),
+            StreamTrimStrategy::MinId => Some("MINID"),
+        };
+        if let Some(strategy) = strategy {
+            cmd.arg(strategy)
+                .arg(if options.trim_exact { "=" } else { "~" })
+                .arg(unsafe { ptr_to_bytes(options.trim_threshold, options.trim_threshold_len) });
+            if !options.trim_exact && options.trim_limit > 0 {
+                cmd.arg("LIMIT").arg(options.trim_limit);
+            }
+        }
+    }
+    let id = unsafe { ptr_to_bytes(id, id_len) };
+    cmd.arg(if id.is_empty() { b"*".as_slice() } else { id });
+    for arg in unsafe {
+        convert_double_pointer_to_vec(
+            fields_and_values as *const *const c_void,
+            field_count * 2,
+            fields_and_values_len,
+        )
+    } {
+        cmd.arg(arg);
+    }
+    unsafe { submit(client_ptr, callback_index, cmd, route_info) };
+}
+
+/// Execute an `XREAD` command.
+/// `streams_and_ids` contains `stream_count * 2` buffers - the keys of the streams, followed by the IDs to read after,
+/// like the `STREAMS` section of the command.
+/// `count` limits the number of entries per stream, `0` means no limit.
+/// `block_ms` is the time to block for, in milliseconds - `0` blocks indefinitely and a negative value doesn't block.
+///
+/// The response is a map of stream keys to maps of entry IDs to arrays of `[field, value]` pairs,
+/// or null if no entries were read. The command is routed by the slot of the first stream key.
+///
+/// `deadline_unix_ms` is an absolute deadline in milliseconds since the unix epoch, `0` means no deadline.
+/// A blocking read which outlives the deadline fails with a timeout error, which allows cancelling it.
+/// See [`command`](crate::command) for details.
+///
+/// # Safety
+/// * `client_ptr` must be a valid pointer created by [`create_client`](crate::create_client), before [`close_client`](crate::close_client) was called.
+/// * `streams_and_ids` must point to `stream_count * 2` consecutive buffer pointers and `streams_and_ids_len` must point to their lengths.
+///   See the safety documentation of [`convert_double_pointer_to_vec`].
+/// * `route_info` could be `null`, but if it is not `null`, it must be a valid [`RouteInfo`] pointer.
+#[allow(clippy::too_many_arguments)]
+#[no_mangle]
+pub unsafe extern "C" fn xread(
+    client_ptr: *const c_void,
+    callback_index: usize,
+    streams_and_ids: *const *const u8,
+    streams_and_ids_len: *const u32,
+    stream_count: u32,
+    count: u32,
+    block_ms: i64,
+    route_info: *const RouteInfo,
+    deadline_unix_ms: u64,
+) {
+    let Some(client) = (unsafe { open_client_from_ptr(client_ptr, callback_index) }) else {
+        return;
+    };
+    let mut cmd = redis::cmd("XREAD");
+    if count > 0 {
+        cmd.arg("COUNT").arg(count);
+    }
+    if block_ms >= 0 {
+        cmd.arg("BLOCK").arg(block_ms);
+    }
+    cmd.arg("STREAMS");
+    for arg in unsafe {
+        convert_double_pointer_to_vec(
+            streams_and_ids as *const *const c_void,
+            stream_count * 2,
+            streams_and_ids_len,
+        )
+    } {
+        cmd.arg(arg);
+    }
+    let route = unsafe { create_route(route_info, &cmd) };
+    match deadline_to_instant(deadline_unix_ms) {
+        Ok(deadline) => unsafe {
+            execute_command(&client, callback_index, cmd, route, deadline, None)
+        },
+        Err(err) => unsafe { client.core.report_error(callback_index, err) },
+    }
+}
+
+/// Execute an `XAUTOCLAIM` command, transferring ownership of pending entries idle for at least `min_idle_time_ms`
+/// milliseconds to `consumer`. `count` limits the number of claimed entries, `0` means the server default.
+///
+/// The response is an array of the stream ID to use as `start` in the next call, a map of claimed entry IDs
+/// to arrays of `[field, value]` pairs, and on servers 7.0 and later an array of IDs of deleted entries.
+///
+/// # Safety
+/// * `client_ptr` must be a valid pointer created by [`create_client`](crate::create_client), before [`close_client`](crate::close_client) was called.
+/// * `key`, `group`, `consumer` and `start` must point to `key_len`, `group_len`, `consumer_len` and `start_len` consecutive bytes respectively.
+///   See the safety documentation of [`ptr_to_bytes`].
+/// * `route_info` could be `null`, but if it is not `null`, it must be a valid [`RouteInfo`] pointer.
+#[allow(clippy::too_many_arguments)]
+#[no_mangle]
+pub unsafe extern "C" fn xautoclaim(
+    client_ptr: *const c_void,
+    callback_index: usize,
+    key: *const u8,
+    key_len: u32,
+    group: *const u8,
+    group_len: u32,
+    consumer: *const u8,
+    consumer_len: u32,
+    min_idle_time_ms: u64,
+    start: *const u8,
+    start_len: u32,
+    count: u32,
+    route_info: *const RouteInfo,
+) {
+    let mut cmd = redis::cmd("XAUTOCLAIM");
+    cmd.arg(unsafe { ptr_to_bytes(key, key_len) })
+        .arg(unsafe { ptr_to_bytes(group, group_len) })
+        .arg(unsafe { ptr_to_bytes(consumer, consumer_len) })
+        .arg(min_idle_time_ms)
+        .arg(unsafe { ptr_to_bytes(start, start_len) });
+    if count > 0 {
+        cmd.arg("COUNT").arg(count);
+    }
+    unsafe { submit(client_ptr, callback_index, cmd, route_info) };
+}
+
+/// Execute an `XINFO STREAM` command, with `FULL` if `full` is set. `count` limits the entries, and the pending entries
+/// of every group and consumer returned with `FULL`, `0` means the server default. It is ignored without `FULL`.
+///
+/// The response is a map of the fields of the stream, e.g. `length`, `last-generated-id` and `groups`, in RESP2 and RESP3.
+/// Entries are maps of entry IDs to arrays of `[field, value]` pairs, like in [`xread`]: `first-entry` and `last-entry`
+/// are maps of a single entry, or null if the stream is empty, and the `entries` of `FULL` is a map of the returned entries.
+/// With `FULL`, `groups` is an array of the groups, maps of their fields like in [`xinfo_groups`], where `pending` is an array
+/// of maps of `id`, `consumer`, `delivery-time` and `delivery-count`, and `consumers` is an array of maps of the fields
+/// of every consumer, whose `pending` is an array of maps of `id`, `delivery-time` and `delivery-count`.
+/// The command is routed by the slot of the key.
+///
+/// # Safety
+/// * `client_ptr` must be a valid pointer created by [`create_client`](crate::create_client), before [`close_client`](crate::close_client) was called.
+/// * `key` must point to `key_len` consecutive bytes. See the safety documentation of [`ptr_to_bytes`].
+/// * `route_info` could be `null`, but if it is not `null`, it must be a valid [`RouteInfo`] pointer.
+#[no_mangle]
+pub unsafe extern "C" fn xinfo_stream(
+    client_ptr: *const c_void,
+    callback_index: usize,
+    key: *const u8,
+    key_len: u32,
+    full: bool,
+    count: u32,
+    route_info: *const RouteInfo,
+) {
+    let mut cmd = redis::cmd("XINFO");
+    cmd.arg("STREAM").arg(unsafe { ptr_to_bytes(key, key_len) });
+    if full {
+        cmd.arg("FULL");
+        if count > 0 {
+            cmd.arg("COUNT").arg(count);
+        }
+    }
+    unsafe {
+        submit_with_conversion(
+            client_ptr,
+            callback_index,
+            cmd,
+            route_info,
+            Some(Box::new(convert_xinfo_stream)),
+        )
+    };
+}
+
+/// Execute an `XINFO GROUPS` command.
+/// The response is an array of the consumer groups of the stream, maps of their fields in RESP2 and RESP3, e.g. `name`,
+/// `consumers` and `pending` counts, `last-delivered-id`, and on servers 7.0 and later `entries-read` and `lag`,
+/// which is null if the lag can't be computed. The command is routed by the slot of the key.
+///
+/// # Safety
+/// * `client_ptr` must be a valid pointer created by [`create_client`](crate::create_client), before [`close_client`](crate::close_client) was called.
+/// * `key` must point to `key_len` consecutive bytes. See the safety documentation of [`ptr_to_bytes`].
+/// * `route_info` could be `null`, but if it is not `null`, it must be a valid [`RouteInfo`] pointer.
+#[no_mangle]
+pub unsafe extern "C" fn xinfo_groups(
+    client_ptr: *const c_void,
+    callback_index: usize,
+    key: *const u8,
+    key_len: u32,
+    route_info: *const RouteInfo,
+) {
+    let mut cmd = redis::cmd("XINFO");
+    cmd.arg("GROUPS").arg(unsafe { ptr_to_bytes(key, key_len) });
+    unsafe {
+        submit_with_conversion(
+            client_ptr,
+            callback_index,
+            cmd,
+            route_info,
+            Some(Box::new(convert_xinfo_groups)),
+        )
+    };
+}
+
+/// Execute an `OBJECT FREQ` command.
+/// The response is the logarithmic access frequency counter of the key as an integer, or null if the key doesn't exist.
+/// If the `maxmemory-policy` isn't an LFU policy, the request fails with a
+/// [`MaxMemoryPolicyMismatch`](crate::errors::RequestErrorType::MaxMemoryPolicyMismatch) error.
+///
+/// # Safety
+/// * `client_ptr` must be a valid pointer created by [`create_client`](crate::create_client), before [`close_client`](crate::close_client) was called.
+/// * `key` must point to `key_len` consecutive bytes. See the safety documentation of [`ptr_to_bytes`].
+/// * `route_info` could be `null`, but if it is not `null`, it must be a valid [`RouteInfo`] pointer.
+#[no_mangle]
+pub unsafe extern "C" fn object_freq(
+    client_ptr: *const c_void,
+    callback_index: usize,
+    key: *const u8,
+    key_len: u32,
+    route_info: *const RouteInfo,
+) {
+    let mut cmd = redis::cmd("OBJECT");
+    cmd.arg("FREQ").arg(unsafe { ptr_to_bytes(key, key_len) });
+    unsafe { submit(client_ptr, callback_index, cmd, route_info) };
+}
+
+/// Execute an `OBJECT IDLETIME` command.
+/// The response is the time in seconds since the key was last accessed as an integer, or null if the key doesn't exist.
+/// If the `maxmemory-policy` is an LFU policy, the request fails with a
+/// [`MaxMemoryPolicyMismatch`](crate::errors::RequestErrorType::MaxMemoryPolicyMismatch) error.
+///
+/// # Safety
+/// * `client_ptr` must be a valid pointer created by [`create_client`](crate::create_client), before [`close_client`](crate::close_client) was called.
+/// * `key` must point to `key_len` consecutive bytes. See the safety documentation of [`ptr_to_bytes`].
+/// * `route_info` could be `null`, but if it is not `null`, it must be a valid [`RouteInfo`] pointer.
+#[no_mangle]
+pub unsafe extern "C" fn object_idletime(
+    client_ptr: *const c_void,
+    callback_index: usize,
+    key: *const u8,
+    key_len: u32,
+    route_info: *const RouteInfo,
+) {
+    let mut cmd = redis::cmd("OBJECT");
+    cmd.arg("IDLETIME")
+        .arg(unsafe { ptr_to_bytes(key, key_len) });
+    unsafe { submit(client_ptr, callback_index, cmd, route_info) };
+}
+
+/// Execute an `OBJECT REFCOUNT` command.
+/// The response is the number of references to the value of the key as an integer, or null if the key doesn't exist.
+/// Shared objects, like small integers, have a very large reference count.
+///
+/// # Safety
+/// * `client_ptr` must be a valid pointer created by [`create_client`](crate::create_client), before [`close_client`](crate::close_client) was called.
+/// * `key` must point to `key_len` consecutive bytes. See the safety documentation of [`ptr_to_bytes`].
+/// * `route_info` could be `null`, but if it is not `null`, it must be a valid [`RouteInfo`] pointer.
+#[no_mangle]
+pub unsafe extern "C" fn object_refcount(
+    client_ptr: *const c_void,
+    callback_index: usize,
+    key: *const u8,
+    key_len: u32,
+    route_info: *const RouteInfo,
+) {
+    let mut cmd = redis::cmd("OBJECT");
+    cmd.arg("REFCOUNT")
+        .arg(unsafe { ptr_to_bytes(key, key_len) });
+    unsafe { submit(client_ptr, callback_index, cmd, route_info) };
+}
+
+/// Execute the `OBJECT` subcommands of a key and combine their results into a map with the keys
+/// `encoding` (string), `refcount` (integer) and either `idletime` or `freq` (integer), depending on the `maxmemory-policy`.
+/// The response is null if the key doesn't exist.
+///
+/// `OBJECT IDLETIME` is sent first, and only if the server rejects it because an LFU policy is selected, `OBJECT FREQ` is sent.
+/// Statistics the server rejects are omitted from the map, rather than failing the whole request.
+///
+/// # Safety
+/// * `client_ptr` must be a valid pointer created by [`create_client`](crate::create_client), before [`close_client`](crate::close_client) was called.
+/// * `key` must point to `key_len` consecutive bytes. See the safety documentation of [`ptr_to_bytes`].
+/// * `route_info` could be `null`, but if it is not `null`, it must be a valid [`RouteInfo`] pointer.
+#[no_mangle]
+pub unsafe extern "C" fn object_inspect(
+    client_ptr: *const c_void,
+    callback_index: usize,
+    key: *const u8,
+    key_len: u32,
+    route_info: *const RouteInfo,
+) {
+    let Some(client) = (unsafe { open_client_from_ptr(client_ptr, callback_index) }) else {
+        return;
+    };
+    let key = unsafe { ptr_to_bytes(key, key_len) }.to_vec();
+    let object_cmd = |subcommand: &str| {
+        let mut cmd = redis::cmd("OBJECT");
+        cmd.arg(subcommand).arg(&key);
+        cmd
+    };
+    let [encoding, refcount, idletime, freq] =
+        ["ENCODING", "REFCOUNT", "IDLETIME", "FREQ"].map(object_cmd);
+    let route = unsafe { create_route(route_info, &encoding) };
+    let core = client.core.clone();
+    client.runtime.spawn(async move {
+        let send = |cmd: Cmd| {
+            let mut client = core.client_pool.next();
+            let route = route.clone();
+            async move { client.send_command(&cmd, route).await }
+        };
+        let (encoding, refcount, idletime) =
+            tokio::join!(send(encoding), send(refcount), send(idletime));
+        let access = match idletime {
+            Err(err) if error_type(&err) == RequestErrorType::MaxMemoryPolicyMismatch => {
+                ("freq", send(freq).await)
+            }
+            idletime => ("idletime", idletime),
+        };
+        let result = match encoding {
+            Ok(Value::Nil) => Ok(Value::Nil),
+            Ok(encoding) => {
+                let stats = [("encoding", Ok(encoding)), ("refcount", refcount), access]
+                    .into_iter()
+                    .filter_map(|(name, result)| {
+                        let value = result.ok().filter(|value| *value != Value::Nil)?;
+                        Some((Value::BulkString(name.as_bytes().to_vec()), value))
+                    })
+                    .collect();
+                Ok(Value::Map(stats))
+            }
+            Err(err) => Err(err),
+        };
+        unsafe { core.report_result(callback_index, result) };
+    });
+}
+
+/// Type of the value stored at a key, as reported by the `TYPE` command.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum KeyType {
+    /// The key doesn't exist.
+    None = 0,
+    String = 1,
+    List = 2,
+    Set = 3,
+    ZSet = 4,
+    Hash = 5,
+    Stream = 6,
+    /// A type of a module, e.g. `ReJSON-RL`.
+    Other = 7,
+}
+
+/// Execute a `TYPE` command, routed by the slot of the key.
+/// The response is the [`KeyType`] of the key as an integer, [`KeyType::None`] if the key doesn't exist.
+///
+/// # Safety
+/// * `client_ptr` must be a valid pointer created by [`create_client`](crate::create_client), before [`close_client`](crate::close_client) was called.
+/// * `key` must point to `key_len` consecutive bytes. See the safety documentation of [`ptr_to_bytes`].
+/// * `route_info` could be `null`, but if it is not `null`, it must be a valid [`RouteInfo`] pointer.
+#[no_mangle]
+pub unsafe extern "C" fn key_type(
+    client_ptr: *const c_void,
+    callback_index: usize,
+    key: *const u8,
+    key_len: u32,
+    route_info: *const RouteInfo,
+) {
+    let mut cmd = redis::cmd("TYPE");
+    cmd.arg(unsafe { ptr_to_bytes(key, key_len) });
+    unsafe {
+        submit_with_conversion(
+            client_ptr,
+            callback_index,
+            cmd,
+            route_info,
+            Some(Box::new(convert_key_type)),
+        )
+    };
+}
+
+/// Convert a `TYPE` response to the integer value of its [`KeyType`].
+fn convert_key_type(value: Value) -> RedisResult<Value> {
+    let name = match &value {
+        Value::SimpleString(name) => name.as_bytes(),
+        Value::BulkString(name) => name.as_slice(),
+        _ => {
+            return Err(RedisError::from((
+                ErrorKind::TypeError,
+                "Unexpected TYPE response",
+                format!("{value:?}"),
+            )))
+        }
+    };
+    let key_type = match name {
+        b"none" => KeyType::None,
+        b"string" => KeyType::String,
+        b"list" => KeyType::List,
+        b"set" => KeyType::Set,
+        b"zset" => KeyType::ZSet,
+        b"hash" => KeyType::Hash,
+        b"stream" => KeyType::Stream,
+        _ => KeyType::Other,
+    };
+    Ok(Value::Int(key_type as i64))
+}
+
+/// Condition of the `EXPIRE` family of commands, on which the expiry is set. Conditions require servers 7.0 and later.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ExpireCondition {
+    /// Set the expiry unconditionally.
+    None = 0,
+    /// Set the expiry only if the key has no expiry (`NX`).
+    HasNoExpiry = 1,
+    /// Set the expiry only if the key has an expiry (`XX`).
+    HasExpiry = 2,
+    /// Set the expiry only if it is greater than the current expiry (`GT`). A key without expiry has an infinite expiry.
+    NewExpiryGreaterThanCurrent = 3,
+    /// Set the expiry only if it is less than the current expiry (`LT`). A key without expiry has an infinite expiry.
+    NewExpiryLessThanCurrent = 4,
+}
+
+/// Create a command of the `EXPIRE` family, e.g. `EXPIRE key seconds NX`.
+pub(crate) fn expire_command(name: &str, key: &[u8], time: i64, condition: ExpireCondition) -> Cmd {
+    let mut cmd = redis::cmd(name);
+    cmd.arg(key).arg(time);
+    match condition {
+        ExpireCondition::None => {}
+        ExpireCondition::HasNoExpiry => {
+            cmd.arg("NX");
+        }
+        ExpireCondition::HasExpiry => {
+            cmd.arg("XX");
+        }
+        ExpireCondition::NewExpiryGreaterThanCurrent => {
+            cmd.arg("GT");
+        }
+        ExpireCondition::NewExpiryLessThanCurrent => {
+            cmd.arg("LT");
+        }
+    }
+    cmd
+}
+
+/// Submit a command of the `EXPIRE` family. The response is `1` if the expiry was set, or `0` if the key doesn't exist
+/// or the condition isn't met. If the server doesn't support conditions, the request fails with an
+/// [`UnsupportedByServer`](crate::errors::RequestErrorType::UnsupportedByServer) error.
+///
+/// # Safety
+///
+/// See the safety documentation of [`expire`].
+#[allow(clippy::too_many_arguments)]
+unsafe fn submit_expire(
+    client_ptr: *const c_void,
+    callback_index: usize,
+    name: &str,
+    key: *const u8,
+    key_len: u32,
+    time: i64,
+    condition: ExpireCondition,
+    route_info: *const RouteInfo,
+) {
+    let Some(client) = (unsafe { open_client_from_ptr(client_ptr, callback_index) }) else {
+        return;
+    };
+    let cmd = expire_command(name, unsafe { ptr_to_bytes(key, key_len) }, time, condition);
+    let route = unsafe { create_route(route_info, &cmd) };
+    let has_condition = condition != ExpireCondition::None;
+    let name = name.to_string();
+    dispatch_command(&client, cmd, route, None, None, move |core, result| {
+        let result = match has_condition {
+            true => result.map_err(|err| unsupported_expire_condition(&name, err)),
+            false => result,
+        };
+        unsafe { core.report_result(callback_index, result) };
+    });
+}
+
+/// Classify the error of a command of the `EXPIRE` family with a condition. The arity of the command is always valid,
+/// so a wrong number of arguments means that the server predates the conditions.
+pub(crate) fn unsupported_expire_condition(name: &str, err: RedisError) -> RedisError {
+    if err.code() == Some("ERR")
+        && err
+            .detail()
+            .is_some_and(|detail| detail.starts_with("wrong number of arguments"))
+    {
+        return redis::make_extension_error(
+            UNSUPPORTED_BY_SERVER_CODE.to_string(),
+            Some(format!(
+                "{name} conditions (NX, XX, GT and LT) require servers 7.0 and later"
+            )),
+        );
+    }
+    err
+}
+
+/// Execute an `EXPIRE` command, setting the expiry of a key in `seconds` from now, if `condition` is met.
+/// The response is `1` if the expiry was set, or `0` if the key doesn't exist or the condition isn't met.
+/// Conditions require servers 7.0 and later, older servers fail the request with an
+/// [`UnsupportedByServer`](crate::errors::RequestErrorType::UnsupportedByServer) error.
+///
+/// # Safety
+/// * `client_ptr` must be a valid pointer created by [`create_client`](crate::create_client), before [`close_client`](crate::close_client) was called.
+/// * `key` must point to `key_len` consecutive bytes. See the safety documentation of [`ptr_to_bytes`].
+/// * `route_info` could be `null`, but if it is not `null`, it must be a valid [`RouteInfo`] pointer.
+#[no_mangle]
+pub unsafe extern "C" fn expire(
+    client_ptr: *const c_void,
+    callback_index: usize,
+    key: *const u8,
+    key_len: u32,
+    seconds: i64,
+    condition: ExpireCondition,
+    route_info: *const RouteInfo,
+) {
+    unsafe {
+        submit_expire(
+            client_ptr,
+            callback_index,
+            "EXPIRE",
+            key,
+            key_len,
+            seconds,
+            condition,
+            route_info,
+        )
+    };
+}
+
+/// Execute a `PEXPIRE` command, like [`expire`] with the expiry in `milliseconds` from now.
+///
+/// # Safety
+///
+/// See the safety documentation of [`expire`].
+#[no_mangle]
+pub unsafe extern "C" fn pexpire(
+    client_ptr: *const c_void,
+    callback_index: usize,
+    key: *const u8,
+    key_len: u32,
+    milliseconds: i64,
+    condition: ExpireCondition,
+    route_info: *const RouteInfo,
+) {
+    unsafe {
+        submit_expire(
+            client_ptr,
+            callback_index,
+            "PEXPIRE",
+            key,
+            key_len,
+            milliseconds,
+            condition,
+            route_info,
+        )
+    };
+}
+
+/// Execute an `EXPIREAT` command, like [`expire`] with the expiry at an absolute unix time in seconds.
+///
+/// # Safety
+///
+/// See the safety documentation of [`expire`].
+#[no_mangle]
+pub unsafe extern "C" fn expireat(
+    client_ptr: *const c_void,
+    callback_index: usize,
+    key: *const u8,
+    key_len: u32,
+    unix_seconds: i64,
+    condition: ExpireCondition,
+    route_info: *const RouteInfo,
+) {
+    unsafe {
+        submit_expire(
+            client_ptr,
+            callback_index,
+            "EXPIREAT",
+            key,
+            key_len,
+            unix_seconds,
+            condition,
+            route_info,
+        )
+    };
+}
+
+/// Execute a `PEXPIREAT` command, like [`expire`] with the expiry at an absolute unix time in milliseconds.
+///
+/// # Safety
+///
+/// See the safety documentation of [`expire`].
+#[no_mangle]
+pub unsafe extern "C" fn pexpireat(
+    client_ptr: *const c_void,
+    callback_index: usize,
+    key: *const u8,
+    key_len: u32,
+    unix_milliseconds: i64,
+    condition: ExpireCondition,
+    route_info: *const RouteInfo,
+) {
+    unsafe {
+        submit_expire(
+            client_ptr,
+            callback_index,
+            "PEXPIREAT",
+            key,
+            key_len,
+            unix_milliseconds,
+            condition,
+            route_info,
+        )
+    };
+}
+
+/// State of the expiry of a key, as reported by the `TTL` family of commands.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum KeyExpiry {
+    /// The key doesn't exist (`-2`).
+    Missing = 0,
+    /// The key exists and has no expiry (`-1`).
+    Persistent = 1,
+    /// The key exists and expires at the returned time.
+    Expires = 2,
+}
+
+/// Submit a command of the `TTL` family, routed by the slot of the key. See [`ttl`] for the response.
+///
+/// # Safety
+///
+/// See the safety documentation of [`ttl`].
+unsafe fn submit_key_expiry(
+    client_ptr: *const c_void,
+    callback_index: usize,
+    name: &str,
+    key: *const u8,
+    key_len: u32,
+    route_info: *const RouteInfo,
+) {
+    let mut cmd = redis::cmd(name);
+    cmd.arg(unsafe { ptr_to_bytes(key, key_len) });
+    unsafe {
+        submit_with_conversion(
+            client_ptr,
+            callback_index,
+            cmd,
+            route_info,
+            Some(Box::new(convert_key_expiry)),
+        )
+    };
+}
+
+/// Convert the integer response of a command of the `TTL` family to a map of its [`KeyExpiry`] state and time,
+/// replacing the negative sentinels.
+fn convert_key_expiry(value: Value) -> RedisResult<Value> {
+    let (state, time) = match value {
+        Value::Int(-2) => (KeyExpiry::Missing, Value::Nil),
+        Value::Int(-1) => (KeyExpiry::Persistent, Value::Nil),
+        Value::Int(time) if time >= 0 => (KeyExpiry::Expires, Value::Int(time)),
+        _ => {
+            return Err(RedisError::from((
+                ErrorKind::TypeError,
+                "Unexpected TTL response",
+                format!("{value:?}"),
+            )))
+        }
+    };
+    Ok(Value::Map(vec![
+        (bulk("state"), Value::Int(state as i64)),
+        (bulk("time"), time),
+    ]))
+}
+
+/// Execute a `TTL` command, getting the remaining time to live of a key in seconds, routed by the slot of the key.
+/// The response is a map of the `state` of the expiry, the [`KeyExpiry`] as an integer, and the `time` - the remaining
+/// seconds as an integer if the state is [`KeyExpiry::Expires`], or null otherwise.
+///
+/// # Safety
+/// * `client_ptr` must be a valid pointer created by [`create_client`](crate::create_client), before [`close_client`](crate::close_client) was called.
+/// * `key` must point to `key_len` consecutive bytes. See the safety documentation of [`ptr_to_bytes`].
+/// * `route_info` could be `null`, but if it is not `null`, it must be a valid [`RouteInfo`] pointer.
+#[no_mangle]
+pub unsafe extern "C" fn ttl(
+    client_ptr: *const c_void,
+    callback_index: usize,
+    key: *const u8,
+    key_len: u32,
+    route_info: *const RouteInfo,
+) {
+    unsafe { submit_key_expiry(client_ptr, callback_index, "TTL", key, key_len, route_info) };
+}
+
+/// Execute a `PTTL` command, like [`ttl`] with the remaining time in milliseconds.
+///
+/// # Safety
+///
+/// See the safety documentation of [`ttl`].
+#[no_mangle]
+pub unsafe extern "C" fn pttl(
+    client_ptr: *const c_void,
+    callback_index: usize,
+    key: *const u8,
+    key_len: u32,
+    route_info: *const RouteInfo,
+) {
+    unsafe { submit_key_expiry(client_ptr, callback_index, "PTTL", key, key_len, route_info) };
+}
+
+/// Execute an `EXPIRETIME` command, like [`ttl`] with the absolute unix time in seconds at which the key expires.
+/// Requires servers 7.0 and later.
+///
+/// # Safety
+///
+/// See the safety documentation of [`ttl`].
+#[no_mangle]
+pub unsafe extern "C" fn expiretime(
+    client_ptr: *const c_void,
+    callback_index: usize,
+    key: *const u8,
+    key_len: u32,
+    route_info: *const RouteInfo,
+) {
+    unsafe {
+        submit_key_expiry(
+            client_ptr,
+            callback_index,
+            "EXPIRETIME",
+            key,
+            key_len,
+            route_info,
+        )
+    };
+}
+
+/// Execute a `PEXPIRETIME` command, like [`ttl`] with the absolute unix time in milliseconds at which the key expires.
+/// Requires servers 7.0 and later.
+///
+/// # Safety
+///
+/// See the safety documentation of [`ttl`].
+#[no_mangle]
+pub unsafe extern "C" fn pexpiretime(
+    client_ptr: *const c_void,
+    callback_index: usize,
+    key: *const u8,
+    key_len: u32,
+    route_info: *const RouteInfo,
+) {
+    unsafe {
+        submit_key_expiry(
+            client_ptr,
+            callback_index,
+            "PEXPIRETIME",
+            key,
+            key_len,
+            route_info,
+        )
+    };
+}
+
+/// Build a command of a key followed by members, like `SMISMEMBER key member [member ...]`.
+///
+/// # Safety
+/// * `key` must point to `key_len` consecutive bytes. See the safety documentation of [`ptr_to_bytes`].
+/// * `members` must point to `member_count` consecutive buffer pointers and `members_len` must point to their lengths.
+///   See the safety documentation of [`convert_double_pointer_to_vec`].
+unsafe fn key_members_command(
+    name: &str,
+    key: *const u8,
+    key_len: u32,
+    members: *const *const u8,
+    members_len: *const u32,
+    member_count: u32,
+) -> Cmd {
+    let mut cmd = redis::cmd(name);
+    cmd.arg(unsafe { ptr_to_bytes(key, key_len) });
+    for member in unsafe {
+        convert_double_pointer_to_vec(members as *const *const c_void, member_count, members_len)
+    } {
+        cmd.arg(member);
+    }
+    cmd
+}
+
+/// Execute an `SMISMEMBER` command, checking the membership of multiple members of a set in a single round trip.
+/// The response is an array of booleans in the order of the members, regardless of the protocol.
+/// A missing key is treated as an empty set.
+///
+/// # Safety
+/// * `client_ptr` must be a valid pointer created by [`create_client`](crate::create_client), before [`close_client`](crate::close_client) was called.
+/// * `key`, `members`, `members_len` and `member_count` - see the safety documentation of [`key_members_command`].
+/// * `route_info` could be `null`, but if it is not `null`, it must be a valid [`RouteInfo`] pointer.
+#[allow(clippy::too_many_arguments)]
+#[no_mangle]
+pub unsafe extern "C" fn smismember(
+    client_ptr: *const c_void,
+    callback_index: usize,
+    key: *const u8,
+    key_len: u32,
+    members: *const *const u8,
+    members_len: *const u32,
+    member_count: u32,
+    route_info: *const RouteInfo,
+) {
+    let cmd = unsafe {
+        key_members_command(
+            "SMISMEMBER",
+            key,
+            key_len,
+            members,
+            members_len,
+            member_count,
+        )
+    };
+    unsafe { submit(client_ptr, callback_index, cmd, route_info) };
+}
+
+/// Execute an `EXISTS` command, counting how many of `key_count` keys exist. A key which is given multiple times is counted
+/// every time. The response is the count as an integer. Without `route_info`, keys of different slots are split in cluster mode
+/// into an `EXISTS` per slot, which are sent in parallel, and their counts are summed, so the keys don't have to share a slot.
+///
+/// # Safety
+/// * `client_ptr` must be a valid pointer created by [`create_client`](crate::create_client), before [`close_client`](crate::close_client) was called.
+/// * `keys` must point to `key_count` consecutive buffer pointers and `keys_len` must point to their lengths.
+///   See the safety documentation of [`convert_double_pointer_to_vec`].
+/// * `route_info` could be `null`, but if it is not `null`, it must be a valid [`RouteInfo`] pointer.
+#[no_mangle]
+pub unsafe extern "C" fn exists(
+    client_ptr: *const c_void,
+    callback_index: usize,
+    keys: *const *const u8,
+    keys_len: *const u32,
+    key_count: u32,
+    route_info: *const RouteInfo,
+) {
+    let mut cmd = redis::cmd("EXISTS");
+    for key in
+        unsafe { convert_double_pointer_to_vec(keys as *const *const c_void, key_count, keys_len) }
+    {
+        cmd.arg(key);
+    }
+    unsafe { submit(client_ptr, callback_index, cmd, route_info) };
+}
+
+/// Execute an `MGET` command, getting the values of `key_count` keys in a single request. The response is an array of the values
+/// in the order of the keys, with null for missing keys. Without `route_info`, keys of different slots are split in cluster mode
+/// into an `MGET` per slot, which are sent in parallel, and their values are reassembled in the order of the keys,
+/// so the keys don't have to share a slot.
+///
+/// # Safety
+/// * `client_ptr` must be a valid pointer created by [`create_client`](crate::create_client), before [`close_client`](crate::close_client) was called.
+/// * `keys` must point to `key_count` consecutive buffer pointers and `keys_len` must point to their lengths.
+///   See the safety documentation of [`convert_double_pointer_to_vec`].
+/// * `route_info` could be `null`, but if it is not `null`, it must be a valid [`RouteInfo`] pointer.
+#[no_mangle]
+pub unsafe extern "C" fn mget(
+    client_ptr: *const c_void,
+    callback_index: usize,
+    keys: *const *const u8,
+    keys_len: *const u32,
+    key_count: u32,
+    route_info: *const RouteInfo,
+) {
+    let mut cmd = redis::cmd("MGET");
+    for key in
+        unsafe { convert_double_pointer_to_vec(keys as *const *const c_void, key_count, keys_len) }
+    {
+        cmd.arg(key);
+    }
+    unsafe { submit(client_ptr, callback_index, cmd, route_info) };
+}
+
+/// Execute a `ZMSCORE` command, getting the scores of multiple members of a sorted set in a single round trip.
+/// The response is an array of doubles in the order of the members, with null for missing members, regardless of the protocol.
+///
+/// # Safety
+/// * `client_ptr` must be a valid pointer created by [`create_client`](crate::create_client), before [`close_client`](crate::close_client) was called.
+/// * `key`, `members`, `members_len` and `member_count` - see the safety documentation of [`key_members_command`].
+/// * `route_info` could be `null`, but if it is not `null`, it must be a valid [`RouteInfo`] pointer.
+#[allow(clippy::too_many_arguments)]
+#[no_mangle]
+pub unsafe extern "C" fn zmscore(
+    client_ptr: *const c_void,
+    callback_index: usize,
+    key: *const u8,
+    key_len: u32,
+    members: *const *const u8,
+    members_len: *const u32,
+    member_count: u32,
+    route_info: *const RouteInfo,
+) {
+    let cmd =
+        unsafe { key_members_command("ZMSCORE", key, key_len, members, members_len, member_count) };
+    unsafe { submit(client_ptr, callback_index, cmd, route_info) };
+}
+
+/// Create a `SINTERCARD` command of `keys`, which stops counting once the cardinality reaches `limit`.
+/// `LIMIT 0` is passed as is, since the server treats it as no limit.
+fn sintercard_command(keys: &[&[u8]], limit: u32) -> Cmd {
+    let mut cmd = redis::cmd("SINTERCARD");
+    cmd.arg(keys.len()).arg(keys).arg("LIMIT").arg(limit);
+    cmd
+}
+
+/// Execute a `SINTERCARD` command, getting the cardinality of the intersection of the sets of `key_count` keys.
+/// The server stops counting once the cardinality reaches `limit`, which caps the work on large sets, `0` means no limit.
+/// The response is an integer.
+///
+/// In cluster mode all keys must map to the same slot, otherwise the request fails with a client error, without being sent.
+///
+/// # Safety
+/// * `client_ptr` must be a valid pointer created by [`create_client`](crate::create_client), before [`close_client`](crate::close_client) was called.
+/// * `keys` must point to `key_count` consecutive buffer pointers and `keys_len` must point to their lengths.
+///   See the safety documentation of [`convert_double_pointer_to_vec`].
+/// * `route_info` could be `null`, but if it is not `null`, it must be a valid [`RouteInfo`] pointer.
+#[no_mangle]
+pub unsafe extern "C" fn sintercard(
+    client_ptr: *const c_void,
+    callback_index: usize,
+    keys: *const *const u8,
+    keys_len: *const u32,
+    key_count: u32,
+    limit: u32,
+    route_info: *const RouteInfo,
+) {
+    let Some(client) = (unsafe { open_client_from_ptr(client_ptr, callback_index) }) else {
+        return;
+    };
+    let keys =
+        unsafe { convert_double_pointer_to_vec(keys as *const *const c_void, key_count, keys_len) };
+    if client.core.standalone_address.is_none()
+        && keys.windows(2).any(|keys| {
+            redis::cluster_topology::get_slot(keys[0]) != redis::cluster_topology::get_slot(keys[1])
+        })
+    {
+        let err = RedisError::from((
+            ErrorKind::ClientError,
+            "SINTERCARD keys must map to the same slot in cluster mode",
+        ));
+        unsafe { client.core.report_error(callback_index, err) };
+        return;
+    }
+    let cmd = sintercard_command(&keys, limit);
+    unsafe { submit(client_ptr, callback_index, cmd, route_info) };
+}
+
+/// Condition on the existence of the members of a `ZADD` command.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ZAddCondition {
+    /// Add new members and update existing members.
+    None = 0,
+    /// Only add new members, don't update existing members (`NX`).
+    OnlyIfNotExists = 1,
+    /// Only update existing members, don't add new members (`XX`).
+    OnlyIfExists = 2,
+}
+
+/// Comparison of the new and the current score of existing members of a `ZADD` command. New members are always added.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ZAddComparison {
+    /// Update the score regardless of the current score.
+    None = 0,
+    /// Only update the score if it is greater than the current score (`GT`).
+    GreaterThan = 1,
+    /// Only update the score if it is less than the current score (`LT`).
+    LessThan = 2,
+}
+
+/// Flags of the `ZADD` command.
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct ZAddFlags {
+    pub condition: ZAddCondition,
+    /// Comparisons can't be combined with [`ZAddCondition::OnlyIfNotExists`].
+    pub comparison: ZAddComparison,
+    /// Count the changed members, i.e. the added members and the members whose score was updated, instead of the added members (`CH`).
+    pub changed: bool,
+    /// Increment the score of the member by the given score, like `ZINCRBY` (`INCR`). Requires exactly one member.
+    pub increment: bool,
+}
+
+/// Build a `ZADD` command of score and member pairs, rejecting combinations of flags which the server would reject.
+fn zadd_command(
+    key: &[u8],
+    members: Vec<&[u8]>,
+    scores: &[f64],
+    flags: &ZAddFlags,
+) -> RedisResult<Cmd> {
+    let invalid = |detail: &'static str| Err(RedisError::from((ErrorKind::ClientError, detail)));
+    if flags.condition == ZAddCondition::OnlyIfNotExists && flags.comparison != ZAddComparison::None
+    {
+        return invalid("ZADD GT and LT can't be combined with NX");
+    }
+    if flags.increment && members.len() != 1 {
+        return invalid("ZADD INCR requires exactly one member");
+    }
+    if members.is_empty() {
+        return invalid("ZADD requires at least one member");
+    }
+    let mut cmd = redis::cmd("ZADD");
+    cmd.arg(key);
+    match flags.condition {
+        ZAddCondition::None => {}
+        ZAddCondition::OnlyIfNotExists => {
+            cmd.arg("NX");
+        }
+        ZAddCondition::OnlyIfExists => {
+            cmd.arg("XX");
+        }
+    }
+    match flags.comparison {
+        ZAddComparison::None => {}
+        ZAddComparison::GreaterThan => {
+            cmd.arg("GT");
+        }
+        ZAddComparison::LessThan => {
+            cmd.arg("LT");
+        }
+    }
+    if flags.changed {
+        cmd.arg("CH");
+    }
+    if flags.increment {
+        cmd.arg("INCR");
+    }
+    for (member, score) in members.into_iter().zip(scores) {
+        cmd.arg(*score).arg(member);
+    }
+    Ok(cmd)
+}
+
+/// Execute a `ZADD` command, adding `member_count` members with their `scores` to a sorted set, or updating their scores.
+/// The response is the number of added members as an integer, or the number of changed members with the `changed` flag.
+/// With the `increment` flag the response is the new score of the member as a double, or null if the member wasn't updated
+/// because of the condition or the comparison. Invalid combinations of flags fail the request with a client error, without sending it.
+/// Without `route_info` the command is routed by the slot of the key.
+///
+/// # Safety
+/// * `client_ptr` must be a valid pointer created by [`create_client`](crate::create_client), before [`close_client`](crate::close_client) was called.
+/// * `key`, `members`, `members_len` and `member_count` - see the safety documentation of [`key_members_command`].
+/// * `scores` must point to `member_count` consecutive doubles, the score of every member.
+/// * `flags` could be `null`, but if it is not `null`, it must be a valid [`ZAddFlags`] pointer.
+/// * `route_info` could be `null`, but if it is not `null`, it must be a valid [`RouteInfo`] pointer.
+#[allow(clippy::too_many_arguments)]
+#[no_mangle]
+pub unsafe extern "C" fn zadd(
+    client_ptr: *const c_void,
+    callback_index: usize,
+    key: *const u8,
+    key_len: u32,
+    members: *const *const u8,
+    members_len: *const u32,
+    scores: *const f64,
+    member_count: u32,
+    flags: *const ZAddFlags,
+    route_info: *const RouteInfo,
+) {
+    let Some(client) = (unsafe { open_client_from_ptr(client_ptr, callback_index) }) else {
+        return;
+    };
+    let members = unsafe {
+        convert_double_pointer_to_vec(members as *const *const c_void, member_count, members_len)
+    };
+    let scores = match member_count {
+        0 => &[][..],
+        _ => unsafe { from_raw_parts(scores, member_count as usize) },
+    };
+    let flags = unsafe { flags.as_ref() }.copied().unwrap_or(ZAddFlags {
+        condition: ZAddCondition::None,
+        comparison: ZAddComparison::None,
+        changed: false,
+        increment: false,
+    });
+    let cmd = match zadd_command(
+        unsafe { ptr_to_bytes(key, key_len) },
+        members,
+        scores,
+        &flags,
+    ) {
+        Ok(cmd) => cmd,
+        Err(err) => {
+            unsafe { client.core.report_error(callback_index, err) };
+            return;
+        }
+    };
+    let route = unsafe { create_route(route_info, &cmd) };
+    unsafe { execute_command(&client, callback_index, cmd, route, None, None) };
+}
+
+/// Interpretation of the range of a `ZRANGE` command.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ZRangeBy {
+    /// The range is of inclusive indices, which may be negative to count from the end.
+    Index = 0,
+    /// The range is of scores (`BYSCORE`), inclusive unless prefixed by `(`, or `-inf` and `+inf`.
+    Score = 1,
+    /// The range is of members (`BYLEX`), prefixed by `[` if inclusive or `(` if exclusive, or `-` and `+`.
+    Lex = 2,
+}
+
+/// Options of the `ZRANGE` command.
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct ZRangeOptions {
+    pub by: ZRangeBy,
+    /// Return the members from the highest to the lowest score (`REV`). The range is then given from `start` down to `stop`,
+    /// e.g. from the maximal score to the minimal score.
+    pub reverse: bool,
+    /// Skip `limit_offset` members and return at most `limit_count` members, a negative count returns all remaining members (`LIMIT`).
+    /// Requires [`ZRangeBy::Score`] or [`ZRangeBy::Lex`].
+    pub has_limit: bool,
+    pub limit_offset: i64,
+    pub limit_count: i64,
+    /// Return the score of every member (`WITHSCORES`). Can't be combined with [`ZRangeBy::Lex`].
+    pub with_scores: bool,
+}
+
+/// Build a `ZRANGE` command, rejecting combinations of options which the server would reject.
+/// The bounds are passed as is, so exclusive bounds and infinities are given in the syntax of the server.
+fn zrange_command(
+    key: &[u8],
+    start: &[u8],
+    stop: &[u8],
+    options: &ZRangeOptions,
+) -> RedisResult<Cmd> {
+    let invalid = |detail: &'static str| Err(RedisError::from((ErrorKind::ClientError, detail)));
+    if options.by == ZRangeBy::Lex && options.with_scores {
+        return invalid("ZRANGE BYLEX can't be combined with WITHSCORES");
+    }
+    if options.by == ZRangeBy::Index && options.has_limit {
+        return invalid("ZRANGE LIMIT requires BYSCORE or BYLEX");
+    }
+    let mut cmd = redis::cmd("ZRANGE");
+    cmd.arg(key).arg(start).arg(stop);
+    match options.by {
+        ZRangeBy::Index => {}
+        ZRangeBy::Score => {
+            cmd.arg("BYSCORE");
+        }
+        ZRangeBy::Lex => {
+            cmd.arg("BYLEX");
+        }
+    }
+    if options.reverse {
+        cmd.arg("REV");
+    }
+    if options.has_limit {
+        cmd.arg("LIMIT")
+            .arg(options.limit_offset)
+            .arg(options.limit_count);
+    }
+    if options.with_scores {
+        cmd.arg("WITHSCORES");
+    }
+    Ok(cmd)
+}
+
+/// Convert the reply of `ZRANGE` with `WITHSCORES`, which glide-core converts to a map of members to double scores,
+/// to an array of maps of the `member` and its `score`, so the order of the range is kept by the caller.
+fn convert_zrange_with_scores(value: Value) -> RedisResult<Value> {
+    let Value::Map(members) = value else {
+        return Err(RedisError::from((
+            ErrorKind::TypeError,
+            "Unexpected ZRANGE response",
+            format!("{value:?}"),
+        )));
+    };
+    Ok(Value::Array(
+        members
+            .into_iter()
+            .map(|(member, score)| {
+                Value::Map(vec![(bulk("member"), member), (bulk("score"), score)])
+            })
+            .collect(),
+    ))
+}
+
+/// Execute a `ZRANGE` command, getting the members of a sorted set in the range from `start` to `stop`, by index, score or member.
+/// `start` and `stop` are passed to the server as is, e.g. `(1.5` for an exclusive score, `+inf`, or `[a` for an inclusive member.
+/// The response is an array of the members in the order of the range, or with the `with_scores` option an array of maps
+/// of the `member` and its `score` as a double. Invalid combinations of options fail the request with a client error,
+/// without sending it. Without `route_info` the command is routed by the slot of the key.
+///
+/// # Safety
+/// * `client_ptr` must be a valid pointer created by [`create_client`](crate::create_client), before [`close_client`](crate::close_client) was called.
+/// * `key` must point to `key_len` consecutive bytes, `start` to `start_len` consecutive bytes and `stop` to `stop_len` consecutive bytes.
+///   See the safety documentation of [`ptr_to_bytes`].
+/// * `options` could be `null`, but if it is not `null`, it must be a valid [`ZRangeOptions`] pointer.
+/// * `route_info` could be `null`, but if it is not `null`, it must be a valid [`RouteInfo`] pointer.
+#[allow(clippy::too_many_arguments)]
+#[no_mangle]
+pub unsafe extern "C" fn zrange(
+    client_ptr: *const c_void,
+    callback_index: usize,
+    key: *const u8,
+    key_len: u32,
+    start: *const u8,
+    start_len: u32,
+    stop: *const u8,
+    stop_len: u32,
+    options: *const ZRangeOptions,
+    route_info: *const RouteInfo,
+) {
+    let Some(client) = (unsafe { open_client_from_ptr(client_ptr, callback_index) }) else {
+        return;
+    };
+    let options = unsafe { options.as_ref() }
+        .copied()
+        .unwrap_or(ZRangeOptions {
+            by: ZRangeBy::Index,
+            reverse: false,
+            has_limit: false,
+            limit_offset: 0,
+            limit_count: 0,
+            with_scores: false,
+        });
+    let cmd = match zrange_command(
+        unsafe { ptr_to_bytes(key, key_len) },
+        unsafe { ptr_to_bytes(start, start_len) },
+        unsafe { ptr_to_bytes(stop, stop_len) },
+        &options,
+    ) {
+        Ok(cmd) => cmd,
+        Err(err) => {
+            unsafe { client.core.report_error(callback_index, err) };
+            return;
+        }
+    };
+    let convert: Option<ResponseConverter> = match options.with_scores {
+        true => Some(Box::new(convert_zrange_with_scores)),
+        false => None,
+    };
+    unsafe { submit_with_conversion(client_ptr, callback_index, cmd, route_info, convert) };
+}
+
+/// Execute a `GEOPOS` command.
+/// The response is an array with a map of `longitude` and `latitude` doubles for every member, or null for missing members.
+///
+/// # Safety
+/// * `client_ptr` must be a valid pointer created by [`create_client`](crate::create_client), before [`close_client`](crate::close_client) was called.
+/// * `key` must point to `key_len` consecutive bytes. See the safety documentation of [`ptr_to_bytes`].
+/// * `members` must point to `member_count` consecutive buffer pointers and `members_len` must point to their lengths.
+///   See the safety documentation of [`convert_double_pointer_to_vec`].
+/// * `route_info` could be `null`, but if it is not `null`, it must be a valid [`RouteInfo`] pointer.
+#[allow(clippy::too_many_arguments)]
+#[no_mangle]
+pub unsafe extern "C" fn geopos(
+    client_ptr: *const c_void,
+    callback_index: usize,
+    key: *const u8,
+    key_len: u32,
+    members: *const *const u8,
+    members_len: *const u32,
+    member_count: u32,
+    route_info: *const RouteInfo,
+) {
+    let mut cmd = redis::cmd("GEOPOS");
+    cmd.arg(unsafe { ptr_to_bytes(key, key_len) });
+    for member in unsafe {
+        convert_double_pointer_to_vec(members as *const *const c_void, member_count, members_len)
+    } {
+        cmd.arg(member);
+    }
+    unsafe {
+        submit_with_conversion(
+            client_ptr,
+            callback_index,
+            cmd,
+            route_info,
+            Some(Box::new(convert_geopos)),
+        )
+    };
+}
+
+/// Unit of distances in GEO commands.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GeoUnit {
+    Meters = 0,
+    Kilometers = 1,
+    Miles = 2,
+    Feet = 3,
+}
+
+impl GeoUnit {
+    fn as_arg(self) -> &'static str {
+        match self {
+            GeoUnit::Meters => "M",
+            GeoUnit::Kilometers => "KM",
+            GeoUnit::Miles => "MI",
+            GeoUnit::Feet => "FT",
+        }
+    }
+}
+
+/// Sort order of GEO search results.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GeoSortOrder {
+    /// Unsorted, in the order the server finds the results.
+    None = 0,
+    Ascending = 1,
+    Descending = 2,
+}
+
+/// Center and shape of a `GEOSEARCH` query.
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct GeoSearchArea {
+    /// Search around an existing member (`FROMMEMBER`), otherwise around `longitude` and `latitude` (`FROMLONLAT`).
+    pub from_member: bool,
+    pub member: *const u8,
+    pub member_len: u32,
+    pub longitude: f64,
+    pub latitude: f64,
+    /// Search in a box of `width` by `height` (`BYBOX`), otherwise in a circle of `radius` (`BYRADIUS`).
+    pub by_box: bool,
+    pub radius: f64,
+    pub width: f64,
+    pub height: f64,
+    pub unit: GeoUnit,
+}
+
+/// Options of the `GEOSEARCH` command.
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct GeoSearchOptions {
+    pub with_coord: bool,
+    pub with_dist: bool,
+    pub with_hash: bool,
+    /// Maximal number of results, `0` means no limit.
+    pub count: u32,
+    /// Return as soon as `count` results are found, which might not be the closest ones (`ANY`).
+    pub any: bool,
+    pub sort: GeoSortOrder,
+}
+
+/// Execute a `GEOSEARCH` command.
+/// The response is an array with a map for every result, containing the `member` and, depending on the options,
+/// its `distance` (double, in the unit of the search area), `hash` (integer), `longitude` and `latitude` (doubles).
+///
+/// # Safety
+/// * `client_ptr` must be a valid pointer created by [`create_client`](crate::create_client), before [`close_client`](crate::close_client) was called.
+/// * `key` must point to `key_len` consecutive bytes. See the safety documentation of [`ptr_to_bytes`].
+/// * `area` must not be `null` and must be a valid [`GeoSearchArea`] pointer. If `from_member` is set,
+///   its `member` must point to `member_len` consecutive bytes.
+/// * `options` could be `null`, but if it is not `null`, it must be a valid [`GeoSearchOptions`] pointer.
+/// * `route_info` could be `null`, but if it is not `null`, it must be a valid [`RouteInfo`] pointer.
+#[no_mangle]
+pub unsafe extern "C" fn geosearch(
+    client_ptr: *const c_void,
+    callback_index: usize,
+    key: *const u8,
+    key_len: u32,
+    area: *const GeoSearchArea,
+    options: *const GeoSearchOptions,
+    route_info: *const RouteInfo,
+) {
+    let area = unsafe { *area };
+    let mut cmd = redis::cmd("GEOSEARCH");
+    cmd.arg(unsafe { ptr_to_bytes(key, key_len) });
+    if area.from_member {
+        cmd.arg("FROMMEMBER")
+            .arg(unsafe { ptr_to_bytes(area.member, area.member_len) });
+    } else {
+        cmd.arg("FROMLONLAT").arg(area.longitude).arg(area.latitude);
+    }
+    if area.by_box {
+        cmd.arg("BYBOX").arg(area.width).arg(area.height);
+    } else {
+        cmd.arg("BYRADIUS").arg(area.radius);
+    }
+    cmd.arg(area.unit.as_arg());
+    if let Some(options) = unsafe { options.as_ref() } {
+        match options.sort {
+            GeoSortOrder::None => {}
+            GeoSortOrder::Ascending => {
+                cmd.arg("ASC");
+            }
+            GeoSortOrder::Descending => {
+                cmd.arg("DESC");
+            }
+        }
+        if options.count > 0 {
+            cmd.arg("COUNT").arg(options.count);
+            if options.any {
+                cmd.arg("ANY");
+            }
+        }
+        if options.with_coord {
+            cmd.arg("WITHCOORD");
+        }
+        if options.with_dist {
+            cmd.arg("WITHDIST");
+        }
+        if options.with_hash {
+            cmd.arg("WITHHASH");
+        }
+    }
+    unsafe {
+        submit_with_conversion(
+            client_ptr,
+            callback_index,
+            cmd,
+            route_info,
+            Some(Box::new(convert_geosearch)),
+        )
+    };
+}
+
+/// Options of the `LCS` command.
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct LcsOptions {
+    /// Return the length of the longest common subsequence instead of the subsequence (`LEN`).
+    pub len: bool,
+    /// Return the ranges of the matches and the length of the subsequence (`IDX`).
+    pub idx: bool,
+    /// Return only matches of at least this length (`MINMATCHLEN`), `0` returns all matches. Only applies with `idx`.
+    pub min_match_len: u32,
+    /// Return the length of every match (`WITHMATCHLEN`). Only applies with `idx`.
+    pub with_match_len: bool,
+}
+
+/// Execute an `LCS` command, finding the longest common subsequence of the strings stored at `key1` and `key2`.
+/// The response is the subsequence, or its length with the `len` option. With the `idx` option the response is a map of
+/// `matches` - an array of maps with the `key1` and `key2` ranges of every match, each a map of inclusive `start` and `end`
+/// integers, and the `match_len` integer with the `with_match_len` option - and `len`, the length of the subsequence.
+/// In cluster mode both keys must map to the same slot, otherwise the request fails with a client error, without being sent.
+///
+/// # Safety
+/// * `client_ptr` must be a valid pointer created by [`create_client`](crate::create_client), before [`close_client`](crate::close_client) was called.
+/// * `key1` must point to `key1_len` consecutive bytes and `key2` must point to `key2_len` consecutive bytes.
+///   See the safety documentation of [`ptr_to_bytes`].
+/// * `options` could be `null`, but if it is not `null`, it must be a valid [`LcsOptions`] pointer.
+/// * `route_info` could be `null`, but if it is not `null`, it must be a valid [`RouteInfo`] pointer.
+#[allow(clippy::too_many_arguments)]
+#[no_mangle]
+pub unsafe extern "C" fn lcs(
+    client_ptr: *const c_void,
+    callback_index: usize,
+    key1: *const u8,
+    key1_len: u32,
+    key2: *const u8,
+    key2_len: u32,
+    options: *const LcsOptions,
+    route_info: *const RouteInfo,
+) {
+    let Some(client) = (unsafe { open_client_from_ptr(client_ptr, callback_index) }) else {
+        return;
+    };
+    let key1 = unsafe { ptr_to_bytes(key1, key1_len) };
+    let key2 = unsafe { ptr_to_bytes(key2, key2_len) };
+    if client.core.standalone_address.is_none()
+        && redis::cluster_topology::get_slot(key1) != redis::cluster_topology::get_slot(key2)
+    {
+        let err = RedisError::from((
+            ErrorKind::ClientError,
+            "LCS keys must map to the same slot in cluster mode",
+        ));
+        unsafe { client.core.report_error(callback_index, err) };
+        return;
+    }
+    let mut cmd = redis::cmd("LCS");
+    cmd.arg(key1).arg(key2);
+    if let Some(options) = unsafe { options.as_ref() } {
+        if options.len {
+            cmd.arg("LEN");
+        }
+        if options.idx {
+            cmd.arg("IDX");
+            if options.min_match_len > 0 {
+                cmd.arg("MINMATCHLEN").arg(options.min_match_len);
+            }
+            if options.with_match_len {
+                cmd.arg("WITHMATCHLEN");
+            }
+        }
+    }
+    let route = unsafe { create_route(route_info, &cmd) };
+    unsafe {
+        execute_command(
+            &client,
+            callback_index,
+            cmd,
+            route,
+            None,
+            Some(Box::new(convert_lcs)),
+        )
+    };
+}
+
+/// Submit a random members command of `key` with `count`, and `values_arg` if `with_values` is set,
+/// converting its response by [`convert_random_members`].
+///
+/// # Safety
+///
+/// * `key` must point to `key_len` consecutive bytes. See the safety documentation of [`ptr_to_bytes`].
+/// * See the safety documentation of [`submit`].
+#[allow(clippy::too_many_arguments)]
+unsafe fn submit_random_members(
+    client_ptr: *const c_void,
+    callback_index: usize,
+    name: &str,
+    key: *const u8,
+    key_len: u32,
+    count: i64,
+    values_arg: Option<(&'static str, &'static str)>,
+    with_values: bool,
+    route_info: *const RouteInfo,
+) {
+    let mut cmd = redis::cmd(name);
+    cmd.arg(unsafe { ptr_to_bytes(key, key_len) }).arg(count);
+    let value_name = values_arg.map(|(_, value_name)| value_name);
+    let with_values = match values_arg {
+        Some((arg, _)) if with_values => {
+            cmd.arg(arg);
+            true
+        }
+        _ => false,
+    };
+    unsafe {
+        submit_with_conversion(
+            client_ptr,
+            callback_index,
+            cmd,
+            route_info,
+            Some(Box::new(move |value| {
+                convert_random_members(value, value_name, with_values)
+            })),
+        )
+    };
+}
+
+/// Execute an `HRANDFIELD` command with a count, returning random fields of the hash stored at `key`.
+/// A positive `count` returns up to `count` distinct fields, while a negative `count` returns exactly `-count` fields,
+/// which may repeat.
+/// The response is an array of maps, one per field, with the `member` (the field) and its `value`, which is null unless
+/// `with_values` is set (`WITHVALUES`). The array is empty if the key doesn't exist.
+///
+/// # Safety
+/// * `client_ptr` must be a valid pointer created by [`create_client`](crate::create_client), before [`close_client`](crate::close_client) was called.
+/// * `key` must point to `key_len` consecutive bytes. See the safety documentation of [`ptr_to_bytes`].
+/// * `route_info` could be `null`, but if it is not `null`, it must be a valid [`RouteInfo`] pointer.
+#[no_mangle]
+pub unsafe extern "C" fn hrandfield(
+    client_ptr: *const c_void,
+    callback_index: usize,
+    key: *const u8,
+    key_len: u32,
+    count: i64,
+    with_values: bool,
+    route_info: *const RouteInfo,
+) {
+    unsafe {
+        submit_random_members(
+            client_ptr,
+            callback_index,
+            "HRANDFIELD",
+            key,
+            key_len,
+            count,
+            Some(("WITHVALUES", "value")),
+            with_values,
+            route_info,
+        )
+    };
+}
+
+/// Execute an `SRANDMEMBER` command with a count, returning random members of the set stored at `key`.
+/// A positive `count` returns up to `count` distinct members, while a negative `count` returns exactly `-count` members,
+/// which may repeat.
+/// The response is an array of maps, one per member, with the `member`, like the responses of [`hrandfield`] and [`zrandmember`].
+/// The array is empty if the key doesn't exist.
+///
+/// # Safety
+/// * `client_ptr` must be a valid pointer created by [`create_client`](crate::create_client), before [`close_client`](crate::close_client) was called.
+/// * `key` must point to `key_len` consecutive bytes. See the safety documentation of [`ptr_to_bytes`].
+/// * `route_info` could be `null`, but if it is not `null`, it must be a valid [`RouteInfo`] pointer.
+#[no_mangle]
+pub unsafe extern "C" fn srandmember(
+    client_ptr: *const c_void,
+    callback_index: usize,
+    key: *const u8,
+    key_len: u32,
+    count: i64,
+    route_info: *const RouteInfo,
+) {
+    unsafe {
+        submit_random_members(
+            client_ptr,
+            callback_index,
+            "SRANDMEMBER",
+            key,
+            key_len,
+            count,
+            None,
+            false,
+            route_info,
+        )
+    };
+}
+
+/// Execute a `ZRANDMEMBER` command with a count, returning random members of the sorted set stored at `key`.
+/// A positive `count` returns up to `count` distinct members, while a negative `count` returns exactly `-count` members,
+/// which may repeat.
+/// The response is an array of maps, one per member, with the `member` and its `score` as a double, which is null unless
+/// `with_scores` is set (`WITHSCORES`). The array is empty if the key doesn't exist.
+///
+/// # Safety
+/// * `client_ptr` must be a valid pointer created by [`create_client`](crate::create_client), before [`close_client`](crate::close_client) was called.
+/// * `key` must point to `key_len` consecutive bytes. See the safety documentation of [`ptr_to_bytes`].
+/// * `route_info` could be `null`, but if it is not `null`, it must be a valid [`RouteInfo`] pointer.
+#[no_mangle]
+pub unsafe extern "C" fn zrandmember(
+    client_ptr: *const c_void,
+    callback_index: usize,
+    key: *const u8,
+    key_len: u32,
+    count: i64,
+    with_scores: bool,
+    route_info: *const RouteInfo,
+) {
+    unsafe {
+        submit_random_members(
+            client_ptr,
+            callback_index,
+            "ZRANDMEMBER",
+            key,
+            key_len,
+            count,
+            Some(("WITHSCORES", "score")),
+            with_scores,
+            route_info,
+        )
+    };
+}
+
+/// Type of clients to kill by `CLIENT KILL`.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ClientType {
+    /// Don't filter by type.
+    Any = 0,
+    Normal = 1,
+    Master = 2,
+    Replica = 3,
+    PubSub = 4,
+}
+
+/// Filters of the `CLIENT KILL` command. Only clients matching all given filters are killed.
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct ClientKillFilter {
+    pub has_id: bool,
+    /// ID of the client, as returned by `CLIENT ID` (`ID`).
+    pub id: u64,
+    /// Address of the client in `ip:port` format (`ADDR`), ignored if empty.
+    pub addr: *const u8,
+    pub addr_len: u32,
+    /// Local address of the server the client is connected to in `ip:port` format (`LADDR`), ignored if empty.
+    pub laddr: *const u8,
+    pub laddr_len: u32,
+    pub client_type: ClientType,
+    pub has_max_age: bool,
+    /// Kill clients connected for at least this amount of seconds (`MAXAGE`), supported since Valkey 7.4.
+    pub max_age: u64,
+}
+
+/// Execute a `CLIENT KILL` command with the given filters.
+/// The response is the number of killed clients, or a map of node addresses to the number of clients killed on every node
+/// if the command is routed to multiple nodes. Without a route the command is sent to a random node.
+///
+/// # Safety
+/// * `client_ptr` must be a valid pointer created by [`create_client`](crate::create_client), before [`close_client`](crate::close_client) was called.
+/// * `filter` must not be `null` and must be a valid [`ClientKillFilter`] pointer, whose `addr` and `laddr`
+///   point to `addr_len` and `laddr_len` consecutive bytes respectively. See the safety documentation of [`ptr_to_bytes`].
+/// * `route_info` could be `null`, but if it is not `null`, it must be a valid [`RouteInfo`] pointer.
+#[no_mangle]
+pub unsafe extern "C" fn client_kill(
+    client_ptr: *const c_void,
+    callback_index: usize,
+    filter: *const ClientKillFilter,
+    route_info: *const RouteInfo,
+) {
+    let filter = unsafe { *filter };
+    let mut cmd = redis::cmd("CLIENT");
+    cmd.arg("KILL");
+    if filter.has_id {
+        cmd.arg("ID").arg(filter.id);
+    }
+    let addr = unsafe { ptr_to_bytes(filter.addr, filter.addr_len) };
+    if !addr.is_empty() {
+        cmd.arg("ADDR").arg(addr);
+    }
+    let laddr = unsafe { ptr_to_bytes(filter.laddr, filter.laddr_len) };
+    if !laddr.is_empty() {
+        cmd.arg("LADDR").arg(laddr);
+    }
+    let client_type = match filter.client_type {
+        ClientType::Any => None,
+        ClientType::Normal => Some("NORMAL"),
+        ClientType::Master => Some("MASTER"),
+        ClientType::Replica => Some("REPLICA"),
+        ClientType::PubSub => Some("PUBSUB"),
+    };
+    if let Some(client_type) = client_type {
+        cmd.arg("TYPE").arg(client_type);
+    }
+    if filter.has_max_age {
+        cmd.arg("MAXAGE").arg(filter.max_age);
+    }
+    unsafe { submit(client_ptr, callback_index, cmd, route_info) };
+}
+
+/// Mode of the `CLIENT PAUSE` command.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ClientPauseMode {
+    /// Pause all commands.
+    All = 0,
+    /// Pause only write commands, reads are still served.
+    Write = 1,
+}
+
+/// Send a command to every primary and report a map of node addresses to `OK`, or to the error message
+/// of nodes which failed to apply it.
+/// The command is checked against the command filter of the client first.
+///
+/// # Safety
+///
+/// * `client_ptr` must be a valid pointer created by [`create_client`](crate::create_client), before [`close_client`](crate::close_client) was called.
+unsafe fn submit_to_all_primaries(client_ptr: *const c_void, callback_index: usize, cmd: Cmd) {
+    let Some(client) = (unsafe { open_client_from_ptr(client_ptr, callback_index) }) else {
+        return;
+    };
+    let core = client.core.clone();
+    if let Err(err) = core.check_command_allowed(&cmd) {
+        unsafe { core.report_result(callback_index, Err(err)) };
+        return;
+    }
+    client.runtime.spawn(async move {
+        let result = core.send_to_all_primaries(cmd).await;
+        unsafe { core.report_result(callback_index, result) };
+    });
+}
+
+/// Execute a `CLIENT PAUSE` command on every primary, pausing clients for `timeout_ms` milliseconds or until
+/// [`client_unpause`] is called. Commands of paused clients block until the pause ends.
+/// The response is a map of node addresses to `OK`, or to the error message of nodes which failed to apply the pause,
+/// so a partially applied pause can be detected and undone.
+///
+/// # Safety
+/// * `client_ptr` must be a valid pointer created by [`create_client`](crate::create_client), before [`close_client`](crate::close_client) was called.
+#[no_mangle]
+pub unsafe extern "C" fn client_pause(
+    client_ptr: *const c_void,
+    callback_index: usize,
+    timeout_ms: u64,
+    mode: ClientPauseMode,
+) {
+    let mut cmd = redis::cmd("CLIENT");
+    cmd.arg("PAUSE").arg(timeout_ms).arg(match mode {
+        ClientPauseMode::All => "ALL",
+        ClientPauseMode::Write => "WRITE",
+    });
+    unsafe { submit_to_all_primaries(client_ptr, callback_index, cmd) };
+}
+
+/// Execute a `CLIENT UNPAUSE` command on every primary, ending a pause set by [`client_pause`].
+/// The response is a map of node addresses to `OK`, or to the error message of nodes which failed to apply it.
+///
+/// # Safety
+/// * `client_ptr` must be a valid pointer created by [`create_client`](crate::create_client), before [`close_client`](crate::close_client) was called.
+#[no_mangle]
+pub unsafe extern "C" fn client_unpause(client_ptr: *const c_void, callback_index: usize) {
+    let mut cmd = redis::cmd("CLIENT");
+    cmd.arg("UNPAUSE");
+    unsafe { submit_to_all_primaries(client_ptr, callback_index, cmd) };
+}
+
+/// Convert a `CLIENT INFO` reply, a line of `name=value` fields, to a map of the fields which identify the connection:
+/// `id` as an integer, `addr` - the address of the client as seen by the server, `laddr` - the local address of the server
+/// the client connected to, and `name`, null if the connection has no name. A reply of multiple nodes is converted per node.
+fn convert_client_address(value: Value) -> RedisResult<Value> {
+    let info = match value {
+        Value::Map(nodes) => {
+            return nodes
+                .into_iter()
+                .map(|(node, info)| Ok((node, convert_client_address(info)?)))
+                .collect::<RedisResult<_>>()
+                .map(Value::Map)
+        }
+        Value::VerbatimString { text, .. } => text,
+        value => redis::from_owned_redis_value::<String>(value)?,
+    };
+    let field = |name: &str| {
+        info.split_whitespace()
+            .find_map(|field| field.strip_prefix(name)?.strip_prefix('='))
+    };
+    let Some(id) = field("id").and_then(|id| id.parse::<i64>().ok()) else {
+        return Err(RedisError::from((
+            ErrorKind::TypeError,
+            "Unexpected CLIENT INFO response",
+            info,
+        )));
+    };
+    let optional = |name: &str| {
+        field(name)
+            .filter(|value| !value.is_empty())
+            .map_or(Value::Nil, bulk)
+    };
+    Ok(Value::Map(vec![
+        (bulk("id"), Value::Int(id)),
+        (bulk("addr"), optional("addr")),
+        (bulk("laddr"), optional("laddr")),
+        (bulk("name"), optional("name")),
+    ]))
+}
+
+/// Get the address of the connection of the client as seen by the server, by `CLIENT INFO`, e.g. to diagnose why `CLIENT KILL`
+/// by address or ACL host rules don't match the client behind NAT or a proxy. The response is a map of the `id` of the connection,
+/// its `addr` as seen by the server, the `laddr` of the server it connected to, and its `name`. In cluster mode the request should
+/// be routed to a specific node, e.g. by [`RouteType::ByAddress`](crate::ffi::RouteType::ByAddress), since every node sees
+/// a different connection. Without `route_info` it is sent to a random node, and with a multi-node route the response is
+/// a map of node addresses to such maps. Requires servers 6.2 and later.
+///
+/// # Safety
+/// * `client_ptr` must be a valid pointer created by [`create_client`](crate::create_client), before [`close_client`](crate::close_client) was called.
+/// * `route_info` could be `null`, but if it is not `null`, it must be a valid [`RouteInfo`] pointer.
+#[no_mangle]
+pub unsafe extern "C" fn get_my_address(
+    client_ptr: *const c_void,
+    callback_index: usize,
+    route_info: *const RouteInfo,
+) {
+    let mut cmd = redis::cmd("CLIENT");
+    cmd.arg("INFO");
+    unsafe {
+        submit_with_conversion(
+            client_ptr,
+            callback_index,
+            cmd,
+            route_info,
+            Some(Box::new(convert_client_address)),
+        )
+    };
+}
+
+/// Mode of the `FLUSHALL` and `FLUSHDB` commands.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FlushMode {
+    /// Flush synchronously, the response is sent once the keys are deleted.
+    Sync = 0,
+    /// Flush asynchronously, the keys are deleted in the background.
+    Async = 1,
+}
+
+fn flush_command(name: &str, mode: FlushMode) -> Cmd {
+    let mut cmd = redis::cmd(name);
+    cmd.arg(match mode {
+        FlushMode::Sync => "SYNC",
+        FlushMode::Async => "ASYNC",
+    });
+    cmd
+}
+
+/// Execute a `FLUSHALL` command on every primary, deleting the keys of all databases on all nodes.
+/// The response is a map of node addresses to `OK`, or to the error message of nodes which failed to flush,
+/// so a partial flush can be detected. Fails with a `CommandNotAllowed` error if `FLUSHALL` is denied by the command filter.
+///
+/// # Safety
+/// * `client_ptr` must be a valid pointer created by [`create_client`](crate::create_client), before [`close_client`](crate::close_client) was called.
+#[no_mangle]
+pub unsafe extern "C" fn flush_all(
+    client_ptr: *const c_void,
+    callback_index: usize,
+    mode: FlushMode,
+) {
+    unsafe { submit_to_all_primaries(client_ptr, callback_index, flush_command("FLUSHALL", mode)) };
+}
+
+/// Execute a `FLUSHDB` command on every primary, deleting the keys of the selected database on all nodes.
+/// The response is like that of [`flush_all`]. Fails with a `CommandNotAllowed` error if `FLUSHDB` is denied by the command filter.
+///
+/// # Safety
+/// * `client_ptr` must be a valid pointer created by [`create_client`](crate::create_client), before [`close_client`](crate::close_client) was called.
+#[no_mangle]
+pub unsafe extern "C" fn flush_db(
+    client_ptr: *const c_void,
+    callback_index: usize,
+    mode: FlushMode,
+) {
+    unsafe { submit_to_all_primaries(client_ptr, callback_index, flush_command("FLUSHDB", mode)) };
+}
+
+/// Execute a `CONFIG GET` command with the given parameters, which may be glob-style patterns.
+/// The response is a map of parameter names to their values, regardless of the protocol - RESP2 replies
+/// with a flat array of names and values, which is converted to a map. If the command is routed to multiple nodes,
+/// e.g. by [`RouteType::AllNodes`](crate::ffi::RouteType::AllNodes), the response is a map of node addresses to these maps.
+/// Without a route the command is sent to a random node.
+///
+/// # Safety
+/// * `client_ptr` must be a valid pointer created by [`create_client`](crate::create_client), before [`close_client`](crate::close_client) was called.
+/// * `params` must point to `param_count` consecutive buffer pointers and `params_len` must point to their lengths.
+///   See the safety documentation of [`convert_double_pointer_to_vec`].
+/// * `route_info` could be `null`, but if it is not `null`, it must be a valid [`RouteInfo`] pointer.
+#[no_mangle]
+pub unsafe extern "C" fn config_get(
+    client_ptr: *const c_void,
+    callback_index: usize,
+    params: *const *const u8,
+    params_len: *const u32,
+    param_count: u32,
+    route_info: *const RouteInfo,
+) {
+    let mut cmd = redis::cmd("CONFIG");
+    cmd.arg("GET");
+    for param in unsafe {
+        convert_double_pointer_to_vec(params as *const *const c_void, param_count, params_len)
+    } {
+        cmd.arg(param);
+    }
+    unsafe { submit(client_ptr, callback_index, cmd, route_info) };
+}
+
+/// Execute a `CONFIG SET` command, setting all the given parameters atomically.
+/// `params_and_values` contains `param_count * 2` buffers - alternating parameter names and values.
+/// The response is `OK`. Without a route the command is sent to all nodes in cluster mode, and succeeds only if all nodes applied it.
+///
+/// # Safety
+/// * `client_ptr` must be a valid pointer created by [`create_client`](crate::create_client), before [`close_client`](crate::close_client) was called.
+/// * `params_and_values` must point to `param_count * 2` consecutive buffer pointers and `params_and_values_len` must point to their lengths.
+///   See the safety documentation of [`convert_double_pointer_to_vec`].
+/// * `route_info` could be `null`, but if it is not `null`, it must be a valid [`RouteInfo`] pointer.
+#[no_mangle]
+pub unsafe extern "C" fn config_set(
+    client_ptr: *const c_void,
+    callback_index: usize,
+    params_and_values: *const *const u8,
+    params_and_values_len: *const u32,
+    param_count: u32,
+    route_info: *const RouteInfo,
+) {
+    let mut cmd = redis::cmd("CONFIG");
+    cmd.arg("SET");
+    for arg in unsafe {
+        convert_double_pointer_to_vec(
+            params_and_values as *const *const c_void,
+            param_count * 2,
+            params_and_values_len,
+        )
+    } {
+        cmd.arg(arg);
+    }
+    unsafe { submit(client_ptr, callback_index, cmd, route_info) };
+}
+
+/// Execute an `INFO` command with the given sections, or the default sections if none are given.
+/// The response is a map of section names (in lower case) to maps of fields to values, instead of the text of the `INFO` response.
+/// Fields are integers or doubles where unambiguous, e.g. `used_memory`, and strings otherwise, e.g. `redis_version`.
+/// Fields with `key=value` sub-structures, like the databases in the `keyspace` section, are maps.
+/// If the command is routed to multiple nodes, which is the default in cluster mode, the response is a map of node addresses to these maps.
+///
+/// # Safety
+/// * `client_ptr` must be a valid pointer created by [`create_client`](crate::create_client), before [`close_client`](crate::close_client) was called.
+/// * `sections` must point to `section_count` consecutive buffer pointers and `sections_len` must point to their lengths.
+///   See the safety documentation of [`convert_double_pointer_to_vec`].
+/// * `route_info` could be `null`, but if it is not `null`, it must be a valid [`RouteInfo`] pointer.
+#[no_mangle]
+pub unsafe extern "C" fn info(
+    client_ptr: *const c_void,
+    callback_index: usize,
+    sections: *const *const u8,
+    sections_len: *const u32,
+    section_count: u32,
+    route_info: *const RouteInfo,
+) {
+    let mut cmd = redis::cmd("INFO");
+    for section in unsafe {
+        convert_double_pointer_to_vec(
+            sections as *const *const c_void,
+            section_count,
+            sections_len,
+        )
+    } {
+        cmd.arg(section);
+    }
+    unsafe {
+        submit_with_conversion(
+            client_ptr,
+            callback_index,
+            cmd,
+            route_info,
+            Some(Box::new(convert_info)),
+        )
+    };
+}
+
+/// Execute a `DBSIZE` command, counting the keys of the database.
+/// In cluster mode the command is sent to every primary by default, and the response is a map of `nodes` - a map of
+/// node addresses to their key counts - and `total`, the sum of the counts, as an integer. With a single node route,
+/// e.g. in standalone mode, the response is the key count of the node as an integer.
+/// With a route to all nodes the counts of the replicas are included in the total too.
+///
+/// # Safety
+/// * `client_ptr` must be a valid pointer created by [`create_client`](crate::create_client), before [`close_client`](crate::close_client) was called.
+/// * `route_info` could be `null`, but if it is not `null`, it must be a valid [`RouteInfo`] pointer.
+#[no_mangle]
+pub unsafe extern "C" fn dbsize(
+    client_ptr: *const c_void,
+    callback_index: usize,
+    route_info: *const RouteInfo,
+) {
+    let Some(client) = (unsafe { open_client_from_ptr(client_ptr, callback_index) }) else {
+        return;
+    };
+    let cmd = redis::cmd("DBSIZE");
+    // The count of every node is kept, instead of being summed by the response policy of `DBSIZE`
+    let route = match unsafe { create_route(route_info, &cmd) } {
+        Some(RoutingInfo::MultiNode((nodes, _))) => Some(RoutingInfo::MultiNode((nodes, None))),
+        None if client.core.standalone_address.is_none() => Some(RoutingInfo::MultiNode((
+            MultipleNodeRoutingInfo::AllMasters,
+            None,
+        ))),
+        route => route,
+    };
+    unsafe {
+        execute_command(
+            &client,
+            callback_index,
+            cmd,
+            route,
+            None,
+            Some(Box::new(convert_dbsize)),
+        )
+    };
+}
+
+/// Convert a `DBSIZE` response of multiple nodes, which is a map of node addresses to counts, to a map of the counts and their total.
+/// The count of a single node is returned as is.
+fn convert_dbsize(value: Value) -> RedisResult<Value> {
+    let Value::Map(nodes) = value else {
+        return Ok(value);
+    };
+    let mut total: i64 = 0;
+    for (_, count) in &nodes {
+        let Value::Int(count) = count else {
+            return Err(RedisError::from((
+                ErrorKind::TypeError,
+                "Unexpected DBSIZE response",
+                format!("{count:?}"),
+            )));
+        };
+        total += count;
+    }
+    Ok(Value::Map(vec![
+        (bulk("nodes"), Value::Map(nodes)),
+        (bulk("total"), Value::Int(total)),
+    ]))
+}
+
+/// Execute a `MEMORY STATS` command. The response is a map of the statistics, e.g. `total.allocated`, to integers or doubles,
+/// where the overheads of every database, e.g. `db.0`, are nested maps, instead of the flat array of the RESP2 response.
+/// In cluster mode the command is sent to every primary by default, and the response is a map of node addresses to these maps,
+/// as with any route to multiple nodes. With a single node route, e.g. in standalone mode, the response is the map of the node.
+///
+/// # Safety
+/// * `client_ptr` must be a valid pointer created by [`create_client`](crate::create_client), before [`close_client`](crate::close_client) was called.
+/// * `route_info` could be `null`, but if it is not `null`, it must be a valid [`RouteInfo`] pointer.
+#[no_mangle]
+pub unsafe extern "C" fn memory_stats(
+    client_ptr: *const c_void,
+    callback_index: usize,
+    route_info: *const RouteInfo,
+) {
+    let Some(client) = (unsafe { open_client_from_ptr(client_ptr, callback_index) }) else {
+        return;
+    };
+    let mut cmd = redis::cmd("MEMORY");
+    cmd.arg("STATS");
+    let route = match unsafe { create_route(route_info, &cmd) } {
+        None if client.core.standalone_address.is_none() => Some(RoutingInfo::MultiNode((
+            MultipleNodeRoutingInfo::AllMasters,
+            None,
+        ))),
+        route => route,
+    };
+    let multiple_nodes = matches!(route, Some(RoutingInfo::MultiNode(_)));
+    unsafe {
+        execute_command(
+            &client,
+            callback_index,
+            cmd,
+            route,
+            None,
+            Some(Box::new(move |value| {
+                convert_memory_stats(value, multiple_nodes)
+            })),
+        )
+    };
+}
+
+/// Submit a `COMMAND` subcommand with the given command names to a random node, and convert its response.
+///
+/// # Safety
+///
+/// * `client_ptr` must be a valid pointer created by [`create_client`](crate::create_client), before [`close_client`](crate::close_client) was called.
+/// * `names` must point to `name_count` consecutive buffer pointers and `names_len` must point to their lengths.
+///   See the safety documentation of [`convert_double_pointer_to_vec`].
+unsafe fn submit_command_metadata(
+    client_ptr: *const c_void,
+    callback_index: usize,
+    subcommand: &str,
+    names: *const *const u8,
+    names_len: *const u32,
+    name_count: u32,
+    convert: ResponseConverter,
+) {
+    let Some(client) = (unsafe { open_client_from_ptr(client_ptr, callback_index) }) else {
+        return;
+    };
+    let mut cmd = redis::cmd("COMMAND");
+    cmd.arg(subcommand);
+    for name in unsafe {
+        convert_double_pointer_to_vec(names as *const *const c_void, name_count, names_len)
+    } {
+        cmd.arg(name);
+    }
+    // All nodes serve the same commands, so any node could answer.
+    let route = Some(RoutingInfo::SingleNode(SingleNodeRoutingInfo::Random));
+    unsafe { execute_command(&client, callback_index, cmd, route, None, Some(convert)) };
+}
+
+/// Execute a `COMMAND INFO` command for the given command names, or for all commands if none are given.
+/// The response is a map of command names to maps of their metadata: `arity`, `flags`, `first_key`, `last_key`, `step`,
+/// and on newer servers `acl_categories`, `tips`, `key_specs` and `subcommands`. Unknown commands are omitted from the map.
+///
+/// # Safety
+/// * `client_ptr` must be a valid pointer created by [`create_client`](crate::create_client), before [`close_client`](crate::close_client) was called.
+/// * `names` must point to `name_count` consecutive buffer pointers and `names_len` must point to their lengths.
+///   See the safety documentation of [`convert_double_pointer_to_vec`].
+#[no_mangle]
+pub unsafe extern "C" fn command_info(
+    client_ptr: *const c_void,
+    callback_index: usize,
+    names: *const *const u8,
+    names_len: *const u32,
+    name_count: u32,
+) {
+    unsafe {
+        submit_command_metadata(
+            client_ptr,
+            callback_index,
+            "INFO",
+            names,
+            names_len,
+            name_count,
+            Box::new(convert_command_info),
+        )
+    };
+}
+
+/// Execute a `COMMAND DOCS` command for the given command names, or for all commands if none are given.
+/// The response is a map of command names to maps of their documentation, e.g. `summary`, `since`, `group` and `arguments`,
+/// regardless of the protocol. Unknown commands are omitted from the map.
+///
+/// # Safety
+/// * `client_ptr` must be a valid pointer created by [`create_client`](crate::create_client), before [`close_client`](crate::close_client) was called.
+/// * `names` must point to `name_count` consecutive buffer pointers and `names_len` must point to their lengths.
+///   See the safety documentation of [`convert_double_pointer_to_vec`].
+#[no_mangle]
+pub unsafe extern "C" fn command_docs(
+    client_ptr: *const c_void,
+    callback_index: usize,
+    names: *const *const u8,
+    names_len: *const u32,
+    name_count: u32,
+) {
+    unsafe {
+        submit_command_metadata(
+            client_ptr,
+            callback_index,
+            "DOCS",
+            names,
+            names_len,
+            name_count,
+            Box::new(convert_command_docs),
+        )
+    };
+}
+
+/// Submit a `CLUSTER` subcommand describing the topology to a random primary, and convert its response.
+///
+/// # Safety
+///
+/// * `client_ptr` must be a valid pointer created by [`create_client`](crate::create_client), before [`close_client`](crate::close_client) was called.
+unsafe fn submit_cluster_topology(
+    client_ptr: *const c_void,
+    callback_index: usize,
+    subcommand: &str,
+    convert: ResponseConverter,
+) {
+    let Some(client) = (unsafe { open_client_from_ptr(client_ptr, callback_index) }) else {
+        return;
+    };
+    let mut cmd = redis::cmd("CLUSTER");
+    cmd.arg(subcommand);
+    // Primaries are the authority on their slots, while a replica may lag behind a failover or a migration
+    let route = Some(RoutingInfo::SingleNode(
+        SingleNodeRoutingInfo::RandomPrimary,
+    ));
+    unsafe { execute_command(&client, callback_index, cmd, route, None, Some(convert)) };
+}
+
+/// Execute a `CLUSTER NODES` command on a primary. The response is an array with a map for every node of the cluster,
+/// with its `id`, `address`, `role`, `health`, `slots` and the slots `migrating` from or `importing` to it, see [`convert_cluster_nodes`].
+/// In standalone mode the request fails with the error of the server.
+///
+/// # Safety
+/// * `client_ptr` must be a valid pointer created by [`create_client`](crate::create_client), before [`close_client`](crate::close_client) was called.
+#[allow(rustdoc::private_intra_doc_links)]
+#[no_mangle]
+pub unsafe extern "C" fn cluster_nodes(client_ptr: *const c_void, callback_index: usize) {
+    unsafe {
+        submit_cluster_topology(
+            client_ptr,
+            callback_index,
+            "NODES",
+            Box::new(convert_cluster_nodes),
+        )
+    };
+}
+
+/// Execute a `CLUSTER SHARDS` command on a primary. The response is an array with a map for every shard, with its `slots`
+/// and its `nodes`, each with its `id`, `address`, `role` and `health`, see [`convert_cluster_shards`].
+/// In standalone mode, or with servers older than 7.0, the request fails with the error of the server.
+///
+/// # Safety
+/// * `client_ptr` must be a valid pointer created by [`create_client`](crate::create_client), before [`close_client`](crate::close_client) was called.
+#[allow(rustdoc::private_intra_doc_links)]
+#[no_mangle]
+pub unsafe extern "C" fn cluster_shards(client_ptr: *const c_void, callback_index: usize) {
+    unsafe {
+        submit_cluster_topology(
+            client_ptr,
+            callback_index,
+            "SHARDS",
+            Box::new(convert_cluster_shards),
+        )
+    };
+}
+
+/// Sort order of the `SORT` command.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SortOrder {
+    /// The server default, which is ascending.
+    None = 0,
+    Ascending = 1,
+    Descending = 2,
+}
+
+/// Execute an `LPOS` command, finding the indices of `element` in the list.
+/// * `rank` - the match to start from, e.g. `2` skips the first match and `-1` searches from the tail. `0` omits the option.
+/// * `has_count` and `count` - the maximal amount of matches to return, `0` returns all matches. Without it only the first match is returned.
+/// * `maxlen` - the maximal amount of elements to compare, `0` compares all elements.
+///
+/// The response is always an array of integer indices, so the caller doesn't depend on the reply shape of the options:
+/// without a count it holds the single match. If the element isn't found, the response is an empty array, never null.
+///
+/// # Safety
+/// * `client_ptr` must be a valid pointer created by [`create_client`](crate::create_client), before [`close_client`](crate::close_client) was called.
+/// * `key` and `element` must point to `key_len` and `element_len` consecutive bytes respectively. See the safety documentation of [`ptr_to_bytes`].
+/// * `route_info` could be `null`, but if it is not `null`, it must be a valid [`RouteInfo`] pointer.
+#[allow(clippy::too_many_arguments)]
+#[no_mangle]
+pub unsafe extern "C" fn lpos(
+    client_ptr: *const c_void,
+    callback_index: usize,
+    key: *const u8,
+    key_len: u32,
+    element: *const u8,
+    element_len: u32,
+    rank: i64,
+    has_count: bool,
+    count: u32,
+    maxlen: u32,
+    route_info: *const RouteInfo,
+) {
+    let mut cmd = redis::cmd("LPOS");
+    cmd.arg(unsafe { ptr_to_bytes(key, key_len) })
+        .arg(unsafe { ptr_to_bytes(element, element_len) });
+    if rank != 0 {
+        cmd.arg("RANK").arg(rank);
+    }
+    if has_count {
+        cmd.arg("COUNT").arg(count);
+    }
+    if maxlen > 0 {
+        cmd.arg("MAXLEN").arg(maxlen);
+    }
+    unsafe {
+        submit_with_conversion(
+            client_ptr,
+            callback_index,
+            cmd,
+            route_info,
+            Some(Box::new(normalize_lpos_response)),
+        )
+    };
+}
+
+/// Convert an `LPOS` response to an array of indices: a single index to an array of it, and null to an empty array.
+fn normalize_lpos_response(value: Value) -> RedisResult<Value> {
+    match value {
+        Value::Nil => Ok(Value::Array(vec![])),
+        Value::Int(index) => Ok(Value::Array(vec![Value::Int(index)])),
+        Value::Array(indices) => Ok(Value::Array(indices)),
+        _ => Err(RedisError::from((
+            ErrorKind::TypeError,
+            "Unexpected LPOS response",
+            format!("{value:?}"),
+        ))),
+    }
+}
+
+/// Options of the `SORT` and `SORT_RO` commands.
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct SortOptions {
+    /// Pattern of external keys to sort by (`BY`), ignored if empty.
+    pub by_pattern: *const u8,
+    pub by_pattern_len: u32,
+    /// Patterns of external keys to return instead of the sorted elements (`GET`), in the given order.
+    pub get_patterns: *const *const u8,
+    pub get_patterns_len: *const u32,
+    pub get_pattern_count: u32,
+    pub has_limit: bool,
+    pub limit_offset: i64,
+    pub limit_count: i64,
+    /// Sort lexicographically instead of numerically (`ALPHA`).
+    pub alpha: bool,
+    pub order: SortOrder,
+}
+
+/// Check that the external keys of the `BY` and `GET` patterns are in the slot of the sorted key.
+/// Every pattern must contain a hash tag of the key's slot, which doesn't contain the `*` placeholder.
+/// `nosort` and `#`, which don't refer to external keys, are allowed.
+fn validate_sort_patterns(key: &[u8], patterns: &[&[u8]]) -> RedisResult<()> {
+    let slot = redis::cluster_topology::get_slot(key);
+    for pattern in patterns {
+        if pattern.eq_ignore_ascii_case(b"nosort") || *pattern == b"#" {
+            continue;
+        }
+        let hash_tag = pattern.iter().position(|c| *c == b'{').and_then(|open| {
+            let close = pattern[open..].iter().position(|c| *c == b'}')?;
+            Some(&pattern[open + 1..open + close])
+        });
+        let is_valid = hash_tag.is_some_and(|tag| !tag.is_empty() && !tag.contains(&b'*'))
+            && redis::cluster_topology::get_slot(pattern) == slot;
+        if !is_valid {
+            return Err(RedisError::from((
+                ErrorKind::ClientError,
+                "SORT patterns must contain a hash tag of the sorted key's slot in cluster mode",
+                String::from_utf8_lossy(pattern).to_string(),
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// Execute a `SORT` command, or `SORT_RO` if `readonly` is set, which may be routed to a replica.
+/// The response is an array of the sorted elements, or of the values of the `GET` patterns for every element, in order.
+/// In cluster mode the `BY` and `GET` patterns must contain a hash tag of the sorted key's slot, otherwise the request
+/// fails with a client error, without being sent.
+///
+/// # Safety
+/// * `client_ptr` must be a valid pointer created by [`create_client`](crate::create_client), before [`close_client`](crate::close_client) was called.
+/// * `key` must point to `key_len` consecutive bytes. See the safety documentation of [`ptr_to_bytes`].
+/// * `options` could be `null`, but if it is not `null`, it must be a valid [`SortOptions`] pointer,
+///   whose `by_pattern` points to `by_pattern_len` consecutive bytes, `get_patterns` points to `get_pattern_count`
+///   consecutive buffer pointers and `get_patterns_len` points to their lengths.
+///   See the safety documentation of [`convert_double_pointer_to_vec`].
+/// * `route_info` could be `null`, but if it is not `null`, it must be a valid [`RouteInfo`] pointer.
+#[allow(clippy::too_many_arguments)]
+#[no_mangle]
+pub unsafe extern "C" fn sort(
+    client_ptr: *const c_void,
+    callback_index: usize,
+    key: *const u8,
+    key_len: u32,
+    options: *const SortOptions,
+    readonly: bool,
+    route_info: *const RouteInfo,
+) {
+    let Some(client) = (unsafe { open_client_from_ptr(client_ptr, callback_index) }) else {
+        return;
+    };
+    let key = unsafe { ptr_to_bytes(key, key_len) };
+    let mut cmd = redis::cmd(if readonly { "SORT_RO" } else { "SORT" });
+    cmd.arg(key);
+    let mut patterns = Vec::new();
+    if let Some(options) = unsafe { options.as_ref() } {
+        let by_pattern = unsafe { ptr_to_bytes(options.by_pattern, options.by_pattern_len) };
+        if !by_pattern.is_empty() {
+            cmd.arg("BY").arg(by_pattern);
+            patterns.push(by_pattern);
+        }
+        if options.has_limit {
+            cmd.arg("LIMIT")
+                .arg(options.limit_offset)
+                .arg(options.limit_count);
+        }
+        if options.get_pattern_count > 0 {
+            for get_pattern in unsafe {
+                convert_double_pointer_to_vec(
+                    options.get_patterns as *const *const c_void,
+                    options.get_pattern_count,
+                    options.get_patterns_len,
+                )
+            } {
+                cmd.arg("GET").arg(get_pattern);
+                patterns.push(get_pattern);
+            }
+        }
+        match options.order {
+            SortOrder::None => {}
+            SortOrder::Ascending => {
+                cmd.arg("ASC");
+            }
+            SortOrder::Descending => {
+                cmd.arg("DESC");
+            }
+        }
+        if options.alpha {
+            cmd.arg("ALPHA");
+        }
+    }
+    if client.core.standalone_address.is_none() {
+        if let Err(err) = validate_sort_patterns(key, &patterns) {
+            unsafe { client.core.report_error(callback_index, err) };
+            return;
+        }
+    }
+    let route = unsafe { create_route(route_info, &cmd) };
+    unsafe { execute_command(&client, callback_index, cmd, route, None, None) };
+}
+
+/// Unit of the indices of a bit range.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BitmapIndexType {
+    /// The server default, which is bytes.
+    Default = 0,
+    Byte = 1,
+    Bit = 2,
+}
+
+/// A range of a bitmap, given by inclusive `start` and `end` indices. Negative indices count from the end.
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct BitRange {
+    pub start: i64,
+    /// Whether `end` is given. Without it the range ends at the last byte, `BITCOUNT` always sends it.
+    pub has_end: bool,
+    pub end: i64,
+    /// Unit of the indices (`BYTE` or `BIT`), supported since Valkey 7.0.
+    pub index_type: BitmapIndexType,
+}
+
+/// Append a bit range to a `BITCOUNT` or `BITPOS` command.
+/// The index type requires an end index, so `-1` - the last byte or bit - is sent if none is given.
+fn add_bit_range(cmd: &mut Cmd, range: &BitRange, require_end: bool) {
+    cmd.arg(range.start);
+    let index_type = match range.index_type {
+        BitmapIndexType::Default => None,
+        BitmapIndexType::Byte => Some("BYTE"),
+        BitmapIndexType::Bit => Some("BIT"),
+    };
+    if range.has_end || require_end || index_type.is_some() {
+        cmd.arg(if range.has_end { range.end } else { -1 });
+    }
+    if let Some(index_type) = index_type {
+        cmd.arg(index_type);
+    }
+}
+
+/// Execute a `BITCOUNT` command, counting the set bits of the whole string or of `range` if given.
+/// The response is the number of set bits as an integer.
+///
+/// # Safety
+/// * `client_ptr` must be a valid pointer created by [`create_client`](crate::create_client), before [`close_client`](crate::close_client) was called.
+/// * `key` must point to `key_len` consecutive bytes. See the safety documentation of [`ptr_to_bytes`].
+/// * `range` could be `null`, but if it is not `null`, it must be a valid [`BitRange`] pointer.
+/// * `route_info` could be `null`, but if it is not `null`, it must be a valid [`RouteInfo`] pointer.
+#[no_mangle]
+pub unsafe extern "C" fn bitcount(
+    client_ptr: *const c_void,
+    callback_index: usize,
+    key: *const u8,
+    key_len: u32,
+    range: *const BitRange,
+    route_info: *const RouteInfo,
+) {
+    let mut cmd = redis::cmd("BITCOUNT");
+    cmd.arg(unsafe { ptr_to_bytes(key, key_len) });
+    if let Some(range) = unsafe { range.as_ref() } {
+        add_bit_range(&mut cmd, range, true);
+    }
+    unsafe { submit(client_ptr, callback_index, cmd, route_info) };
+}
+
+/// Execute a `BITPOS` command, finding the first bit set to `bit` in the whole string or in `range` if given.
+/// The response is the position of the bit as an integer, or `-1` if there is no such bit.
+///
+/// # Safety
+/// * `client_ptr` must be a valid pointer created by [`create_client`](crate::create_client), before [`close_client`](crate::close_client) was called.
+/// * `key` must point to `key_len` consecutive bytes. See the safety documentation of [`ptr_to_bytes`].
+/// * `range` could be `null`, but if it is not `null`, it must be a valid [`BitRange`] pointer.
+/// * `route_info` could be `null`, but if it is not `null`, it must be a valid [`RouteInfo`] pointer.
+#[allow(clippy::too_many_arguments)]
+#[no_mangle]
+pub unsafe extern "C" fn bitpos(
+    client_ptr: *const c_void,
+    callback_index: usize,
+    key: *const u8,
+    key_len: u32,
+    bit: bool,
+    range: *const BitRange,
+    route_info: *const RouteInfo,
+) {
+    let mut cmd = redis::cmd("BITPOS");
+    cmd.arg(unsafe { ptr_to_bytes(key, key_len) })
+        .arg(u8::from(bit));
+    if let Some(range) = unsafe { range.as_ref() } {
+        add_bit_range(&mut cmd, range, false);
+    }
+    unsafe { submit(client_ptr, callback_index, cmd, route_info) };
+}
+
+/// Type of a `BITFIELD` operation.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BitFieldOperationType {
+    Get = 0,
+    Set = 1,
+    IncrBy = 2,
+    /// Set the overflow behavior of the following `SET` and `INCRBY` operations.
+    Overflow = 3,
+}
+
+/// Overflow behavior of `BITFIELD` `SET` and `INCRBY` operations.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BitFieldOverflow {
+    Wrap = 0,
+    Sat = 1,
+    /// Don't change the field on overflow, the result of the operation is null.
+    Fail = 2,
+}
+
+/// An operation of the `BITFIELD` command.
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct BitFieldOperation {
+    pub operation: BitFieldOperationType,
+    /// Whether the field is a signed integer, up to 64 bits wide, or an unsigned integer, up to 63 bits wide.
+    pub signed: bool,
+    pub bits: u8,
+    pub offset: i64,
+    /// Multiply the offset by the field width (`#<offset>`), so fields can be addressed like array elements.
+    pub offset_multiplied: bool,
+    /// The value to set or the increment, ignored by `GET` and `OVERFLOW`.
+    pub value: i64,
+    /// The overflow behavior, used only by `OVERFLOW`.
+    pub overflow: BitFieldOverflow,
+}
+
+/// Execute a `BITFIELD` command, or `BITFIELD_RO` if `readonly` is set, which may be routed to a replica
+/// and allows only `GET` operations.
+/// The response is an array aligned to the operations - the integer result of every `GET`, `SET` and `INCRBY`
+/// operation, which is null if the operation failed due to `OVERFLOW FAIL`, and null for every `OVERFLOW` operation.
+/// Invalid field widths or operations fail with a client error, without sending the command.
+///
+/// # Safety
+/// * `client_ptr` must be a valid pointer created by [`create_client`](crate::create_client), before [`close_client`](crate::close_client) was called.
+/// * `key` must point to `key_len` consecutive bytes. See the safety documentation of [`ptr_to_bytes`].
+/// * `operations` must point to `operation_count` consecutive [`BitFieldOperation`]s.
+/// * `route_info` could be `null`, but if it is not `null`, it must be a valid [`RouteInfo`] pointer.
+#[allow(clippy::too_many_arguments)]
+#[no_mangle]
+pub unsafe extern "C" fn bitfield(
+    client_ptr: *const c_void,
+    callback_index: usize,
+    key: *const u8,
+    key_len: u32,
+    operations: *const BitFieldOperation,
+    operation_count: u32,
+    readonly: bool,
+    route_info: *const RouteInfo,
+) {
+    let Some(client) = (unsafe { open_client_from_ptr(client_ptr, callback_index) }) else {
+        return;
+    };
+    let operations = if operation_count == 0 {
+        &[]
+    } else {
+        unsafe { from_raw_parts(operations, operation_count as usize) }
+    };
+    let mut cmd = redis::cmd(if readonly { "BITFIELD_RO" } else { "BITFIELD" });
+    cmd.arg(unsafe { ptr_to_bytes(key, key_len) });
+    let mut overflow_positions = Vec::new();
+    for (position, operation) in operations.iter().enumerate() {
+        if let Err(err) = add_bitfield_operation(&mut cmd, operation, readonly) {
+            unsafe { client.core.report_error(callback_index, err) };
+            return;
+        }
+        if operation.operation == BitFieldOperationType::Overflow {
+            overflow_positions.push(position);
+        }
+    }
+    let route = unsafe { create_route(route_info, &cmd) };
+    let convert: ResponseConverter =
+        Box::new(move |value| align_bitfield_response(value, &overflow_positions));
+    unsafe { execute_command(&client, callback_index, cmd, route, None, Some(convert)) };
+}
+
+/// Execute a `BITFIELD_RO` command, which only reads fields by `GET` operations, so it can be served by a replica:
+/// it is routed to a replica when the client reads from replicas, or by `route_info`, like other read-only commands.
+/// The response is the array of the integer results of the operations. Any operation other than `GET` fails with
+/// a client error, without sending the command. See [`bitfield`] for the operations.
+///
+/// # Safety
+/// * `client_ptr` must be a valid pointer created by [`create_client`](crate::create_client), before [`close_client`](crate::close_client) was called.
+/// * `key` must point to `key_len` consecutive bytes. See the safety documentation of [`ptr_to_bytes`].
+/// * `get_operations` must point to `operation_count` consecutive [`BitFieldOperation`]s.
+/// * `route_info` could be `null`, but if it is not `null`, it must be a valid [`RouteInfo`] pointer.
+#[no_mangle]
+pub unsafe extern "C" fn bitfield_ro(
+    client_ptr: *const c_void,
+    callback_index: usize,
+    key: *const u8,
+    key_len: u32,
+    get_operations: *const BitFieldOperation,
+    operation_count: u32,
+    route_info: *const RouteInfo,
+) {
+    unsafe {
+        bitfield(
+            client_ptr,
+            callback_index,
+            key,
+            key_len,
+            get_operations,
+            operation_count,
+            true,
+            route_info,
+        )
+    };
+}
+
+/// Append a `BITFIELD` operation to the command.
+fn add_bitfield_operation(
+    cmd: &mut Cmd,
+    operation: &BitFieldOperation,
+    readonly: bool,
+) -> RedisResult<()> {
+    if readonly && operation.operation != BitFieldOperationType::Get {
+        return Err(RedisError::from((
+            ErrorKind::ClientError,
+            "BITFIELD_RO supports only GET operations",
+            format!(
+                "{:?} operations can't be sent by BITFIELD_RO",
+                operation.operation
+            ),
+        )));
+    }
+    let name = match operation.operation {
+        BitFieldOperationType::Get => "GET",
+        BitFieldOperationType::Set => "SET",
+        BitFieldOperationType::IncrBy => "INCRBY",
+        BitFieldOperationType::Overflow => {
+            cmd.arg("OVERFLOW").arg(match operation.overflow {
+                BitFieldOverflow::Wrap => "WRAP",
+                BitFieldOverflow::Sat => "SAT",
+                BitFieldOverflow::Fail => "FAIL",
+            });
+            return Ok(());
+        }
+    };
+    cmd.arg(name)
+        .arg(bitfield_encoding(operation.signed, operation.bits)?);
+    if operation.offset_multiplied {
+        cmd.arg(format!("#{}", operation.offset));
+    } else {
+        cmd.arg(operation.offset);
+    }
+    if operation.operation != BitFieldOperationType::Get {
+        cmd.arg(operation.value);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        close_client,
+        test_utilities::{
+            cluster_slots, create_test_client, has_result, next_callback_index, unknown_command,
+            wait_for_result, MockServer,
+        },
+    };
+    use redis::cluster_routing::{is_readonly, AggregateOp, ResponsePolicy, SlotAddr};
+    use std::{
+        collections::{HashMap, HashSet},
+        sync::{Arc, Mutex},
+        time::{Duration, Instant},
+    };
+
+    fn args(cmd: &Cmd) -> Vec<String> {
+        cmd.args_iter()
+            .map(|arg| match arg {
+                redis::Arg::Simple(arg) => String::from_utf8_lossy(arg).to_string(),
+                redis::Arg::Cursor => "CURSOR".to_string(),
+            })
+            .collect()
+    }
+
+    fn zadd_flags(condition: ZAddCondition, comparison: ZAddComparison) -> ZAddFlags {
+        ZAddFlags {
+            condition,
+            comparison,
+            changed: false,
+            increment: false,
+        }
+    }
+
+    #[test]
+    fn test_zadd_arguments() {
+        let mut flags = zadd_flags(ZAddCondition::OnlyIfExists, ZAddComparison::GreaterThan);
+        flags.changed = true;
+        let cmd = zadd_command(b"key", vec![b"one", b"two"], &[1.0, 2.5], &flags).unwrap();
+        assert_eq!(
+            args(&cmd),
+            ["ZADD", "key", "XX", "GT", "CH", "1.0", "one", "2.5", "two"]
+        );
+        flags.increment = true;
+        let cmd = zadd_command(b"key", vec![b"one"], &[-1.5], &flags).unwrap();
+        assert_eq!(
+            args(&cmd),
+            ["ZADD", "key", "XX", "GT", "CH", "INCR", "-1.5", "one"]
+        );
+    }
+
+    #[test]
+    fn test_zadd_rejects_invalid_flags() {
+        let flags = zadd_flags(ZAddCondition::OnlyIfNotExists, ZAddComparison::LessThan);
+        let err = zadd_command(b"key", vec![b"one"], &[1.0], &flags).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::ClientError);
+        let mut flags = zadd_flags(ZAddCondition::None, ZAddComparison::None);
+        flags.increment = true;
+        let err = zadd_command(b"key", vec![b"one", b"two"], &[1.0, 2.0], &flags).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::ClientError);
+        assert!(zadd_command(b"key", vec![], &[], &flags).is_err());
+    }
+
+    fn zrange_options(by: ZRangeBy) -> ZRangeOptions {
+        ZRangeOptions {
+            by,
+            reverse: false,
+            has_limit: false,
+            limit_offset: 0,
+            limit_count: 0,
+            with_scores: false,
+        }
+    }
+
+    #[test]
+    fn test_zrange_arguments() {
+        let mut options = zrange_options(ZRangeBy::Score);
+        options.reverse = true;
+        options.has_limit = true;
+        options.limit_offset = 2;
+        options.limit_count = -1;
+        options.with_scores = true;
+        let cmd = zrange_command(b"key", b"+inf", b"(1.5", &options).unwrap();
+        assert_eq!(
+            args(&cmd),
+            [
+                "ZRANGE",
+                "key",
+                "+inf",
+                "(1.5",
+                "BYSCORE",
+                "REV",
+                "LIMIT",
+                "2",
+                "-1",
+                "WITHSCORES"
+            ]
+        );
+        let cmd = zrange_command(b"key", b"[a", b"(c", &zrange_options(ZRangeBy::Lex)).unwrap();
+        assert_eq!(args(&cmd), ["ZRANGE", "key", "[a", "(c", "BYLEX"]);
+        let cmd = zrange_command(b"key", b"0", b"-1", &zrange_options(ZRangeBy::Index)).unwrap();
+        assert_eq!(args(&cmd), ["ZRANGE", "key", "0", "-1"]);
+    }
+
+    #[test]
+    fn test_zrange_rejects_invalid_options() {
+        let mut options = zrange_options(ZRangeBy::Lex);
+        options.with_scores = true;
+        let err = zrange_command(b"key", b"-", b"+", &options).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::ClientError);
+        let mut options = zrange_options(ZRangeBy::Index);
+        options.has_limit = true;
+        let err = zrange_command(b"key", b"0", b"-1", &options).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::ClientError);
+    }
+
+    #[test]
+    fn test_convert_zrange_with_scores_keeps_order() {
+        let value = Value::Map(vec![
+            (bulk("two"), Value::Double(2.0)),
+            (bulk("one"), Value::Double(f64::NEG_INFINITY)),
+        ]);
+        assert_eq!(
+            convert_zrange_with_scores(value).unwrap(),
+            Value::Array(vec![
+                Value::Map(vec![
+                    (bulk("member"), bulk("two")),
+                    (bulk("score"), Value::Double(2.0))
+                ]),
+                Value::Map(vec![
+                    (bulk("member"), bulk("one")),
+                    (bulk("score"), Value::Double(f64::NEG_INFINITY))
+                ]),
+            ])
+        );
+    }
+
+    fn set_options() -> SetOptions {
+        SetOptions {
+            only_if_not_exists: false,
+            only_if_exists: false,
+            expiry_type: SetExpiry::None,
+            expiry: 0,
+            keep_ttl: false,
+            get: false,
+        }
+    }
+
+    #[test]
+    fn test_set_arguments() {
+        let cmd = set_command(b"key", b"\0value", &set_options()).unwrap();
+        assert_eq!(args(&cmd), ["SET", "key", "\0value"]);
+        let mut options = set_options();
+        options.only_if_exists = true;
+        options.expiry_type = SetExpiry::UnixMilliseconds;
+        options.expiry = 1_700_000_000_000;
+        options.get = true;
+        let cmd = set_command(b"key", b"value", &options).unwrap();
+        assert_eq!(
+            args(&cmd),
+            ["SET", "key", "value", "XX", "PXAT", "1700000000000", "GET"]
+        );
+        let mut options = set_options();
+        options.only_if_not_exists = true;
+        options.keep_ttl = true;
+        let cmd = set_command(b"key", b"value", &options).unwrap();
+        assert_eq!(args(&cmd), ["SET", "key", "value", "NX", "KEEPTTL"]);
+    }
+
+    #[test]
+    fn test_set_rejects_invalid_options() {
+        let mut options = set_options();
+        options.only_if_not_exists = true;
+        options.only_if_exists = true;
+        let err = set_command(b"key", b"value", &options).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::ClientError);
+        let mut options = set_options();
+        options.keep_ttl = true;
+        options.expiry_type = SetExpiry::Seconds;
+        options.expiry = 10;
+        let err = set_command(b"key", b"value", &options).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::ClientError);
+        options.keep_ttl = false;
+        options.expiry = 0;
+        assert!(set_command(b"key", b"value", &options).is_err());
+    }
+
+    #[test]
+    fn test_getset_arguments() {
+        assert_eq!(
+            args(&getset_command(b"key", b"value", true)),
+            ["SET", "key", "value", "GET"]
+        );
+        assert_eq!(
+            args(&getset_command(b"key", b"value", false)),
+            ["GETSET", "key", "value"]
+        );
+    }
+
+    #[test]
+    fn test_convert_client_address() {
+        let info = "id=7 addr=10.0.0.5:53102 laddr=10.0.0.1:6379 fd=8 name= age=3 db=0\n";
+        assert_eq!(
+            convert_client_address(bulk(info)).unwrap(),
+            Value::Map(vec![
+                (bulk("id"), Value::Int(7)),
+                (bulk("addr"), bulk("10.0.0.5:53102")),
+                (bulk("laddr"), bulk("10.0.0.1:6379")),
+                (bulk("name"), Value::Nil),
+            ])
+        );
+        let nodes = convert_client_address(Value::Map(vec![(
+            bulk("10.0.0.1:6379"),
+            Value::VerbatimString {
+                format: redis::VerbatimFormat::Text,
+                text: "id=9 addr=10.0.0.5:1 laddr=10.0.0.1:6379 name=worker".to_string(),
+            },
+        )]))
+        .unwrap();
+        let Value::Map(nodes) = nodes else {
+            panic!("Expected a map of nodes");
+        };
+        assert_eq!(
+            nodes[0].1,
+            Value::Map(vec![
+                (bulk("id"), Value::Int(9)),
+                (bulk("addr"), bulk("10.0.0.5:1")),
+                (bulk("laddr"), bulk("10.0.0.1:6379")),
+                (bulk("name"), bulk("worker")),
+            ])
+        );
+        assert!(convert_client_address(bulk("unexpected")).is_err());
+    }
+
+    #[test]
+    fn test_copy_arguments() {
+        let cmd = copy_command(b"{user}:1", b"{user}:2", false, 0, false);
+        assert_eq!(args(&cmd), ["COPY", "{user}:1", "{user}:2"]);
+        let cmd = copy_command(b"{user}:1", b"{user}:2", true, 0, true);
+        assert_eq!(
+            args(&cmd),
+            ["COPY", "{user}:1", "{user}:2", "DB", "0", "REPLACE"]
+        );
+    }
+
+    #[test]
+    fn test_dbsize_counts_are_summed() {
+        let nodes = vec![
+            (bulk("127.0.0.1:7000"), Value::Int(3)),
+            (bulk("127.0.0.1:7001"), Value::Int(5)),
+        ];
+        assert_eq!(
+            convert_dbsize(Value::Map(nodes.clone())).unwrap(),
+            Value::Map(vec![
+                (bulk("nodes"), Value::Map(nodes)),
+                (bulk("total"), Value::Int(8)),
+            ])
+        );
+        assert_eq!(convert_dbsize(Value::Int(3)).unwrap(), Value::Int(3));
+        assert!(convert_dbsize(Value::Map(vec![(bulk("127.0.0.1:7000"), Value::Nil)])).is_err());
+    }
+
+    #[test]
+    fn test_exists_is_split_by_slot() {
+        let mut cmd = redis::cmd("EXISTS");
+        cmd.arg("{a}1").arg("{b}1").arg("{a}2").arg("{c}1");
+        let Some(RoutingInfo::MultiNode((
+            MultipleNodeRoutingInfo::MultiSlot((routes, _)),
+            Some(ResponsePolicy::Aggregate(AggregateOp::Sum)),
+        ))) = RoutingInfo::for_routable(&cmd)
+        else {
+            panic!("Expected EXISTS to be split by slot");
+        };
+        let mut slots: Vec<(u16, Vec<usize>)> = routes
+            .into_iter()
+            .map(|(route, indices)| (route.slot(), indices))
+            .collect();
+        slots.sort();
+        let mut expected = vec![
+            (redis::cluster_topology::get_slot(b"a"), vec![0, 2]),
+            (redis::cluster_topology::get_slot(b"b"), vec![1]),
+            (redis::cluster_topology::get_slot(b"c"), vec![3]),
+        ];
+        expected.sort();
+        assert_eq!(slots, expected);
+    }
+
+    #[test]
+    fn test_convert_float_reply() {
+        let reply = |raw: &str| convert_float_reply(bulk(raw)).unwrap();
+        assert_eq!(
+            reply("3000"),
+            Value::Map(vec![
+                (bulk("value"), Value::Double(3000.0)),
+                (bulk("raw"), bulk("3000")),
+            ])
+        );
+        let long = "0.1000000000000000055511151231257827021181583404541015625";
+        assert_eq!(
+            reply(long),
+            Value::Map(vec![
+                (bulk("value"), Value::Double(0.1)),
+                (bulk("raw"), bulk(long)),
+            ])
+        );
+        assert!(convert_float_reply(bulk("3,5")).is_err());
+        assert!(convert_float_reply(Value::Nil).is_err());
+    }
+
+    #[test]
+    fn test_mget_is_split_by_slot() {
+        let mut cmd = redis::cmd("MGET");
+        cmd.arg("{a}1").arg("{b}1").arg("{a}2");
+        let Some(RoutingInfo::MultiNode((
+            MultipleNodeRoutingInfo::MultiSlot((routes, _)),
+            Some(ResponsePolicy::CombineArrays),
+        ))) = RoutingInfo::for_routable(&cmd)
+        else {
+            panic!("Expected MGET to be split by slot");
+        };
+        // The indices of the keys of every slot, by which the values are reassembled in the order of the keys
+        let mut slots: Vec<(u16, Vec<usize>)> = routes
+            .into_iter()
+            .map(|(route, indices)| (route.slot(), indices))
+            .collect();
+        slots.sort();
+        let mut expected = vec![
+            (redis::cluster_topology::get_slot(b"a"), vec![0, 2]),
+            (redis::cluster_topology::get_slot(b"b"), vec![1]),
+        ];
+        expected.sort();
+        assert_eq!(slots, expected);
+    }
+
+    #[test]
+    fn test_setrange_keeps_binary_value() {
+        let value = [0x00, 0xff, 0x00, b'\r', b'\n', 0xc3];
+        let cmd = setrange_command(b"blob\x00key", 3, &value);
+        let raw_args: Vec<&[u8]> = cmd
+            .args_iter()
+            .map(|arg| match arg {
+                redis::Arg::Simple(arg) => arg,
+                redis::Arg::Cursor => b"CURSOR",
+            })
+            .collect();
+        assert_eq!(
+            raw_args,
+            [&b"SETRANGE"[..], b"blob\x00key", b"3", &value[..]]
+        );
+    }
+
+    #[test]
+    fn test_append_keeps_binary_chunks() {
+        let chunks: [&[u8]; 3] = [b"\x00\x00", b"\xffmid\x00dle\r\n", b"\xc3\x00"];
+        let mut appended = Vec::new();
+        for chunk in chunks {
+            let cmd = append_command(b"blob\x00key", chunk);
+            let raw_args: Vec<&[u8]> = cmd
+                .args_iter()
+                .map(|arg| match arg {
+                    redis::Arg::Simple(arg) => arg,
+                    redis::Arg::Cursor => b"CURSOR",
+                })
+                .collect();
+            assert_eq!(raw_args, [&b"APPEND"[..], b"blob\x00key", chunk]);
+            appended.extend_from_slice(raw_args[2]);
+        }
+        assert_eq!(appended, chunks.concat());
+        assert_eq!(
+            RoutingInfo::for_routable(&append_command(b"blob\x00key", b"\x00")),
+            RoutingInfo::for_routable(&setrange_command(b"blob\x00key", 0, b"\x00"))
+        );
+    }
+
+    #[test]
+    fn test_bit_range_arguments() {
+        let mut range = BitRange {
+            start: 1,
+            has_end: false,
+            end: 0,
+            index_type: BitmapIndexType::Default,
+        };
+        let mut cmd = redis::cmd("BITPOS");
+        add_bit_range(&mut cmd, &range, false);
+        assert_eq!(args(&cmd), ["BITPOS", "1"]);
+        let mut cmd = redis::cmd("BITCOUNT");
+        add_bit_range(&mut cmd, &range, true);
+        assert_eq!(args(&cmd), ["BITCOUNT", "1", "-1"]);
+        range.index_type = BitmapIndexType::Bit;
+        let mut cmd = redis::cmd("BITPOS");
+        add_bit_range(&mut cmd, &range, false);
+        assert_eq!(args(&cmd), ["BITPOS", "1", "-1", "BIT"]);
+        range.has_end = true;
+        range.end = 5;
+        range.index_type = BitmapIndexType::Byte;
+        let mut cmd = redis::cmd("BITCOUNT");
+        add_bit_range(&mut cmd, &range, true);
+        assert_eq!(args(&cmd), ["BITCOUNT", "1", "5", "BYTE"]);
+    }
+
+    #[test]
+    fn test_unsupported_expire_condition() {
+        let err =
+            redis::parse_redis_value(b"-ERR wrong number of arguments for 'expire' command\r\n")
+                .and_then(|value| value.extract_error())
+                .unwrap_err();
+        let err = unsupported_expire_condition("EXPIRE", err);
+        assert_eq!(error_type(&err), RequestErrorType::UnsupportedByServer);
+        let err = RedisError::from((
+            ErrorKind::ResponseError,
+            "An error was signalled by the server",
+            "no such key".to_string(),
+        ));
+        assert_eq!(
+            error_type(&unsupported_expire_condition("EXPIRE", err)),
+            RequestErrorType::Unspecified
+        );
+    }
+
+    #[test]
+    fn test_convert_key_type() {
+        let key_type =
+            |name: &str| convert_key_type(Value::SimpleString(name.to_string())).unwrap();
+        assert_eq!(key_type("none"), Value::Int(KeyType::None as i64));
+        assert_eq!(key_type("string"), Value::Int(KeyType::String as i64));
+        assert_eq!(key_type("list"), Value::Int(KeyType::List as i64));
+        assert_eq!(key_type("set"), Value::Int(KeyType::Set as i64));
+        assert_eq!(key_type("zset"), Value::Int(KeyType::ZSet as i64));
+        assert_eq!(key_type("hash"), Value::Int(KeyType::Hash as i64));
+        assert_eq!(key_type("stream"), Value::Int(KeyType::Stream as i64));
+        assert_eq!(key_type("ReJSON-RL"), Value::Int(KeyType::Other as i64));
+        assert!(convert_key_type(Value::Nil).is_err());
+    }
+
+    #[test]
+    fn test_convert_key_expiry() {
+        let expiry = |state: KeyExpiry, time: Value| {
+            Value::Map(vec![
+                (bulk("state"), Value::Int(state as i64)),
+                (bulk("time"), time),
+            ])
+        };
+        assert_eq!(
+            convert_key_expiry(Value::Int(-2)).unwrap(),
+            expiry(KeyExpiry::Missing, Value::Nil)
+        );
+        assert_eq!(
+            convert_key_expiry(Value::Int(-1)).unwrap(),
+            expiry(KeyExpiry::Persistent, Value::Nil)
+        );
+        assert_eq!(
+            convert_key_expiry(Value::Int(0)).unwrap(),
+            expiry(KeyExpiry::Expires, Value::Int(0))
+        );
+        assert_eq!(
+            convert_key_expiry(Value::Int(1700000000)).unwrap(),
+            expiry(KeyExpiry::Expires, Value::Int(1700000000))
+        );
+        assert!(convert_key_expiry(Value::Int(-3)).is_err());
+        assert!(convert_key_expiry(Value::Nil).is_err());
+    }
+
+    #[test]
+    fn test_normalize_lpos_response() {
+        assert_eq!(
+            normalize_lpos_response(Value::Nil).unwrap(),
+            Value::Array(vec![])
+        );
+        assert_eq!(
+            normalize_lpos_response(Value::Int(3)).unwrap(),
+            Value::Array(vec![Value::Int(3)])
+        );
+        let indices = Value::Array(vec![Value::Int(1), Value::Int(4)]);
+        assert_eq!(normalize_lpos_response(indices.clone()).unwrap(), indices);
+        assert!(normalize_lpos_response(Value::Okay).is_err());
+    }
+
+    #[test]
+    fn test_bitfield_operation_arguments() {
+        let operation = BitFieldOperation {
+            operation: BitFieldOperationType::IncrBy,
+            signed: false,
+            bits: 63,
+            offset: 2,
+            offset_multiplied: true,
+            value: i64::MAX,
+            overflow: BitFieldOverflow::Wrap,
+        };
+        let mut cmd = redis::cmd("BITFIELD");
+        add_bitfield_operation(&mut cmd, &operation, false).unwrap();
+        add_bitfield_operation(
+            &mut cmd,
+            &BitFieldOperation {
+                operation: BitFieldOperationType::Overflow,
+                overflow: BitFieldOverflow::Fail,
+                ..operation
+            },
+            false,
+        )
+        .unwrap();
+        assert_eq!(
+            args(&cmd),
+            [
+                "BITFIELD",
+                "INCRBY",
+                "u63",
+                "#2",
+                "9223372036854775807",
+                "OVERFLOW",
+                "FAIL"
+            ]
+        );
+        let err = add_bitfield_operation(&mut cmd, &operation, true).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::ClientError);
+        assert!(err.to_string().contains("IncrBy"));
+        assert!(add_bitfield_operation(
+            &mut cmd,
+            &BitFieldOperation {
+                bits: 64,
+                ..operation
+            },
+            false
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_bitfield_ro_is_routed_to_replicas() {
+        let mut cmd = redis::cmd("BITFIELD_RO");
+        cmd.arg("counters");
+        add_bitfield_operation(
+            &mut cmd,
+            &BitFieldOperation {
+                operation: BitFieldOperationType::Get,
+                signed: true,
+                bits: 8,
+                offset: 0,
+                offset_multiplied: false,
+                value: 0,
+                overflow: BitFieldOverflow::Wrap,
+            },
+            true,
+        )
+        .unwrap();
+        assert_eq!(args(&cmd), ["BITFIELD_RO", "counters", "GET", "i8", "0"]);
+        assert!(is_readonly(&cmd));
+        let Some(RoutingInfo::SingleNode(SingleNodeRoutingInfo::SpecificNode(route))) =
+            RoutingInfo::for_routable(&cmd)
+        else {
+            panic!("BITFIELD_RO isn't routed by its key");
+        };
+        assert_eq!(route.slot_addr(), SlotAddr::ReplicaOptional);
+    }
+
+    #[test]
+    fn test_take_command() {
+        let take = |mode, expiry_type, expiry| take_command(b"lock", mode, expiry_type, expiry);
+        assert_eq!(
+            args(&take(TakeMode::Delete, SetExpiry::None, 0).unwrap()),
+            ["GETDEL", "lock"]
+        );
+        assert_eq!(
+            args(&take(TakeMode::Extend, SetExpiry::Milliseconds, 500).unwrap()),
+            ["GETEX", "lock", "PX", "500"]
+        );
+        assert_eq!(
+            take(TakeMode::Extend, SetExpiry::None, 500)
+                .unwrap_err()
+                .kind(),
+            ErrorKind::ClientError
+        );
+        assert_eq!(
+            take(TakeMode::Extend, SetExpiry::Seconds, 0)
+                .unwrap_err()
+                .kind(),
+            ErrorKind::ClientError
+        );
+    }
+
+    #[test]
+    fn test_sintercard_command() {
+        assert_eq!(
+            args(&sintercard_command(&[&b"set1"[..], b"set2"], 10)),
+            ["SINTERCARD", "2", "set1", "set2", "LIMIT", "10"]
+        );
+        assert_eq!(
+            args(&sintercard_command(&[&b"set1"[..], b"set2"], 0)),
+            ["SINTERCARD", "2", "set1", "set2", "LIMIT", "0"]
+        );
+    }
+
+    #[test]
+    fn test_sort_patterns_in_key_slot() {
+        assert!(validate_sort_patterns(
+            b"{user}:ids",
+            &[
+                b"{user}:weight_*".as_slice(),
+                b"{user}:name_*->first".as_slice(),
+                b"#".as_slice(),
+                b"nosort".as_slice()
+            ]
+        )
+        .is_ok());
+    }
+
+    #[test]
+    fn test_sort_patterns_without_hash_tag() {
+        assert!(validate_sort_patterns(b"{user}:ids", &[b"weight_*".as_slice()]).is_err());
+        assert!(validate_sort_patterns(b"{user}:ids", &[b"{*}:weight".as_slice()]).is_err());
+    }
+
+    #[test]
+    fn test_sort_patterns_in_other_slot() {
+        assert!(validate_sort_patterns(b"{user}:ids", &[b"{order}:weight_*".as_slice()]).is_err());
+    }
+
+    /// Set `key` by [`set`], and return the callback index of the request.
+    fn submit_set(client: *const c_void, key: &str) -> usize {
+        let callback_index = next_callback_index();
+        unsafe {
+            set(
+                client,
+                callback_index,
+                key.as_ptr(),
+                key.len() as u32,
+                b"value".as_ptr(),
+                5,
+                std::ptr::null(),
+                std::ptr::null(),
+            )
+        };
+        callback_index
+    }
+
+    /// The response of a command sent to all primaries, by node address.
+    fn node_results(callback_index: usize) -> HashMap<String, Value> {
+        let Ok(Value::Map(results)) = wait_for_result(callback_index) else {
+            panic!("Expected a map of node addresses to results");
+        };
+        results
+            .into_iter()
+            .map(|(node, result)| match node {
+                Value::BulkString(node) => (String::from_utf8(node).unwrap(), result),
+                node => panic!("Unexpected node address {node:?}"),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_flush_empties_the_keyspace_of_every_primary() {
+        let servers: Vec<_> = (0..3).map(|_| MockServer::bind()).collect();
+        let ports: Vec<u16> = servers.iter().map(MockServer::port).collect();
+        let keyspaces: Vec<_> = ports
+            .iter()
+            .map(|_| Arc::new(Mutex::new(HashSet::new())))
+            .collect();
+        for (server, keyspace) in servers.into_iter().zip(keyspaces.clone()) {
+            let slots = cluster_slots(&ports);
+            server.serve(Arc::new(move |args: &[Vec<u8>]| {
+                match args[0].to_ascii_uppercase().as_slice() {
+                    b"CLUSTER" => slots.clone(),
+                    b"SET" => {
+                        keyspace.lock().unwrap().insert(args[1].clone());
+                        Value::Okay
+                    }
+                    b"FLUSHALL" | b"FLUSHDB" if matches!(&args[1][..], b"SYNC" | b"ASYNC") => {
+                        keyspace.lock().unwrap().clear();
+                        Value::Okay
+                    }
+                    _ => unknown_command(args),
+                }
+            }));
+        }
+        let client = create_test_client(&ports, true, |_| {});
+        let nodes: HashSet<String> = ports
+            .iter()
+            .map(|port| format!("127.0.0.1:{port}"))
+            .collect();
+
+        let flushes: [(
+            unsafe extern "C" fn(*const c_void, usize, FlushMode),
+            FlushMode,
+        ); 2] = [(flush_all, FlushMode::Sync), (flush_db, FlushMode::Async)];
+        for (flush, mode) in flushes {
+            let sets: Vec<usize> = (0..30)
+                .map(|index| submit_set(client, &format!("key{index}")))
+                .collect();
+            for set in sets {
+                assert_eq!(wait_for_result(set), Ok(Value::Okay));
+            }
+            assert!(keyspaces
+                .iter()
+                .all(|keyspace| !keyspace.lock().unwrap().is_empty()));
+
+            let callback_index = next_callback_index();
+            unsafe { flush(client, callback_index, mode) };
+            let results = node_results(callback_index);
+            assert_eq!(results.keys().cloned().collect::<HashSet<_>>(), nodes);
+            assert!(results.values().all(|result| *result == Value::Okay));
+            assert!(keyspaces
+                .iter()
+                .all(|keyspace| keyspace.lock().unwrap().is_empty()));
+        }
+        close_client(client);
+    }
+
+    #[test]
+    fn test_writes_block_while_the_primaries_are_paused() {
+        let servers: Vec<_> = (0..2).map(|_| MockServer::bind()).collect();
+        let ports: Vec<u16> = servers.iter().map(MockServer::port).collect();
+        for (index, server) in servers.into_iter().enumerate() {
+            let slots = cluster_slots(&ports);
+            let paused_until = Mutex::new(None::<Instant>);
+            server.serve(Arc::new(move |args: &[Vec<u8>]| {
+                let subcommand = args.get(1).map(|arg| arg.to_ascii_uppercase());
+                match (
+                    args[0].to_ascii_uppercase().as_slice(),
+                    subcommand.as_deref(),
+                ) {
+                    (b"CLUSTER", _) => slots.clone(),
+                    // The first node fails to apply the pause
+                    (b"CLIENT", Some(b"PAUSE")) if index == 0 => {
+                        redis::parse_redis_value(b"-ERR pause failed\r\n").unwrap()
+                    }
+                    (b"CLIENT", Some(b"PAUSE")) if &args[3][..] == b"WRITE" => {
+                        let timeout = String::from_utf8_lossy(&args[2]).parse().unwrap();
+                        *paused_until.lock().unwrap() =
+                            Some(Instant::now() + Duration::from_millis(timeout));
+                        Value::Okay
+                    }
+                    (b"CLIENT", Some(b"UNPAUSE")) => {
+                        *paused_until.lock().unwrap() = None;
+                        Value::Okay
+                    }
+                    // A write blocks its connection until the pause ends
+                    (b"SET", _) => {
+                        while paused_until
+                            .lock()
+                            .unwrap()
+                            .is_some_and(|until| Instant::now() < until)
+                        {
+                            std::thread::sleep(Duration::from_millis(5));
+                        }
+                        Value::Okay
+                    }
+                    _ => unknown_command(args),
+                }
+            }));
+        }
+        // The connections of the client which writes are blocked, so another client pauses and unpauses
+        let admin = create_test_client(&ports, true, |_| {});
+        let client = create_test_client(&ports, true, |config| {
+            config.has_request_timeout = true;
+            config.request_timeout = 60_000;
+        });
+        let first_node = format!("127.0.0.1:{}", ports[0]);
+        let second_node = format!("127.0.0.1:{}", ports[1]);
+
+        let pause = |timeout_ms| {
+            let callback_index = next_callback_index();
+            unsafe { client_pause(admin, callback_index, timeout_ms, ClientPauseMode::Write) };
+            let results = node_results(callback_index);
+            assert_eq!(results.len(), 2);
+            assert!(matches!(
+                &results[&first_node],
+                Value::BulkString(message) if String::from_utf8_lossy(message).contains("pause failed")
+            ));
+            assert_eq!(results[&second_node], Value::Okay);
+        };
+
+        // "foo" is served by the second node, the write is done once the primaries are unpaused
+        pause(60_000);
+        let set = submit_set(client, "foo");
+        std::thread::sleep(Duration::from_millis(200));
+        assert!(!has_result(set));
+        let unpause = next_callback_index();
+        unsafe { client_unpause(admin, unpause) };
+        let results = node_results(unpause);
+        assert_eq!(results.len(), 2);
+        assert!(results.values().all(|result| *result == Value::Okay));
+        assert_eq!(wait_for_result(set), Ok(Value::Okay));
+
+        // Or once the pause elapsed
+        pause(500);
+        let started = Instant::now();
+        let set = submit_set(client, "foo");
+        assert_eq!(wait_for_result(set), Ok(Value::Okay));
+        assert!(started.elapsed() >= Duration::from_millis(300));
+
+        close_client(client);
+        close_client(admin);
+    }
+}
@@ -0,0 +1,64 @@
+// Copyright Valkey GLIDE Project Contributors - SPDX Identifier: Apache-2.0
+
+use redis::{ErrorKind, RedisError, RedisResult, Value};
+
+/// Prefix of values compressed by the client, used to tell them apart from uncompressed values.
+const COMPRESSED_PREFIX: &[u8] = b"GLIDE-LZ4:";
+
+/// Compress the value with LZ4 if compression is enabled and the value is at least `threshold` bytes long.
+/// `0` threshold means that compression is disabled.
+pub(crate) fn compress(value: &[u8], threshold: u32) -> Vec<u8> {
+    if threshold == 0 || value.len() < threshold as usize {
+        return value.to_vec();
+    }
+    let mut compressed = COMPRESSED_PREFIX.to_vec();
+    compressed.extend(lz4_flex::compress_prepend_size(value));
+    compressed
+}
+
+/// Decompress a value compressed by [`compress`]. Values which weren't compressed by the client are returned as is.
+pub(crate) fn decompress(value: Value) -> RedisResult<Value> {
+    match value {
+        Value::BulkString(bytes) if bytes.starts_with(COMPRESSED_PREFIX) => {
+            lz4_flex::decompress_size_prepended(&bytes[COMPRESSED_PREFIX.len()..])
+                .map(Value::BulkString)
+                .map_err(|err| {
+                    RedisError::from((
+                        ErrorKind::TypeError,
+                        "Failed to decompress value",
+                        err.to_string(),
+                    ))
+                })
+        }
+        value => Ok(value),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compression_round_trip() {
+        let value = b"value".repeat(100);
+        let compressed = compress(&value, 100);
+        assert!(compressed.starts_with(COMPRESSED_PREFIX));
+        assert!(compressed.len() < value.len());
+        assert_eq!(
+            decompress(Value::BulkString(compressed)).unwrap(),
+            Value::BulkString(value)
+        );
+    }
+
+    #[test]
+    fn test_values_below_threshold_are_not_compressed() {
+        let value = b"value".to_vec();
+        assert_eq!(compress(&value, 100), value);
+        assert_eq!(compress(&value, 0), value);
+        assert_eq!(
+            decompress(Value::BulkString(value.clone())).unwrap(),
+            Value::BulkString(value)
+        );
+        assert_eq!(decompress(Value::Nil).unwrap(), Value::Nil);
+    }
+}
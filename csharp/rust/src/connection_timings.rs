@@ -0,0 +1,163 @@
+// Copyright Valkey GLIDE Project Contributors - SPDX Identifier: Apache-2.0
+
+use std::{
+    collections::BTreeMap,
+    sync::Mutex,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use redis::{ConnectionObserver, Value};
+
+use crate::info::bulk;
+
+/// Connection timings of a node.
+#[derive(Debug, Default)]
+struct NodeTimings {
+    /// Time it took to establish and set up the last connection.
+    last_latency: Option<Duration>,
+    /// Time at which the last connection was established.
+    last_connected_at: Option<SystemTime>,
+    /// Connections to the node which are currently open.
+    open_connections: u32,
+    /// Connection attempts which failed, including reconnections.
+    failed_attempts: u64,
+}
+
+/// Records how long establishing the connections to every node took, during the creation of the client and on reconnections,
+/// e.g. to find a single slow node which delays [`create_client`](crate::create_client).
+#[derive(Debug, Default)]
+pub(crate) struct ConnectionTimings {
+    nodes: Mutex<BTreeMap<String, NodeTimings>>,
+}
+
+impl ConnectionObserver for ConnectionTimings {
+    fn connected(&self, address: &str, latency: Duration) {
+        let mut nodes = self.nodes.lock().unwrap();
+        let node = nodes.entry(address.to_string()).or_default();
+        node.last_latency = Some(latency);
+        node.last_connected_at = Some(SystemTime::now());
+        node.open_connections += 1;
+    }
+
+    fn connection_failed(&self, address: &str) {
+        let mut nodes = self.nodes.lock().unwrap();
+        nodes
+            .entry(address.to_string())
+            .or_default()
+            .failed_attempts += 1;
+    }
+
+    fn disconnected(&self, address: &str) {
+        let mut nodes = self.nodes.lock().unwrap();
+        if let Some(node) = nodes.get_mut(address) {
+            node.open_connections = node.open_connections.saturating_sub(1);
+        }
+    }
+}
+
+impl ConnectionTimings {
+    /// Get the open connections and the failed connection attempts of every node, by the address of the node.
+    pub(crate) fn connection_counts(&self) -> Vec<(String, u32, u64)> {
+        let nodes = self.nodes.lock().unwrap();
+        nodes
+            .iter()
+            .map(|(address, node)| (address.clone(), node.open_connections, node.failed_attempts))
+            .collect()
+    }
+
+    /// Get the timings as a map of the `host:port` address of every node the client connected to, or tried to, to a map of:
+    /// * `connect_latency_us` - the time it took to establish and set up the last connection, in microseconds, or null.
+    /// * `last_connected_ms` - the unix time in milliseconds at which the last connection was established, or null.
+    /// * `connected` - whether a connection to the node is currently open.
+    /// * `failed_attempts` - the number of connection attempts which failed.
+    pub(crate) fn to_value(&self) -> Value {
+        let nodes = self.nodes.lock().unwrap();
+        Value::Map(
+            nodes
+                .iter()
+                .map(|(address, node)| {
+                    let latency = node
+                        .last_latency
+                        .map_or(Value::Nil, |latency| Value::Int(latency.as_micros() as i64));
+                    let connected_at = node.last_connected_at.map_or(Value::Nil, |time| {
+                        let time = time.duration_since(UNIX_EPOCH).unwrap_or_default();
+                        Value::Int(time.as_millis() as i64)
+                    });
+                    (
+                        bulk(address),
+                        Value::Map(vec![
+                            (bulk("connect_latency_us"), latency),
+                            (bulk("last_connected_ms"), connected_at),
+                            (bulk("connected"), Value::Boolean(node.open_connections > 0)),
+                            (
+                                bulk("failed_attempts"),
+                                Value::Int(node.failed_attempts as i64),
+                            ),
+                        ]),
+                    )
+                })
+                .collect(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn field<'a>(timings: &'a Value, address: &str, name: &str) -> &'a Value {
+        let Value::Map(nodes) = timings else {
+            panic!("Expected a map, got {timings:?}");
+        };
+        let Some((_, Value::Map(fields))) = nodes.iter().find(|(key, _)| *key == bulk(address))
+        else {
+            panic!("Missing node {address}");
+        };
+        fields
+            .iter()
+            .find(|(key, _)| *key == bulk(name))
+            .map(|(_, value)| value)
+            .unwrap_or_else(|| panic!("Missing field {name}"))
+    }
+
+    #[test]
+    fn test_records_connections_per_node() {
+        let timings = ConnectionTimings::default();
+        timings.connected("node1:6379", Duration::from_millis(3));
+        timings.connection_failed("node2:6379");
+        timings.connected("node1:6379", Duration::from_millis(5));
+        timings.disconnected("node1:6379");
+
+        let value = timings.to_value();
+        assert_eq!(
+            field(&value, "node1:6379", "connect_latency_us"),
+            &Value::Int(5000)
+        );
+        assert_eq!(
+            field(&value, "node1:6379", "connected"),
+            &Value::Boolean(true)
+        );
+        assert!(matches!(
+            field(&value, "node1:6379", "last_connected_ms"),
+            Value::Int(_)
+        ));
+        assert_eq!(
+            field(&value, "node2:6379", "connect_latency_us"),
+            &Value::Nil
+        );
+        assert_eq!(
+            field(&value, "node2:6379", "connected"),
+            &Value::Boolean(false)
+        );
+        assert_eq!(
+            field(&value, "node2:6379", "failed_attempts"),
+            &Value::Int(1)
+        );
+
+        timings.disconnected("node1:6379");
+        assert_eq!(
+            field(&timings.to_value(), "node1:6379", "connected"),
+            &Value::Boolean(false)
+        );
+    }
+}
@@ -0,0 +1,109 @@
+// Copyright Valkey GLIDE Project Contributors - SPDX Identifier: Apache-2.0
+
+//! Passwords pulled from the caller when connections are established, e.g. short-lived IAM tokens,
+//! see [`ConnectionConfig::credential_provider`](crate::ffi::ConnectionConfig::credential_provider).
+
+use std::{
+    ffi::{c_char, CStr},
+    sync::Arc,
+    time::Duration,
+};
+
+use redis::{ConnectionObserver, ErrorKind, RedisError, RedisResult};
+use tokio::sync::Notify;
+
+/// Credential provider callback, which returns a fresh password of the client, e.g. a newly generated IAM token.
+///
+/// The callback returns a null-terminated UTF-8 string, which is copied before the callback returns to the client,
+/// so it needs to stay valid only until the callback is called again. It returns `null` if no password is available,
+/// in which case the connection or the re-authentication which needed it fails.
+///
+/// The callback is called synchronously when the client is created, and by a thread of the client afterwards,
+/// so it should be cheap and never block.
+pub type CredentialProviderCallback = unsafe extern "C" fn() -> *const c_char;
+
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct CredentialProvider {
+    callback: CredentialProviderCallback,
+}
+
+impl CredentialProvider {
+    pub(crate) fn new(callback: CredentialProviderCallback) -> Self {
+        CredentialProvider { callback }
+    }
+
+    /// Pull a fresh password from the callback.
+    pub(crate) fn fetch(&self) -> RedisResult<String> {
+        let password = unsafe { (self.callback)() };
+        if password.is_null() {
+            return Err(RedisError::from((
+                ErrorKind::AuthenticationFailed,
+                "The credential provider returned no password",
+            )));
+        }
+        match unsafe { CStr::from_ptr(password) }.to_str() {
+            Ok(password) => Ok(password.to_string()),
+            Err(_) => Err(RedisError::from((
+                ErrorKind::AuthenticationFailed,
+                "The credential provider returned a password which isn't a UTF-8 string",
+            ))),
+        }
+    }
+}
+
+/// Requests a refresh of the password of the client once a connection is lost or fails to connect, so that the connection
+/// is established again with a fresh password. The refresh is done by the client, since the observer is owned by the
+/// connections, and concurrent requests are coalesced into one.
+pub(crate) struct CredentialRefresh(pub(crate) Arc<Notify>);
+
+impl ConnectionObserver for CredentialRefresh {
+    fn connected(&self, _address: &str, _latency: Duration) {}
+
+    fn connection_failed(&self, _address: &str) {
+        self.0.notify_one();
+    }
+
+    fn disconnected(&self, _address: &str) {
+        self.0.notify_one();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    unsafe extern "C" fn token() -> *const c_char {
+        c"fresh-token".as_ptr() as *const c_char
+    }
+
+    unsafe extern "C" fn no_token() -> *const c_char {
+        std::ptr::null()
+    }
+
+    #[test]
+    fn test_fetches_the_password_from_the_callback() {
+        assert_eq!(
+            CredentialProvider::new(token).fetch().unwrap(),
+            "fresh-token"
+        );
+        let err = CredentialProvider::new(no_token).fetch().unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::AuthenticationFailed);
+    }
+
+    #[test]
+    fn test_coalesces_refresh_requests() {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_time()
+            .build()
+            .unwrap();
+        let refresh = Arc::new(Notify::new());
+        let observer = CredentialRefresh(refresh.clone());
+        observer.disconnected("node:6379");
+        observer.connection_failed("node:6379");
+        runtime.block_on(async {
+            refresh.notified().await;
+            let pending = tokio::time::timeout(Duration::from_millis(10), refresh.notified()).await;
+            assert!(pending.is_err());
+        });
+    }
+}
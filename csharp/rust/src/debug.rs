@@ -0,0 +1,197 @@
+// Copyright Valkey GLIDE Project Contributors - SPDX Identifier: Apache-2.0
+
+//! `DEBUG` helpers for test harnesses, available only with the `testing` feature.
+//! The server must allow `DEBUG` commands (`enable-debug-command`).
+//! A route is required, so the commands reach exactly the chosen node, except for commands of a key, which are routed by its slot.
+
+use std::ffi::c_void;
+
+use redis::{
+    cluster_routing::{Route, RoutingInfo, SingleNodeRoutingInfo, SlotAddr},
+    cluster_topology::get_slot,
+    Cmd, ErrorKind, RedisError, RedisResult, Value,
+};
+
+use crate::{
+    commands::{ptr_to_bytes, submit},
+    execute_command,
+    ffi::{convert_double_pointer_to_vec, create_route, RouteInfo},
+    info::{bulk, typed_value},
+    open_client_from_ptr,
+};
+
+/// Submit a `DEBUG` command, failing if no route is given.
+///
+/// # Safety
+///
+/// See the safety documentation of [`submit`].
+unsafe fn submit_routed(
+    client_ptr: *const c_void,
+    callback_index: usize,
+    cmd: Cmd,
+    route_info: *const RouteInfo,
+) {
+    if route_info.is_null() {
+        let Some(client) = (unsafe { open_client_from_ptr(client_ptr, callback_index) }) else {
+            return;
+        };
+        let err = RedisError::from((
+            redis::ErrorKind::ClientError,
+            "DEBUG commands require a route",
+        ));
+        unsafe { client.core.report_error(callback_index, err) };
+        return;
+    }
+    unsafe { submit(client_ptr, callback_index, cmd, route_info) };
+}
+
+/// Execute `DEBUG SLEEP` on the node chosen by `route_info`, blocking it for `seconds`.
+/// Note that the request timeout of the client applies, so it should be longer than the sleep.
+///
+/// # Safety
+/// * `client_ptr` must be a valid pointer created by [`create_client`](crate::create_client), before [`close_client`](crate::close_client) was called.
+/// * `route_info` must be a valid [`RouteInfo`] pointer.
+#[no_mangle]
+pub unsafe extern "C" fn debug_sleep(
+    client_ptr: *const c_void,
+    callback_index: usize,
+    seconds: f64,
+    route_info: *const RouteInfo,
+) {
+    let mut cmd = redis::cmd("DEBUG");
+    cmd.arg("SLEEP").arg(seconds);
+    unsafe { submit_routed(client_ptr, callback_index, cmd, route_info) };
+}
+
+/// Execute a `DEBUG` subcommand, e.g. `JMAP` or `OBJECT <key>`, on the node chosen by `route_info`.
+///
+/// # Safety
+/// * `client_ptr` must be a valid pointer created by [`create_client`](crate::create_client), before [`close_client`](crate::close_client) was called.
+/// * `args` must point to `arg_count` consecutive buffer pointers and `args_len` must point to their lengths.
+///   See the safety documentation of [`convert_double_pointer_to_vec`].
+/// * `route_info` must be a valid [`RouteInfo`] pointer.
+#[no_mangle]
+pub unsafe extern "C" fn debug_command(
+    client_ptr: *const c_void,
+    callback_index: usize,
+    args: *const *const u8,
+    args_len: *const u32,
+    arg_count: u32,
+    route_info: *const RouteInfo,
+) {
+    let mut cmd = redis::cmd("DEBUG");
+    for arg in
+        unsafe { convert_double_pointer_to_vec(args as *const *const c_void, arg_count, args_len) }
+    {
+        cmd.arg(arg);
+    }
+    unsafe { submit_routed(client_ptr, callback_index, cmd, route_info) };
+}
+
+/// Parse the text of a `DEBUG OBJECT` response, e.g.
+/// `Value at:0x7f0c refcount:1 encoding:quicklist serializedlength:19 lru:1010 lru_seconds_idle:5 ql_nodes:1 ...`,
+/// to a map of its `field:value` pairs, typed as integers or doubles where unambiguous.
+/// All fields are kept, so fields added by newer servers are returned as well.
+fn parse_debug_object(text: &str) -> Value {
+    Value::Map(
+        text.split_whitespace()
+            .filter_map(|token| token.split_once(':'))
+            .filter(|(field, _)| !field.is_empty())
+            .map(|(field, value)| (bulk(field), typed_value(value)))
+            .collect(),
+    )
+}
+
+fn convert_debug_object(value: Value) -> RedisResult<Value> {
+    match value {
+        Value::BulkString(_) | Value::SimpleString(_) | Value::VerbatimString { .. } => {
+            let text: String = redis::from_owned_redis_value(value)?;
+            Ok(parse_debug_object(&text))
+        }
+        _ => Err(RedisError::from((
+            ErrorKind::TypeError,
+            "Unexpected DEBUG OBJECT response",
+            format!("{value:?}"),
+        ))),
+    }
+}
+
+/// Execute `DEBUG OBJECT <key>`, and parse its response to a map of fields, like `encoding`, `serializedlength` and `ql_nodes`, to values.
+/// Numeric fields are integers or doubles, and other fields, like `encoding`, are strings.
+/// In cluster mode the command is routed to the primary of the key's slot, unless a route is given.
+///
+/// # Safety
+/// * `client_ptr` must be a valid pointer created by [`create_client`](crate::create_client), before [`close_client`](crate::close_client) was called.
+/// * `key` must point to `key_len` consecutive bytes. See the safety documentation of [`ptr_to_bytes`].
+/// * `route_info` could be `null`, but if it is not `null`, it must be a valid [`RouteInfo`] pointer.
+#[no_mangle]
+pub unsafe extern "C" fn debug_object(
+    client_ptr: *const c_void,
+    callback_index: usize,
+    key: *const u8,
+    key_len: u32,
+    route_info: *const RouteInfo,
+) {
+    let Some(client) = (unsafe { open_client_from_ptr(client_ptr, callback_index) }) else {
+        return;
+    };
+    let key = unsafe { ptr_to_bytes(key, key_len) };
+    let mut cmd = redis::cmd("DEBUG");
+    cmd.arg("OBJECT").arg(key);
+    let route = if route_info.is_null() {
+        client.core.standalone_address.is_none().then(|| {
+            RoutingInfo::SingleNode(SingleNodeRoutingInfo::SpecificNode(Route::new(
+                get_slot(key),
+                SlotAddr::Master,
+            )))
+        })
+    } else {
+        unsafe { create_route(route_info, &cmd) }
+    };
+    unsafe {
+        execute_command(
+            &client,
+            callback_index,
+            cmd,
+            route,
+            None,
+            Some(Box::new(convert_debug_object)),
+        )
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_debug_object() {
+        let response = Value::SimpleString(
+            "Value at:0x7f0c2a4b8e40 refcount:1 encoding:quicklist serializedlength:19 lru:1010 \
+             lru_seconds_idle:5 ql_nodes:1 ql_avg_node:3.00 ql_listpack_max:-2 ql_compressed:0 ql_uncompressed_size:17"
+                .to_string(),
+        );
+        let Value::Map(fields) = convert_debug_object(response).unwrap() else {
+            panic!("DEBUG OBJECT response wasn't parsed to a map");
+        };
+        let field = |name: &str| {
+            fields
+                .iter()
+                .find(|(field, _)| *field == bulk(name))
+                .map(|(_, value)| value.clone())
+        };
+        assert_eq!(field("at"), Some(bulk("0x7f0c2a4b8e40")));
+        assert_eq!(field("encoding"), Some(bulk("quicklist")));
+        assert_eq!(field("serializedlength"), Some(Value::Int(19)));
+        assert_eq!(field("ql_nodes"), Some(Value::Int(1)));
+        assert_eq!(field("ql_avg_node"), Some(Value::Double(3.0)));
+        assert_eq!(field("ql_listpack_max"), Some(Value::Int(-2)));
+        assert_eq!(field("Value"), None);
+        assert_eq!(fields.len(), 11);
+    }
+
+    #[test]
+    fn test_unexpected_debug_object_response() {
+        assert!(convert_debug_object(Value::Int(1)).is_err());
+    }
+}
@@ -0,0 +1,212 @@
+// Copyright Valkey GLIDE Project Contributors - SPDX Identifier: Apache-2.0
+
+use redis::{
+    cluster_routing::{
+        is_readonly, MultipleNodeRoutingInfo, ResponsePolicy, Routable, Route, RoutingInfo,
+        SingleNodeRoutingInfo, SlotAddr,
+    },
+    Cmd,
+};
+use std::hash::{BuildHasher, Hasher};
+
+use crate::primary_preferred::primary_preferred;
+
+/// A route for commands submitted without an explicit route, set by the `default_route` of the connection configuration.
+///
+/// The precedence is: explicit per-command route > read-your-writes route > default route > command-type default.
+/// The read-your-writes route applies to reads of recently written slots, see [`RecentWrites`](crate::read_your_writes::RecentWrites).
+/// The default route only applies to commands which may be served by a replica:
+/// * Commands with keys are routed to the slot of their keys, with the slot type of the default route if it is a slot route.
+///   Other routes don't apply to commands with keys, since they could send the command to a node which doesn't serve its slot.
+/// * Read-only commands without keys, which are otherwise sent to a random node, are routed by the default route.
+///
+/// Commands which must be routed to a primary, like writes, and commands routed to multiple nodes
+/// are always routed by the command-type default.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum DefaultRoute {
+    Random,
+    /// A random replica for commands without keys, and a replica of the slot for commands with keys.
+    RandomReplica,
+    /// The primary of the slot for commands with keys, falling back to a replica of the slot, see [`primary_preferred`].
+    /// Commands without keys use the command-type default.
+    PrimaryPreferred,
+    AllNodes,
+    AllPrimaries,
+    Slot {
+        slot: u16,
+        slot_addr: SlotAddr,
+    },
+    ByAddress {
+        host: String,
+        port: u16,
+    },
+}
+
+impl DefaultRoute {
+    /// Get the route of a command submitted without an explicit route, `None` if the command-type default should be used.
+    pub(crate) fn route_for(&self, cmd: &Cmd) -> Option<RoutingInfo> {
+        match RoutingInfo::for_routable(cmd) {
+            Some(RoutingInfo::SingleNode(SingleNodeRoutingInfo::SpecificNode(route)))
+                if route.slot_addr() != SlotAddr::Master =>
+            {
+                let slot_addr = match self {
+                    DefaultRoute::Slot { slot_addr, .. } => *slot_addr,
+                    DefaultRoute::RandomReplica => SlotAddr::ReplicaRequired,
+                    DefaultRoute::PrimaryPreferred => return Some(primary_preferred(route.slot())),
+                    _ => return None,
+                };
+                Some(RoutingInfo::SingleNode(
+                    SingleNodeRoutingInfo::SpecificNode(Route::new(route.slot(), slot_addr)),
+                ))
+            }
+            Some(RoutingInfo::SingleNode(SingleNodeRoutingInfo::Random)) | None
+                if is_readonly(cmd) =>
+            {
+                Some(match self {
+                    DefaultRoute::Random => RoutingInfo::SingleNode(SingleNodeRoutingInfo::Random),
+                    DefaultRoute::RandomReplica => random_replica(),
+                    DefaultRoute::AllNodes => RoutingInfo::MultiNode((
+                        MultipleNodeRoutingInfo::AllNodes,
+                        ResponsePolicy::for_command(&cmd.command()?),
+                    )),
+                    DefaultRoute::AllPrimaries => RoutingInfo::MultiNode((
+                        MultipleNodeRoutingInfo::AllMasters,
+                        ResponsePolicy::for_command(&cmd.command()?),
+                    )),
+                    DefaultRoute::Slot { slot, slot_addr } => RoutingInfo::SingleNode(
+                        SingleNodeRoutingInfo::SpecificNode(Route::new(*slot, *slot_addr)),
+                    ),
+                    DefaultRoute::ByAddress { host, port } => {
+                        RoutingInfo::SingleNode(SingleNodeRoutingInfo::ByAddress {
+                            host: host.clone(),
+                            port: *port,
+                        })
+                    }
+                    DefaultRoute::PrimaryPreferred => return None,
+                })
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Number of slots in a cluster.
+const SLOT_COUNT: u64 = 16384;
+
+/// Create a route to a replica of a random shard, by picking a random slot.
+pub(crate) fn random_replica() -> RoutingInfo {
+    let random = std::collections::hash_map::RandomState::new()
+        .build_hasher()
+        .finish();
+    let slot = (random % SLOT_COUNT) as u16;
+    RoutingInfo::SingleNode(SingleNodeRoutingInfo::SpecificNode(Route::new(
+        slot,
+        SlotAddr::ReplicaRequired,
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const REPLICA: DefaultRoute = DefaultRoute::Slot {
+        slot: 0,
+        slot_addr: SlotAddr::ReplicaRequired,
+    };
+
+    fn specific_node(route: Option<RoutingInfo>) -> Route {
+        match route {
+            Some(RoutingInfo::SingleNode(SingleNodeRoutingInfo::SpecificNode(route))) => route,
+            route => panic!("unexpected route {route:?}"),
+        }
+    }
+
+    #[test]
+    fn test_reads_with_keys_use_slot_type_of_default_route() {
+        let mut cmd = redis::cmd("GET");
+        cmd.arg("foo");
+        let route = specific_node(REPLICA.route_for(&cmd));
+        assert_eq!(route.slot(), redis::cluster_topology::get_slot(b"foo"));
+        assert_eq!(route.slot_addr(), SlotAddr::ReplicaRequired);
+    }
+
+    #[test]
+    fn test_writes_use_command_type_default() {
+        let mut cmd = redis::cmd("SET");
+        cmd.arg("foo").arg("bar");
+        assert_eq!(REPLICA.route_for(&cmd), None);
+        let mut cmd = redis::cmd("DEL");
+        cmd.arg("foo");
+        assert_eq!(REPLICA.route_for(&cmd), None);
+    }
+
+    #[test]
+    fn test_non_slot_default_route_does_not_apply_to_commands_with_keys() {
+        let mut cmd = redis::cmd("GET");
+        cmd.arg("foo");
+        assert_eq!(DefaultRoute::Random.route_for(&cmd), None);
+        let by_address = DefaultRoute::ByAddress {
+            host: "localhost".to_string(),
+            port: 6379,
+        };
+        assert_eq!(by_address.route_for(&cmd), None);
+    }
+
+    #[test]
+    fn test_reads_without_keys_use_default_route() {
+        let by_address = DefaultRoute::ByAddress {
+            host: "localhost".to_string(),
+            port: 6379,
+        };
+        let mut cmd = redis::cmd("ECHO");
+        cmd.arg("foo");
+        assert_eq!(
+            by_address.route_for(&cmd),
+            Some(RoutingInfo::SingleNode(SingleNodeRoutingInfo::ByAddress {
+                host: "localhost".to_string(),
+                port: 6379,
+            }))
+        );
+        let route = specific_node(REPLICA.route_for(&cmd));
+        assert_eq!(route, Route::new(0, SlotAddr::ReplicaRequired));
+        let route = specific_node(REPLICA.route_for(&redis::cmd("ROLE")));
+        assert_eq!(route, Route::new(0, SlotAddr::ReplicaRequired));
+    }
+
+    #[test]
+    fn test_random_replica_default_route() {
+        let mut cmd = redis::cmd("GET");
+        cmd.arg("foo");
+        let route = specific_node(DefaultRoute::RandomReplica.route_for(&cmd));
+        assert_eq!(route.slot(), redis::cluster_topology::get_slot(b"foo"));
+        assert_eq!(route.slot_addr(), SlotAddr::ReplicaRequired);
+        let route = specific_node(DefaultRoute::RandomReplica.route_for(&redis::cmd("ROLE")));
+        assert_eq!(route.slot_addr(), SlotAddr::ReplicaRequired);
+        let mut cmd = redis::cmd("SET");
+        cmd.arg("foo").arg("bar");
+        assert_eq!(DefaultRoute::RandomReplica.route_for(&cmd), None);
+    }
+
+    #[test]
+    fn test_primary_preferred_default_route() {
+        let mut cmd = redis::cmd("GET");
+        cmd.arg("foo");
+        assert_eq!(
+            DefaultRoute::PrimaryPreferred.route_for(&cmd),
+            Some(primary_preferred(redis::cluster_topology::get_slot(b"foo")))
+        );
+        assert_eq!(
+            DefaultRoute::PrimaryPreferred.route_for(&redis::cmd("ROLE")),
+            None
+        );
+    }
+
+    #[test]
+    fn test_multi_node_commands_use_command_type_default() {
+        assert_eq!(REPLICA.route_for(&redis::cmd("DBSIZE")), None);
+        assert_eq!(
+            DefaultRoute::Random.route_for(&redis::cmd("FLUSHALL")),
+            None
+        );
+    }
+}
@@ -0,0 +1,178 @@
+// Copyright Valkey GLIDE Project Contributors - SPDX Identifier: Apache-2.0
+
+//! Deleting the keys which match a pattern without `KEYS`, see [`delete_by_pattern`].
+
+use std::ffi::c_void;
+
+use glide_core::{
+    client::{Client as GlideClient, FINISHED_SCAN_CURSOR},
+    cluster_scan_container::{get_cluster_scan_cursor, remove_scan_state_cursor},
+};
+use redis::{ClusterScanArgs, ErrorKind, RedisError, RedisResult, ScanStateRC, Value};
+
+use crate::{commands::ptr_to_bytes, open_client_from_ptr};
+
+/// Split a reply of `SCAN`, or of a cluster scan, to its cursor and its keys.
+fn parse_scan_reply(reply: Value) -> RedisResult<(Vec<u8>, Vec<Value>)> {
+    match reply {
+        Value::Array(mut reply) if reply.len() == 2 => match (reply.remove(0), reply.remove(0)) {
+            (Value::BulkString(cursor), Value::Array(keys)) => Ok((cursor, keys)),
+            (Value::SimpleString(cursor), Value::Array(keys)) => Ok((cursor.into_bytes(), keys)),
+            (cursor, keys) => Err(unexpected_reply(Value::Array(vec![cursor, keys]))),
+        },
+        reply => Err(unexpected_reply(reply)),
+    }
+}
+
+fn unexpected_reply(reply: Value) -> RedisError {
+    RedisError::from((
+        ErrorKind::TypeError,
+        "Unexpected scan reply",
+        format!("{reply:?}"),
+    ))
+}
+
+/// Unlink `keys`, returning the number of keys which were deleted. In cluster mode keys of different slots
+/// are split into an `UNLINK` per slot, like any `UNLINK` without a route.
+async fn unlink(client: &mut GlideClient, keys: Vec<Value>) -> RedisResult<i64> {
+    if keys.is_empty() {
+        return Ok(0);
+    }
+    let mut cmd = redis::cmd("UNLINK");
+    for key in keys {
+        if let Value::BulkString(key) = key {
+            cmd.arg(key);
+        }
+    }
+    match client.send_command(&cmd, None).await? {
+        Value::Int(deleted) => Ok(deleted),
+        reply => Err(unexpected_reply(reply)),
+    }
+}
+
+/// Scan the keys matching `pattern` by `SCAN` on the server, unlinking the keys of every iteration.
+async fn delete_by_scan(
+    client: &mut GlideClient,
+    pattern: &[u8],
+    batch_size: Option<u32>,
+) -> RedisResult<i64> {
+    let mut deleted = 0;
+    let mut cursor = b"0".to_vec();
+    loop {
+        let mut scan = redis::cmd("SCAN");
+        scan.arg(&cursor).arg("MATCH").arg(pattern);
+        if let Some(batch_size) = batch_size {
+            scan.arg("COUNT").arg(batch_size);
+        }
+        let (next_cursor, keys) = parse_scan_reply(client.send_command(&scan, None).await?)?;
+        cursor = next_cursor;
+        deleted += unlink(client, keys).await?;
+        if cursor == b"0" {
+            return Ok(deleted);
+        }
+    }
+}
+
+/// Scan the keys matching `pattern` by a cluster scan, which scans every slot once, unlinking the keys of every iteration.
+async fn delete_by_cluster_scan(
+    client: &mut GlideClient,
+    pattern: &[u8],
+    batch_size: Option<u32>,
+) -> RedisResult<i64> {
+    let mut deleted = 0;
+    let mut cursor = ScanStateRC::new();
+    loop {
+        let mut args = ClusterScanArgs::builder().with_match_pattern(pattern);
+        if let Some(batch_size) = batch_size {
+            args = args.with_count(batch_size);
+        }
+        let (cursor_id, keys) =
+            parse_scan_reply(client.cluster_scan(&cursor, args.build()).await?)?;
+        let cursor_id = String::from_utf8_lossy(&cursor_id).into_owned();
+        // glide-core keeps the cursor until it is removed, so it is removed before unlinking, whose failure returns early
+        if cursor_id != FINISHED_SCAN_CURSOR {
+            cursor = get_cluster_scan_cursor(cursor_id.clone())?;
+            remove_scan_state_cursor(cursor_id.clone());
+        }
+        deleted += unlink(client, keys).await?;
+        if cursor_id == FINISHED_SCAN_CURSOR {
+            return Ok(deleted);
+        }
+    }
+}
+
+/// Delete the keys matching the glob-style `pattern`, without `KEYS`, which blocks the server while it iterates all keys.
+/// The keys are iterated by `SCAN` in standalone mode, and by a cluster scan, which scans every slot of every primary once,
+/// in cluster mode. The keys of every iteration are deleted by `UNLINK`, which frees their values in the background,
+/// split by slot in cluster mode. `batch_size` is the `COUNT` hint of every iteration, `0` uses the default of the server.
+///
+/// The deletion isn't atomic: keys which are added or renamed during the scan may be missed, and keys which are modified
+/// after they were scanned are deleted anyway. With [`ConnectionConfig::key_prefix`](crate::ffi::ConnectionConfig::key_prefix),
+/// only the keys of the prefix are deleted.
+///
+/// The response is the number of deleted keys. If an iteration fails, the request fails with its error, and the keys of
+/// the previous iterations remain deleted.
+///
+/// # Safety
+/// * `client_ptr` must be a valid pointer created by [`create_client`](crate::create_client), before [`close_client`](crate::close_client) was called.
+/// * `pattern` must point to `pattern_len` consecutive bytes. See the safety documentation of [`ptr_to_bytes`].
+#[no_mangle]
+pub unsafe extern "C" fn delete_by_pattern(
+    client_ptr: *const c_void,
+    callback_index: usize,
+    pattern: *const u8,
+    pattern_len: u32,
+    batch_size: u32,
+) {
+    let Some(client) = (unsafe { open_client_from_ptr(client_ptr, callback_index) }) else {
+        return;
+    };
+    let core = client.core.clone();
+    let pending_guard = match core
+        .check_command_allowed(&redis::cmd("SCAN"))
+        .and_then(|_| core.check_command_allowed(&redis::cmd("UNLINK")))
+        .and_then(|_| core.reserve_pending_task())
+    {
+        Ok(pending_guard) => pending_guard,
+        Err(err) => {
+            unsafe { core.report_error(callback_index, err) };
+            return;
+        }
+    };
+    let mut pattern = unsafe { ptr_to_bytes(pattern, pattern_len) }.to_vec();
+    if let Some(key_prefix) = &core.key_prefix {
+        pattern = [key_prefix.prefix(), &pattern].concat();
+    }
+    let batch_size = (batch_size > 0).then_some(batch_size);
+
+    client.runtime.spawn(async move {
+        let mut client = core.client_pool.next();
+        let result = match core.standalone_address {
+            Some(_) => delete_by_scan(&mut client, &pattern, batch_size).await,
+            None => delete_by_cluster_scan(&mut client, &pattern, batch_size).await,
+        }
+        .map(Value::Int);
+        core.track_authentication(&result);
+        unsafe { core.report_result(callback_index, result) };
+        drop(pending_guard);
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_scan_reply() {
+        let (cursor, keys) = parse_scan_reply(Value::Array(vec![
+            Value::BulkString(b"17".to_vec()),
+            Value::Array(vec![Value::BulkString(b"key".to_vec())]),
+        ]))
+        .unwrap();
+        assert_eq!(cursor, b"17");
+        assert_eq!(keys, vec![Value::BulkString(b"key".to_vec())]);
+
+        let err = parse_scan_reply(Value::Int(0)).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::TypeError);
+    }
+}
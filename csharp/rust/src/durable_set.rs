@@ -0,0 +1,170 @@
+// Copyright Valkey GLIDE Project Contributors - SPDX Identifier: Apache-2.0
+
+//! Writes confirmed by replicas, see [`durable_set`].
+
+use std::ffi::c_void;
+
+use redis::{
+    cluster_routing::{Route, RoutingInfo, SingleNodeRoutingInfo, SlotAddr},
+    cluster_topology::get_slot,
+    ErrorKind, Pipeline, PipelineRetryStrategy, RedisError, RedisResult, Value,
+};
+
+use crate::{
+    commands::ptr_to_bytes,
+    ffi::{create_route, RouteInfo},
+    info::bulk,
+    open_client_from_ptr,
+};
+
+/// Time added to the timeout of `WAIT` for the timeout of the pipeline, so the pipeline doesn't time out before `WAIT` replies.
+const WAIT_TIMEOUT_EXTENSION_MS: u32 = 500;
+
+/// Replication state of a write of [`durable_set`].
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Durability {
+    /// At least the requested number of replicas acknowledged the write.
+    Acknowledged = 0,
+    /// Fewer replicas than requested acknowledged the write within the timeout. The write was applied on the primary,
+    /// but it could be lost on a failover.
+    PartiallyAcknowledged = 1,
+}
+
+/// Convert the replies of the `SET` and `WAIT` pipeline to a map of the [`Durability`] state and the number of replicas.
+fn convert_durable_set(value: Value, num_replicas: u32) -> RedisResult<Value> {
+    let replicas = match value {
+        Value::Array(replies) => match replies.as_slice() {
+            [Value::Okay, Value::Int(replicas)] => *replicas,
+            _ => {
+                return Err(RedisError::from((
+                    ErrorKind::TypeError,
+                    "Unexpected SET and WAIT responses",
+                    format!("{replies:?}"),
+                )))
+            }
+        },
+        value => {
+            return Err(RedisError::from((
+                ErrorKind::TypeError,
+                "Unexpected SET and WAIT responses",
+                format!("{value:?}"),
+            )))
+        }
+    };
+    let state = match replicas >= num_replicas.into() {
+        true => Durability::Acknowledged,
+        false => Durability::PartiallyAcknowledged,
+    };
+    Ok(Value::Map(vec![
+        (bulk("state"), Value::Int(state as i64)),
+        (bulk("replicas"), Value::Int(replicas)),
+    ]))
+}
+
+/// Set `key` to `value` and wait until `num_replicas` replicas acknowledged the write, for critical writes which need
+/// synchronous replication. `SET` and `WAIT` are sent in a pipeline to the same primary, so `WAIT` confirms this write.
+/// `timeout_ms` is the timeout of `WAIT` in milliseconds, it must be positive, since `WAIT` would block forever otherwise.
+///
+/// The response is a map of the `state` of the write, the [`Durability`] as an integer, and `replicas` - the number of replicas
+/// which acknowledged it, as an integer. If `SET` fails the request fails with its error, without waiting.
+/// Without `route_info` the pipeline is routed to the primary of the slot of the key.
+///
+/// # Safety
+/// * `client_ptr` must be a valid pointer created by [`create_client`](crate::create_client), before [`close_client`](crate::close_client) was called.
+/// * `key` must point to `key_len` consecutive bytes and `value` must point to `value_len` consecutive bytes.
+///   See the safety documentation of [`ptr_to_bytes`].
+/// * `route_info` could be `null`, but if it is not `null`, it must be a valid [`RouteInfo`] pointer.
+#[allow(clippy::too_many_arguments)]
+#[no_mangle]
+pub unsafe extern "C" fn durable_set(
+    client_ptr: *const c_void,
+    callback_index: usize,
+    key: *const u8,
+    key_len: u32,
+    value: *const u8,
+    value_len: u32,
+    num_replicas: u32,
+    timeout_ms: u32,
+    route_info: *const RouteInfo,
+) {
+    let Some(client) = (unsafe { open_client_from_ptr(client_ptr, callback_index) }) else {
+        return;
+    };
+    let core = client.core.clone();
+    let mut set = redis::cmd("SET");
+    set.arg(unsafe { ptr_to_bytes(key, key_len) })
+        .arg(unsafe { ptr_to_bytes(value, value_len) });
+    let mut wait = redis::cmd("WAIT");
+    wait.arg(num_replicas).arg(timeout_ms);
+    let pending_guard = match core
+        .check_command_allowed(&set)
+        .and_then(|_| core.check_command_allowed(&wait))
+        .and_then(|_| match timeout_ms {
+            0 => Err(RedisError::from((
+                ErrorKind::ClientError,
+                "The timeout of a durable SET must be positive",
+            ))),
+            _ => core.reserve_pending_task(),
+        }) {
+        Ok(pending_guard) => pending_guard,
+        Err(err) => {
+            unsafe { core.report_error(callback_index, err) };
+            return;
+        }
+    };
+    let route = unsafe { create_route(route_info, &set) }.or_else(|| {
+        core.standalone_address.is_none().then(|| {
+            RoutingInfo::SingleNode(SingleNodeRoutingInfo::SpecificNode(Route::new(
+                get_slot(unsafe { ptr_to_bytes(key, key_len) }),
+                SlotAddr::Master,
+            )))
+        })
+    });
+    let mut pipeline = Pipeline::with_capacity(2);
+    pipeline.add_command(set).add_command(wait);
+
+    client.runtime.spawn(async move {
+        let mut client = core.client_pool.next();
+        // The pipeline isn't retried, since `WAIT` on another connection wouldn't confirm the write
+        let result = client
+            .send_pipeline(
+                &pipeline,
+                route,
+                true,
+                Some(timeout_ms.saturating_add(WAIT_TIMEOUT_EXTENSION_MS)),
+                PipelineRetryStrategy::default(),
+            )
+            .await;
+        core.track_authentication(&result);
+        let result = result.and_then(|value| convert_durable_set(value, num_replicas));
+        unsafe { core.report_result(callback_index, result) };
+        drop(pending_guard);
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn durable_set_response(state: Durability, replicas: i64) -> Value {
+        Value::Map(vec![
+            (bulk("state"), Value::Int(state as i64)),
+            (bulk("replicas"), Value::Int(replicas)),
+        ])
+    }
+
+    #[test]
+    fn test_partial_acknowledgement_is_distinct() {
+        let replies = |replicas| Value::Array(vec![Value::Okay, Value::Int(replicas)]);
+        assert_eq!(
+            convert_durable_set(replies(2), 2).unwrap(),
+            durable_set_response(Durability::Acknowledged, 2)
+        );
+        assert_eq!(
+            convert_durable_set(replies(1), 2).unwrap(),
+            durable_set_response(Durability::PartiallyAcknowledged, 1)
+        );
+        assert!(convert_durable_set(Value::Array(vec![Value::Nil]), 2).is_err());
+    }
+}
@@ -0,0 +1,358 @@
+// Copyright Valkey GLIDE Project Contributors - SPDX Identifier: Apache-2.0
+
+use glide_core::client::{
+    ConnectionRequest, ConnectionRetryStrategy, PeriodicCheck, ReadFrom, TlsMode,
+    DEFAULT_CONNECTION_TIMEOUT, DEFAULT_PERIODIC_TOPOLOGY_CHECKS_INTERVAL,
+    DEFAULT_RESPONSE_TIMEOUT,
+};
+use redis::{ProtocolVersion, Value};
+
+use crate::ffi::{default_hash_tag, key_prefix, ConnectionConfig};
+
+/// Replaces secrets, like passwords, in the effective configuration.
+const REDACTED: &str = "<redacted>";
+
+fn bulk(value: &str) -> Value {
+    Value::BulkString(value.as_bytes().to_vec())
+}
+
+fn optional(value: Option<&str>) -> Value {
+    value.map_or(Value::Nil, bulk)
+}
+
+/// Builds the map of the effective configuration of a client, see [`get_effective_config`](crate::get_effective_config).
+pub(crate) struct EffectiveConfig(Vec<(Value, Value)>);
+
+impl EffectiveConfig {
+    /// Resolve the settings of the connection request, after the defaults of glide-core are applied.
+    /// The password is redacted.
+    pub(crate) fn new(request: &ConnectionRequest) -> Self {
+        let addresses = request
+            .addresses
+            .iter()
+            .map(|address| bulk(&format!("{}:{}", address.host, address.port)))
+            .collect();
+        let (read_from, client_az) = match request.read_from.clone().unwrap_or_default() {
+            ReadFrom::Primary => ("Primary", None),
+            ReadFrom::PreferReplica => ("PreferReplica", None),
+            ReadFrom::AZAffinity(az) => ("AZAffinity", Some(az)),
+            ReadFrom::AZAffinityReplicasAndPrimary(az) => {
+                ("AZAffinityReplicasAndPrimary", Some(az))
+            }
+        };
+        let tls_mode = match request.tls_mode.unwrap_or_default() {
+            TlsMode::NoTls => "NoTls",
+            TlsMode::InsecureTls => "InsecureTls",
+            TlsMode::SecureTls => "SecureTls",
+        };
+        let protocol = match request.protocol.unwrap_or_default() {
+            ProtocolVersion::RESP2 => "RESP2",
+            ProtocolVersion::RESP3 => "RESP3",
+        };
+        let timeout_ms = |timeout: Option<u32>, default: std::time::Duration| {
+            Value::Int(timeout.map_or(default.as_millis() as i64, i64::from))
+        };
+        // Zero parameters are replaced by the defaults when the strategy is applied.
+        let default_retry_strategy = ConnectionRetryStrategy::default();
+        let retry_strategy = request
+            .connection_retry_strategy
+            .unwrap_or(default_retry_strategy);
+        let non_zero =
+            |value: u32, default: u32| i64::from(if value > 0 { value } else { default });
+        let retry_strategy = Value::Map(vec![
+            (
+                bulk("exponent_base"),
+                Value::Int(non_zero(
+                    retry_strategy.exponent_base,
+                    default_retry_strategy.exponent_base,
+                )),
+            ),
+            (
+                bulk("factor"),
+                Value::Int(non_zero(
+                    retry_strategy.factor,
+                    default_retry_strategy.factor,
+                )),
+            ),
+            (
+                bulk("number_of_retries"),
+                Value::Int(retry_strategy.number_of_retries.into()),
+            ),
+        ]);
+        // The topology is refreshed periodically only in cluster mode.
+        let periodic_topology_refresh_secs = match &request.periodic_checks {
+            _ if !request.cluster_mode_enabled => None,
+            Some(PeriodicCheck::Disabled) => None,
+            Some(PeriodicCheck::ManualInterval(interval)) => Some(*interval),
+            Some(PeriodicCheck::Enabled) | None => Some(DEFAULT_PERIODIC_TOPOLOGY_CHECKS_INTERVAL),
+        };
+        let (username, password) = match &request.authentication_info {
+            Some(info) => (
+                info.username.as_deref(),
+                info.password.as_ref().map(|_| REDACTED),
+            ),
+            None => (None, None),
+        };
+        EffectiveConfig(vec![
+            (bulk("addresses"), Value::Array(addresses)),
+            (
+                bulk("cluster_mode"),
+                Value::Boolean(request.cluster_mode_enabled),
+            ),
+            (bulk("database_id"), Value::Int(request.database_id)),
+            (bulk("protocol"), bulk(protocol)),
+            (bulk("tls_mode"), bulk(tls_mode)),
+            (
+                bulk("tls_sni_hostname"),
+                optional(request.tls_sni_hostname.as_deref()),
+            ),
+            (bulk("read_from"), bulk(read_from)),
+            (bulk("client_az"), optional(client_az.as_deref())),
+            (
+                bulk("client_name"),
+                optional(request.client_name.as_deref()),
+            ),
+            (bulk("username"), optional(username)),
+            (bulk("password"), optional(password)),
+            (
+                bulk("request_timeout_ms"),
+                timeout_ms(request.request_timeout, DEFAULT_RESPONSE_TIMEOUT),
+            ),
+            (
+                bulk("connection_timeout_ms"),
+                timeout_ms(request.connection_timeout, DEFAULT_CONNECTION_TIMEOUT),
+            ),
+            (bulk("connection_retry_strategy"), retry_strategy),
+            (
+                bulk("periodic_topology_refresh_secs"),
+                periodic_topology_refresh_secs
+                    .map_or(Value::Nil, |interval| Value::Int(interval.as_secs() as i64)),
+            ),
+            (bulk("tcp_nodelay"), Value::Boolean(request.tcp_nodelay)),
+            (
+                bulk("address_family"),
+                bulk(&format!("{:?}", request.address_family)),
+            ),
+        ])
+    }
+
+    /// Add the settings of the client itself, which aren't part of the connection request.
+    /// Settings which only apply in cluster mode are reported as disabled in standalone mode.
+    ///
+    /// # Safety
+    ///
+    /// * `config` must be a valid pointer to a [`ConnectionConfig`] struct. See the safety documentation of [`key_prefix`].
+    pub(crate) unsafe fn with_client_settings(
+        mut self,
+        config: *const ConnectionConfig,
+        cluster_mode: bool,
+    ) -> Self {
+        let config = unsafe { &*config };
+        let key_prefix = unsafe { key_prefix(config) }.map_or(Value::Nil, Value::BulkString);
+        let default_hash_tag =
+            unsafe { default_hash_tag(config) }.map_or(Value::Nil, Value::BulkString);
+        self.0.extend([
+            (
+                bulk("max_inflight_requests_per_node"),
+                Value::Int(config.max_inflight_requests_per_node.into()),
+            ),
+            (
+                bulk("max_pending_commands"),
+                Value::Int(config.max_pending_commands.into()),
+            ),
+            (
+                bulk("normalize_empty_replies"),
+                Value::Boolean(config.normalize_empty_replies),
+            ),
+            (
+                bulk("response_metadata"),
+                Value::Boolean(config.response_metadata),
+            ),
+            (
+                bulk("error_message_format"),
+                bulk(&format!("{:?}", config.error_message_format)),
+            ),
+            (
+                bulk("connections_per_node"),
+                Value::Int(config.connections_per_node.max(1).into()),
+            ),
+            (
+                bulk("max_response_bytes"),
+                Value::Int(config.max_response_bytes as i64),
+            ),
+            (
+                bulk("max_command_arg_bytes"),
+                Value::Int(config.max_command_arg_bytes.into()),
+            ),
+            (
+                bulk("command_trace_capacity"),
+                Value::Int(config.command_trace_capacity.into()),
+            ),
+            (
+                bulk("command_trace_full_args"),
+                Value::Boolean(config.command_trace_full_args),
+            ),
+            (
+                bulk("runtime_saturation_threshold"),
+                Value::Int(config.runtime_saturation_threshold.into()),
+            ),
+            (
+                bulk("order_commands_per_key"),
+                Value::Boolean(config.order_commands_per_key),
+            ),
+            (
+                bulk("client_keepalive_interval_secs"),
+                Value::Int(config.client_keepalive_interval_secs.into()),
+            ),
+            (bulk("default_hash_tag"), default_hash_tag),
+            (
+                bulk("fail_fast_on_degraded"),
+                Value::Boolean(config.fail_fast_on_degraded),
+            ),
+            (
+                bulk("reauthenticate_on_noauth"),
+                Value::Boolean(config.reauthenticate_on_noauth),
+            ),
+            (
+                bulk("deduplicate_reads"),
+                Value::Boolean(config.deduplicate_reads),
+            ),
+            (
+                bulk("fallback_to_primary_on_replica_error"),
+                Value::Boolean(cluster_mode && config.fallback_to_primary_on_replica_error),
+            ),
+            (
+                bulk("retry_writes_on_readonly"),
+                Value::Boolean(cluster_mode && config.retry_writes_on_readonly),
+            ),
+            (
+                bulk("circuit_breaker_threshold"),
+                Value::Int(if cluster_mode {
+                    config.circuit_breaker_threshold.into()
+                } else {
+                    0
+                }),
+            ),
+            (
+                bulk("value_transform"),
+                Value::Boolean(config.value_encoder.is_some()),
+            ),
+            (
+                bulk("read_your_writes_window_ms"),
+                Value::Int(if cluster_mode {
+                    config.read_your_writes_window_ms.into()
+                } else {
+                    0
+                }),
+            ),
+            (
+                bulk("compression_threshold"),
+                Value::Int(config.compression_threshold.into()),
+            ),
+            (
+                bulk("slow_command_threshold_ms"),
+                Value::Int(config.slow_command_threshold.into()),
+            ),
+            (
+                bulk("slow_callback_threshold_ms"),
+                Value::Int(config.slow_callback_threshold_ms.into()),
+            ),
+            (
+                bulk("offload_callbacks"),
+                Value::Boolean(config.offload_callbacks),
+            ),
+            (
+                bulk("credential_provider"),
+                Value::Boolean(config.credential_provider.is_some()),
+            ),
+            (
+                bulk("command_filter_mode"),
+                bulk(&format!("{:?}", config.command_filter_mode)),
+            ),
+            (
+                bulk("client_tracking_mode"),
+                bulk(&format!("{:?}", config.client_tracking_mode)),
+            ),
+            (bulk("key_prefix"), key_prefix),
+            (
+                bulk("strip_key_prefix"),
+                Value::Boolean(config.strip_key_prefix),
+            ),
+        ]);
+        self
+    }
+
+    pub(crate) fn into_value(self) -> Value {
+        Value::Map(self.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use glide_core::client::{AuthenticationInfo, NodeAddress};
+
+    fn get<'a>(config: &'a Value, name: &str) -> &'a Value {
+        let Value::Map(entries) = config else {
+            panic!("unexpected config {config:?}");
+        };
+        entries
+            .iter()
+            .find(|(key, _)| *key == bulk(name))
+            .map(|(_, value)| value)
+            .unwrap_or_else(|| panic!("missing {name}"))
+    }
+
+    #[test]
+    fn test_defaults_are_applied() {
+        let request = ConnectionRequest {
+            addresses: vec![NodeAddress {
+                host: "localhost".to_string(),
+                port: 6379,
+            }],
+            cluster_mode_enabled: true,
+            connection_retry_strategy: Some(ConnectionRetryStrategy {
+                exponent_base: 0,
+                factor: 50,
+                number_of_retries: 2,
+            }),
+            ..Default::default()
+        };
+        let config = EffectiveConfig::new(&request).into_value();
+        assert_eq!(
+            get(&config, "addresses"),
+            &Value::Array(vec![bulk("localhost:6379")])
+        );
+        assert_eq!(get(&config, "protocol"), &bulk("RESP2"));
+        assert_eq!(get(&config, "read_from"), &bulk("Primary"));
+        assert_eq!(get(&config, "request_timeout_ms"), &Value::Int(250));
+        assert_eq!(
+            get(&config, "connection_retry_strategy"),
+            &Value::Map(vec![
+                (bulk("exponent_base"), Value::Int(2)),
+                (bulk("factor"), Value::Int(50)),
+                (bulk("number_of_retries"), Value::Int(2)),
+            ])
+        );
+        assert_eq!(
+            get(&config, "periodic_topology_refresh_secs"),
+            &Value::Int(60)
+        );
+    }
+
+    #[test]
+    fn test_password_is_redacted() {
+        let request = ConnectionRequest {
+            authentication_info: Some(AuthenticationInfo {
+                username: Some("user".to_string()),
+                password: Some("secret".to_string()),
+            }),
+            ..Default::default()
+        };
+        let config = EffectiveConfig::new(&request).into_value();
+        assert_eq!(get(&config, "username"), &bulk("user"));
+        assert_eq!(get(&config, "password"), &bulk(REDACTED));
+        assert!(!format!("{config:?}").contains("secret"));
+        assert_eq!(get(&config, "periodic_topology_refresh_secs"), &Value::Nil);
+    }
+}
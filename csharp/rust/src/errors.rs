@@ -0,0 +1,414 @@
+// Copyright Valkey GLIDE Project Contributors - SPDX Identifier: Apache-2.0
+
+use glide_core::errors::{self as core_errors, RequestErrorType as CoreRequestErrorType};
+use redis::{ErrorKind, RedisError};
+
+use crate::command_filter::COMMAND_NOT_ALLOWED_CODE;
+
+/// A mirror of [`CoreRequestErrorType`] adopted for FFI.
+/// Additionally it classifies server errors which callers may want to handle specifically.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RequestErrorType {
+    Unspecified = 0,
+    ExecAbort = 1,
+    Timeout = 2,
+    Disconnect = 3,
+    /// The server rejected a command because `maxmemory` was reached (`OOM`).
+    OutOfMemory = 4,
+    /// The server rejected a write command because it can't persist data (`MISCONF`).
+    Misconfiguration = 5,
+    /// The node serving the request is unavailable, e.g. a primary is mid-failover (`MASTERDOWN`), or the node is considered unhealthy,
+    /// see [`ConnectionConfig::fail_fast_on_degraded`](crate::ffi::ConnectionConfig::fail_fast_on_degraded).
+    Unavailable = 6,
+    /// The user has no permissions to run the command or to access the keys or channels (`NOPERM`).
+    NoPermission = 7,
+    /// The request was submitted after the client was closed.
+    ClientClosed = 8,
+    /// The requested statistic isn't tracked under the configured `maxmemory-policy`,
+    /// e.g. `OBJECT FREQ` without an LFU policy, or `OBJECT IDLETIME` with an LFU policy.
+    MaxMemoryPolicyMismatch = 9,
+    /// The server rejected the credentials of a connection (`WRONGPASS` or `NOAUTH`).
+    AuthenticationFailed = 10,
+    /// The command isn't allowed by the command filter of the client, it wasn't sent to the server.
+    CommandNotAllowed = 11,
+    /// The server doesn't support the command or some of its options, e.g. conditions of `EXPIRE` before 7.0.
+    UnsupportedByServer = 12,
+    /// A connection lost its authentication (`NOAUTH`) and re-authenticating it with the credentials of the client failed.
+    ReauthenticationFailed = 13,
+    /// The response exceeded the configured maximal size, so it wasn't passed to the caller.
+    ResponseTooLarge = 14,
+    /// The client reached the maximal amount of pending commands, or the node reached the maximal amount of in-flight requests,
+    /// so the command wasn't sent.
+    Backpressure = 15,
+    /// The blocking command was cancelled by its cancellation handle, see [`create_cancellation_handle`](crate::blocking::create_cancellation_handle).
+    Cancelled = 16,
+    /// An argument of the command exceeded the configured maximal size, so the command wasn't sent.
+    ArgumentTooLarge = 17,
+    /// The configuration of the client is invalid, e.g. inconsistent fields, so the client wasn't created.
+    InvalidConfig = 18,
+    /// The cluster is down (`CLUSTERDOWN`), e.g. not enough primaries are reachable, or the circuit breaker of the client is open,
+    /// see [`ConnectionConfig::circuit_breaker_threshold`](crate::ffi::ConnectionConfig::circuit_breaker_threshold).
+    ClusterDown = 19,
+    /// A write was sent to a replica (`READONLY`), e.g. by a stale topology during a failover,
+    /// see [`ConnectionConfig::retry_writes_on_readonly`](crate::ffi::ConnectionConfig::retry_writes_on_readonly).
+    ReadOnly = 20,
+    /// The slot of a command routed by a topology snapshot moved to another node since the snapshot was taken (`MOVED` or `ASK`),
+    /// so the command wasn't redirected, see [`command_with_topology`](crate::topology_snapshot::command_with_topology).
+    SlotMoved = 21,
+}
+
+impl From<CoreRequestErrorType> for RequestErrorType {
+    fn from(error_type: CoreRequestErrorType) -> Self {
+        match error_type {
+            CoreRequestErrorType::Unspecified => RequestErrorType::Unspecified,
+            CoreRequestErrorType::ExecAbort => RequestErrorType::ExecAbort,
+            CoreRequestErrorType::Timeout => RequestErrorType::Timeout,
+            CoreRequestErrorType::Disconnect => RequestErrorType::Disconnect,
+        }
+    }
+}
+
+/// Error code of requests which the server doesn't support, classified as [`RequestErrorType::UnsupportedByServer`].
+/// The server replies to such requests with generic errors, so they are classified by the helper which sent them.
+pub(crate) const UNSUPPORTED_BY_SERVER_CODE: &str = "UNSUPPORTED";
+
+/// Error code of requests which failed with `NOAUTH`, and re-authenticating the connection failed too,
+/// classified as [`RequestErrorType::ReauthenticationFailed`].
+pub(crate) const REAUTHENTICATION_FAILED_CODE: &str = "REAUTHFAILED";
+
+/// Error code of responses which exceeded the configured maximal size, classified as [`RequestErrorType::ResponseTooLarge`].
+pub(crate) const RESPONSE_TOO_LARGE_CODE: &str = "TOOLARGE";
+
+/// Error code of commands rejected because the client reached the configured maximal amount of pending commands,
+/// or the node reached the configured maximal amount of in-flight requests, classified as [`RequestErrorType::Backpressure`].
+pub(crate) const BACKPRESSURE_CODE: &str = "BACKPRESSURE";
+
+/// Error code of commands with an argument which exceeded the configured maximal size, classified as [`RequestErrorType::ArgumentTooLarge`].
+pub(crate) const ARGUMENT_TOO_LARGE_CODE: &str = "ARGTOOLARGE";
+
+/// Error code of commands failed fast because their node is considered unhealthy, classified as [`RequestErrorType::Unavailable`].
+pub(crate) const NODE_UNAVAILABLE_CODE: &str = "UNAVAILABLE";
+
+/// Error code of the commands of a batch which didn't complete before the timeout of the batch, classified as [`RequestErrorType::Timeout`],
+/// see [`BatchOptionsInfo::partial_results_chunk_size`](crate::ffi::BatchOptionsInfo::partial_results_chunk_size).
+pub(crate) const BATCH_TIMEOUT_CODE: &str = "BATCHTIMEOUT";
+
+/// Error code of commands whose slot is served by another node than in the topology snapshot they were routed by,
+/// classified as [`RequestErrorType::SlotMoved`].
+pub(crate) const SLOT_MOVED_CODE: &str = "SLOTMOVED";
+
+/// Error code of blocking commands cancelled by the caller, classified as [`RequestErrorType::Cancelled`].
+pub(crate) const CANCELLED_CODE: &str = "CANCELLED";
+
+pub(crate) fn error_type(error: &RedisError) -> RequestErrorType {
+    match error.code() {
+        Some("OOM") => RequestErrorType::OutOfMemory,
+        Some("MISCONF") => RequestErrorType::Misconfiguration,
+        Some("NOPERM") => RequestErrorType::NoPermission,
+        Some("WRONGPASS" | "NOAUTH") => RequestErrorType::AuthenticationFailed,
+        Some(COMMAND_NOT_ALLOWED_CODE) => RequestErrorType::CommandNotAllowed,
+        Some(UNSUPPORTED_BY_SERVER_CODE) => RequestErrorType::UnsupportedByServer,
+        Some(REAUTHENTICATION_FAILED_CODE) => RequestErrorType::ReauthenticationFailed,
+        Some(RESPONSE_TOO_LARGE_CODE) => RequestErrorType::ResponseTooLarge,
+        Some(BACKPRESSURE_CODE) => RequestErrorType::Backpressure,
+        Some(CANCELLED_CODE) => RequestErrorType::Cancelled,
+        Some(ARGUMENT_TOO_LARGE_CODE) => RequestErrorType::ArgumentTooLarge,
+        Some(NODE_UNAVAILABLE_CODE) => RequestErrorType::Unavailable,
+        Some(BATCH_TIMEOUT_CODE) => RequestErrorType::Timeout,
+        Some(SLOT_MOVED_CODE) => RequestErrorType::SlotMoved,
+        Some("ERR")
+            if error
+                .detail()
+                .is_some_and(|detail| detail.contains("maxmemory policy is")) =>
+        {
+            RequestErrorType::MaxMemoryPolicyMismatch
+        }
+        _ if error.kind() == ErrorKind::ClusterDown => RequestErrorType::ClusterDown,
+        _ if error.kind() == ErrorKind::ReadOnly => RequestErrorType::ReadOnly,
+        _ if matches!(error.kind(), ErrorKind::Moved | ErrorKind::Ask) => {
+            RequestErrorType::SlotMoved
+        }
+        _ if error.kind() == ErrorKind::MasterDown => RequestErrorType::Unavailable,
+        _ if error.kind() == ErrorKind::AuthenticationFailed => {
+            RequestErrorType::AuthenticationFailed
+        }
+        _ if error.kind() == ErrorKind::InvalidClientConfig => RequestErrorType::InvalidConfig,
+        _ => core_errors::error_type(error).into(),
+    }
+}
+
+pub(crate) fn error_message(error: &RedisError) -> String {
+    core_errors::error_message(error)
+}
+
+/// Whether a request failed to be delivered to the server or to get its reply, rather than failed by the reply of the server:
+/// connection errors, timeouts, unavailable nodes or clusters, and requests which the client rejected before sending them.
+pub(crate) fn is_delivery_failure(error: &RedisError) -> bool {
+    matches!(
+        error_type(error),
+        RequestErrorType::Disconnect
+            | RequestErrorType::Timeout
+            | RequestErrorType::Unavailable
+            | RequestErrorType::ClusterDown
+            | RequestErrorType::CommandNotAllowed
+            | RequestErrorType::Backpressure
+            | RequestErrorType::ArgumentTooLarge
+    ) || error.kind() == ErrorKind::ClientError
+}
+
+/// Format of the error messages passed to the failure callback, see [`ConnectionConfig::error_message_format`](crate::ffi::ConnectionConfig::error_message_format).
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ErrorMessageFormat {
+    /// A human readable message, e.g. `Received connection error ... Will attempt to reconnect`.
+    Text = 0,
+    /// A JSON object of the error type, the error code, the kind of the error, the human readable message and the detail, e.g.
+    /// `{"type":"OutOfMemory","code":"OOM","kind":"ExtensionError","message":"OOM: command not allowed","detail":"command not allowed"}`.
+    /// The code and the detail are `null` if the error has none.
+    Json = 1,
+}
+
+/// Format the message of an error passed to the failure callback. Formatting can't fail, and control characters are escaped,
+/// so a JSON message is always a valid C string.
+pub(crate) fn format_error(error: &RedisError, format: ErrorMessageFormat) -> String {
+    match format {
+        ErrorMessageFormat::Text => error_message(error),
+        ErrorMessageFormat::Json => {
+            let optional = |text: Option<&str>| text.map_or("null".to_string(), json_string);
+            format!(
+                r#"{{"type":{},"code":{},"kind":{},"message":{},"detail":{}}}"#,
+                json_string(&format!("{:?}", error_type(error))),
+                optional(error.code()),
+                json_string(&format!("{:?}", error.kind())),
+                json_string(&error_message(error)),
+                optional(error.detail()),
+            )
+        }
+    }
+}
+
+/// Format the message of an error like [`format_error`], tagged with the correlation id of the request which failed,
+/// e.g. `... (correlation id: checkout-42)`, or a `correlation_id` field of the JSON object.
+pub(crate) fn format_correlated_error(
+    error: &RedisError,
+    format: ErrorMessageFormat,
+    correlation_id: &str,
+) -> String {
+    let mut message = format_error(error, format);
+    match format {
+        ErrorMessageFormat::Text => {
+            message.push_str(&format!(" (correlation id: {correlation_id})"))
+        }
+        ErrorMessageFormat::Json => {
+            message.pop();
+            message.push_str(&format!(
+                r#","correlation_id":{}}}"#,
+                json_string(correlation_id)
+            ));
+        }
+    }
+    message
+}
+
+/// Quote and escape a JSON string.
+fn json_string(text: &str) -> String {
+    let mut json = String::with_capacity(text.len() + 2);
+    json.push('"');
+    for c in text.chars() {
+        match c {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            '\n' => json.push_str("\\n"),
+            '\r' => json.push_str("\\r"),
+            '\t' => json.push_str("\\t"),
+            c if c.is_control() => json.push_str(&format!("\\u{:04x}", c as u32)),
+            c => json.push(c),
+        }
+    }
+    json.push('"');
+    json
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn server_error(reply: &str) -> RedisError {
+        redis::parse_redis_value(reply.as_bytes())
+            .and_then(|value| value.extract_error())
+            .unwrap_err()
+    }
+
+    #[test]
+    fn test_classify_out_of_memory() {
+        let err = server_error("-OOM command not allowed when used memory > 'maxmemory'.\r\n");
+        assert_eq!(error_type(&err), RequestErrorType::OutOfMemory);
+    }
+
+    #[test]
+    fn test_classify_misconfiguration() {
+        let err =
+            server_error("-MISCONF Errors writing to the AOF file: No space left on device\r\n");
+        assert_eq!(error_type(&err), RequestErrorType::Misconfiguration);
+    }
+
+    #[test]
+    fn test_classify_no_permission() {
+        let err =
+            server_error("-NOPERM User restricted has no permissions to run the 'set' command\r\n");
+        assert_eq!(error_type(&err), RequestErrorType::NoPermission);
+    }
+
+    #[test]
+    fn test_server_errors_are_not_delivery_failures() {
+        let err =
+            server_error("-WRONGTYPE Operation against a key holding the wrong kind of value\r\n");
+        assert!(!is_delivery_failure(&err));
+        let err = server_error("-OOM command not allowed when used memory > 'maxmemory'.\r\n");
+        assert!(!is_delivery_failure(&err));
+    }
+
+    #[test]
+    fn test_connection_errors_are_delivery_failures() {
+        let err = RedisError::from(std::io::Error::from(std::io::ErrorKind::ConnectionReset));
+        assert!(is_delivery_failure(&err));
+        let err = redis::make_extension_error(BACKPRESSURE_CODE.to_string(), None);
+        assert!(is_delivery_failure(&err));
+    }
+
+    #[test]
+    fn test_classify_unavailable() {
+        let err = server_error("-MASTERDOWN Link with MASTER is down\r\n");
+        assert_eq!(error_type(&err), RequestErrorType::Unavailable);
+        let err = redis::make_extension_error(NODE_UNAVAILABLE_CODE.to_string(), None);
+        assert_eq!(error_type(&err), RequestErrorType::Unavailable);
+    }
+
+    #[test]
+    fn test_classify_cluster_down() {
+        let err = server_error("-CLUSTERDOWN The cluster is down\r\n");
+        assert_eq!(error_type(&err), RequestErrorType::ClusterDown);
+        assert!(is_delivery_failure(&err));
+    }
+
+    #[test]
+    fn test_classify_read_only() {
+        let err = server_error("-READONLY You can't write against a read only replica.\r\n");
+        assert_eq!(error_type(&err), RequestErrorType::ReadOnly);
+        assert!(!is_delivery_failure(&err));
+    }
+
+    #[test]
+    fn test_classify_slot_moved() {
+        let err = server_error("-MOVED 3999 127.0.0.1:6381\r\n");
+        assert_eq!(error_type(&err), RequestErrorType::SlotMoved);
+        let err = server_error("-ASK 3999 127.0.0.1:6381\r\n");
+        assert_eq!(error_type(&err), RequestErrorType::SlotMoved);
+        let err = redis::make_extension_error(SLOT_MOVED_CODE.to_string(), None);
+        assert_eq!(error_type(&err), RequestErrorType::SlotMoved);
+    }
+
+    #[test]
+    fn test_classify_max_memory_policy_mismatch() {
+        let err = server_error("-ERR An LFU maxmemory policy is not selected, access frequency not tracked. Please note that when switching between policies at runtime LRU and LFU data will take some time to adjust.\r\n");
+        assert_eq!(error_type(&err), RequestErrorType::MaxMemoryPolicyMismatch);
+        let err = server_error("-ERR An LFU maxmemory policy is selected, idle time not tracked. Please note that when switching between policies at runtime LRU and LFU data will take some time to adjust.\r\n");
+        assert_eq!(error_type(&err), RequestErrorType::MaxMemoryPolicyMismatch);
+    }
+
+    #[test]
+    fn test_classify_authentication_failed() {
+        let err =
+            server_error("-WRONGPASS invalid username-password pair or user is disabled.\r\n");
+        assert_eq!(error_type(&err), RequestErrorType::AuthenticationFailed);
+        let err = server_error("-NOAUTH Authentication required.\r\n");
+        assert_eq!(error_type(&err), RequestErrorType::AuthenticationFailed);
+        let err = RedisError::from((
+            ErrorKind::AuthenticationFailed,
+            "Password authentication failed",
+        ));
+        assert_eq!(error_type(&err), RequestErrorType::AuthenticationFailed);
+    }
+
+    #[test]
+    fn test_classify_command_not_allowed() {
+        let err = redis::make_extension_error(COMMAND_NOT_ALLOWED_CODE.to_string(), None);
+        assert_eq!(error_type(&err), RequestErrorType::CommandNotAllowed);
+    }
+
+    #[test]
+    fn test_classify_reauthentication_failed() {
+        let err = redis::make_extension_error(REAUTHENTICATION_FAILED_CODE.to_string(), None);
+        assert_eq!(error_type(&err), RequestErrorType::ReauthenticationFailed);
+    }
+
+    #[test]
+    fn test_classify_response_too_large() {
+        let err = redis::make_extension_error(RESPONSE_TOO_LARGE_CODE.to_string(), None);
+        assert_eq!(error_type(&err), RequestErrorType::ResponseTooLarge);
+    }
+
+    #[test]
+    fn test_classify_argument_too_large() {
+        let err = redis::make_extension_error(ARGUMENT_TOO_LARGE_CODE.to_string(), None);
+        assert_eq!(error_type(&err), RequestErrorType::ArgumentTooLarge);
+    }
+
+    #[test]
+    fn test_classify_invalid_config() {
+        let err = RedisError::from((
+            ErrorKind::InvalidClientConfig,
+            "Invalid configuration",
+            "read_from.az: required".to_string(),
+        ));
+        assert_eq!(error_type(&err), RequestErrorType::InvalidConfig);
+    }
+
+    #[test]
+    fn test_classify_backpressure() {
+        let err = redis::make_extension_error(BACKPRESSURE_CODE.to_string(), None);
+        assert_eq!(error_type(&err), RequestErrorType::Backpressure);
+    }
+
+    #[test]
+    fn test_json_error_message() {
+        let err = server_error("-OOM command not allowed\r\n");
+        assert_eq!(
+            format_error(&err, ErrorMessageFormat::Json),
+            format!(
+                r#"{{"type":"OutOfMemory","code":"OOM","kind":"{:?}","message":{},"detail":"command not allowed"}}"#,
+                err.kind(),
+                json_string(&error_message(&err))
+            )
+        );
+
+        let err = RedisError::from((ErrorKind::ClientError, "Quoted \"detail\"\0\n"));
+        let message = format_error(&err, ErrorMessageFormat::Json);
+        assert!(message.contains(r#""code":null"#));
+        assert!(message.contains(r#"Quoted \"detail\"\u0000\n"#));
+        assert!(std::ffi::CString::new(message).is_ok());
+    }
+
+    #[test]
+    fn test_correlated_error_message() {
+        let err = RedisError::from((ErrorKind::ClientError, "Failed"));
+        assert_eq!(
+            format_correlated_error(&err, ErrorMessageFormat::Text, "checkout-42"),
+            format!("{} (correlation id: checkout-42)", error_message(&err))
+        );
+        let json = format_error(&err, ErrorMessageFormat::Json);
+        assert_eq!(
+            format_correlated_error(&err, ErrorMessageFormat::Json, "a\"b"),
+            format!(r#"{},"correlation_id":"a\"b"}}"#, &json[..json.len() - 1])
+        );
+    }
+
+    #[test]
+    fn test_classify_other_server_errors() {
+        let err = server_error("-ERR unknown command 'FOO'\r\n");
+        assert_eq!(error_type(&err), RequestErrorType::Unspecified);
+        let err = server_error("-EXECABORT Transaction discarded because of previous errors.\r\n");
+        assert_eq!(error_type(&err), RequestErrorType::ExecAbort);
+    }
+}
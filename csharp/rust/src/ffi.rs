@@ -1,20 +1,31 @@
 // Copyright Valkey GLIDE Project Contributors - SPDX Identifier: Apache-2.0
 
 use std::{
-    ffi::{c_char, c_void, CStr},
+    ffi::{c_char, c_void, CStr, CString},
     slice::from_raw_parts,
+    time::Duration,
 };
 
-use glide_core::client::{
-    AuthenticationInfo, ConnectionRequest, ConnectionRetryStrategy, NodeAddress,
-    ReadFrom as coreReadFrom, TlsMode,
+use crate::client_tracking::{ClientTracking, ClientTrackingMode};
+use crate::command_filter::{CommandFilter, CommandFilterMode};
+use crate::credential_provider::CredentialProviderCallback;
+use crate::default_route::{random_replica, DefaultRoute};
+use crate::errors::{error_message, error_type, ErrorMessageFormat, ARGUMENT_TOO_LARGE_CODE};
+use crate::primary_preferred::primary_preferred;
+use crate::value_transform::ValueTransformCallback;
+use glide_core::{
+    client::{
+        AuthenticationInfo, ConnectionRequest, ConnectionRetryStrategy, NodeAddress, PeriodicCheck,
+        ReadFrom as coreReadFrom, TlsMode,
+    },
+    request_type::RequestType,
 };
 use redis::{
     cluster_routing::{
         MultipleNodeRoutingInfo, ResponsePolicy, Routable, Route, RoutingInfo,
         SingleNodeRoutingInfo, SlotAddr,
     },
-    Cmd, Value,
+    AddressFamily, Cmd, ErrorKind, Pipeline, RedisError, RedisResult, Value,
 };
 
 /// Convert raw C string to a rust string.
@@ -62,14 +73,221 @@ pub struct ConnectionConfig {
     pub connection_retry_strategy: ConnectionRetryStrategy,
     pub has_authentication_info: bool,
     pub authentication_info: Credentials,
+    /// Index of the logical database of the connections, selected by `SELECT` unless it is `0`.
+    ///
+    /// The protocol, the credentials, the database and the client name are applied by glide-core in a fixed order on every new
+    /// connection, including reconnects and the connections of [`ConnectionConfig::connections_per_node`]:
+    /// `HELLO` with the protocol and the credentials (or `AUTH` with RESP2), then `SELECT`, then `CLIENT SETNAME`.
+    /// The connection is only used once all of them succeeded, otherwise it is dropped and the connection attempt fails.
     pub database_id: u32,
     pub has_protocol: bool,
+    /// Protocol to negotiate with the server, RESP3 is used if not set. See [`ConnectionConfig::database_id`] for the setup order.
+    /// See [`ResponseValue::from_value`] for differences in returned values.
     pub protocol: redis::ProtocolVersion,
-    /// zero pointer is valid, means no client name is given (`None`)
+    /// zero pointer is valid, means no client name is given (`None`). See [`ConnectionConfig::database_id`] for the setup order.
     pub client_name: *const c_char,
+    /// Maximum amount of in-flight requests per node, `0` means no limit. Further requests to the node fail with a `Backpressure` error.
+    pub max_inflight_requests_per_node: u32,
+    /// In-flight requests depth above which a node is reported as hot, `0` disables the detection.
+    pub hot_node_threshold: u32,
+    /// Duration in milliseconds the depth of a node should stay above `hot_node_threshold` before the node is reported as hot.
+    /// The depth is checked every 100 milliseconds, so the node is reported up to 100 milliseconds later.
+    pub hot_node_duration: u32,
+    /// Fail commands immediately with an `Unavailable` error when they are routed to a node which is considered unhealthy,
+    /// instead of waiting for glide-core to reconnect or to settle the topology.
+    /// A node is considered unhealthy after a command sent to it failed with a connection or a cluster-down error,
+    /// until the next topology refresh. This trades availability for latency - commands which could succeed
+    /// after a short failover fail instead. Only affects commands sent to a single node in cluster mode.
+    pub fail_fast_on_degraded: bool,
+    /// Values of at least this size in bytes are compressed with LZ4 by `set_compressed`, `0` disables compression.
+    pub compression_threshold: u32,
+    pub has_default_route: bool,
+    /// Route of commands submitted without an explicit route. See [`DefaultRoute`] for the precedence and the commands it applies to.
+    pub default_route: RouteInfo,
+    /// Latency in milliseconds above which commands are reported through the slow command callback, `0` disables the reporting.
+    /// The latency is measured from sending the command to receiving its response, excluding the conversion of arguments and results.
+    /// Only the first attempt is measured, excluding re-authentication, the retry of writes which reached a replica and fallbacks to another node.
+    /// The callback is called by a separate thread, so it doesn't delay the commands, but it might be called after the command completed.
+    /// Up to 1024 slow commands wait for the callback, further ones are dropped and counted by `ClientStatistics::dropped_slow_commands`.
+    pub slow_command_threshold: u32,
+    /// Amount of freed responses passed to the success callback to keep for reuse, instead of allocating a new one per response,
+    /// `0` disables pooling. Every client has its own pool.
+    pub response_pool_capacity: u32,
+    /// Interval in seconds of a proactive topology refresh in cluster mode, which catches slot migrations before commands
+    /// are redirected. `0` disables it, leaving the default periodic checks of glide-core.
+    pub periodic_topology_refresh_secs: u32,
+    /// Log a warning for every command argument which isn't valid UTF-8. The arguments are sent as is, since binary
+    /// arguments are legal, so this is a diagnostic aid for catching encoding bugs, e.g. strings which were encoded twice.
+    pub log_non_utf8_arguments: bool,
+    /// Window in milliseconds after a write to a slot, in which reads of the slot submitted without an explicit route
+    /// are routed to the primary, so they observe the write even if the replicas haven't applied it yet (read-your-writes).
+    /// It only applies in cluster mode. `0` disables it, so reads may be served by replicas according to `read_from` and may be stale.
+    pub read_your_writes_window_ms: u32,
+    /// Rate of `MOVED` and `ASK` redirections per second above which a `Redirection` event is reported, only in cluster mode.
+    /// `0` disables the event. The redirections are counted regardless, see [`get_statistics`](crate::get_statistics).
+    pub redirection_event_threshold: u32,
+    /// Whether the commands listed in `command_filter` are the only allowed commands or denied, see [`CommandFilter`].
+    /// Commands which aren't allowed fail with a `CommandNotAllowed` error before they are dispatched.
+    pub command_filter_mode: CommandFilterMode,
+    pub command_filter_count: u32,
+    /// Pointer to an array of `command_filter_count` request types, could be `null` if the count is `0`.
+    pub command_filter: *const RequestType,
+    /// When a command fails because its connection lost its authentication (`NOAUTH`), e.g. after a server restart,
+    /// re-issue `AUTH` with the configured credentials on the node and retry the command once, instead of failing it.
+    /// If re-authenticating fails, the command fails with a `ReauthenticationFailed` error.
+    pub reauthenticate_on_noauth: bool,
+    /// Coalesce concurrent identical reads (the same request type and arguments) into a single round trip to the server,
+    /// whose result is passed to all of them, e.g. to relieve the server from a burst of reads of a key on a cache miss.
+    /// Only reads without a route and a deadline are coalesced, and results aren't cached beyond the round trip.
+    pub deduplicate_reads: bool,
+    /// Maximal size in bytes of a response converted to a [`ResponseValue`], `0` means no limit.
+    /// Larger responses, e.g. of `KEYS *` on a huge database, fail with a `ResponseTooLarge` error instead of being converted,
+    /// as a safety valve against running out of memory. Note that the reply is still received before its size is checked.
+    pub max_response_bytes: u64,
+    /// Enable server-assisted client-side caching (`CLIENT TRACKING`) on the connections, on all nodes in cluster mode.
+    /// Keys invalidated by the server are passed to the invalidation callback of [`create_client`](crate::create_client).
+    /// Tracking is enabled again whenever a connection is re-established. Requires the RESP3 protocol, since invalidations are pushed on the tracked connections.
+    pub client_tracking_mode: ClientTrackingMode,
+    pub client_tracking_prefix_count: u32,
+    /// Pointer to an array of `client_tracking_prefix_count` C strings, the prefixes of the keys to invalidate in
+    /// [`ClientTrackingMode::Broadcast`], all keys if the count is `0`. Could be `null` if the count is `0`.
+    pub client_tracking_prefixes: *const *const c_char,
+    /// Disable Nagle's algorithm (`TCP_NODELAY`) on the sockets of all connections, including reconnections,
+    /// so small commands are sent immediately instead of being delayed until previous writes are acknowledged.
+    /// `false` in a zeroed configuration, so Nagle's algorithm is used unless it is set, while the C# client builders set it by default.
+    pub tcp_nodelay: bool,
+    /// Retry a read once on the primary of its slot, if it was served by a replica which failed with a connection or
+    /// unavailability error, e.g. because the replica is down. Other errors, e.g. `WRONGTYPE`, are reported as is.
+    /// Applies to reads routed to a replica, and to reads without a route if `read_from` serves them by replicas. Only in cluster mode.
+    pub fallback_to_primary_on_replica_error: bool,
+    /// Prefix prepended to the keys of commands submitted by [`command`](crate::command) and [`command_by_name`](crate::command_by_name),
+    /// to namespace the keys of a tenant in a shared database. Could be `null`, which means keys aren't prefixed.
+    /// The positions of the keys are found by the key specifications the server reports by `COMMAND`, which is queried
+    /// when the client is created. The patterns of `KEYS` and `SCAN` are prefixed too, so they only match keys with the prefix.
+    /// Routes by a slot key are routed by the prefixed key.
+    pub key_prefix: *const c_char,
+    /// Strip the [`key_prefix`](Self::key_prefix) from the keys in the responses of `KEYS`, `SCAN` and `RANDOMKEY`,
+    /// and the [`default_hash_tag`](Self::default_hash_tag).
+    pub strip_key_prefix: bool,
+    /// Maximal amount of commands and batches which were submitted and whose callback wasn't called yet, `0` means no limit.
+    /// Further commands fail right away with a `Backpressure` error, until pending commands complete. Unlike
+    /// [`max_inflight_requests_per_node`](Self::max_inflight_requests_per_node), this limits the whole client.
+    /// The pending commands are exposed by [`get_statistics`](crate::get_statistics).
+    pub max_pending_commands: u32,
+    /// Return null instead of an empty array, set or map as the reply of a command, so "nothing" is always null, e.g.
+    /// `LRANGE`, `SMEMBERS` or `HGETALL` of a missing key and `KEYS` without matches, like `GET` of a missing key.
+    /// Only the reply of a command as a whole is replaced, and in a batch the reply of each of its commands.
+    /// Empty collections nested in a reply are kept, e.g. the empty array of keys in the reply of a `SCAN` without matches.
+    /// Disabled by default, so replies keep the distinction made by the server.
+    pub normalize_empty_replies: bool,
+    /// Return the replies of commands with their metadata, as [`ValueType::WithMetadata`] values. The metadata is a map of:
+    /// * `source` - the kind of node which served the reply: `primary`, `replica`, or `unknown` if the node was chosen by
+    ///   the `read_from` strategy or the command was sent to multiple nodes.
+    /// * `node` - the address of the node, if it is known. The address of a replica is only known if it was routed to by its address.
+    /// * `attributes` - the RESP3 attributes the server sent with the reply as a map, e.g. about the encoding of the value, or null.
+    ///
+    /// The metadata is part of the response, and it is freed with it by [`free_respose`](crate::free_respose).
+    /// Applies to commands submitted by [`command`](crate::command), [`command_by_name`](crate::command_by_name) and most command helpers,
+    /// but not to batches, to commands on leased connections, or to blocking pops. Requests coalesced by [`deduplicate_reads`](Self::deduplicate_reads) share the metadata of the request which was sent.
+    /// If disabled, no metadata is collected, and attributes sent by the server are dropped.
+    pub response_metadata: bool,
+    /// Format of the error messages passed to the failure callback, e.g. JSON for structured logging.
+    /// Batches executed without raising errors still store the human readable messages of the failed commands.
+    pub error_message_format: ErrorMessageFormat,
+    /// Server name sent by SNI during the TLS handshake, and validated against the certificates of the servers, instead of
+    /// the hosts of the addresses, e.g. when connecting by IP through a load balancer to servers whose certificates are issued
+    /// for a hostname. Applies to all nodes, including the nodes discovered in cluster mode. Could be `null`, which means the
+    /// host of every node is used. Ignored without TLS.
+    pub tls_sni_hostname: *const c_char,
+    /// Number of connections to every node, which the commands of the client are spread across in round-robin, so a large reply
+    /// only delays the commands sent on its own connection. `0` or `1` means a single connection, which all commands are pipelined on.
+    /// Every additional connection is a separate client with its own connection to every node and read buffers, and in cluster mode
+    /// its own slot map and periodic topology checks, so the memory and sockets of the client grow about linearly with the count.
+    /// Commands submitted concurrently could be executed in a different order than submitted, since they could be sent on different
+    /// connections; commands which rely on the state of the connection, e.g. `WATCH` or `SELECT`, should use a leased connection.
+    pub connections_per_node: u32,
+    /// Maximal size in bytes of an argument of a command submitted by [`command`](crate::command) or
+    /// [`command_by_name`](crate::command_by_name), `0` means no limit. Commands with a larger argument, e.g. a bug serializing
+    /// a whole dataset into a single value, fail with an `ArgumentTooLarge` error before the arguments are copied or sent.
+    /// A client-side guardrail complementing the `proto-max-bulk-len` of the server.
+    pub max_command_arg_bytes: u32,
+    /// Number of the last commands recorded in memory, retrievable by [`get_command_trace`](crate::get_command_trace) to reproduce
+    /// issues, `0` disables recording. Commands submitted by [`command`](crate::command), [`command_by_name`](crate::command_by_name)
+    /// and most command helpers are recorded once they complete, with their name, route, node, latency and result type.
+    pub command_trace_capacity: u32,
+    /// Record the full arguments of the commands in the trace, instead of a hash of them. The arguments could contain
+    /// sensitive data, and copying them adds to the overhead of recording.
+    pub command_trace_full_args: bool,
+    /// Delay in milliseconds between submitting a command or a batch and the start of its task on the runtime, above which
+    /// a `RuntimeSaturated` event is reported through the event callback, `0` disables the detection. A growing delay means
+    /// the worker threads can't keep up with the load, which calls for shedding load or adding clients, unlike a slow server.
+    pub runtime_saturation_threshold: u32,
+    /// Dispatch the commands on the same key one at a time, in the order they were submitted, e.g. so `LRANGE` observes a
+    /// preceding `LPUSH` of the same key, while commands on different keys remain concurrent. A command is dispatched once
+    /// the previous command on its key completed, so the throughput of a single hot key is limited to one round trip at a time.
+    /// Applies to commands routed by a single key submitted by [`command`](crate::command), [`command_by_name`](crate::command_by_name)
+    /// and most command helpers, but not to commands on multiple keys, e.g. `MGET`, to batches, or to blocking pops.
+    /// The deadline of a command includes the time it waited for its turn.
+    pub order_commands_per_key: bool,
+    /// Interval in seconds of a `PING` on idle connections, which keeps them alive at the application layer, e.g. behind proxies
+    /// which close connections without traffic regardless of TCP keepalive. `0` disables it. In standalone mode the connections
+    /// are checked every half interval, and pinged if no command was sent by them since the previous check, so no connection
+    /// is idle for longer than the interval. In cluster mode the connections to all nodes are pinged every interval,
+    /// since a connection used for some nodes may still be idle on others. The interval should be shorter than the idle timeout of the proxy.
+    pub client_keepalive_interval_secs: u32,
+    /// Hash tag wrapped around the keys of commands submitted by [`command`](crate::command) and [`command_by_name`](crate::command_by_name),
+    /// as `{tag}key`, so all keys of the client map to the same slot, e.g. for multi-key commands and transactions in cluster mode.
+    /// Could be `null` or empty, which means keys aren't wrapped. It must not contain `}`, which would end the hash tag early.
+    /// The tag is added like [`key_prefix`](Self::key_prefix), after it if both are set, so keys are `prefix{tag}key`.
+    /// Since only the first hash tag of a key determines its slot, hash tags already included in the keys, or in the prefix,
+    /// take no effect, or override the default hash tag respectively.
+    pub default_hash_tag: *const c_char,
+    /// Number of consecutive commands failing with `CLUSTERDOWN` after which the circuit breaker opens, `0` disables it.
+    /// While the breaker is open, commands and batches fail fast with a `ClusterDown` error instead of being retried against
+    /// the recovering cluster. A `CircuitOpen` event is reported once it opens, and a `CircuitClosed` event once the topology
+    /// is refreshed successfully after a backoff, which starts at 500 milliseconds and doubles while the cluster keeps failing,
+    /// up to 10 seconds. Only applies in cluster mode.
+    pub circuit_breaker_threshold: u32,
+    /// Encoder of the values of commands submitted by [`command`](crate::command) and [`command_by_name`](crate::command_by_name),
+    /// e.g. to compress or encrypt values before they leave the process, see [`ValueTransformCallback`]. Only the values of known
+    /// string and hash commands are encoded, e.g. of `SET`, `MSET` and `HSET`, never their keys or hash fields.
+    /// Could be `null`, which means values are sent as is. It must be set together with [`value_decoder`](Self::value_decoder).
+    /// Commands of batches are not transformed.
+    pub value_encoder: Option<ValueTransformCallback>,
+    /// Decoder of the values in the responses of known string and hash commands, the inverse of [`value_encoder`](Self::value_encoder).
+    /// It is called with every value read by those commands, including values which weren't encoded, e.g. values stored
+    /// before the encoder was set, so it should pass them through if it can tell them apart.
+    pub value_decoder: Option<ValueTransformCallback>,
+    /// Retry a write once on the primary of its slot, if it failed with `READONLY` because it reached a replica, e.g. by a stale
+    /// topology during a failover. The topology is refreshed first, and the write is sent to the primary it reports for the slot.
+    /// A write which fails with `READONLY` again, writes routed to multiple nodes or by address, and reads are reported as is,
+    /// with a [`RequestErrorType::ReadOnly`](crate::errors::RequestErrorType::ReadOnly) error. Only in cluster mode.
+    pub retry_writes_on_readonly: bool,
+    /// Time in milliseconds spent in a success or failure callback above which a warning is logged with the callback index
+    /// of the request, `0` disables timing. Callbacks are called by the threads of the client, so a callback which blocks
+    /// delays the results of other requests, and the warning identifies it.
+    pub slow_callback_threshold_ms: u32,
+    /// Call the success and failure callbacks on the blocking thread pool of the client, rather than on the threads which
+    /// run the requests, so a slow callback can't starve them. Each callback takes a thread of the pool until it returns,
+    /// and the pool starts more threads as needed, up to its limit, after which callbacks wait for a free thread.
+    /// Callbacks are no longer called in the order the requests completed, and callbacks of different requests may run
+    /// concurrently. The failure callbacks of requests rejected before they were submitted are offloaded too,
+    /// so they may be called after the function which submitted the request returned.
+    pub offload_callbacks: bool,
+    /// Provider of fresh passwords, e.g. short-lived IAM tokens, see [`CredentialProviderCallback`]. Could be `null`.
+    /// The password of the first connections is pulled when the client is created, replacing the password of
+    /// `authentication_info`, and glide-core authenticates the connections it establishes by it.
+    /// Once a connection is lost or fails to connect, a fresh password is pulled and stored, so the connection is established
+    /// again with it. The first attempt to reconnect may still use the previous password, and the following attempts use the fresh one.
+    /// With [`reauthenticate_on_noauth`](Self::reauthenticate_on_noauth), a fresh password is pulled to re-authenticate too.
+    pub credential_provider: Option<CredentialProviderCallback>,
+    /// IP version of the addresses the hosts are resolved to and connected by, for the addresses of the configuration,
+    /// the nodes discovered in cluster mode, and reconnections. [`AddressFamily::Auto`] connects by any address of a host,
+    /// trying all of them concurrently. Restricting it, e.g. to IPv4 when IPv6 routes are blocked, avoids waiting for the
+    /// connection timeout on unreachable addresses. Hosts without an address of the family fail to connect.
+    pub address_family: AddressFamily,
     /*
     TODO below
-    pub periodic_checks: Option<PeriodicCheck>,
     pub pubsub_subscriptions: Option<redis::PubSubSubscriptionInfo>,
     pub inflight_requests_limit: Option<u32>,
     pub otel_endpoint: Option<String>,
@@ -77,7 +295,76 @@ pub struct ConnectionConfig {
     */
 }
 
+/// Create the command filter of the connection configuration, `None` if it is disabled.
+///
+/// # Safety
+///
+/// * `config` must be a valid pointer to a [`ConnectionConfig`] struct.
+/// * `command_filter` must point to `command_filter_count` consecutive valid request types, unless the count is `0`.
+pub(crate) unsafe fn create_command_filter(
+    config: *const ConnectionConfig,
+) -> Option<CommandFilter> {
+    let request_types = match unsafe { (*config).command_filter_count } {
+        0 => &[][..],
+        count => unsafe { from_raw_parts((*config).command_filter, count as usize) },
+    };
+    CommandFilter::new(unsafe { (*config).command_filter_mode }, request_types)
+}
+
+/// Create the client tracking configuration of the connection configuration, `None` if it is disabled.
+///
+/// # Safety
+///
+/// * `config` must be a valid pointer to a [`ConnectionConfig`] struct.
+/// * `client_tracking_prefixes` must point to `client_tracking_prefix_count` consecutive valid C string pointers, unless the count is `0`.
+///   See the safety documentation of [`ptr_to_str`].
+pub(crate) unsafe fn create_client_tracking(
+    config: *const ConnectionConfig,
+) -> Option<ClientTracking> {
+    let prefixes = match unsafe { (*config).client_tracking_prefix_count } {
+        0 => vec![],
+        count => unsafe { from_raw_parts((*config).client_tracking_prefixes, count as usize) }
+            .iter()
+            .map(|&prefix| unsafe { ptr_to_str(prefix) })
+            .collect(),
+    };
+    ClientTracking::new(unsafe { (*config).client_tracking_mode }, prefixes)
+}
+
+/// Get the key prefix of the connection configuration, `None` if it is `null` or empty.
+///
+/// # Safety
+///
+/// * `config` must be a valid pointer to a [`ConnectionConfig`] struct.
+/// * `key_prefix` could be `null`, but if it is not `null`, it must be a valid C string pointer.
+///   See the safety documentation of [`std::ffi::CStr::from_ptr`].
+pub(crate) unsafe fn key_prefix(config: *const ConnectionConfig) -> Option<Vec<u8>> {
+    let key_prefix = unsafe { (*config).key_prefix };
+    if key_prefix.is_null() {
+        return None;
+    }
+    let key_prefix = unsafe { CStr::from_ptr(key_prefix) }.to_bytes();
+    (!key_prefix.is_empty()).then(|| key_prefix.to_vec())
+}
+
+/// Get the default hash tag of the connection configuration, without braces, `None` if it is `null` or empty.
+///
+/// # Safety
+///
+/// * `config` must be a valid pointer to a [`ConnectionConfig`] struct.
+/// * `default_hash_tag` could be `null`, but if it is not `null`, it must be a valid C string pointer.
+///   See the safety documentation of [`std::ffi::CStr::from_ptr`].
+pub(crate) unsafe fn default_hash_tag(config: *const ConnectionConfig) -> Option<Vec<u8>> {
+    let default_hash_tag = unsafe { (*config).default_hash_tag };
+    if default_hash_tag.is_null() {
+        return None;
+    }
+    let default_hash_tag = unsafe { CStr::from_ptr(default_hash_tag) }.to_bytes();
+    (!default_hash_tag.is_empty()).then(|| default_hash_tag.to_vec())
+}
+
 /// Convert connection configuration to a corresponding object.
+/// Fails with an [`ErrorKind::InvalidClientConfig`] error if the configuration is invalid, see [`validate_config`].
 ///
 /// # Safety
 ///
@@ -87,8 +374,9 @@ pub struct ConnectionConfig {
 ///   See the safety documentation of [`convert_node_addresses`], [`ptr_to_str`] and [`ptr_to_opt_str`].
 pub(crate) unsafe fn create_connection_request(
     config: *const ConnectionConfig,
-) -> ConnectionRequest {
-    ConnectionRequest {
+) -> RedisResult<ConnectionRequest> {
+    unsafe { validate_config(config) }?;
+    Ok(ConnectionRequest {
         read_from: if (*config).has_read_from {
             Some(match (*config).read_from.strategy {
                 ReadFromStrategy::Primary => coreReadFrom::Primary,
@@ -137,18 +425,123 @@ pub(crate) unsafe fn create_connection_request(
         } else {
             None
         },
+        tcp_nodelay: (*config).tcp_nodelay,
+        tls_sni_hostname: unsafe { ptr_to_opt_str((*config).tls_sni_hostname) },
+        address_family: (*config).address_family,
         connection_retry_strategy: if (*config).has_connection_retry_strategy {
             Some((*config).connection_retry_strategy)
         } else {
             None
         },
+        periodic_checks: ((*config).periodic_topology_refresh_secs > 0).then(|| {
+            PeriodicCheck::ManualInterval(Duration::from_secs(
+                (*config).periodic_topology_refresh_secs.into(),
+            ))
+        }),
+        // Set by the client, which owns the redirection counters.
+        redirection_observer: None,
+        // Set by the client, which owns the connection timings.
+        connection_observer: None,
         // TODO below
-        periodic_checks: None,
         pubsub_subscriptions: None,
         inflight_requests_limit: None,
         otel_endpoint: None,
         otel_span_flush_interval_ms: None,
+    })
+}
+
+/// Check the configuration for invalid or inconsistent fields, which would otherwise fail late with an unclear error,
+/// e.g. while connecting. The error is an [`ErrorKind::InvalidClientConfig`] error, whose detail names the field and why it is invalid.
+///
+/// # Safety
+///
+/// See the safety documentation of [`create_connection_request`].
+unsafe fn validate_config(config: *const ConnectionConfig) -> RedisResult<()> {
+    let invalid = |field: &str, reason: &str| {
+        Err(RedisError::from((
+            ErrorKind::InvalidClientConfig,
+            "Invalid configuration",
+            format!("{field}: {reason}"),
+        )))
+    };
+    let config = unsafe { &*config };
+    // Strings are converted by `ptr_to_str`, which requires valid UTF-8
+    let is_utf8 =
+        |ptr: *const c_char| ptr.is_null() || unsafe { CStr::from_ptr(ptr) }.to_str().is_ok();
+    let is_empty = |ptr: *const c_char| ptr.is_null() || unsafe { CStr::from_ptr(ptr) }.is_empty();
+
+    if config.address_count == 0 || config.addresses.is_null() {
+        return invalid("addresses", "at least one address is required");
+    }
+    let addresses =
+        unsafe { from_raw_parts(config.addresses as *const Address, config.address_count) };
+    for (index, address) in addresses.iter().enumerate() {
+        if is_empty(address.host) || !is_utf8(address.host) {
+            return invalid(
+                &format!("addresses[{index}].host"),
+                "the host must be a non-empty UTF-8 string",
+            );
+        }
+        if address.port == 0 {
+            return invalid(
+                &format!("addresses[{index}].port"),
+                "the port must be between 1 and 65535",
+            );
+        }
+    }
+    if config.has_read_from
+        && matches!(
+            config.read_from.strategy,
+            ReadFromStrategy::AZAffinity | ReadFromStrategy::AZAffinityReplicasAndPrimary
+        )
+        && (is_empty(config.read_from.az) || !is_utf8(config.read_from.az))
+    {
+        return invalid(
+            "read_from.az",
+            "the availability zone of the client is required by the AZ affinity strategies",
+        );
+    }
+    if config.has_authentication_info {
+        let credentials = &config.authentication_info;
+        if !credentials.username.is_null()
+            && credentials.password.is_null()
+            && config.credential_provider.is_none()
+        {
+            return invalid(
+                "authentication_info.password",
+                "a password or a credential provider is required with a username",
+            );
+        }
+        if !is_utf8(credentials.username) || !is_utf8(credentials.password) {
+            return invalid(
+                "authentication_info",
+                "the username and the password must be UTF-8 strings",
+            );
+        }
+    }
+    let has_tls = config.has_tls && !matches!(config.tls_mode, TlsMode::NoTls);
+    if !config.tls_sni_hostname.is_null() && !has_tls {
+        return invalid("tls_sni_hostname", "a server name requires TLS");
+    }
+    if !is_utf8(config.client_name) {
+        return invalid("client_name", "the name must be a UTF-8 string");
+    }
+    if !is_utf8(config.tls_sni_hostname) {
+        return invalid("tls_sni_hostname", "the server name must be a UTF-8 string");
+    }
+    if config.value_encoder.is_some() != config.value_decoder.is_some() {
+        return invalid(
+            "value_decoder",
+            "the value encoder and the value decoder must be set together",
+        );
+    }
+    if unsafe { default_hash_tag(config) }.is_some_and(|tag| tag.contains(&b'}')) {
+        return invalid(
+            "default_hash_tag",
+            "the hash tag must not contain '}', which would end it early",
+        );
     }
+    Ok(())
 }
 
 /// A mirror of [`NodeAddress`] adopted for FFI.
@@ -198,6 +591,7 @@ pub enum ReadFromStrategy {
 }
 
 /// A mirror of [`AuthenticationInfo`] adopted for FFI.
+/// The credentials are used by `HELLO`/`AUTH` on every connection, including reconnections.
 #[repr(C)]
 pub struct Credentials {
     /// zero pointer is valid, means no username is given (`None`)
@@ -215,6 +609,18 @@ pub enum RouteType {
     SlotId,
     SlotKey,
     ByAddress,
+    /// A replica of a random shard, for commands without keys.
+    RandomReplica,
+    /// The primary of the slot of `slot_key`, or of the command's keys if no slot key is given. Reads fall back to a replica
+    /// of the slot once, if the primary reached its in-flight limit or failed with a connection or unavailability error,
+    /// but not on other errors. Commands without keys and without a slot key are routed by their command-type default.
+    /// A read falls back at most once, so a read which fell back to a replica doesn't fall back to the primary again
+    /// by [`ConnectionConfig::fallback_to_primary_on_replica_error`]. Only in cluster mode.
+    PrimaryPreferred,
+    /// The node of `slot_type` owning the slot of `slot_key`, an arbitrary routing key which isn't a key of the command,
+    /// e.g. `{tenant123}` to send a keyless command to the node holding the keys of that hash tag. Unlike [`RouteType::SlotKey`],
+    /// the routing key isn't prefixed by [`ConnectionConfig::key_prefix`].
+    BySlotKey,
 }
 
 /// A mirror of [`SlotAddr`]
@@ -238,7 +644,7 @@ impl From<&SlotType> for SlotAddr {
 /// Depending on [`RouteType`], the struct stores:
 /// * Only `route_type` is filled, if route is a simple route;
 /// * `route_type`, `slot_id` and `slot_type`, if route is a Slot ID route;
-/// * `route_type`, `slot_key` and `slot_type`, if route is a Slot key route;
+/// * `route_type`, `slot_key` and `slot_type`, if route is a Slot key route or a By slot key route;
 /// * `route_type`, `hostname` and `port`, if route is a Address route;
 #[repr(C)]
 #[derive(Debug, Clone, Copy)]
@@ -261,6 +667,20 @@ pub struct RouteInfo {
 /// * `slot_key` and `hostname` in dereferenced [`RouteInfo`] struct must contain valid string pointers when corresponding `route_type` is set.
 ///   See description of [`RouteInfo`] and the safety documentation of [`ptr_to_str`].
 pub(crate) unsafe fn create_route(route_info: *const RouteInfo, cmd: &Cmd) -> Option<RoutingInfo> {
+    unsafe { create_route_with_key_prefix(route_info, cmd, &[]) }
+}
+
+/// Convert route configuration to a corresponding object, like [`create_route`], where a slot key is prefixed by `key_prefix`,
+/// see [`ConnectionConfig::key_prefix`].
+///
+/// # Safety
+///
+/// See the safety documentation of [`create_route`].
+pub(crate) unsafe fn create_route_with_key_prefix(
+    route_info: *const RouteInfo,
+    cmd: &Cmd,
+    key_prefix: &[u8],
+) -> Option<RoutingInfo> {
     if route_info.is_null() {
         return None;
     }
@@ -282,7 +702,9 @@ pub(crate) unsafe fn create_route(route_info: *const RouteInfo, cmd: &Cmd) -> Op
         )),
         RouteType::SlotKey => Some(RoutingInfo::SingleNode(
             SingleNodeRoutingInfo::SpecificNode(Route::new(
-                redis::cluster_topology::get_slot(ptr_to_str((*route_info).slot_key).as_bytes()),
+                redis::cluster_topology::get_slot(
+                    &[key_prefix, ptr_to_str((*route_info).slot_key).as_bytes()].concat(),
+                ),
                 (&(*route_info).slot_type).into(),
             )),
         )),
@@ -290,6 +712,209 @@ pub(crate) unsafe fn create_route(route_info: *const RouteInfo, cmd: &Cmd) -> Op
             host: ptr_to_str((*route_info).hostname),
             port: (*route_info).port as u16,
         })),
+        RouteType::BySlotKey => Some(RoutingInfo::SingleNode(
+            SingleNodeRoutingInfo::SpecificNode(Route::new(
+                redis::cluster_topology::get_slot(ptr_to_str((*route_info).slot_key).as_bytes()),
+                (&(*route_info).slot_type).into(),
+            )),
+        )),
+        RouteType::RandomReplica => Some(random_replica()),
+        RouteType::PrimaryPreferred => {
+            if (*route_info).slot_key.is_null() {
+                match RoutingInfo::for_routable(cmd) {
+                    Some(RoutingInfo::SingleNode(SingleNodeRoutingInfo::SpecificNode(route))) => {
+                        Some(primary_preferred(route.slot()))
+                    }
+                    _ => None,
+                }
+            } else {
+                Some(primary_preferred(redis::cluster_topology::get_slot(
+                    &[key_prefix, ptr_to_str((*route_info).slot_key).as_bytes()].concat(),
+                )))
+            }
+        }
+    }
+}
+
+/// Convert the default route configuration to an owned object, which outlives the configuration.
+///
+/// # Safety
+///
+/// * `slot_key` and `hostname` in `route_info` must contain valid string pointers when corresponding `route_type` is set.
+///   See description of [`RouteInfo`] and the safety documentation of [`ptr_to_str`].
+pub(crate) unsafe fn create_default_route(route_info: &RouteInfo) -> DefaultRoute {
+    match route_info.route_type {
+        RouteType::Random => DefaultRoute::Random,
+        RouteType::AllNodes => DefaultRoute::AllNodes,
+        RouteType::AllPrimaries => DefaultRoute::AllPrimaries,
+        RouteType::SlotId => DefaultRoute::Slot {
+            slot: route_info.slot_id as u16,
+            slot_addr: (&route_info.slot_type).into(),
+        },
+        RouteType::SlotKey | RouteType::BySlotKey => DefaultRoute::Slot {
+            slot: redis::cluster_topology::get_slot(
+                unsafe { ptr_to_str(route_info.slot_key) }.as_bytes(),
+            ),
+            slot_addr: (&route_info.slot_type).into(),
+        },
+        RouteType::ByAddress => DefaultRoute::ByAddress {
+            host: unsafe { ptr_to_str(route_info.hostname) },
+            port: route_info.port as u16,
+        },
+        RouteType::RandomReplica => DefaultRoute::RandomReplica,
+        RouteType::PrimaryPreferred => DefaultRoute::PrimaryPreferred,
+    }
+}
+
+/// In-flight requests depth of a single node.
+#[repr(C)]
+#[derive(Debug)]
+pub struct NodeInflightInfo {
+    /// Node address in `host:port` format.
+    pub address: *const c_char,
+    pub depth: u32,
+}
+
+/// In-flight requests depth of all nodes the client sent commands to.
+#[repr(C)]
+#[derive(Debug)]
+pub struct NodeInflightStatistics {
+    pub node_count: usize,
+    /// Pointer to an array of [`NodeInflightInfo`].
+    pub nodes: *const NodeInflightInfo,
+}
+
+impl NodeInflightStatistics {
+    pub(crate) fn new(depths: Vec<(String, u32)>) -> Self {
+        let nodes: Vec<NodeInflightInfo> = depths
+            .into_iter()
+            .map(|(address, depth)| NodeInflightInfo {
+                address: CString::new(address).unwrap_or_default().into_raw(),
+                depth,
+            })
+            .collect();
+        let (nodes, node_count) = convert_vec_to_pointer(nodes);
+        NodeInflightStatistics { node_count, nodes }
+    }
+
+    /// Restore ownership and free all memory allocated by the current [`NodeInflightStatistics`].
+    ///
+    /// # Safety
+    /// * The struct must be created by [`NodeInflightStatistics::new`].
+    pub(crate) unsafe fn free_memory(&self) {
+        let nodes = unsafe {
+            Vec::from_raw_parts(
+                self.nodes as *mut NodeInflightInfo,
+                self.node_count,
+                self.node_count,
+            )
+        };
+        for node in nodes {
+            drop(unsafe { CString::from_raw(node.address as *mut c_char) });
+        }
+    }
+}
+
+/// A structure which represents a single command of a batch.
+#[repr(C)]
+#[derive(Debug)]
+pub struct CmdInfo {
+    pub request_type: RequestType,
+    /// Pointer to an array of argument pointers.
+    pub args: *const *const u8,
+    pub arg_count: u32,
+    /// Pointer to an array of argument lengths.
+    pub args_len: *const u32,
+}
+
+/// A structure which represents a batch of commands - a pipeline or a transaction.
+#[repr(C)]
+#[derive(Debug)]
+pub struct BatchInfo {
+    pub cmd_count: u32,
+    /// Pointer to an array of [`CmdInfo`] pointers.
+    pub cmds: *const *const CmdInfo,
+    pub is_atomic: bool,
+}
+
+/// Options of a batch execution.
+/// In cluster mode, a non-atomic batch is split by the nodes the commands route to, sub-pipelines are sent
+/// concurrently and the results are returned in the order of the commands. Commands without keys are sent to a random node.
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct BatchOptionsInfo {
+    /// Retry commands of a non-atomic batch which failed with a retriable server error (e.g. `TRYAGAIN`).
+    /// Might cause reordering of commands within the same slot.
+    pub retry_server_error: bool,
+    /// Retry sub-pipelines of a non-atomic batch on connection errors. Might cause duplicate executions.
+    pub retry_connection_error: bool,
+    pub has_timeout: bool,
+    pub timeout: u32,
+    /// Return the results completed before the timeout of a non-atomic batch, rather than failing the whole batch with a `Timeout` error.
+    /// The batch is sent by consecutive sub-pipelines of at most this many commands, each once the previous one completed, so the
+    /// commands are executed in the order they were given. Once the timeout expires, the response contains the results of the
+    /// completed sub-pipelines in their positions, and a `Timeout` error value in the position of every other command, so only
+    /// those could be retried. Commands of the sub-pipeline in flight may have been executed nevertheless.
+    /// `0` sends the batch at once, without partial results. Requires a timeout, and isn't supported for atomic batches.
+    pub partial_results_chunk_size: u32,
+}
+
+/// Convert batch configuration to a corresponding [`Pipeline`].
+/// Returns `None` if any of the commands has an unknown request type.
+///
+/// # Safety
+///
+/// * `batch` must not be `null` and must be a valid pointer to a [`BatchInfo`] struct.
+/// * `cmds` in the dereferenced [`BatchInfo`] must point to `cmd_count` consecutive valid [`CmdInfo`] pointers.
+/// * Arguments of each [`CmdInfo`] must be valid. See the safety documentation of [`convert_double_pointer_to_vec`].
+pub(crate) unsafe fn create_pipeline(batch: *const BatchInfo) -> Option<Pipeline> {
+    let cmd_pointers = unsafe { from_raw_parts((*batch).cmds, (*batch).cmd_count as usize) };
+    let mut pipeline = Pipeline::with_capacity(cmd_pointers.len());
+    if unsafe { (*batch).is_atomic } {
+        pipeline.atomic();
+    }
+    for &cmd_ptr in cmd_pointers {
+        let cmd_info = unsafe { &*cmd_ptr };
+        let mut cmd = cmd_info.request_type.get_command()?;
+        let args = unsafe {
+            convert_double_pointer_to_vec(
+                cmd_info.args as *const *const c_void,
+                cmd_info.arg_count,
+                cmd_info.args_len,
+            )
+        };
+        for arg in args {
+            cmd.arg(arg);
+        }
+        pipeline.add_command(cmd);
+    }
+    Some(pipeline)
+}
+
+/// Fail with an `ArgumentTooLarge` error if an argument is longer than `max_bytes`, `0` means no limit.
+/// Only the lengths are checked, so the arguments aren't read.
+///
+/// # Safety
+///
+/// * `args_len` must point to `arg_count` consecutive argument lengths, unless `arg_count` or `max_bytes` is `0`.
+pub(crate) unsafe fn check_argument_sizes(
+    args_len: *const u32,
+    arg_count: u32,
+    max_bytes: u32,
+) -> RedisResult<()> {
+    if max_bytes == 0 || arg_count == 0 {
+        return Ok(());
+    }
+    let lengths = unsafe { from_raw_parts(args_len, arg_count as usize) };
+    match lengths.iter().position(|&len| len > max_bytes) {
+        Some(index) => Err(redis::make_extension_error(
+            ARGUMENT_TOO_LARGE_CODE.to_string(),
+            Some(format!(
+                "Argument {index} of {} bytes exceeds the maximal size of {max_bytes} bytes",
+                lengths[index]
+            )),
+        )),
+        None => Ok(()),
     }
 }
 
@@ -318,6 +943,22 @@ pub(crate) unsafe fn convert_double_pointer_to_vec<'a>(
     result
 }
 
+/// Log a warning for every argument which isn't valid UTF-8, without the argument's content, which may be sensitive.
+pub(crate) fn log_non_utf8_arguments(command: &str, args: &[&[u8]]) {
+    for (index, arg) in args.iter().enumerate() {
+        if let Err(err) = std::str::from_utf8(arg) {
+            logger_core::log_warn(
+                "argument encoding",
+                format!(
+                    "Argument {index} of {command} isn't valid UTF-8: invalid sequence at byte {} of {}",
+                    err.valid_up_to(),
+                    arg.len()
+                ),
+            );
+        }
+    }
+}
+
 pub(crate) fn convert_vec_to_pointer<T>(mut vec: Vec<T>) -> (*const T, usize) {
     vec.shrink_to_fit();
     let vec_ptr = vec.as_ptr();
@@ -340,6 +981,17 @@ pub enum ValueType {
     Set = 7,
     BulkString = 8,
     OK = 9,
+    /// A RESP3 verbatim string, stored as `<format>:<text>`, where the format is 3 bytes long, e.g. `txt` or `mkd`.
+    VerbatimString = 10,
+    /// An error in place of the result of a command of a batch executed without raising errors, see [`batch`](crate::batch).
+    /// Stored like an array of two values: the [`RequestErrorType`](crate::errors::RequestErrorType) as an integer and the error message as a string.
+    Error = 11,
+    /// A reply with its metadata, see [`ConnectionConfig::response_metadata`].
+    /// Stored like an array of two values: the reply and a map of its metadata.
+    WithMetadata = 12,
+    /// A RESP3 big number, which is out of the range of [`ValueType::Int`], stored as its decimal string, e.g. `-170141183460469231731687303715884105728`,
+    /// so it isn't truncated.
+    BigNumber = 13,
 }
 
 /// Represents FFI-safe variant of [`Value`].
@@ -348,21 +1000,35 @@ pub enum ValueType {
 ///   while corresponding [`ResponseValue::typ`] is set.
 /// * For complex values, such as [`Value::BulkString`], [`Value::VerbatimString`], [`Value::SimpleString`], only a pointer
 ///   is stored in [`ResponseValue::val`], while corresponding [`ResponseValue::typ`] and [`ResponseValue::size`] are set.
+///   The format of a [`Value::VerbatimString`] is kept as a prefix of the text, see [`ValueType::VerbatimString`].
+///   A [`Value::BigNumber`] is stored as its decimal string, see [`ValueType::BigNumber`].
 /// * Way more complex types are stored by reference. For [`Value::Array`], [`Value::Set`] and [`Value::Map`], in
 ///   [`ResponseValue::val`] a pointer to an array of another [`ResponseValue`] is stored and [`ResponseValue::size`] contains
 ///   the array length (for a map - it is 2x map size).
+/// * Errors returned as values ([`Value::ServerError`]) are stored like an array of their type and message, see [`ValueType::Error`].
 #[repr(C)]
 #[derive(Default, Debug, Clone)]
 pub struct ResponseValue {
     pub typ: ValueType,
     pub val: i64,
-    /// For [`Value::BulkString`], [`Value::VerbatimString`], [`Value::SimpleString`] - size in bytes.
+    /// For [`Value::BulkString`], [`Value::VerbatimString`], [`Value::SimpleString`], [`Value::BigNumber`] - size in bytes.
     /// For Maps, sets and arrays - amount of values [`ResponseValue::val`] points to.
     pub size: u32,
 }
 
 impl ResponseValue {
     /// Build [`ResponseValue`] from a [`Value`].
+    ///
+    /// The value types produced depend on the protocol negotiated by the connection:
+    /// * RESP3 replies may contain [`ValueType::Map`], [`ValueType::Set`], [`ValueType::Float`] and [`ValueType::Bool`].
+    /// * RESP2 has no such types, so the same replies arrive as flat [`ValueType::Array`]s, [`ValueType::BulkString`]s or [`ValueType::Int`]s.
+    ///
+    /// Note that glide-core converts replies of some commands (e.g. `CONFIG GET`, `HGETALL`) to their RESP3 form,
+    /// so those are returned as [`ValueType::Map`] regardless of the protocol.
+    ///
+    /// Integer replies are always [`ValueType::Int`], never strings, e.g. the count of `DEL`, the `0` or `1` of `SETNX`
+    /// or the length of `STRLEN`, and so are RESP3 big numbers in the range of an `i64`. glide-core converts the integer replies
+    /// of some commands to booleans though, e.g. of `EXPIRE`, `HSETNX` or `SISMEMBER`, which are returned as [`ValueType::Bool`].
     pub(crate) fn from_value(value: Value) -> Self {
         match value {
             Value::Nil => ResponseValue {
@@ -430,7 +1096,16 @@ impl ResponseValue {
                 val: if boolean { 1 } else { 0 },
                 size: 0,
             },
-            Value::VerbatimString { format: _, text } | Value::SimpleString(text) => {
+            Value::VerbatimString { format, text } => {
+                let (vec_ptr, len) =
+                    convert_vec_to_pointer(format!("{format}:{text}").into_bytes());
+                ResponseValue {
+                    typ: ValueType::VerbatimString,
+                    val: vec_ptr as i64,
+                    size: len as u32,
+                }
+            }
+            Value::SimpleString(text) => {
                 let (vec_ptr, len) = convert_vec_to_pointer(text.clone().into_bytes());
                 ResponseValue {
                     typ: ValueType::String,
@@ -438,19 +1113,116 @@ impl ResponseValue {
                     size: len as u32,
                 }
             }
-            _ => todo!(), // push, bigint, attribute
+            Value::ServerError(err) => {
+                let err = RedisError::from(err);
+                let vec = vec![
+                    ResponseValue::from_value(Value::Int(error_type(&err) as i64)),
+                    ResponseValue::from_value(Value::SimpleString(error_message(&err))),
+                ];
+                let (vec_ptr, len) = convert_vec_to_pointer(vec);
+                ResponseValue {
+                    typ: ValueType::Error,
+                    val: vec_ptr as i64,
+                    size: len as u32,
+                }
+            }
+            Value::Attribute { data, attributes } => {
+                let vec = vec![
+                    ResponseValue::from_value(*data),
+                    ResponseValue::from_value(Value::Map(attributes)),
+                ];
+                let (vec_ptr, len) = convert_vec_to_pointer(vec);
+                ResponseValue {
+                    typ: ValueType::WithMetadata,
+                    val: vec_ptr as i64,
+                    size: len as u32,
+                }
+            }
+            Value::BigNumber(number) => match i64::try_from(&number) {
+                // A big number in the range of an integer is an integer, like the reply of any other integer command
+                Ok(int) => ResponseValue::from_value(Value::Int(int)),
+                Err(_) => {
+                    let (vec_ptr, len) = convert_vec_to_pointer(number.to_string().into_bytes());
+                    ResponseValue {
+                        typ: ValueType::BigNumber,
+                        val: vec_ptr as i64,
+                        size: len as u32,
+                    }
+                }
+            },
+            _ => todo!(), // push
+        }
+    }
+
+    /// Replace an empty array, set or map by [`Value::Nil`], see [`ConnectionConfig::normalize_empty_replies`].
+    /// Other values are kept as is, including empty collections nested in them.
+    pub(crate) fn normalize_empty(value: Value) -> Value {
+        match value {
+            Value::Array(values) | Value::Set(values) if values.is_empty() => Value::Nil,
+            Value::Map(items) if items.is_empty() => Value::Nil,
+            value => value,
         }
     }
 
+    /// Check whether the memory allocated by [`ResponseValue::from_value`] for `value` would exceed `max_bytes`.
+    /// The size is accumulated while walking the value, so the walk stops as soon as the limit is exceeded,
+    /// without visiting the rest of a huge reply.
+    pub(crate) fn exceeds_size(value: &Value, max_bytes: u64) -> bool {
+        fn consume(value: &Value, remaining: &mut u64) -> bool {
+            let data_len = match value {
+                Value::BulkString(text) => text.len(),
+                Value::SimpleString(text) => text.len(),
+                // The format and a separator are prepended to the text, see `ValueType::VerbatimString`.
+                Value::VerbatimString { text, .. } => text.len() + 4,
+                // An upper bound of the length of the decimal string: a sign and a digit per 3.3 bits, see `ValueType::BigNumber`.
+                Value::BigNumber(number) => number.bits() as usize * 10 / 33 + 2,
+                // The error type and the message are stored as two more values.
+                Value::ServerError(err) => {
+                    2 * std::mem::size_of::<ResponseValue>() + err.details().map_or(0, str::len)
+                }
+                _ => 0,
+            };
+            let size = (std::mem::size_of::<ResponseValue>() + data_len) as u64;
+            if size > *remaining {
+                return false;
+            }
+            *remaining -= size;
+            match value {
+                Value::Array(values) | Value::Set(values) => {
+                    values.iter().all(|value| consume(value, remaining))
+                }
+                Value::Map(items) => items
+                    .iter()
+                    .all(|(key, value)| consume(key, remaining) && consume(value, remaining)),
+                // The reply and the map of its metadata are stored as two more values.
+                Value::Attribute { data, attributes } => {
+                    let map_size = std::mem::size_of::<ResponseValue>() as u64;
+                    consume(data, remaining) && map_size <= *remaining && {
+                        *remaining -= map_size;
+                        attributes.iter().all(|(key, value)| {
+                            consume(key, remaining) && consume(value, remaining)
+                        })
+                    }
+                }
+                _ => true,
+            }
+        }
+        !consume(value, &mut { max_bytes })
+    }
+
     /// Restore ownership and free all memory allocated by the current [`ResponseValue`] and referenced [`ResponseValue`] recursively.
     ///
     /// # Safety
-    /// * [`ResponseValue::val`] must not be `null` if [`ResponseValue::typ`] is [`ValueType::Array`] or [`ValueType::Set`] or [`ValueType::Map`] or [`ValueType::String`] or [`ValueType::BulkString`].
-    /// * [`ResponseValue::val`] must be able to be safely casted to a valid [`Vec<u8>`] (when [`ResponseValue::typ`] is [`ValueType::String`] or [`ValueType::BulkString`])
+    /// * [`ResponseValue::val`] must not be `null` if [`ResponseValue::typ`] is [`ValueType::Array`] or [`ValueType::Set`] or [`ValueType::Map`] or [`ValueType::Error`] or [`ValueType::WithMetadata`] or [`ValueType::String`] or [`ValueType::BulkString`] or [`ValueType::VerbatimString`] or [`ValueType::BigNumber`].
+    /// * [`ResponseValue::val`] must be able to be safely casted to a valid [`Vec<u8>`] (when [`ResponseValue::typ`] is [`ValueType::String`] or [`ValueType::BulkString`] or [`ValueType::VerbatimString`] or [`ValueType::BigNumber`])
     ///   or [`Vec<ResponseValue>`] in other cases via [`Vec::from_raw_parts`]. See the safety documentation of [`Vec::from_raw_parts`].
     pub(crate) unsafe fn free_memory(&self) {
         match self.typ {
-            ValueType::Array | ValueType::Set | ValueType::Map => {
+            ValueType::Array
+            | ValueType::Set
+            | ValueType::Map
+            | ValueType::Error
+            | ValueType::WithMetadata => {
                 let vec = unsafe {
                     Vec::from_raw_parts(
                         self.val as *mut ResponseValue,
@@ -462,7 +1234,10 @@ impl ResponseValue {
                     unsafe { val.free_memory() };
                 }
             }
-            ValueType::String | ValueType::BulkString => {
+            ValueType::String
+            | ValueType::BulkString
+            | ValueType::VerbatimString
+            | ValueType::BigNumber => {
                 let _ = unsafe {
                     Vec::from_raw_parts(self.val as *mut u8, self.size as usize, self.size as usize)
                 };
@@ -471,3 +1246,158 @@ impl ResponseValue {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::errors::RequestErrorType;
+
+    fn by_slot_key_route(routing_key: &str) -> Option<RoutingInfo> {
+        let routing_key = CString::new(routing_key).unwrap();
+        let route_info = RouteInfo {
+            route_type: RouteType::BySlotKey,
+            slot_id: 0,
+            slot_key: routing_key.as_ptr(),
+            slot_type: SlotType::Primary,
+            hostname: std::ptr::null(),
+            port: 0,
+        };
+        unsafe { create_route_with_key_prefix(&route_info, &redis::cmd("DEBUG"), b"prefix:") }
+    }
+
+    #[test]
+    fn test_by_slot_key_route_matches_cluster_keyslot() {
+        // Slots replied by `CLUSTER KEYSLOT`
+        for (routing_key, slot) in [("foo", 12182), ("bar", 5061), ("hello", 866)] {
+            let Some(RoutingInfo::SingleNode(SingleNodeRoutingInfo::SpecificNode(route))) =
+                by_slot_key_route(routing_key)
+            else {
+                panic!("Expected a route to a specific node");
+            };
+            assert_eq!(route.slot(), slot);
+            assert_eq!(route.slot_addr(), SlotAddr::Master);
+        }
+    }
+
+    #[test]
+    fn test_by_slot_key_route_by_hash_tag() {
+        let slot = |routing_key| match by_slot_key_route(routing_key) {
+            Some(RoutingInfo::SingleNode(SingleNodeRoutingInfo::SpecificNode(route))) => {
+                route.slot()
+            }
+            route => panic!("Expected a route to a specific node, got {route:?}"),
+        };
+        assert_eq!(slot("{tenant123}"), slot("tenant123"));
+        assert_eq!(slot("{tenant123}:orders"), slot("tenant123"));
+    }
+
+    #[test]
+    fn test_argument_size_limit() {
+        let gigabyte = 1 << 30;
+        let lengths = [3, gigabyte];
+        let err = unsafe { check_argument_sizes(lengths.as_ptr(), 2, 1024) }.unwrap_err();
+        assert_eq!(error_type(&err), RequestErrorType::ArgumentTooLarge);
+        assert!(unsafe { check_argument_sizes(lengths.as_ptr(), 1, 1024) }.is_ok());
+        assert!(unsafe { check_argument_sizes(lengths.as_ptr(), 2, gigabyte) }.is_ok());
+        assert!(unsafe { check_argument_sizes(lengths.as_ptr(), 2, 0) }.is_ok());
+        assert!(unsafe { check_argument_sizes(std::ptr::null(), 0, 1024) }.is_ok());
+    }
+
+    #[test]
+    fn test_response_size_limit() {
+        let value = Value::Array(vec![
+            Value::BulkString(vec![0; 100]),
+            Value::Map(vec![(
+                Value::SimpleString("field".to_string()),
+                Value::Int(1),
+            )]),
+        ]);
+        let size = 5 * std::mem::size_of::<ResponseValue>() as u64 + 105;
+        assert!(!ResponseValue::exceeds_size(&value, size));
+        assert!(ResponseValue::exceeds_size(&value, size - 1));
+        assert!(ResponseValue::exceeds_size(&Value::Nil, 0));
+    }
+
+    #[test]
+    fn test_normalize_empty() {
+        assert_eq!(
+            ResponseValue::normalize_empty(Value::Array(vec![])),
+            Value::Nil
+        );
+        assert_eq!(
+            ResponseValue::normalize_empty(Value::Set(vec![])),
+            Value::Nil
+        );
+        assert_eq!(
+            ResponseValue::normalize_empty(Value::Map(vec![])),
+            Value::Nil
+        );
+        let scan = Value::Array(vec![Value::BulkString(b"0".to_vec()), Value::Array(vec![])]);
+        assert_eq!(ResponseValue::normalize_empty(scan.clone()), scan);
+        let empty_string = Value::BulkString(vec![]);
+        assert_eq!(
+            ResponseValue::normalize_empty(empty_string.clone()),
+            empty_string
+        );
+    }
+
+    #[test]
+    fn test_big_number_value() {
+        let number = "-170141183460469231731687303715884105728";
+        let value = redis::parse_redis_value(format!("({number}\r\n").as_bytes()).unwrap();
+        assert!(matches!(value, Value::BigNumber(_)));
+        let value = ResponseValue::from_value(value);
+        assert!(matches!(value.typ, ValueType::BigNumber));
+        let text = unsafe { from_raw_parts(value.val as *const u8, value.size as usize) };
+        assert_eq!(text, number.as_bytes());
+        unsafe { value.free_memory() };
+
+        // A big number in the range of an integer is an integer
+        let value = redis::parse_redis_value(b"(-42\r\n").unwrap();
+        let value = ResponseValue::from_value(value);
+        assert!(matches!(value.typ, ValueType::Int));
+        assert_eq!(value.val, -42);
+
+        let above_i64 = format!("{}1", i64::MAX);
+        let value = redis::parse_redis_value(format!("({above_i64}\r\n").as_bytes()).unwrap();
+        let value = ResponseValue::from_value(value);
+        let text = unsafe { from_raw_parts(value.val as *const u8, value.size as usize) };
+        assert_eq!(text, above_i64.as_bytes());
+        unsafe { value.free_memory() };
+    }
+
+    #[test]
+    fn test_integer_replies_are_integers() {
+        // The replies of `DEL` with a count, `SETNX` with `0` or `1` and `STRLEN` with a length, as sent by the server
+        for (reply, int) in [
+            (&b":2\r\n"[..], 2),
+            (b":0\r\n", 0),
+            (b":1\r\n", 1),
+            (b":11\r\n", 11),
+            (b":-9223372036854775808\r\n", i64::MIN),
+        ] {
+            let value = ResponseValue::from_value(redis::parse_redis_value(reply).unwrap());
+            assert!(matches!(value.typ, ValueType::Int));
+            assert_eq!(value.val, int);
+            assert_eq!(value.size, 0);
+        }
+    }
+
+    #[test]
+    fn test_server_error_value() {
+        let err = redis::parse_redis_value(
+            b"-WRONGTYPE Operation against a key holding the wrong kind of value\r\n",
+        )
+        .unwrap();
+        let value = ResponseValue::from_value(err);
+        assert!(matches!(value.typ, ValueType::Error));
+        assert_eq!(value.size, 2);
+        let items = unsafe { from_raw_parts(value.val as *const ResponseValue, 2) };
+        assert!(matches!(items[0].typ, ValueType::Int));
+        assert_eq!(items[0].val, RequestErrorType::Unspecified as i64);
+        assert!(matches!(items[1].typ, ValueType::String));
+        let message = unsafe { from_raw_parts(items[1].val as *const u8, items[1].size as usize) };
+        assert!(String::from_utf8_lossy(message).contains("WRONGTYPE"));
+        unsafe { value.free_memory() };
+    }
+}
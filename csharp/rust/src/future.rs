@@ -0,0 +1,228 @@
+// Copyright Valkey GLIDE Project Contributors - SPDX Identifier: Apache-2.0
+
+//! A polling alternative to the callback model: [`command_future`] returns a handle right away,
+//! which is polled by [`poll_future`] until the command completes, and freed by [`free_future`].
+
+use std::{
+    ffi::{c_char, c_void, CString},
+    sync::{Arc, Mutex},
+};
+
+use glide_core::request_type::RequestType;
+use redis::{RedisResult, Value};
+
+use crate::{
+    client_from_ptr, create_command, deadline_to_instant, dispatch_command,
+    errors::{error_type, format_error, ErrorMessageFormat, RequestErrorType},
+    ffi::{create_route, ResponseValue, RouteInfo},
+    response_pool,
+};
+
+/// State of a command future, returned by [`poll_future`].
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FutureState {
+    /// The command hasn't completed yet.
+    Pending = 0,
+    /// The command succeeded, the response is passed to the caller.
+    Ready = 1,
+    /// The command failed, the error is passed to the caller.
+    Failed = 2,
+    /// The result was already passed to the caller by a previous poll.
+    Consumed = 3,
+}
+
+#[derive(Debug)]
+enum Outcome {
+    Pending,
+    Ready(Value),
+    Failed(RequestErrorType, String),
+    Consumed,
+}
+
+/// Completes the future of a command. If it is dropped without completing, e.g. because the runtime shut down
+/// while the command was in flight, the future fails with [`RequestErrorType::ClientClosed`], so it isn't pending forever.
+struct Completer {
+    outcome: Arc<Mutex<Outcome>>,
+}
+
+impl Completer {
+    fn complete(&self, result: RedisResult<Value>, format: ErrorMessageFormat) {
+        *self.outcome.lock().unwrap() = match result {
+            Ok(value) => Outcome::Ready(value),
+            Err(err) => Outcome::Failed(error_type(&err), format_error(&err, format)),
+        };
+    }
+}
+
+impl Drop for Completer {
+    fn drop(&mut self) {
+        let mut outcome = self.outcome.lock().unwrap();
+        if matches!(*outcome, Outcome::Pending) {
+            *outcome = Outcome::Failed(
+                RequestErrorType::ClientClosed,
+                "Client was closed".to_string(),
+            );
+        }
+    }
+}
+
+/// The handle returned by [`command_future`].
+struct CommandFuture {
+    outcome: Arc<Mutex<Outcome>>,
+    /// The error message passed to the caller, kept until the future is freed.
+    error_message: Option<CString>,
+}
+
+impl CommandFuture {
+    fn new() -> (Self, Completer) {
+        let outcome = Arc::new(Mutex::new(Outcome::Pending));
+        let future = CommandFuture {
+            outcome: outcome.clone(),
+            error_message: None,
+        };
+        (future, Completer { outcome })
+    }
+
+    fn into_raw(self) -> *const c_void {
+        Box::into_raw(Box::new(self)) as *const c_void
+    }
+}
+
+/// Execute a command like [`command`](crate::command), but instead of calling the client's callbacks,
+/// return a handle to poll the result with [`poll_future`]. The handle must be freed with [`free_future`].
+///
+/// Requests submitted after the client was closed, or which can't be dispatched, return a handle which is already failed.
+///
+/// # Safety
+/// * `client_ptr` must not be `null`.
+/// * `client_ptr` must be a pointer created by [`create_client`](crate::create_client), which wasn't deallocated yet.
+/// * `args` must point to `arg_count` consecutive string pointers and `args_len` must point to their lengths.
+///   See the safety documentation of [`convert_double_pointer_to_vec`](crate::ffi::convert_double_pointer_to_vec).
+/// * `route_info` could be `null`, but if it is not `null`, it must be a valid [`RouteInfo`] pointer. See the safety documentation of [`create_route`].
+#[allow(clippy::too_many_arguments)]
+#[no_mangle]
+pub unsafe extern "C" fn command_future(
+    client_ptr: *const c_void,
+    request_type: RequestType,
+    args: *const *mut c_char,
+    arg_count: u32,
+    args_len: *const u32,
+    route_info: *const RouteInfo,
+    deadline_unix_ms: u64,
+) -> *const c_void {
+    let (future, completer) = CommandFuture::new();
+    let Some(client) = (unsafe { client_from_ptr(client_ptr) }) else {
+        // Dropping the completer fails the future with `ClientClosed`.
+        return future.into_raw();
+    };
+    let command = unsafe {
+        create_command(
+            &request_type,
+            args,
+            arg_count,
+            args_len,
+            client.core.log_non_utf8_arguments,
+        )
+    }
+    .and_then(|cmd| Ok((cmd, deadline_to_instant(deadline_unix_ms)?)));
+    match command {
+        Ok((cmd, deadline)) => {
+            let route = unsafe { create_route(route_info, &cmd) };
+            dispatch_command(&client, cmd, route, deadline, None, move |core, result| {
+                completer.complete(core.limit_response_size(result), core.error_message_format)
+            });
+        }
+        Err(err) => completer.complete(Err(err), client.core.error_message_format),
+    }
+    future.into_raw()
+}
+
+/// Poll the result of a command submitted by [`command_future`], without blocking.
+/// * [`FutureState::Ready`] - `out_value` is set to the response, which should be freed with [`free_respose`](crate::free_respose).
+/// * [`FutureState::Failed`] - `out_error_type` and `out_error_message` are set. The message is a null-terminated string,
+///   which is valid until the future is freed.
+/// * [`FutureState::Pending`] and [`FutureState::Consumed`] - nothing is set. The result is passed only by the first poll
+///   after the command completed, later polls return [`FutureState::Consumed`].
+///
+/// # Safety
+/// * `future_ptr` must be a pointer returned by [`command_future`], which wasn't freed yet.
+/// * `out_value`, `out_error_type` and `out_error_message` must be valid pointers to write to.
+#[no_mangle]
+pub unsafe extern "C" fn poll_future(
+    future_ptr: *const c_void,
+    out_value: *mut *mut ResponseValue,
+    out_error_type: *mut RequestErrorType,
+    out_error_message: *mut *const c_char,
+) -> FutureState {
+    let future = unsafe { &mut *(future_ptr as *mut CommandFuture) };
+    let mut outcome = future.outcome.lock().unwrap();
+    if matches!(*outcome, Outcome::Pending) {
+        return FutureState::Pending;
+    }
+    match std::mem::replace(&mut *outcome, Outcome::Consumed) {
+        Outcome::Ready(value) => {
+            unsafe { *out_value = response_pool::into_raw(ResponseValue::from_value(value), None) };
+            FutureState::Ready
+        }
+        Outcome::Failed(error_type, message) => {
+            let message = future
+                .error_message
+                .insert(CString::new(message).unwrap_or_default());
+            unsafe {
+                *out_error_type = error_type;
+                *out_error_message = message.as_ptr();
+            }
+            FutureState::Failed
+        }
+        Outcome::Pending | Outcome::Consumed => FutureState::Consumed,
+    }
+}
+
+/// Free a handle returned by [`command_future`]. A pending command still completes, but its result is dropped.
+///
+/// # Safety
+/// * `future_ptr` must be a pointer returned by [`command_future`], which wasn't freed yet.
+#[no_mangle]
+pub unsafe extern "C" fn free_future(future_ptr: *const c_void) {
+    drop(unsafe { Box::from_raw(future_ptr as *mut CommandFuture) });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_future_result_is_passed_once() {
+        let (future, completer) = CommandFuture::new();
+        let future = future.into_raw();
+        let mut value = std::ptr::null_mut();
+        let mut error_type = RequestErrorType::Unspecified;
+        let mut message = std::ptr::null();
+        let mut poll = || unsafe { poll_future(future, &mut value, &mut error_type, &mut message) };
+        assert_eq!(poll(), FutureState::Pending);
+        completer.complete(Ok(Value::Int(5)), ErrorMessageFormat::Text);
+        assert_eq!(poll(), FutureState::Ready);
+        assert_eq!(poll(), FutureState::Consumed);
+        unsafe {
+            assert_eq!((*value).val, 5);
+            crate::free_respose(value);
+            free_future(future);
+        }
+    }
+
+    #[test]
+    fn test_dropped_completer_fails_future() {
+        let (future, completer) = CommandFuture::new();
+        let future = future.into_raw();
+        drop(completer);
+        let mut value = std::ptr::null_mut();
+        let mut error_type = RequestErrorType::Unspecified;
+        let mut message = std::ptr::null();
+        let state = unsafe { poll_future(future, &mut value, &mut error_type, &mut message) };
+        assert_eq!(state, FutureState::Failed);
+        assert_eq!(error_type, RequestErrorType::ClientClosed);
+        assert!(value.is_null());
+        unsafe { free_future(future) };
+    }
+}
@@ -0,0 +1,150 @@
+// Copyright Valkey GLIDE Project Contributors - SPDX Identifier: Apache-2.0
+
+use redis::{ErrorKind, RedisError, RedisResult, Value};
+
+/// Build a map value with the given string keys.
+fn map(entries: Vec<(&str, Value)>) -> Value {
+    Value::Map(
+        entries
+            .into_iter()
+            .map(|(key, value)| (Value::BulkString(key.as_bytes().to_vec()), value))
+            .collect(),
+    )
+}
+
+/// Convert a `[longitude, latitude]` pair, already converted to doubles by glide-core, to a map with `longitude` and `latitude`.
+fn coordinates(value: Value) -> RedisResult<Vec<(&'static str, Value)>> {
+    match value {
+        Value::Array(coordinates) => match <[Value; 2]>::try_from(coordinates) {
+            Ok([longitude @ Value::Double(_), latitude @ Value::Double(_)]) => {
+                Ok(vec![("longitude", longitude), ("latitude", latitude)])
+            }
+            _ => Err(invalid_response("coordinates are not a pair of doubles")),
+        },
+        _ => Err(invalid_response("coordinates are not an array")),
+    }
+}
+
+/// Convert a `GEOPOS` response to an array of maps with `longitude` and `latitude` doubles, or nulls for missing members.
+pub(crate) fn convert_geopos(value: Value) -> RedisResult<Value> {
+    let Value::Array(positions) = value else {
+        return Err(invalid_response("GEOPOS response is not an array"));
+    };
+    positions
+        .into_iter()
+        .map(|position| match position {
+            Value::Nil => Ok(Value::Nil),
+            position => coordinates(position).map(map),
+        })
+        .collect::<RedisResult<_>>()
+        .map(Value::Array)
+}
+
+/// Convert a `GEOSEARCH` response to an array of maps with a `member` and, depending on the options given,
+/// `distance` (double), `hash` (integer), `longitude` and `latitude` (doubles).
+///
+/// Without options the server returns only the members. With options glide-core converts every result
+/// to `[member, [distance?, hash?, [longitude, latitude]?]]`, where the present values can be told apart by their types.
+pub(crate) fn convert_geosearch(value: Value) -> RedisResult<Value> {
+    let Value::Array(results) = value else {
+        return Err(invalid_response("GEOSEARCH response is not an array"));
+    };
+    results
+        .into_iter()
+        .map(|result| {
+            let Value::Array(result) = result else {
+                return Ok(map(vec![("member", result)]));
+            };
+            let mut items = result.into_iter();
+            let (Some(member), Some(Value::Array(attributes)), None) =
+                (items.next(), items.next(), items.next())
+            else {
+                return Err(invalid_response(
+                    "search result is not a member and its attributes",
+                ));
+            };
+            let mut entries = vec![("member", member)];
+            for attribute in attributes {
+                match attribute {
+                    distance @ Value::Double(_) => entries.push(("distance", distance)),
+                    hash @ Value::Int(_) => entries.push(("hash", hash)),
+                    position => entries.extend(coordinates(position)?),
+                }
+            }
+            Ok(map(entries))
+        })
+        .collect::<RedisResult<_>>()
+        .map(Value::Array)
+}
+
+fn invalid_response(detail: &str) -> RedisError {
+    RedisError::from((
+        ErrorKind::TypeError,
+        "Unexpected GEO response",
+        detail.to_string(),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bulk(value: &str) -> Value {
+        Value::BulkString(value.as_bytes().to_vec())
+    }
+
+    #[test]
+    fn test_convert_geopos_preserves_doubles() {
+        // values which aren't exactly representable in decimal, to make sure they aren't rounded
+        let longitude = 40.0 / 3.0;
+        let latitude = -1.0 / 7.0;
+        let value = Value::Array(vec![
+            Value::Array(vec![Value::Double(longitude), Value::Double(latitude)]),
+            Value::Nil,
+        ]);
+        assert_eq!(
+            convert_geopos(value).unwrap(),
+            Value::Array(vec![
+                map(vec![
+                    ("longitude", Value::Double(longitude)),
+                    ("latitude", Value::Double(latitude)),
+                ]),
+                Value::Nil,
+            ])
+        );
+    }
+
+    #[test]
+    fn test_convert_geosearch_with_all_options() {
+        let value = Value::Array(vec![Value::Array(vec![
+            bulk("Palermo"),
+            Value::Array(vec![
+                Value::Double(190.4424),
+                Value::Int(3479099956230698),
+                Value::Array(vec![Value::Double(13.361389), Value::Double(38.115556)]),
+            ]),
+        ])]);
+        assert_eq!(
+            convert_geosearch(value).unwrap(),
+            Value::Array(vec![map(vec![
+                ("member", bulk("Palermo")),
+                ("distance", Value::Double(190.4424)),
+                ("hash", Value::Int(3479099956230698)),
+                ("longitude", Value::Double(13.361389)),
+                ("latitude", Value::Double(38.115556)),
+            ])])
+        );
+    }
+
+    #[test]
+    fn test_convert_geosearch_without_options() {
+        let value = Value::Array(vec![bulk("Palermo"), bulk("Catania")]);
+        assert_eq!(
+            convert_geosearch(value).unwrap(),
+            Value::Array(vec![
+                map(vec![("member", bulk("Palermo"))]),
+                map(vec![("member", bulk("Catania"))]),
+            ])
+        );
+    }
+}
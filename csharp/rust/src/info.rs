@@ -0,0 +1,172 @@
+// Copyright Valkey GLIDE Project Contributors - SPDX Identifier: Apache-2.0
+
+use redis::{ErrorKind, RedisError, RedisResult, Value};
+
+pub(crate) fn bulk(value: &str) -> Value {
+    Value::BulkString(value.as_bytes().to_vec())
+}
+
+/// Type a field value as an integer or a double if it is unambiguously a number, e.g. `used_memory:1024`
+/// or `mem_fragmentation_ratio:1.25`, but not `redis_version:7.2.4` or `run_id:0123abc`.
+pub(crate) fn typed_value(value: &str) -> Value {
+    if let Ok(int) = value.parse::<i64>() {
+        // leading zeros or a sign would be lost, so such values are kept as strings
+        if int.to_string() == value {
+            return Value::Int(int);
+        }
+    }
+    let digits = value.strip_prefix('-').unwrap_or(value);
+    if let Some((whole, fraction)) = digits.split_once('.') {
+        let is_number = |part: &str| !part.is_empty() && part.bytes().all(|b| b.is_ascii_digit());
+        if is_number(whole) && is_number(fraction) {
+            if let Ok(double) = value.parse::<f64>() {
+                return Value::Double(double);
+            }
+        }
+    }
+    bulk(value)
+}
+
+/// Parse a field value, which is either a single value, or a sub-structure of comma separated `key=value` pairs,
+/// e.g. `db0:keys=1,expires=0,avg_ttl=0` in the keyspace section, or `slave0:ip=...,port=6380,...` in the replication section.
+fn parse_value(value: &str) -> Value {
+    let pairs: Option<Vec<(&str, &str)>> = value
+        .split(',')
+        .map(|pair| pair.split_once('=').filter(|(key, _)| !key.is_empty()))
+        .collect();
+    match pairs {
+        Some(pairs) => Value::Map(
+            pairs
+                .into_iter()
+                .map(|(key, value)| (bulk(key), typed_value(value)))
+                .collect(),
+        ),
+        None => typed_value(value),
+    }
+}
+
+/// Parse the text of an `INFO` response to a map of section names (in lower case) to maps of fields to values.
+fn parse_info(info: &str) -> Value {
+    let mut sections: Vec<(Value, Value)> = Vec::new();
+    for line in info.lines().map(str::trim).filter(|line| !line.is_empty()) {
+        if let Some(section) = line.strip_prefix('#') {
+            sections.push((bulk(&section.trim().to_lowercase()), Value::Map(vec![])));
+            continue;
+        }
+        let Some((field, value)) = line.split_once(':') else {
+            continue;
+        };
+        if sections.is_empty() {
+            // fields before the first section header, which servers don't send
+            sections.push((bulk(""), Value::Map(vec![])));
+        }
+        if let Some((_, Value::Map(fields))) = sections.last_mut() {
+            fields.push((bulk(field), parse_value(value)));
+        }
+    }
+    Value::Map(sections)
+}
+
+fn parse_info_value(value: Value) -> RedisResult<Value> {
+    let info: String = redis::from_owned_redis_value(value)?;
+    Ok(parse_info(&info))
+}
+
+/// Convert an `INFO` response to a map of section → field → value, see [`parse_info`].
+/// Fields are typed as integers or doubles where unambiguous, and sub-structures of `key=value` pairs,
+/// like the databases of the keyspace section, are converted to maps.
+/// A response of multiple nodes, which is a map of node addresses to responses, is converted to a map of node addresses to such maps.
+pub(crate) fn convert_info(value: Value) -> RedisResult<Value> {
+    match value {
+        Value::Map(nodes) => nodes
+            .into_iter()
+            .map(|(node, info)| Ok((node, parse_info_value(info)?)))
+            .collect::<RedisResult<_>>()
+            .map(Value::Map),
+        Value::BulkString(_) | Value::SimpleString(_) | Value::VerbatimString { .. } => {
+            parse_info_value(value)
+        }
+        _ => Err(RedisError::from((
+            ErrorKind::TypeError,
+            "Unexpected INFO response",
+            format!("{value:?}"),
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn map(entries: Vec<(&str, Value)>) -> Value {
+        Value::Map(
+            entries
+                .into_iter()
+                .map(|(key, value)| (bulk(key), value))
+                .collect(),
+        )
+    }
+
+    const INFO: &str = "# Server\r\nredis_version:7.2.4\r\nrun_id:0123abc\r\nprocess_id:42\r\n\r\n\
+        # Memory\r\nused_memory:1024\r\nmem_fragmentation_ratio:1.25\r\nused_memory_human:1.00K\r\n\r\n\
+        # Keyspace\r\ndb0:keys=10,expires=2,avg_ttl=0\r\ndb3:keys=1,expires=0,avg_ttl=0\r\n";
+
+    #[test]
+    fn test_convert_info() {
+        let db = |keys, expires| {
+            map(vec![
+                ("keys", Value::Int(keys)),
+                ("expires", Value::Int(expires)),
+                ("avg_ttl", Value::Int(0)),
+            ])
+        };
+        assert_eq!(
+            convert_info(bulk(INFO)).unwrap(),
+            map(vec![
+                (
+                    "server",
+                    map(vec![
+                        ("redis_version", bulk("7.2.4")),
+                        ("run_id", bulk("0123abc")),
+                        ("process_id", Value::Int(42)),
+                    ])
+                ),
+                (
+                    "memory",
+                    map(vec![
+                        ("used_memory", Value::Int(1024)),
+                        ("mem_fragmentation_ratio", Value::Double(1.25)),
+                        ("used_memory_human", bulk("1.00K")),
+                    ])
+                ),
+                ("keyspace", map(vec![("db0", db(10, 2)), ("db3", db(1, 0))])),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_convert_info_of_multiple_nodes() {
+        let info = "# Clients\r\nconnected_clients:3\r\n";
+        let value = Value::Map(vec![
+            (bulk("node1:6379"), bulk(info)),
+            (bulk("node2:6379"), bulk(info)),
+        ]);
+        let node = map(vec![(
+            "clients",
+            map(vec![("connected_clients", Value::Int(3))]),
+        )]);
+        assert_eq!(
+            convert_info(value).unwrap(),
+            map(vec![("node1:6379", node.clone()), ("node2:6379", node)])
+        );
+    }
+
+    #[test]
+    fn test_ambiguous_values_are_strings() {
+        assert_eq!(typed_value("007"), bulk("007"));
+        assert_eq!(typed_value("+1"), bulk("+1"));
+        assert_eq!(typed_value("1."), bulk("1."));
+        assert_eq!(typed_value("-0.5"), Value::Double(-0.5));
+        assert_eq!(parse_value("/etc/valkey.conf"), bulk("/etc/valkey.conf"));
+    }
+}
@@ -0,0 +1,153 @@
+// Copyright Valkey GLIDE Project Contributors - SPDX Identifier: Apache-2.0
+
+use std::{
+    collections::{hash_map::RandomState, HashMap},
+    hash::BuildHasher,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex,
+    },
+};
+
+use redis::{cluster_routing::RoutingInfo, Cmd};
+use tokio::sync::oneshot;
+
+/// Number of shards of the keys, so commands on different keys rarely contend on the same lock.
+const SHARD_COUNT: usize = 16;
+
+/// The last command submitted on a key, which the next command on the key waits for.
+#[derive(Debug)]
+struct Tail {
+    sequence: u64,
+    completed: oneshot::Receiver<()>,
+}
+
+/// Dispatches the commands on the same key in the order they were submitted, one at a time, while commands on different keys
+/// remain concurrent, see [`ConnectionConfig::order_commands_per_key`](crate::ffi::ConnectionConfig::order_commands_per_key).
+#[derive(Debug)]
+pub(crate) struct KeyOrdering {
+    shards: Vec<Mutex<HashMap<Vec<u8>, Tail>>>,
+    hasher: RandomState,
+    next_sequence: AtomicU64,
+}
+
+/// The turn of a command on its key. The command may be dispatched once [`KeyTurn::wait`] returns,
+/// and the next command on the key is dispatched once the turn is dropped.
+#[derive(Debug)]
+pub(crate) struct KeyTurn {
+    ordering: Arc<KeyOrdering>,
+    key: Vec<u8>,
+    sequence: u64,
+    previous: Option<oneshot::Receiver<()>>,
+    // Dropping the sender completes the receiver of the next command on the key.
+    _completed: oneshot::Sender<()>,
+}
+
+impl Default for KeyOrdering {
+    fn default() -> Self {
+        KeyOrdering {
+            shards: (0..SHARD_COUNT).map(|_| Mutex::default()).collect(),
+            hasher: RandomState::new(),
+            next_sequence: AtomicU64::new(0),
+        }
+    }
+}
+
+impl KeyOrdering {
+    fn shard(&self, key: &[u8]) -> &Mutex<HashMap<Vec<u8>, Tail>> {
+        &self.shards[self.hasher.hash_one(key) as usize % SHARD_COUNT]
+    }
+
+    /// Take the turn of `cmd` after the commands submitted on its key so far, `None` if the command isn't routed by a single key,
+    /// e.g. `MGET`, so it isn't ordered. Must be called in the order the commands are submitted.
+    pub(crate) fn enter(self: &Arc<Self>, cmd: &Cmd) -> Option<KeyTurn> {
+        let key = RoutingInfo::routing_key(cmd)?.to_vec();
+        let sequence = self.next_sequence.fetch_add(1, Ordering::Relaxed);
+        let (sender, receiver) = oneshot::channel();
+        let previous = self.shard(&key).lock().unwrap().insert(
+            key.clone(),
+            Tail {
+                sequence,
+                completed: receiver,
+            },
+        );
+        Some(KeyTurn {
+            ordering: self.clone(),
+            key,
+            sequence,
+            previous: previous.map(|tail| tail.completed),
+            _completed: sender,
+        })
+    }
+}
+
+impl KeyTurn {
+    /// Wait until the previous command on the key completed.
+    pub(crate) async fn wait(mut self) -> Self {
+        if let Some(previous) = self.previous.take() {
+            // The sender is dropped once the previous command completed, or if it was cancelled
+            let _ = previous.await;
+        }
+        self
+    }
+}
+
+impl Drop for KeyTurn {
+    fn drop(&mut self) {
+        let mut shard = self.ordering.shard(&self.key).lock().unwrap();
+        // The key is removed once its last command completes, so the keys don't accumulate
+        if shard
+            .get(&self.key)
+            .is_some_and(|tail| tail.sequence == self.sequence)
+        {
+            shard.remove(&self.key);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn get(key: &str) -> Cmd {
+        let mut cmd = redis::cmd("GET");
+        cmd.arg(key);
+        cmd
+    }
+
+    #[tokio::test]
+    async fn test_commands_on_a_key_are_dispatched_in_order() {
+        let ordering = Arc::new(KeyOrdering::default());
+        let log = Arc::new(Mutex::new(Vec::new()));
+        let mut tasks = Vec::new();
+        for index in 0..10 {
+            let turn = ordering.enter(&get("key")).unwrap();
+            let log = log.clone();
+            tasks.push(tokio::spawn(async move {
+                let _turn = turn.wait().await;
+                // Later commands are faster, so they would complete first without ordering
+                tokio::time::sleep(Duration::from_millis(10 - index)).await;
+                log.lock().unwrap().push(index);
+            }));
+        }
+        for task in tasks {
+            task.await.unwrap();
+        }
+        assert_eq!(*log.lock().unwrap(), (0..10).collect::<Vec<_>>());
+        assert!(ordering.shard(b"key").lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_commands_on_different_keys_are_concurrent() {
+        let ordering = Arc::new(KeyOrdering::default());
+        let first = ordering.enter(&get("first")).unwrap().wait().await;
+        // Doesn't wait for the command on the first key, which is still in flight
+        let second = ordering.enter(&get("second")).unwrap().wait().await;
+        drop((first, second));
+
+        let mut mget = redis::cmd("MGET");
+        mget.arg("first").arg("second");
+        assert!(ordering.enter(&mget).is_none());
+    }
+}
@@ -0,0 +1,630 @@
+// Copyright Valkey GLIDE Project Contributors - SPDX Identifier: Apache-2.0
+
+use std::collections::{BTreeSet, HashMap};
+
+use redis::{cluster_routing::Routable, Arg, Cmd, ErrorKind, RedisError, RedisResult, Value};
+
+use crate::{command_info::convert_command_info, ResponseConverter};
+
+/// Where the search for the keys of a command begins, see the key specifications of `COMMAND INFO`.
+#[derive(Debug, Clone, PartialEq)]
+enum BeginSearch {
+    /// The keys begin at the given argument.
+    Index(usize),
+    /// The keys begin after the given keyword, searched from the given argument, backwards from the end if it is negative.
+    Keyword { keyword: Vec<u8>, start_from: i64 },
+}
+
+/// How to find the keys once the search began, see the key specifications of `COMMAND INFO`.
+#[derive(Debug, Clone, PartialEq)]
+enum FindKeys {
+    /// Keys up to `last_key` relative to the beginning, or to the end if it is negative, every `key_step` arguments.
+    /// If the keys reach the end, `limit` > 1 takes only a part of the remaining arguments, e.g. half for `2`.
+    Range {
+        last_key: i64,
+        key_step: usize,
+        limit: usize,
+    },
+    /// The number of keys is in the argument at `key_num_index` relative to the beginning, and the keys begin at `first_key`.
+    KeyNum {
+        key_num_index: usize,
+        first_key: usize,
+        key_step: usize,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct KeySpec {
+    begin_search: BeginSearch,
+    find_keys: FindKeys,
+}
+
+impl KeySpec {
+    /// Get the positions of the keys in the arguments of a command, where the command name is at position `0`.
+    fn key_positions(&self, args: &[&[u8]]) -> Vec<usize> {
+        let count = args.len();
+        let begin = match &self.begin_search {
+            BeginSearch::Index(index) => *index,
+            BeginSearch::Keyword {
+                keyword,
+                start_from,
+            } => {
+                let is_keyword = |&position: &usize| args[position].eq_ignore_ascii_case(keyword);
+                let found = if *start_from >= 0 {
+                    (*start_from as usize..count).find(is_keyword)
+                } else {
+                    let start = count as i64 + start_from;
+                    (1..=start.max(0) as usize).rev().find(is_keyword)
+                };
+                match found {
+                    Some(position) => position + 1,
+                    None => return vec![],
+                }
+            }
+        };
+        if begin == 0 || begin >= count {
+            return vec![];
+        }
+        match self.find_keys {
+            FindKeys::Range {
+                last_key,
+                key_step,
+                limit,
+            } => {
+                let last = if last_key >= 0 {
+                    begin + last_key as usize
+                } else {
+                    let last = (count as i64 + last_key).max(0) as usize;
+                    if limit > 1 && last >= begin {
+                        begin + (last - begin + 1) / limit - 1
+                    } else {
+                        last
+                    }
+                };
+                (begin..=last.min(count - 1))
+                    .step_by(key_step.max(1))
+                    .collect()
+            }
+            FindKeys::KeyNum {
+                key_num_index,
+                first_key,
+                key_step,
+            } => {
+                let key_count = args
+                    .get(begin + key_num_index)
+                    .and_then(|count| std::str::from_utf8(count).ok()?.parse::<usize>().ok())
+                    .unwrap_or(0);
+                (0..key_count)
+                    .map(|key| begin + first_key + key * key_step.max(1))
+                    .take_while(|&position| position < count)
+                    .collect()
+            }
+        }
+    }
+}
+
+/// Prefixes the keys of commands with a per-client prefix, to namespace the keys of a tenant in a shared database,
+/// see [`ConnectionConfig::key_prefix`](crate::ffi::ConnectionConfig::key_prefix).
+///
+/// The positions of the keys are found by the key specifications of the commands, as reported by the `COMMAND` command
+/// of the server, or by the legacy first key, last key and step on servers which don't report key specifications.
+pub(crate) struct KeyPrefix {
+    prefix: Vec<u8>,
+    /// Strip the prefix from keys in the responses of `KEYS`, `SCAN` and `RANDOMKEY`.
+    strip_responses: bool,
+    /// Key specifications by lower case command names, and `command|subcommand` names for subcommands.
+    key_specs: HashMap<String, Vec<KeySpec>>,
+}
+
+impl KeyPrefix {
+    /// Create the prefixing of keys from a response of the `COMMAND` command, which lists the metadata of all commands.
+    pub(crate) fn new(
+        prefix: Vec<u8>,
+        strip_responses: bool,
+        commands: Value,
+    ) -> RedisResult<Self> {
+        let Value::Map(commands) = convert_command_info(commands)? else {
+            return Err(invalid_metadata("commands are not a map"));
+        };
+        let mut key_specs = HashMap::new();
+        for (_, info) in commands {
+            add_key_specs(&mut key_specs, info)?;
+        }
+        Ok(KeyPrefix {
+            prefix,
+            strip_responses,
+            key_specs,
+        })
+    }
+
+    pub(crate) fn prefix(&self) -> &[u8] {
+        &self.prefix
+    }
+
+    fn prefixed(&self, arg: &[u8]) -> Vec<u8> {
+        [self.prefix.as_slice(), arg].concat()
+    }
+
+    /// A glob-style pattern matching the prefix, followed by `pattern`.
+    fn prefixed_pattern(&self, pattern: &[u8]) -> Vec<u8> {
+        let mut prefixed = Vec::with_capacity(self.prefix.len() + pattern.len());
+        for &byte in &self.prefix {
+            if matches!(byte, b'*' | b'?' | b'[' | b']' | b'\\') {
+                prefixed.push(b'\\');
+            }
+            prefixed.push(byte);
+        }
+        prefixed.extend_from_slice(pattern);
+        prefixed
+    }
+
+    /// Prefix the keys of a command. Arguments which aren't keys are untouched, except for the patterns of `KEYS`
+    /// and `SCAN`, which are prefixed so only keys with the prefix are matched.
+    pub(crate) fn apply(&self, cmd: Cmd) -> Cmd {
+        // cursor commands are built internally, and never by the caller
+        if cmd.args_iter().any(|arg| matches!(arg, Arg::Cursor)) {
+            return cmd;
+        }
+        let args: Vec<&[u8]> = cmd
+            .args_iter()
+            .filter_map(|arg| match arg {
+                Arg::Simple(arg) => Some(arg),
+                Arg::Cursor => None,
+            })
+            .collect();
+        let Some(name) = args.first().map(|name| name.to_ascii_lowercase()) else {
+            return cmd;
+        };
+        let mut prefixed = Cmd::new();
+        match name.as_slice() {
+            b"keys" => {
+                for (position, arg) in args.iter().enumerate() {
+                    match position {
+                        1 => prefixed.arg(self.prefixed_pattern(arg)),
+                        _ => prefixed.arg(*arg),
+                    };
+                }
+            }
+            b"scan" => {
+                let mut has_match = false;
+                for (position, arg) in args.iter().enumerate() {
+                    if position > 1 && args[position - 1].eq_ignore_ascii_case(b"MATCH") {
+                        prefixed.arg(self.prefixed_pattern(arg));
+                        has_match = true;
+                    } else {
+                        prefixed.arg(*arg);
+                    }
+                }
+                if !has_match {
+                    prefixed.arg("MATCH").arg(self.prefixed_pattern(b"*"));
+                }
+            }
+            _ => {
+                let positions = self.key_positions(&name, &args);
+                if positions.is_empty() {
+                    return cmd;
+                }
+                for (position, arg) in args.iter().enumerate() {
+                    if positions.contains(&position) {
+                        prefixed.arg(self.prefixed(arg));
+                    } else {
+                        prefixed.arg(*arg);
+                    }
+                }
+            }
+        }
+        prefixed
+    }
+
+    /// Get the keys of a command, by the key specifications of the command.
+    pub(crate) fn keys<'a>(&self, cmd: &'a Cmd) -> Vec<&'a [u8]> {
+        let args: Vec<&[u8]> = cmd
+            .args_iter()
+            .filter_map(|arg| match arg {
+                Arg::Simple(arg) => Some(arg),
+                Arg::Cursor => None,
+            })
+            .collect();
+        let Some(name) = args.first().map(|name| name.to_ascii_lowercase()) else {
+            return vec![];
+        };
+        self.key_positions(&name, &args)
+            .into_iter()
+            .map(|position| args[position])
+            .collect()
+    }
+
+    fn key_positions(&self, name: &[u8], args: &[&[u8]]) -> BTreeSet<usize> {
+        let name = String::from_utf8_lossy(name);
+        let subcommand = args
+            .get(1)
+            .map(|subcommand| {
+                format!("{name}|{}", String::from_utf8_lossy(subcommand)).to_lowercase()
+            })
+            .and_then(|subcommand| self.key_specs.get(&subcommand));
+        subcommand
+            .or_else(|| self.key_specs.get(&*name))
+            .into_iter()
+            .flatten()
+            .flat_map(|spec| spec.key_positions(args))
+            .collect()
+    }
+
+    /// Get the conversion which strips the prefix from the keys in the response of a command, `None` if stripping isn't
+    /// configured or the command doesn't return key names. Only `KEYS`, `SCAN` and `RANDOMKEY` are stripped.
+    pub(crate) fn response_converter(&self, cmd: &Cmd) -> Option<ResponseConverter> {
+        if !self.strip_responses {
+            return None;
+        }
+        let command = cmd.command()?.to_ascii_lowercase();
+        if !matches!(command.as_slice(), b"keys" | b"scan" | b"randomkey") {
+            return None;
+        }
+        let prefix = self.prefix.clone();
+        Some(Box::new(move |value| {
+            Ok(strip_response(&prefix, &command, value))
+        }))
+    }
+}
+
+/// Strip `prefix` from the keys in the response of `command`, see [`KeyPrefix::response_converter`].
+fn strip_response(prefix: &[u8], command: &[u8], value: Value) -> Value {
+    let strip_keys = |keys: Vec<Value>| -> Vec<Value> {
+        keys.into_iter().map(|key| strip_key(prefix, key)).collect()
+    };
+    match (command, value) {
+        (b"keys", Value::Array(keys)) => Value::Array(strip_keys(keys)),
+        (b"scan", Value::Array(mut response)) => {
+            if let Some(Value::Array(keys)) = response.get_mut(1) {
+                *keys = strip_keys(std::mem::take(keys));
+            }
+            Value::Array(response)
+        }
+        (b"randomkey", key) => strip_key(prefix, key),
+        (_, value) => value,
+    }
+}
+
+fn strip_key(prefix: &[u8], key: Value) -> Value {
+    match key {
+        Value::BulkString(key) if key.starts_with(prefix) => {
+            Value::BulkString(key[prefix.len()..].to_vec())
+        }
+        key => key,
+    }
+}
+
+fn field<'a>(entries: &'a [(Value, Value)], name: &str) -> Option<&'a Value> {
+    entries
+        .iter()
+        .find(|(field, _)| match field {
+            Value::BulkString(field) => field == name.as_bytes(),
+            Value::SimpleString(field) => field == name,
+            _ => false,
+        })
+        .map(|(_, value)| value)
+}
+
+fn int_field(entries: &[(Value, Value)], name: &str) -> Option<i64> {
+    match field(entries, name)? {
+        Value::Int(value) => Some(*value),
+        _ => None,
+    }
+}
+
+fn string_field(entries: &[(Value, Value)], name: &str) -> Option<Vec<u8>> {
+    match field(entries, name)? {
+        Value::BulkString(value) => Some(value.clone()),
+        Value::SimpleString(value) => Some(value.as_bytes().to_vec()),
+        _ => None,
+    }
+}
+
+fn map_field<'a>(entries: &'a [(Value, Value)], name: &str) -> Option<&'a [(Value, Value)]> {
+    match field(entries, name)? {
+        Value::Map(entries) => Some(entries),
+        _ => None,
+    }
+}
+
+/// Parse a key specification converted by [`convert_command_info`], `None` if its key positions can't be found,
+/// e.g. the `unknown` specifications of commands whose keys depend on their arguments in other ways.
+fn parse_key_spec(spec: &Value) -> Option<KeySpec> {
+    let Value::Map(spec) = spec else {
+        return None;
+    };
+    let begin_search = map_field(spec, "begin_search")?;
+    let begin_spec = map_field(begin_search, "spec")?;
+    let begin_search = match string_field(begin_search, "type")?.as_slice() {
+        b"index" => BeginSearch::Index(int_field(begin_spec, "index")?.try_into().ok()?),
+        b"keyword" => BeginSearch::Keyword {
+            keyword: string_field(begin_spec, "keyword")?,
+            start_from: int_field(begin_spec, "startfrom")?,
+        },
+        _ => return None,
+    };
+    let find_keys = map_field(spec, "find_keys")?;
+    let find_spec = map_field(find_keys, "spec")?;
+    let find_keys = match string_field(find_keys, "type")?.as_slice() {
+        b"range" => FindKeys::Range {
+            last_key: int_field(find_spec, "lastkey")?,
+            key_step: int_field(find_spec, "keystep")?.try_into().ok()?,
+            limit: int_field(find_spec, "limit")?.try_into().ok()?,
+        },
+        b"keynum" => FindKeys::KeyNum {
+            key_num_index: int_field(find_spec, "keynumidx")?.try_into().ok()?,
+            first_key: int_field(find_spec, "firstkey")?.try_into().ok()?,
+            key_step: int_field(find_spec, "keystep")?.try_into().ok()?,
+        },
+        _ => return None,
+    };
+    Some(KeySpec {
+        begin_search,
+        find_keys,
+    })
+}
+
+/// Add the key specifications of a command and its subcommands, converted by [`convert_command_info`].
+fn add_key_specs(key_specs: &mut HashMap<String, Vec<KeySpec>>, info: Value) -> RedisResult<()> {
+    let Value::Map(info) = info else {
+        return Err(invalid_metadata("command info is not a map"));
+    };
+    let name = string_field(&info, "name")
+        .map(|name| String::from_utf8_lossy(&name).to_lowercase())
+        .ok_or_else(|| invalid_metadata("command info has no name"))?;
+    let specs: Vec<KeySpec> = match field(&info, "key_specs") {
+        Some(Value::Array(specs)) => specs.iter().filter_map(parse_key_spec).collect(),
+        // servers before 7.0 report only the legacy first key, last key and step
+        _ => match (
+            int_field(&info, "first_key"),
+            int_field(&info, "last_key"),
+            int_field(&info, "step"),
+        ) {
+            (Some(first_key), Some(last_key), Some(step)) if first_key > 0 => vec![KeySpec {
+                begin_search: BeginSearch::Index(first_key as usize),
+                find_keys: FindKeys::Range {
+                    last_key: if last_key >= 0 {
+                        last_key - first_key
+                    } else {
+                        last_key
+                    },
+                    key_step: step.max(1) as usize,
+                    limit: 0,
+                },
+            }],
+            _ => vec![],
+        },
+    };
+    if !specs.is_empty() {
+        key_specs.insert(name, specs);
+    }
+    if let Some(Value::Array(subcommands)) = field(&info, "subcommands") {
+        for subcommand in subcommands {
+            add_key_specs(key_specs, subcommand.clone())?;
+        }
+    }
+    Ok(())
+}
+
+fn invalid_metadata(detail: &str) -> RedisError {
+    RedisError::from((
+        ErrorKind::TypeError,
+        "Unexpected COMMAND response",
+        detail.to_string(),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bulk(value: &str) -> Value {
+        Value::BulkString(value.as_bytes().to_vec())
+    }
+
+    fn flat(entries: Vec<(&str, Value)>) -> Value {
+        Value::Array(
+            entries
+                .into_iter()
+                .flat_map(|(field, value)| [bulk(field), value])
+                .collect(),
+        )
+    }
+
+    fn key_spec(begin: (&str, Value), find: (&str, Value)) -> Value {
+        flat(vec![
+            (
+                "begin_search",
+                flat(vec![("type", bulk(begin.0)), ("spec", begin.1)]),
+            ),
+            (
+                "find_keys",
+                flat(vec![("type", bulk(find.0)), ("spec", find.1)]),
+            ),
+        ])
+    }
+
+    fn index(index: i64) -> (&'static str, Value) {
+        ("index", flat(vec![("index", Value::Int(index))]))
+    }
+
+    fn range(last_key: i64, key_step: i64, limit: i64) -> (&'static str, Value) {
+        (
+            "range",
+            flat(vec![
+                ("lastkey", Value::Int(last_key)),
+                ("keystep", Value::Int(key_step)),
+                ("limit", Value::Int(limit)),
+            ]),
+        )
+    }
+
+    fn command(name: &str, key_specs: Vec<Value>) -> Value {
+        Value::Array(vec![
+            bulk(name),
+            Value::Int(-1),
+            Value::Array(vec![]),
+            Value::Int(0),
+            Value::Int(0),
+            Value::Int(0),
+            Value::Array(vec![]),
+            Value::Array(vec![]),
+            Value::Array(key_specs),
+            Value::Array(vec![]),
+        ])
+    }
+
+    fn key_prefix() -> KeyPrefix {
+        key_prefix_of("tenant:")
+    }
+
+    fn key_prefix_of(prefix: &str) -> KeyPrefix {
+        let commands = Value::Array(vec![
+            command("get", vec![key_spec(index(1), range(0, 1, 0))]),
+            command("mset", vec![key_spec(index(1), range(-1, 2, 0))]),
+            command(
+                "zunionstore",
+                vec![
+                    key_spec(index(1), range(0, 1, 0)),
+                    key_spec(
+                        index(2),
+                        (
+                            "keynum",
+                            flat(vec![
+                                ("keynumidx", Value::Int(0)),
+                                ("firstkey", Value::Int(1)),
+                                ("keystep", Value::Int(1)),
+                            ]),
+                        ),
+                    ),
+                ],
+            ),
+            command(
+                "xread",
+                vec![key_spec(
+                    (
+                        "keyword",
+                        flat(vec![
+                            ("keyword", bulk("STREAMS")),
+                            ("startfrom", Value::Int(1)),
+                        ]),
+                    ),
+                    range(-1, 1, 2),
+                )],
+            ),
+            // a legacy entry without key specifications
+            Value::Array(vec![
+                bulk("del"),
+                Value::Int(-2),
+                Value::Array(vec![]),
+                Value::Int(1),
+                Value::Int(-1),
+                Value::Int(1),
+            ]),
+        ]);
+        KeyPrefix::new(prefix.as_bytes().to_vec(), true, commands).unwrap()
+    }
+
+    fn args(cmd: &Cmd) -> Vec<String> {
+        cmd.args_iter()
+            .map(|arg| match arg {
+                Arg::Simple(arg) => String::from_utf8_lossy(arg).to_string(),
+                Arg::Cursor => "CURSOR".to_string(),
+            })
+            .collect()
+    }
+
+    fn prefixed(key_prefix: &KeyPrefix, args_to_prefix: &[&str]) -> Vec<String> {
+        let mut cmd = Cmd::new();
+        for arg in args_to_prefix {
+            cmd.arg(*arg);
+        }
+        args(&key_prefix.apply(cmd))
+    }
+
+    #[test]
+    fn test_all_keys_of_multi_key_commands_are_prefixed() {
+        let key_prefix = key_prefix();
+        assert_eq!(prefixed(&key_prefix, &["GET", "a"]), ["GET", "tenant:a"]);
+        assert_eq!(
+            prefixed(&key_prefix, &["MSET", "a", "1", "b", "2"]),
+            ["MSET", "tenant:a", "1", "tenant:b", "2"]
+        );
+        assert_eq!(
+            prefixed(
+                &key_prefix,
+                &["ZUNIONSTORE", "dest", "2", "a", "b", "WEIGHTS", "1", "2"]
+            ),
+            [
+                "ZUNIONSTORE",
+                "tenant:dest",
+                "2",
+                "tenant:a",
+                "tenant:b",
+                "WEIGHTS",
+                "1",
+                "2"
+            ]
+        );
+        assert_eq!(
+            prefixed(
+                &key_prefix,
+                &["XREAD", "COUNT", "2", "STREAMS", "a", "b", "0", "0"]
+            ),
+            ["XREAD", "COUNT", "2", "STREAMS", "tenant:a", "tenant:b", "0", "0"]
+        );
+        assert_eq!(
+            prefixed(&key_prefix, &["DEL", "a", "b"]),
+            ["DEL", "tenant:a", "tenant:b"]
+        );
+        assert_eq!(prefixed(&key_prefix, &["PING", "a"]), ["PING", "a"]);
+    }
+
+    #[test]
+    fn test_default_hash_tag_maps_all_keys_to_one_slot() {
+        // The prefix of a key prefix and a default hash tag, see `ConnectionConfig::default_hash_tag`
+        let key_prefix = key_prefix_of("tenant:{t1}");
+        let keys: Vec<String> = [
+            prefixed(&key_prefix, &["MSET", "a", "1", "b", "2"]),
+            prefixed(&key_prefix, &["ZUNIONSTORE", "dest", "2", "c", "{user}d"]),
+            prefixed(&key_prefix, &["DEL", "e"]),
+        ]
+        .into_iter()
+        .flat_map(|args| args.into_iter().filter(|arg| arg.starts_with("tenant:")))
+        .collect();
+        assert_eq!(keys.len(), 6);
+        assert!(keys.contains(&"tenant:{t1}{user}d".to_string()));
+        let slot = redis::cluster_topology::get_slot(b"t1");
+        assert!(keys
+            .iter()
+            .all(|key| redis::cluster_topology::get_slot(key.as_bytes()) == slot));
+    }
+
+    #[test]
+    fn test_patterns_are_prefixed_and_responses_stripped() {
+        let key_prefix = key_prefix();
+        assert_eq!(
+            prefixed(&key_prefix, &["KEYS", "user*"]),
+            ["KEYS", "tenant:user*"]
+        );
+        assert_eq!(
+            prefixed(&key_prefix, &["SCAN", "0"]),
+            ["SCAN", "0", "MATCH", "tenant:*"]
+        );
+        assert_eq!(
+            prefixed(&key_prefix, &["SCAN", "0", "MATCH", "a?", "COUNT", "10"]),
+            ["SCAN", "0", "MATCH", "tenant:a?", "COUNT", "10"]
+        );
+
+        let response = Value::Array(vec![
+            bulk("0"),
+            Value::Array(vec![bulk("tenant:a"), bulk("tenant:b")]),
+        ]);
+        let strip = key_prefix.response_converter(&redis::cmd("SCAN")).unwrap();
+        assert_eq!(
+            strip(response).unwrap(),
+            Value::Array(vec![bulk("0"), Value::Array(vec![bulk("a"), bulk("b")])])
+        );
+        assert!(key_prefix.response_converter(&redis::cmd("GET")).is_none());
+    }
+}
@@ -0,0 +1,153 @@
+// Copyright Valkey GLIDE Project Contributors - SPDX Identifier: Apache-2.0
+
+use redis::{ErrorKind, RedisError, RedisResult, Value};
+
+/// Build a map value with the given string keys.
+fn map(entries: Vec<(&str, Value)>) -> Value {
+    Value::Map(
+        entries
+            .into_iter()
+            .map(|(key, value)| (Value::BulkString(key.as_bytes().to_vec()), value))
+            .collect(),
+    )
+}
+
+/// Convert an inclusive `[start, end]` range of a match to a map with `start` and `end` integers.
+fn range(value: Value) -> RedisResult<Value> {
+    match value {
+        Value::Array(range) => match <[Value; 2]>::try_from(range) {
+            Ok([start @ Value::Int(_), end @ Value::Int(_)]) => {
+                Ok(map(vec![("start", start), ("end", end)]))
+            }
+            _ => Err(invalid_response("match range is not a pair of integers")),
+        },
+        _ => Err(invalid_response("match range is not an array")),
+    }
+}
+
+/// Convert a match, `[[start1, end1], [start2, end2]]` or `[[start1, end1], [start2, end2], length]` with `WITHMATCHLEN`,
+/// to a map with the `key1` and `key2` ranges and, if present, the `match_len` integer.
+fn convert_match(value: Value) -> RedisResult<Value> {
+    let Value::Array(items) = value else {
+        return Err(invalid_response("match is not an array"));
+    };
+    let mut items = items.into_iter();
+    let (Some(key1), Some(key2)) = (items.next(), items.next()) else {
+        return Err(invalid_response(
+            "match doesn't contain the ranges of both keys",
+        ));
+    };
+    let mut entries = vec![("key1", range(key1)?), ("key2", range(key2)?)];
+    match (items.next(), items.next()) {
+        (None, None) => {}
+        (Some(length @ Value::Int(_)), None) => entries.push(("match_len", length)),
+        _ => return Err(invalid_response("match length is not an integer")),
+    }
+    Ok(map(entries))
+}
+
+/// Convert an `LCS ... IDX` response, which glide-core converts to a map of `matches` and `len`, to a map of
+/// `matches` - an array of maps with the `key1` and `key2` ranges, each a map of inclusive `start` and `end` integers,
+/// and the `match_len` integer with `WITHMATCHLEN` - and `len`, the length of the longest common subsequence.
+/// Responses without `IDX`, the subsequence itself or its length, are returned as is.
+pub(crate) fn convert_lcs(value: Value) -> RedisResult<Value> {
+    let Value::Map(fields) = value else {
+        return Ok(value);
+    };
+    let mut entries = Vec::with_capacity(fields.len());
+    for (field, value) in fields {
+        let field = match &field {
+            Value::SimpleString(field) => field.as_bytes(),
+            Value::BulkString(field) => field.as_slice(),
+            _ => return Err(invalid_response("field name is not a string")),
+        };
+        match (field, value) {
+            (b"matches", Value::Array(matches)) => entries.push((
+                "matches",
+                matches
+                    .into_iter()
+                    .map(convert_match)
+                    .collect::<RedisResult<_>>()
+                    .map(Value::Array)?,
+            )),
+            (b"len", len @ Value::Int(_)) => entries.push(("len", len)),
+            _ => return Err(invalid_response("unexpected field")),
+        }
+    }
+    Ok(map(entries))
+}
+
+fn invalid_response(detail: &str) -> RedisError {
+    RedisError::from((
+        ErrorKind::TypeError,
+        "Unexpected LCS response",
+        detail.to_string(),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pair(start: i64, end: i64) -> Value {
+        Value::Array(vec![Value::Int(start), Value::Int(end)])
+    }
+
+    fn typed_range(start: i64, end: i64) -> Value {
+        map(vec![("start", Value::Int(start)), ("end", Value::Int(end))])
+    }
+
+    #[test]
+    fn test_convert_lcs_with_match_len() {
+        let value = Value::Map(vec![
+            (
+                Value::SimpleString("matches".to_string()),
+                Value::Array(vec![
+                    Value::Array(vec![pair(4, 7), pair(5, 8), Value::Int(4)]),
+                    Value::Array(vec![pair(2, 3), pair(0, 1), Value::Int(2)]),
+                ]),
+            ),
+            (Value::SimpleString("len".to_string()), Value::Int(6)),
+        ]);
+        assert_eq!(
+            convert_lcs(value).unwrap(),
+            map(vec![
+                (
+                    "matches",
+                    Value::Array(vec![
+                        map(vec![
+                            ("key1", typed_range(4, 7)),
+                            ("key2", typed_range(5, 8)),
+                            ("match_len", Value::Int(4)),
+                        ]),
+                        map(vec![
+                            ("key1", typed_range(2, 3)),
+                            ("key2", typed_range(0, 1)),
+                            ("match_len", Value::Int(2)),
+                        ]),
+                    ])
+                ),
+                ("len", Value::Int(6)),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_convert_lcs_without_idx() {
+        let subsequence = Value::BulkString(b"mytext".to_vec());
+        assert_eq!(convert_lcs(subsequence.clone()).unwrap(), subsequence);
+        assert_eq!(convert_lcs(Value::Int(6)).unwrap(), Value::Int(6));
+    }
+
+    #[test]
+    fn test_convert_lcs_rejects_malformed_range() {
+        let value = Value::Map(vec![(
+            Value::SimpleString("matches".to_string()),
+            Value::Array(vec![Value::Array(vec![
+                pair(4, 7),
+                Value::Array(vec![Value::Int(5)]),
+            ])]),
+        )]);
+        assert!(convert_lcs(value).is_err());
+    }
+}
@@ -0,0 +1,249 @@
+// Copyright Valkey GLIDE Project Contributors - SPDX Identifier: Apache-2.0
+
+//! Connection leases, which pin a sequence of commands depending on connection state
+//! (e.g. `SELECT` and then `GET`, or `WATCH` and then `MULTI`) to a single dedicated connection.
+//! Commands of the shared client are multiplexed, so concurrent commands may change the state in between.
+
+use std::{
+    collections::HashMap,
+    ffi::{c_char, c_void, CString},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex, Weak,
+    },
+};
+
+#[cfg(feature = "testing")]
+use glide_core::client::TlsMode;
+use glide_core::{
+    client::{Client as GlideClient, ConnectionRequest, ReadFrom},
+    request_type::RequestType,
+};
+use redis::{RedisError, RedisResult};
+
+use crate::{
+    client_from_ptr, create_command,
+    errors::RequestErrorType,
+    ffi::{create_route, RouteInfo},
+    Client, FailureCallback,
+};
+
+/// Dedicated connections of a client, by lease id.
+pub(crate) struct LeasePool {
+    /// The connection request of the client, used to open dedicated connections.
+    request: ConnectionRequest,
+    next_id: AtomicU64,
+    connections: Mutex<HashMap<u64, GlideClient>>,
+}
+
+impl LeasePool {
+    pub(crate) fn new(request: &ConnectionRequest) -> Self {
+        let mut request = request.clone();
+        // Commands on a lease must reach the connection to the primary, and it shouldn't receive pushes.
+        request.read_from = Some(ReadFrom::Primary);
+        request.pubsub_subscriptions = None;
+        LeasePool {
+            request,
+            next_id: AtomicU64::new(1),
+            connections: Default::default(),
+        }
+    }
+
+    /// Open a dedicated connection, which isn't tracked by the pool.
+    pub(crate) async fn connect(&self) -> RedisResult<GlideClient> {
+        GlideClient::new(self.request.clone(), None)
+            .await
+            .map_err(|err| {
+                RedisError::from((
+                    redis::ErrorKind::ClientError,
+                    "Failed to connect",
+                    err.to_string(),
+                ))
+            })
+    }
+
+    /// Open a single connection to the node at `address` (`host:port`), bypassing glide-core, for commands which take over
+    /// the connection, e.g. `MONITOR`. It's set up with the credentials, the protocol, the database and the TLS mode of the client,
+    /// but TLS validates the host of the address rather than [`ConnectionRequest::tls_sni_hostname`].
+    #[cfg(feature = "testing")]
+    #[allow(deprecated)]
+    pub(crate) async fn connect_to_node(
+        &self,
+        address: &str,
+    ) -> RedisResult<redis::aio::Connection> {
+        let invalid_address = || {
+            RedisError::from((
+                redis::ErrorKind::ClientError,
+                "Invalid node address",
+                address.to_string(),
+            ))
+        };
+        let (host, port) = address.rsplit_once(':').ok_or_else(invalid_address)?;
+        let port = port.parse().map_err(|_| invalid_address())?;
+        let host = host.to_string();
+        let addr = match self.request.tls_mode.unwrap_or(TlsMode::NoTls) {
+            TlsMode::NoTls => redis::ConnectionAddr::Tcp(host, port),
+            tls_mode => redis::ConnectionAddr::TcpTls {
+                host,
+                port,
+                insecure: tls_mode == TlsMode::InsecureTls,
+                tls_params: None,
+            },
+        };
+        let authentication_info = self.request.authentication_info.as_ref();
+        let connection_info = redis::ConnectionInfo {
+            addr,
+            redis: redis::RedisConnectionInfo {
+                db: self.request.database_id,
+                username: authentication_info.and_then(|info| info.username.clone()),
+                password: authentication_info.and_then(|info| info.password.clone()),
+                protocol: self.request.protocol.unwrap_or_default(),
+                client_name: self.request.client_name.clone(),
+                pubsub_subscriptions: None,
+            },
+        };
+        redis::Client::open(connection_info)?
+            .get_async_connection(None)
+            .await
+    }
+
+    async fn acquire(&self) -> RedisResult<u64> {
+        let connection = self.connect().await?;
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        self.connections.lock().unwrap().insert(id, connection);
+        Ok(id)
+    }
+
+    fn get(&self, id: u64) -> Option<GlideClient> {
+        self.connections.lock().unwrap().get(&id).cloned()
+    }
+
+    fn release(&self, id: u64) {
+        self.connections.lock().unwrap().remove(&id);
+    }
+}
+
+/// An opaque lease handle, returned by [`acquire_connection`].
+/// It doesn't keep the client alive, so the leased connection is closed with the client even if the lease is never released.
+struct Lease {
+    client: Weak<Client>,
+    /// The failure callback of the client, to report requests submitted after the client was deallocated.
+    failure_callback: FailureCallback,
+    id: u64,
+}
+
+/// Lease a dedicated connection, on which [`command_on_lease`] runs commands until [`release_connection`] is called.
+/// In cluster mode the lease holds a dedicated connection per node, so commands routed to the same node share a connection.
+/// Returns `null` if the client is closed or the connection can't be established.
+///
+/// This function blocks while connecting, so it must not be called from a callback of the client.
+///
+/// # Safety
+/// * `client_ptr` must not be `null`.
+/// * `client_ptr` must be a valid pointer created by [`create_client`](crate::create_client), before [`close_client`](crate::close_client) was called with the pointer.
+#[no_mangle]
+pub unsafe extern "C" fn acquire_connection(client_ptr: *const c_void) -> *const c_void {
+    let Some(client) = (unsafe { client_from_ptr(client_ptr) }) else {
+        return std::ptr::null();
+    };
+    match client.runtime.block_on(client.core.leases.acquire()) {
+        Ok(id) => Box::into_raw(Box::new(Lease {
+            client: Arc::downgrade(&client),
+            failure_callback: client.core.failure_callback,
+            id,
+        })) as *const c_void,
+        Err(err) => {
+            logger_core::log_warn(
+                "connection lease",
+                format!("Failed to lease a connection: {err}"),
+            );
+            std::ptr::null()
+        }
+    }
+}
+
+/// Execute a command on the connection of a lease, like [`command`](crate::command).
+/// Fails with [`RequestErrorType::ClientClosed`] once the client is closed, also if it was already deallocated.
+///
+/// # Safety
+/// * `lease_ptr` must not be `null`.
+/// * `lease_ptr` must be a pointer returned by [`acquire_connection`], before [`release_connection`] was called with the pointer.
+/// * `args` must point to `arg_count` consecutive string pointers and `args_len` must point to their lengths.
+///   See the safety documentation of [`convert_double_pointer_to_vec`](crate::ffi::convert_double_pointer_to_vec).
+/// * `route_info` could be `null`, but if it is not `null`, it must be a valid [`RouteInfo`] pointer. See the safety documentation of [`create_route`].
+#[no_mangle]
+pub unsafe extern "C" fn command_on_lease(
+    lease_ptr: *const c_void,
+    callback_index: usize,
+    request_type: RequestType,
+    args: *const *mut c_char,
+    arg_count: u32,
+    args_len: *const u32,
+    route_info: *const RouteInfo,
+) {
+    let lease = unsafe { &*(lease_ptr as *const Lease) };
+    let Some(client) = lease
+        .client
+        .upgrade()
+        .filter(|client| !client.closed.load(Ordering::Acquire))
+    else {
+        let message = CString::new("Client was closed").unwrap_or_default();
+        unsafe {
+            (lease.failure_callback)(
+                callback_index,
+                RequestErrorType::ClientClosed,
+                message.as_ptr(),
+            )
+        };
+        return;
+    };
+    let core = client.core.clone();
+    let cmd = match unsafe {
+        create_command(
+            &request_type,
+            args,
+            arg_count,
+            args_len,
+            client.core.log_non_utf8_arguments,
+        )
+    } {
+        Ok(cmd) => cmd,
+        Err(err) => {
+            unsafe { core.report_error(callback_index, err) };
+            return;
+        }
+    };
+    if let Err(err) = core.check_command_allowed(&cmd) {
+        unsafe { core.report_error(callback_index, err) };
+        return;
+    }
+    let Some(mut connection) = core.leases.get(lease.id) else {
+        let err = RedisError::from((
+            redis::ErrorKind::ClientError,
+            "Connection lease was released",
+        ));
+        unsafe { core.report_error(callback_index, err) };
+        return;
+    };
+    let route = unsafe { create_route(route_info, &cmd) };
+    client.runtime.spawn(async move {
+        let result = connection.send_command(&cmd, route).await;
+        core.track_authentication(&result);
+        unsafe { core.report_result(callback_index, core.normalize_reply(result)) };
+    });
+}
+
+/// Release a lease acquired by [`acquire_connection`], closing its connection.
+/// The connection isn't reused by other leases, so state set on it (e.g. the selected database) doesn't leak.
+/// Commands submitted on the lease before are still answered.
+///
+/// # Safety
+/// * `lease_ptr` must not be `null`.
+/// * `lease_ptr` must be a pointer returned by [`acquire_connection`], which wasn't released yet.
+#[no_mangle]
+pub unsafe extern "C" fn release_connection(lease_ptr: *const c_void) {
+    let lease = unsafe { Box::from_raw(lease_ptr as *mut Lease) };
+    if let Some(client) = lease.client.upgrade() {
+        client.core.leases.release(lease.id);
+    }
+}
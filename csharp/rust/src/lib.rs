@@ -1,17 +1,145 @@
 // Copyright Valkey GLIDE Project Contributors - SPDX Identifier: Apache-2.0
 
+#[cfg(feature = "testing")]
+#[doc(hidden)]
+pub mod benchmarks;
+mod bitmap;
+pub mod blocking;
+mod bulk_expire;
+mod bulk_set;
+mod callback_watchdog;
+mod circuit_breaker;
+mod client_pool;
+mod client_registry;
+mod client_tracking;
+mod cluster_nodes;
+mod command_counts;
+mod command_filter;
+mod command_info;
+mod command_trace;
+pub mod commands;
+mod compression;
+mod connection_timings;
+mod credential_provider;
+#[cfg(feature = "testing")]
+mod debug;
+mod default_route;
+mod delete_by_pattern;
+mod durable_set;
+mod effective_config;
+mod errors;
 mod ffi;
+mod future;
+mod geo;
+mod info;
+mod key_ordering;
+mod key_prefix;
+mod lcs;
+mod lease;
+mod memory_stats;
+mod metrics;
+#[cfg(feature = "testing")]
+mod monitor;
+mod node_inflight;
+mod partial_batch;
+mod pending_tasks;
+mod primary_preferred;
+mod random_members;
+mod rate_limit;
+mod read_your_writes;
+mod redirection;
+mod reset;
+mod resolve_route;
+mod response_metadata;
+mod response_pool;
+mod route_decider;
+mod saturation;
+mod script_cache;
+mod server_capabilities;
+mod server_version;
+mod single_flight;
+mod stream_info;
+mod subscriptions;
+#[cfg(test)]
+mod test_utilities;
+mod topology;
+mod topology_snapshot;
+mod value_transform;
+use blocking::BlockingConnections;
+use callback_watchdog::CallbackWatchdog;
+use circuit_breaker::{CircuitBreaker, MAX_BACKOFF};
+use client_pool::ClientPool;
+use client_tracking::{report_invalidation, ClientTracking, InvalidationCallback};
+use command_counts::CommandCounts;
+use command_filter::CommandFilter;
+use command_trace::CommandTrace;
+use connection_timings::ConnectionTimings;
+use credential_provider::{CredentialProvider, CredentialRefresh};
+use default_route::DefaultRoute;
+use effective_config::EffectiveConfig;
+use errors::{
+    error_message, error_type, format_correlated_error, format_error, is_delivery_failure,
+    ErrorMessageFormat, RequestErrorType, BACKPRESSURE_CODE, NODE_UNAVAILABLE_CODE,
+    REAUTHENTICATION_FAILED_CODE, RESPONSE_TOO_LARGE_CODE,
+};
 use ffi::{
-    convert_double_pointer_to_vec, create_connection_request, create_route, ConnectionConfig,
-    ResponseValue, RouteInfo,
+    check_argument_sizes, convert_double_pointer_to_vec, create_client_tracking,
+    create_command_filter, create_connection_request, create_default_route, create_pipeline,
+    create_route, create_route_with_key_prefix, log_non_utf8_arguments, BatchInfo,
+    BatchOptionsInfo, ConnectionConfig, NodeInflightStatistics, ResponseValue, RouteInfo,
+};
+use glide_core::{
+    client::{
+        AuthenticationInfo, Client as GlideClient, ConnectionError, ReadFrom,
+        StandaloneClientConnectionError,
+    },
+    request_type::RequestType,
 };
-use glide_core::{client::Client as GlideClient, request_type::RequestType};
-use redis::RedisResult;
+use key_ordering::KeyOrdering;
+use key_prefix::KeyPrefix;
+use lease::LeasePool;
+use metrics::LatencyHistogram;
+use node_inflight::{InflightGuard, NodeInflightTracker, Reservation, HOT_NODE_CHECK_INTERVAL};
+use partial_batch::send_with_partial_results;
+use pending_tasks::{PendingTaskGuard, PendingTasks};
+use primary_preferred::{should_fall_back_to_replica, split_primary_preferred};
+use read_your_writes::RecentWrites;
+use redirection::RedirectionTracker;
+use redis::{
+    cluster_routing::{
+        is_readonly, MultipleNodeRoutingInfo, ResponsePolicy, Routable, Route, RoutingInfo,
+        SingleNodeRoutingInfo, SlotAddr,
+    },
+    Cmd, ConnectionObserver, Pipeline, PipelineRetryStrategy, ProtocolVersion, PushInfo, PushKind,
+    RedisError, RedisResult, Value,
+};
+use reset::reset_pipeline;
+use response_metadata::{split_attributes, ResponseMetadata};
+use response_pool::ResponsePool;
+use route_decider::{check_decided_route, decide_route, RouteDeciderCallback};
+use saturation::SaturationDetector;
+use server_capabilities::ServerCapabilities;
+use server_version::parse_server_version;
+use single_flight::{Flight, SingleFlight};
 use std::{
-    ffi::{c_char, c_void, CStr},
-    sync::Arc,
+    collections::{HashMap, HashSet},
+    ffi::{c_char, c_void, CStr, CString},
+    sync::{
+        atomic::{AtomicBool, AtomicU64, Ordering},
+        Arc, Mutex, RwLock,
+    },
+    time::{Duration, SystemTime, UNIX_EPOCH},
 };
+use subscriptions::subscriptions_value;
 use tokio::runtime::{Builder, Runtime};
+use tokio::sync::{
+    mpsc::{error::TrySendError, Receiver, Sender},
+    Notify,
+};
+use tokio::task::{JoinHandle, JoinSet};
+use tokio::time::Instant;
+use topology::{route_to_address, Topology};
+use value_transform::ValueTransform;
 
 #[repr(C)]
 pub enum Level {
@@ -23,150 +151,2880 @@ pub enum Level {
     Off = 5,
 }
 
-// TODO define `SuccessCallback` and `FailureCallback` types
-
-pub struct Client {
-    runtime: Runtime,
-    core: Arc<CommandExecutionCore>,
+/// Success callback that is called when a command succeeds.
+///
+/// The response value is allocated by Rust and should be freed with [`free_respose`].
+pub type SuccessCallback = unsafe extern "C" fn(usize, *const ResponseValue) -> ();
+
+/// Failure callback that is called when a command fails.
+///
+/// The error message is a null-terminated string, which is only valid until the callback returns,
+/// so it should be copied synchronously.
+pub type FailureCallback = unsafe extern "C" fn(usize, RequestErrorType, *const c_char) -> ();
+
+/// Type of a client event, reported through the [`EventCallback`].
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ClientEventType {
+    /// A node's in-flight requests depth stayed above the configured threshold for the configured duration.
+    /// The event details contain the node address.
+    HotNode = 0,
+    /// The server rejected the credentials of the client, while connecting or reconnecting.
+    /// Reported once, when the first request fails, until a request succeeds again, so the credentials
+    /// could be refreshed once rather than per request. The event details contain the error message.
+    AuthenticationFailed = 1,
+    /// The slot map of the cluster changed, e.g. due to a slot migration or a failover.
+    /// Detected when the topology snapshot is refreshed, periodically in cluster mode. The event details contain the addresses of the primaries, separated by commas.
+    TopologyChanged = 2,
+    /// The `MOVED` and `ASK` redirections handled by the client exceeded the configured rate, which indicates topology churn,
+    /// e.g. rebalancing. Reported at most once per second. The event details contain the redirection which exceeded the rate:
+    /// its kind, slot and target node, separated by spaces, e.g. `MOVED 3999 127.0.0.1:6381`.
+    Redirection = 3,
+    /// The delay between submitting a request and the start of its task on the runtime exceeded the configured threshold,
+    /// which indicates that the worker threads can't keep up with the load, rather than a slow server.
+    /// Reported at most once per second. The event details contain the measured delay in microseconds.
+    RuntimeSaturated = 4,
+    /// The circuit breaker opened after consecutive `CLUSTERDOWN` errors, so commands fail fast until it closes,
+    /// see [`ConnectionConfig::circuit_breaker_threshold`]. The event details contain the error message of the last command.
+    CircuitOpen = 5,
+    /// The circuit breaker closed after a successful topology refresh, so commands are dispatched again.
+    /// The event details contain the duration the breaker was open in milliseconds.
+    CircuitClosed = 6,
+}
+
+/// Event callback that is called when a client event occurs.
+///
+/// The event details are a null-terminated string, which is only valid until the callback returns,
+/// so it should be copied synchronously.
+pub type EventCallback = unsafe extern "C" fn(ClientEventType, *const c_char) -> ();
+
+/// Slow command callback that is called when the latency of a command exceeds the configured threshold.
+///
+/// The arguments are the command name (e.g. `GET` or `CONFIG GET`), the latency in microseconds and the address of the node
+/// the command was sent to, or `null` if the command wasn't sent to a single known node.
+/// The strings are null-terminated and only valid until the callback returns, so they should be copied synchronously.
+pub type SlowCommandCallback = unsafe extern "C" fn(*const c_char, u64, *const c_char) -> ();
+
+pub struct Client {
+    runtime: Runtime,
+    core: Arc<CommandExecutionCore>,
+    /// Set by [`close_client`], requests submitted afterwards fail with [`RequestErrorType::ClientClosed`].
+    closed: AtomicBool,
+    /// The periodic topology refresh task in cluster mode, see [`ConnectionConfig::periodic_topology_refresh_secs`],
+    /// `None` if it's disabled. It is stopped once the client is closed.
+    topology_refresh: Mutex<Option<JoinHandle<()>>>,
+    /// The task updating the topology after `MOVED` redirections in cluster mode, stopped once the client is closed.
+    topology_sync: Mutex<Option<JoinHandle<()>>>,
+    /// The task reporting hot nodes, see [`ConnectionConfig::hot_node_threshold`], stopped once the client is closed.
+    hot_node_check: Mutex<Option<JoinHandle<()>>>,
+    /// The keepalive task, see [`ConnectionConfig::client_keepalive_interval_secs`], stopped once the client is closed.
+    keepalive: Mutex<Option<JoinHandle<()>>>,
+}
+
+impl Client {
+    /// Cancel the periodic tasks of the client and wait for them to stop, unless called from a thread of the runtime.
+    fn stop_periodic_tasks(&self) {
+        for task in [
+            &self.topology_refresh,
+            &self.topology_sync,
+            &self.hot_node_check,
+            &self.keepalive,
+        ] {
+            let Some(task) = task.lock().unwrap().take() else {
+                continue;
+            };
+            task.abort();
+            if tokio::runtime::Handle::try_current().is_err() {
+                let _ = self.runtime.block_on(task);
+            }
+        }
+    }
+}
+
+struct CommandExecutionCore {
+    /// The first client of [`Self::client_pool`], which is used for commands about the client itself, e.g. topology refreshes.
+    client: GlideClient,
+    /// Clients which commands are spread across, see [`ConnectionConfig::connections_per_node`].
+    client_pool: ClientPool,
+    success_callback: SuccessCallback,
+    failure_callback: FailureCallback,
+    /// Times the success and failure callbacks, and offloads them if configured, see [`ConnectionConfig::offload_callbacks`].
+    callback_watchdog: CallbackWatchdog,
+    /// Allocations of the responses passed to the success callback, see [`ConnectionConfig::response_pool_capacity`].
+    response_pool: Option<Arc<ResponsePool>>,
+    event_callback: Option<EventCallback>,
+    /// Latency above which commands are reported through the slow command callback, `None` if disabled.
+    slow_command_threshold: Option<Duration>,
+    /// Sends the slow commands to the task calling the slow command callback, so the callback doesn't delay the commands.
+    slow_commands: Option<Sender<SlowCommand>>,
+    /// Slow commands which weren't reported, since [`SLOW_COMMANDS_CAPACITY`] commands were waiting for the callback already.
+    dropped_slow_commands: AtomicU64,
+    /// Address of the node in standalone mode, `None` in cluster mode.
+    standalone_address: Option<String>,
+    topology: RwLock<Arc<Topology>>,
+    node_inflight: Arc<NodeInflightTracker>,
+    /// Commands and batches spawned on the runtime whose callback wasn't called yet, see [`ConnectionConfig::max_pending_commands`].
+    pending_tasks: Arc<PendingTasks>,
+    fail_fast_on_degraded: bool,
+    /// Nodes on which commands failed with a connection or a cluster-down error since the last topology refresh.
+    unhealthy_nodes: Mutex<HashSet<String>>,
+    /// See [`ConnectionConfig::circuit_breaker_threshold`]. `None` if the breaker is disabled or in standalone mode.
+    circuit_breaker: Option<CircuitBreaker>,
+    /// Minimal size of values compressed by the compression helpers, `0` means compression is disabled.
+    compression_threshold: u32,
+    default_route: Option<DefaultRoute>,
+    /// Allow or deny list of commands, `None` if all commands are allowed.
+    command_filter: Option<CommandFilter>,
+    /// Slots written recently, whose reads are routed to the primary. `None` if read-your-writes is disabled or in standalone mode.
+    recent_writes: Option<RecentWrites>,
+    /// Set once a request failed authentication, until a request succeeds.
+    authentication_failed: AtomicBool,
+    /// Server versions by node address. A node's version is dropped once it disconnects, so it is queried again after reconnecting.
+    server_versions: Mutex<HashMap<String, String>>,
+    /// Log command arguments which aren't valid UTF-8, see [`ConnectionConfig::log_non_utf8_arguments`].
+    log_non_utf8_arguments: bool,
+    /// Dedicated connections leased by [`lease::acquire_connection`], closed with the client if they weren't released.
+    leases: LeasePool,
+    /// Idle dedicated connections of blocking pops, see [`blocking`].
+    blocking_connections: BlockingConnections,
+    /// Re-authenticate a node and retry the command once, when a command fails because the node's connection lost
+    /// its authentication (`NOAUTH`), see [`ConnectionConfig::reauthenticate_on_noauth`].
+    reauthenticate_on_noauth: bool,
+    /// The username and the password of the client, used to re-authenticate.
+    credentials: Option<(Option<String>, String)>,
+    /// Source of fresh passwords, which replace the password of [`CommandExecutionCore::credentials`] once it is needed again,
+    /// see [`ConnectionConfig::credential_provider`].
+    credential_provider: Option<CredentialProvider>,
+    /// Latencies of the commands dispatched by [`dispatch_command_with_affinity`], exposed by [`get_metrics_prometheus`].
+    command_latency: LatencyHistogram,
+    /// Counters of the redirections handled in cluster mode, exposed by [`get_statistics`].
+    redirections: Arc<RedirectionTracker>,
+    /// Connection timings of every node, returned by [`get_connection_timings`].
+    connection_timings: Arc<ConnectionTimings>,
+    /// Capabilities of every node reported by `HELLO`, returned by [`get_server_capabilities`], and the protocol negotiated with
+    /// every node, returned by [`get_protocol_versions`].
+    server_capabilities: Arc<ServerCapabilities>,
+    /// Orders the commands on the same key, see [`ConnectionConfig::order_commands_per_key`]. `None` if disabled.
+    key_ordering: Option<Arc<KeyOrdering>>,
+    /// Coalesces concurrent identical reads submitted by [`command`], `None` if disabled.
+    single_flight: Option<SingleFlight>,
+    /// Maximal size of a converted response, see [`ConnectionConfig::max_response_bytes`]. `0` means no limit.
+    max_response_bytes: u64,
+    /// Maximal size of an argument of a command, see [`ConnectionConfig::max_command_arg_bytes`]. `0` means no limit.
+    max_command_arg_bytes: u32,
+    /// Commands submitted by [`command`] per request type, returned by [`get_command_counts`].
+    command_counts: CommandCounts,
+    /// The last commands, see [`ConnectionConfig::command_trace_capacity`]. `None` if recording is disabled.
+    command_trace: Option<CommandTrace>,
+    /// See [`ConnectionConfig::runtime_saturation_threshold`]. `None` if the detection is disabled.
+    runtime_saturation: Option<SaturationDetector>,
+    /// Tracking enabled on the connections, see [`ConnectionConfig::client_tracking_mode`]. `None` if disabled.
+    client_tracking: Option<ClientTracking>,
+    invalidation_callback: Option<InvalidationCallback>,
+    /// Decides the routes of commands submitted without a route, see [`RouteDeciderCallback`]. Always `None` in standalone mode.
+    route_decider: Option<RouteDeciderCallback>,
+    /// Retry reads which failed on a replica on the primary, see [`ConnectionConfig::fallback_to_primary_on_replica_error`].
+    /// Always `false` in standalone mode.
+    fallback_to_primary_on_replica_error: bool,
+    /// Retry writes which reached a replica on the primary, see [`ConnectionConfig::retry_writes_on_readonly`].
+    /// Always `false` in standalone mode.
+    retry_writes_on_readonly: bool,
+    /// Whether reads without a route are served by replicas, according to the `read_from` strategy.
+    reads_from_replicas: bool,
+    /// Prefix of the keys of commands, see [`ConnectionConfig::key_prefix`], followed by [`ConnectionConfig::default_hash_tag`].
+    /// `None` if keys aren't prefixed.
+    key_prefix: Option<KeyPrefix>,
+    /// Encodes and decodes the values of commands, see [`ConnectionConfig::value_encoder`]. `None` if values are sent as is.
+    value_transform: Option<ValueTransform>,
+    /// The resolved configuration of the client, returned by [`get_effective_config`].
+    effective_config: Value,
+    /// The subscriptions of the client, returned by [`get_subscriptions`].
+    subscriptions: Value,
+    /// The pipeline which resets a connection and applies its setup again, see [`reset_connection`](reset::reset_connection).
+    connection_reset: Pipeline,
+    /// Replace empty replies by null, see [`ConnectionConfig::normalize_empty_replies`].
+    normalize_empty_replies: bool,
+    /// Return replies with their metadata, see [`ConnectionConfig::response_metadata`].
+    response_metadata: bool,
+    /// Format of the error messages passed to the failure callback.
+    error_message_format: ErrorMessageFormat,
+}
+
+impl CommandExecutionCore {
+    /// Report the given error through the failure callback.
+    ///
+    /// # Safety
+    ///
+    /// * `failure_callback` must be safe to call with any `callback_index` submitted to the client.
+    unsafe fn report_error(&self, callback_index: usize, err: RedisError) {
+        unsafe { self.report_correlated_error(callback_index, err, None) };
+    }
+
+    /// Report the given error through the failure callback, like [`Self::report_error`]. If the request has a correlation id,
+    /// the error is logged with it, and the message passed to the failure callback is tagged with it, see [`format_correlated_error`].
+    ///
+    /// # Safety
+    ///
+    /// * `failure_callback` must be safe to call with any `callback_index` submitted to the client.
+    unsafe fn report_correlated_error(
+        &self,
+        callback_index: usize,
+        err: RedisError,
+        correlation_id: Option<&str>,
+    ) {
+        let message = match correlation_id {
+            Some(correlation_id) => {
+                logger_core::log_warn(
+                    "command",
+                    format!("Request with correlation id {correlation_id} failed: {err}"),
+                );
+                format_correlated_error(&err, self.error_message_format, correlation_id)
+            }
+            None => format_error(&err, self.error_message_format),
+        };
+        let message = CString::new(message).unwrap_or_default();
+        unsafe { self.call_failure_callback(callback_index, error_type(&err), message) };
+    }
+
+    /// Call the success callback with a response, through the [`CallbackWatchdog`].
+    ///
+    /// # Safety
+    ///
+    /// * `success_callback` must be safe to call with any `callback_index` submitted to the client.
+    unsafe fn call_success_callback(&self, callback_index: usize, response: *mut ResponseValue) {
+        let success_callback = self.success_callback;
+        // The response is owned by the callback, which may be called on another thread
+        let response = response as usize;
+        self.callback_watchdog.call(callback_index, move || unsafe {
+            success_callback(callback_index, response as *const ResponseValue)
+        });
+    }
+
+    /// Call the failure callback with an error, through the [`CallbackWatchdog`].
+    /// The message is kept until the callback returns.
+    ///
+    /// # Safety
+    ///
+    /// * `failure_callback` must be safe to call with any `callback_index` submitted to the client.
+    unsafe fn call_failure_callback(
+        &self,
+        callback_index: usize,
+        error_type: RequestErrorType,
+        message: CString,
+    ) {
+        let failure_callback = self.failure_callback;
+        self.callback_watchdog.call(callback_index, move || unsafe {
+            failure_callback(callback_index, error_type, message.as_ptr())
+        });
+    }
+
+    /// Fail a command with an argument which exceeds [`ConnectionConfig::max_command_arg_bytes`], before its arguments are copied.
+    ///
+    /// # Safety
+    ///
+    /// * `args_len` must point to `arg_count` consecutive argument lengths. See the safety documentation of [`check_argument_sizes`].
+    unsafe fn check_argument_sizes(
+        &self,
+        command_name: impl FnOnce() -> String,
+        args_len: *const u32,
+        arg_count: u32,
+    ) -> RedisResult<()> {
+        let result =
+            unsafe { check_argument_sizes(args_len, arg_count, self.max_command_arg_bytes) };
+        if let Err(err) = &result {
+            logger_core::log_warn(
+                "argument size",
+                format!("Rejected {}: {}", command_name(), error_message(err)),
+            );
+        }
+        result
+    }
+
+    /// Fail a response which exceeds [`ConnectionConfig::max_response_bytes`], before it is converted to a [`ResponseValue`].
+    fn limit_response_size(&self, result: RedisResult<Value>) -> RedisResult<Value> {
+        match result {
+            Ok(value)
+                if self.max_response_bytes > 0
+                    && ResponseValue::exceeds_size(&value, self.max_response_bytes) =>
+            {
+                Err(redis::make_extension_error(
+                    RESPONSE_TOO_LARGE_CODE.to_string(),
+                    Some(format!(
+                        "The response exceeds the maximal size of {} bytes",
+                        self.max_response_bytes
+                    )),
+                ))
+            }
+            result => result,
+        }
+    }
+
+    /// Report the result of a request through the success or the failure callback.
+    ///
+    /// # Safety
+    ///
+    /// * `success_callback` and `failure_callback` must be safe to call with any `callback_index` submitted to the client.
+    unsafe fn report_result(&self, callback_index: usize, result: RedisResult<Value>) {
+        unsafe { self.report_correlated_result(callback_index, result, None) };
+    }
+
+    /// Report the result of a request through the success or the failure callback, with the correlation id of the request,
+    /// see [`Self::report_correlated_error`].
+    ///
+    /// # Safety
+    ///
+    /// * `success_callback` and `failure_callback` must be safe to call with any `callback_index` submitted to the client.
+    unsafe fn report_correlated_result(
+        &self,
+        callback_index: usize,
+        result: RedisResult<Value>,
+        correlation_id: Option<&str>,
+    ) {
+        match self.limit_response_size(result) {
+            Ok(value) => {
+                let ptr = response_pool::into_raw(
+                    ResponseValue::from_value(value),
+                    self.response_pool.as_ref(),
+                );
+                unsafe { self.call_success_callback(callback_index, ptr) };
+            }
+            Err(err) => unsafe {
+                self.report_correlated_error(callback_index, err, correlation_id)
+            },
+        }
+    }
+
+    /// Report a result to every request of `callback_indices`, e.g. to the requests coalesced by [`SingleFlight`].
+    ///
+    /// # Safety
+    ///
+    /// * `success_callback` and `failure_callback` must be safe to call with any `callback_index` submitted to the client.
+    unsafe fn report_result_to_all(&self, callback_indices: &[usize], result: RedisResult<Value>) {
+        match self.limit_response_size(result) {
+            Ok(value) => {
+                for callback_index in callback_indices {
+                    let ptr = response_pool::into_raw(
+                        ResponseValue::from_value(value.clone()),
+                        self.response_pool.as_ref(),
+                    );
+                    unsafe { self.call_success_callback(*callback_index, ptr) };
+                }
+            }
+            Err(err) => {
+                let message =
+                    CString::new(format_error(&err, self.error_message_format)).unwrap_or_default();
+                for callback_index in callback_indices {
+                    unsafe {
+                        self.call_failure_callback(
+                            *callback_index,
+                            error_type(&err),
+                            message.clone(),
+                        )
+                    };
+                }
+            }
+        }
+    }
+
+    /// Report an event through the event callback, if one is given.
+    fn report_event(&self, event_type: ClientEventType, details: &str) {
+        report_event(self.event_callback, event_type, details);
+    }
+
+    /// Prefix the keys of a command submitted by the caller and create its route, see [`ConnectionConfig::key_prefix`].
+    /// Returns the command, its route and the conversion which strips the prefix from the response, if needed.
+    ///
+    /// # Safety
+    ///
+    /// * `route_info` could be `null`, but if it is not `null`, it must be a valid [`RouteInfo`] pointer. See the safety documentation of [`create_route`].
+    unsafe fn prefix_keys(
+        &self,
+        cmd: Cmd,
+        route_info: *const RouteInfo,
+    ) -> (Cmd, Option<RoutingInfo>, Option<ResponseConverter>) {
+        unsafe { prefix_keys(self.key_prefix.as_ref(), cmd, route_info) }
+    }
+
+    /// Encode the values of a command submitted by the caller, see [`ConnectionConfig::value_encoder`].
+    /// Returns the command and the conversion which decodes the values in its response, if needed.
+    fn encode_values(&self, cmd: Cmd) -> RedisResult<(Cmd, Option<ResponseConverter>)> {
+        match &self.value_transform {
+            Some(value_transform) => value_transform.encode(cmd),
+            None => Ok((cmd, None)),
+        }
+    }
+
+    /// Get the route of a command submitted without a route: to the primary within the read-your-writes window,
+    /// otherwise the default route of the client, if any applies.
+    fn implicit_route(&self, cmd: &Cmd) -> Option<RoutingInfo> {
+        self.recent_writes
+            .as_ref()
+            .and_then(|recent_writes| recent_writes.route_for(cmd))
+            .or_else(|| {
+                self.default_route
+                    .as_ref()
+                    .and_then(|default_route| default_route.route_for(cmd))
+            })
+    }
+
+    /// Fail with a `CommandNotAllowed` error if the command isn't allowed by the command filter of the client.
+    fn check_command_allowed(&self, cmd: &Cmd) -> RedisResult<()> {
+        match &self.command_filter {
+            Some(command_filter) => command_filter.check(cmd),
+            None => Ok(()),
+        }
+    }
+
+    /// Re-issue `AUTH` with the credentials of the client on `node`, or on the server in standalone mode.
+    /// Fails with a [`RequestErrorType::ReauthenticationFailed`] error if the client has no password or the server rejects it.
+    async fn reauthenticate(&self, client: &GlideClient, node: Option<&str>) -> RedisResult<()> {
+        let reauthentication_failed = |detail: String| {
+            redis::make_extension_error(REAUTHENTICATION_FAILED_CODE.to_string(), Some(detail))
+        };
+        let Some((username, password)) = &self.credentials else {
+            return Err(reauthentication_failed(
+                "The connection isn't authenticated and no password is configured".to_string(),
+            ));
+        };
+        let password = match &self.credential_provider {
+            Some(credential_provider) => self
+                .refresh_password(credential_provider)
+                .await
+                .map_err(|err| reauthentication_failed(error_message(&err)))?,
+            None => password.clone(),
+        };
+        let mut cmd = redis::cmd("AUTH");
+        if let Some(username) = username {
+            cmd.arg(username);
+        }
+        cmd.arg(password);
+        let route = node
+            .filter(|_| self.standalone_address.is_none())
+            .and_then(route_to_address);
+        client
+            .clone()
+            .send_command(&cmd, route)
+            .await
+            .map(|_| ())
+            .map_err(|err| reauthentication_failed(error_message(&err)))
+    }
+
+    /// Pull a fresh password from the credential provider and store it in every client, so that the connections established
+    /// from now on authenticate with it. glide-core authenticates the connections it establishes again by the stored password.
+    async fn refresh_password(
+        &self,
+        credential_provider: &CredentialProvider,
+    ) -> RedisResult<String> {
+        let password = credential_provider.fetch()?;
+        for client in self.client_pool.all() {
+            client
+                .clone()
+                .update_connection_password(Some(password.clone()), false)
+                .await?;
+        }
+        Ok(password)
+    }
+
+    /// Report a [`ClientEventType::AuthenticationFailed`] event on the first request failing authentication.
+    /// The event is reported again only after a request succeeded in between.
+    fn track_authentication<T>(&self, result: &RedisResult<T>) {
+        match result {
+            Ok(_) => self.authentication_failed.store(false, Ordering::Release),
+            Err(err)
+                if matches!(
+                    error_type(err),
+                    RequestErrorType::AuthenticationFailed
+                        | RequestErrorType::ReauthenticationFailed
+                ) =>
+            {
+                if !self.authentication_failed.swap(true, Ordering::AcqRel) {
+                    self.report_event(ClientEventType::AuthenticationFailed, &error_message(err));
+                }
+            }
+            Err(_) => {}
+        }
+    }
+
+    /// Fail with a `ClusterDown` error if the circuit breaker is open.
+    fn check_circuit_breaker(&self) -> RedisResult<()> {
+        match &self.circuit_breaker {
+            Some(circuit_breaker) => circuit_breaker.check(),
+            None => Ok(()),
+        }
+    }
+
+    /// Record the result of a command in the circuit breaker. Once it opens, a [`ClientEventType::CircuitOpen`] event is reported,
+    /// and the topology is refreshed after a backoff, retried until it succeeds, which closes the breaker.
+    fn track_cluster_down(self: &Arc<Self>, result: &RedisResult<Value>) {
+        let Some(circuit_breaker) = &self.circuit_breaker else {
+            return;
+        };
+        let (Some(backoff), Err(err)) = (circuit_breaker.record(result), result) else {
+            return;
+        };
+        self.report_event(ClientEventType::CircuitOpen, &error_message(err));
+        let opened = Instant::now();
+        let weak_core = Arc::downgrade(self);
+        tokio::spawn(async move {
+            let mut backoff = backoff;
+            loop {
+                tokio::time::sleep(backoff).await;
+                let Some(core) = weak_core.upgrade() else {
+                    break;
+                };
+                match core.refresh_topology().await {
+                    Ok(()) => {
+                        if let Some(circuit_breaker) = &core.circuit_breaker {
+                            circuit_breaker.close();
+                        }
+                        core.report_event(
+                            ClientEventType::CircuitClosed,
+                            &opened.elapsed().as_millis().to_string(),
+                        );
+                        break;
+                    }
+                    Err(err) => {
+                        logger_core::log_warn(
+                            "circuit breaker",
+                            format!("Failed to refresh topology: {err}"),
+                        );
+                        backoff = (backoff * 2).min(MAX_BACKOFF);
+                    }
+                }
+            }
+        });
+    }
+
+    /// Report a command through the slow command callback, if one is given and the latency exceeds the threshold.
+    /// The callback is called by a separate thread, see [`report_slow_commands`]. If the callback falls behind,
+    /// the command isn't reported, but counted by [`ClientStatistics::dropped_slow_commands`].
+    fn report_slow_command(&self, cmd: &Cmd, latency: Duration, node: Option<&str>) {
+        let (Some(slow_commands), Some(threshold)) =
+            (&self.slow_commands, self.slow_command_threshold)
+        else {
+            return;
+        };
+        if latency <= threshold {
+            return;
+        }
+        let slow_command = SlowCommand {
+            name: CString::new(cmd.command().unwrap_or_default()).unwrap_or_default(),
+            latency,
+            node: node.map(|node| CString::new(node).unwrap_or_default()),
+        };
+        if let Err(TrySendError::Full(_)) = slow_commands.try_send(slow_command) {
+            self.dropped_slow_commands.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    /// Refresh the topology snapshot used to attribute commands to nodes.
+    async fn refresh_topology(&self) -> RedisResult<()> {
+        let mut cmd = redis::cmd("CLUSTER");
+        cmd.arg("SLOTS");
+        let value = self
+            .client
+            .clone()
+            .send_command(
+                &cmd,
+                Some(RoutingInfo::SingleNode(SingleNodeRoutingInfo::Random)),
+            )
+            .await?;
+        self.set_topology(Topology::from_cluster_slots(value)?);
+        self.unhealthy_nodes.lock().unwrap().clear();
+        Ok(())
+    }
+
+    /// Update the topology snapshot to the slot map glide-core routes commands by, without sending a command to the servers.
+    async fn sync_topology(&self) -> RedisResult<()> {
+        let Some(value) = self.client.clone().get_slot_map().await? else {
+            return Ok(());
+        };
+        self.set_topology(Topology::from_cluster_slots(value)?);
+        Ok(())
+    }
+
+    fn set_topology(&self, topology: Topology) {
+        let previous = std::mem::replace(
+            &mut *self.topology.write().unwrap(),
+            Arc::new(topology.clone()),
+        );
+        if !previous.ranges.is_empty() && *previous != topology {
+            self.report_event(
+                ClientEventType::TopologyChanged,
+                &topology.primaries().join(","),
+            );
+        }
+    }
+
+    /// Get a route to the primary of the slot of a write which reached a replica, by the address the refreshed topology reports
+    /// for it, so the write doesn't follow the stale slots of the connections, see [`ConnectionConfig::retry_writes_on_readonly`].
+    /// `None` if the write isn't routed to a single slot, or the topology can't be refreshed.
+    async fn primary_route_after_refresh(
+        &self,
+        cmd: &Cmd,
+        route: Option<&RoutingInfo>,
+    ) -> Option<RoutingInfo> {
+        let slot = match route.cloned().or_else(|| RoutingInfo::for_routable(cmd))? {
+            RoutingInfo::SingleNode(SingleNodeRoutingInfo::SpecificNode(route)) => route.slot(),
+            _ => return None,
+        };
+        self.refresh_topology().await.ok()?;
+        let topology = self.topology.read().unwrap().clone();
+        route_to_address(topology.primary_for_slot(slot)?)
+    }
+
+    /// Get the addresses of all nodes with routes to them, refreshing the topology in cluster mode.
+    /// In standalone mode the route is `None`, since all commands are sent to the single node.
+    async fn all_nodes(&self) -> RedisResult<Vec<(String, Option<RoutingInfo>)>> {
+        match &self.standalone_address {
+            Some(address) => Ok(vec![(address.clone(), None)]),
+            None => {
+                self.refresh_topology().await?;
+                let topology = self.topology.read().unwrap().clone();
+                Ok(topology
+                    .nodes()
+                    .into_iter()
+                    .filter_map(|node| {
+                        let route = route_to_address(&node)?;
+                        Some((node, Some(route)))
+                    })
+                    .collect())
+            }
+        }
+    }
+
+    /// Send `QUIT` to every node, waiting up to `timeout` for the nodes to confirm.
+    /// Since commands are pipelined on every connection, the confirmation also means that all
+    /// previously sent commands were answered.
+    async fn quit_connections(&self, timeout: Duration) {
+        let nodes = match self.all_nodes().await {
+            Ok(nodes) => nodes,
+            Err(err) => {
+                logger_core::log_warn(
+                    "graceful close",
+                    format!("Failed to get the nodes to disconnect from: {err}"),
+                );
+                return;
+            }
+        };
+        let mut quits = JoinSet::new();
+        for client in self.client_pool.all() {
+            for (_, route) in nodes.clone() {
+                let mut client = client.clone();
+                quits.spawn(async move { client.send_command(&redis::cmd("QUIT"), route).await });
+            }
+        }
+        if tokio::time::timeout(timeout, async {
+            while quits.join_next().await.is_some() {}
+        })
+        .await
+        .is_err()
+        {
+            logger_core::log_warn(
+                "graceful close",
+                "Timed out waiting for the nodes to confirm QUIT",
+            );
+        }
+    }
+
+    /// Close the connections of every client to the node at `address` by `QUIT`, which the node answers after the commands
+    /// sent before it on the connection. The connections are established again once commands are sent to the node.
+    async fn disconnect_node(&self, address: &str) -> RedisResult<Value> {
+        let route = match &self.standalone_address {
+            Some(standalone_address) if standalone_address == address => None,
+            Some(_) => {
+                return Err(RedisError::from((
+                    redis::ErrorKind::ClientError,
+                    "The address isn't the server of the client",
+                    address.to_string(),
+                )))
+            }
+            None => Some(route_to_address(address).ok_or_else(|| {
+                RedisError::from((
+                    redis::ErrorKind::ClientError,
+                    "Invalid node address",
+                    address.to_string(),
+                ))
+            })?),
+        };
+        for client in self.client_pool.all() {
+            client
+                .clone()
+                .send_command(&redis::cmd("QUIT"), route.clone())
+                .await?;
+        }
+        Ok(Value::Okay)
+    }
+
+    /// Send `PING` on the idle connections, see [`ConnectionConfig::client_keepalive_interval_secs`].
+    async fn keep_connections_alive(&self) -> RedisResult<()> {
+        let ping = redis::cmd("PING");
+        if self.standalone_address.is_some() {
+            for mut client in self.client_pool.take_idle() {
+                client.send_command(&ping, None).await?;
+            }
+            return Ok(());
+        }
+        let route = RoutingInfo::MultiNode((
+            MultipleNodeRoutingInfo::AllNodes,
+            ResponsePolicy::for_command(b"PING"),
+        ));
+        for client in self.client_pool.all() {
+            client
+                .clone()
+                .send_command(&ping, Some(route.clone()))
+                .await?;
+        }
+        Ok(())
+    }
+
+    /// Enable client tracking on the connections of every client to every node in the topology, if it is configured.
+    async fn enable_client_tracking(&self) -> RedisResult<()> {
+        let Some(tracking) = &self.client_tracking else {
+            return Ok(());
+        };
+        let cmd = tracking.command();
+        let nodes = self.all_nodes().await?;
+        for client in self.client_pool.all() {
+            for (_, route) in nodes.clone() {
+                client.clone().send_command(&cmd, route).await?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Handle a push of the server: pass invalidations to the invalidation callback, and enable tracking again
+    /// once a connection is lost, since the re-established connection isn't tracked.
+    fn handle_push(self: Arc<Self>, push: PushInfo) {
+        match push.kind {
+            PushKind::Disconnection => {
+                tokio::spawn(async move {
+                    if let Err(err) = self.enable_client_tracking().await {
+                        logger_core::log_warn(
+                            "client tracking",
+                            format!("Failed to enable tracking after a disconnection: {err}"),
+                        );
+                    }
+                });
+            }
+            _ => {
+                if let Some(callback) = self.invalidation_callback {
+                    unsafe { report_invalidation(callback, &push) };
+                }
+            }
+        }
+    }
+
+    /// Send `PING` to every node in the topology by every client, so connections to all nodes are established.
+    /// Returns a map of node addresses to whether the node responded within the timeout on all connections.
+    async fn prewarm_connections(&self, timeout: Option<Duration>) -> RedisResult<Value> {
+        let nodes = self.all_nodes().await?;
+        let mut pings = JoinSet::new();
+        for (client, (node, route)) in self
+            .client_pool
+            .all()
+            .iter()
+            .flat_map(|client| nodes.iter().cloned().map(move |node| (client, node)))
+        {
+            let mut client = client.clone();
+            pings.spawn(async move {
+                let cmd = redis::cmd("PING");
+                let ping = client.send_command(&cmd, route);
+                let connected = match timeout {
+                    Some(timeout) => tokio::time::timeout(timeout, ping)
+                        .await
+                        .is_ok_and(|result| result.is_ok()),
+                    None => ping.await.is_ok(),
+                };
+                (node, connected)
+            });
+        }
+
+        let mut results: Vec<(String, bool)> = Vec::new();
+        while let Some(result) = pings.join_next().await {
+            if let Ok((node, connected)) = result {
+                match results.iter_mut().find(|(address, _)| *address == node) {
+                    Some((_, all_connected)) => *all_connected &= connected,
+                    None => results.push((node, connected)),
+                }
+            }
+        }
+        Ok(Value::Map(
+            results
+                .into_iter()
+                .map(|(node, connected)| {
+                    (
+                        Value::BulkString(node.into_bytes()),
+                        Value::Boolean(connected),
+                    )
+                })
+                .collect(),
+        ))
+    }
+
+    /// Send a command to every primary, or to the server in standalone mode, refreshing the topology first.
+    /// Returns a map of node addresses to `OK` if the node applied the command, or to the error message otherwise,
+    /// so a node which failed is reported without hiding the results of the others.
+    async fn send_to_all_primaries(&self, cmd: Cmd) -> RedisResult<Value> {
+        let nodes = match &self.standalone_address {
+            Some(address) => vec![(address.clone(), None)],
+            None => {
+                self.refresh_topology().await?;
+                let topology = self.topology.read().unwrap().clone();
+                topology
+                    .primaries()
+                    .into_iter()
+                    .filter_map(|node| Some((node.to_string(), Some(route_to_address(node)?))))
+                    .collect()
+            }
+        };
+        let cmd = Arc::new(cmd);
+        let mut requests = JoinSet::new();
+        for (node, route) in nodes {
+            let mut client = self.client.clone();
+            let cmd = cmd.clone();
+            requests.spawn(async move { (node, client.send_command(&cmd, route).await) });
+        }
+
+        let mut results = Vec::new();
+        while let Some(result) = requests.join_next().await {
+            if let Ok((node, result)) = result {
+                let result = match result {
+                    Ok(_) => Value::Okay,
+                    Err(err) => Value::BulkString(error_message(&err).into_bytes()),
+                };
+                results.push((Value::BulkString(node.into_bytes()), result));
+            }
+        }
+        Ok(Value::Map(results))
+    }
+
+    /// Get the version of the servers `INFO SERVER` is routed to.
+    /// Returns the version string if the command is routed to a single node, or a map of node addresses to versions otherwise.
+    /// Versions of single known nodes are served from the cache.
+    async fn server_version(self: &Arc<Self>, route: Option<RoutingInfo>) -> RedisResult<Value> {
+        let mut cmd = redis::cmd("INFO");
+        cmd.arg("SERVER");
+        let node = self.node_for_command(&cmd, route.as_ref());
+        let cached = node
+            .as_ref()
+            .and_then(|node| self.server_versions.lock().unwrap().get(node).cloned());
+        if let Some(version) = cached {
+            return Ok(Value::BulkString(version.into_bytes()));
+        }
+
+        let result = self.client.clone().send_command(&cmd, route).await;
+        self.update_node_health(node.as_deref(), &result);
+        match result? {
+            Value::Map(nodes) => {
+                let mut server_versions = self.server_versions.lock().unwrap();
+                nodes
+                    .into_iter()
+                    .map(|(address, info)| {
+                        let address: String = redis::from_owned_redis_value(address)?;
+                        let version = parse_server_version(info)?;
+                        server_versions.insert(address.clone(), version.clone());
+                        Ok((
+                            Value::BulkString(address.into_bytes()),
+                            Value::BulkString(version.into_bytes()),
+                        ))
+                    })
+                    .collect::<RedisResult<_>>()
+                    .map(Value::Map)
+            }
+            info => {
+                let version = parse_server_version(info)?;
+                if let Some(node) = node {
+                    self.server_versions
+                        .lock()
+                        .unwrap()
+                        .insert(node, version.clone());
+                }
+                Ok(Value::BulkString(version.into_bytes()))
+            }
+        }
+    }
+
+    /// Get the address of the node the command is sent to.
+    /// Returns `None` if the command isn't sent to a single known node.
+    fn node_for_command(&self, cmd: &Cmd, route: Option<&RoutingInfo>) -> Option<String> {
+        match &self.standalone_address {
+            Some(address) => Some(address.clone()),
+            None => {
+                let topology = self.topology.read().unwrap().clone();
+                match route {
+                    Some(route) => topology.node_for_route(route),
+                    None => RoutingInfo::for_routable(cmd)
+                        .and_then(|route| topology.node_for_route(&route)),
+                }
+            }
+        }
+    }
+
+    /// Fail if fail-fast is enabled and the node is considered unhealthy.
+    fn check_node_available(&self, node: Option<&str>) -> RedisResult<()> {
+        let Some(node) = node else {
+            return Ok(());
+        };
+        if self.fail_fast_on_degraded && self.unhealthy_nodes.lock().unwrap().contains(node) {
+            return Err(redis::make_extension_error(
+                NODE_UNAVAILABLE_CODE.to_string(),
+                Some(format!("Node is unavailable: {node}")),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Track the health of the node according to the result of a command sent to it.
+    /// A topology refresh is triggered once a node becomes unhealthy, in order to detect a failover.
+    fn update_node_health(self: &Arc<Self>, node: Option<&str>, result: &RedisResult<Value>) {
+        let Some(node) = node else {
+            return;
+        };
+        let is_degraded = result.as_ref().is_err_and(is_unavailable);
+        if is_degraded {
+            self.server_versions.lock().unwrap().remove(node);
+        }
+        if !self.fail_fast_on_degraded {
+            return;
+        }
+        let mut unhealthy_nodes = self.unhealthy_nodes.lock().unwrap();
+        if !is_degraded {
+            unhealthy_nodes.remove(node);
+        } else if unhealthy_nodes.insert(node.to_string()) && self.standalone_address.is_none() {
+            let core = self.clone();
+            tokio::spawn(async move {
+                if let Err(err) = core.refresh_topology().await {
+                    logger_core::log_warn(
+                        "topology refresh",
+                        format!("Failed to refresh topology: {err}"),
+                    );
+                }
+            });
+        }
+    }
+
+    /// Reserve an in-flight request on the given node.
+    /// Returns `None` if the command isn't sent to a single known node.
+    fn reserve_node_inflight(&self, node: Option<String>) -> RedisResult<Option<InflightGuard>> {
+        let Some(node) = node else {
+            return Ok(None);
+        };
+        match self.node_inflight.reserve(node) {
+            Reservation::LimitReached => Err(redis::make_extension_error(
+                BACKPRESSURE_CODE.to_string(),
+                Some("Reached maximum inflight requests for the node".to_string()),
+            )),
+            Reservation::Reserved(guard) => Ok(Some(guard)),
+        }
+    }
+
+    /// Replace an empty reply of a command by null, if [`ConnectionConfig::normalize_empty_replies`] is set.
+    fn normalize_reply(&self, result: RedisResult<Value>) -> RedisResult<Value> {
+        match result {
+            Ok(value) if self.normalize_empty_replies => Ok(ResponseValue::normalize_empty(value)),
+            result => result,
+        }
+    }
+
+    /// Attach the metadata of a reply sent by `route`, if [`ConnectionConfig::response_metadata`] is set.
+    /// `attributes` are the RESP3 attributes the server sent with the reply.
+    fn attach_metadata(
+        &self,
+        cmd: &Cmd,
+        route: Option<&RoutingInfo>,
+        node: Option<String>,
+        result: RedisResult<Value>,
+        attributes: Option<Vec<(Value, Value)>>,
+    ) -> RedisResult<Value> {
+        if !self.response_metadata {
+            return result;
+        }
+        let topology = self.topology.read().unwrap().clone();
+        result.map(|value| {
+            ResponseMetadata::new(cmd, route, node, &topology, self.reads_from_replicas)
+                .attach(value, attributes)
+        })
+    }
+
+    /// Replace the empty replies of the commands of a batch by null, like [`normalize_reply`](Self::normalize_reply).
+    /// The reply of the batch itself is kept, e.g. an atomic batch which was aborted is still null.
+    fn normalize_batch_reply(&self, result: RedisResult<Value>) -> RedisResult<Value> {
+        match result {
+            Ok(Value::Array(replies)) if self.normalize_empty_replies => Ok(Value::Array(
+                replies
+                    .into_iter()
+                    .map(ResponseValue::normalize_empty)
+                    .collect(),
+            )),
+            result => result,
+        }
+    }
+
+    /// Reserve a pending task for a command or a batch which is about to be spawned on the runtime.
+    /// The guard should be dropped once the callback of the request was called.
+    fn reserve_pending_task(&self) -> RedisResult<PendingTaskGuard> {
+        self.pending_tasks.reserve().ok_or_else(|| {
+            redis::make_extension_error(
+                BACKPRESSURE_CODE.to_string(),
+                Some("Reached maximum pending commands of the client".to_string()),
+            )
+        })
+    }
+}
+
+/// Notifies every observer of the connections of a client, see [`ConnectionRequest::connection_observer`](glide_core::client::ConnectionRequest::connection_observer).
+struct ConnectionObservers(Vec<Arc<dyn ConnectionObserver>>);
+
+impl ConnectionObserver for ConnectionObservers {
+    fn connected(&self, address: &str, latency: Duration) {
+        for observer in &self.0 {
+            observer.connected(address, latency);
+        }
+    }
+
+    fn connection_failed(&self, address: &str) {
+        for observer in &self.0 {
+            observer.connection_failed(address);
+        }
+    }
+
+    fn disconnected(&self, address: &str) {
+        for observer in &self.0 {
+            observer.disconnected(address);
+        }
+    }
+
+    fn server_hello(&self, address: &str, hello: &Value) {
+        for observer in &self.0 {
+            observer.server_hello(address, hello);
+        }
+    }
+}
+
+/// # Safety
+///
+/// * `config` must be a valid [`ConnectionConfig`] pointer. See the safety documentation of [`create_connection_request`].
+unsafe fn create_client_internal(
+    config: *const ConnectionConfig,
+    success_callback: SuccessCallback,
+    failure_callback: FailureCallback,
+    event_callback: Option<EventCallback>,
+    slow_command_callback: Option<SlowCommandCallback>,
+    invalidation_callback: Option<InvalidationCallback>,
+    route_decider: Option<RouteDeciderCallback>,
+) -> RedisResult<Client> {
+    let mut request = unsafe { create_connection_request(config) }?;
+    let credential_provider = unsafe { (*config).credential_provider }.map(CredentialProvider::new);
+    if let Some(credential_provider) = &credential_provider {
+        // The password of the first connections, which replaces the configured password, if any
+        let password = credential_provider.fetch()?;
+        match &mut request.authentication_info {
+            Some(info) => info.password = Some(password),
+            None => {
+                request.authentication_info = Some(AuthenticationInfo {
+                    username: None,
+                    password: Some(password),
+                })
+            }
+        }
+    }
+    let redirections = Arc::new(RedirectionTracker::new(event_callback, unsafe {
+        (*config).redirection_event_threshold
+    }));
+    if request.cluster_mode_enabled {
+        request.redirection_observer = Some(redirections.clone());
+    }
+    let connection_timings = Arc::new(ConnectionTimings::default());
+    let server_capabilities = Arc::new(ServerCapabilities::default());
+    let mut observers: Vec<Arc<dyn ConnectionObserver>> =
+        vec![connection_timings.clone(), server_capabilities.clone()];
+    let credential_refresh = Arc::new(Notify::new());
+    if credential_provider.is_some() {
+        observers.push(Arc::new(CredentialRefresh(credential_refresh.clone())));
+    }
+    request.connection_observer = Some(Arc::new(ConnectionObservers(observers)));
+    let leases = LeasePool::new(&request);
+    let effective_config = unsafe {
+        EffectiveConfig::new(&request)
+            .with_client_settings(config, request.cluster_mode_enabled)
+            .into_value()
+    };
+    let subscriptions = subscriptions_value(request.pubsub_subscriptions.as_ref());
+    let cluster_mode_enabled = request.cluster_mode_enabled;
+    let reads_from_replicas = !matches!(request.read_from, None | Some(ReadFrom::Primary));
+    let credentials = request
+        .authentication_info
+        .as_ref()
+        .and_then(|info| Some((info.username.clone(), info.password.clone()?)));
+    let standalone_address = (!request.cluster_mode_enabled)
+        .then(|| request.addresses.first())
+        .flatten()
+        .map(|address| format!("{}:{}", address.host, address.port));
+    let node_inflight = Arc::new(unsafe {
+        NodeInflightTracker::new(
+            (*config).max_inflight_requests_per_node,
+            (*config).hot_node_threshold,
+            Duration::from_millis((*config).hot_node_duration.into()),
+        )
+    });
+    let runtime = Builder::new_multi_thread()
+        .enable_all()
+        .thread_name("GLIDE C# thread")
+        .build()?;
+    let _runtime_handle = runtime.enter();
+    let client_tracking = unsafe { create_client_tracking(config) };
+    if client_tracking.is_some() && request.protocol != Some(ProtocolVersion::RESP3) {
+        return Err(RedisError::from((
+            redis::ErrorKind::InvalidClientConfig,
+            "Client tracking requires the RESP3 protocol",
+        )));
+    }
+    let (push_sender, push_receiver) = match client_tracking {
+        Some(_) => {
+            let (sender, receiver) = tokio::sync::mpsc::unbounded_channel();
+            (Some(sender), Some(receiver))
+        }
+        None => (None, None),
+    };
+    let client = match runtime.block_on(GlideClient::new(request.clone(), push_sender.clone())) {
+        Ok(client) => client,
+        Err(err) => {
+            if is_authentication_failure(&err) {
+                report_event(
+                    event_callback,
+                    ClientEventType::AuthenticationFailed,
+                    &err.to_string(),
+                );
+            }
+            return Err(RedisError::from((
+                redis::ErrorKind::ClientError,
+                "Failed to connect",
+                err.to_string(),
+            )));
+        }
+    };
+    let client_pool = runtime.block_on(ClientPool::connect(
+        client.clone(),
+        &request,
+        push_sender,
+        unsafe { (*config).connections_per_node },
+    ))?;
+    let mut key_prefix = unsafe { ffi::key_prefix(config) };
+    if let Some(tag) = unsafe { ffi::default_hash_tag(config) } {
+        // The default hash tag is prefixed like the key prefix, after it
+        let prefix = key_prefix.get_or_insert_with(Vec::new);
+        prefix.push(b'{');
+        prefix.extend(tag);
+        prefix.push(b'}');
+    }
+    let key_prefix = match key_prefix {
+        Some(prefix) => {
+            // The key positions of all commands, which are the same on all nodes.
+            let commands = runtime.block_on(client.clone().send_command(
+                &redis::cmd("COMMAND"),
+                Some(RoutingInfo::SingleNode(SingleNodeRoutingInfo::Random)),
+            ))?;
+            Some(KeyPrefix::new(
+                prefix,
+                unsafe { (*config).strip_key_prefix },
+                commands,
+            )?)
+        }
+        None => None,
+    };
+    let connection_reset = reset_pipeline(&request, client_tracking.as_ref());
+    let (slow_command_threshold, slow_commands) = match (slow_command_callback, unsafe {
+        (*config).slow_command_threshold
+    }) {
+        (Some(slow_command_callback), threshold @ 1..) => {
+            let (sender, receiver) = tokio::sync::mpsc::channel(SLOW_COMMANDS_CAPACITY);
+            // A thread rather than a task, so a slow callback doesn't block a worker of the runtime. Not the blocking pool
+            // either, since dropping the runtime waits for its blocking tasks, and this one only ends once the client is dropped.
+            std::thread::Builder::new()
+                .name("glide-slow-commands".to_string())
+                .spawn(move || report_slow_commands(slow_command_callback, receiver))?;
+            (Some(Duration::from_millis(threshold.into())), Some(sender))
+        }
+        _ => (None, None),
+    };
+    let core = Arc::new(CommandExecutionCore {
+        success_callback,
+        failure_callback,
+        callback_watchdog: CallbackWatchdog::new(
+            unsafe {
+                ((*config).slow_callback_threshold_ms > 0)
+                    .then(|| Duration::from_millis((*config).slow_callback_threshold_ms.into()))
+            },
+            unsafe { (*config).offload_callbacks }.then(|| runtime.handle().clone()),
+        ),
+        response_pool: ResponsePool::new(unsafe { (*config).response_pool_capacity } as usize),
+        event_callback,
+        slow_command_threshold,
+        slow_commands,
+        dropped_slow_commands: AtomicU64::new(0),
+        standalone_address,
+        topology: Default::default(),
+        node_inflight,
+        pending_tasks: Arc::new(PendingTasks::new(unsafe { (*config).max_pending_commands })),
+        fail_fast_on_degraded: unsafe { (*config).fail_fast_on_degraded },
+        unhealthy_nodes: Default::default(),
+        circuit_breaker: CircuitBreaker::new(unsafe { (*config).circuit_breaker_threshold })
+            .filter(|_| cluster_mode_enabled),
+        compression_threshold: unsafe { (*config).compression_threshold },
+        authentication_failed: AtomicBool::new(false),
+        server_versions: Default::default(),
+        leases,
+        blocking_connections: Default::default(),
+        command_latency: LatencyHistogram::default(),
+        redirections,
+        connection_timings,
+        server_capabilities,
+        reauthenticate_on_noauth: unsafe { (*config).reauthenticate_on_noauth },
+        credential_provider,
+        credentials,
+        log_non_utf8_arguments: unsafe { (*config).log_non_utf8_arguments },
+        default_route: unsafe {
+            (*config)
+                .has_default_route
+                .then(|| create_default_route(&(*config).default_route))
+        },
+        command_filter: unsafe { create_command_filter(config) },
+        single_flight: unsafe { (*config).deduplicate_reads }.then(SingleFlight::default),
+        key_ordering: unsafe { (*config).order_commands_per_key }.then(Default::default),
+        max_response_bytes: unsafe { (*config).max_response_bytes },
+        max_command_arg_bytes: unsafe { (*config).max_command_arg_bytes },
+        command_counts: Default::default(),
+        command_trace: unsafe {
+            CommandTrace::new(
+                (*config).command_trace_capacity,
+                (*config).command_trace_full_args,
+            )
+        },
+        runtime_saturation: SaturationDetector::new(event_callback, unsafe {
+            (*config).runtime_saturation_threshold
+        }),
+        client_tracking,
+        invalidation_callback,
+        route_decider: route_decider.filter(|_| cluster_mode_enabled),
+        fallback_to_primary_on_replica_error: cluster_mode_enabled
+            && unsafe { (*config).fallback_to_primary_on_replica_error },
+        retry_writes_on_readonly: cluster_mode_enabled
+            && unsafe { (*config).retry_writes_on_readonly },
+        reads_from_replicas,
+        key_prefix,
+        value_transform: ValueTransform::new(unsafe { (*config).value_encoder }, unsafe {
+            (*config).value_decoder
+        }),
+        effective_config,
+        subscriptions,
+        connection_reset,
+        normalize_empty_replies: unsafe { (*config).normalize_empty_replies },
+        response_metadata: unsafe { (*config).response_metadata },
+        error_message_format: unsafe { (*config).error_message_format },
+        recent_writes: unsafe {
+            (cluster_mode_enabled && (*config).read_your_writes_window_ms > 0).then(|| {
+                RecentWrites::new(Duration::from_millis(
+                    (*config).read_your_writes_window_ms.into(),
+                ))
+            })
+        },
+        client,
+        client_pool,
+    });
+    if let Some(mut push_receiver) = push_receiver {
+        runtime.block_on(core.enable_client_tracking())?;
+        let weak_core = Arc::downgrade(&core);
+        runtime.spawn(async move {
+            while let Some(push) = push_receiver.recv().await {
+                let Some(core) = weak_core.upgrade() else {
+                    break;
+                };
+                core.handle_push(push);
+            }
+        });
+    }
+    if let Some(credential_provider) = core.credential_provider {
+        let weak_core = Arc::downgrade(&core);
+        runtime.spawn(async move {
+            loop {
+                credential_refresh.notified().await;
+                let Some(core) = weak_core.upgrade() else {
+                    break;
+                };
+                if let Err(err) = core.refresh_password(&credential_provider).await {
+                    logger_core::log_warn(
+                        "credential provider",
+                        format!("Failed to refresh the password: {err}"),
+                    );
+                }
+            }
+        });
+    }
+    let topology_refresh_secs = unsafe { (*config).periodic_topology_refresh_secs };
+    let topology_refresh =
+        (core.standalone_address.is_none() && topology_refresh_secs > 0).then(|| {
+            let topology_refresh_interval = Duration::from_secs(topology_refresh_secs.into());
+            let weak_core = Arc::downgrade(&core);
+            runtime.spawn(async move {
+                let mut interval = tokio::time::interval(topology_refresh_interval);
+                loop {
+                    interval.tick().await;
+                    let Some(core) = weak_core.upgrade() else {
+                        break;
+                    };
+                    if let Err(err) = core.sync_topology().await {
+                        logger_core::log_warn(
+                            "topology refresh",
+                            format!("Failed to refresh topology: {err}"),
+                        );
+                    }
+                }
+            })
+        });
+    let topology_sync = core.standalone_address.is_none().then(|| {
+        let weak_core = Arc::downgrade(&core);
+        let redirections = core.redirections.clone();
+        runtime.spawn(async move {
+            loop {
+                let Some(core) = weak_core.upgrade() else {
+                    break;
+                };
+                if let Err(err) = core.sync_topology().await {
+                    logger_core::log_warn(
+                        "topology refresh",
+                        format!("Failed to refresh topology: {err}"),
+                    );
+                }
+                drop(core);
+                redirections.slots_moved().await;
+            }
+        })
+    });
+    let hot_node_check = core.node_inflight.detects_hot_nodes().then(|| {
+        let weak_core = Arc::downgrade(&core);
+        runtime.spawn(async move {
+            let mut interval = tokio::time::interval(HOT_NODE_CHECK_INTERVAL);
+            loop {
+                interval.tick().await;
+                let Some(core) = weak_core.upgrade() else {
+                    break;
+                };
+                for node in core.node_inflight.hot_nodes() {
+                    core.report_event(ClientEventType::HotNode, &node);
+                }
+            }
+        })
+    });
+    let keepalive = match unsafe { (*config).client_keepalive_interval_secs } {
+        0 => None,
+        secs => {
+            let keepalive_interval = Duration::from_secs(secs.into());
+            // A connection used right before a check is pinged by the next one, so in standalone mode, where only the idle
+            // connections are pinged, the connections are checked twice per interval to ping them at least once per interval
+            let check_interval = match core.standalone_address {
+                Some(_) => keepalive_interval / 2,
+                None => keepalive_interval,
+            };
+            let weak_core = Arc::downgrade(&core);
+            Some(runtime.spawn(async move {
+                let mut interval = tokio::time::interval_at(
+                    tokio::time::Instant::now() + check_interval,
+                    check_interval,
+                );
+                interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+                loop {
+                    interval.tick().await;
+                    let Some(core) = weak_core.upgrade() else {
+                        break;
+                    };
+                    if let Err(err) = core.keep_connections_alive().await {
+                        logger_core::log_warn(
+                            "keepalive",
+                            format!("Failed to ping idle connections: {err}"),
+                        );
+                    }
+                }
+            }))
+        }
+    };
+    Ok(Client {
+        runtime,
+        core,
+        closed: AtomicBool::new(false),
+        topology_refresh: Mutex::new(topology_refresh),
+        topology_sync: Mutex::new(topology_sync),
+        hot_node_check: Mutex::new(hot_node_check),
+        keepalive: Mutex::new(keepalive),
+    })
+}
+
+/// Slow commands waiting for the slow command callback, further slow commands are dropped, see [`CommandExecutionCore::report_slow_command`].
+const SLOW_COMMANDS_CAPACITY: usize = 1024;
+
+/// A command whose latency exceeded [`ConnectionConfig::slow_command_threshold`].
+struct SlowCommand {
+    name: CString,
+    latency: Duration,
+    node: Option<CString>,
+}
+
+/// Call the slow command callback for every slow command, until the client is dropped.
+fn report_slow_commands(
+    slow_command_callback: SlowCommandCallback,
+    mut slow_commands: Receiver<SlowCommand>,
+) {
+    while let Some(slow_command) = slow_commands.blocking_recv() {
+        unsafe {
+            slow_command_callback(
+                slow_command.name.as_ptr(),
+                slow_command.latency.as_micros() as u64,
+                slow_command
+                    .node
+                    .as_ref()
+                    .map_or(std::ptr::null(), |node| node.as_ptr()),
+            )
+        };
+    }
+}
+
+/// Report an event through the event callback, if one is given.
+fn report_event(event_callback: Option<EventCallback>, event_type: ClientEventType, details: &str) {
+    if let Some(event_callback) = event_callback {
+        let details = CString::new(details).unwrap_or_default();
+        unsafe { event_callback(event_type, details.as_ptr()) };
+    }
+}
+
+/// Check whether the client failed to connect because the server rejected its credentials.
+fn is_authentication_failure(err: &ConnectionError) -> bool {
+    let is_authentication_error =
+        |err: &RedisError| error_type(err) == RequestErrorType::AuthenticationFailed;
+    match err {
+        ConnectionError::Cluster(err) => is_authentication_error(err),
+        ConnectionError::Standalone(StandaloneClientConnectionError::FailedConnection(errs)) => {
+            errs.iter().any(|(_, err)| is_authentication_error(err))
+        }
+        _ => false,
+    }
+}
+
+/// Callback index of the failure callback called when [`create_client`] fails, which is never the index of a request.
+pub const CREATE_CLIENT_CALLBACK_INDEX: usize = usize::MAX;
+
+/// Creates a new client with the given configuration.
+/// The success callback needs to copy the given string synchronously, since it will be dropped by Rust once the callback returns.
+/// All callbacks should be offloaded to separate threads in order not to exhaust the client's thread pool.
+/// The event callback, the slow command callback, the invalidation callback and the route decider are optional.
+/// The invalidation callback is called only if client tracking is enabled, see [`ConnectionConfig::client_tracking_mode`].
+/// The route decider is called only in cluster mode, see [`RouteDeciderCallback`].
+///
+/// If the client can't be created, `null` is returned, and the failure callback is called once with the callback index
+/// [`CREATE_CLIENT_CALLBACK_INDEX`] before returning. An invalid configuration, e.g. an AZ affinity strategy without the AZ
+/// of the client, fails with [`RequestErrorType::InvalidConfig`] before connecting, and the error message names the invalid field.
+///
+/// # Safety
+///
+/// * `config` must be a valid [`ConnectionConfig`] pointer. See the safety documentation of [`create_client_internal`].
+#[allow(rustdoc::private_intra_doc_links)]
+#[no_mangle]
+pub unsafe extern "C" fn create_client(
+    config: *const ConnectionConfig,
+    success_callback: SuccessCallback,
+    failure_callback: FailureCallback,
+    event_callback: Option<EventCallback>,
+    slow_command_callback: Option<SlowCommandCallback>,
+    invalidation_callback: Option<InvalidationCallback>,
+    route_decider: Option<RouteDeciderCallback>,
+) -> *const c_void {
+    match unsafe {
+        create_client_internal(
+            config,
+            success_callback,
+            failure_callback,
+            event_callback,
+            slow_command_callback,
+            invalidation_callback,
+            route_decider,
+        )
+    } {
+        Err(err) => {
+            logger_core::log_error("create client", format!("Failed to create a client: {err}"));
+            let message = CString::new(format_error(&err, unsafe {
+                (*config).error_message_format
+            }))
+            .unwrap_or_default();
+            unsafe {
+                failure_callback(
+                    CREATE_CLIENT_CALLBACK_INDEX,
+                    error_type(&err),
+                    message.as_ptr(),
+                )
+            };
+            std::ptr::null()
+        }
+        Ok(client) => client_registry::register(client),
+    }
+}
+
+/// Closes the given client, deallocating it from the heap.
+/// Connections are dropped without waiting for the requests in flight to be answered, see [`close_client_graceful`] for a clean disconnect.
+///
+/// The client is marked as closed first, so while requests submitted earlier are still in flight,
+/// new requests fail with [`RequestErrorType::ClientClosed`] and repeated calls to this function are ignored.
+/// The periodic topology refresh and the keepalive are stopped right away.
+/// Once all requests are done the client is deallocated. Requests submitted with the pointer still fail with
+/// [`RequestErrorType::ClientClosed`], since the pointer is checked against the registry of the clients before it is dereferenced.
+/// The logs are flushed before returning, see [`flush_logs`].
+///
+/// # Safety
+///
+/// * `client_ptr` must not be `null`.
+/// * `client_ptr` must be a pointer created by [`create_client`].
+#[no_mangle]
+pub extern "C" fn close_client(client_ptr: *const c_void) {
+    assert!(!client_ptr.is_null());
+    let Some(client) = client_registry::close(client_ptr) else {
+        return;
+    };
+    client.stop_periodic_tasks();
+    // This will bring the strong count down to 0 once all client requests are done.
+    drop(client);
+    logger_core::flush();
+}
+
+/// Closes the given client gracefully, like [`close_client`], after sending `QUIT` to every node.
+/// Waits up to `timeout_ms` milliseconds for the nodes to confirm, which also means that all requests sent before
+/// were answered, so the server logs a clean disconnect instead of a connection reset.
+/// New requests fail with [`RequestErrorType::ClientClosed`] while waiting. The logs are flushed before returning, see [`flush_logs`].
+///
+/// This function blocks, so it must not be called from a callback of the client.
+///
+/// # Safety
+///
+/// * `client_ptr` must not be `null`.
+/// * `client_ptr` must be a pointer created by [`create_client`].
+#[no_mangle]
+pub unsafe extern "C" fn close_client_graceful(client_ptr: *const c_void, timeout_ms: u32) {
+    assert!(!client_ptr.is_null());
+    let Some(client) = client_registry::close(client_ptr) else {
+        return;
+    };
+    client.stop_periodic_tasks();
+    client.runtime.block_on(
+        client
+            .core
+            .quit_connections(Duration::from_millis(timeout_ms.into())),
+    );
+    // This will bring the strong count down to 0 once all client requests are done.
+    drop(client);
+    logger_core::flush();
+}
+
+/// Wait until the client has no pending commands, i.e. until the callbacks of all commands and batches submitted before
+/// were called, so the client can be closed by [`close_client`] without failing any of them.
+/// The response is `true` once the client is idle, or `false` if `timeout_ms` milliseconds elapsed first, `0` means no timeout.
+///
+/// Commands submitted while waiting are waited for too, so if commands keep arriving, e.g. because the application
+/// isn't drained yet, the client might never be idle and the response is `false`. The client isn't closed,
+/// and new commands aren't rejected, see [`close_client_graceful`] to also reject them.
+/// The same pending commands are counted as for [`ConnectionConfig::max_pending_commands`].
+///
+/// # Safety
+/// * `client_ptr` must not be `null`.
+/// * `client_ptr` must be a valid pointer created by [`create_client`], before [`close_client`] was called with the pointer.
+#[no_mangle]
+pub unsafe extern "C" fn await_idle(
+    client_ptr: *const c_void,
+    callback_index: usize,
+    timeout_ms: u32,
+) {
+    let Some(client) = (unsafe { open_client_from_ptr(client_ptr, callback_index) }) else {
+        return;
+    };
+    let core = client.core.clone();
+    let timeout = (timeout_ms > 0).then(|| Duration::from_millis(timeout_ms.into()));
+    // The wait isn't a pending task itself, otherwise the client would never be idle
+    client.runtime.spawn(async move {
+        let idle = core.pending_tasks.wait_idle(timeout).await;
+        unsafe { core.report_result(callback_index, Ok(Value::Boolean(idle))) };
+    });
+}
+
+/// Restore an owned reference to the client from a pointer created by [`create_client`], `None` if the client was closed.
+/// A pointer which isn't a client is logged, and `None` is returned too.
+///
+/// # Safety
+/// * `client_ptr` must not be `null`.
+/// * `client_ptr` should be a pointer created by [`create_client`].
+pub(crate) unsafe fn client_from_ptr(client_ptr: *const c_void) -> Option<Arc<Client>> {
+    client_registry::get(client_ptr).ok()
+}
+
+/// Restore an owned reference to the client, like [`client_from_ptr`], unless the client was closed.
+/// If it was, the request fails with [`RequestErrorType::ClientClosed`] through the failure callback and `None` is returned.
+/// The client might have been deallocated already, so the failure callback is called directly, rather than through the
+/// [`CallbackWatchdog`] of the client. A pointer which isn't a client is logged, and the request is dropped without a callback,
+/// since there is no failure callback to call.
+///
+/// # Safety
+/// * `client_ptr` must not be `null`.
+/// * `client_ptr` should be a pointer created by [`create_client`].
+pub(crate) unsafe fn open_client_from_ptr(
+    client_ptr: *const c_void,
+    callback_index: usize,
+) -> Option<Arc<Client>> {
+    match client_registry::get(client_ptr) {
+        Ok(client) => Some(client),
+        Err(None) => None,
+        Err(Some(failure_callback)) => {
+            let message = CString::new("Client was closed").unwrap_or_default();
+            unsafe {
+                failure_callback(
+                    callback_index,
+                    RequestErrorType::ClientClosed,
+                    message.as_ptr(),
+                )
+            };
+            None
+        }
+    }
+}
+
+/// Get a value of the client as a response, like the getters of the client do, or `closed` if the client was closed.
+/// The response should be freed with [`free_respose`].
+///
+/// # Safety
+/// * `client_ptr` must not be `null`.
+/// * `client_ptr` should be a pointer created by [`create_client`].
+unsafe fn client_response(
+    client_ptr: *const c_void,
+    closed: Value,
+    value: impl FnOnce(&Client) -> Value,
+) -> *mut ResponseValue {
+    let value = match unsafe { client_from_ptr(client_ptr) } {
+        Some(client) => value(&client),
+        None => closed,
+    };
+    response_pool::into_raw(ResponseValue::from_value(value), None)
+}
+
+/// Execute a command.
+/// Expects that arguments will be kept valid until the callback is called.
+///
+/// # Safety
+/// * `client_ptr` must not be `null`.
+/// * `client_ptr` must be a pointer created by [`create_client`]. Once [`close_client`] was called with the pointer,
+///   the request fails with [`RequestErrorType::ClientClosed`].
+/// * `args` and `args_len` must not be `null`.
+/// * `data` must point to `arg_count` consecutive string pointers.
+/// * `args_len` must point to `arg_count` consecutive string lengths. See the safety documentation of [`convert_double_pointer_to_vec`].
+/// * `route_info` could be `null`, but if it is not `null`, it must be a valid [`RouteInfo`] pointer. See the safety documentation of [`create_route`].
+///
+/// If `route_info` is `null`, the command is routed by the default route of the client, if one is configured.
+/// See [`DefaultRoute`] for the precedence of routes. In cluster mode, if a route decider was passed to [`create_client`],
+/// it is called first, and the route it returns takes precedence over the routes of the client, like `route_info`.
+/// If the returned route is invalid for the command, see [`check_decided_route`], the command isn't dispatched
+/// and fails with a `ClientError`.
+///
+/// `deadline_unix_ms` is an absolute deadline in milliseconds since the unix epoch, `0` means no deadline.
+/// The remaining time is computed once, when the command is submitted. If the deadline has already passed,
+/// the command is not dispatched and fails immediately with a timeout error.
+///
+/// If [`ConnectionConfig::deduplicate_reads`] is set, a read without a route and a deadline, which is identical to a read in flight,
+/// isn't sent to the server, but receives the result of the read in flight.
+///
+/// If `fire_and_forget` is set, the reply isn't passed to the caller, so no [`ResponseValue`] is allocated and the success callback
+/// isn't called, e.g. for high-volume metrics increments. The reply is still read from the connection, so the commands sent after it
+/// receive their own replies. The failure callback is called only if the command couldn't be delivered, see [`is_delivery_failure`],
+/// e.g. on connection errors, timeouts or backpressure. Errors replied by the server, e.g. `WRONGTYPE`, aren't surfaced in this mode.
+/// Such commands are never coalesced by [`ConnectionConfig::deduplicate_reads`].
+/// Since nothing is called once such a command succeeds, the caller can't tell when its `callback_index` is free again,
+/// and a delivery failure may be reported through it at any time until the request timeout. So `callback_index` must not be
+/// an index of a request awaiting its result, nor be reused for one, e.g. all fire-and-forget commands share a dedicated index
+/// whose failures are only logged.
+///
+/// If `keyless` is set and `route_info` isn't `null`, the caller asserts that the command has no keys, e.g. `TIME` or `DBSIZE`,
+/// so the keys of the command aren't looked up in the command metadata to prefix them by [`ConnectionConfig::key_prefix`].
+/// The command is sent by `route_info` as is. Without `route_info` the flag is ignored, since the command is routed by its metadata anyway.
+///
+/// `correlation_id` could be `null`, but if it is not `null`, it must be a valid null-terminated string, which identifies the request
+/// in the logs of the caller. If the command fails, the error is logged with the correlation id, and the message passed to the failure
+/// callback is tagged with it. Such commands are never coalesced by [`ConnectionConfig::deduplicate_reads`], since every failure carries
+/// the correlation id of its own request. Without a correlation id nothing is allocated for it.
+///
+/// `affinity_token` is a hint to send the commands with the same token by the same connection, e.g. the commands of a session,
+/// for the locality of their connection, `0` means no token. It only affects the choice among equivalent connections, i.e. the clients
+/// of [`ConnectionConfig::connections_per_node`], which the commands are otherwise spread over in turn. The route of the command,
+/// and the node it is sent to, are the same with or without a token. It isn't a lock: commands with the same token may still
+/// be in flight concurrently, and commands with other tokens share their connection.
+///
+/// If `raw_no_retry_no_wait` is set, all resilience is disabled for the command, which is meant for health check probes only,
+/// whose results should reflect the state of the node rather than recover from it. The first error is reported as is, e.g. a
+/// connection error, `MOVED` or `TRYAGAIN`: glide-core doesn't retry the command, follow redirections or wait for a connection
+/// being established, and the client doesn't re-authenticate, retry writes on the primary or fall back to another node.
+/// A command to a node which is down fails right away, rather than after the reconnection attempts. The connections are still
+/// established again, and the topology refreshed, in the background. This is stronger than [`ConnectionConfig::fail_fast_on_degraded`],
+/// which only fails commands to nodes which were already found unhealthy. Such commands are never coalesced by
+/// [`ConnectionConfig::deduplicate_reads`]. Don't use it for application commands, since they fail on every transient error.
+#[allow(rustdoc::private_intra_doc_links)]
+#[allow(clippy::too_many_arguments)]
+#[no_mangle]
+pub unsafe extern "C" fn command(
+    client_ptr: *const c_void,
+    callback_index: usize,
+    request_type: RequestType,
+    args: *const *mut c_char,
+    arg_count: u32,
+    args_len: *const u32,
+    route_info: *const RouteInfo,
+    deadline_unix_ms: u64,
+    fire_and_forget: bool,
+    keyless: bool,
+    correlation_id: *const c_char,
+    affinity_token: u64,
+    raw_no_retry_no_wait: bool,
+) {
+    let Some(client) = (unsafe { open_client_from_ptr(client_ptr, callback_index) }) else {
+        return;
+    };
+    let core = client.core.clone();
+    let correlation_id = (!correlation_id.is_null()).then(|| {
+        unsafe { CStr::from_ptr(correlation_id) }
+            .to_string_lossy()
+            .into_owned()
+    });
+
+    if let Err(err) =
+        unsafe { core.check_argument_sizes(|| format!("{request_type:?}"), args_len, arg_count) }
+    {
+        unsafe { core.report_correlated_error(callback_index, err, correlation_id.as_deref()) };
+        return;
+    }
+
+    // Create the command outside of the task to ensure that the command arguments passed are still valid
+    let cmd = match unsafe {
+        create_command(
+            &request_type,
+            args,
+            arg_count,
+            args_len,
+            core.log_non_utf8_arguments,
+        )
+    } {
+        Ok(cmd) => cmd,
+        Err(err) => {
+            unsafe { core.report_correlated_error(callback_index, err, correlation_id.as_deref()) };
+            return;
+        }
+    };
+    core.command_counts.increment(request_type);
+    let (cmd, decode) = match core.encode_values(cmd) {
+        Ok(encoded) => encoded,
+        Err(err) => {
+            unsafe { core.report_correlated_error(callback_index, err, correlation_id.as_deref()) };
+            return;
+        }
+    };
+    let decided_route_info = match core.route_decider.filter(|_| route_info.is_null()) {
+        Some(route_decider) => unsafe { decide_route(route_decider, request_type, &cmd) },
+        None => std::ptr::null(),
+    };
+    let (mut cmd, route, convert) = match decided_route_info.is_null() {
+        true => unsafe { route_command(core.key_prefix.as_ref(), cmd, route_info, keyless) },
+        false => {
+            let (cmd, route, convert) = unsafe { core.prefix_keys(cmd, decided_route_info) };
+            if let Some(Err(err)) = route.as_ref().map(|route| check_decided_route(&cmd, route)) {
+                unsafe {
+                    core.report_correlated_error(callback_index, err, correlation_id.as_deref())
+                };
+                return;
+            }
+            (cmd, route, convert)
+        }
+    };
+    // Only responses of key names are stripped of the key prefix, and only responses of values are decoded, never both
+    let convert = convert.or(decode);
+    cmd.set_no_retry(raw_no_retry_no_wait);
+
+    let affinity_token = (affinity_token != 0).then_some(affinity_token);
+    let deadline = match deadline_to_instant(deadline_unix_ms) {
+        Ok(deadline) => deadline,
+        Err(err) => {
+            unsafe { core.report_correlated_error(callback_index, err, correlation_id.as_deref()) };
+            return;
+        }
+    };
+
+    if fire_and_forget {
+        // The reply is dropped, so it isn't converted either
+        dispatch_command_with_affinity(
+            &client,
+            cmd,
+            route,
+            deadline,
+            None,
+            affinity_token,
+            move |core, result| {
+                if let Err(err) = result.map(drop) {
+                    if is_delivery_failure(&err) {
+                        unsafe {
+                            core.report_correlated_error(
+                                callback_index,
+                                err,
+                                correlation_id.as_deref(),
+                            )
+                        };
+                    }
+                }
+            },
+        );
+        return;
+    }
+
+    // Only reads without a route or a deadline are coalesced, so every request which joins a flight expects the same result
+    let single_flight = core.single_flight.as_ref().filter(|_| {
+        route.is_none()
+            && deadline.is_none()
+            && correlation_id.is_none()
+            && !raw_no_retry_no_wait
+            && is_readonly(&cmd)
+    });
+    match single_flight.map(|single_flight| single_flight.join(&cmd, callback_index)) {
+        Some(Flight::Joined) => {}
+        Some(Flight::Leader(key)) => dispatch_command_with_affinity(
+            &client,
+            cmd,
+            route,
+            deadline,
+            convert,
+            affinity_token,
+            move |core, result| {
+                let callback_indices = core
+                    .single_flight
+                    .as_ref()
+                    .map(|single_flight| single_flight.land(key))
+                    .unwrap_or_default();
+                unsafe { core.report_result_to_all(&callback_indices, result) };
+            },
+        ),
+        Some(Flight::Alone) | None => match correlation_id {
+            Some(correlation_id) => dispatch_command_with_affinity(
+                &client,
+                cmd,
+                route,
+                deadline,
+                convert,
+                affinity_token,
+                move |core, result| unsafe {
+                    core.report_correlated_result(callback_index, result, Some(&correlation_id))
+                },
+            ),
+            None => dispatch_command_with_affinity(
+                &client,
+                cmd,
+                route,
+                deadline,
+                convert,
+                affinity_token,
+                move |core, result| unsafe { core.report_result(callback_index, result) },
+            ),
+        },
+    }
+}
+
+/// Execute a command by its name, for commands without a [`RequestType`], e.g. module commands like `JSON.SET` or `FT.SEARCH`.
+/// Expects that arguments will be kept valid until the callback is called.
+///
+/// # Safety
+/// * `client_ptr` must not be `null`.
+/// * `client_ptr` must be a pointer created by [`create_client`]. Once [`close_client`] was called with the pointer,
+///   the request fails with [`RequestErrorType::ClientClosed`].
+/// * `command_name` must point to `command_name_len` consecutive bytes. See the safety documentation of [`ptr_to_bytes`](commands::ptr_to_bytes).
+/// * `args` must point to `arg_count` consecutive string pointers and `args_len` must point to their lengths.
+///   See the safety documentation of [`convert_double_pointer_to_vec`].
+/// * `route_info` could be `null`, but if it is not `null`, it must be a valid [`RouteInfo`] pointer. See the safety documentation of [`create_route`].
+///
+/// The client doesn't know the keys of commands it has no request type for, so if `route_info` is `null`
+/// the command is routed to a random node, and the default route of the client doesn't apply.
+/// Commands on keys should be routed by [`RouteType::SlotKey`](ffi::RouteType::SlotKey) in cluster mode, to avoid redirections.
+///
+/// `deadline_unix_ms` is handled like in [`command`].
+#[allow(rustdoc::private_intra_doc_links)]
+#[allow(clippy::too_many_arguments)]
+#[no_mangle]
+pub unsafe extern "C" fn command_by_name(
+    client_ptr: *const c_void,
+    callback_index: usize,
+    command_name: *const u8,
+    command_name_len: u32,
+    args: *const *mut c_char,
+    arg_count: u32,
+    args_len: *const u32,
+    route_info: *const RouteInfo,
+    deadline_unix_ms: u64,
+) {
+    let Some(client) = (unsafe { open_client_from_ptr(client_ptr, callback_index) }) else {
+        return;
+    };
+    let core = client.core.clone();
+
+    let name = unsafe { commands::ptr_to_bytes(command_name, command_name_len) };
+    if name.is_empty() {
+        let err = RedisError::from((redis::ErrorKind::ClientError, "Empty command name"));
+        unsafe { core.report_error(callback_index, err) };
+        return;
+    }
+    if let Err(err) = unsafe {
+        core.check_argument_sizes(
+            || String::from_utf8_lossy(name).to_string(),
+            args_len,
+            arg_count,
+        )
+    } {
+        unsafe { core.report_error(callback_index, err) };
+        return;
+    }
+    let args =
+        unsafe { convert_double_pointer_to_vec(args as *const *const c_void, arg_count, args_len) };
+    if core.log_non_utf8_arguments {
+        log_non_utf8_arguments(&String::from_utf8_lossy(name), &args);
+    }
+    let mut cmd = Cmd::new();
+    cmd.arg(name);
+    for arg in args {
+        cmd.arg(arg);
+    }
+    let (cmd, decode) = match core.encode_values(cmd) {
+        Ok(encoded) => encoded,
+        Err(err) => {
+            unsafe { core.report_error(callback_index, err) };
+            return;
+        }
+    };
+    let (cmd, route, convert) = unsafe { core.prefix_keys(cmd, route_info) };
+    let convert = convert.or(decode);
+    let route = route.or(Some(RoutingInfo::SingleNode(SingleNodeRoutingInfo::Random)));
+
+    let deadline = match deadline_to_instant(deadline_unix_ms) {
+        Ok(deadline) => deadline,
+        Err(err) => {
+            unsafe { core.report_error(callback_index, err) };
+            return;
+        }
+    };
+
+    unsafe { execute_command(&client, callback_index, cmd, route, deadline, convert) };
+}
+
+/// Create a command of the given request type with the given arguments.
+/// If `log_non_utf8` is set, arguments which aren't valid UTF-8 are logged, see [`log_non_utf8_arguments`].
+///
+/// # Safety
+/// * `args` must point to `arg_count` consecutive string pointers and `args_len` must point to their lengths.
+///   See the safety documentation of [`convert_double_pointer_to_vec`].
+pub(crate) unsafe fn create_command(
+    request_type: &RequestType,
+    args: *const *mut c_char,
+    arg_count: u32,
+    args_len: *const u32,
+    log_non_utf8: bool,
+) -> RedisResult<Cmd> {
+    let args =
+        unsafe { convert_double_pointer_to_vec(args as *const *const c_void, arg_count, args_len) };
+    if log_non_utf8 {
+        log_non_utf8_arguments(&format!("{request_type:?}"), &args);
+    }
+    let Some(mut cmd) = request_type.get_command() else {
+        return Err(RedisError::from((
+            redis::ErrorKind::ClientError,
+            "Unknown request type",
+        )));
+    };
+    for command_arg in args {
+        cmd.arg(command_arg);
+    }
+    Ok(cmd)
+}
+
+/// Prefix the keys of a command submitted by the caller and create its route, see [`ConnectionConfig::key_prefix`].
+/// Returns the command, its route and the conversion which strips the prefix from the response, if needed.
+///
+/// # Safety
+///
+/// * `route_info` could be `null`, but if it is not `null`, it must be a valid [`RouteInfo`] pointer. See the safety documentation of [`create_route`].
+pub(crate) unsafe fn prefix_keys(
+    key_prefix: Option<&KeyPrefix>,
+    cmd: Cmd,
+    route_info: *const RouteInfo,
+) -> (Cmd, Option<RoutingInfo>, Option<ResponseConverter>) {
+    let Some(key_prefix) = key_prefix else {
+        let route = unsafe { create_route(route_info, &cmd) };
+        return (cmd, route, None);
+    };
+    let cmd = key_prefix.apply(cmd);
+    let route = unsafe { create_route_with_key_prefix(route_info, &cmd, key_prefix.prefix()) };
+    let convert = key_prefix.response_converter(&cmd);
+    (cmd, route, convert)
+}
+
+/// Like [`prefix_keys`], but a command the caller declared `keyless` and routed explicitly is routed as is,
+/// without looking up its keys, see [`command`].
+///
+/// # Safety
+///
+/// * `route_info` could be `null`, but if it is not `null`, it must be a valid [`RouteInfo`] pointer. See the safety documentation of [`create_route`].
+pub(crate) unsafe fn route_command(
+    key_prefix: Option<&KeyPrefix>,
+    cmd: Cmd,
+    route_info: *const RouteInfo,
+    keyless: bool,
+) -> (Cmd, Option<RoutingInfo>, Option<ResponseConverter>) {
+    match keyless && !route_info.is_null() {
+        // There are no keys to prefix, and the route doesn't depend on the keys
+        true => {
+            let route = unsafe { create_route(route_info, &cmd) };
+            (cmd, route, None)
+        }
+        false => unsafe { prefix_keys(key_prefix, cmd, route_info) },
+    }
+}
+
+/// Conversion applied to a successful response before it is passed to the success callback.
+pub(crate) type ResponseConverter = Box<dyn FnOnce(Value) -> RedisResult<Value> + Send>;
+
+/// Submit a command to the client's runtime and report the result through the client's callbacks.
+///
+/// # Safety
+/// * The client's callbacks must be safe to call with `callback_index`.
+pub(crate) unsafe fn execute_command(
+    client: &Client,
+    callback_index: usize,
+    cmd: Cmd,
+    route: Option<RoutingInfo>,
+    deadline: Option<Instant>,
+    convert: Option<ResponseConverter>,
+) {
+    dispatch_command(
+        client,
+        cmd,
+        route,
+        deadline,
+        convert,
+        move |core, result| unsafe { core.report_result(callback_index, result) },
+    );
+}
+
+/// Dispatch a command like [`dispatch_command_with_affinity`], by the next client of the pool.
+pub(crate) fn dispatch_command(
+    client: &Client,
+    cmd: Cmd,
+    route: Option<RoutingInfo>,
+    deadline: Option<Instant>,
+    convert: Option<ResponseConverter>,
+    complete: impl FnOnce(&CommandExecutionCore, RedisResult<Value>) + Send + 'static,
+) {
+    dispatch_command_with_affinity(client, cmd, route, deadline, convert, None, complete);
+}
+
+/// Submit a command to the client's runtime and pass the result to `complete`.
+/// If the command can't be dispatched, e.g. because its node is unavailable, `complete` is called right away.
+pub(crate) fn dispatch_command_with_affinity(
+    client: &Client,
+    cmd: Cmd,
+    route: Option<RoutingInfo>,
+    deadline: Option<Instant>,
+    convert: Option<ResponseConverter>,
+    affinity_token: Option<u64>,
+    complete: impl FnOnce(&CommandExecutionCore, RedisResult<Value>) + Send + 'static,
+) {
+    let core = client.core.clone();
+    if let Some(recent_writes) = &core.recent_writes {
+        recent_writes.record(&cmd);
+    }
+    let route = route.or_else(|| core.implicit_route(&cmd));
+    // A primary preferred route is sent to the primary, and a read falls back to a replica once if the primary is overloaded
+    // or unavailable. Such a read doesn't fall back to the primary again, see `fallback_to_primary_on_replica_error`.
+    let (mut route, mut replica_fallback) = split_primary_preferred(&cmd, route);
+    // Commands which aren't retried aren't retried by the client either, see `raw_no_retry_no_wait` of `command`
+    let no_retry = cmd.is_no_retry();
+    if no_retry {
+        replica_fallback = None;
+    }
+    let primary_preferred = replica_fallback.is_some();
+    let pending_guard = match core
+        .check_command_allowed(&cmd)
+        .and_then(|_| check_replica_route(&cmd, route.as_ref()))
+        .and_then(|_| core.check_circuit_breaker())
+        .and_then(|_| core.reserve_pending_task())
+    {
+        Ok(pending_guard) => pending_guard,
+        Err(err) => {
+            complete(&core, Err(err));
+            return;
+        }
+    };
+    let reserve_node = |node: Option<String>| {
+        core.check_node_available(node.as_deref())
+            .and_then(|_| core.reserve_node_inflight(node))
+    };
+    let mut node = core.node_for_command(&cmd, route.as_ref());
+    let mut reserved = reserve_node(node.clone());
+    if reserved
+        .as_ref()
+        .is_err_and(|err| replica_fallback.is_some() && should_fall_back_to_replica(err))
+    {
+        route = replica_fallback.take();
+        node = core.node_for_command(&cmd, route.as_ref());
+        reserved = reserve_node(node.clone());
+    }
+    let inflight_guard = match reserved {
+        Ok(inflight_guard) => inflight_guard,
+        Err(err) => {
+            complete(&core, Err(err));
+            return;
+        }
+    };
+
+    // The turn is taken before spawning, so the commands on a key are dispatched in the order they were submitted
+    let key_turn = core
+        .key_ordering
+        .as_ref()
+        .and_then(|key_ordering| key_ordering.enter(&cmd));
+
+    let spawned = Instant::now();
+    client.runtime.spawn(async move {
+        if let Some(runtime_saturation) = &core.runtime_saturation {
+            runtime_saturation.task_started(spawned);
+        }
+        let key_turn = match key_turn {
+            Some(key_turn) => Some(key_turn.wait().await),
+            None => None,
+        };
+        let mut client = match affinity_token {
+            Some(affinity_token) => core.client_pool.for_token(affinity_token),
+            None => core.client_pool.next(),
+        };
+        let started = Instant::now();
+        // Latency of the first attempt to send the command, which is reported if it's slow.
+        let mut send_latency = None;
+        // The node whose health the result reflects, unless the read fell back to another node.
+        let mut health_node = node.clone();
+        // The route the result came from, if the read fell back to another node.
+        let mut served_route = None;
+        let send = async {
+            let result = client.send_command(&cmd, route.clone()).await;
+            send_latency = Some(started.elapsed());
+            let result = match result {
+                Err(err)
+                    if core.reauthenticate_on_noauth
+                        && !no_retry
+                        && err.code() == Some("NOAUTH") =>
+                {
+                    core.reauthenticate(&client, node.as_deref()).await?;
+                    client.send_command(&cmd, route.clone()).await
+                }
+                result => result,
+            };
+            let result = match result {
+                Err(err)
+                    if core.retry_writes_on_readonly
+                        && !no_retry
+                        && error_type(&err) == RequestErrorType::ReadOnly
+                        && !is_readonly(&cmd) =>
+                {
+                    match core.primary_route_after_refresh(&cmd, route.as_ref()).await {
+                        Some(primary_route) => {
+                            health_node = None;
+                            served_route = Some(primary_route.clone());
+                            client.send_command(&cmd, Some(primary_route)).await
+                        }
+                        None => Err(err),
+                    }
+                }
+                result => result,
+            };
+            let fallback_route = match &result {
+                Err(err) if primary_preferred => replica_fallback
+                    .clone()
+                    .filter(|_| should_fall_back_to_replica(err)),
+                Err(err)
+                    if core.fallback_to_primary_on_replica_error
+                        && !no_retry
+                        && is_unavailable(err) =>
+                {
+                    primary_fallback_route(&cmd, route.as_ref(), core.reads_from_replicas)
+                }
+                _ => None,
+            };
+            match fallback_route {
+                Some(fallback_route) => {
+                    core.update_node_health(node.as_deref(), &result);
+                    health_node = None;
+                    served_route = Some(fallback_route.clone());
+                    client.send_command(&cmd, Some(fallback_route)).await
+                }
+                None => result,
+            }
+        };
+        let result = match deadline {
+            Some(deadline) => tokio::time::timeout_at(deadline, send)
+                .await
+                .unwrap_or_else(|_| Err(timeout_error())),
+            None => send.await,
+        };
+        let latency = started.elapsed();
+        core.command_latency.record(latency);
+        drop(key_turn);
+        drop(inflight_guard);
+        if let Some(command_trace) = &core.command_trace {
+            command_trace.record(&cmd, route.as_ref(), node.as_deref(), latency, &result);
+        }
+        core.update_node_health(health_node.as_deref(), &result);
+        core.track_authentication(&result);
+        core.track_cluster_down(&result);
+        let (result, attributes) = match result {
+            Ok(value) => {
+                let (value, attributes) = split_attributes(value);
+                (Ok(value), attributes)
+            }
+            Err(err) => (Err(err), None),
+        };
+        let result = match convert {
+            Some(convert) => result.and_then(convert),
+            None => result,
+        };
+        let result = core.normalize_reply(result);
+        let result = match served_route {
+            Some(served_route) => {
+                let served_node = core.node_for_command(&cmd, Some(&served_route));
+                core.attach_metadata(&cmd, Some(&served_route), served_node, result, attributes)
+            }
+            None => core.attach_metadata(&cmd, route.as_ref(), node.clone(), result, attributes),
+        };
+        complete(&core, result);
+        drop(pending_guard);
+        // A command which timed out during its first attempt is reported by the time until the timeout
+        core.report_slow_command(&cmd, send_latency.unwrap_or(latency), node.as_deref());
+    });
+}
+
+/// Fail commands which aren't read-only if they are explicitly routed to a replica, since the replica would reject them.
+fn check_replica_route(cmd: &Cmd, route: Option<&RoutingInfo>) -> RedisResult<()> {
+    match route {
+        Some(RoutingInfo::SingleNode(SingleNodeRoutingInfo::SpecificNode(route)))
+            if route.slot_addr() == SlotAddr::ReplicaRequired && !is_readonly(cmd) =>
+        {
+            Err(RedisError::from((
+                redis::ErrorKind::ClientError,
+                "Only read-only commands can be routed to a replica",
+                cmd.command()
+                    .map(|name| String::from_utf8_lossy(&name).to_string())
+                    .unwrap_or_default(),
+            )))
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Whether an error means that the node which served the request is disconnected or unavailable, or the cluster is down.
+fn is_unavailable(err: &RedisError) -> bool {
+    matches!(
+        error_type(err),
+        RequestErrorType::Disconnect
+            | RequestErrorType::Unavailable
+            | RequestErrorType::ClusterDown
+    )
+}
+
+/// Get a route to the primary to retry a read on, if the read was routed to a replica.
+/// Reads without a route are routed to a replica by glide-core only if `reads_from_replicas`.
+fn primary_fallback_route(
+    cmd: &Cmd,
+    route: Option<&RoutingInfo>,
+    reads_from_replicas: bool,
+) -> Option<RoutingInfo> {
+    if !is_readonly(cmd) {
+        return None;
+    }
+    let route = match route {
+        Some(route) => route.clone(),
+        None if reads_from_replicas => RoutingInfo::for_routable(cmd)?,
+        None => return None,
+    };
+    match route {
+        RoutingInfo::SingleNode(SingleNodeRoutingInfo::SpecificNode(route))
+            if route.slot_addr() != SlotAddr::Master =>
+        {
+            Some(RoutingInfo::SingleNode(
+                SingleNodeRoutingInfo::SpecificNode(Route::new(route.slot(), SlotAddr::Master)),
+            ))
+        }
+        _ => None,
+    }
+}
+
+/// Execute a batch - a pipeline or a transaction.
+/// Commands of a non-atomic batch in cluster mode are grouped by the node they route to and dispatched concurrently.
+/// The response is an array with the results of the commands, in the order they were given.
+/// Expects that arguments will be kept valid until the function returns.
+///
+/// If `raise_on_error` is set, the first failed command fails the whole batch through the failure callback.
+/// Otherwise errors are collected: the result of every failed command is a [`ValueType::Error`](ffi::ValueType::Error) value
+/// in its position, with the type and the message of the error, so the failed subset of the commands could be retried.
+/// With [`BatchOptionsInfo::partial_results_chunk_size`], a non-atomic batch which times out returns the results completed so far
+/// in their positions, and a `Timeout` error value in the position of every command which didn't complete.
+///
+/// # Safety
+/// * `client_ptr` must not be `null`.
+/// * `client_ptr` must be able to be safely casted to a valid [`Arc<Client>`] via [`Arc::from_raw`]. See the safety documentation of [`Arc::from_raw`].
+/// * This function should only be called should with a pointer created by [`create_client`], before [`close_client`] was called with the pointer.
+/// * `batch_ptr` must not be `null` and must be a valid [`BatchInfo`] pointer. See the safety documentation of [`create_pipeline`].
+/// * `options_ptr` could be `null`, but if it is not `null`, it must be a valid [`BatchOptionsInfo`] pointer.
+#[allow(rustdoc::private_intra_doc_links)]
+#[no_mangle]
+pub unsafe extern "C" fn batch(
+    client_ptr: *const c_void,
+    callback_index: usize,
+    batch_ptr: *const BatchInfo,
+    raise_on_error: bool,
+    options_ptr: *const BatchOptionsInfo,
+) {
+    let Some(client) = (unsafe { open_client_from_ptr(client_ptr, callback_index) }) else {
+        return;
+    };
+    let core = client.core.clone();
+
+    // Create the pipeline outside of the task to ensure that the command arguments passed are still valid
+    let Some(pipeline) = (unsafe { create_pipeline(batch_ptr) }) else {
+        unsafe {
+            core.report_error(
+                callback_index,
+                RedisError::from((redis::ErrorKind::ClientError, "Unknown request type")),
+            );
+        }
+        return;
+    };
+    if let Err(err) = pipeline
+        .cmd_iter()
+        .try_for_each(|cmd| core.check_command_allowed(cmd))
+        .and_then(|_| core.check_circuit_breaker())
+    {
+        unsafe { core.report_error(callback_index, err) };
+        return;
+    }
+    let pending_guard = match core.reserve_pending_task() {
+        Ok(pending_guard) => pending_guard,
+        Err(err) => {
+            unsafe { core.report_error(callback_index, err) };
+            return;
+        }
+    };
+    let options = unsafe { options_ptr.as_ref() }.copied();
+    let timeout = options.and_then(|options| options.has_timeout.then_some(options.timeout));
+    let retry_strategy = options.map_or_else(PipelineRetryStrategy::default, |options| {
+        PipelineRetryStrategy::new(options.retry_server_error, options.retry_connection_error)
+    });
+    let partial_results_chunk_size =
+        options.map_or(0, |options| options.partial_results_chunk_size);
+    if partial_results_chunk_size > 0 && (pipeline.is_atomic() || timeout.is_none()) {
+        let err = RedisError::from((
+            redis::ErrorKind::ClientError,
+            "Partial results require a timeout and a non-atomic batch",
+        ));
+        unsafe { core.report_error(callback_index, err) };
+        return;
+    }
+
+    let spawned = Instant::now();
+    client.runtime.spawn(async move {
+        if let Some(runtime_saturation) = &core.runtime_saturation {
+            runtime_saturation.task_started(spawned);
+        }
+        let mut client = core.client_pool.next();
+        let result = if pipeline.is_atomic() {
+            client
+                .send_transaction(&pipeline, None, timeout, raise_on_error)
+                .await
+        } else if let Some(timeout) = timeout.filter(|_| partial_results_chunk_size > 0) {
+            send_with_partial_results(
+                &mut client,
+                &pipeline,
+                partial_results_chunk_size,
+                Duration::from_millis(timeout.into()),
+                raise_on_error,
+                retry_strategy,
+            )
+            .await
+        } else {
+            client
+                .send_pipeline(&pipeline, None, raise_on_error, timeout, retry_strategy)
+                .await
+        };
+        core.track_authentication(&result);
+        let result = core.normalize_batch_reply(result);
+        unsafe { core.report_result(callback_index, result) };
+        drop(pending_guard);
+    });
+}
+
+/// Eagerly establish connections to every primary and replica in the current topology.
+/// In standalone mode only ensures that the connection to the server is established.
+/// The response is a map of node addresses to a boolean, which tells whether the node responded
+/// within `timeout_ms` milliseconds (`0` means the request timeout of the client).
+///
+/// # Safety
+/// * `client_ptr` must not be `null`.
+/// * `client_ptr` must be a valid pointer created by [`create_client`], before [`close_client`] was called with the pointer.
+#[no_mangle]
+pub unsafe extern "C" fn prewarm_connections(
+    client_ptr: *const c_void,
+    callback_index: usize,
+    timeout_ms: u32,
+) {
+    let Some(client) = (unsafe { open_client_from_ptr(client_ptr, callback_index) }) else {
+        return;
+    };
+    let core = client.core.clone();
+    let timeout = (timeout_ms > 0).then(|| Duration::from_millis(timeout_ms.into()));
+    client.runtime.spawn(async move {
+        let result = core.prewarm_connections(timeout).await;
+        unsafe { core.report_result(callback_index, result) };
+    });
+}
+
+/// Get the version of the server, parsed from the `INFO SERVER` response.
+/// The response is the version string if the request is routed to a single node, e.g. in standalone mode
+/// or with a single node route, and a map of node addresses to versions otherwise, which is the default in cluster mode.
+/// This allows detecting mixed-version clusters, e.g. during an upgrade.
+///
+/// Versions of single nodes are cached, until a command sent to the node fails with a connection error.
+///
+/// # Safety
+/// * `client_ptr` must not be `null`.
+/// * `client_ptr` must be a valid pointer created by [`create_client`], before [`close_client`] was called with the pointer.
+/// * `route_info` could be `null`, but if it is not `null`, it must be a valid [`RouteInfo`] pointer. See the safety documentation of [`create_route`].
+#[allow(rustdoc::private_intra_doc_links)]
+#[no_mangle]
+pub unsafe extern "C" fn get_server_version(
+    client_ptr: *const c_void,
+    callback_index: usize,
+    route_info: *const RouteInfo,
+) {
+    let Some(client) = (unsafe { open_client_from_ptr(client_ptr, callback_index) }) else {
+        return;
+    };
+    let core = client.core.clone();
+    let mut cmd = redis::cmd("INFO");
+    cmd.arg("SERVER");
+    let route = unsafe { create_route(route_info, &cmd) };
+    client.runtime.spawn(async move {
+        let result = core.server_version(route).await;
+        unsafe { core.report_result(callback_index, result) };
+    });
+}
+
+/// Send `PING` to a node and measure its round-trip time, e.g. to monitor the latency of every replica.
+/// The response is the round-trip time in microseconds as an integer. It is measured around the request in the runtime,
+/// so it doesn't include the overhead of the callbacks, but it does include waiting for commands sent before on the connection.
+///
+/// The request should be routed to a single node, e.g. by [`RouteType::ByAddress`](ffi::RouteType::ByAddress).
+/// Without a route it is sent to the server in standalone mode and to a random node in cluster mode.
+///
+/// # Safety
+/// * `client_ptr` must not be `null`.
+/// * `client_ptr` must be a valid pointer created by [`create_client`], before [`close_client`] was called with the pointer.
+/// * `route_info` could be `null`, but if it is not `null`, it must be a valid [`RouteInfo`] pointer. See the safety documentation of [`create_route`].
+#[allow(rustdoc::private_intra_doc_links)]
+#[no_mangle]
+pub unsafe extern "C" fn ping_node(
+    client_ptr: *const c_void,
+    callback_index: usize,
+    route_info: *const RouteInfo,
+) {
+    let Some(client) = (unsafe { open_client_from_ptr(client_ptr, callback_index) }) else {
+        return;
+    };
+    let core = client.core.clone();
+    let cmd = redis::cmd("PING");
+    let route = unsafe { create_route(route_info, &cmd) };
+    client.runtime.spawn(async move {
+        let mut client = core.client.clone();
+        let started = Instant::now();
+        let result = client.send_command(&cmd, route).await;
+        let round_trip = started.elapsed();
+        let result = result.map(|_| Value::Int(round_trip.as_micros() as i64));
+        unsafe { core.report_result(callback_index, result) };
+    });
+}
+
+/// Close the connections to the node at `host` and `port`, e.g. to drop connections to nodes which are being removed during
+/// a scale-down, without waiting for a topology refresh. Commands sent to the node before are answered first, since the
+/// connections are closed by `QUIT`. The connections are established again once commands are routed to the node,
+/// e.g. if it is still part of the topology. In standalone mode only the server of the client can be disconnected.
+/// The response is `OK`.
+///
+/// Dedicated connections, e.g. of leases or blocking commands, aren't closed.
+///
+/// # Safety
+/// * `client_ptr` must not be `null`.
+/// * `client_ptr` must be a valid pointer created by [`create_client`], before [`close_client`] was called with the pointer.
+/// * `host` must be a valid null-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn disconnect_node(
+    client_ptr: *const c_void,
+    callback_index: usize,
+    host: *const c_char,
+    port: u16,
+) {
+    let Some(client) = (unsafe { open_client_from_ptr(client_ptr, callback_index) }) else {
+        return;
+    };
+    let core = client.core.clone();
+    let Ok(host) = unsafe { CStr::from_ptr(host) }.to_str() else {
+        let err = RedisError::from((
+            redis::ErrorKind::ClientError,
+            "The host must be a UTF-8 string",
+        ));
+        unsafe { core.report_error(callback_index, err) };
+        return;
+    };
+    let address = format!("{host}:{port}");
+    client.runtime.spawn(async move {
+        let result = core.disconnect_node(&address).await;
+        unsafe { core.report_result(callback_index, result) };
+    });
+}
+
+/// Get the in-flight requests depth of every node the client sent commands to.
+/// Commands which aren't sent to a single node (e.g. routed to all primaries) aren't counted.
+/// The returned value should be freed with [`free_node_inflight_statistics`].
+///
+/// # Safety
+/// * `client_ptr` must not be `null`.
+/// * `client_ptr` must be a pointer created by [`create_client`]. Once [`close_client`] was called with the pointer,
+///   the statistics are empty.
+#[no_mangle]
+pub unsafe extern "C" fn get_node_inflight_statistics(
+    client_ptr: *const c_void,
+) -> *mut NodeInflightStatistics {
+    let depths = match unsafe { client_from_ptr(client_ptr) } {
+        Some(client) => client.core.node_inflight.depths(),
+        None => vec![],
+    };
+    let statistics = NodeInflightStatistics::new(depths);
+    Box::into_raw(Box::new(statistics))
 }
 
-struct CommandExecutionCore {
-    client: GlideClient,
-    success_callback: unsafe extern "C" fn(usize, *const ResponseValue) -> (),
-    failure_callback: unsafe extern "C" fn(usize) -> (), // TODO - add specific error codes
+/// Free the memory allocated for a [`NodeInflightStatistics`].
+///
+/// # Safety
+/// * `ptr` must not be `null`.
+/// * `ptr` must be a pointer returned by [`get_node_inflight_statistics`], which wasn't freed yet.
+#[no_mangle]
+pub unsafe extern "C" fn free_node_inflight_statistics(ptr: *mut NodeInflightStatistics) {
+    unsafe {
+        let statistics = Box::from_raw(ptr);
+        statistics.free_memory();
+    }
 }
 
+/// Get the effective configuration of the client, after the defaults are applied, e.g. to compare the configurations of clients
+/// or to create an identical client. The response is a map of setting names to their values, like `request_timeout_ms`,
+/// `read_from` or `tls_mode`. Secrets, like the password, are redacted.
+/// The returned value should be freed with [`free_respose`].
+///
 /// # Safety
+/// * `client_ptr` must not be `null`.
+/// * `client_ptr` must be a pointer created by [`create_client`]. Once [`close_client`] was called with the pointer,
+///   the response is an empty map.
+#[no_mangle]
+pub unsafe extern "C" fn get_effective_config(client_ptr: *const c_void) -> *mut ResponseValue {
+    unsafe {
+        client_response(client_ptr, Value::Map(vec![]), |client| {
+            client.core.effective_config.clone()
+        })
+    }
+}
+
+/// Get the last commands of the client recorded by [`ConnectionConfig::command_trace_capacity`], from the oldest to the newest,
+/// e.g. to reproduce an issue. The response is an array of maps, see [`CommandTrace::to_value`] for their fields, which is empty
+/// if recording is disabled. The returned value should be freed with [`free_respose`].
 ///
-/// * `config` must be a valid [`ConnectionConfig`] pointer. See the safety documentation of [`create_connection_request`].
-unsafe fn create_client_internal(
-    config: *const ConnectionConfig,
-    success_callback: unsafe extern "C" fn(usize, *const ResponseValue) -> (),
-    failure_callback: unsafe extern "C" fn(usize) -> (),
-) -> RedisResult<Client> {
-    let request = unsafe { create_connection_request(config) };
-    let runtime = Builder::new_multi_thread()
-        .enable_all()
-        .thread_name("GLIDE C# thread")
-        .build()?;
-    let _runtime_handle = runtime.enter();
-    let client = runtime.block_on(GlideClient::new(request, None)).unwrap(); // TODO - handle errors.
-    let core = Arc::new(CommandExecutionCore {
-        success_callback,
-        failure_callback,
-        client,
-    });
-    Ok(Client { runtime, core })
+/// # Safety
+/// * `client_ptr` must not be `null`.
+/// * `client_ptr` must be a pointer created by [`create_client`]. Once [`close_client`] was called with the pointer,
+///   the response is an empty array.
+#[allow(rustdoc::private_intra_doc_links)]
+#[no_mangle]
+pub unsafe extern "C" fn get_command_trace(client_ptr: *const c_void) -> *mut ResponseValue {
+    unsafe {
+        client_response(client_ptr, Value::Array(vec![]), |client| {
+            match &client.core.command_trace {
+                Some(command_trace) => command_trace.to_value(),
+                None => Value::Array(vec![]),
+            }
+        })
+    }
 }
 
-/// Creates a new client with the given configuration.
-/// The success callback needs to copy the given string synchronously, since it will be dropped by Rust once the callback returns.
-/// All callbacks should be offloaded to separate threads in order not to exhaust the client's thread pool.
+/// Get the number of commands submitted by [`command`] per request type, since the client was created or since
+/// [`reset_command_counts`] was called, e.g. to find which commands dominate the traffic. The response is a map of the numeric
+/// values of the request types to their counts, without request types which weren't submitted. Commands submitted by
+/// [`command_by_name`], by batches or by command helpers aren't counted. The returned value should be freed with [`free_respose`].
 ///
 /// # Safety
+/// * `client_ptr` must not be `null`.
+/// * `client_ptr` must be a pointer created by [`create_client`]. Once [`close_client`] was called with the pointer,
+///   the response is an empty map.
+#[no_mangle]
+pub unsafe extern "C" fn get_command_counts(client_ptr: *const c_void) -> *mut ResponseValue {
+    unsafe {
+        client_response(client_ptr, Value::Map(vec![]), |client| {
+            client.core.command_counts.to_value()
+        })
+    }
+}
+
+/// Reset the counts returned by [`get_command_counts`].
 ///
-/// * `config` must be a valid [`ConnectionConfig`] pointer. See the safety documentation of [`create_client_internal`].
-#[allow(rustdoc::private_intra_doc_links)]
+/// # Safety
+/// * `client_ptr` must not be `null`.
+/// * `client_ptr` must be a pointer created by [`create_client`]. Once [`close_client`] was called with the pointer,
+///   nothing is reset.
 #[no_mangle]
-pub unsafe extern "C" fn create_client(
-    config: *const ConnectionConfig,
-    success_callback: unsafe extern "C" fn(usize, *const ResponseValue) -> (),
-    failure_callback: unsafe extern "C" fn(usize) -> (),
-) -> *const c_void {
-    match unsafe { create_client_internal(config, success_callback, failure_callback) } {
-        Err(_) => std::ptr::null(), // TODO - log errors
-        Ok(client) => Arc::into_raw(Arc::new(client)) as *const c_void,
+pub unsafe extern "C" fn reset_command_counts(client_ptr: *const c_void) {
+    if let Some(client) = unsafe { client_from_ptr(client_ptr) } {
+        client.core.command_counts.reset();
     }
 }
 
-/// Closes the given client, deallocating it from the heap.
-/// This function should only be called once per pointer created by [`create_client`].
-/// After calling this function the `client_ptr` is not in a valid state.
+/// Get the channels, patterns and shard channels the client is subscribed to, without a request to the server.
+/// The subscriptions are those the client was created with, which glide-core resubscribes to after reconnecting or
+/// after the slots of sharded channels moved. The response is a map of `channels`, `patterns` and `sharded_channels`,
+/// each an array of names. The returned value should be freed with [`free_respose`].
 ///
 /// # Safety
+/// * `client_ptr` must not be `null`.
+/// * `client_ptr` must be a pointer created by [`create_client`]. Once [`close_client`] was called with the pointer,
+///   the response has no subscriptions.
+#[no_mangle]
+pub unsafe extern "C" fn get_subscriptions(client_ptr: *const c_void) -> *mut ResponseValue {
+    unsafe {
+        client_response(client_ptr, subscriptions_value(None), |client| {
+            client.core.subscriptions.clone()
+        })
+    }
+}
+
+/// Get the connection timings of every node the client connected to, or tried to, including reconnections,
+/// e.g. to find a single slow node which delays [`create_client`]. The response is a map of the `host:port` addresses of the nodes
+/// to maps of `connect_latency_us` - the time it took to establish and set up the last connection, `last_connected_ms` - the unix time
+/// of the last successful connection, `connected` - whether a connection to the node is open, and `failed_attempts`.
+/// The returned value should be freed with [`free_respose`].
 ///
+/// # Safety
 /// * `client_ptr` must not be `null`.
-/// * `client_ptr` must be able to be safely casted to a valid [`Box<Client>`] via [`Box::from_raw`]. See the safety documentation of [`Box::from_raw`].
+/// * `client_ptr` must be a pointer created by [`create_client`]. Once [`close_client`] was called with the pointer,
+///   the response is an empty map.
 #[no_mangle]
-pub extern "C" fn close_client(client_ptr: *const c_void) {
-    assert!(!client_ptr.is_null());
-    // This will bring the strong count down to 0 once all client requests are done.
-    unsafe { Arc::decrement_strong_count(client_ptr as *const Client) };
+pub unsafe extern "C" fn get_connection_timings(client_ptr: *const c_void) -> *mut ResponseValue {
+    unsafe {
+        client_response(client_ptr, Value::Map(vec![]), |client| {
+            client.core.connection_timings.to_value()
+        })
+    }
 }
 
-/// Execute a command.
-/// Expects that arguments will be kept valid until the callback is called.
+/// Get the capabilities of the nodes, as reported by `HELLO` while the client connected to them, without another round trip,
+/// e.g. to decide which commands are safe to use on a cluster whose nodes run different versions. The response is a map
+/// of the `host:port` addresses of the nodes to maps of `server` - the name of the server, e.g. `valkey`, `version`,
+/// `proto` - the negotiated protocol, and `modules` - the loaded modules, see [`ServerCapabilities::to_value`].
+/// With RESP2 connections are set up without `HELLO`, so the map is empty.
+///
+/// If `route_info` is `null`, the capabilities of every node the client connected to are returned. Otherwise only the nodes
+/// of the route are included in cluster mode: the primaries for [`RouteType::AllPrimaries`](ffi::RouteType::AllPrimaries),
+/// and the node of a single node route. A random route is handled like `null`, and in standalone mode the route is ignored.
+/// The returned value should be freed with [`free_respose`].
 ///
 /// # Safety
 /// * `client_ptr` must not be `null`.
-/// * `client_ptr` must be able to be safely casted to a valid [`Box<Client>`] via [`Box::from_raw`]. See the safety documentation of [`Box::from_raw`].
-/// * This function should only be called should with a pointer created by [`create_client`], before [`close_client`] was called with the pointer.
-/// * `args` and `args_len` must not be `null`.
-/// * `data` must point to `arg_count` consecutive string pointers.
-/// * `args_len` must point to `arg_count` consecutive string lengths. See the safety documentation of [`convert_double_pointer_to_vec`].
-/// * `route_info` could be `null`, but if it is not `null`, it must be a valid [`RouteInfo`] pointer. See the safety documentation of [`create_route`].
+/// * `client_ptr` must be a pointer created by [`create_client`]. Once [`close_client`] was called with the pointer,
+///   the response is an empty map.
+/// * `route_info` could be `null`, but if it is not `null`, it must be a valid [`RouteInfo`] pointer.
 #[allow(rustdoc::private_intra_doc_links)]
 #[no_mangle]
-pub unsafe extern "C" fn command(
+pub unsafe extern "C" fn get_server_capabilities(
     client_ptr: *const c_void,
-    callback_index: usize,
-    request_type: RequestType,
-    args: *const *mut c_char,
-    arg_count: u32,
-    args_len: *const u32,
     route_info: *const RouteInfo,
-) {
-    let client = unsafe {
-        // we increment the strong count to ensure that the client is not dropped just because we turned it into an Arc.
-        Arc::increment_strong_count(client_ptr);
-        Arc::from_raw(client_ptr as *mut Client)
+) -> *mut ResponseValue {
+    let Some(client) = (unsafe { client_from_ptr(client_ptr) }) else {
+        return response_pool::into_raw(ResponseValue::from_value(Value::Map(vec![])), None);
     };
-    let core = client.core.clone();
-
-    let arg_vec =
-        unsafe { convert_double_pointer_to_vec(args as *const *const c_void, arg_count, args_len) };
-
-    // Create the command outside of the task to ensure that the command arguments passed are still valid
-    let Some(mut cmd) = request_type.get_command() else {
-        unsafe {
-            (core.failure_callback)(callback_index); // TODO - report errors
-            return;
+    let core = &client.core;
+    let route = unsafe { create_route(route_info, &redis::cmd("HELLO")) }
+        .filter(|_| core.standalone_address.is_none());
+    let topology = core.topology.read().unwrap().clone();
+    let value = match route {
+        None
+        | Some(RoutingInfo::MultiNode((MultipleNodeRoutingInfo::AllNodes, _)))
+        | Some(RoutingInfo::SingleNode(SingleNodeRoutingInfo::Random)) => {
+            core.server_capabilities.to_value(|_| true)
+        }
+        Some(RoutingInfo::MultiNode((MultipleNodeRoutingInfo::AllMasters, _))) => {
+            let primaries = topology.primaries();
+            core.server_capabilities
+                .to_value(|address| primaries.contains(&address))
+        }
+        Some(route) => {
+            let node = topology.node_for_route(&route);
+            core.server_capabilities
+                .to_value(|address| Some(address) == node.as_deref())
         }
     };
-    for command_arg in arg_vec {
-        cmd.arg(command_arg);
+    response_pool::into_raw(ResponseValue::from_value(value), None)
+}
+
+/// Get the RESP protocol negotiated with every node the client connected to, e.g. to verify that RESP3 is used by every node
+/// while migrating from RESP2. The response is a map of the `host:port` addresses of the nodes to the protocol version,
+/// e.g. `3`, as reported by `HELLO` while connecting. Connections set up without `HELLO` use RESP2, so their nodes are mapped to `2`.
+/// The returned value should be freed with [`free_respose`].
+///
+/// # Safety
+/// * `client_ptr` must not be `null`.
+/// * `client_ptr` must be a pointer created by [`create_client`]. Once [`close_client`] was called with the pointer,
+///   the response is an empty map.
+#[no_mangle]
+pub unsafe extern "C" fn get_protocol_versions(client_ptr: *const c_void) -> *mut ResponseValue {
+    unsafe {
+        client_response(client_ptr, Value::Map(vec![]), |client| {
+            client.core.server_capabilities.protocols_to_value()
+        })
     }
+}
 
-    let route = create_route(route_info, &cmd);
+/// Per-client statistics, returned by [`get_statistics`].
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct ClientStatistics {
+    /// `MOVED` redirections handled by the client in cluster mode.
+    pub moved_redirections: u64,
+    /// `ASK` redirections handled by the client in cluster mode.
+    pub ask_redirections: u64,
+    /// Commands and batches which were submitted and whose callback wasn't called yet.
+    /// Unlike the other statistics, this is the current amount, which isn't reset by [`reset_statistics`].
+    pub pending_commands: u64,
+    /// Slow commands which weren't reported through the slow command callback, since the callback fell behind by
+    /// more than 1024 commands, see [`ConnectionConfig::slow_command_threshold`].
+    pub dropped_slow_commands: u64,
+}
 
-    client.runtime.spawn(async move {
-        let result = core.client.clone().send_command(&cmd, route).await;
-        unsafe {
-            match result {
-                Ok(value) => {
-                    let ptr = Box::into_raw(Box::new(ResponseValue::from_value(value)));
-                    (core.success_callback)(callback_index, ptr);
-                }
-                Err(err) => {
-                    dbg!(err); // TODO - report errors
-                    (core.failure_callback)(callback_index)
-                }
-            };
+/// Get the statistics of the client since it was created or since [`reset_statistics`] was called.
+/// Frequent redirections indicate topology churn, e.g. slot migrations while the cluster is rebalanced.
+///
+/// # Safety
+/// * `client_ptr` must not be `null`.
+/// * `client_ptr` must be a pointer created by [`create_client`]. Once [`close_client`] was called with the pointer,
+///   the statistics are zeroed.
+#[no_mangle]
+pub unsafe extern "C" fn get_statistics(client_ptr: *const c_void) -> ClientStatistics {
+    let Some(client) = (unsafe { client_from_ptr(client_ptr) }) else {
+        return ClientStatistics {
+            moved_redirections: 0,
+            ask_redirections: 0,
+            pending_commands: 0,
+            dropped_slow_commands: 0,
         };
-    });
+    };
+    let (moved_redirections, ask_redirections) = client.core.redirections.counts();
+    ClientStatistics {
+        moved_redirections,
+        ask_redirections,
+        pending_commands: client.core.pending_tasks.count().into(),
+        dropped_slow_commands: client.core.dropped_slow_commands.load(Ordering::Relaxed),
+    }
+}
+
+/// Get the metrics of the client in the Prometheus text exposition format, e.g. to serve them on the scrape endpoint
+/// of the application, instead of calling the individual statistics functions. The response is a bulk string with:
+/// * `glide_open_connections{node}` - gauge of the connections to the node which are open, see [`get_connection_timings`].
+/// * `glide_connection_failures_total{node}` - counter of the failed attempts to connect to the node.
+/// * `glide_inflight_requests{node}` - gauge of the requests to the node which weren't answered yet, see [`get_node_inflight_statistics`].
+/// * `glide_pending_commands` - gauge of the commands and batches whose callback wasn't called yet, see [`get_statistics`].
+/// * `glide_commands_total{request_type}` - counter of the commands submitted per request type, e.g. `Get`, see [`get_command_counts`].
+///   It restarts from `0` once [`reset_command_counts`] is called, which Prometheus handles like a restart of the process.
+/// * `glide_command_latency_seconds` - histogram of the latencies of the commands, with buckets from 100 microseconds to 5 seconds,
+///   whose percentiles are computed by the scraper, e.g. by `histogram_quantile(0.99, ...)`. Batches aren't included.
+/// * `glide_redirections_total{kind}` - counter of the `moved` and `ask` redirections, like [`get_statistics`].
+///
+/// `node` is the `host:port` address of a node, so the per-node metrics have a series per node the client connected to,
+/// which keeps growing as nodes are replaced, e.g. hundreds of series in a large cluster after a few rollouts.
+/// `request_type` has a series per request type the application used. Drop or aggregate these labels by relabeling
+/// if the cardinality is a concern. Metrics of different clients have the same names, so they should be exposed with
+/// a label of the client, e.g. by the scraper. The returned value should be freed with [`free_respose`].
+///
+/// # Safety
+/// * `client_ptr` must not be `null`.
+/// * `client_ptr` must be a pointer created by [`create_client`]. Once [`close_client`] was called with the pointer,
+///   the response is an empty string.
+#[no_mangle]
+pub unsafe extern "C" fn get_metrics_prometheus(client_ptr: *const c_void) -> *mut ResponseValue {
+    unsafe {
+        client_response(client_ptr, Value::BulkString(vec![]), |client| {
+            Value::BulkString(metrics::prometheus_text(&client.core).into_bytes())
+        })
+    }
+}
+
+/// Reset the statistics returned by [`get_statistics`].
+///
+/// # Safety
+/// * `client_ptr` must not be `null`.
+/// * `client_ptr` must be a pointer created by [`create_client`]. Once [`close_client`] was called with the pointer,
+///   nothing is reset.
+#[no_mangle]
+pub unsafe extern "C" fn reset_statistics(client_ptr: *const c_void) {
+    if let Some(client) = unsafe { client_from_ptr(client_ptr) } {
+        client.core.redirections.reset();
+        client
+            .core
+            .dropped_slow_commands
+            .store(0, Ordering::Relaxed);
+    }
+}
+
+/// Convert an absolute deadline given in milliseconds since the unix epoch to a runtime [`Instant`].
+/// Returns `Ok(None)` if no deadline is given (`0`) and a timeout error if the deadline has already passed.
+pub(crate) fn deadline_to_instant(deadline_unix_ms: u64) -> RedisResult<Option<Instant>> {
+    if deadline_unix_ms == 0 {
+        return Ok(None);
+    }
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default();
+    match Duration::from_millis(deadline_unix_ms).checked_sub(now) {
+        Some(remaining) if !remaining.is_zero() => Ok(Some(Instant::now() + remaining)),
+        _ => Err(timeout_error()),
+    }
+}
+
+fn timeout_error() -> RedisError {
+    std::io::Error::from(std::io::ErrorKind::TimedOut).into()
 }
 
 /// Free the memory allocated for a [`ResponseValue`] and nested structure.
+/// If response pooling is enabled, the top-level value is returned to the pool instead of being freed.
 ///
 /// # Safety
 /// * `ptr` must not be `null`.
-/// * `ptr` must be able to be safely casted to a valid [`Box<ResponseValue>`] via [`Box::from_raw`]. See the safety documentation of [`Box::from_raw`].
+/// * `ptr` must be a response passed to the success callback or returned by [`get_effective_config`], which wasn't freed yet.
+/// * `ptr` must not be used after this function is called.
 #[allow(rustdoc::private_intra_doc_links)]
 #[no_mangle]
 pub unsafe extern "C" fn free_respose(ptr: *mut ResponseValue) {
     unsafe {
-        let val = Box::leak(Box::from_raw(ptr));
-        val.free_memory();
+        (*ptr).free_memory();
+        response_pool::release(ptr);
     }
 }
 
@@ -222,6 +3080,16 @@ pub unsafe extern "C" fn log(
     }
 }
 
+/// Flush the logs written so far to the console or to the log file, e.g. to keep the last error logs of a crashing process.
+/// [`close_client`] and [`close_client_graceful`] flush the logs as well. Flushing is idempotent.
+///
+/// The logger is global and independent of the clients, so logging keeps working after all clients are closed,
+/// including the logs of requests which are still completing after their client was closed.
+#[no_mangle]
+pub extern "C" fn flush_logs() {
+    logger_core::flush();
+}
+
 /// Unsafe function because creating string from pointer.
 ///
 /// # Safety
@@ -247,3 +3115,749 @@ pub unsafe extern "C" fn init(level: Option<Level>, file_name: *const c_char) ->
         logger_level.into()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::AtomicUsize;
+
+    use crate::{
+        ffi::{CmdInfo, ReadFromStrategy, RouteType, SlotType, ValueType},
+        test_utilities::{
+            cluster_slots, create_test_client, create_test_client_with_slow_command_callback,
+            has_result, next_callback_index, unknown_command, wait_for_result, MockServer,
+        },
+    };
+
+    /// Start a cluster of mock primaries, which reply to `GET` by `<port>:<key>` and to `ECHO` by their port,
+    /// and list only `GET` in the reply to `COMMAND`.
+    /// Returns the ports of the nodes and the amount of `ECHO` commands they received.
+    fn start_cluster(nodes: usize) -> (Vec<u16>, Arc<AtomicUsize>) {
+        let servers: Vec<_> = (0..nodes).map(|_| MockServer::bind()).collect();
+        let ports: Vec<u16> = servers.iter().map(MockServer::port).collect();
+        let echoes = Arc::new(AtomicUsize::new(0));
+        for server in servers {
+            let port = server.port();
+            let slots = cluster_slots(&ports);
+            let echoes = echoes.clone();
+            server.serve(Arc::new(move |args: &[Vec<u8>]| {
+                match args[0].to_ascii_uppercase().as_slice() {
+                    b"CLUSTER" => slots.clone(),
+                    b"GET" => Value::BulkString(
+                        format!("{port}:{}", String::from_utf8_lossy(&args[1])).into_bytes(),
+                    ),
+                    b"SET" => Value::Okay,
+                    // The legacy command info: name, arity, flags, first key, last key and step
+                    b"COMMAND" => Value::Array(vec![Value::Array(vec![
+                        Value::BulkString(b"get".to_vec()),
+                        Value::Int(2),
+                        Value::Array(vec![]),
+                        Value::Int(1),
+                        Value::Int(1),
+                        Value::Int(1),
+                    ])]),
+                    b"ECHO" => {
+                        echoes.fetch_add(1, Ordering::Relaxed);
+                        Value::BulkString(port.to_string().into_bytes())
+                    }
+                    _ => unknown_command(args),
+                }
+            }));
+        }
+        (ports, echoes)
+    }
+
+    /// Execute a non-atomic batch of the given commands, and wait for its result.
+    fn execute_batch(client: *const c_void, commands: &[(RequestType, &[&str])]) -> Value {
+        let args: Vec<Vec<*const u8>> = commands
+            .iter()
+            .map(|(_, args)| args.iter().map(|arg| arg.as_ptr()).collect())
+            .collect();
+        let args_len: Vec<Vec<u32>> = commands
+            .iter()
+            .map(|(_, args)| args.iter().map(|arg| arg.len() as u32).collect())
+            .collect();
+        let cmd_infos: Vec<CmdInfo> = commands
+            .iter()
+            .enumerate()
+            .map(|(index, (request_type, _))| CmdInfo {
+                request_type: *request_type,
+                args: args[index].as_ptr(),
+                arg_count: args[index].len() as u32,
+                args_len: args_len[index].as_ptr(),
+            })
+            .collect();
+        let cmds: Vec<*const CmdInfo> = cmd_infos.iter().map(|cmd| cmd as *const _).collect();
+        let batch_info = BatchInfo {
+            cmd_count: cmds.len() as u32,
+            cmds: cmds.as_ptr(),
+            is_atomic: false,
+        };
+        let callback_index = next_callback_index();
+        unsafe { batch(client, callback_index, &batch_info, true, std::ptr::null()) };
+        wait_for_result(callback_index).unwrap()
+    }
+
+    #[test]
+    fn test_batch_replies_in_the_order_of_the_commands() {
+        let (ports, _) = start_cluster(2);
+        let client = create_test_client(&ports, true, |_| {});
+        // "bar" and "baz" map to the first node, "foo" to the second one
+        let reply = execute_batch(
+            client,
+            &[
+                (RequestType::Get, &["foo"]),
+                (RequestType::Get, &["bar"]),
+                (RequestType::Set, &["foo", "value"]),
+                (RequestType::Get, &["baz"]),
+                (RequestType::Get, &["foo"]),
+            ],
+        );
+        let get = |port: u16, key: &str| Value::BulkString(format!("{port}:{key}").into_bytes());
+        assert_eq!(
+            reply,
+            Value::Array(vec![
+                get(ports[1], "foo"),
+                get(ports[0], "bar"),
+                Value::Okay,
+                get(ports[0], "baz"),
+                get(ports[1], "foo"),
+            ])
+        );
+        close_client(client);
+    }
+
+    #[test]
+    fn test_batch_sends_keyless_commands_to_a_single_node() {
+        let (ports, echoes) = start_cluster(3);
+        let client = create_test_client(&ports, true, |_| {});
+        let reply = execute_batch(
+            client,
+            &[
+                (RequestType::Get, &["bar"]),
+                (RequestType::Echo, &["hello"]),
+                (RequestType::Get, &["foo"]),
+            ],
+        );
+        let Value::Array(replies) = reply else {
+            panic!("Expected an array, got {reply:?}");
+        };
+        assert_eq!(replies.len(), 3);
+        // A keyless command is sent to a single node of the cluster, rather than fanned out to all of them
+        assert_eq!(echoes.load(Ordering::Relaxed), 1);
+        let Value::BulkString(port) = &replies[1] else {
+            panic!("Expected the port of a node, got {:?}", replies[1]);
+        };
+        let port: u16 = String::from_utf8_lossy(port).parse().unwrap();
+        assert!(ports.contains(&port));
+        assert_eq!(
+            replies[2],
+            Value::BulkString(format!("{}:foo", ports[2]).into_bytes())
+        );
+        close_client(client);
+    }
+
+    #[test]
+    fn test_command_fails_after_the_client_was_closed_and_deallocated() {
+        let (ports, _) = start_cluster(1);
+        let client = create_test_client(&ports, true, |_| {});
+        let weak_client = Arc::downgrade(&unsafe { client_from_ptr(client) }.unwrap());
+        close_client(client);
+        // Nothing was in flight, so the client was deallocated right away
+        assert!(weak_client.upgrade().is_none());
+
+        let args = [b"foo".as_ptr() as *mut c_char];
+        let args_len = [3];
+        let callback_index = next_callback_index();
+        unsafe {
+            command(
+                client,
+                callback_index,
+                RequestType::Get,
+                args.as_ptr(),
+                1,
+                args_len.as_ptr(),
+                std::ptr::null(),
+                0,
+                false,
+                false,
+                std::ptr::null(),
+                0,
+                false,
+            )
+        };
+        assert!(matches!(
+            wait_for_result(callback_index),
+            Err((RequestErrorType::ClientClosed, _))
+        ));
+        // Closing the client again is ignored
+        close_client(client);
+    }
+
+    #[test]
+    fn test_keyless_commands_are_not_prefixed() {
+        let (ports, _) = start_cluster(1);
+        let key_prefix = CString::new("tenant:").unwrap();
+        let client = create_test_client(&ports, true, |config| {
+            config.key_prefix = key_prefix.as_ptr();
+        });
+        let route_info = RouteInfo {
+            route_type: RouteType::Random,
+            slot_id: 0,
+            slot_key: std::ptr::null(),
+            slot_type: SlotType::Primary,
+            hostname: std::ptr::null(),
+            port: 0,
+        };
+        let args = [b"foo".as_ptr() as *mut c_char];
+        let args_len = [3];
+        // A keyless command is sent as is, even if it has keys, since the caller asserted that it has none
+        for (keyless, key) in [(true, "foo"), (false, "tenant:foo")] {
+            let callback_index = next_callback_index();
+            unsafe {
+                command(
+                    client,
+                    callback_index,
+                    RequestType::Get,
+                    args.as_ptr(),
+                    1,
+                    args_len.as_ptr(),
+                    &route_info,
+                    0,
+                    false,
+                    keyless,
+                    std::ptr::null(),
+                    0,
+                    false,
+                )
+            };
+            assert_eq!(
+                wait_for_result(callback_index).unwrap(),
+                Value::BulkString(format!("{}:{key}", ports[0]).into_bytes())
+            );
+        }
+        close_client(client);
+    }
+
+    #[test]
+    fn test_getters_of_a_closed_client_are_empty() {
+        let (ports, _) = start_cluster(1);
+        let client = create_test_client(&ports, true, |_| {});
+        // The reference keeps the client allocated, so no other client is allocated at its address
+        let _allocated = unsafe { client_from_ptr(client) }.unwrap();
+        close_client(client);
+
+        let empty = |response: *mut ResponseValue, typ: ValueType| {
+            let (response_typ, size) = unsafe { ((*response).typ.clone(), (*response).size) };
+            unsafe { free_respose(response) };
+            assert_eq!(format!("{response_typ:?}"), format!("{typ:?}"));
+            assert_eq!(size, 0);
+        };
+        unsafe {
+            empty(get_effective_config(client), ValueType::Map);
+            empty(get_command_trace(client), ValueType::Array);
+            empty(get_command_counts(client), ValueType::Map);
+            empty(get_connection_timings(client), ValueType::Map);
+            empty(
+                get_server_capabilities(client, std::ptr::null()),
+                ValueType::Map,
+            );
+            empty(get_protocol_versions(client), ValueType::Map);
+            empty(get_metrics_prometheus(client), ValueType::BulkString);
+            let subscriptions = get_subscriptions(client);
+            assert!(matches!((*subscriptions).typ, ValueType::Map));
+            free_respose(subscriptions);
+            let statistics = get_node_inflight_statistics(client);
+            assert_eq!((*statistics).node_count, 0);
+            free_node_inflight_statistics(statistics);
+            assert_eq!(get_statistics(client).pending_commands, 0);
+            reset_command_counts(client);
+            reset_statistics(client);
+        }
+    }
+
+    static SLOW_COMMANDS_RELEASED: AtomicBool = AtomicBool::new(false);
+
+    unsafe extern "C" fn blocking_slow_command_callback(
+        _: *const c_char,
+        _: u64,
+        _: *const c_char,
+    ) {
+        while !SLOW_COMMANDS_RELEASED.load(Ordering::Acquire) {
+            std::thread::sleep(Duration::from_millis(1));
+        }
+    }
+
+    #[test]
+    fn test_slow_commands_are_dropped_once_the_callback_falls_behind() {
+        let server = MockServer::bind();
+        let port = server.port();
+        server.serve(Arc::new(|args: &[Vec<u8>]| {
+            match args[0].to_ascii_uppercase().as_slice() {
+                b"INFO" => Value::BulkString(b"role:master".to_vec()),
+                b"GET" => {
+                    std::thread::sleep(Duration::from_millis(2));
+                    Value::Nil
+                }
+                _ => unknown_command(args),
+            }
+        }));
+        let client = create_test_client_with_slow_command_callback(
+            &[port],
+            false,
+            |config| {
+                config.slow_command_threshold = 1;
+                // The commands wait for each other at the server
+                config.has_request_timeout = true;
+                config.request_timeout = 60_000;
+            },
+            Some(blocking_slow_command_callback),
+        );
+        let args = [b"foo".as_ptr() as *mut c_char];
+        let args_len = [3];
+        // The first slow command blocks the callback, so the rest fill the queue, and the last ones are dropped
+        let callback_indices: Vec<usize> = (0..SLOW_COMMANDS_CAPACITY + 20)
+            .map(|_| {
+                let callback_index = next_callback_index();
+                unsafe {
+                    command(
+                        client,
+                        callback_index,
+                        RequestType::Get,
+                        args.as_ptr(),
+                        1,
+                        args_len.as_ptr(),
+                        std::ptr::null(),
+                        0,
+                        false,
+                        false,
+                        std::ptr::null(),
+                        0,
+                        false,
+                    )
+                };
+                callback_index
+            })
+            .collect();
+        for callback_index in callback_indices {
+            assert_eq!(wait_for_result(callback_index), Ok(Value::Nil));
+        }
+        let dropped = unsafe { get_statistics(client) }.dropped_slow_commands;
+        SLOW_COMMANDS_RELEASED.store(true, Ordering::Release);
+        assert!(dropped > 0, "No slow commands were dropped");
+        unsafe { reset_statistics(client) };
+        assert_eq!(unsafe { get_statistics(client) }.dropped_slow_commands, 0);
+        close_client(client);
+    }
+
+    /// Submit a command without a route, and return its callback index.
+    fn submit_command(
+        client: *const c_void,
+        request_type: RequestType,
+        args: &[&str],
+        fire_and_forget: bool,
+        raw_no_retry_no_wait: bool,
+    ) -> usize {
+        let arg_ptrs: Vec<*mut c_char> =
+            args.iter().map(|arg| arg.as_ptr() as *mut c_char).collect();
+        let args_len: Vec<u32> = args.iter().map(|arg| arg.len() as u32).collect();
+        let callback_index = next_callback_index();
+        unsafe {
+            command(
+                client,
+                callback_index,
+                request_type,
+                arg_ptrs.as_ptr(),
+                args.len() as u32,
+                args_len.as_ptr(),
+                std::ptr::null(),
+                0,
+                fire_and_forget,
+                false,
+                std::ptr::null(),
+                0,
+                raw_no_retry_no_wait,
+            )
+        };
+        callback_index
+    }
+
+    #[test]
+    fn test_replies_of_fire_and_forget_commands_are_drained() {
+        let server = MockServer::bind();
+        let increments = Arc::new(AtomicUsize::new(0));
+        let server_increments = increments.clone();
+        let port = server.port();
+        server.serve(Arc::new(move |args: &[Vec<u8>]| {
+            match args[0].to_ascii_uppercase().as_slice() {
+                b"INFO" => Value::BulkString(b"role:master".to_vec()),
+                b"INCR" => Value::Int(server_increments.fetch_add(1, Ordering::Relaxed) as i64 + 1),
+                b"GET" => Value::BulkString(b"value".to_vec()),
+                _ => unknown_command(args),
+            }
+        }));
+        let client = create_test_client(&[port], false, |_| {});
+        let incr = submit_command(client, RequestType::Incr, &["counter"], true, false);
+        let get = submit_command(client, RequestType::Get, &["key"], false, false);
+        // The reply of INCR was read from the connection, so GET received its own reply rather than the counter
+        assert_eq!(
+            wait_for_result(get),
+            Ok(Value::BulkString(b"value".to_vec()))
+        );
+        assert_eq!(increments.load(Ordering::Relaxed), 1);
+        assert!(!has_result(incr));
+        close_client(client);
+    }
+
+    /// Start mock primaries which can be taken down, see [`MockServer::serve_with_outage`], and reply to `GET` by `value`.
+    /// Returns the ports of the nodes and the flags taking them down.
+    fn start_nodes_with_outage(nodes: usize) -> (Vec<u16>, Vec<Arc<AtomicBool>>) {
+        let servers: Vec<_> = (0..nodes).map(|_| MockServer::bind()).collect();
+        let ports: Vec<u16> = servers.iter().map(MockServer::port).collect();
+        let downs: Vec<_> = ports
+            .iter()
+            .map(|_| Arc::new(AtomicBool::new(false)))
+            .collect();
+        for (server, down) in servers.into_iter().zip(&downs) {
+            let slots = cluster_slots(&ports);
+            server.serve_with_outage(
+                Arc::new(
+                    move |args: &[Vec<u8>]| match args[0].to_ascii_uppercase().as_slice() {
+                        b"INFO" => Value::BulkString(b"role:master".to_vec()),
+                        b"CLUSTER" => slots.clone(),
+                        b"GET" => Value::BulkString(b"value".to_vec()),
+                        _ => unknown_command(args),
+                    },
+                ),
+                down.clone(),
+            );
+        }
+        (ports, downs)
+    }
+
+    #[test]
+    fn test_raw_no_retry_no_wait_commands_fail_right_away_on_a_down_node() {
+        let value = Ok(Value::BulkString(b"value".to_vec()));
+        let (ports, downs) = start_nodes_with_outage(1);
+        let client = create_test_client(&ports, false, |config| {
+            // Long enough for the normal command to wait for the node to be up again
+            config.has_request_timeout = true;
+            config.request_timeout = 10_000;
+        });
+        let get = submit_command(client, RequestType::Get, &["foo"], false, false);
+        assert_eq!(wait_for_result(get), value);
+
+        downs[0].store(true, Ordering::Release);
+        std::thread::sleep(Duration::from_millis(100));
+        let started = Instant::now();
+        let probe = submit_command(client, RequestType::Get, &["foo"], false, true);
+        assert!(wait_for_result(probe).is_err());
+        assert!(started.elapsed() < Duration::from_millis(500));
+
+        // A normal command waits for the node to be up again, rather than failing
+        let get = submit_command(client, RequestType::Get, &["foo"], false, false);
+        std::thread::sleep(Duration::from_millis(500));
+        assert!(!has_result(get));
+        downs[0].store(false, Ordering::Release);
+        assert_eq!(wait_for_result(get), value);
+        close_client(client);
+    }
+
+    #[test]
+    fn test_raw_no_retry_no_wait_commands_fail_right_away_on_a_down_cluster_node() {
+        let value = Ok(Value::BulkString(b"value".to_vec()));
+        let (ports, downs) = start_nodes_with_outage(2);
+        let client = create_test_client(&ports, true, |config| {
+            config.has_request_timeout = true;
+            config.request_timeout = 10_000;
+        });
+        // The slot of `foo` is served by the second node
+        let get = submit_command(client, RequestType::Get, &["foo"], false, false);
+        assert_eq!(wait_for_result(get), value);
+
+        downs[1].store(true, Ordering::Release);
+        std::thread::sleep(Duration::from_millis(100));
+        let started = Instant::now();
+        let probe = submit_command(client, RequestType::Get, &["foo"], false, true);
+        assert!(wait_for_result(probe).is_err());
+        assert!(started.elapsed() < Duration::from_millis(500));
+
+        // A normal command is retried, until the node is up again or it's redirected to another node
+        let get = submit_command(client, RequestType::Get, &["foo"], false, false);
+        std::thread::sleep(Duration::from_millis(200));
+        downs[1].store(false, Ordering::Release);
+        assert_eq!(wait_for_result(get), value);
+        close_client(client);
+    }
+
+    #[test]
+    fn test_reads_from_replicas_fall_back_to_the_primary_of_the_slot() {
+        let slot_route = |slot_addr| {
+            RoutingInfo::SingleNode(SingleNodeRoutingInfo::SpecificNode(Route::new(
+                12182, slot_addr,
+            )))
+        };
+        let mut get = redis::cmd("GET");
+        get.arg("foo");
+        let mut set = redis::cmd("SET");
+        set.arg("foo").arg("bar");
+
+        for slot_addr in [SlotAddr::ReplicaOptional, SlotAddr::ReplicaRequired] {
+            assert_eq!(
+                primary_fallback_route(&get, Some(&slot_route(slot_addr)), false),
+                Some(slot_route(SlotAddr::Master))
+            );
+        }
+        // A read which was already served by the primary isn't retried
+        assert_eq!(
+            primary_fallback_route(&get, Some(&slot_route(SlotAddr::Master)), true),
+            None
+        );
+        // Writes are never sent to replicas, so they don't fall back
+        assert_eq!(
+            primary_fallback_route(&set, Some(&slot_route(SlotAddr::ReplicaOptional)), true),
+            None
+        );
+        assert_eq!(
+            primary_fallback_route(
+                &get,
+                Some(&RoutingInfo::SingleNode(SingleNodeRoutingInfo::Random)),
+                true
+            ),
+            None
+        );
+        // Reads without a route are sent to replicas by glide-core only if the client reads from replicas
+        assert_eq!(
+            primary_fallback_route(&get, None, true),
+            Some(slot_route(SlotAddr::Master))
+        );
+        assert_eq!(primary_fallback_route(&get, None, false), None);
+    }
+
+    #[test]
+    fn test_read_falls_back_to_the_primary_once_the_replica_died_while_serving_it() {
+        for fallback_to_primary_on_replica_error in [true, false] {
+            let primary = MockServer::bind();
+            let replica = MockServer::bind();
+            let node = |port: u16| {
+                Value::Array(vec![
+                    Value::BulkString(b"127.0.0.1".to_vec()),
+                    Value::Int(port.into()),
+                    Value::BulkString(format!("node{port}").into_bytes()),
+                ])
+            };
+            let slots = Value::Array(vec![Value::Array(vec![
+                Value::Int(0),
+                Value::Int(16383),
+                node(primary.port()),
+                node(replica.port()),
+            ])]);
+            let handler = |slots: Value, get: Arc<dyn Fn() -> Value + Send + Sync>| {
+                Arc::new(
+                    move |args: &[Vec<u8>]| match args[0].to_ascii_uppercase().as_slice() {
+                        b"CLUSTER" => slots.clone(),
+                        b"GET" => get(),
+                        _ => unknown_command(args),
+                    },
+                )
+            };
+            let addresses = [primary.port()];
+            primary.serve(handler(
+                slots.clone(),
+                Arc::new(|| Value::BulkString(b"primary".to_vec())),
+            ));
+            let replica_down = Arc::new(AtomicBool::new(false));
+            let killed_replica = replica_down.clone();
+            replica.serve_with_outage(
+                handler(
+                    slots,
+                    Arc::new(move || {
+                        killed_replica.store(true, Ordering::Release);
+                        Value::Nil
+                    }),
+                ),
+                replica_down,
+            );
+            let client = create_test_client(&addresses, true, |config| {
+                config.has_read_from = true;
+                config.read_from.strategy = ReadFromStrategy::PreferReplica;
+                config.fallback_to_primary_on_replica_error = fallback_to_primary_on_replica_error;
+            });
+            let get = submit_command(client, RequestType::Get, &["foo"], false, false);
+            match fallback_to_primary_on_replica_error {
+                true => assert_eq!(
+                    wait_for_result(get),
+                    Ok(Value::BulkString(b"primary".to_vec()))
+                ),
+                false => assert!(wait_for_result(get).is_err()),
+            }
+            close_client(client);
+        }
+    }
+
+    #[test]
+    fn test_disconnected_replica_reconnects_once_reads_are_routed_to_it() {
+        let primary = MockServer::bind();
+        let replica = MockServer::bind();
+        let replica_port = replica.port();
+        let node = |port: u16| {
+            Value::Array(vec![
+                Value::BulkString(b"127.0.0.1".to_vec()),
+                Value::Int(port.into()),
+                Value::BulkString(format!("node{port}").into_bytes()),
+            ])
+        };
+        let slots = Value::Array(vec![Value::Array(vec![
+            Value::Int(0),
+            Value::Int(16383),
+            node(primary.port()),
+            node(replica_port),
+        ])]);
+        let handler = |slots: Value, get: &'static [u8]| {
+            Arc::new(
+                move |args: &[Vec<u8>]| match args[0].to_ascii_uppercase().as_slice() {
+                    b"CLUSTER" => slots.clone(),
+                    b"GET" => Value::BulkString(get.to_vec()),
+                    _ => unknown_command(args),
+                },
+            )
+        };
+        let addresses = [primary.port()];
+        primary.serve(handler(slots.clone(), b"primary"));
+        let replica_down = Arc::new(AtomicBool::new(false));
+        let replica_connections =
+            replica.serve_with_outage(handler(slots, b"replica"), replica_down.clone());
+        let client = create_test_client(&addresses, true, |config| {
+            config.has_read_from = true;
+            config.read_from.strategy = ReadFromStrategy::PreferReplica;
+        });
+        let get = submit_command(client, RequestType::Get, &["foo"], false, false);
+        assert_eq!(
+            wait_for_result(get),
+            Ok(Value::BulkString(b"replica".to_vec()))
+        );
+        let closed = replica_connections.closed.load(Ordering::Relaxed);
+
+        let host = CString::new("127.0.0.1").unwrap();
+        let disconnect = next_callback_index();
+        unsafe { disconnect_node(client, disconnect, host.as_ptr(), replica_port) };
+        assert!(matches!(wait_for_result(disconnect), Ok(Value::Okay)));
+        let disconnected = Instant::now();
+        while replica_connections.closed.load(Ordering::Relaxed) == closed {
+            assert!(disconnected.elapsed() < Duration::from_secs(10));
+            std::thread::sleep(Duration::from_millis(10));
+        }
+        // The replica is being removed, so it can't be reconnected
+        replica_down.store(true, Ordering::Release);
+        for _ in 0..3 {
+            let get = submit_command(client, RequestType::Get, &["foo"], false, false);
+            assert_eq!(
+                wait_for_result(get),
+                Ok(Value::BulkString(b"primary".to_vec()))
+            );
+        }
+
+        // Once the replica is back, reads are routed to it again over a new connection
+        replica_down.store(false, Ordering::Release);
+        let reconnected = Instant::now();
+        loop {
+            let get = submit_command(client, RequestType::Get, &["foo"], false, false);
+            if wait_for_result(get) == Ok(Value::BulkString(b"replica".to_vec())) {
+                break;
+            }
+            assert!(reconnected.elapsed() < Duration::from_secs(10));
+            std::thread::sleep(Duration::from_millis(50));
+        }
+        close_client(client);
+    }
+
+    #[test]
+    fn test_keepalive_prevents_idle_connections_from_being_closed() {
+        for (keepalive_secs, kept_alive) in [(1, true), (0, false)] {
+            let server = MockServer::bind();
+            let port = server.port();
+            // A proxy closing connections which were idle for longer than the keepalive interval
+            let connections = server.serve_with_idle_timeout(
+                Arc::new(
+                    |args: &[Vec<u8>]| match args[0].to_ascii_uppercase().as_slice() {
+                        b"INFO" => Value::BulkString(b"role:master".to_vec()),
+                        b"GET" => Value::BulkString(b"value".to_vec()),
+                        _ => unknown_command(args),
+                    },
+                ),
+                Duration::from_millis(1500),
+            );
+            let client = create_test_client(&[port], false, |config| {
+                config.client_keepalive_interval_secs = keepalive_secs;
+            });
+            let get = submit_command(client, RequestType::Get, &["key"], false, false);
+            assert_eq!(
+                wait_for_result(get),
+                Ok(Value::BulkString(b"value".to_vec()))
+            );
+            let connected = connections.accepted.load(Ordering::Relaxed);
+
+            std::thread::sleep(Duration::from_millis(3500));
+            let get = submit_command(client, RequestType::Get, &["key"], false, false);
+            assert_eq!(
+                wait_for_result(get),
+                Ok(Value::BulkString(b"value".to_vec()))
+            );
+            // Without the keepalive, the connection was closed by the proxy, and had to be established again
+            assert_eq!(
+                connections.accepted.load(Ordering::Relaxed) == connected,
+                kept_alive,
+                "keepalive: {keepalive_secs}s"
+            );
+            close_client(client);
+        }
+    }
+
+    #[test]
+    fn test_periodic_topology_refresh_is_spawned_only_if_configured() {
+        let (ports, _) = start_cluster(1);
+        for (secs, spawned) in [(0, false), (1, true)] {
+            let client = create_test_client(&ports, true, |config| {
+                config.periodic_topology_refresh_secs = secs;
+            });
+            let topology_refresh = &unsafe { client_from_ptr(client) }.unwrap().topology_refresh;
+            assert_eq!(topology_refresh.lock().unwrap().is_some(), spawned);
+            close_client(client);
+        }
+    }
+
+    #[test]
+    fn test_topology_is_copied_from_the_slot_map_of_glide_core() {
+        let servers: Vec<_> = (0..2).map(|_| MockServer::bind()).collect();
+        let ports: Vec<u16> = servers.iter().map(MockServer::port).collect();
+        let cluster_commands = Arc::new(AtomicUsize::new(0));
+        for server in servers {
+            let slots = cluster_slots(&ports);
+            let cluster_commands = cluster_commands.clone();
+            server.serve(Arc::new(move |args: &[Vec<u8>]| {
+                match args[0].to_ascii_uppercase().as_slice() {
+                    b"CLUSTER" => {
+                        cluster_commands.fetch_add(1, Ordering::Relaxed);
+                        slots.clone()
+                    }
+                    _ => unknown_command(args),
+                }
+            }));
+        }
+        let client = create_test_client(&ports, true, |_| {});
+        let sent_on_connect = cluster_commands.load(Ordering::Relaxed);
+        let core = unsafe { client_from_ptr(client) }.unwrap().core.clone();
+        let started = Instant::now();
+        while core.topology.read().unwrap().ranges.is_empty() {
+            assert!(started.elapsed() < Duration::from_secs(10));
+            std::thread::sleep(Duration::from_millis(10));
+        }
+        let expected: Vec<String> = ports
+            .iter()
+            .map(|port| format!("127.0.0.1:{port}"))
+            .collect();
+        assert_eq!(core.topology.read().unwrap().primaries(), expected);
+        assert_eq!(cluster_commands.load(Ordering::Relaxed), sent_on_connect);
+        close_client(client);
+    }
+}
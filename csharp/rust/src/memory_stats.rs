@@ -0,0 +1,150 @@
+// Copyright Valkey GLIDE Project Contributors - SPDX Identifier: Apache-2.0
+
+use redis::{ErrorKind, RedisError, RedisResult, Value};
+
+use crate::info::{bulk, typed_value};
+
+fn unexpected_response(value: &Value) -> RedisError {
+    RedisError::from((
+        ErrorKind::TypeError,
+        "Unexpected MEMORY STATS response",
+        format!("{value:?}"),
+    ))
+}
+
+fn field_name(field: Value) -> RedisResult<Value> {
+    match field {
+        Value::BulkString(_) => Ok(field),
+        Value::SimpleString(field) => Ok(bulk(&field)),
+        field => Err(unexpected_response(&field)),
+    }
+}
+
+/// Type a value of `MEMORY STATS`: numbers sent as strings, like `dataset.percentage` in RESP2, are converted to integers
+/// or doubles, and nested statistics, like the overheads of `db.0`, are converted to maps.
+fn memory_stat(value: Value) -> RedisResult<Value> {
+    match value {
+        Value::BulkString(ref text) => match std::str::from_utf8(text) {
+            Ok(text) => Ok(typed_value(text)),
+            Err(_) => Ok(value),
+        },
+        Value::SimpleString(text) => Ok(typed_value(&text)),
+        Value::Array(_) | Value::Map(_) => memory_stats_map(value),
+        value => Ok(value),
+    }
+}
+
+/// Convert the statistics of a node, a flat array of alternating field names and values in RESP2, or a map in RESP3,
+/// to a map of field names to typed values.
+fn memory_stats_map(value: Value) -> RedisResult<Value> {
+    let fields = match value {
+        Value::Map(fields) => fields,
+        Value::Array(items) if items.len() % 2 == 0 => {
+            let mut items = items.into_iter();
+            let mut fields = Vec::new();
+            while let (Some(field), Some(value)) = (items.next(), items.next()) {
+                fields.push((field, value));
+            }
+            fields
+        }
+        value => return Err(unexpected_response(&value)),
+    };
+    fields
+        .into_iter()
+        .map(|(field, value)| Ok((field_name(field)?, memory_stat(value)?)))
+        .collect::<RedisResult<_>>()
+        .map(Value::Map)
+}
+
+/// Convert a `MEMORY STATS` response of a single node to a map of field names, e.g. `total.allocated`, to integers or doubles,
+/// where the statistics of every database, e.g. `db.0`, are nested maps. Fields which aren't numbers are kept as strings.
+/// A response of multiple nodes, which is a map of node addresses to responses, is converted to a map of node addresses
+/// to such maps if `multiple_nodes` is set, since the response of a single node is a map too in RESP3.
+pub(crate) fn convert_memory_stats(value: Value, multiple_nodes: bool) -> RedisResult<Value> {
+    if !multiple_nodes {
+        return memory_stats_map(value);
+    }
+    match value {
+        Value::Map(nodes) => nodes
+            .into_iter()
+            .map(|(node, stats)| Ok((node, memory_stats_map(stats)?)))
+            .collect::<RedisResult<_>>()
+            .map(Value::Map),
+        value => Err(unexpected_response(&value)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn map(entries: Vec<(&str, Value)>) -> Value {
+        Value::Map(
+            entries
+                .into_iter()
+                .map(|(key, value)| (bulk(key), value))
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn test_convert_resp2_memory_stats() {
+        let stats = Value::Array(vec![
+            bulk("peak.allocated"),
+            Value::Int(1049088),
+            bulk("total.allocated"),
+            Value::Int(972352),
+            bulk("db.0"),
+            Value::Array(vec![
+                bulk("overhead.hashtable.main"),
+                Value::Int(72),
+                bulk("overhead.hashtable.expires"),
+                Value::Int(0),
+            ]),
+            bulk("dataset.percentage"),
+            bulk("12.5"),
+            bulk("allocator.fragmentation.ratio"),
+            bulk("1.0625"),
+        ]);
+        assert_eq!(
+            convert_memory_stats(stats, false).unwrap(),
+            map(vec![
+                ("peak.allocated", Value::Int(1049088)),
+                ("total.allocated", Value::Int(972352)),
+                (
+                    "db.0",
+                    map(vec![
+                        ("overhead.hashtable.main", Value::Int(72)),
+                        ("overhead.hashtable.expires", Value::Int(0)),
+                    ])
+                ),
+                ("dataset.percentage", Value::Double(12.5)),
+                ("allocator.fragmentation.ratio", Value::Double(1.0625)),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_convert_memory_stats_of_multiple_nodes() {
+        let stats = Value::Map(vec![
+            (bulk("total.allocated"), Value::Int(972352)),
+            (bulk("fragmentation"), Value::Double(1.5)),
+        ]);
+        let value = Value::Map(vec![
+            (bulk("node1:6379"), stats.clone()),
+            (bulk("node2:6379"), stats),
+        ]);
+        let expected = map(vec![
+            ("total.allocated", Value::Int(972352)),
+            ("fragmentation", Value::Double(1.5)),
+        ]);
+        assert_eq!(
+            convert_memory_stats(value, true).unwrap(),
+            map(vec![
+                ("node1:6379", expected.clone()),
+                ("node2:6379", expected),
+            ])
+        );
+        assert!(convert_memory_stats(Value::Array(vec![bulk("total.allocated")]), false).is_err());
+    }
+}
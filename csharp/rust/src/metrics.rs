@@ -0,0 +1,219 @@
+// Copyright Valkey GLIDE Project Contributors - SPDX Identifier: Apache-2.0
+
+//! Metrics of the client in the Prometheus text exposition format, see [`get_metrics_prometheus`](crate::get_metrics_prometheus).
+
+use std::{
+    fmt::{Display, Write},
+    sync::atomic::{AtomicU64, Ordering},
+    time::Duration,
+};
+
+use crate::CommandExecutionCore;
+
+/// Upper bounds of the buckets of [`LatencyHistogram`] in microseconds, from 100 microseconds to 5 seconds.
+const LATENCY_BUCKETS_US: [u64; 15] = [
+    100, 250, 500, 1_000, 2_500, 5_000, 10_000, 25_000, 50_000, 100_000, 250_000, 500_000,
+    1_000_000, 2_500_000, 5_000_000,
+];
+
+/// Histogram of the latencies of the commands dispatched by the client, from which percentiles are computed by the scraper,
+/// e.g. by `histogram_quantile` in Prometheus. Recording takes two atomic increments, so every command is recorded.
+#[derive(Debug, Default)]
+pub(crate) struct LatencyHistogram {
+    /// Commands per bucket of [`LATENCY_BUCKETS_US`], with the commands above the last bound in the last one.
+    buckets: [AtomicU64; LATENCY_BUCKETS_US.len() + 1],
+    sum_us: AtomicU64,
+}
+
+impl LatencyHistogram {
+    pub(crate) fn record(&self, latency: Duration) {
+        let latency_us = latency.as_micros().min(u64::MAX.into()) as u64;
+        let bucket = LATENCY_BUCKETS_US.partition_point(|&bound| bound < latency_us);
+        self.buckets[bucket].fetch_add(1, Ordering::Relaxed);
+        self.sum_us.fetch_add(latency_us, Ordering::Relaxed);
+    }
+
+    /// Get the cumulative counts of the buckets, by their upper bounds in seconds, `None` for `+Inf`,
+    /// and the sum of the latencies in seconds.
+    fn cumulative_buckets(&self) -> (Vec<(Option<f64>, u64)>, f64) {
+        let mut count = 0;
+        let buckets = self
+            .buckets
+            .iter()
+            .enumerate()
+            .map(|(index, bucket)| {
+                count += bucket.load(Ordering::Relaxed);
+                let bound = LATENCY_BUCKETS_US
+                    .get(index)
+                    .map(|&bound| bound as f64 / 1e6);
+                (bound, count)
+            })
+            .collect();
+        (buckets, self.sum_us.load(Ordering::Relaxed) as f64 / 1e6)
+    }
+}
+
+/// Escape a label value, see the Prometheus text exposition format.
+fn escape_label(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+/// Builder of a text in the Prometheus exposition format.
+#[derive(Default)]
+struct Exposition(String);
+
+impl Exposition {
+    fn metric(&mut self, name: &str, kind: &str, help: &str) {
+        let _ = writeln!(self.0, "# HELP {name} {help}");
+        let _ = writeln!(self.0, "# TYPE {name} {kind}");
+    }
+
+    fn sample(&mut self, name: &str, labels: &[(&str, &str)], value: impl Display) {
+        self.0.push_str(name);
+        if !labels.is_empty() {
+            let labels: Vec<String> = labels
+                .iter()
+                .map(|(label, value)| format!("{label}=\"{}\"", escape_label(value)))
+                .collect();
+            let _ = write!(self.0, "{{{}}}", labels.join(","));
+        }
+        let _ = writeln!(self.0, " {value}");
+    }
+}
+
+/// Render the metrics of the client, see [`get_metrics_prometheus`](crate::get_metrics_prometheus) for their names.
+pub(crate) fn prometheus_text(core: &CommandExecutionCore) -> String {
+    let mut exposition = Exposition::default();
+
+    let nodes = core.connection_timings.connection_counts();
+    exposition.metric(
+        "glide_open_connections",
+        "gauge",
+        "Connections to the node which are currently open.",
+    );
+    for (node, open_connections, _) in &nodes {
+        exposition.sample(
+            "glide_open_connections",
+            &[("node", node)],
+            open_connections,
+        );
+    }
+    exposition.metric(
+        "glide_connection_failures_total",
+        "counter",
+        "Attempts to connect to the node which failed, including reconnections.",
+    );
+    for (node, _, failed_attempts) in &nodes {
+        exposition.sample(
+            "glide_connection_failures_total",
+            &[("node", node)],
+            failed_attempts,
+        );
+    }
+
+    exposition.metric(
+        "glide_inflight_requests",
+        "gauge",
+        "Requests sent to the node which weren't answered yet.",
+    );
+    for (node, depth) in core.node_inflight.depths() {
+        exposition.sample("glide_inflight_requests", &[("node", &node)], depth);
+    }
+    exposition.metric(
+        "glide_pending_commands",
+        "gauge",
+        "Commands and batches which were submitted and whose callback wasn't called yet.",
+    );
+    exposition.sample("glide_pending_commands", &[], core.pending_tasks.count());
+
+    exposition.metric(
+        "glide_commands_total",
+        "counter",
+        "Commands submitted per request type.",
+    );
+    for (request_type, count) in core.command_counts.named_counts() {
+        exposition.sample(
+            "glide_commands_total",
+            &[("request_type", &request_type)],
+            count,
+        );
+    }
+
+    exposition.metric(
+        "glide_command_latency_seconds",
+        "histogram",
+        "Latency of the commands dispatched by the client, from sending them to receiving their replies.",
+    );
+    let (buckets, sum) = core.command_latency.cumulative_buckets();
+    let count = buckets.last().map_or(0, |(_, count)| *count);
+    for (bound, count) in buckets {
+        let bound = bound.map_or_else(|| "+Inf".to_string(), |bound| bound.to_string());
+        exposition.sample(
+            "glide_command_latency_seconds_bucket",
+            &[("le", &bound)],
+            count,
+        );
+    }
+    exposition.sample("glide_command_latency_seconds_sum", &[], sum);
+    exposition.sample("glide_command_latency_seconds_count", &[], count);
+
+    let (moved_redirections, ask_redirections) = core.redirections.counts();
+    exposition.metric(
+        "glide_redirections_total",
+        "counter",
+        "MOVED and ASK redirections handled by the client in cluster mode.",
+    );
+    exposition.sample(
+        "glide_redirections_total",
+        &[("kind", "moved")],
+        moved_redirections,
+    );
+    exposition.sample(
+        "glide_redirections_total",
+        &[("kind", "ask")],
+        ask_redirections,
+    );
+
+    exposition.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_latency_buckets_are_cumulative() {
+        let histogram = LatencyHistogram::default();
+        histogram.record(Duration::from_micros(100));
+        histogram.record(Duration::from_micros(101));
+        histogram.record(Duration::from_millis(3));
+        histogram.record(Duration::from_secs(10));
+        let (buckets, sum) = histogram.cumulative_buckets();
+        assert_eq!(buckets[0], (Some(0.0001), 1));
+        assert_eq!(buckets[1], (Some(0.00025), 2));
+        assert_eq!(buckets[5], (Some(0.005), 3));
+        assert_eq!(buckets[14], (Some(5.0), 3));
+        assert_eq!(buckets[15], (None, 4));
+        assert_eq!(sum, 10.003201);
+    }
+
+    #[test]
+    fn test_exposition_format() {
+        let mut exposition = Exposition::default();
+        exposition.metric("glide_open_connections", "gauge", "Open connections.");
+        exposition.sample("glide_open_connections", &[("node", "node1:6379")], 2);
+        exposition.sample("glide_pending_commands", &[], 0);
+        exposition.sample("glide_label", &[("name", "a\"b\\c")], 1);
+        assert_eq!(
+            exposition.0,
+            "# HELP glide_open_connections Open connections.\n\
+             # TYPE glide_open_connections gauge\n\
+             glide_open_connections{node=\"node1:6379\"} 2\n\
+             glide_pending_commands 0\n\
+             glide_label{name=\"a\\\"b\\\\c\"} 1\n"
+        );
+    }
+}
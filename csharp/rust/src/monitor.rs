@@ -0,0 +1,273 @@
+// Copyright Valkey GLIDE Project Contributors - SPDX Identifier: Apache-2.0
+
+//! Streaming `MONITOR` of a node to a callback for debugging, available only with the `testing` feature.
+//! While monitored, the node sends every command it processes to the monitoring connection, which severely degrades
+//! its throughput, so a monitor must not be started against production nodes.
+
+use std::{
+    ffi::{c_char, c_void, CString},
+    sync::{Arc, Weak},
+    time::Duration,
+};
+
+use redis::{aio::ConnectionLike, cluster_routing::RoutingInfo, Pipeline, RedisResult, Value};
+use tokio::{sync::oneshot, task::JoinHandle};
+
+use crate::{
+    client_from_ptr,
+    ffi::{create_route, RouteInfo},
+    Client, CommandExecutionCore,
+};
+
+/// Time to wait for the reply of `RESET` once the monitor is stopped. Commands monitored before it are still received until then.
+const RESET_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// Monitor callback that is called with every command the monitored node processes, as formatted by `MONITOR`, e.g.
+/// `1700000000.123456 [0 127.0.0.1:50000] "SET" "key" "value"`, and with `null` once the monitor ended, either because it was
+/// stopped by [`stop_monitor`] or because its connection failed.
+///
+/// The line is a null-terminated string, which is only valid until the callback returns, so it should be copied synchronously.
+pub type MonitorCallback = unsafe extern "C" fn(*const c_char) -> ();
+
+/// An opaque monitor handle, returned by [`start_monitor`].
+/// It doesn't keep the client alive, so the monitor ends with the client even if it is never stopped.
+struct Monitor {
+    client: Weak<Client>,
+    stop: oneshot::Sender<()>,
+    task: JoinHandle<()>,
+}
+
+/// Get the address of the node to monitor, refreshing the topology in cluster mode if the route can't be resolved yet.
+async fn monitored_node(core: &CommandExecutionCore, route: Option<RoutingInfo>) -> Option<String> {
+    let cmd = redis::cmd("MONITOR");
+    if let Some(node) = core.node_for_command(&cmd, route.as_ref()) {
+        return Some(node);
+    }
+    if core.standalone_address.is_none() && core.refresh_topology().await.is_ok() {
+        return core.node_for_command(&cmd, route.as_ref());
+    }
+    None
+}
+
+/// Pass the commands monitored on `connection` to `callback` until `stop` is signalled or the connection fails,
+/// then end the monitor mode by `RESET`.
+// `aio::Connection` is deprecated in favour of multiplexed connections, which `MONITOR` can't take over.
+#[allow(deprecated)]
+async fn stream_monitor(
+    mut connection: redis::aio::Connection,
+    mut stop: oneshot::Receiver<()>,
+    callback: MonitorCallback,
+) {
+    // An empty pipeline sends nothing, so it only reads the next line sent by the node
+    let next_line = Pipeline::new();
+    loop {
+        let line = tokio::select! {
+            biased;
+            _ = &mut stop => break,
+            line = connection.req_packed_commands(&next_line, 0, 1, None) => line,
+        };
+        match line.map(|mut lines| lines.pop()) {
+            Ok(Some(Value::SimpleString(line))) => {
+                let line = CString::new(line).unwrap_or_default();
+                unsafe { callback(line.as_ptr()) };
+            }
+            Ok(_) => {}
+            Err(err) => {
+                logger_core::log_warn("monitor", format!("MONITOR connection failed: {err}"));
+                unsafe { callback(std::ptr::null()) };
+                return;
+            }
+        }
+    }
+
+    let mut reset = Pipeline::new();
+    reset.add_command(redis::cmd("RESET"));
+    let reset = async {
+        let mut pipeline = &reset;
+        loop {
+            match connection
+                .req_packed_commands(pipeline, 0, 1, None)
+                .await?
+                .pop()
+            {
+                Some(Value::SimpleString(reply)) if reply == "RESET" => return Ok(()),
+                _ => pipeline = &next_line,
+            }
+        }
+    };
+    let result: RedisResult<()> = tokio::time::timeout(RESET_TIMEOUT, reset)
+        .await
+        .unwrap_or_else(|_| Err(crate::timeout_error()));
+    if let Err(err) = result {
+        logger_core::log_warn(
+            "monitor",
+            format!("Failed to end MONITOR by RESET, closing the connection: {err}"),
+        );
+    }
+    unsafe { callback(std::ptr::null()) };
+}
+
+/// Start streaming the commands processed by the node chosen by `route_info` to `callback`, see [`MonitorCallback`].
+/// A dedicated connection is opened for the monitor, since `MONITOR` takes over the connection, and it is closed by [`stop_monitor`].
+/// In standalone mode `route_info` could be `null`. In cluster mode it must route to a single node, e.g. by address or by slot,
+/// in which case the primary of the slot is monitored. Returns `null` if the client is closed, `MONITOR` isn't allowed by the
+/// command filter, or the node can't be resolved or connected to.
+///
+/// Warning: the node sends every command it processes to the monitor, which severely degrades its throughput.
+/// Monitors are meant for debugging in test environments only.
+///
+/// This function blocks while connecting, so it must not be called from a callback of the client.
+///
+/// # Safety
+/// * `client_ptr` must not be `null`.
+/// * `client_ptr` must be a valid pointer created by [`create_client`](crate::create_client), before [`close_client`](crate::close_client) was called with the pointer.
+/// * `route_info` could be `null`, but if it is not `null`, it must be a valid [`RouteInfo`] pointer. See the safety documentation of [`create_route`].
+/// * `callback` must be safe to call from any thread until the monitor ended.
+#[no_mangle]
+pub unsafe extern "C" fn start_monitor(
+    client_ptr: *const c_void,
+    route_info: *const RouteInfo,
+    callback: MonitorCallback,
+) -> *const c_void {
+    let Some(client) = (unsafe { client_from_ptr(client_ptr) }) else {
+        return std::ptr::null();
+    };
+    let core = client.core.clone();
+    let route = unsafe { create_route(route_info, &redis::cmd("MONITOR")) };
+    let connect = async {
+        core.check_command_allowed(&redis::cmd("MONITOR"))?;
+        let Some(node) = monitored_node(&core, route).await else {
+            return Err(redis::RedisError::from((
+                redis::ErrorKind::ClientError,
+                "MONITOR requires a route to a single node",
+            )));
+        };
+        let mut connection = core.leases.connect_to_node(&node).await?;
+        redis::cmd("MONITOR")
+            .query_async::<_, ()>(&mut connection)
+            .await?;
+        Ok((node, connection))
+    };
+    let (node, connection) = match client.runtime.block_on(connect) {
+        Ok(monitor) => monitor,
+        Err(err) => {
+            logger_core::log_warn("monitor", format!("Failed to start MONITOR: {err}"));
+            return std::ptr::null();
+        }
+    };
+    logger_core::log_warn(
+        "monitor",
+        format!(
+            "MONITOR started on {node}. The node sends every command it processes to the monitor, which severely degrades its throughput"
+        ),
+    );
+    let (stop, stopped) = oneshot::channel();
+    let task = client
+        .runtime
+        .spawn(stream_monitor(connection, stopped, callback));
+    Box::into_raw(Box::new(Monitor {
+        client: Arc::downgrade(&client),
+        stop,
+        task,
+    })) as *const c_void
+}
+
+/// Stop a monitor started by [`start_monitor`]: end the monitor mode by `RESET` and close its connection.
+/// Returns once the callback of the monitor was called with `null`, so it isn't called afterwards.
+///
+/// This function blocks until the monitor ended, so it must not be called from a callback of the client or of the monitor.
+///
+/// # Safety
+/// * `monitor_ptr` must not be `null`.
+/// * `monitor_ptr` must be a pointer returned by [`start_monitor`], which wasn't stopped yet.
+#[no_mangle]
+pub unsafe extern "C" fn stop_monitor(monitor_ptr: *const c_void) {
+    let monitor = unsafe { Box::from_raw(monitor_ptr as *mut Monitor) };
+    let _ = monitor.stop.send(());
+    // Once the client is deallocated its runtime is shut down, which already ended the monitor
+    if let Some(client) = monitor.client.upgrade() {
+        let _ = client.runtime.block_on(monitor.task);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        close_client, commands,
+        test_utilities::{
+            create_test_client, next_callback_index, unknown_command, wait_for_result, MockServer,
+        },
+    };
+    use std::{ffi::CStr, sync::Mutex, time::Instant};
+
+    /// The lines passed to [`record_line`], `None` for `null`.
+    static LINES: Mutex<Vec<Option<String>>> = Mutex::new(Vec::new());
+
+    unsafe extern "C" fn record_line(line: *const c_char) {
+        let line = (!line.is_null()).then(|| {
+            unsafe { CStr::from_ptr(line) }
+                .to_string_lossy()
+                .to_string()
+        });
+        LINES.lock().unwrap().push(line);
+    }
+
+    #[test]
+    fn test_commands_of_other_clients_are_monitored() {
+        let server = MockServer::bind();
+        let port = server.port();
+        server.serve(Arc::new(|args: &[Vec<u8>]| {
+            match args[0].to_ascii_uppercase().as_slice() {
+                b"INFO" => Value::BulkString(b"role:master".to_vec()),
+                b"SET" => Value::Okay,
+                _ => unknown_command(args),
+            }
+        }));
+        let client = create_test_client(&[port], false, |_| {});
+        let monitor = unsafe { start_monitor(client, std::ptr::null(), record_line) };
+        assert!(!monitor.is_null());
+
+        let other_client = create_test_client(&[port], false, |_| {});
+        let set = next_callback_index();
+        unsafe {
+            commands::set(
+                other_client,
+                set,
+                b"key".as_ptr(),
+                3,
+                b"value".as_ptr(),
+                5,
+                std::ptr::null(),
+                std::ptr::null(),
+            )
+        };
+        assert_eq!(wait_for_result(set), Ok(Value::Okay));
+        let started = Instant::now();
+        while !LINES
+            .lock()
+            .unwrap()
+            .iter()
+            .flatten()
+            .any(|line| line.ends_with(r#""SET" "key" "value""#))
+        {
+            assert!(started.elapsed() < Duration::from_secs(10));
+            std::thread::sleep(Duration::from_millis(10));
+        }
+
+        unsafe { stop_monitor(monitor) };
+        // The monitor ended once stopped, so the callback isn't called afterwards
+        assert_eq!(LINES.lock().unwrap().last(), Some(&None));
+        assert_eq!(
+            LINES
+                .lock()
+                .unwrap()
+                .iter()
+                .filter(|line| line.is_none())
+                .count(),
+            1
+        );
+        close_client(other_client);
+        close_client(client);
+    }
+}
@@ -0,0 +1,163 @@
+// Copyright Valkey GLIDE Project Contributors - SPDX Identifier: Apache-2.0
+
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+/// Interval in which the depth of the nodes is checked against the hot node threshold.
+pub(crate) const HOT_NODE_CHECK_INTERVAL: Duration = Duration::from_millis(100);
+
+#[derive(Debug, Default)]
+struct NodeState {
+    depth: u32,
+    /// When the depth exceeded the hot node threshold, `None` if it is below the threshold.
+    hot_since: Option<Instant>,
+    hot_reported: bool,
+}
+
+/// Tracks the amount of in-flight requests per node.
+/// Unlike the global inflight limit, this allows detecting a single slow node which is masked by healthy ones.
+#[derive(Debug)]
+pub(crate) struct NodeInflightTracker {
+    /// Maximum in-flight requests per node, `0` means no limit.
+    limit: u32,
+    /// Depth above which a node is considered hot, `0` disables hot node detection.
+    hot_threshold: u32,
+    /// How long a node's depth should stay above the threshold before it is reported as hot.
+    hot_duration: Duration,
+    nodes: Mutex<HashMap<String, NodeState>>,
+}
+
+/// Result of reserving an in-flight slot for a request.
+pub(crate) enum Reservation {
+    Reserved(InflightGuard),
+    LimitReached,
+}
+
+/// Releases the in-flight slot of a request on drop.
+pub(crate) struct InflightGuard {
+    tracker: Arc<NodeInflightTracker>,
+    node: String,
+}
+
+impl Drop for InflightGuard {
+    fn drop(&mut self) {
+        self.tracker.release(&self.node);
+    }
+}
+
+impl NodeInflightTracker {
+    pub(crate) fn new(limit: u32, hot_threshold: u32, hot_duration: Duration) -> Self {
+        NodeInflightTracker {
+            limit,
+            hot_threshold,
+            hot_duration,
+            nodes: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub(crate) fn reserve(self: &Arc<Self>, node: String) -> Reservation {
+        let mut nodes = self.nodes.lock().unwrap();
+        let state = nodes.entry(node.clone()).or_default();
+        if self.limit > 0 && state.depth >= self.limit {
+            return Reservation::LimitReached;
+        }
+        state.depth += 1;
+        if self.hot_threshold > 0 && state.depth > self.hot_threshold {
+            state.hot_since.get_or_insert_with(Instant::now);
+        }
+        Reservation::Reserved(InflightGuard {
+            tracker: self.clone(),
+            node,
+        })
+    }
+
+    fn release(&self, node: &str) {
+        let mut nodes = self.nodes.lock().unwrap();
+        if let Some(state) = nodes.get_mut(node) {
+            state.depth = state.depth.saturating_sub(1);
+            if state.depth <= self.hot_threshold {
+                state.hot_since = None;
+                state.hot_reported = false;
+            }
+        }
+    }
+
+    /// Whether hot node detection is enabled, i.e. [`Self::hot_nodes`] should be checked periodically.
+    pub(crate) fn detects_hot_nodes(&self) -> bool {
+        self.hot_threshold > 0
+    }
+
+    /// Get the nodes whose depth stayed above the hot node threshold for the configured duration.
+    /// A node is returned once, until its depth drops to the threshold.
+    pub(crate) fn hot_nodes(&self) -> Vec<String> {
+        let mut nodes = self.nodes.lock().unwrap();
+        nodes
+            .iter_mut()
+            .filter_map(|(node, state)| {
+                let hot_since = state.hot_since?;
+                if state.hot_reported || hot_since.elapsed() < self.hot_duration {
+                    return None;
+                }
+                state.hot_reported = true;
+                Some(node.clone())
+            })
+            .collect()
+    }
+
+    /// Get the current in-flight depth of every node which was used.
+    pub(crate) fn depths(&self) -> Vec<(String, u32)> {
+        self.nodes
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(node, state)| (node.clone(), state.depth))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn reserve(tracker: &Arc<NodeInflightTracker>, node: &str) -> InflightGuard {
+        match tracker.reserve(node.to_string()) {
+            Reservation::Reserved(guard) => guard,
+            Reservation::LimitReached => panic!("Unexpected limit on {node}"),
+        }
+    }
+
+    #[test]
+    fn test_hot_node_is_reported_once_the_depth_was_sustained() {
+        let tracker = Arc::new(NodeInflightTracker::new(0, 1, Duration::from_millis(50)));
+        let _first = reserve(&tracker, "node1");
+        let second = reserve(&tracker, "node1");
+        let _other = reserve(&tracker, "node2");
+        assert!(tracker.hot_nodes().is_empty());
+
+        // No further reservation is needed for the sustained depth to be detected.
+        std::thread::sleep(Duration::from_millis(60));
+        assert_eq!(tracker.hot_nodes(), vec!["node1".to_string()]);
+        assert!(tracker.hot_nodes().is_empty());
+
+        drop(second);
+        let _second = reserve(&tracker, "node1");
+        std::thread::sleep(Duration::from_millis(60));
+        assert_eq!(tracker.hot_nodes(), vec!["node1".to_string()]);
+    }
+
+    #[test]
+    fn test_limit_is_per_node() {
+        let tracker = Arc::new(NodeInflightTracker::new(1, 0, Duration::ZERO));
+        let first = reserve(&tracker, "node1");
+        let _other = reserve(&tracker, "node2");
+        assert!(matches!(
+            tracker.reserve("node1".to_string()),
+            Reservation::LimitReached
+        ));
+        drop(first);
+        let _first = reserve(&tracker, "node1");
+    }
+}
@@ -0,0 +1,119 @@
+// Copyright Valkey GLIDE Project Contributors - SPDX Identifier: Apache-2.0
+
+//! Non-atomic batches which return the results completed before their timeout,
+//! see [`BatchOptionsInfo::partial_results_chunk_size`](crate::ffi::BatchOptionsInfo::partial_results_chunk_size).
+
+use glide_core::client::Client as GlideClient;
+use redis::{Cmd, ErrorKind, Pipeline, PipelineRetryStrategy, RedisError, RedisResult, Value};
+use tokio::time::{Duration, Instant};
+
+use crate::errors::BATCH_TIMEOUT_CODE;
+
+/// The result of a command of the batch which didn't complete before the timeout of the batch.
+/// It's an error value like the server's error replies, so it's parsed from one.
+fn timed_out_command() -> Value {
+    let reply =
+        format!("-{BATCH_TIMEOUT_CODE} The batch timed out before the command completed\r\n");
+    redis::parse_redis_value(reply.as_bytes()).expect("A valid error reply")
+}
+
+/// Split a pipeline to consecutive sub-pipelines of at most `chunk_size` commands.
+fn split_pipeline(pipeline: &Pipeline, chunk_size: usize) -> Vec<Pipeline> {
+    let commands: Vec<_> = pipeline.cmd_iter().collect();
+    commands
+        .chunks(chunk_size)
+        .map(|chunk| {
+            let mut sub_pipeline = Pipeline::with_capacity(chunk.len());
+            for cmd in chunk {
+                sub_pipeline.add_command(Cmd::clone(cmd));
+            }
+            sub_pipeline
+        })
+        .collect()
+}
+
+/// Send a non-atomic batch by consecutive sub-pipelines of at most `chunk_size` commands, each sent once the previous one
+/// completed, so the commands are executed in the order they were given. Once `timeout` expires, the results of the completed
+/// sub-pipelines are returned in their positions, and every other command gets a [`BATCH_TIMEOUT_CODE`] error value.
+/// A sub-pipeline which fails otherwise, e.g. by a connection error, fails the whole batch.
+pub(crate) async fn send_with_partial_results(
+    client: &mut GlideClient,
+    pipeline: &Pipeline,
+    chunk_size: u32,
+    timeout: Duration,
+    raise_on_error: bool,
+    retry_strategy: PipelineRetryStrategy,
+) -> RedisResult<Value> {
+    let deadline = Instant::now() + timeout;
+    let mut results = Vec::with_capacity(pipeline.len());
+    for sub_pipeline in split_pipeline(pipeline, chunk_size as usize) {
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        if remaining.is_zero() {
+            break;
+        }
+        // The timeout of the sub-pipeline replaces the request timeout of the client, which could be shorter than the batch's
+        let sent = client.send_pipeline(
+            &sub_pipeline,
+            None,
+            raise_on_error,
+            Some(remaining.as_millis().clamp(1, u32::MAX.into()) as u32),
+            retry_strategy,
+        );
+        match tokio::time::timeout_at(deadline, sent).await {
+            Ok(Ok(Value::Array(replies))) if replies.len() == sub_pipeline.len() => {
+                results.extend(replies)
+            }
+            Ok(Ok(value)) => {
+                return Err(RedisError::from((
+                    ErrorKind::TypeError,
+                    "Unexpected sub-pipeline response",
+                    format!("{value:?}"),
+                )))
+            }
+            Ok(Err(err)) if !err.is_timeout() => return Err(err),
+            Ok(Err(_)) | Err(_) => break,
+        }
+    }
+    results.resize_with(pipeline.len(), timed_out_command);
+    Ok(Value::Array(results))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::errors::{error_type, RequestErrorType};
+
+    #[test]
+    fn test_split_keeps_the_order_of_commands() {
+        let mut pipeline = Pipeline::new();
+        for index in 0..5 {
+            pipeline.cmd("INCR").arg(format!("key{index}"));
+        }
+        let sub_pipelines = split_pipeline(&pipeline, 2);
+        assert_eq!(
+            sub_pipelines.iter().map(Pipeline::len).collect::<Vec<_>>(),
+            vec![2, 2, 1]
+        );
+        let commands: Vec<_> = sub_pipelines
+            .iter()
+            .flat_map(|sub_pipeline| sub_pipeline.cmd_iter())
+            .map(|cmd| cmd.get_packed_command())
+            .collect();
+        let expected: Vec<_> = pipeline
+            .cmd_iter()
+            .map(|cmd| cmd.get_packed_command())
+            .collect();
+        assert_eq!(commands, expected);
+    }
+
+    #[test]
+    fn test_timed_out_commands_are_timeout_errors() {
+        let Value::ServerError(err) = timed_out_command() else {
+            panic!("Expected an error value");
+        };
+        assert_eq!(
+            error_type(&RedisError::from(err)),
+            RequestErrorType::Timeout
+        );
+    }
+}
@@ -0,0 +1,158 @@
+// Copyright Valkey GLIDE Project Contributors - SPDX Identifier: Apache-2.0
+
+use std::{
+    sync::{
+        atomic::{AtomicU32, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+use tokio::sync::Notify;
+
+/// Counts the command tasks spawned on the runtime which haven't completed yet, i.e. whose callback wasn't called yet.
+/// Unlike the per-node in-flight limit, this is a single throttle of the whole client, which also bounds the tasks
+/// waiting to be scheduled under bursts.
+#[derive(Debug)]
+pub(crate) struct PendingTasks {
+    count: AtomicU32,
+    /// Maximum pending tasks, `0` means no limit.
+    limit: u32,
+    /// Notified whenever the last pending task completes, see [`PendingTasks::wait_idle`].
+    idle: Notify,
+}
+
+/// Releases a pending task on drop, once its callback was called.
+pub(crate) struct PendingTaskGuard {
+    tasks: Arc<PendingTasks>,
+}
+
+impl Drop for PendingTaskGuard {
+    fn drop(&mut self) {
+        if self.tasks.count.fetch_sub(1, Ordering::AcqRel) == 1 {
+            self.tasks.idle.notify_waiters();
+        }
+    }
+}
+
+impl PendingTasks {
+    pub(crate) fn new(limit: u32) -> Self {
+        PendingTasks {
+            count: AtomicU32::new(0),
+            limit,
+            idle: Notify::new(),
+        }
+    }
+
+    /// Count a task which is about to be spawned, `None` if the limit was reached.
+    pub(crate) fn reserve(self: &Arc<Self>) -> Option<PendingTaskGuard> {
+        let reserved = self
+            .count
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |count| {
+                (self.limit == 0 || count < self.limit).then_some(count + 1)
+            });
+        reserved.is_ok().then(|| PendingTaskGuard {
+            tasks: self.clone(),
+        })
+    }
+
+    /// Get the amount of tasks which were spawned and haven't completed yet.
+    pub(crate) fn count(&self) -> u32 {
+        self.count.load(Ordering::Acquire)
+    }
+
+    /// Wait until no task is pending, or until `timeout` elapses if one is given. Returns whether the tasks are idle.
+    /// Tasks which are spawned while waiting are waited for too, so under a steady stream of tasks which overlap
+    /// this only returns once the timeout elapses.
+    pub(crate) async fn wait_idle(&self, timeout: Option<Duration>) -> bool {
+        let idle = async {
+            loop {
+                // Register before checking the count, so a task completing in between isn't missed
+                let notified = self.idle.notified();
+                tokio::pin!(notified);
+                notified.as_mut().enable();
+                if self.count() == 0 {
+                    return;
+                }
+                notified.await;
+            }
+        };
+        match timeout {
+            Some(timeout) => tokio::time::timeout(timeout, idle).await.is_ok(),
+            None => {
+                idle.await;
+                true
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_limit_is_enforced_and_count_returns_to_zero() {
+        let tasks = Arc::new(PendingTasks::new(2));
+        let first = tasks.reserve().unwrap();
+        let second = tasks.reserve().unwrap();
+        assert!(tasks.reserve().is_none());
+        assert_eq!(tasks.count(), 2);
+
+        drop(first);
+        let third = tasks.reserve().unwrap();
+        drop(second);
+        drop(third);
+        assert_eq!(tasks.count(), 0);
+    }
+
+    #[test]
+    fn test_no_limit() {
+        let tasks = Arc::new(PendingTasks::new(0));
+        let guards: Vec<_> = (0..1000).map(|_| tasks.reserve().unwrap()).collect();
+        assert_eq!(tasks.count(), 1000);
+        drop(guards);
+        assert_eq!(tasks.count(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_wait_idle_resolves_when_the_last_task_completes() {
+        let tasks = Arc::new(PendingTasks::new(0));
+        assert!(tasks.wait_idle(Some(Duration::ZERO)).await);
+
+        let first = tasks.reserve().unwrap();
+        let second = tasks.reserve().unwrap();
+        let waiter = tokio::spawn({
+            let tasks = tasks.clone();
+            async move { tasks.wait_idle(Some(Duration::from_secs(5))).await }
+        });
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        drop(first);
+        assert!(!waiter.is_finished());
+        let start = std::time::Instant::now();
+        drop(second);
+        assert!(waiter.await.unwrap());
+        assert!(start.elapsed() < Duration::from_secs(1));
+    }
+
+    #[tokio::test]
+    async fn test_wait_idle_times_out_while_tasks_keep_arriving() {
+        let tasks = Arc::new(PendingTasks::new(0));
+        let mut previous = tasks.reserve().unwrap();
+        let arrivals = tokio::spawn({
+            let tasks = tasks.clone();
+            async move {
+                loop {
+                    // Every task is spawned before the previous one completes
+                    let next = tasks.reserve().unwrap();
+                    drop(std::mem::replace(&mut previous, next));
+                    tokio::time::sleep(Duration::from_millis(1)).await;
+                }
+            }
+        });
+        assert!(!tasks.wait_idle(Some(Duration::from_millis(50))).await);
+        arrivals.abort();
+        let _ = arrivals.await;
+        assert!(tasks.wait_idle(Some(Duration::from_secs(1))).await);
+    }
+}
@@ -0,0 +1,123 @@
+// Copyright Valkey GLIDE Project Contributors - SPDX Identifier: Apache-2.0
+
+//! Routes which prefer the primary of a slot, and fall back to a replica of the slot if the primary is overloaded or unavailable.
+//! This is the inverse of [`ConnectionConfig::fallback_to_primary_on_replica_error`](crate::ffi::ConnectionConfig::fallback_to_primary_on_replica_error).
+//!
+//! Such a route is represented by a route to the slot with [`SlotAddr::ReplicaOptional`], which user routes don't produce otherwise.
+//! It is split by [`split_primary_preferred`] before the command is dispatched, so glide-core never receives it.
+
+use redis::{
+    cluster_routing::{is_readonly, Route, RoutingInfo, SingleNodeRoutingInfo, SlotAddr},
+    Cmd, RedisError,
+};
+
+use crate::errors::{error_type, RequestErrorType};
+
+/// Create a route to the primary of `slot`, which falls back to a replica of the slot.
+pub(crate) fn primary_preferred(slot: u16) -> RoutingInfo {
+    RoutingInfo::SingleNode(SingleNodeRoutingInfo::SpecificNode(Route::new(
+        slot,
+        SlotAddr::ReplicaOptional,
+    )))
+}
+
+/// Split a primary preferred route to the route to the primary and the route to fall back to.
+/// Only reads fall back to a replica, since a replica would reject other commands. Other routes are returned as is.
+pub(crate) fn split_primary_preferred(
+    cmd: &Cmd,
+    route: Option<RoutingInfo>,
+) -> (Option<RoutingInfo>, Option<RoutingInfo>) {
+    match route {
+        Some(RoutingInfo::SingleNode(SingleNodeRoutingInfo::SpecificNode(route)))
+            if route.slot_addr() == SlotAddr::ReplicaOptional =>
+        {
+            let slot_route = |slot_addr| {
+                RoutingInfo::SingleNode(SingleNodeRoutingInfo::SpecificNode(Route::new(
+                    route.slot(),
+                    slot_addr,
+                )))
+            };
+            (
+                Some(slot_route(SlotAddr::Master)),
+                is_readonly(cmd).then(|| slot_route(SlotAddr::ReplicaRequired)),
+            )
+        }
+        route => (route, None),
+    }
+}
+
+/// Whether a command which failed on the primary should fall back to a replica: if the node's in-flight limit was reached
+/// (`Backpressure`), or the node is disconnected or unavailable, or the cluster is down. Logical errors, e.g. `WRONGTYPE`,
+/// are reported as is.
+pub(crate) fn should_fall_back_to_replica(err: &RedisError) -> bool {
+    matches!(
+        error_type(err),
+        RequestErrorType::Backpressure
+            | RequestErrorType::Disconnect
+            | RequestErrorType::Unavailable
+            | RequestErrorType::ClusterDown
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::errors::BACKPRESSURE_CODE;
+    use redis::ErrorKind;
+
+    fn slot_route(slot: u16, slot_addr: SlotAddr) -> Option<RoutingInfo> {
+        Some(RoutingInfo::SingleNode(
+            SingleNodeRoutingInfo::SpecificNode(Route::new(slot, slot_addr)),
+        ))
+    }
+
+    #[test]
+    fn test_reads_fall_back_to_a_replica_of_the_slot() {
+        let mut cmd = redis::cmd("GET");
+        cmd.arg("key");
+        assert_eq!(
+            split_primary_preferred(&cmd, Some(primary_preferred(42))),
+            (
+                slot_route(42, SlotAddr::Master),
+                slot_route(42, SlotAddr::ReplicaRequired)
+            )
+        );
+    }
+
+    #[test]
+    fn test_writes_and_other_routes_do_not_fall_back() {
+        let mut cmd = redis::cmd("SET");
+        cmd.arg("key").arg("value");
+        assert_eq!(
+            split_primary_preferred(&cmd, Some(primary_preferred(42))),
+            (slot_route(42, SlotAddr::Master), None)
+        );
+
+        let mut cmd = redis::cmd("GET");
+        cmd.arg("key");
+        let route = slot_route(42, SlotAddr::Master);
+        assert_eq!(split_primary_preferred(&cmd, route.clone()), (route, None));
+        assert_eq!(split_primary_preferred(&cmd, None), (None, None));
+    }
+
+    #[test]
+    fn test_fall_back_only_on_backpressure_and_unavailability() {
+        let backpressure = redis::make_extension_error(BACKPRESSURE_CODE.to_string(), None);
+        assert!(should_fall_back_to_replica(&backpressure));
+        let disconnect = RedisError::from(std::io::Error::from(std::io::ErrorKind::BrokenPipe));
+        assert!(should_fall_back_to_replica(&disconnect));
+        let cluster_down = RedisError::from((ErrorKind::ClusterDown, "The cluster is down"));
+        assert!(should_fall_back_to_replica(&cluster_down));
+
+        let wrong_type = redis::parse_redis_value(
+            b"-WRONGTYPE Operation against a key holding the wrong kind of value\r\n",
+        )
+        .and_then(|value| value.extract_error())
+        .unwrap_err();
+        assert!(!should_fall_back_to_replica(&wrong_type));
+        let out_of_memory = redis::parse_redis_value(b"-OOM command not allowed\r\n")
+            .and_then(|value| value.extract_error())
+            .unwrap_err();
+        assert!(!should_fall_back_to_replica(&out_of_memory));
+    }
+}
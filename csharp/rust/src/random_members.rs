@@ -0,0 +1,107 @@
+// Copyright Valkey GLIDE Project Contributors - SPDX Identifier: Apache-2.0
+
+use redis::{ErrorKind, RedisError, RedisResult, Value};
+
+fn bulk(value: &str) -> Value {
+    Value::BulkString(value.as_bytes().to_vec())
+}
+
+/// Convert the response of `HRANDFIELD`, `SRANDMEMBER` or `ZRANDMEMBER` with a count to an array of maps, one per returned
+/// member in order, including duplicates. Every map contains the `member` and, if `value_name` is given, the value of the
+/// member under that name, e.g. `value` for `HRANDFIELD` or `score` for `ZRANDMEMBER`, which is null if it wasn't requested.
+///
+/// glide-core already converts the responses with `WITHVALUES` or `WITHSCORES` to arrays of `[member, value]` pairs,
+/// with the scores as doubles, while without them the responses are arrays of members.
+pub(crate) fn convert_random_members(
+    value: Value,
+    value_name: Option<&'static str>,
+    with_values: bool,
+) -> RedisResult<Value> {
+    let Value::Array(members) = value else {
+        return Err(invalid_response("response is not an array"));
+    };
+    members
+        .into_iter()
+        .map(|member| {
+            let (member, value) = if with_values {
+                let Value::Array(pair) = member else {
+                    return Err(invalid_response("member and value are not an array"));
+                };
+                let [member, value] = <[Value; 2]>::try_from(pair)
+                    .map_err(|_| invalid_response("member and value are not a pair"))?;
+                (member, value)
+            } else {
+                (member, Value::Nil)
+            };
+            let mut entries = vec![(bulk("member"), member)];
+            if let Some(value_name) = value_name {
+                entries.push((bulk(value_name), value));
+            }
+            Ok(Value::Map(entries))
+        })
+        .collect::<RedisResult<_>>()
+        .map(Value::Array)
+}
+
+fn invalid_response(detail: &str) -> RedisError {
+    RedisError::from((
+        ErrorKind::TypeError,
+        "Unexpected random members response",
+        detail.to_string(),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn member(member: &str, value_name: &str, value: Value) -> Value {
+        Value::Map(vec![
+            (bulk("member"), bulk(member)),
+            (bulk(value_name), value),
+        ])
+    }
+
+    #[test]
+    fn test_shape_is_consistent_with_and_without_values() {
+        // A negative count allows duplicates, which are kept in order.
+        let with_scores = Value::Array(vec![
+            Value::Array(vec![bulk("one"), Value::Double(1.5)]),
+            Value::Array(vec![bulk("one"), Value::Double(1.5)]),
+        ]);
+        assert_eq!(
+            convert_random_members(with_scores, Some("score"), true).unwrap(),
+            Value::Array(vec![
+                member("one", "score", Value::Double(1.5)),
+                member("one", "score", Value::Double(1.5)),
+            ])
+        );
+
+        let without_scores = Value::Array(vec![bulk("one"), bulk("one")]);
+        assert_eq!(
+            convert_random_members(without_scores, Some("score"), false).unwrap(),
+            Value::Array(vec![
+                member("one", "score", Value::Nil),
+                member("one", "score", Value::Nil),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_set_members() {
+        assert_eq!(
+            convert_random_members(Value::Array(vec![bulk("a")]), None, false).unwrap(),
+            Value::Array(vec![Value::Map(vec![(bulk("member"), bulk("a"))])])
+        );
+        assert_eq!(
+            convert_random_members(Value::Array(vec![]), None, false).unwrap(),
+            Value::Array(vec![])
+        );
+    }
+
+    #[test]
+    fn test_malformed_pair() {
+        let value = Value::Array(vec![Value::Array(vec![bulk("field")])]);
+        assert!(convert_random_members(value, Some("value"), true).is_err());
+    }
+}
@@ -0,0 +1,191 @@
+// Copyright Valkey GLIDE Project Contributors - SPDX Identifier: Apache-2.0
+
+//! Token bucket rate limiting by a bundled script, see [`rate_limit`].
+
+use std::{ffi::c_void, sync::OnceLock};
+
+use glide_core::scripts_container::add_script;
+use redis::{ErrorKind, RedisError, RedisResult, Value};
+
+use crate::{
+    commands::ptr_to_bytes,
+    ffi::{create_route, RouteInfo},
+    info::bulk,
+    open_client_from_ptr,
+};
+
+/// Token bucket stored in a hash of the `tokens` left and the `timestamp` of the last request in microseconds.
+/// The clock is the `TIME` of the server, so clients with skewed clocks share the bucket consistently.
+/// Numbers are passed to `redis.call` as is, rather than by `tostring`, which would round the timestamp.
+/// Once the bucket would be full again it is equivalent to a missing bucket, so it expires then.
+const TOKEN_BUCKET_SCRIPT: &str = r#"
+local capacity = tonumber(ARGV[1])
+local refill_rate = tonumber(ARGV[2])
+local cost = tonumber(ARGV[3])
+if redis.replicate_commands then
+    redis.replicate_commands()
+end
+local time = redis.call('TIME')
+local now = tonumber(time[1]) * 1000000 + tonumber(time[2])
+local bucket = redis.call('HMGET', KEYS[1], 'tokens', 'timestamp')
+local tokens = tonumber(bucket[1])
+local timestamp = tonumber(bucket[2])
+if tokens == nil or timestamp == nil then
+    tokens = capacity
+    timestamp = now
+end
+tokens = math.min(capacity, tokens + math.max(0, now - timestamp) * refill_rate / 1000000)
+local allowed = 0
+if tokens >= cost then
+    tokens = tokens - cost
+    allowed = 1
+end
+redis.call('HMSET', KEYS[1], 'tokens', tokens, 'timestamp', now)
+redis.call('PEXPIRE', KEYS[1], math.ceil((capacity - tokens) * 1000 / refill_rate) + 1)
+return {allowed, math.floor(tokens)}
+"#;
+
+/// Hash of [`TOKEN_BUCKET_SCRIPT`], which is added to the script cache of glide-core once, so it's loaded again
+/// if the server doesn't have it, e.g. after a restart.
+static TOKEN_BUCKET_SCRIPT_HASH: OnceLock<String> = OnceLock::new();
+
+fn token_bucket_script_hash() -> &'static str {
+    TOKEN_BUCKET_SCRIPT_HASH.get_or_init(|| add_script(TOKEN_BUCKET_SCRIPT.as_bytes()))
+}
+
+/// Fail on parameters the script can't satisfy: an empty bucket, a bucket which never refills, or a cost above the capacity.
+fn check_parameters(capacity: u64, refill_rate: f64, cost: u64) -> RedisResult<()> {
+    let invalid = |reason: &'static str| Err(RedisError::from((ErrorKind::ClientError, reason)));
+    if capacity == 0 {
+        return invalid("The capacity of a rate limit must be positive");
+    }
+    if !(refill_rate.is_finite() && refill_rate > 0.0) {
+        return invalid("The refill rate of a rate limit must be a positive number");
+    }
+    if cost > capacity {
+        return invalid("The cost of a request must not exceed the capacity of the rate limit");
+    }
+    Ok(())
+}
+
+/// Convert the reply of [`TOKEN_BUCKET_SCRIPT`] to a map of whether the request is `allowed` and the `remaining` tokens.
+fn convert_rate_limit(value: Value) -> RedisResult<Value> {
+    match value {
+        Value::Array(replies) => match replies.as_slice() {
+            [Value::Int(allowed), Value::Int(remaining)] => Ok(Value::Map(vec![
+                (bulk("allowed"), Value::Boolean(*allowed == 1)),
+                (bulk("remaining"), Value::Int(*remaining)),
+            ])),
+            _ => Err(RedisError::from((
+                ErrorKind::TypeError,
+                "Unexpected rate limit response",
+                format!("{replies:?}"),
+            ))),
+        },
+        value => Err(RedisError::from((
+            ErrorKind::TypeError,
+            "Unexpected rate limit response",
+            format!("{value:?}"),
+        ))),
+    }
+}
+
+/// Take `cost` tokens from the token bucket stored at `key`, which holds up to `capacity` tokens and refills `refill_rate` tokens
+/// per second, e.g. to limit the requests of a user across processes. The bucket is updated by a bundled script, so concurrent
+/// requests on the same bucket are atomic, and its clock is the server's `TIME`. A missing bucket is full, and it expires once
+/// it would be full again. The script is invoked by its hash, and loaded to the server if the server doesn't have it.
+///
+/// The response is a map of whether the request is `allowed`, as a boolean, and the `remaining` whole tokens, as an integer.
+/// A request which isn't allowed takes no tokens. A `cost` of `0` gets the remaining tokens without taking any.
+/// The request fails if `capacity` is `0`, `refill_rate` isn't positive, or `cost` exceeds `capacity`.
+/// Without `route_info` the script is routed to the primary of the slot of the key.
+///
+/// # Safety
+/// * `client_ptr` must be a valid pointer created by [`create_client`](crate::create_client), before [`close_client`](crate::close_client) was called.
+/// * `key` must point to `key_len` consecutive bytes. See the safety documentation of [`ptr_to_bytes`].
+/// * `route_info` could be `null`, but if it is not `null`, it must be a valid [`RouteInfo`] pointer.
+#[allow(clippy::too_many_arguments)]
+#[no_mangle]
+pub unsafe extern "C" fn rate_limit(
+    client_ptr: *const c_void,
+    callback_index: usize,
+    key: *const u8,
+    key_len: u32,
+    capacity: u64,
+    refill_rate: f64,
+    cost: u64,
+    route_info: *const RouteInfo,
+) {
+    let Some(client) = (unsafe { open_client_from_ptr(client_ptr, callback_index) }) else {
+        return;
+    };
+    let core = client.core.clone();
+    let key = unsafe { ptr_to_bytes(key, key_len) }.to_vec();
+    let hash = token_bucket_script_hash();
+    let args = [
+        capacity.to_string().into_bytes(),
+        refill_rate.to_string().into_bytes(),
+        cost.to_string().into_bytes(),
+    ];
+    let mut evalsha = redis::cmd("EVALSHA");
+    evalsha.arg(hash).arg(1).arg(&key);
+    for arg in &args {
+        evalsha.arg(arg);
+    }
+    let pending_guard = match check_parameters(capacity, refill_rate, cost)
+        .and_then(|_| core.check_command_allowed(&evalsha))
+        .and_then(|_| core.reserve_pending_task())
+    {
+        Ok(pending_guard) => pending_guard,
+        Err(err) => {
+            unsafe { core.report_error(callback_index, err) };
+            return;
+        }
+    };
+    let route = unsafe { create_route(route_info, &evalsha) };
+
+    client.runtime.spawn(async move {
+        let mut client = core.client_pool.next();
+        let keys = vec![key.as_slice()];
+        let args: Vec<&[u8]> = args.iter().map(Vec::as_slice).collect();
+        let result = client.invoke_script(hash, &keys, &args, route).await;
+        core.track_authentication(&result);
+        let result = result.and_then(convert_rate_limit);
+        unsafe { core.report_result(callback_index, result) };
+        drop(pending_guard);
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_converts_allowed_and_remaining_tokens() {
+        assert_eq!(
+            convert_rate_limit(Value::Array(vec![Value::Int(1), Value::Int(9)])).unwrap(),
+            Value::Map(vec![
+                (bulk("allowed"), Value::Boolean(true)),
+                (bulk("remaining"), Value::Int(9)),
+            ])
+        );
+        assert_eq!(
+            convert_rate_limit(Value::Array(vec![Value::Int(0), Value::Int(0)])).unwrap(),
+            Value::Map(vec![
+                (bulk("allowed"), Value::Boolean(false)),
+                (bulk("remaining"), Value::Int(0)),
+            ])
+        );
+        assert!(convert_rate_limit(Value::Nil).is_err());
+    }
+
+    #[test]
+    fn test_rejects_unsatisfiable_parameters() {
+        assert!(check_parameters(10, 1.5, 10).is_ok());
+        assert!(check_parameters(10, 1.0, 0).is_ok());
+        assert!(check_parameters(0, 1.0, 0).is_err());
+        assert!(check_parameters(10, 0.0, 1).is_err());
+        assert!(check_parameters(10, f64::NAN, 1).is_err());
+        assert!(check_parameters(10, 1.0, 11).is_err());
+    }
+}
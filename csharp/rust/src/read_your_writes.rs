@@ -0,0 +1,112 @@
+// Copyright Valkey GLIDE Project Contributors - SPDX Identifier: Apache-2.0
+
+use std::{
+    collections::HashMap,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use redis::{
+    cluster_routing::{is_readonly, Route, RoutingInfo, SingleNodeRoutingInfo, SlotAddr},
+    Cmd,
+};
+
+/// Tracks the slots written recently, so reads of these slots are routed to the primary instead of a replica
+/// for a window after the write, see [`ConnectionConfig::read_your_writes_window_ms`](crate::ffi::ConnectionConfig::read_your_writes_window_ms).
+///
+/// Replication is asynchronous, so a replica may not have applied a write yet when a read following it arrives.
+/// Routing these reads to the primary guarantees they observe the write, at the cost of loading the primary.
+/// Reads after the window may still be stale if the replication lag is longer than the window.
+pub(crate) struct RecentWrites {
+    window: Duration,
+    /// The time until which reads of a slot are routed to the primary, by slot.
+    slots: Mutex<HashMap<u16, Instant>>,
+}
+
+impl RecentWrites {
+    pub(crate) fn new(window: Duration) -> Self {
+        RecentWrites {
+            window,
+            slots: Default::default(),
+        }
+    }
+
+    /// Record the slot of a write command. Commands without keys, and commands routed to multiple slots, aren't tracked.
+    pub(crate) fn record(&self, cmd: &Cmd) {
+        if is_readonly(cmd) {
+            return;
+        }
+        let Some(slot) = slot_of(cmd) else {
+            return;
+        };
+        let now = Instant::now();
+        let mut slots = self.slots.lock().unwrap();
+        slots.retain(|_, until| *until > now);
+        slots.insert(slot, now + self.window);
+    }
+
+    /// Get a route to the primary for a read of a slot written within the window, `None` if the read may be served by a replica.
+    pub(crate) fn route_for(&self, cmd: &Cmd) -> Option<RoutingInfo> {
+        if !is_readonly(cmd) {
+            return None;
+        }
+        let slot = slot_of(cmd)?;
+        let until = *self.slots.lock().unwrap().get(&slot)?;
+        (until > Instant::now()).then(|| {
+            RoutingInfo::SingleNode(SingleNodeRoutingInfo::SpecificNode(Route::new(
+                slot,
+                SlotAddr::Master,
+            )))
+        })
+    }
+}
+
+fn slot_of(cmd: &Cmd) -> Option<u16> {
+    match RoutingInfo::for_routable(cmd) {
+        Some(RoutingInfo::SingleNode(SingleNodeRoutingInfo::SpecificNode(route))) => {
+            Some(route.slot())
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cmd(args: &[&str]) -> Cmd {
+        let mut cmd = redis::cmd(args[0]);
+        for arg in &args[1..] {
+            cmd.arg(*arg);
+        }
+        cmd
+    }
+
+    #[test]
+    fn test_reads_after_write_are_routed_to_primary() {
+        let recent_writes = RecentWrites::new(Duration::from_secs(60));
+        assert_eq!(recent_writes.route_for(&cmd(&["GET", "foo"])), None);
+        recent_writes.record(&cmd(&["SET", "foo", "bar"]));
+        let Some(RoutingInfo::SingleNode(SingleNodeRoutingInfo::SpecificNode(route))) =
+            recent_writes.route_for(&cmd(&["GET", "foo"]))
+        else {
+            panic!("read wasn't routed to the primary");
+        };
+        assert_eq!(route.slot_addr(), SlotAddr::Master);
+        assert_eq!(recent_writes.route_for(&cmd(&["GET", "other"])), None);
+    }
+
+    #[test]
+    fn test_reads_are_not_tracked() {
+        let recent_writes = RecentWrites::new(Duration::from_secs(60));
+        recent_writes.record(&cmd(&["GET", "foo"]));
+        assert_eq!(recent_writes.route_for(&cmd(&["GET", "foo"])), None);
+    }
+
+    #[test]
+    fn test_window_expires() {
+        let recent_writes = RecentWrites::new(Duration::ZERO);
+        recent_writes.record(&cmd(&["SET", "foo", "bar"]));
+        assert_eq!(recent_writes.route_for(&cmd(&["GET", "foo"])), None);
+    }
+}
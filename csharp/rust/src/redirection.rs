@@ -0,0 +1,127 @@
+// Copyright Valkey GLIDE Project Contributors - SPDX Identifier: Apache-2.0
+
+use std::{
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Mutex,
+    },
+    time::{Duration, Instant},
+};
+
+use redis::cluster_async::{RedirectKind, RedirectionObserver};
+use tokio::sync::Notify;
+
+use crate::{report_event, ClientEventType, EventCallback};
+
+/// Length of the window in which the redirections rate is compared against the threshold.
+const RATE_WINDOW: Duration = Duration::from_secs(1);
+
+/// Delay after a `MOVED` redirection before the slot map is read, so glide-core applies the redirection first,
+/// and the redirections of a slot migration are coalesced.
+const SLOTS_MOVED_DELAY: Duration = Duration::from_millis(100);
+
+/// Counts the `MOVED` and `ASK` redirections handled internally by a cluster client, and reports a
+/// [`ClientEventType::Redirection`] event once per second in which the redirections exceed the configured threshold.
+pub(crate) struct RedirectionTracker {
+    moved: AtomicU64,
+    ask: AtomicU64,
+    event_callback: Option<EventCallback>,
+    /// Redirections per second above which an event is reported, `0` if disabled.
+    event_threshold: u32,
+    /// Start of the current rate window and the redirections counted in it.
+    window: Mutex<(Instant, u32)>,
+    /// Notified on `MOVED` redirections, after which the slot map of glide-core has changed.
+    slots_moved: Notify,
+}
+
+impl RedirectionTracker {
+    pub(crate) fn new(event_callback: Option<EventCallback>, event_threshold: u32) -> Self {
+        RedirectionTracker {
+            moved: AtomicU64::new(0),
+            ask: AtomicU64::new(0),
+            event_callback,
+            event_threshold,
+            window: Mutex::new((Instant::now(), 0)),
+            slots_moved: Notify::new(),
+        }
+    }
+
+    /// Get the amount of `MOVED` and `ASK` redirections since the client was created or the counters were reset.
+    pub(crate) fn counts(&self) -> (u64, u64) {
+        (
+            self.moved.load(Ordering::Relaxed),
+            self.ask.load(Ordering::Relaxed),
+        )
+    }
+
+    pub(crate) fn reset(&self) {
+        self.moved.store(0, Ordering::Relaxed);
+        self.ask.store(0, Ordering::Relaxed);
+    }
+
+    /// Wait for a `MOVED` redirection, including one which happened since the previous call returned,
+    /// and for glide-core to update its slot map accordingly.
+    pub(crate) async fn slots_moved(&self) {
+        self.slots_moved.notified().await;
+        tokio::time::sleep(SLOTS_MOVED_DELAY).await;
+    }
+
+    /// Count a redirection in the current rate window, returns whether it is the first one exceeding the threshold.
+    fn exceeds_threshold(&self) -> bool {
+        if self.event_threshold == 0 {
+            return false;
+        }
+        let mut window = self.window.lock().unwrap();
+        let now = Instant::now();
+        if now.duration_since(window.0) >= RATE_WINDOW {
+            *window = (now, 0);
+        }
+        window.1 = window.1.saturating_add(1);
+        window.1 == self.event_threshold.saturating_add(1)
+    }
+}
+
+impl RedirectionObserver for RedirectionTracker {
+    fn on_redirect(&self, kind: RedirectKind, slot: u16, address: &str) {
+        let (counter, name) = match kind {
+            RedirectKind::Moved => (&self.moved, "MOVED"),
+            RedirectKind::Ask => (&self.ask, "ASK"),
+        };
+        counter.fetch_add(1, Ordering::Relaxed);
+        if kind == RedirectKind::Moved {
+            self.slots_moved.notify_one();
+        }
+        if self.exceeds_threshold() {
+            report_event(
+                self.event_callback,
+                ClientEventType::Redirection,
+                &format!("{name} {slot} {address}"),
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_redirections_are_counted_until_reset() {
+        let tracker = RedirectionTracker::new(None, 0);
+        tracker.on_redirect(RedirectKind::Moved, 1, "127.0.0.1:7000");
+        tracker.on_redirect(RedirectKind::Moved, 2, "127.0.0.1:7000");
+        tracker.on_redirect(RedirectKind::Ask, 3, "127.0.0.1:7001");
+        assert_eq!(tracker.counts(), (2, 1));
+        tracker.reset();
+        assert_eq!(tracker.counts(), (0, 0));
+    }
+
+    #[test]
+    fn test_threshold_is_exceeded_once_per_window() {
+        let tracker = RedirectionTracker::new(None, 2);
+        assert!(!tracker.exceeds_threshold());
+        assert!(!tracker.exceeds_threshold());
+        assert!(tracker.exceeds_threshold());
+        assert!(!tracker.exceeds_threshold());
+    }
+}
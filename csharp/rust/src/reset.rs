@@ -0,0 +1,193 @@
+// Copyright Valkey GLIDE Project Contributors - SPDX Identifier: Apache-2.0
+
+//! Resetting connections to a clean state, see [`reset_connection`].
+
+use std::ffi::c_void;
+
+use glide_core::client::ConnectionRequest;
+use redis::{
+    cluster_routing::RoutingInfo, ErrorKind, Pipeline, PipelineRetryStrategy, ProtocolVersion,
+    RedisError, Value,
+};
+
+use crate::{
+    client_tracking::ClientTracking,
+    ffi::{create_route, RouteInfo},
+    open_client_from_ptr,
+};
+
+/// Create the pipeline which resets a connection: `RESET`, followed by the setup which glide-core applies to every new connection,
+/// in the same order - `HELLO` with the protocol and the credentials (or `AUTH` with RESP2), `SELECT`, `CLIENT SETNAME`,
+/// and `CLIENT TRACKING` if client tracking is configured, since `RESET` disables it as well.
+pub(crate) fn reset_pipeline(
+    request: &ConnectionRequest,
+    client_tracking: Option<&ClientTracking>,
+) -> Pipeline {
+    let mut pipeline = Pipeline::new();
+    pipeline.add_command(redis::cmd("RESET"));
+    let credentials = request
+        .authentication_info
+        .as_ref()
+        .and_then(|info| Some((info.username.as_deref(), info.password.as_deref()?)));
+    match request.protocol {
+        Some(ProtocolVersion::RESP2) => {
+            if let Some((username, password)) = credentials {
+                let mut auth = redis::cmd("AUTH");
+                if let Some(username) = username {
+                    auth.arg(username);
+                }
+                pipeline.add_command(auth.arg(password).clone());
+            }
+        }
+        _ => {
+            let mut hello = redis::cmd("HELLO");
+            hello.arg("3");
+            if let Some((username, password)) = credentials {
+                hello
+                    .arg("AUTH")
+                    .arg(username.unwrap_or("default"))
+                    .arg(password);
+            }
+            pipeline.add_command(hello);
+        }
+    }
+    if request.database_id != 0 {
+        pipeline.add_command(redis::cmd("SELECT").arg(request.database_id).clone());
+    }
+    if let Some(client_name) = &request.client_name {
+        pipeline.add_command(redis::cmd("CLIENT").arg("SETNAME").arg(client_name).clone());
+    }
+    if let Some(client_tracking) = client_tracking {
+        pipeline.add_command(client_tracking.command());
+    }
+    pipeline
+}
+
+/// Reset connections to a clean state without reconnecting, e.g. to recover them after transactions or subscriptions.
+/// `RESET` discards a pending `MULTI`, unwatches all keys, removes all subscriptions and leaves monitor mode, and the setup
+/// of the connection is applied again, see [`reset_pipeline`].
+///
+/// If `route_info` is `null`, the connections of the client to every node are reset, otherwise the connections to the node
+/// of the route, which must be a single node route. With [`ConnectionConfig::connections_per_node`](crate::ffi::ConnectionConfig::connections_per_node)
+/// the connections of every client of the pool are reset. The response is `OK`, or the first error, in which case some
+/// connections may not have been reset.
+///
+/// # Safety
+/// * `client_ptr` must be a valid pointer created by [`create_client`](crate::create_client), before [`close_client`](crate::close_client) was called.
+/// * `route_info` could be `null`, but if it is not `null`, it must be a valid [`RouteInfo`] pointer.
+#[no_mangle]
+pub unsafe extern "C" fn reset_connection(
+    client_ptr: *const c_void,
+    callback_index: usize,
+    route_info: *const RouteInfo,
+) {
+    let Some(client) = (unsafe { open_client_from_ptr(client_ptr, callback_index) }) else {
+        return;
+    };
+    let core = client.core.clone();
+    let reset = redis::cmd("RESET");
+    let route = unsafe { create_route(route_info, &reset) };
+    let pending_guard = match core
+        .check_command_allowed(&reset)
+        .and_then(|_| match route {
+            Some(RoutingInfo::MultiNode(_)) => Err(RedisError::from((
+                ErrorKind::ClientError,
+                "Connections can be reset on a single node, or on every node without a route",
+            ))),
+            _ => core.reserve_pending_task(),
+        }) {
+        Ok(pending_guard) => pending_guard,
+        Err(err) => {
+            unsafe { core.report_error(callback_index, err) };
+            return;
+        }
+    };
+
+    client.runtime.spawn(async move {
+        let result = async {
+            let routes = match route {
+                Some(route) => vec![Some(route)],
+                None => core
+                    .all_nodes()
+                    .await?
+                    .into_iter()
+                    .map(|(_, route)| route)
+                    .collect(),
+            };
+            for client in core.client_pool.all() {
+                for route in routes.clone() {
+                    client
+                        .clone()
+                        .send_pipeline(
+                            &core.connection_reset,
+                            route,
+                            true,
+                            None,
+                            PipelineRetryStrategy::default(),
+                        )
+                        .await?;
+                }
+            }
+            Ok(Value::Okay)
+        }
+        .await;
+        core.track_authentication(&result);
+        unsafe { core.report_result(callback_index, result) };
+        drop(pending_guard);
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client_tracking::ClientTrackingMode;
+    use glide_core::client::AuthenticationInfo;
+
+    fn commands(pipeline: &Pipeline) -> Vec<String> {
+        pipeline
+            .cmd_iter()
+            .map(|cmd| {
+                cmd.args_iter()
+                    .map(|arg| match arg {
+                        redis::Arg::Simple(arg) => String::from_utf8_lossy(arg).into_owned(),
+                        redis::Arg::Cursor => "CURSOR".to_string(),
+                    })
+                    .collect::<Vec<_>>()
+                    .join(" ")
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_reset_reapplies_connection_setup() {
+        let mut request = ConnectionRequest::default();
+        assert_eq!(
+            commands(&reset_pipeline(&request, None)),
+            ["RESET", "HELLO 3"]
+        );
+
+        request.authentication_info = Some(AuthenticationInfo {
+            username: None,
+            password: Some("secret".to_string()),
+        });
+        request.database_id = 2;
+        request.client_name = Some("worker".to_string());
+        let tracking = ClientTracking::new(ClientTrackingMode::Default, vec![]);
+        assert_eq!(
+            commands(&reset_pipeline(&request, tracking.as_ref())),
+            [
+                "RESET",
+                "HELLO 3 AUTH default secret",
+                "SELECT 2",
+                "CLIENT SETNAME worker",
+                "CLIENT TRACKING ON"
+            ]
+        );
+
+        request.protocol = Some(ProtocolVersion::RESP2);
+        assert_eq!(
+            commands(&reset_pipeline(&request, None)),
+            ["RESET", "AUTH secret", "SELECT 2", "CLIENT SETNAME worker"]
+        );
+    }
+}
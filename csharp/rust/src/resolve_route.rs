@@ -0,0 +1,319 @@
+// Copyright Valkey GLIDE Project Contributors - SPDX Identifier: Apache-2.0
+
+//! Resolving the nodes a command would be sent to, without sending it, see [`resolve_route`].
+
+use std::{collections::BTreeSet, ffi::c_void, os::raw::c_char};
+
+use glide_core::request_type::RequestType;
+use redis::{
+    cluster_routing::{
+        MultipleNodeRoutingInfo, Route, RoutingInfo, SingleNodeRoutingInfo, SlotAddr,
+    },
+    cluster_topology::get_slot,
+    ErrorKind, RedisError, RedisResult, Value,
+};
+
+use crate::{
+    create_command,
+    ffi::RouteInfo,
+    info::bulk,
+    open_client_from_ptr,
+    primary_preferred::split_primary_preferred,
+    route_decider::{check_decided_route, decide_route},
+    topology::Topology,
+};
+
+/// Build a map value with the given string keys.
+fn map(entries: Vec<(&str, Value)>) -> Value {
+    Value::Map(
+        entries
+            .into_iter()
+            .map(|(key, value)| (bulk(key), value))
+            .collect(),
+    )
+}
+
+/// Get the addresses of the nodes which may serve a slot route: the replicas of the slot if the route allows them,
+/// and the client reads from replicas or the route requires a replica, otherwise the primary.
+fn slot_nodes(topology: &Topology, route: &Route, reads_from_replicas: bool) -> Vec<String> {
+    let Some(range) = topology.range_for_slot(route.slot()) else {
+        return vec![];
+    };
+    let use_replicas = match route.slot_addr() {
+        SlotAddr::Master => false,
+        SlotAddr::ReplicaOptional => reads_from_replicas,
+        SlotAddr::ReplicaRequired => true,
+    };
+    match use_replicas && !range.replicas.is_empty() {
+        true => range.replicas.clone(),
+        false => vec![range.primary.clone()],
+    }
+}
+
+/// Resolve a cluster mode route to a map of the kind of the `route`, the `slots` it is routed by and the candidate `nodes`.
+/// Fails with a `CrossSlot` error if `key_slots`, the slots of the keys of the command, differ, unless the command is split by slot.
+fn resolve(
+    topology: &Topology,
+    route: RoutingInfo,
+    key_slots: &BTreeSet<u16>,
+    reads_from_replicas: bool,
+) -> RedisResult<Value> {
+    let is_split = matches!(
+        route,
+        RoutingInfo::MultiNode((MultipleNodeRoutingInfo::MultiSlot(_), _))
+    );
+    if key_slots.len() > 1 && !is_split {
+        return Err(RedisError::from((
+            ErrorKind::CrossSlot,
+            "Keys in request don't hash to the same slot",
+            format!("slots {key_slots:?}"),
+        )));
+    }
+    let primaries = || -> Vec<String> {
+        topology
+            .primaries()
+            .into_iter()
+            .map(str::to_string)
+            .collect()
+    };
+    let (kind, slots, nodes): (&str, Vec<u16>, Vec<String>) = match route {
+        RoutingInfo::SingleNode(SingleNodeRoutingInfo::Random) => {
+            ("random", vec![], topology.nodes())
+        }
+        RoutingInfo::SingleNode(SingleNodeRoutingInfo::RandomPrimary) => {
+            ("random_primary", vec![], primaries())
+        }
+        RoutingInfo::SingleNode(SingleNodeRoutingInfo::SpecificNode(route)) => (
+            "slot",
+            vec![route.slot()],
+            slot_nodes(topology, &route, reads_from_replicas),
+        ),
+        RoutingInfo::SingleNode(SingleNodeRoutingInfo::ByAddress { host, port }) => {
+            ("address", vec![], vec![format!("{host}:{port}")])
+        }
+        RoutingInfo::MultiNode((MultipleNodeRoutingInfo::AllNodes, _)) => {
+            ("all_nodes", vec![], topology.nodes())
+        }
+        RoutingInfo::MultiNode((MultipleNodeRoutingInfo::AllMasters, _)) => {
+            ("all_primaries", vec![], primaries())
+        }
+        RoutingInfo::MultiNode((MultipleNodeRoutingInfo::MultiSlot((routes, _)), _)) => {
+            let mut nodes: Vec<String> = routes
+                .iter()
+                .flat_map(|(route, _)| slot_nodes(topology, route, reads_from_replicas))
+                .collect();
+            nodes.sort();
+            nodes.dedup();
+            let slots = routes.iter().map(|(route, _)| route.slot()).collect();
+            ("multi_slot", slots, nodes)
+        }
+    };
+    Ok(map(vec![
+        ("route", bulk(kind)),
+        (
+            "slots",
+            Value::Array(
+                slots
+                    .into_iter()
+                    .map(|slot| Value::Int(slot.into()))
+                    .collect(),
+            ),
+        ),
+        (
+            "nodes",
+            Value::Array(nodes.iter().map(|node| bulk(node)).collect()),
+        ),
+    ]))
+}
+
+/// Resolve where a command would be sent to by [`command`](crate::command), without sending it, e.g. to test routing assumptions
+/// or to debug misrouting. The route is computed like `command` does: the keys are prefixed, see [`ConnectionConfig::key_prefix`](crate::ffi::ConnectionConfig::key_prefix),
+/// and without `route_info` the route decider, the read-your-writes window and the default route of the client apply,
+/// before the command is routed by its keys. Writes aren't recorded for read-your-writes, since nothing is written.
+///
+/// The response is a map of:
+/// * `route` - `standalone`, `slot` for commands routed by the slot of their keys, `multi_slot` for commands split by slot,
+///   e.g. `MGET`, `random` for commands without keys, `random_primary`, `all_nodes`, `all_primaries` or `address`.
+/// * `slots` - the slots the command is routed by, as integers, empty if it isn't routed by slot.
+/// * `nodes` - the `host:port` addresses of the candidate nodes. A command routed to a single node is sent to one of them,
+///   e.g. to one of the replicas of the slot when reading from replicas, while a command routed to multiple nodes is sent to all.
+///
+/// The nodes are resolved by the topology snapshot of the client, which is refreshed periodically, so the nodes might be
+/// slightly behind the topology glide-core routes by, or empty if the topology wasn't fetched yet.
+///
+/// If the keys of a command which isn't split by slot map to different slots, the request fails with a `CrossSlot` error, like the server
+/// would reply. The keys besides the one the command is routed by are known by their key specifications, which are only
+/// loaded with a key prefix or a default hash tag, so otherwise only the routing key is checked. Commands which aren't allowed
+/// by the command filter fail with a `CommandNotAllowed` error.
+///
+/// # Safety
+/// * `client_ptr` must be a valid pointer created by [`create_client`](crate::create_client), before [`close_client`](crate::close_client) was called.
+/// * `args` must point to `arg_count` consecutive string pointers and `args_len` must point to their lengths.
+///   See the safety documentation of [`convert_double_pointer_to_vec`](crate::ffi::convert_double_pointer_to_vec).
+/// * `route_info` could be `null`, but if it is not `null`, it must be a valid [`RouteInfo`] pointer.
+#[allow(rustdoc::private_intra_doc_links)]
+#[no_mangle]
+pub unsafe extern "C" fn resolve_route(
+    client_ptr: *const c_void,
+    callback_index: usize,
+    request_type: RequestType,
+    args: *const *mut c_char,
+    arg_count: u32,
+    args_len: *const u32,
+    route_info: *const RouteInfo,
+) {
+    let Some(client) = (unsafe { open_client_from_ptr(client_ptr, callback_index) }) else {
+        return;
+    };
+    let core = &client.core;
+    let cmd = match unsafe {
+        create_command(
+            &request_type,
+            args,
+            arg_count,
+            args_len,
+            core.log_non_utf8_arguments,
+        )
+    } {
+        Ok(cmd) => cmd,
+        Err(err) => {
+            unsafe { core.report_error(callback_index, err) };
+            return;
+        }
+    };
+    let decided_route_info = match core.route_decider.filter(|_| route_info.is_null()) {
+        Some(route_decider) => unsafe { decide_route(route_decider, request_type, &cmd) },
+        None => std::ptr::null(),
+    };
+    let (cmd, route, _) = match decided_route_info.is_null() {
+        true => unsafe { core.prefix_keys(cmd, route_info) },
+        false => unsafe { core.prefix_keys(cmd, decided_route_info) },
+    };
+    let result = core
+        .check_command_allowed(&cmd)
+        .and_then(|_| match &route {
+            Some(route) if !decided_route_info.is_null() => check_decided_route(&cmd, route),
+            _ => Ok(()),
+        })
+        .and_then(|_| {
+            let route = route.or_else(|| core.implicit_route(&cmd));
+            let (route, _) = split_primary_preferred(&cmd, route);
+            if let Some(address) = &core.standalone_address {
+                return Ok(map(vec![
+                    ("route", bulk("standalone")),
+                    ("slots", Value::Array(vec![])),
+                    ("nodes", Value::Array(vec![bulk(address)])),
+                ]));
+            }
+            let key_slots: BTreeSet<u16> = match &core.key_prefix {
+                Some(key_prefix) => key_prefix.keys(&cmd).into_iter().map(get_slot).collect(),
+                None => RoutingInfo::routing_key(&cmd)
+                    .map(get_slot)
+                    .into_iter()
+                    .collect(),
+            };
+            let route = route
+                .or_else(|| RoutingInfo::for_routable(&cmd))
+                .unwrap_or(RoutingInfo::SingleNode(SingleNodeRoutingInfo::Random));
+            let topology = core.topology.read().unwrap().clone();
+            resolve(&topology, route, &key_slots, core.reads_from_replicas)
+        });
+    unsafe { core.report_result(callback_index, result) };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::topology::SlotRange;
+
+    fn topology() -> Topology {
+        Topology {
+            ranges: vec![
+                SlotRange {
+                    start: 0,
+                    end: 8191,
+                    primary: "node1:6379".to_string(),
+                    replicas: vec!["node3:6379".to_string(), "node4:6379".to_string()],
+                },
+                SlotRange {
+                    start: 8192,
+                    end: 16383,
+                    primary: "node2:6379".to_string(),
+                    replicas: vec![],
+                },
+            ],
+        }
+    }
+
+    fn nodes(resolved: &Value) -> Vec<String> {
+        let Value::Map(fields) = resolved else {
+            panic!("not a map: {resolved:?}");
+        };
+        let Some((_, Value::Array(nodes))) = fields.iter().find(|(key, _)| *key == bulk("nodes"))
+        else {
+            panic!("no nodes: {resolved:?}");
+        };
+        nodes
+            .iter()
+            .map(|node| match node {
+                Value::BulkString(node) => String::from_utf8_lossy(node).into_owned(),
+                node => panic!("not a string: {node:?}"),
+            })
+            .collect()
+    }
+
+    fn route_of(args: &[&str]) -> RoutingInfo {
+        let mut cmd = redis::cmd(args[0]);
+        for arg in &args[1..] {
+            cmd.arg(*arg);
+        }
+        RoutingInfo::for_routable(&cmd).unwrap()
+    }
+
+    #[test]
+    fn test_resolve_slot_routes() {
+        let topology = topology();
+        // "a" is in slot 15495, "b" in slot 3300
+        let get_b = route_of(&["GET", "b"]);
+        let resolved = resolve(&topology, get_b.clone(), &BTreeSet::from([3300]), false).unwrap();
+        assert_eq!(nodes(&resolved), ["node1:6379"]);
+        let resolved = resolve(&topology, get_b, &BTreeSet::from([3300]), true).unwrap();
+        assert_eq!(nodes(&resolved), ["node3:6379", "node4:6379"]);
+        // Without replicas, reads are served by the primary
+        let resolved = resolve(&topology, route_of(&["GET", "a"]), &BTreeSet::new(), true).unwrap();
+        assert_eq!(nodes(&resolved), ["node2:6379"]);
+        let resolved = resolve(
+            &topology,
+            route_of(&["SET", "b", "1"]),
+            &BTreeSet::new(),
+            true,
+        )
+        .unwrap();
+        assert_eq!(nodes(&resolved), ["node1:6379"]);
+    }
+
+    #[test]
+    fn test_resolve_multi_node_and_keyless_routes() {
+        let topology = topology();
+        let mget = route_of(&["MGET", "a", "b"]);
+        let resolved = resolve(&topology, mget, &BTreeSet::from([15495, 3300]), false).unwrap();
+        assert_eq!(nodes(&resolved), ["node1:6379", "node2:6379"]);
+        let resolved = resolve(&topology, route_of(&["TIME"]), &BTreeSet::new(), false).unwrap();
+        assert_eq!(nodes(&resolved).len(), 4);
+        let resolved =
+            resolve(&topology, route_of(&["FLUSHALL"]), &BTreeSet::new(), false).unwrap();
+        assert_eq!(nodes(&resolved), ["node1:6379", "node2:6379"]);
+    }
+
+    #[test]
+    fn test_resolve_cross_slot_fails() {
+        let err = resolve(
+            &topology(),
+            route_of(&["SUNIONSTORE", "a", "b"]),
+            &BTreeSet::from([15495, 3300]),
+            false,
+        )
+        .unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::CrossSlot);
+    }
+}
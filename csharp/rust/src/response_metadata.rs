@@ -0,0 +1,208 @@
+// Copyright Valkey GLIDE Project Contributors - SPDX Identifier: Apache-2.0
+
+//! Metadata returned alongside the replies of commands, see [`ConnectionConfig::response_metadata`](crate::ffi::ConnectionConfig::response_metadata).
+//!
+//! The metadata is carried to [`ResponseValue::from_value`](crate::ffi::ResponseValue::from_value) as a [`Value::Attribute`],
+//! which becomes a [`ValueType::WithMetadata`](crate::ffi::ValueType::WithMetadata) value. RESP3 attributes sent by the server
+//! are split from the reply before it is converted, so they never reach the caller otherwise.
+
+use redis::{
+    cluster_routing::{is_readonly, RoutingInfo, SingleNodeRoutingInfo, SlotAddr},
+    Cmd, Value,
+};
+
+use crate::topology::Topology;
+
+/// The kind of node which served a reply.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum ResponseSource {
+    Primary,
+    Replica,
+    /// The node was chosen by glide-core, e.g. a read served by the `read_from` strategy, or the command was sent to multiple nodes.
+    Unknown,
+}
+
+impl ResponseSource {
+    fn as_str(self) -> &'static str {
+        match self {
+            ResponseSource::Primary => "primary",
+            ResponseSource::Replica => "replica",
+            ResponseSource::Unknown => "unknown",
+        }
+    }
+}
+
+/// Split the RESP3 attributes sent by the server from a reply.
+pub(crate) fn split_attributes(value: Value) -> (Value, Option<Vec<(Value, Value)>>) {
+    match value {
+        Value::Attribute { data, attributes } => (*data, Some(attributes)),
+        value => (value, None),
+    }
+}
+
+/// Metadata of a reply: the kind of node which served it, and the node's address if it is known.
+#[derive(Debug, PartialEq)]
+pub(crate) struct ResponseMetadata {
+    source: ResponseSource,
+    node: Option<String>,
+}
+
+impl ResponseMetadata {
+    /// Resolve the node which served a command sent by `route`, which is the route the reply came from if the command fell back to another node.
+    /// `node` is the node the command was attributed to, see [`Topology::node_for_route`]. Since reads routed to a replica are attributed
+    /// to the primary of the shard, the address of a replica is only known if the command was routed to it by its address.
+    pub(crate) fn new(
+        cmd: &Cmd,
+        route: Option<&RoutingInfo>,
+        node: Option<String>,
+        topology: &Topology,
+        reads_from_replicas: bool,
+    ) -> Self {
+        let (source, node) = match route {
+            Some(RoutingInfo::SingleNode(SingleNodeRoutingInfo::SpecificNode(route))) => {
+                match route.slot_addr() {
+                    SlotAddr::Master => (ResponseSource::Primary, node),
+                    SlotAddr::ReplicaRequired => (ResponseSource::Replica, None),
+                    SlotAddr::ReplicaOptional => (ResponseSource::Unknown, None),
+                }
+            }
+            Some(RoutingInfo::SingleNode(SingleNodeRoutingInfo::ByAddress { .. })) => {
+                let source = match &node {
+                    Some(node) if topology.primaries().contains(&node.as_str()) => {
+                        ResponseSource::Primary
+                    }
+                    Some(node) if topology.nodes().contains(node) => ResponseSource::Replica,
+                    _ => ResponseSource::Unknown,
+                };
+                (source, node)
+            }
+            Some(RoutingInfo::SingleNode(SingleNodeRoutingInfo::RandomPrimary)) => {
+                (ResponseSource::Primary, None)
+            }
+            Some(_) => (ResponseSource::Unknown, None),
+            None if reads_from_replicas && is_readonly(cmd) => (ResponseSource::Unknown, None),
+            None => (ResponseSource::Primary, node),
+        };
+        ResponseMetadata { source, node }
+    }
+
+    /// Attach the metadata, and the RESP3 attributes the server sent with the reply if any, to a reply.
+    pub(crate) fn attach(self, value: Value, attributes: Option<Vec<(Value, Value)>>) -> Value {
+        let bulk = |text: &str| Value::BulkString(text.as_bytes().to_vec());
+        Value::Attribute {
+            data: Box::new(value),
+            attributes: vec![
+                (bulk("source"), bulk(self.source.as_str())),
+                (bulk("node"), self.node.as_deref().map_or(Value::Nil, bulk)),
+                (
+                    bulk("attributes"),
+                    attributes.map_or(Value::Nil, Value::Map),
+                ),
+            ],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::topology::SlotRange;
+    use redis::cluster_routing::Route;
+
+    fn topology() -> Topology {
+        Topology {
+            ranges: vec![SlotRange {
+                start: 0,
+                end: 16383,
+                primary: "primary:6379".to_string(),
+                replicas: vec!["replica:6379".to_string()],
+            }],
+        }
+    }
+
+    fn by_address(host: &str) -> RoutingInfo {
+        RoutingInfo::SingleNode(SingleNodeRoutingInfo::ByAddress {
+            host: host.to_string(),
+            port: 6379,
+        })
+    }
+
+    #[test]
+    fn test_source_of_routes() {
+        let mut get = redis::cmd("GET");
+        get.arg("key");
+        let primary = Some("primary:6379".to_string());
+        let metadata = |route: Option<&RoutingInfo>, node: Option<String>| {
+            ResponseMetadata::new(&get, route, node, &topology(), true)
+        };
+
+        let replica_route = RoutingInfo::SingleNode(SingleNodeRoutingInfo::SpecificNode(
+            Route::new(42, SlotAddr::ReplicaRequired),
+        ));
+        assert_eq!(
+            metadata(Some(&replica_route), primary.clone()),
+            ResponseMetadata {
+                source: ResponseSource::Replica,
+                node: None
+            }
+        );
+        assert_eq!(
+            metadata(
+                Some(&by_address("replica")),
+                Some("replica:6379".to_string())
+            ),
+            ResponseMetadata {
+                source: ResponseSource::Replica,
+                node: Some("replica:6379".to_string())
+            }
+        );
+        assert_eq!(
+            metadata(Some(&by_address("primary")), primary.clone()).source,
+            ResponseSource::Primary
+        );
+        // Reads without a route are served by the `read_from` strategy.
+        assert_eq!(
+            metadata(None, primary.clone()).source,
+            ResponseSource::Unknown
+        );
+
+        let mut set = redis::cmd("SET");
+        set.arg("key").arg("value");
+        assert_eq!(
+            ResponseMetadata::new(&set, None, primary.clone(), &topology(), true),
+            ResponseMetadata {
+                source: ResponseSource::Primary,
+                node: primary
+            }
+        );
+    }
+
+    #[test]
+    fn test_server_attributes_are_attached() {
+        let attributes = vec![(
+            Value::BulkString(b"encoding".to_vec()),
+            Value::BulkString(b"embstr".to_vec()),
+        )];
+        let reply = Value::Attribute {
+            data: Box::new(Value::BulkString(b"value".to_vec())),
+            attributes: attributes.clone(),
+        };
+        let (value, server_attributes) = split_attributes(reply);
+        assert_eq!(value, Value::BulkString(b"value".to_vec()));
+
+        let metadata = ResponseMetadata {
+            source: ResponseSource::Primary,
+            node: None,
+        };
+        let Value::Attribute {
+            data,
+            attributes: metadata,
+        } = metadata.attach(value, server_attributes)
+        else {
+            panic!("metadata wasn't attached");
+        };
+        assert_eq!(*data, Value::BulkString(b"value".to_vec()));
+        assert_eq!(metadata[1].1, Value::Nil);
+        assert_eq!(metadata[2].1, Value::Map(attributes));
+    }
+}
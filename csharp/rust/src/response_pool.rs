@@ -0,0 +1,115 @@
+// Copyright Valkey GLIDE Project Contributors - SPDX Identifier: Apache-2.0
+
+//! An opt-in pool of top-level [`ResponseValue`] allocations per client, which are reused across responses instead of
+//! allocating and freeing one per response. Only the top-level values are pooled - nested values and strings
+//! are freed as usual. [`free_respose`](crate::free_respose) isn't given a client, so every allocation carries the pool
+//! of the client it was handed out by, if any. The pool is a lock-free queue, since responses are allocated by the
+//! threads of the runtime and freed by the threads of the caller.
+//!
+//! The ownership contract is unchanged: a response is owned by the caller until it is passed to
+//! [`free_respose`](crate::free_respose), after which it may be handed out again as another response.
+
+use std::sync::Arc;
+
+use concurrent_queue::ConcurrentQueue;
+
+use crate::ffi::ResponseValue;
+
+/// The allocation handed out as a response. The response is its first field, so a pointer to it is a pointer to the response.
+#[repr(C)]
+struct PooledResponse {
+    value: ResponseValue,
+    /// The pool the allocation is returned to once it's released, `None` while it's in the pool, or if pooling is disabled.
+    pool: Option<Arc<ResponsePool>>,
+}
+
+/// Free allocations of the responses of a client, see [`ConnectionConfig::response_pool_capacity`](crate::ffi::ConnectionConfig::response_pool_capacity).
+pub(crate) struct ResponsePool {
+    free: ConcurrentQueue<Box<PooledResponse>>,
+}
+
+impl ResponsePool {
+    /// Create a pool holding up to `capacity` free allocations, `None` if `capacity` is `0`.
+    pub(crate) fn new(capacity: usize) -> Option<Arc<Self>> {
+        (capacity > 0).then(|| {
+            Arc::new(ResponsePool {
+                free: ConcurrentQueue::bounded(capacity),
+            })
+        })
+    }
+}
+
+/// Move a response to the heap, reusing an allocation of `pool` if one is available.
+/// The allocation is returned to `pool` once it's released, if a pool is given.
+pub(crate) fn into_raw(
+    value: ResponseValue,
+    pool: Option<&Arc<ResponsePool>>,
+) -> *mut ResponseValue {
+    let boxed = match pool.and_then(|pool| pool.free.pop().ok()) {
+        Some(mut boxed) => {
+            boxed.value = value;
+            boxed.pool = pool.cloned();
+            boxed
+        }
+        None => Box::new(PooledResponse {
+            value,
+            pool: pool.cloned(),
+        }),
+    };
+    Box::into_raw(boxed) as *mut ResponseValue
+}
+
+/// Return an allocation created by [`into_raw`] to its pool, or free it if it has no pool or the pool is full.
+/// The nested memory of the response should be freed beforehand.
+///
+/// # Safety
+/// * `ptr` must be a pointer returned by [`into_raw`], which wasn't released yet.
+pub(crate) unsafe fn release(ptr: *mut ResponseValue) {
+    let mut boxed = unsafe { Box::from_raw(ptr as *mut PooledResponse) };
+    // The pool is taken out, otherwise the allocations in the pool would keep the pool alive
+    if let Some(pool) = boxed.pool.take() {
+        let _ = pool.free.push(boxed);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_released_allocations_are_reused() {
+        let pool = ResponsePool::new(16);
+        let first = into_raw(ResponseValue::default(), pool.as_ref());
+        unsafe { release(first) };
+        let second = into_raw(
+            ResponseValue {
+                val: 42,
+                ..Default::default()
+            },
+            pool.as_ref(),
+        );
+        assert_eq!(first, second);
+        assert_eq!(unsafe { (*second).val }, 42);
+        unsafe { release(second) };
+    }
+
+    #[test]
+    fn test_allocations_are_pooled_per_client() {
+        let pool = ResponsePool::new(1).unwrap();
+        let other_pool = ResponsePool::new(1).unwrap();
+        let first = into_raw(ResponseValue::default(), Some(&pool));
+        let unpooled = into_raw(ResponseValue::default(), None);
+        unsafe { release(first) };
+        unsafe { release(unpooled) };
+        assert_eq!(pool.free.len(), 1);
+        assert_eq!(other_pool.free.len(), 0);
+
+        // The pool is full, so a second allocation is freed
+        let first = into_raw(ResponseValue::default(), Some(&pool));
+        let second = into_raw(ResponseValue::default(), Some(&pool));
+        unsafe { release(first) };
+        unsafe { release(second) };
+        assert_eq!(pool.free.len(), 1);
+        assert_eq!(Arc::strong_count(&pool), 1);
+    }
+}
@@ -0,0 +1,105 @@
+// Copyright Valkey GLIDE Project Contributors - SPDX Identifier: Apache-2.0
+
+//! Custom routing of commands submitted without a route, see [`RouteDeciderCallback`].
+
+use glide_core::request_type::RequestType;
+use redis::{
+    cluster_routing::{RoutingInfo, SingleNodeRoutingInfo},
+    cluster_topology::get_slot,
+    Cmd, ErrorKind, RedisError, RedisResult,
+};
+
+use crate::ffi::RouteInfo;
+
+/// Route decider callback that is called by [`command`](crate::command) for every command submitted without a route in cluster mode,
+/// to replace the default routing of the client, e.g. to pin a tenant's reads to a node.
+///
+/// The arguments are the request type and the key which the command is routed by, or `null` and `0` if the command isn't routed
+/// by a single key, e.g. `PING` or `MGET`. The key isn't prefixed by [`ConnectionConfig::key_prefix`](crate::ffi::ConnectionConfig::key_prefix).
+/// The key is only valid until the callback returns, so it should be copied synchronously.
+///
+/// The callback returns the route of the command, or `null` to route it like any command without a route. The returned
+/// [`RouteInfo`] is read before the callback is called again by the same thread, and it is never freed by the client.
+/// The callback is called synchronously on the thread which submits the command, so it should be cheap and never block.
+pub type RouteDeciderCallback =
+    unsafe extern "C" fn(RequestType, *const u8, u32) -> *const RouteInfo;
+
+/// Call `route_decider` with the request type and the routing key of `cmd`.
+///
+/// # Safety
+/// * `route_decider` must be safe to call with any request type and key.
+pub(crate) unsafe fn decide_route(
+    route_decider: RouteDeciderCallback,
+    request_type: RequestType,
+    cmd: &Cmd,
+) -> *const RouteInfo {
+    let key = RoutingInfo::routing_key(cmd).unwrap_or_default();
+    unsafe { route_decider(request_type, key.as_ptr(), key.len() as u32) }
+}
+
+/// Fail with a `ClientError` if the route returned by the route decider can't be used for `cmd`, rather than misrouting it:
+/// a route to multiple nodes, since the response policy of the command wouldn't apply, or a route to the slot of another key
+/// than the key of the command, which would be redirected by the server. Routes to a node by its address are used as is.
+pub(crate) fn check_decided_route(cmd: &Cmd, route: &RoutingInfo) -> RedisResult<()> {
+    let invalid_route = |detail: String| {
+        Err(RedisError::from((
+            ErrorKind::ClientError,
+            "The route decider returned an invalid route",
+            detail,
+        )))
+    };
+    match route {
+        RoutingInfo::MultiNode(_) => {
+            invalid_route("the route is to multiple nodes, but it must be to a single node".into())
+        }
+        RoutingInfo::SingleNode(SingleNodeRoutingInfo::SpecificNode(route)) => {
+            match RoutingInfo::routing_key(cmd).map(get_slot) {
+                Some(slot) if slot != route.slot() => invalid_route(format!(
+                    "the route is to slot {}, but the key of the command is in slot {slot}",
+                    route.slot()
+                )),
+                _ => Ok(()),
+            }
+        }
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use redis::cluster_routing::{MultipleNodeRoutingInfo, Route, SlotAddr};
+
+    #[test]
+    fn test_check_decided_route() {
+        let mut get = redis::cmd("GET");
+        get.arg("{tenant}key");
+        let slot_route = |key: &[u8]| {
+            RoutingInfo::SingleNode(SingleNodeRoutingInfo::SpecificNode(Route::new(
+                get_slot(key),
+                SlotAddr::ReplicaRequired,
+            )))
+        };
+        assert!(check_decided_route(&get, &slot_route(b"{tenant}other")).is_ok());
+        assert!(check_decided_route(
+            &get,
+            &RoutingInfo::SingleNode(SingleNodeRoutingInfo::ByAddress {
+                host: "node1".to_string(),
+                port: 6379,
+            })
+        )
+        .is_ok());
+
+        let err = check_decided_route(&get, &slot_route(b"other")).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::ClientError);
+        let err = check_decided_route(
+            &get,
+            &RoutingInfo::MultiNode((MultipleNodeRoutingInfo::AllMasters, None)),
+        )
+        .unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::ClientError);
+
+        // Commands without a routing key may be routed to any slot
+        assert!(check_decided_route(&redis::cmd("PING"), &slot_route(b"other")).is_ok());
+    }
+}
@@ -0,0 +1,82 @@
+// Copyright Valkey GLIDE Project Contributors - SPDX Identifier: Apache-2.0
+
+use std::{sync::Mutex, time::Duration};
+
+use tokio::time::Instant;
+
+use crate::{report_event, ClientEventType, EventCallback};
+
+/// Minimal interval between two [`ClientEventType::RuntimeSaturated`] events, so a saturated runtime isn't flooded with events.
+const EVENT_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Detects that the runtime can't keep up with the submitted requests, by the delay between spawning the task of a request
+/// and the start of the task. Reports a [`ClientEventType::RuntimeSaturated`] event at most once per second while the delay
+/// exceeds the threshold, see [`ConnectionConfig::runtime_saturation_threshold`](crate::ffi::ConnectionConfig::runtime_saturation_threshold).
+pub(crate) struct SaturationDetector {
+    threshold: Duration,
+    event_callback: EventCallback,
+    /// Time at which the last event was reported.
+    last_event: Mutex<Option<Instant>>,
+}
+
+impl SaturationDetector {
+    /// Create a detector reporting delays above `threshold_ms` milliseconds, `None` if `threshold_ms` is `0` or there is no event callback.
+    pub(crate) fn new(event_callback: Option<EventCallback>, threshold_ms: u32) -> Option<Self> {
+        let event_callback = event_callback.filter(|_| threshold_ms > 0)?;
+        Some(SaturationDetector {
+            threshold: Duration::from_millis(threshold_ms.into()),
+            event_callback,
+            last_event: Mutex::new(None),
+        })
+    }
+
+    /// Check the scheduling delay of a task spawned at `spawned`, called once the task starts.
+    pub(crate) fn task_started(&self, spawned: Instant) {
+        let now = Instant::now();
+        let delay = now.saturating_duration_since(spawned);
+        if self.should_report(delay, now) {
+            report_event(
+                Some(self.event_callback),
+                ClientEventType::RuntimeSaturated,
+                &delay.as_micros().to_string(),
+            );
+        }
+    }
+
+    /// Whether a delay measured at `now` should be reported, i.e. it exceeds the threshold and no event was reported in the last interval.
+    fn should_report(&self, delay: Duration, now: Instant) -> bool {
+        if delay <= self.threshold {
+            return false;
+        }
+        let mut last_event = self.last_event.lock().unwrap();
+        if last_event.is_some_and(|last_event| now.duration_since(last_event) < EVENT_INTERVAL) {
+            return false;
+        }
+        *last_event = Some(now);
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::ffi::c_char;
+
+    unsafe extern "C" fn ignore_event(_: ClientEventType, _: *const c_char) {}
+
+    #[test]
+    fn test_disabled_without_threshold_or_callback() {
+        assert!(SaturationDetector::new(Some(ignore_event), 0).is_none());
+        assert!(SaturationDetector::new(None, 10).is_none());
+    }
+
+    #[test]
+    fn test_reports_delays_above_threshold_once_per_interval() {
+        let detector = SaturationDetector::new(Some(ignore_event), 10).unwrap();
+        let now = Instant::now();
+        assert!(!detector.should_report(Duration::from_millis(10), now));
+        assert!(detector.should_report(Duration::from_millis(11), now));
+        assert!(!detector.should_report(Duration::from_millis(50), now + EVENT_INTERVAL / 2));
+        assert!(detector.should_report(Duration::from_millis(50), now + EVENT_INTERVAL));
+    }
+}
@@ -0,0 +1,160 @@
+// Copyright Valkey GLIDE Project Contributors - SPDX Identifier: Apache-2.0
+
+//! Loading the scripts cached by glide-core to the nodes ahead of their use, see [`ensure_scripts_loaded`].
+
+use std::{ffi::c_void, sync::Arc};
+
+use glide_core::{client::Client as GlideClient, scripts_container::get_all_scripts};
+use redis::{cluster_routing::RoutingInfo, ErrorKind, RedisError, RedisResult, Value};
+use tokio::task::JoinSet;
+
+use crate::{
+    errors::error_message, open_client_from_ptr, topology::route_to_address, CommandExecutionCore,
+};
+
+/// Check that a node loaded a script by the hash it is cached by, which the script is invoked by.
+fn check_loaded_hash(hash: &str, loaded: Value) -> RedisResult<()> {
+    let loaded = match loaded {
+        Value::BulkString(loaded) => String::from_utf8_lossy(&loaded).to_string(),
+        Value::SimpleString(loaded) => loaded,
+        value => {
+            return Err(RedisError::from((
+                ErrorKind::TypeError,
+                "Unexpected SCRIPT LOAD response",
+                format!("{value:?}"),
+            )))
+        }
+    };
+    if !loaded.eq_ignore_ascii_case(hash) {
+        return Err(RedisError::from((
+            ErrorKind::TypeError,
+            "The node loaded the script by another hash",
+            format!("expected {hash}, got {loaded}"),
+        )));
+    }
+    Ok(())
+}
+
+/// Load `scripts` to the node of `route` one by one, failing on the first script the node didn't load.
+async fn load_scripts(
+    mut client: GlideClient,
+    route: Option<RoutingInfo>,
+    scripts: Arc<Vec<(String, Vec<u8>)>>,
+) -> RedisResult<()> {
+    for (hash, script) in scripts.iter() {
+        let mut cmd = redis::cmd("SCRIPT");
+        cmd.arg("LOAD").arg(script);
+        let loaded = client.send_command(&cmd, route.clone()).await?;
+        check_loaded_hash(hash, loaded)?;
+    }
+    Ok(())
+}
+
+/// Get the nodes to load the scripts to with their routes: the primaries, and the replicas too if `include_replicas`,
+/// refreshing the topology first. In standalone mode the route is `None`, since all commands are sent to the single node.
+async fn script_nodes(
+    core: &CommandExecutionCore,
+    include_replicas: bool,
+) -> RedisResult<Vec<(String, Option<RoutingInfo>)>> {
+    if include_replicas || core.standalone_address.is_some() {
+        return core.all_nodes().await;
+    }
+    core.refresh_topology().await?;
+    let topology = core.topology.read().unwrap().clone();
+    Ok(topology
+        .primaries()
+        .into_iter()
+        .filter_map(|node| Some((node.to_string(), Some(route_to_address(node)?))))
+        .collect())
+}
+
+/// Load every script cached by the client to every node, and report a map of node addresses to `OK`,
+/// or to the error message of nodes which failed to load a script.
+async fn load_scripts_to_all_nodes(
+    core: &CommandExecutionCore,
+    include_replicas: bool,
+) -> RedisResult<Value> {
+    let nodes = script_nodes(core, include_replicas).await?;
+    let scripts: Vec<(String, Vec<u8>)> = get_all_scripts()
+        .into_iter()
+        .map(|(hash, script)| (hash, script.to_vec()))
+        .collect();
+    let scripts = Arc::new(scripts);
+    let mut requests = JoinSet::new();
+    for (node, route) in nodes {
+        let load = load_scripts(core.client_pool.next(), route, scripts.clone());
+        requests.spawn(async move { (node, load.await) });
+    }
+
+    let mut results = Vec::new();
+    while let Some(result) = requests.join_next().await {
+        if let Ok((node, result)) = result {
+            let result = match result {
+                Ok(()) => Value::Okay,
+                Err(err) => {
+                    logger_core::log_warn(
+                        "script cache",
+                        format!("Failed to load the scripts to {node}: {err}"),
+                    );
+                    Value::BulkString(error_message(&err).into_bytes())
+                }
+            };
+            results.push((Value::BulkString(node.into_bytes()), result));
+        }
+    }
+    Ok(Value::Map(results))
+}
+
+/// Load every script cached by the client, i.e. scripts invoked by their hash like the script of [`rate_limit`](crate::rate_limit::rate_limit),
+/// to every primary by `SCRIPT LOAD`, and to the replicas too if `include_replicas` is set, e.g. for scripts routed to replicas.
+/// A node which doesn't have a script fails `EVALSHA` with `NOSCRIPT`, which the client handles by loading the script
+/// and invoking it again, so loading the scripts ahead spares that round trip, e.g. on nodes added by a scale-out.
+/// It should be called on every [`ClientEventType::TopologyChanged`](crate::ClientEventType::TopologyChanged) event.
+/// Loading a script which a node already has is cheap, so nodes which have all the scripts are loaded again harmlessly.
+///
+/// The topology is refreshed first. The response is a map of node addresses to `OK` if the node loaded all the scripts,
+/// or to the error message of the first script it failed to load, e.g. if its script cache was flushed concurrently.
+/// In standalone mode the scripts are loaded to the server. `SCRIPT LOAD` is checked against the command filter of the client first.
+///
+/// # Safety
+/// * `client_ptr` must be a valid pointer created by [`create_client`](crate::create_client), before [`close_client`](crate::close_client) was called.
+#[allow(rustdoc::private_intra_doc_links)]
+#[no_mangle]
+pub unsafe extern "C" fn ensure_scripts_loaded(
+    client_ptr: *const c_void,
+    callback_index: usize,
+    include_replicas: bool,
+) {
+    let Some(client) = (unsafe { open_client_from_ptr(client_ptr, callback_index) }) else {
+        return;
+    };
+    let core = client.core.clone();
+    let mut cmd = redis::cmd("SCRIPT");
+    cmd.arg("LOAD");
+    if let Err(err) = core.check_command_allowed(&cmd) {
+        unsafe { core.report_error(callback_index, err) };
+        return;
+    }
+    client.runtime.spawn(async move {
+        let result = load_scripts_to_all_nodes(&core, include_replicas).await;
+        unsafe { core.report_result(callback_index, result) };
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_checks_the_loaded_hash() {
+        let hash = "e0e1f9fabfc9d4800c877a703b823ac0578ff8db";
+        assert!(check_loaded_hash(hash, Value::BulkString(hash.as_bytes().to_vec())).is_ok());
+        assert!(check_loaded_hash(hash, Value::SimpleString(hash.to_uppercase())).is_ok());
+        assert!(check_loaded_hash(
+            hash,
+            Value::BulkString(b"a9993e364706816aba3e25717850c26c9cd0d89d".to_vec())
+        )
+        .is_err());
+        assert!(check_loaded_hash(hash, Value::Nil).is_err());
+    }
+}
@@ -0,0 +1,201 @@
+// Copyright Valkey GLIDE Project Contributors - SPDX Identifier: Apache-2.0
+
+use std::{collections::BTreeMap, sync::Mutex};
+
+use redis::{ConnectionObserver, Value};
+
+use crate::info::bulk;
+
+/// Fields of the `HELLO` reply which are kept as the capabilities of a node.
+const CAPABILITY_FIELDS: [&str; 4] = ["server", "version", "proto", "modules"];
+
+/// Protocol of connections which were set up without `HELLO`, which is only sent to negotiate RESP3.
+const DEFAULT_PROTOCOL: i64 = 2;
+
+/// Records the capabilities of every node, as reported by `HELLO` while connecting to it, e.g. to decide which commands
+/// are safe to use on a cluster whose nodes run different versions, without another round trip.
+/// The protocol negotiated with every node is recorded as well, including nodes connected without `HELLO`.
+#[derive(Debug, Default)]
+pub(crate) struct ServerCapabilities {
+    nodes: Mutex<BTreeMap<String, Value>>,
+    protocols: Mutex<BTreeMap<String, i64>>,
+}
+
+/// Get the name of a field of a `HELLO` reply.
+fn field_name(key: &Value) -> Option<&str> {
+    match key {
+        Value::BulkString(key) => std::str::from_utf8(key).ok(),
+        Value::SimpleString(key) => Some(key),
+        _ => None,
+    }
+}
+
+/// Keep the [`CAPABILITY_FIELDS`] of a `HELLO` reply, which is a map, or an array of fields and values with older servers.
+fn capabilities(hello: &Value) -> Value {
+    let fields: Vec<(&Value, &Value)> = match hello {
+        Value::Map(fields) => fields.iter().map(|(key, value)| (key, value)).collect(),
+        Value::Array(fields) => fields
+            .chunks_exact(2)
+            .map(|field| (&field[0], &field[1]))
+            .collect(),
+        _ => vec![],
+    };
+    Value::Map(
+        CAPABILITY_FIELDS
+            .iter()
+            .map(|name| {
+                let value = fields
+                    .iter()
+                    .find(|(key, _)| field_name(key) == Some(*name))
+                    .map_or(Value::Nil, |(_, value)| (*value).clone());
+                (bulk(name), value)
+            })
+            .collect(),
+    )
+}
+
+impl ConnectionObserver for ServerCapabilities {
+    fn connected(&self, address: &str, _latency: std::time::Duration) {
+        // Called once the connection is set up, so after `server_hello` if the connection was set up by `HELLO`
+        self.protocols
+            .lock()
+            .unwrap()
+            .entry(address.to_string())
+            .or_insert(DEFAULT_PROTOCOL);
+    }
+
+    fn connection_failed(&self, _address: &str) {}
+
+    fn disconnected(&self, _address: &str) {}
+
+    fn server_hello(&self, address: &str, hello: &Value) {
+        let capabilities = capabilities(hello);
+        let protocol = match &capabilities {
+            Value::Map(fields) => fields.iter().find_map(|(key, value)| match value {
+                Value::Int(proto) if field_name(key) == Some("proto") => Some(*proto),
+                _ => None,
+            }),
+            _ => None,
+        };
+        self.protocols
+            .lock()
+            .unwrap()
+            .insert(address.to_string(), protocol.unwrap_or(DEFAULT_PROTOCOL));
+        self.nodes
+            .lock()
+            .unwrap()
+            .insert(address.to_string(), capabilities);
+    }
+}
+
+impl ServerCapabilities {
+    /// Get the capabilities as a map of the `host:port` address of every node whose connection was set up by `HELLO`,
+    /// for which `include` returns `true`, to a map of:
+    /// * `server` - the name of the server, e.g. `valkey` or `redis`.
+    /// * `version` - the version of the server.
+    /// * `proto` - the protocol negotiated with the node.
+    /// * `modules` - the modules loaded by the server.
+    ///
+    /// The capabilities are the ones reported by the last connection to the node, so they are updated once the node
+    /// is upgraded and the client reconnects.
+    pub(crate) fn to_value(&self, include: impl Fn(&str) -> bool) -> Value {
+        let nodes = self.nodes.lock().unwrap();
+        Value::Map(
+            nodes
+                .iter()
+                .filter(|(address, _)| include(address))
+                .map(|(address, capabilities)| (bulk(address), capabilities.clone()))
+                .collect(),
+        )
+    }
+
+    /// Get the protocol negotiated with every node the client connected to, as a map of the `host:port` address of the node
+    /// to the protocol version, e.g. `3`. Connections set up without `HELLO` use RESP2, so their nodes are mapped to `2`.
+    /// The protocol is the one negotiated by the last connection to the node.
+    pub(crate) fn protocols_to_value(&self) -> Value {
+        let protocols = self.protocols.lock().unwrap();
+        Value::Map(
+            protocols
+                .iter()
+                .map(|(address, protocol)| (bulk(address), Value::Int(*protocol)))
+                .collect(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_records_capabilities_per_node() {
+        let server_capabilities = ServerCapabilities::default();
+        server_capabilities.server_hello(
+            "node1:6379",
+            &Value::Map(vec![
+                (bulk("server"), bulk("valkey")),
+                (bulk("version"), bulk("8.0.1")),
+                (bulk("proto"), Value::Int(3)),
+                (bulk("id"), Value::Int(7)),
+                (bulk("modules"), Value::Array(vec![])),
+            ]),
+        );
+        server_capabilities.server_hello(
+            "node2:6379",
+            &Value::Array(vec![
+                bulk("server"),
+                bulk("redis"),
+                bulk("version"),
+                bulk("7.2.4"),
+            ]),
+        );
+
+        assert_eq!(
+            server_capabilities.to_value(|address| address == "node1:6379"),
+            Value::Map(vec![(
+                bulk("node1:6379"),
+                Value::Map(vec![
+                    (bulk("server"), bulk("valkey")),
+                    (bulk("version"), bulk("8.0.1")),
+                    (bulk("proto"), Value::Int(3)),
+                    (bulk("modules"), Value::Array(vec![])),
+                ])
+            )])
+        );
+        assert_eq!(
+            server_capabilities.to_value(|address| address == "node2:6379"),
+            Value::Map(vec![(
+                bulk("node2:6379"),
+                Value::Map(vec![
+                    (bulk("server"), bulk("redis")),
+                    (bulk("version"), bulk("7.2.4")),
+                    (bulk("proto"), Value::Nil),
+                    (bulk("modules"), Value::Nil),
+                ])
+            )])
+        );
+    }
+
+    #[test]
+    fn test_records_protocol_per_node() {
+        let server_capabilities = ServerCapabilities::default();
+        server_capabilities.server_hello(
+            "node1:6379",
+            &Value::Map(vec![
+                (bulk("server"), bulk("valkey")),
+                (bulk("proto"), Value::Int(3)),
+            ]),
+        );
+        server_capabilities.connected("node1:6379", std::time::Duration::ZERO);
+        // Connected without `HELLO`
+        server_capabilities.connected("node2:6379", std::time::Duration::ZERO);
+
+        assert_eq!(
+            server_capabilities.protocols_to_value(),
+            Value::Map(vec![
+                (bulk("node1:6379"), Value::Int(3)),
+                (bulk("node2:6379"), Value::Int(2)),
+            ])
+        );
+    }
+}
@@ -0,0 +1,78 @@
+// Copyright Valkey GLIDE Project Contributors - SPDX Identifier: Apache-2.0
+
+use redis::{ErrorKind, RedisError, RedisResult, Value};
+
+/// Parse the server version from an `INFO SERVER` response.
+/// Valkey reports its own version in `valkey_version`, and a compatible Redis version in `redis_version`,
+/// so the former is preferred.
+pub(crate) fn parse_server_version(info: Value) -> RedisResult<String> {
+    let info: String = redis::from_owned_redis_value(info)?;
+    let field = |name: &str| {
+        info.lines()
+            .find_map(|line| line.strip_prefix(name)?.strip_prefix(':'))
+            .map(|version| version.trim().to_string())
+    };
+    field("valkey_version")
+        .or_else(|| field("redis_version"))
+        .ok_or_else(|| {
+            RedisError::from((
+                ErrorKind::TypeError,
+                "Server version is missing from the INFO response",
+            ))
+        })
+}
+
+/// Whether `version`, e.g. `7.2.4`, is at least `major.minor`. Components which aren't numbers are considered `0`.
+pub(crate) fn version_at_least(version: &str, major: u32, minor: u32) -> bool {
+    let mut components = version
+        .split('.')
+        .map(|component| component.parse::<u32>().unwrap_or(0));
+    let version = (
+        components.next().unwrap_or(0),
+        components.next().unwrap_or(0),
+    );
+    version >= (major, minor)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_valkey_version() {
+        let info =
+            "# Server\r\nredis_version:7.2.4\r\nserver_name:valkey\r\nvalkey_version:8.0.1\r\n";
+        assert_eq!(
+            parse_server_version(Value::BulkString(info.as_bytes().to_vec())).unwrap(),
+            "8.0.1"
+        );
+    }
+
+    #[test]
+    fn test_parse_redis_version() {
+        let info = "# Server\r\nredis_version:7.0.15\r\nredis_git_sha1:00000000\r\n";
+        assert_eq!(
+            parse_server_version(Value::VerbatimString {
+                format: redis::VerbatimFormat::Text,
+                text: info.to_string(),
+            })
+            .unwrap(),
+            "7.0.15"
+        );
+    }
+
+    #[test]
+    fn test_version_at_least() {
+        assert!(version_at_least("6.2.0", 6, 2));
+        assert!(version_at_least("8.0.1", 6, 2));
+        assert!(!version_at_least("6.0.20", 6, 2));
+        assert!(!version_at_least("5.0.7", 6, 2));
+        assert!(!version_at_least("unknown", 6, 2));
+    }
+
+    #[test]
+    fn test_missing_version() {
+        let info = "# Server\r\nredis_git_sha1:00000000\r\n";
+        assert!(parse_server_version(Value::BulkString(info.as_bytes().to_vec())).is_err());
+    }
+}
@@ -0,0 +1,91 @@
+// Copyright Valkey GLIDE Project Contributors - SPDX Identifier: Apache-2.0
+
+use std::{
+    collections::{hash_map::DefaultHasher, HashMap},
+    hash::{Hash, Hasher},
+    sync::Mutex,
+};
+
+use redis::Cmd;
+
+/// The role of a request in a [`SingleFlight`].
+#[derive(Debug, PartialEq)]
+pub(crate) enum Flight {
+    /// The first of the identical requests, which is sent to the server. Its result must be passed to the
+    /// waiters returned by [`SingleFlight::land`] with the key.
+    Leader(u64),
+    /// An identical request is in flight, and the result of this request is passed on by its leader.
+    Joined,
+    /// A different request with the same hash is in flight, so this request is sent on its own.
+    Alone,
+}
+
+/// The packed command of a request in flight and the callback indices of the requests waiting for it.
+type InflightRequest = (Vec<u8>, Vec<usize>);
+
+/// Coalesces concurrent identical read requests into a single server round trip, whose result is passed to all of them,
+/// see [`ConnectionConfig::deduplicate_reads`](crate::ffi::ConnectionConfig::deduplicate_reads).
+///
+/// Requests are only coalesced while one is in flight, results aren't cached, so a read never observes a value older
+/// than one which was read by a request sent after it was submitted.
+#[derive(Default)]
+pub(crate) struct SingleFlight {
+    /// The requests in flight by the hash of their packed command.
+    inflight: Mutex<HashMap<u64, InflightRequest>>,
+}
+
+impl SingleFlight {
+    /// Join the in-flight request identical to `cmd`, or register `cmd` as the leader if there is none.
+    pub(crate) fn join(&self, cmd: &Cmd, callback_index: usize) -> Flight {
+        let packed = cmd.get_packed_command();
+        let mut hasher = DefaultHasher::new();
+        packed.hash(&mut hasher);
+        let key = hasher.finish();
+        let mut inflight = self.inflight.lock().unwrap();
+        match inflight.get_mut(&key) {
+            Some((inflight_packed, waiters)) if *inflight_packed == packed => {
+                waiters.push(callback_index);
+                Flight::Joined
+            }
+            Some(_) => Flight::Alone,
+            None => {
+                inflight.insert(key, (packed, vec![callback_index]));
+                Flight::Leader(key)
+            }
+        }
+    }
+
+    /// Complete the request of a leader, returns the callback indices of the leader and the requests which joined it.
+    pub(crate) fn land(&self, key: u64) -> Vec<usize> {
+        self.inflight
+            .lock()
+            .unwrap()
+            .remove(&key)
+            .map(|(_, waiters)| waiters)
+            .unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_concurrent_identical_reads_are_coalesced() {
+        let single_flight = SingleFlight::default();
+        let mut get = redis::cmd("GET");
+        get.arg("foo");
+        let Flight::Leader(key) = single_flight.join(&get, 0) else {
+            panic!("first request isn't the leader");
+        };
+        for callback_index in 1..100 {
+            assert_eq!(single_flight.join(&get, callback_index), Flight::Joined);
+        }
+        let mut other = redis::cmd("GET");
+        other.arg("bar");
+        assert!(matches!(single_flight.join(&other, 100), Flight::Leader(_)));
+
+        assert_eq!(single_flight.land(key), (0..100).collect::<Vec<_>>());
+        assert!(matches!(single_flight.join(&get, 101), Flight::Leader(_)));
+    }
+}
@@ -0,0 +1,357 @@
+// Copyright Valkey GLIDE Project Contributors - SPDX Identifier: Apache-2.0
+
+//! Conversion of the `XINFO STREAM` and `XINFO GROUPS` responses, see [`xinfo_stream`](crate::commands::xinfo_stream)
+//! and [`xinfo_groups`](crate::commands::xinfo_groups).
+
+use redis::{ErrorKind, RedisError, RedisResult, Value};
+
+use crate::info::bulk;
+
+fn unexpected_response(value: &Value) -> RedisError {
+    RedisError::from((
+        ErrorKind::TypeError,
+        "Unexpected XINFO response",
+        format!("{value:?}"),
+    ))
+}
+
+/// Convert a simple string to a bulk string, so field names and IDs have the same type in RESP2 and RESP3.
+fn plain(value: Value) -> Value {
+    match value {
+        Value::SimpleString(text) => bulk(&text),
+        value => value,
+    }
+}
+
+/// Convert fields, a flat array of alternating field names and values in RESP2, or a map in RESP3, to a map of field names
+/// to values converted by `convert`, which gets the name of the field. Fields unknown to `convert`, e.g. of newer servers,
+/// should be kept.
+fn convert_fields(
+    value: Value,
+    convert: impl Fn(&[u8], Value) -> RedisResult<Value>,
+) -> RedisResult<Value> {
+    let fields = match value {
+        Value::Map(fields) => fields,
+        Value::Array(items) if items.len() % 2 == 0 => {
+            let mut items = items.into_iter();
+            let mut fields = Vec::new();
+            while let (Some(field), Some(value)) = (items.next(), items.next()) {
+                fields.push((field, value));
+            }
+            fields
+        }
+        value => return Err(unexpected_response(&value)),
+    };
+    fields
+        .into_iter()
+        .map(|(field, value)| {
+            let field = plain(field);
+            let Value::BulkString(name) = &field else {
+                return Err(unexpected_response(&field));
+            };
+            let value = convert(name, value)?;
+            Ok((field, value))
+        })
+        .collect::<RedisResult<_>>()
+        .map(Value::Map)
+}
+
+fn array(value: Value) -> RedisResult<Vec<Value>> {
+    match value {
+        Value::Array(items) => Ok(items),
+        value => Err(unexpected_response(&value)),
+    }
+}
+
+/// Convert an entry, an array of its ID and a flat array of its fields and values, to the ID and an array of
+/// `[field, value]` pairs, like the entries of `XREAD`.
+fn entry(value: Value) -> RedisResult<(Value, Value)> {
+    let [id, fields] = <[Value; 2]>::try_from(array(value)?)
+        .map_err(|items| unexpected_response(&Value::Array(items)))?;
+    let pairs = match fields {
+        Value::Array(fields) if fields.len() % 2 == 0 => {
+            let mut fields = fields.into_iter();
+            let mut pairs = Vec::new();
+            while let (Some(field), Some(value)) = (fields.next(), fields.next()) {
+                pairs.push(Value::Array(vec![field, value]));
+            }
+            pairs
+        }
+        // The fields of an entry are null if it was deleted, e.g. in the pending entries of a consumer
+        Value::Nil => Vec::new(),
+        fields => return Err(unexpected_response(&fields)),
+    };
+    Ok((plain(id), Value::Array(pairs)))
+}
+
+/// Convert entries to a map of entry IDs to arrays of `[field, value]` pairs, in the order of the stream.
+fn entries(value: Value) -> RedisResult<Value> {
+    array(value)?
+        .into_iter()
+        .map(entry)
+        .collect::<RedisResult<_>>()
+        .map(Value::Map)
+}
+
+/// Convert the first or last entry of a stream to a map of a single entry ID to its `[field, value]` pairs,
+/// or null if the stream is empty.
+fn single_entry(value: Value) -> RedisResult<Value> {
+    match value {
+        Value::Nil => Ok(Value::Nil),
+        value => Ok(Value::Map(vec![entry(value)?])),
+    }
+}
+
+/// Convert pending entries, arrays of values, to maps of the given field names to the values.
+fn pending_entries(value: Value, names: &[&str]) -> RedisResult<Value> {
+    array(value)?
+        .into_iter()
+        .map(|pending| {
+            let values = array(pending)?;
+            if values.len() != names.len() {
+                return Err(unexpected_response(&Value::Array(values)));
+            }
+            Ok(Value::Map(
+                names
+                    .iter()
+                    .zip(values)
+                    .map(|(name, value)| (bulk(name), plain(value)))
+                    .collect(),
+            ))
+        })
+        .collect::<RedisResult<_>>()
+        .map(Value::Array)
+}
+
+/// Convert a consumer of a group of `XINFO STREAM FULL`.
+fn consumer(value: Value) -> RedisResult<Value> {
+    convert_fields(value, |name, value| match (name, value) {
+        (b"pending", Value::Array(pending)) => pending_entries(
+            Value::Array(pending),
+            &["id", "delivery-time", "delivery-count"],
+        ),
+        (_, value) => Ok(plain(value)),
+    })
+}
+
+/// Convert a group of `XINFO GROUPS`, where the consumers and the pending entries are counts,
+/// or of `XINFO STREAM FULL`, where they are arrays.
+fn group(value: Value) -> RedisResult<Value> {
+    convert_fields(value, |name, value| match (name, value) {
+        (b"pending", Value::Array(pending)) => pending_entries(
+            Value::Array(pending),
+            &["id", "consumer", "delivery-time", "delivery-count"],
+        ),
+        (b"consumers", Value::Array(consumers)) => consumers
+            .into_iter()
+            .map(consumer)
+            .collect::<RedisResult<_>>()
+            .map(Value::Array),
+        (_, value) => Ok(plain(value)),
+    })
+}
+
+/// Convert an `XINFO GROUPS` response to an array of maps of the fields of every group, e.g. `name`, `pending` and `lag`.
+pub(crate) fn convert_xinfo_groups(value: Value) -> RedisResult<Value> {
+    array(value)?
+        .into_iter()
+        .map(group)
+        .collect::<RedisResult<_>>()
+        .map(Value::Array)
+}
+
+/// Convert an `XINFO STREAM` response, with or without `FULL`, to a map of its fields, e.g. `length` and `last-generated-id`.
+/// Entries are converted to maps of entry IDs to arrays of `[field, value]` pairs: `first-entry` and `last-entry` to maps
+/// of a single entry, or null if the stream is empty, and the `entries` of `FULL` to a map of all returned entries.
+/// The `groups` of `FULL` are converted like [`convert_xinfo_groups`], with arrays of their consumers and pending entries,
+/// which are maps of their fields too, instead of counts.
+pub(crate) fn convert_xinfo_stream(value: Value) -> RedisResult<Value> {
+    convert_fields(value, |name, value| match (name, value) {
+        (b"first-entry" | b"last-entry", value) => single_entry(value),
+        (b"entries", value) => entries(value),
+        (b"groups", Value::Array(groups)) => convert_xinfo_groups(Value::Array(groups)),
+        (_, value) => Ok(plain(value)),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn map(entries: Vec<(&str, Value)>) -> Value {
+        Value::Map(
+            entries
+                .into_iter()
+                .map(|(key, value)| (bulk(key), value))
+                .collect(),
+        )
+    }
+
+    fn raw_entry(id: &str, fields: &[&str]) -> Value {
+        Value::Array(vec![
+            bulk(id),
+            Value::Array(fields.iter().map(|field| bulk(field)).collect()),
+        ])
+    }
+
+    #[test]
+    fn test_convert_resp2_xinfo_stream() {
+        let value = Value::Array(vec![
+            bulk("length"),
+            Value::Int(2),
+            bulk("last-generated-id"),
+            bulk("2-0"),
+            bulk("groups"),
+            Value::Int(1),
+            bulk("first-entry"),
+            raw_entry("1-0", &["field", "value"]),
+            bulk("last-entry"),
+            raw_entry("2-0", &["a", "1", "b", "2"]),
+        ]);
+        let pair = |field: &str, value: &str| Value::Array(vec![bulk(field), bulk(value)]);
+        assert_eq!(
+            convert_xinfo_stream(value).unwrap(),
+            map(vec![
+                ("length", Value::Int(2)),
+                ("last-generated-id", bulk("2-0")),
+                ("groups", Value::Int(1)),
+                (
+                    "first-entry",
+                    map(vec![("1-0", Value::Array(vec![pair("field", "value")]))])
+                ),
+                (
+                    "last-entry",
+                    map(vec![(
+                        "2-0",
+                        Value::Array(vec![pair("a", "1"), pair("b", "2")])
+                    )])
+                ),
+            ])
+        );
+
+        let empty = Value::Map(vec![
+            (Value::SimpleString("length".to_string()), Value::Int(0)),
+            (bulk("first-entry"), Value::Nil),
+        ]);
+        assert_eq!(
+            convert_xinfo_stream(empty).unwrap(),
+            map(vec![("length", Value::Int(0)), ("first-entry", Value::Nil)])
+        );
+    }
+
+    #[test]
+    fn test_convert_xinfo_stream_full() {
+        let consumer = Value::Map(vec![
+            (bulk("name"), bulk("consumer1")),
+            (bulk("seen-time"), Value::Int(1700000000000)),
+            (bulk("pel-count"), Value::Int(1)),
+            (
+                bulk("pending"),
+                Value::Array(vec![Value::Array(vec![
+                    bulk("1-0"),
+                    Value::Int(1700000000000),
+                    Value::Int(1),
+                ])]),
+            ),
+        ]);
+        let group = Value::Map(vec![
+            (bulk("name"), bulk("group1")),
+            (bulk("lag"), Value::Int(1)),
+            (bulk("pel-count"), Value::Int(1)),
+            (
+                bulk("pending"),
+                Value::Array(vec![Value::Array(vec![
+                    bulk("1-0"),
+                    bulk("consumer1"),
+                    Value::Int(1700000000000),
+                    Value::Int(1),
+                ])]),
+            ),
+            (bulk("consumers"), Value::Array(vec![consumer])),
+        ]);
+        let value = Value::Map(vec![
+            (bulk("length"), Value::Int(1)),
+            (
+                bulk("entries"),
+                Value::Array(vec![raw_entry("1-0", &["field", "value"])]),
+            ),
+            (bulk("groups"), Value::Array(vec![group])),
+        ]);
+        assert_eq!(
+            convert_xinfo_stream(value).unwrap(),
+            map(vec![
+                ("length", Value::Int(1)),
+                (
+                    "entries",
+                    map(vec![(
+                        "1-0",
+                        Value::Array(vec![Value::Array(vec![bulk("field"), bulk("value")])])
+                    )])
+                ),
+                (
+                    "groups",
+                    Value::Array(vec![map(vec![
+                        ("name", bulk("group1")),
+                        ("lag", Value::Int(1)),
+                        ("pel-count", Value::Int(1)),
+                        (
+                            "pending",
+                            Value::Array(vec![map(vec![
+                                ("id", bulk("1-0")),
+                                ("consumer", bulk("consumer1")),
+                                ("delivery-time", Value::Int(1700000000000)),
+                                ("delivery-count", Value::Int(1)),
+                            ])])
+                        ),
+                        (
+                            "consumers",
+                            Value::Array(vec![map(vec![
+                                ("name", bulk("consumer1")),
+                                ("seen-time", Value::Int(1700000000000)),
+                                ("pel-count", Value::Int(1)),
+                                (
+                                    "pending",
+                                    Value::Array(vec![map(vec![
+                                        ("id", bulk("1-0")),
+                                        ("delivery-time", Value::Int(1700000000000)),
+                                        ("delivery-count", Value::Int(1)),
+                                    ])])
+                                ),
+                            ])])
+                        ),
+                    ])])
+                ),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_convert_xinfo_groups() {
+        let value = Value::Array(vec![Value::Array(vec![
+            bulk("name"),
+            bulk("group1"),
+            bulk("consumers"),
+            Value::Int(2),
+            bulk("pending"),
+            Value::Int(3),
+            bulk("last-delivered-id"),
+            bulk("3-0"),
+            bulk("lag"),
+            Value::Nil,
+        ])]);
+        assert_eq!(
+            convert_xinfo_groups(value).unwrap(),
+            Value::Array(vec![map(vec![
+                ("name", bulk("group1")),
+                ("consumers", Value::Int(2)),
+                ("pending", Value::Int(3)),
+                ("last-delivered-id", bulk("3-0")),
+                ("lag", Value::Nil),
+            ])])
+        );
+        assert!(
+            convert_xinfo_groups(Value::Array(vec![Value::Array(vec![bulk("name")])])).is_err()
+        );
+        assert!(convert_xinfo_groups(Value::Int(1)).is_err());
+    }
+}
@@ -0,0 +1,60 @@
+// Copyright Valkey GLIDE Project Contributors - SPDX Identifier: Apache-2.0
+
+use redis::{PubSubSubscriptionInfo, PubSubSubscriptionKind, Value};
+
+use crate::info::bulk;
+
+/// Convert the subscriptions of a client to a map of `channels`, `patterns` and `sharded_channels`, each an array of
+/// the subscribed names sorted in byte order. Kinds without subscriptions are empty arrays.
+pub(crate) fn subscriptions_value(subscriptions: Option<&PubSubSubscriptionInfo>) -> Value {
+    let names = |kind: PubSubSubscriptionKind| {
+        let mut names: Vec<Vec<u8>> = subscriptions
+            .and_then(|subscriptions| subscriptions.get(&kind))
+            .map(|names| names.iter().cloned().collect())
+            .unwrap_or_default();
+        names.sort();
+        Value::Array(names.into_iter().map(Value::BulkString).collect())
+    };
+    Value::Map(vec![
+        (bulk("channels"), names(PubSubSubscriptionKind::Exact)),
+        (bulk("patterns"), names(PubSubSubscriptionKind::Pattern)),
+        (
+            bulk("sharded_channels"),
+            names(PubSubSubscriptionKind::Sharded),
+        ),
+    ])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_subscriptions_by_kind() {
+        let empty = Value::Map(vec![
+            (bulk("channels"), Value::Array(vec![])),
+            (bulk("patterns"), Value::Array(vec![])),
+            (bulk("sharded_channels"), Value::Array(vec![])),
+        ]);
+        assert_eq!(subscriptions_value(None), empty);
+
+        let subscriptions = PubSubSubscriptionInfo::from([
+            (
+                PubSubSubscriptionKind::Exact,
+                [b"news".to_vec(), b"alerts".to_vec()].into(),
+            ),
+            (PubSubSubscriptionKind::Pattern, [b"user:*".to_vec()].into()),
+        ]);
+        assert_eq!(
+            subscriptions_value(Some(&subscriptions)),
+            Value::Map(vec![
+                (
+                    bulk("channels"),
+                    Value::Array(vec![bulk("alerts"), bulk("news")])
+                ),
+                (bulk("patterns"), Value::Array(vec![bulk("user:*")])),
+                (bulk("sharded_channels"), Value::Array(vec![])),
+            ])
+        );
+    }
+}
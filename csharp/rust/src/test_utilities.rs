@@ -0,0 +1,477 @@
+// Copyright Valkey GLIDE Project Contributors - SPDX Identifier: Apache-2.0
+
+//! A minimal RESP2 server replying to commands by a handler, and a harness creating clients through the FFI,
+//! so the FFI functions can be tested without a server.
+
+use std::{
+    collections::HashMap,
+    ffi::{c_char, c_void, CStr, CString},
+    io::{ErrorKind, Read, Write},
+    net::{TcpListener, TcpStream},
+    slice::from_raw_parts,
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        mpsc::{channel, Sender},
+        Arc, Condvar, Mutex,
+    },
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+use redis::Value;
+
+use crate::{
+    create_client,
+    errors::RequestErrorType,
+    ffi::{Address, ConnectionConfig, ResponseValue, ValueType},
+    free_respose, SlowCommandCallback, CREATE_CLIENT_CALLBACK_INDEX,
+};
+
+/// Replies to the arguments of a command, the first argument is the name of the command.
+pub(crate) type Handler = dyn Fn(&[Vec<u8>]) -> Value + Send + Sync;
+
+/// Connections of a [`MockServer`].
+#[derive(Default)]
+pub(crate) struct Connections {
+    /// Connections accepted so far.
+    pub(crate) accepted: AtomicUsize,
+    /// Connections closed so far, by the server or by the client.
+    pub(crate) closed: AtomicUsize,
+}
+
+/// A server listening on a port of the loopback interface, which serves every connection on its own thread.
+pub(crate) struct MockServer {
+    listener: TcpListener,
+}
+
+impl MockServer {
+    pub(crate) fn bind() -> Self {
+        MockServer {
+            listener: TcpListener::bind("127.0.0.1:0").unwrap(),
+        }
+    }
+
+    pub(crate) fn port(&self) -> u16 {
+        self.listener.local_addr().unwrap().port()
+    }
+
+    /// Serve the connections until the test process exits. The commands setting up a connection are replied by the server,
+    /// other commands by `handler`. A connection is closed once `QUIT` is replied. After `MONITOR` a connection receives
+    /// the commands of the other connections, until it sends `RESET`.
+    pub(crate) fn serve(self, handler: Arc<Handler>) -> Arc<Connections> {
+        self.serve_connections(handler, Arc::new(AtomicBool::new(false)), None)
+    }
+
+    /// Serve the connections like [`Self::serve`], but while `down` is set, the open connections are closed,
+    /// and new connections are closed right after they're accepted, like a node which is down.
+    /// If `handler` sets `down`, the command isn't replied, like a node which died while serving it.
+    pub(crate) fn serve_with_outage(
+        self,
+        handler: Arc<Handler>,
+        down: Arc<AtomicBool>,
+    ) -> Arc<Connections> {
+        self.serve_connections(handler, down, None)
+    }
+
+    /// Serve the connections like [`Self::serve`], but close connections which received no command for `idle_timeout`,
+    /// like a proxy.
+    pub(crate) fn serve_with_idle_timeout(
+        self,
+        handler: Arc<Handler>,
+        idle_timeout: Duration,
+    ) -> Arc<Connections> {
+        self.serve_connections(
+            handler,
+            Arc::new(AtomicBool::new(false)),
+            Some(idle_timeout),
+        )
+    }
+
+    fn serve_connections(
+        self,
+        handler: Arc<Handler>,
+        down: Arc<AtomicBool>,
+        idle_timeout: Option<Duration>,
+    ) -> Arc<Connections> {
+        let connections = Arc::new(Connections::default());
+        let served_connections = connections.clone();
+        let monitors = Arc::new(Monitors::default());
+        std::thread::spawn(move || {
+            for stream in self.listener.incoming() {
+                let Ok(stream) = stream else {
+                    break;
+                };
+                if down.load(Ordering::Acquire) {
+                    continue;
+                }
+                served_connections.accepted.fetch_add(1, Ordering::Relaxed);
+                let handler = handler.clone();
+                let down = down.clone();
+                let connections = served_connections.clone();
+                let monitors = monitors.clone();
+                std::thread::spawn(move || {
+                    serve_connection(stream, &*handler, &down, &monitors, idle_timeout);
+                    connections.closed.fetch_add(1, Ordering::Relaxed);
+                });
+            }
+        });
+        connections
+    }
+}
+
+/// The connections of a server in `MONITOR` mode, which are sent the commands formatted like by `MONITOR`.
+type Monitors = Mutex<Vec<Sender<String>>>;
+
+fn serve_connection(
+    mut stream: TcpStream,
+    handler: &Handler,
+    down: &AtomicBool,
+    monitors: &Monitors,
+    idle_timeout: Option<Duration>,
+) {
+    let peer = stream.peer_addr().unwrap();
+    let mut buffer = Vec::new();
+    let mut chunk = [0; 4096];
+    // Reads time out, so the connection is closed soon after the server goes down
+    stream
+        .set_read_timeout(Some(Duration::from_millis(10)))
+        .unwrap();
+    let mut last_read = Instant::now();
+    loop {
+        if down.load(Ordering::Acquire)
+            || idle_timeout.is_some_and(|idle_timeout| last_read.elapsed() > idle_timeout)
+        {
+            return;
+        }
+        let read = match stream.read(&mut chunk) {
+            Ok(read @ 1..) => read,
+            Err(err) if matches!(err.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut) => {
+                continue
+            }
+            _ => return,
+        };
+        last_read = Instant::now();
+        buffer.extend_from_slice(&chunk[..read]);
+        let mut reply = Vec::new();
+        let (mut quit, mut monitor) = (false, false);
+        while let Some((args, consumed)) = parse_command(&buffer) {
+            buffer.drain(..consumed);
+            if args[0].eq_ignore_ascii_case(b"QUIT") {
+                encode(&Value::Okay, &mut reply);
+                quit = true;
+                break;
+            }
+            if args[0].eq_ignore_ascii_case(b"MONITOR") {
+                encode(&Value::Okay, &mut reply);
+                monitor = true;
+                break;
+            }
+            let time = SystemTime::now().duration_since(UNIX_EPOCH).unwrap();
+            let args_line: Vec<String> = args
+                .iter()
+                .map(|arg| format!("{:?}", String::from_utf8_lossy(arg)))
+                .collect();
+            let line = format!(
+                "{}.{:06} [0 {peer}] {}",
+                time.as_secs(),
+                time.subsec_micros(),
+                args_line.join(" ")
+            );
+            monitors
+                .lock()
+                .unwrap()
+                .retain(|monitor| monitor.send(line.clone()).is_ok());
+            let value = setup_reply(&args).unwrap_or_else(|| handler(&args));
+            encode(&value, &mut reply);
+        }
+        if down.load(Ordering::Acquire) || stream.write_all(&reply).is_err() || quit {
+            return;
+        }
+        if monitor {
+            if !stream_monitor(&mut stream, down, monitors) {
+                return;
+            }
+            buffer.clear();
+            last_read = Instant::now();
+        }
+    }
+}
+
+/// Send the commands of the other connections to `stream` until it sends `RESET`, which is replied.
+/// Returns whether the connection is still open.
+fn stream_monitor(stream: &mut TcpStream, down: &AtomicBool, monitors: &Monitors) -> bool {
+    let (sender, receiver) = channel();
+    monitors.lock().unwrap().push(sender);
+    let mut buffer = Vec::new();
+    let mut chunk = [0; 4096];
+    loop {
+        if down.load(Ordering::Acquire) {
+            return false;
+        }
+        for line in receiver.try_iter() {
+            if stream.write_all(format!("+{line}\r\n").as_bytes()).is_err() {
+                return false;
+            }
+        }
+        let read = match stream.read(&mut chunk) {
+            Ok(read @ 1..) => read,
+            Err(err) if matches!(err.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut) => {
+                continue
+            }
+            _ => return false,
+        };
+        buffer.extend_from_slice(&chunk[..read]);
+        while let Some((args, consumed)) = parse_command(&buffer) {
+            buffer.drain(..consumed);
+            if args[0].eq_ignore_ascii_case(b"RESET") {
+                return stream.write_all(b"+RESET\r\n").is_ok();
+            }
+        }
+    }
+}
+
+/// Reply to the commands glide-core sends to set up a connection.
+fn setup_reply(args: &[Vec<u8>]) -> Option<Value> {
+    let name = String::from_utf8_lossy(args.first()?).to_ascii_uppercase();
+    let subcommand = args
+        .get(1)
+        .map(|arg| String::from_utf8_lossy(arg).to_ascii_uppercase());
+    match (name.as_str(), subcommand.as_deref()) {
+        ("PING", _) => Some(Value::SimpleString("PONG".to_string())),
+        ("CLIENT", Some("SETINFO" | "SETNAME")) | ("SELECT" | "READONLY", _) => Some(Value::Okay),
+        _ => None,
+    }
+}
+
+/// Reply of a command the handler doesn't know, e.g. a command glide-core sends which a test doesn't expect.
+pub(crate) fn unknown_command(args: &[Vec<u8>]) -> Value {
+    let error = format!(
+        "-ERR unknown command '{}'\r\n",
+        String::from_utf8_lossy(&args[0])
+    );
+    redis::parse_redis_value(error.as_bytes()).unwrap()
+}
+
+/// Parse a command sent as an array of bulk strings, returns its arguments and the length of the command in `buffer`,
+/// or `None` if the command isn't complete yet.
+fn parse_command(buffer: &[u8]) -> Option<(Vec<Vec<u8>>, usize)> {
+    fn line(buffer: &[u8], start: usize, prefix: u8) -> Option<(usize, usize)> {
+        let end = start + buffer.get(start..)?.windows(2).position(|w| w == b"\r\n")?;
+        assert_eq!(buffer[start], prefix, "Unexpected RESP type");
+        let number = std::str::from_utf8(&buffer[start + 1..end])
+            .ok()?
+            .parse()
+            .ok()?;
+        Some((number, end + 2))
+    }
+    let (count, mut position) = line(buffer, 0, b'*')?;
+    let mut args = Vec::with_capacity(count);
+    for _ in 0..count {
+        let (len, start) = line(buffer, position, b'$')?;
+        args.push(buffer.get(start..start + len)?.to_vec());
+        position = start + len + 2;
+    }
+    (buffer.len() >= position).then_some((args, position))
+}
+
+fn encode(value: &Value, out: &mut Vec<u8>) {
+    match value {
+        Value::Nil => out.extend_from_slice(b"$-1\r\n"),
+        Value::Okay => out.extend_from_slice(b"+OK\r\n"),
+        Value::Int(int) => out.extend_from_slice(format!(":{int}\r\n").as_bytes()),
+        Value::SimpleString(text) => out.extend_from_slice(format!("+{text}\r\n").as_bytes()),
+        Value::BulkString(bytes) => {
+            out.extend_from_slice(format!("${}\r\n", bytes.len()).as_bytes());
+            out.extend_from_slice(bytes);
+            out.extend_from_slice(b"\r\n");
+        }
+        Value::Array(values) => {
+            out.extend_from_slice(format!("*{}\r\n", values.len()).as_bytes());
+            for value in values {
+                encode(value, out);
+            }
+        }
+        Value::ServerError(err) => out.extend_from_slice(
+            format!(
+                "-{} {}\r\n",
+                err.err_code(),
+                err.details().unwrap_or_default()
+            )
+            .as_bytes(),
+        ),
+        value => panic!("Encoding {value:?} isn't supported"),
+    }
+}
+
+/// Reply of `CLUSTER SLOTS` of a cluster of primaries without replicas on the given ports of the loopback interface,
+/// which serve equal ranges of the slots, in the order of the ports.
+pub(crate) fn cluster_slots(ports: &[u16]) -> Value {
+    let slots_per_node = 16384 / ports.len();
+    Value::Array(
+        ports
+            .iter()
+            .enumerate()
+            .map(|(index, port)| {
+                let start = index * slots_per_node;
+                let end = match index + 1 == ports.len() {
+                    true => 16383,
+                    false => start + slots_per_node - 1,
+                };
+                Value::Array(vec![
+                    Value::Int(start as i64),
+                    Value::Int(end as i64),
+                    Value::Array(vec![
+                        Value::BulkString(b"127.0.0.1".to_vec()),
+                        Value::Int((*port).into()),
+                        Value::BulkString(format!("node{port}").into_bytes()),
+                    ]),
+                ])
+            })
+            .collect(),
+    )
+}
+
+/// The result of a request, as passed to the success or the failure callback.
+pub(crate) type CallbackResult = Result<Value, (RequestErrorType, String)>;
+
+static RESULTS: Mutex<Option<HashMap<usize, CallbackResult>>> = Mutex::new(None);
+static RESULT_ARRIVED: Condvar = Condvar::new();
+static NEXT_CALLBACK_INDEX: AtomicUsize = AtomicUsize::new(0);
+
+/// Get a callback index which isn't used by any other test, since the callbacks are shared by all clients.
+pub(crate) fn next_callback_index() -> usize {
+    NEXT_CALLBACK_INDEX.fetch_add(1, Ordering::Relaxed)
+}
+
+/// Wait for the result of the request with the given callback index.
+pub(crate) fn wait_for_result(callback_index: usize) -> CallbackResult {
+    let results = RESULTS.lock().unwrap();
+    let (mut results, timeout) = RESULT_ARRIVED
+        .wait_timeout_while(results, Duration::from_secs(10), |results| {
+            !results
+                .as_ref()
+                .is_some_and(|results| results.contains_key(&callback_index))
+        })
+        .unwrap();
+    assert!(
+        !timeout.timed_out(),
+        "Request {callback_index} didn't complete"
+    );
+    results.as_mut().unwrap().remove(&callback_index).unwrap()
+}
+
+/// Whether the result of the request with the given callback index arrived, without waiting for it.
+pub(crate) fn has_result(callback_index: usize) -> bool {
+    RESULTS
+        .lock()
+        .unwrap()
+        .as_ref()
+        .is_some_and(|results| results.contains_key(&callback_index))
+}
+
+fn store_result(callback_index: usize, result: CallbackResult) {
+    RESULTS
+        .lock()
+        .unwrap()
+        .get_or_insert_with(HashMap::new)
+        .insert(callback_index, result);
+    RESULT_ARRIVED.notify_all();
+}
+
+unsafe extern "C" fn success_callback(callback_index: usize, response: *const ResponseValue) {
+    let value = unsafe { to_value(&*response) };
+    unsafe { free_respose(response as *mut ResponseValue) };
+    store_result(callback_index, Ok(value));
+}
+
+unsafe extern "C" fn failure_callback(
+    callback_index: usize,
+    error_type: RequestErrorType,
+    message: *const c_char,
+) {
+    let message = unsafe { CStr::from_ptr(message) }
+        .to_string_lossy()
+        .to_string();
+    store_result(callback_index, Err((error_type, message)));
+}
+
+/// Convert a response back to a [`Value`], the types which aren't produced by the mock server aren't supported.
+unsafe fn to_value(response: &ResponseValue) -> Value {
+    let bytes = || unsafe { from_raw_parts(response.val as *const u8, response.size as usize) };
+    let values =
+        || unsafe { from_raw_parts(response.val as *const ResponseValue, response.size as usize) };
+    match response.typ {
+        ValueType::Null => Value::Nil,
+        ValueType::OK => Value::Okay,
+        ValueType::Int => Value::Int(response.val),
+        ValueType::Bool => Value::Boolean(response.val != 0),
+        ValueType::BulkString => Value::BulkString(bytes().to_vec()),
+        ValueType::String => Value::SimpleString(String::from_utf8_lossy(bytes()).to_string()),
+        ValueType::Array | ValueType::Error => Value::Array(
+            values()
+                .iter()
+                .map(|value| unsafe { to_value(value) })
+                .collect(),
+        ),
+        ValueType::Map => Value::Map(
+            values()
+                .chunks(2)
+                .map(|pair| unsafe { (to_value(&pair[0]), to_value(&pair[1])) })
+                .collect(),
+        ),
+        ref typ => panic!("Converting {typ:?} isn't supported"),
+    }
+}
+
+/// Create a client through [`create_client`] connected to the mock servers on the given ports, with the RESP2 protocol.
+/// `configure` could change the rest of the configuration, which is zeroed, i.e. all features are disabled.
+pub(crate) fn create_test_client(
+    ports: &[u16],
+    cluster_mode: bool,
+    configure: impl FnOnce(&mut ConnectionConfig),
+) -> *const c_void {
+    create_test_client_with_slow_command_callback(ports, cluster_mode, configure, None)
+}
+
+/// Create a client like [`create_test_client`], which reports slow commands through `slow_command_callback`.
+pub(crate) fn create_test_client_with_slow_command_callback(
+    ports: &[u16],
+    cluster_mode: bool,
+    configure: impl FnOnce(&mut ConnectionConfig),
+    slow_command_callback: Option<SlowCommandCallback>,
+) -> *const c_void {
+    let host = CString::new("127.0.0.1").unwrap();
+    let addresses: Vec<Address> = ports
+        .iter()
+        .map(|&port| Address {
+            host: host.as_ptr(),
+            port,
+        })
+        .collect();
+    // All fields are integers, booleans, pointers, optional functions or enums with a variant of `0`
+    let mut config: ConnectionConfig = unsafe { std::mem::zeroed() };
+    config.address_count = addresses.len();
+    // The addresses are read as consecutive structs, see `convert_node_addresses`
+    config.addresses = addresses.as_ptr() as *const *const Address;
+    config.cluster_mode = cluster_mode;
+    config.has_protocol = true;
+    config.protocol = redis::ProtocolVersion::RESP2;
+    configure(&mut config);
+    let client = unsafe {
+        create_client(
+            &config,
+            success_callback,
+            failure_callback,
+            None,
+            slow_command_callback,
+            None,
+            None,
+        )
+    };
+    if client.is_null() {
+        panic!(
+            "Failed to create a client: {:?}",
+            wait_for_result(CREATE_CLIENT_CALLBACK_INDEX)
+        );
+    }
+    client
+}
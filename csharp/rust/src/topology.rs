@@ -0,0 +1,136 @@
+// Copyright Valkey GLIDE Project Contributors - SPDX Identifier: Apache-2.0
+
+use redis::{
+    cluster_routing::{RoutingInfo, SingleNodeRoutingInfo},
+    ErrorKind, RedisError, RedisResult, Value,
+};
+
+/// A range of slots and the nodes serving it, as reported by `CLUSTER SLOTS`.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct SlotRange {
+    pub(crate) start: u16,
+    pub(crate) end: u16,
+    pub(crate) primary: String,
+    pub(crate) replicas: Vec<String>,
+}
+
+/// A snapshot of the cluster topology, used to attribute commands to nodes.
+/// It is copied from the slot map glide-core routes commands by after `MOVED` redirections and periodically,
+/// so it might be slightly behind it.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct Topology {
+    pub(crate) ranges: Vec<SlotRange>,
+}
+
+impl Topology {
+    /// Parse a `CLUSTER SLOTS` response.
+    pub(crate) fn from_cluster_slots(value: Value) -> RedisResult<Self> {
+        let Value::Array(ranges) = value else {
+            return Err(invalid_response("CLUSTER SLOTS response is not an array"));
+        };
+        let ranges = ranges
+            .into_iter()
+            .map(|range| {
+                let Value::Array(range) = range else {
+                    return Err(invalid_response("slot range is not an array"));
+                };
+                let mut items = range.into_iter();
+                let (Some(Value::Int(start)), Some(Value::Int(end)), Some(primary)) =
+                    (items.next(), items.next(), items.next())
+                else {
+                    return Err(invalid_response("slot range is incomplete"));
+                };
+                Ok(SlotRange {
+                    start: start as u16,
+                    end: end as u16,
+                    primary: node_address(primary)?,
+                    replicas: items.map(node_address).collect::<RedisResult<_>>()?,
+                })
+            })
+            .collect::<RedisResult<_>>()?;
+        Ok(Topology { ranges })
+    }
+
+    /// Get the range of slots containing the given slot.
+    pub(crate) fn range_for_slot(&self, slot: u16) -> Option<&SlotRange> {
+        self.ranges
+            .iter()
+            .find(|range| range.start <= slot && slot <= range.end)
+    }
+
+    /// Get the address of the primary serving the given slot.
+    pub(crate) fn primary_for_slot(&self, slot: u16) -> Option<&str> {
+        self.range_for_slot(slot)
+            .map(|range| range.primary.as_str())
+    }
+
+    /// Get the addresses of the primaries, in the order of their slot ranges.
+    pub(crate) fn primaries(&self) -> Vec<&str> {
+        let mut primaries: Vec<&str> = Vec::new();
+        for range in &self.ranges {
+            if !primaries.contains(&range.primary.as_str()) {
+                primaries.push(&range.primary);
+            }
+        }
+        primaries
+    }
+
+    /// Get the addresses of all primaries and replicas.
+    pub(crate) fn nodes(&self) -> Vec<String> {
+        let mut nodes: Vec<String> = self
+            .ranges
+            .iter()
+            .flat_map(|range| std::iter::once(&range.primary).chain(&range.replicas))
+            .cloned()
+            .collect();
+        nodes.sort();
+        nodes.dedup();
+        nodes
+    }
+
+    /// Get the address of the node a command with the given routing is sent to, if it is a single known node.
+    /// Commands routed to a replica are attributed to the primary of the shard.
+    pub(crate) fn node_for_route(&self, routing: &RoutingInfo) -> Option<String> {
+        match routing {
+            RoutingInfo::SingleNode(SingleNodeRoutingInfo::SpecificNode(route)) => {
+                self.primary_for_slot(route.slot()).map(str::to_string)
+            }
+            RoutingInfo::SingleNode(SingleNodeRoutingInfo::ByAddress { host, port }) => {
+                Some(format!("{host}:{port}"))
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Create a route to the node with the given `host:port` address.
+pub(crate) fn route_to_address(address: &str) -> Option<RoutingInfo> {
+    let (host, port) = address.rsplit_once(':')?;
+    Some(RoutingInfo::SingleNode(SingleNodeRoutingInfo::ByAddress {
+        host: host.to_string(),
+        port: port.parse().ok()?,
+    }))
+}
+
+/// Parse a node entry of a slot range - `[host, port, id, ...]` into a `host:port` address.
+fn node_address(node: Value) -> RedisResult<String> {
+    let Value::Array(node) = node else {
+        return Err(invalid_response("node entry is not an array"));
+    };
+    let mut items = node.into_iter();
+    match (items.next(), items.next()) {
+        (Some(Value::BulkString(host)), Some(Value::Int(port))) => {
+            Ok(format!("{}:{port}", String::from_utf8_lossy(&host)))
+        }
+        (Some(Value::SimpleString(host)), Some(Value::Int(port))) => Ok(format!("{host}:{port}")),
+        _ => Err(invalid_response("node entry is incomplete")),
+    }
+}
+
+fn invalid_response(detail: &str) -> RedisError {
+    RedisError::from((
+        ErrorKind::TypeError,
+        "Unexpected CLUSTER SLOTS response",
+        detail.to_string(),
+    ))
+}
@@ -0,0 +1,267 @@
+// Copyright Valkey GLIDE Project Contributors - SPDX Identifier: Apache-2.0
+
+//! Commands routed by a fixed snapshot of the cluster topology rather than by the live topology, e.g. for all commands
+//! of a phase of a migration, so it is known which node served every slot. A command whose slot moved since the snapshot
+//! was taken fails instead of following the slot to its new node.
+
+use std::{
+    ffi::{c_char, c_void, CString},
+    sync::{atomic::Ordering, Arc, Weak},
+};
+
+use glide_core::request_type::RequestType;
+use redis::{
+    cluster_routing::{RoutingInfo, SingleNodeRoutingInfo},
+    Cmd, ErrorKind, RedisError, RedisResult,
+};
+
+use crate::{
+    client_from_ptr, create_command,
+    errors::{RequestErrorType, SLOT_MOVED_CODE},
+    execute_command,
+    topology::{route_to_address, Topology},
+    Client, FailureCallback,
+};
+
+/// A snapshot of the topology of a client, created by [`snapshot_topology`].
+struct TopologySnapshot {
+    client: Weak<Client>,
+    failure_callback: FailureCallback,
+    topology: Arc<Topology>,
+}
+
+fn slot_moved(detail: String) -> RedisError {
+    redis::make_extension_error(SLOT_MOVED_CODE.to_string(), Some(detail))
+}
+
+impl TopologySnapshot {
+    /// Get the route to the primary which served the slot of the command when the snapshot was taken.
+    /// Fails with a [`RequestErrorType::SlotMoved`] error if the live topology, as last refreshed by the client,
+    /// already reports another primary for the slot, and with a client error if the keys of the command aren't in a single slot.
+    fn route_for(&self, cmd: &Cmd, live: &Topology) -> RedisResult<RoutingInfo> {
+        let Some(RoutingInfo::SingleNode(SingleNodeRoutingInfo::SpecificNode(route))) =
+            RoutingInfo::for_routable(cmd)
+        else {
+            return Err(RedisError::from((
+                ErrorKind::ClientError,
+                "Only commands with keys in a single slot can be routed by a topology snapshot",
+            )));
+        };
+        let slot = route.slot();
+        let Some(primary) = self.topology.primary_for_slot(slot) else {
+            return Err(slot_moved(format!(
+                "Slot {slot} isn't served by any node of the topology snapshot"
+            )));
+        };
+        if let Some(live_primary) = live
+            .primary_for_slot(slot)
+            .filter(|live_primary| *live_primary != primary)
+        {
+            return Err(slot_moved(format!(
+                "Slot {slot} moved from {primary} to {live_primary} since the topology snapshot was taken"
+            )));
+        }
+        route_to_address(primary).ok_or_else(|| {
+            RedisError::from((
+                ErrorKind::ClientError,
+                "Invalid node address in the topology snapshot",
+                primary.to_string(),
+            ))
+        })
+    }
+}
+
+/// Take a snapshot of the slot map of a cluster client, for [`command_with_topology`]. The topology is refreshed first,
+/// so the snapshot reflects the cluster when it is taken. Returns `null` in standalone mode, if the client was closed,
+/// or if the topology can't be refreshed. The snapshot must be freed by [`free_topology_snapshot`].
+///
+/// A snapshot is never refreshed, so it goes stale once slots are migrated or a failover happens. Commands routed by
+/// a stale snapshot fail rather than reach the node which serves their slot now, so a long-lived snapshot fails more and
+/// more commands, and a snapshot taken mid-migration fails the commands on the slots being migrated. Take a new snapshot
+/// for every phase which needs a fixed topology, and use [`command`](crate::command) for everything else.
+///
+/// # Safety
+/// * `client_ptr` must not be `null`.
+/// * `client_ptr` must be a valid pointer created by [`create_client`](crate::create_client), before [`close_client`](crate::close_client) was called with the pointer.
+#[no_mangle]
+pub unsafe extern "C" fn snapshot_topology(client_ptr: *const c_void) -> *const c_void {
+    let Some(client) = (unsafe { client_from_ptr(client_ptr) }) else {
+        return std::ptr::null();
+    };
+    if client.core.standalone_address.is_some() {
+        return std::ptr::null();
+    }
+    if let Err(err) = client.runtime.block_on(client.core.refresh_topology()) {
+        logger_core::log_warn(
+            "topology snapshot",
+            format!("Failed to refresh topology: {err}"),
+        );
+        return std::ptr::null();
+    }
+    let topology = client.core.topology.read().unwrap().clone();
+    Box::into_raw(Box::new(TopologySnapshot {
+        client: Arc::downgrade(&client),
+        failure_callback: client.core.failure_callback,
+        topology,
+    })) as *const c_void
+}
+
+/// Execute a command, like [`command`](crate::command), on the primary which served the slot of its keys when the snapshot
+/// was taken, even if the client has since learned that the slot moved. The command must have keys in a single slot.
+///
+/// The command fails with [`RequestErrorType::SlotMoved`] rather than being re-routed if the slot moved: right away if
+/// the live topology of the client already reports another primary for the slot, otherwise once the node replies with
+/// `MOVED` or `ASK`, which isn't followed. The command isn't retried on any other error either, see `raw_no_retry_no_wait`
+/// of [`command`](crate::command), so every failure is reported against the node of the snapshot.
+/// Fails with [`RequestErrorType::ClientClosed`] once the client is closed, also if it was already deallocated.
+///
+/// # Safety
+/// * `snapshot_ptr` must not be `null`.
+/// * `snapshot_ptr` must be a pointer returned by [`snapshot_topology`], before [`free_topology_snapshot`] was called with the pointer.
+/// * `args` must point to `arg_count` consecutive string pointers and `args_len` must point to their lengths.
+///   See the safety documentation of [`convert_double_pointer_to_vec`](crate::ffi::convert_double_pointer_to_vec).
+#[no_mangle]
+pub unsafe extern "C" fn command_with_topology(
+    snapshot_ptr: *const c_void,
+    callback_index: usize,
+    request_type: RequestType,
+    args: *const *mut c_char,
+    arg_count: u32,
+    args_len: *const u32,
+) {
+    let snapshot = unsafe { &*(snapshot_ptr as *const TopologySnapshot) };
+    let Some(client) = snapshot
+        .client
+        .upgrade()
+        .filter(|client| !client.closed.load(Ordering::Acquire))
+    else {
+        let message = CString::new("Client was closed").unwrap_or_default();
+        unsafe {
+            (snapshot.failure_callback)(
+                callback_index,
+                RequestErrorType::ClientClosed,
+                message.as_ptr(),
+            )
+        };
+        return;
+    };
+    let core = client.core.clone();
+    if let Err(err) =
+        unsafe { core.check_argument_sizes(|| format!("{request_type:?}"), args_len, arg_count) }
+    {
+        unsafe { core.report_error(callback_index, err) };
+        return;
+    }
+    let cmd = match unsafe {
+        create_command(
+            &request_type,
+            args,
+            arg_count,
+            args_len,
+            core.log_non_utf8_arguments,
+        )
+    } {
+        Ok(cmd) => cmd,
+        Err(err) => {
+            unsafe { core.report_error(callback_index, err) };
+            return;
+        }
+    };
+    core.command_counts.increment(request_type);
+    let (cmd, decode) = match core.encode_values(cmd) {
+        Ok(encoded) => encoded,
+        Err(err) => {
+            unsafe { core.report_error(callback_index, err) };
+            return;
+        }
+    };
+    // The route of the keys is replaced by the route of the snapshot
+    let (mut cmd, _, convert) = unsafe { core.prefix_keys(cmd, std::ptr::null()) };
+    let live_topology = core.topology.read().unwrap().clone();
+    let route = match snapshot.route_for(&cmd, &live_topology) {
+        Ok(route) => route,
+        Err(err) => {
+            unsafe { core.report_error(callback_index, err) };
+            return;
+        }
+    };
+    // Redirections aren't followed, so a moved slot is reported rather than served by another node
+    cmd.set_no_retry(true);
+    unsafe {
+        execute_command(
+            &client,
+            callback_index,
+            cmd,
+            Some(route),
+            None,
+            convert.or(decode),
+        )
+    };
+}
+
+/// Free a snapshot taken by [`snapshot_topology`]. Commands submitted with the snapshot before are still answered.
+///
+/// # Safety
+/// * `snapshot_ptr` must not be `null`.
+/// * `snapshot_ptr` must be a pointer returned by [`snapshot_topology`], which wasn't freed yet.
+#[no_mangle]
+pub unsafe extern "C" fn free_topology_snapshot(snapshot_ptr: *const c_void) {
+    drop(unsafe { Box::from_raw(snapshot_ptr as *mut TopologySnapshot) });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::topology::SlotRange;
+
+    unsafe extern "C" fn failure_callback(_: usize, _: RequestErrorType, _: *const c_char) {}
+
+    fn topology(primary: &str) -> Topology {
+        Topology {
+            ranges: vec![SlotRange {
+                start: 0,
+                end: 16383,
+                primary: primary.to_string(),
+                replicas: vec![],
+            }],
+        }
+    }
+
+    fn snapshot(primary: &str) -> TopologySnapshot {
+        TopologySnapshot {
+            client: Weak::new(),
+            failure_callback,
+            topology: Arc::new(topology(primary)),
+        }
+    }
+
+    #[test]
+    fn test_routes_by_the_snapshot() {
+        let snapshot = snapshot("node1:6379");
+        let mut get = redis::cmd("GET");
+        get.arg("key");
+        assert_eq!(
+            snapshot.route_for(&get, &topology("node1:6379")).unwrap(),
+            route_to_address("node1:6379").unwrap()
+        );
+        // The live topology might not be known yet
+        assert_eq!(
+            snapshot.route_for(&get, &Topology::default()).unwrap(),
+            route_to_address("node1:6379").unwrap()
+        );
+        let err = snapshot.route_for(&redis::cmd("PING"), &Topology::default());
+        assert_eq!(err.unwrap_err().kind(), ErrorKind::ClientError);
+    }
+
+    #[test]
+    fn test_fails_commands_whose_slot_moved() {
+        let snapshot = snapshot("node1:6379");
+        let mut get = redis::cmd("GET");
+        get.arg("key");
+        let err = snapshot
+            .route_for(&get, &topology("node2:6379"))
+            .unwrap_err();
+        assert_eq!(crate::errors::error_type(&err), RequestErrorType::SlotMoved);
+        assert!(err.to_string().contains("node2:6379"));
+    }
+}
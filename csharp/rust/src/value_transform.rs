@@ -0,0 +1,305 @@
+// Copyright Valkey GLIDE Project Contributors - SPDX Identifier: Apache-2.0
+
+//! Transform of the values of commands by the caller, e.g. to encrypt them before they leave the process,
+//! see [`ConnectionConfig::value_encoder`](crate::ffi::ConnectionConfig::value_encoder).
+
+use redis::{Arg, Cmd, ErrorKind, RedisError, RedisResult, Value};
+
+use crate::{commands::ptr_to_bytes, ResponseConverter};
+
+/// Value transform callback, which encodes a value before it is sent, or decodes a value once it is received.
+///
+/// The arguments are the value, its length, and a pointer to which the callback writes the length of the transformed value.
+/// The callback returns the transformed value, which may contain any bytes, or `null` if the value can't be transformed,
+/// in which case the command fails with a `ClientError`. An empty transformed value must still be returned by a non-null pointer.
+/// The value is only valid until the callback returns. The returned value is copied before the callback is called again
+/// by the same thread, and it is never freed by the client.
+///
+/// The encoder is called synchronously on the thread which submits the command, and the decoder on a thread of the client,
+/// so both should be cheap and never block.
+pub type ValueTransformCallback = unsafe extern "C" fn(*const u8, u32, *mut u32) -> *const u8;
+
+/// Encodes the values of known commands and decodes the values in their responses, by the callbacks of the caller.
+///
+/// Key specifications locate only the keys of commands, so values are located by their positions in the known commands:
+/// `SET`, `SETNX`, `GETSET`, `SETEX`, `PSETEX` and `MSET`, `MSETNX` for strings, and `HSET`, `HMSET`, `HSETNX` for hash fields.
+/// Values are decoded in the responses of `GET`, `GETDEL`, `GETEX`, `GETSET`, `SET` with `GET`, `MGET`, and of `HGET`, `HMGET`,
+/// `HVALS`, `HGETALL`. Keys, hash fields and the arguments of other commands, e.g. `APPEND`, are sent as is.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct ValueTransform {
+    encode: ValueTransformCallback,
+    decode: ValueTransformCallback,
+}
+
+/// The values in the response of a command, see [`ValueTransform`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum ValueReply {
+    /// The response is a single value.
+    Value,
+    /// The response is an array of values.
+    Values,
+    /// The response is a map of hash fields to values, or an array of alternating fields and values.
+    HashValues,
+}
+
+/// Get the positions of the values in the arguments of a command, where the command name is at position `0`.
+fn value_positions(name: &[u8], count: usize) -> Vec<usize> {
+    let positions: Vec<usize> = match name {
+        b"set" | b"setnx" | b"getset" => vec![2],
+        b"setex" | b"psetex" | b"hsetnx" => vec![3],
+        // Every key is followed by its value
+        b"mset" | b"msetnx" => (2..count).step_by(2).collect(),
+        // Every field is followed by its value
+        b"hset" | b"hmset" => (3..count).step_by(2).collect(),
+        _ => vec![],
+    };
+    positions
+        .into_iter()
+        .filter(|&position| position < count)
+        .collect()
+}
+
+fn value_reply(name: &[u8]) -> Option<ValueReply> {
+    match name {
+        // `SET` replies with a value only with `GET`, otherwise with a simple string or null, which aren't decoded
+        b"get" | b"getdel" | b"getex" | b"getset" | b"set" | b"hget" => Some(ValueReply::Value),
+        b"mget" | b"hmget" | b"hvals" => Some(ValueReply::Values),
+        b"hgetall" => Some(ValueReply::HashValues),
+        _ => None,
+    }
+}
+
+/// Transform `value` by `callback`, failing with `error` if the callback returns `null`.
+///
+/// # Safety
+/// * `callback` must be safe to call with any value, see [`ValueTransformCallback`].
+unsafe fn transform(
+    callback: ValueTransformCallback,
+    value: &[u8],
+    error: &'static str,
+) -> RedisResult<Vec<u8>> {
+    let mut len = 0;
+    let transformed = unsafe { callback(value.as_ptr(), value.len() as u32, &mut len) };
+    if transformed.is_null() {
+        return Err(RedisError::from((ErrorKind::ClientError, error)));
+    }
+    Ok(unsafe { ptr_to_bytes(transformed, len) }.to_vec())
+}
+
+impl ValueTransform {
+    /// Create the transform of the callbacks, `None` unless both are given.
+    pub(crate) fn new(
+        encode: Option<ValueTransformCallback>,
+        decode: Option<ValueTransformCallback>,
+    ) -> Option<Self> {
+        Some(ValueTransform {
+            encode: encode?,
+            decode: decode?,
+        })
+    }
+
+    /// Encode the values of a command. Returns the command and the conversion which decodes the values in its response, if needed.
+    pub(crate) fn encode(&self, cmd: Cmd) -> RedisResult<(Cmd, Option<ResponseConverter>)> {
+        let args: Vec<&[u8]> = cmd
+            .args_iter()
+            .filter_map(|arg| match arg {
+                Arg::Simple(arg) => Some(arg),
+                Arg::Cursor => None,
+            })
+            .collect();
+        let Some(name) = args.first().map(|name| name.to_ascii_lowercase()) else {
+            return Ok((cmd, None));
+        };
+        let convert = value_reply(&name).map(|reply| self.response_converter(reply));
+        let positions = value_positions(&name, args.len());
+        if positions.is_empty() {
+            return Ok((cmd, convert));
+        }
+        let mut encoded = Cmd::new();
+        for (position, arg) in args.iter().enumerate() {
+            if positions.contains(&position) {
+                encoded.arg(unsafe { transform(self.encode, arg, "Failed to encode a value") }?);
+            } else {
+                encoded.arg(*arg);
+            }
+        }
+        Ok((encoded, convert))
+    }
+
+    fn response_converter(&self, reply: ValueReply) -> ResponseConverter {
+        let decode = self.decode;
+        let decode_value = move |value: Value| match value {
+            Value::BulkString(value) => {
+                unsafe { transform(decode, &value, "Failed to decode a value") }
+                    .map(Value::BulkString)
+            }
+            value => Ok(value),
+        };
+        Box::new(move |value| match (reply, value) {
+            (ValueReply::Value, value) => decode_value(value),
+            (ValueReply::Values, Value::Array(values)) => values
+                .into_iter()
+                .map(decode_value)
+                .collect::<RedisResult<_>>()
+                .map(Value::Array),
+            (ValueReply::HashValues, Value::Map(fields)) => fields
+                .into_iter()
+                .map(|(field, value)| Ok((field, decode_value(value)?)))
+                .collect::<RedisResult<_>>()
+                .map(Value::Map),
+            (ValueReply::HashValues, Value::Array(fields)) => fields
+                .into_iter()
+                .enumerate()
+                .map(|(position, item)| match position % 2 {
+                    0 => Ok(item),
+                    _ => decode_value(item),
+                })
+                .collect::<RedisResult<_>>()
+                .map(Value::Array),
+            (_, value) => Ok(value),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+
+    use super::*;
+
+    thread_local! {
+        static TRANSFORMED: RefCell<Vec<u8>> = const { RefCell::new(Vec::new()) };
+    }
+
+    unsafe extern "C" fn xor(value: *const u8, len: u32, transformed_len: *mut u32) -> *const u8 {
+        let value = unsafe { ptr_to_bytes(value, len) };
+        TRANSFORMED.with_borrow_mut(|transformed| {
+            *transformed = value.iter().map(|byte| byte ^ 0x5a).collect();
+            unsafe { *transformed_len = transformed.len() as u32 };
+            transformed.as_ptr()
+        })
+    }
+
+    unsafe extern "C" fn fail(_: *const u8, _: u32, _: *mut u32) -> *const u8 {
+        std::ptr::null()
+    }
+
+    fn packed_args(cmd: &Cmd) -> Vec<Vec<u8>> {
+        cmd.args_iter()
+            .filter_map(|arg| match arg {
+                Arg::Simple(arg) => Some(arg.to_vec()),
+                Arg::Cursor => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_set_get_round_trip() {
+        let value_transform = ValueTransform::new(Some(xor), Some(xor)).unwrap();
+        // Binary values, including bytes which are invalid UTF-8 and bytes which the XOR turns to `0`
+        let value = vec![0u8, 0x5a, 0xff, b'\r', b'\n', 0x80];
+        let mut set = redis::cmd("SET");
+        set.arg("key").arg(&value).arg("EX").arg(10);
+        let (set, _) = value_transform.encode(set).unwrap();
+        let args = packed_args(&set);
+        assert_eq!(args[1], b"key");
+        assert_ne!(args[2], value);
+        assert_eq!(&args[3..], [b"EX".to_vec(), b"10".to_vec()]);
+
+        let mut get = redis::cmd("GET");
+        get.arg("key");
+        let (get, convert) = value_transform.encode(get).unwrap();
+        assert_eq!(packed_args(&get), [b"GET".to_vec(), b"key".to_vec()]);
+        let stored = Value::BulkString(args[2].clone());
+        assert_eq!(convert.unwrap()(stored).unwrap(), Value::BulkString(value));
+    }
+
+    #[test]
+    fn test_encodes_only_values() {
+        let value_transform = ValueTransform::new(Some(xor), Some(xor)).unwrap();
+        let encoded = |value: &str| value.bytes().map(|byte| byte ^ 0x5a).collect::<Vec<_>>();
+
+        let mut mset = redis::cmd("MSET");
+        mset.arg("key1").arg("value1").arg("key2").arg("value2");
+        let (mset, convert) = value_transform.encode(mset).unwrap();
+        assert!(convert.is_none());
+        assert_eq!(
+            packed_args(&mset),
+            [
+                b"MSET".to_vec(),
+                b"key1".to_vec(),
+                encoded("value1"),
+                b"key2".to_vec(),
+                encoded("value2"),
+            ]
+        );
+
+        let mut hset = redis::cmd("HSET");
+        hset.arg("key").arg("field").arg("value");
+        let (hset, _) = value_transform.encode(hset).unwrap();
+        assert_eq!(
+            packed_args(&hset),
+            [
+                b"HSET".to_vec(),
+                b"key".to_vec(),
+                b"field".to_vec(),
+                encoded("value"),
+            ]
+        );
+
+        let mut append = redis::cmd("APPEND");
+        append.arg("key").arg("value");
+        let (append, convert) = value_transform.encode(append).unwrap();
+        assert!(convert.is_none());
+        assert_eq!(
+            packed_args(&append),
+            [b"APPEND".to_vec(), b"key".to_vec(), b"value".to_vec()]
+        );
+    }
+
+    #[test]
+    fn test_decodes_values_in_responses() {
+        let value_transform = ValueTransform::new(Some(xor), Some(xor)).unwrap();
+        let encoded = |value: &str| {
+            Value::BulkString(value.bytes().map(|byte| byte ^ 0x5a).collect::<Vec<_>>())
+        };
+        let bulk = |value: &str| Value::BulkString(value.as_bytes().to_vec());
+
+        let mut mget = redis::cmd("MGET");
+        mget.arg("key1").arg("key2");
+        let (_, convert) = value_transform.encode(mget).unwrap();
+        assert_eq!(
+            convert.unwrap()(Value::Array(vec![encoded("value"), Value::Nil])).unwrap(),
+            Value::Array(vec![bulk("value"), Value::Nil])
+        );
+
+        let mut hgetall = redis::cmd("HGETALL");
+        hgetall.arg("key");
+        let (_, convert) = value_transform.encode(hgetall).unwrap();
+        assert_eq!(
+            convert.unwrap()(Value::Map(vec![(bulk("field"), encoded("value"))])).unwrap(),
+            Value::Map(vec![(bulk("field"), bulk("value"))])
+        );
+
+        // `SET` without `GET` replies with a simple string, which isn't a value
+        let mut set = redis::cmd("SET");
+        set.arg("key").arg("value");
+        let (_, convert) = value_transform.encode(set).unwrap();
+        assert_eq!(convert.unwrap()(Value::Okay).unwrap(), Value::Okay);
+    }
+
+    #[test]
+    fn test_failed_transforms_fail_the_command() {
+        let value_transform = ValueTransform::new(Some(fail), Some(fail)).unwrap();
+        let mut set = redis::cmd("SET");
+        set.arg("key").arg("value");
+        assert!(value_transform.encode(set).is_err());
+
+        let mut get = redis::cmd("GET");
+        get.arg("key");
+        let (_, convert) = value_transform.encode(get).unwrap();
+        assert!(convert.unwrap()(Value::BulkString(b"value".to_vec())).is_err());
+
+        assert!(ValueTransform::new(Some(xor), None).is_none());
+    }
+}
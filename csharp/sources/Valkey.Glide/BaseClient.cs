@@ -21,6 +21,23 @@ public abstract class BaseClient : IDisposable, IStringBaseCommands
     public async Task<GlideString?> Get(GlideString key)
         => await Command(RequestType.Get, [key], response => HandleServerResponse<GlideString>(response, true));
 
+    /// <summary>
+    /// Get the effective configuration of the client, after the defaults are applied, e.g. to compare the configurations of clients.
+    /// Secrets, like the password, are redacted.
+    /// </summary>
+    /// <returns>A map of setting names, like <c>request_timeout_ms</c> or <c>read_from</c>, to their values.</returns>
+    public Dictionary<GlideString, object?> GetEffectiveConfig()
+        => HandleServerResponse<Dictionary<GlideString, object?>>(GetEffectiveConfigFfi(_clientPointer), false);
+
+    /// <summary>
+    /// Get the last commands of the client, recorded if it was configured with <c>WithCommandTrace</c>, from the oldest to the newest,
+    /// e.g. to reproduce an issue.
+    /// </summary>
+    /// <returns>An array of maps of <c>timestamp_ms</c>, <c>command</c>, <c>args_hash</c> or <c>args</c>, <c>route</c>, <c>node</c>,
+    /// <c>latency_us</c> and <c>result</c> - <c>Ok</c> or the <see cref="RequestErrorType" /> name. Empty if recording is disabled.</returns>
+    public object?[] GetCommandTrace()
+        => HandleServerResponse<object?[]>(GetCommandTraceFfi(_clientPointer), false);
+
     public void Dispose()
     {
         GC.SuppressFinalize(this);
@@ -51,11 +68,11 @@ public abstract class BaseClient : IDisposable, IStringBaseCommands
         nint failureCallbackPointer = Marshal.GetFunctionPointerForDelegate(_failureCallbackDelegate);
         nint configPtr = Marshal.AllocHGlobal(Marshal.SizeOf(typeof(ConnectionRequest)));
         Marshal.StructureToPtr(config.ToRequest(), configPtr, false);
-        _clientPointer = CreateClientFfi(configPtr, successCallbackPointer, failureCallbackPointer);
+        _clientPointer = CreateClientFfi(configPtr, successCallbackPointer, failureCallbackPointer, IntPtr.Zero, IntPtr.Zero, IntPtr.Zero, IntPtr.Zero);
         Marshal.FreeHGlobal(configPtr);
         if (_clientPointer == IntPtr.Zero)
         {
-            throw new Exception("Failed creating a client");
+            throw _creationError ?? new Exception("Failed creating a client");
         }
     }
 
@@ -95,7 +112,7 @@ public abstract class BaseClient : IDisposable, IStringBaseCommands
 
         // 5. Sumbit request to the rust part
         Message message = _messageContainer.GetMessageForCall();
-        CommandFfi(_clientPointer, (ulong)message.Index, (int)requestType, argsPointer, (uint)arguments.Length, lengthsPointer, routePtr);
+        CommandFfi(_clientPointer, (ulong)message.Index, (int)requestType, argsPointer, (uint)arguments.Length, lengthsPointer, routePtr, 0, false, false, null, 0, false);
         // All data must be copied in sync manner, so we
 
         // 6. Free memories allocated
@@ -138,6 +155,11 @@ public abstract class BaseClient : IDisposable, IStringBaseCommands
         try
         {
             object? value = HandleResponse(response);
+            // Typed commands return the reply without its metadata.
+            if (value is ResponseWithMetadata withMetadata && !typeof(R).IsAssignableFrom(typeof(ResponseWithMetadata)))
+            {
+                value = withMetadata.Value;
+            }
             if (value is null)
             {
                 if (isNullable)
@@ -164,9 +186,19 @@ public abstract class BaseClient : IDisposable, IStringBaseCommands
         // Work needs to be offloaded from the calling thread, because otherwise we might starve the client's thread pool.
         Task.Run(() => _messageContainer.GetMessage((int)index).SetResult(ptr));
 
-    private void FailureCallback(ulong index) =>
+    private void FailureCallback(ulong index, RequestErrorType errorType, IntPtr message)
+    {
+        // The message is freed by Rust once the callback returns, so it has to be copied synchronously.
+        string errorMessage = Marshal.PtrToStringAnsi(message) ?? "Operation failed";
+        if (index == CreateClientCallbackIndex)
+        {
+            // Reported synchronously, before the client creation returns.
+            _creationError = new RequestException(errorType, errorMessage);
+            return;
+        }
         // Work needs to be offloaded from the calling thread, because otherwise we might starve the client's thread pool.
-        Task.Run(() => _messageContainer.GetMessage((int)index).SetException(new Exception("Operation failed")));
+        _ = Task.Run(() => _messageContainer.GetMessage((int)index).SetException(new RequestException(errorType, errorMessage)));
+    }
 
     ~BaseClient() => Dispose();
     #endregion private methods
@@ -184,6 +216,10 @@ public abstract class BaseClient : IDisposable, IStringBaseCommands
     /// Raw pointer to the underlying native client.
     private IntPtr _clientPointer;
     private readonly MessageContainer _messageContainer = new();
+    /// The error of the client creation, reported through the failure callback.
+    private RequestException? _creationError;
+    /// Callback index of a failure of the client creation, see <c>CREATE_CLIENT_CALLBACK_INDEX</c> in <c>lib.rs</c>.
+    private const ulong CreateClientCallbackIndex = ulong.MaxValue;
     private readonly ArrayPool<IntPtr> _arrayPool = ArrayPool<IntPtr>.Shared;
     private readonly object _lock = new();
 
@@ -192,16 +228,22 @@ public abstract class BaseClient : IDisposable, IStringBaseCommands
     #region FFI function declarations
 
     private delegate void SuccessAction(ulong index, IntPtr ptr);
-    private delegate void FailureAction(ulong index);
+    private delegate void FailureAction(ulong index, RequestErrorType errorType, IntPtr message);
 
     [DllImport("libglide_rs", CallingConvention = CallingConvention.Cdecl, EntryPoint = "command")]
-    private static extern void CommandFfi(IntPtr client, ulong index, int requestType, IntPtr args, uint argCount, IntPtr argLengths, IntPtr routeInfo);
+    private static extern void CommandFfi(IntPtr client, ulong index, int requestType, IntPtr args, uint argCount, IntPtr argLengths, IntPtr routeInfo, ulong deadlineUnixMs, [MarshalAs(UnmanagedType.U1)] bool fireAndForget, [MarshalAs(UnmanagedType.U1)] bool keyless, [MarshalAs(UnmanagedType.LPUTF8Str)] string? correlationId, ulong affinityToken, [MarshalAs(UnmanagedType.U1)] bool rawNoRetryNoWait);
 
     [DllImport("libglide_rs", CallingConvention = CallingConvention.Cdecl, EntryPoint = "free_respose")]
     private static extern void FreeResponse(IntPtr response);
 
+    [DllImport("libglide_rs", CallingConvention = CallingConvention.Cdecl, EntryPoint = "get_effective_config")]
+    private static extern IntPtr GetEffectiveConfigFfi(IntPtr client);
+
+    [DllImport("libglide_rs", CallingConvention = CallingConvention.Cdecl, EntryPoint = "get_command_trace")]
+    private static extern IntPtr GetCommandTraceFfi(IntPtr client);
+
     [DllImport("libglide_rs", CallingConvention = CallingConvention.Cdecl, EntryPoint = "create_client")]
-    private static extern IntPtr CreateClientFfi(IntPtr config, IntPtr successCallback, IntPtr failureCallback);
+    private static extern IntPtr CreateClientFfi(IntPtr config, IntPtr successCallback, IntPtr failureCallback, IntPtr eventCallback, IntPtr slowCommandCallback, IntPtr invalidationCallback, IntPtr routeDecider);
 
     [DllImport("libglide_rs", CallingConvention = CallingConvention.Cdecl, EntryPoint = "close_client")]
     private static extern void CloseClientFfi(IntPtr client);
@@ -211,10 +253,20 @@ public abstract class BaseClient : IDisposable, IStringBaseCommands
     #region RequestType
 
     // TODO: generate this with a bindings generator
-    protected enum RequestType
+    /// <summary>
+    /// Type of a command, mirroring the request types of GLIDE.
+    /// </summary>
+    public enum RequestType
     {
         InvalidRequest = 0,
         CustomCommand = 1,
+        Keys = 408,
+        ConfigGet = 1122,
+        ConfigResetStat = 1123,
+        ConfigRewrite = 1124,
+        ConfigSet = 1125,
+        FlushAll = 1128,
+        FlushDB = 1129,
         Get = 1504,
         Set = 1517,
     }
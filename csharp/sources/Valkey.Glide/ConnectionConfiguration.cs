@@ -38,6 +38,70 @@ public abstract class ConnectionConfiguration
         public Protocol Protocol;
         [MarshalAs(UnmanagedType.LPStr)]
         public string? ClientName;
+        public uint MaxInflightRequestsPerNode;
+        public uint HotNodeThreshold;
+        public uint HotNodeDuration;
+        [MarshalAs(UnmanagedType.U1)]
+        public bool FailFastOnDegraded;
+        public uint CompressionThreshold;
+        [MarshalAs(UnmanagedType.U1)]
+        public bool HasDefaultRoute;
+        public Route.RouteInfo DefaultRoute;
+        public uint SlowCommandThreshold;
+        public uint ResponsePoolCapacity;
+        public uint PeriodicTopologyRefreshSecs;
+        [MarshalAs(UnmanagedType.U1)]
+        public bool LogNonUtf8Arguments;
+        public uint ReadYourWritesWindowMs;
+        public uint RedirectionEventThreshold;
+        public CommandFilterMode CommandFilterMode;
+        public uint CommandFilterCount;
+        public IntPtr CommandFilter; // * RequestType - array pointer
+        [MarshalAs(UnmanagedType.U1)]
+        public bool ReauthenticateOnNoAuth;
+        [MarshalAs(UnmanagedType.U1)]
+        public bool DeduplicateReads;
+        public ulong MaxResponseBytes;
+        public ClientTrackingMode ClientTrackingMode;
+        public uint ClientTrackingPrefixCount;
+        public IntPtr ClientTrackingPrefixes; // ** char - array pointer
+        [MarshalAs(UnmanagedType.U1)]
+        public bool TcpNoDelay;
+        [MarshalAs(UnmanagedType.U1)]
+        public bool FallbackToPrimaryOnReplicaError;
+        [MarshalAs(UnmanagedType.LPStr)]
+        public string? KeyPrefix;
+        [MarshalAs(UnmanagedType.U1)]
+        public bool StripKeyPrefix;
+        public uint MaxPendingCommands;
+        [MarshalAs(UnmanagedType.U1)]
+        public bool NormalizeEmptyReplies;
+        [MarshalAs(UnmanagedType.U1)]
+        public bool ResponseMetadata;
+        public ErrorMessageFormat ErrorMessageFormat;
+        [MarshalAs(UnmanagedType.LPStr)]
+        public string? TlsSniHostname;
+        public uint ConnectionsPerNode;
+        public uint MaxCommandArgBytes;
+        public uint CommandTraceCapacity;
+        [MarshalAs(UnmanagedType.U1)]
+        public bool CommandTraceFullArgs;
+        public uint RuntimeSaturationThreshold;
+        [MarshalAs(UnmanagedType.U1)]
+        public bool OrderCommandsPerKey;
+        public uint ClientKeepaliveIntervalSecs;
+        [MarshalAs(UnmanagedType.LPStr)]
+        public string? DefaultHashTag;
+        public uint CircuitBreakerThreshold;
+        public IntPtr ValueEncoder; // ValueTransformCallback - function pointer
+        public IntPtr ValueDecoder; // ValueTransformCallback - function pointer
+        [MarshalAs(UnmanagedType.U1)]
+        public bool RetryWritesOnReadOnly;
+        public uint SlowCallbackThresholdMs;
+        [MarshalAs(UnmanagedType.U1)]
+        public bool OffloadCallbacks;
+        public IntPtr CredentialProvider; // CredentialProviderCallback - function pointer
+        public AddressFamily AddressFamily;
         // TODO more config params, see ffi.rs
     }
 
@@ -136,6 +200,38 @@ public abstract class ConnectionConfiguration
         }
     }
 
+    internal enum CommandFilterMode : uint
+    {
+        Disabled = 0,
+        Allow = 1,
+        Deny = 2,
+    }
+
+    internal enum ClientTrackingMode : uint
+    {
+        Disabled = 0,
+        Default = 1,
+        Broadcast = 2,
+        OptIn = 3,
+        OptOut = 4,
+    }
+
+    /// <summary>
+    /// Format of the messages of <see cref="RequestException" />s.
+    /// </summary>
+    public enum ErrorMessageFormat : uint
+    {
+        /// <summary>
+        /// A human readable message.
+        /// </summary>
+        Text = 0,
+        /// <summary>
+        /// A JSON object with the fields <c>type</c> - the <see cref="RequestErrorType" />, <c>code</c> - the error code, e.g. <c>OOM</c>,
+        /// <c>kind</c>, <c>message</c> - the human readable message, and <c>detail</c>. <c>code</c> and <c>detail</c> may be <see langword="null" />.
+        /// </summary>
+        Json = 1,
+    }
+
     /// <summary>
     /// Represents the client's read from strategy.
     /// </summary>
@@ -162,6 +258,25 @@ public abstract class ConnectionConfiguration
         AzAffinityReplicasAndPrimary,
     }
 
+    /// <summary>
+    /// IP version of the addresses which the hosts of the nodes are resolved to and connected by.
+    /// </summary>
+    public enum AddressFamily : uint
+    {
+        /// <summary>
+        /// Connect by any address of a host, IPv4 or IPv6.
+        /// </summary>
+        Auto = 0,
+        /// <summary>
+        /// Connect only by the IPv4 addresses of a host.
+        /// </summary>
+        IPv4 = 1,
+        /// <summary>
+        /// Connect only by the IPv6 addresses of a host.
+        /// </summary>
+        IPv6 = 2,
+    }
+
     /// <summary>
     /// Represents the communication protocol with the server.
     /// </summary>
@@ -219,7 +334,7 @@ public abstract class ConnectionConfiguration
 
         protected ClientConfigurationBuilder(bool clusterMode)
         {
-            Config = new ConnectionRequest { ClusterMode = clusterMode };
+            Config = new ConnectionRequest { ClusterMode = clusterMode, TcpNoDelay = true };
         }
 
         #region address
@@ -325,6 +440,26 @@ public abstract class ConnectionConfiguration
             return (T)this;
         }
         #endregion
+
+        #region TLS SNI Hostname
+        /// <summary>
+        /// Server name sent by SNI during the TLS handshake, and validated against the certificates of the servers, instead of
+        /// the hosts of the addresses, e.g. when connecting by IP through a load balancer to servers whose certificates are issued for a hostname.<br />
+        /// Applies to all nodes, including the nodes discovered in cluster mode. Ignored without TLS.
+        /// If not set, the host of every node is used.
+        /// </summary>
+        public string? TlsSniHostname
+        {
+            set => Config.TlsSniHostname = value;
+        }
+
+        /// <inheritdoc cref="TlsSniHostname"/>
+        public T WithTlsSniHostname(string? tlsSniHostname)
+        {
+            TlsSniHostname = tlsSniHostname;
+            return (T)this;
+        }
+        #endregion
         #region Request Timeout
         /// <summary>
         /// The duration in milliseconds that the client should wait for a request to complete. This
@@ -398,11 +533,15 @@ public abstract class ConnectionConfiguration
         /// </value>
         public (string? username, string password) Authentication
         {
-            set => Config.AuthenticationInfo = new AuthenticationInfo
-                  (
-                      value.username,
-                      value.password
-                  );
+            set
+            {
+                Config.HasAuthenticationInfo = true;
+                Config.AuthenticationInfo = new AuthenticationInfo
+                (
+                    value.username,
+                    value.password
+                );
+            }
         }
         /// <summary>
         /// Configure credentials for authentication process. If none are set, the client will not authenticate itself with the server.
@@ -452,6 +591,518 @@ public abstract class ConnectionConfiguration
             return (T)this;
         }
         #endregion
+        #region Max Inflight Requests Per Node
+        /// <summary>
+        /// The maximum number of concurrent requests sent to a single node. Requests exceeding the limit are rejected
+        /// with <see cref="RequestErrorType.Backpressure" />.<br />
+        /// Unlike the global limit, this prevents a single slow node from being masked by healthy ones.
+        /// If not set, there is no limit.
+        /// </summary>
+        public uint MaxInflightRequestsPerNode
+        {
+            set => Config.MaxInflightRequestsPerNode = value;
+        }
+
+        /// <inheritdoc cref="MaxInflightRequestsPerNode"/>
+        public T WithMaxInflightRequestsPerNode(uint maxInflightRequestsPerNode)
+        {
+            MaxInflightRequestsPerNode = maxInflightRequestsPerNode;
+            return (T)this;
+        }
+        #endregion
+
+        #region Max Pending Commands
+        /// <summary>
+        /// The maximum number of commands and batches which were submitted and haven't completed yet, across all nodes.
+        /// Further commands are rejected with <see cref="RequestErrorType.Backpressure" /> until pending commands complete.<br />
+        /// Unlike <see cref="MaxInflightRequestsPerNode" />, this is a single throttle of the whole client.
+        /// If not set, there is no limit.
+        /// </summary>
+        public uint MaxPendingCommands
+        {
+            set => Config.MaxPendingCommands = value;
+        }
+
+        /// <inheritdoc cref="MaxPendingCommands"/>
+        public T WithMaxPendingCommands(uint maxPendingCommands)
+        {
+            MaxPendingCommands = maxPendingCommands;
+            return (T)this;
+        }
+        #endregion
+
+        #region Connections Per Node
+        /// <summary>
+        /// Number of connections to every node, which the commands of the client are spread across in round-robin, so a large reply
+        /// only delays the commands sent on its own connection.<br />
+        /// Every additional connection is a separate client with its own connection to every node and read buffers, and in cluster mode
+        /// its own slot map and periodic topology checks, so the memory and sockets of the client grow about linearly with the count.
+        /// Commands submitted concurrently could be executed in a different order than submitted; commands which rely on the state of
+        /// the connection, e.g. <c>WATCH</c> or <c>SELECT</c>, should use a leased connection.
+        /// If not set, all commands are pipelined on a single connection to every node.
+        /// </summary>
+        public uint ConnectionsPerNode
+        {
+            set => Config.ConnectionsPerNode = value;
+        }
+
+        /// <inheritdoc cref="ConnectionsPerNode"/>
+        public T WithConnectionsPerNode(uint connectionsPerNode)
+        {
+            ConnectionsPerNode = connectionsPerNode;
+            return (T)this;
+        }
+        #endregion
+
+        #region Normalize Empty Replies
+        /// <summary>
+        /// Return <see langword="null" /> instead of an empty array, set or map as the reply of a command, so "nothing" is always
+        /// <see langword="null" />, e.g. <c>LRANGE</c>, <c>SMEMBERS</c> or <c>HGETALL</c> of a missing key and <c>KEYS</c> without matches.<br />
+        /// Only the reply of a command as a whole is replaced, and in a batch the reply of each of its commands. Empty collections
+        /// nested in a reply are kept, e.g. the empty array of keys in the reply of a <c>SCAN</c> without matches.
+        /// If not set, replies keep the distinction made by the server.
+        /// </summary>
+        public bool NormalizeEmptyReplies
+        {
+            set => Config.NormalizeEmptyReplies = value;
+        }
+
+        /// <inheritdoc cref="NormalizeEmptyReplies"/>
+        public T WithNormalizeEmptyReplies(bool normalizeEmptyReplies)
+        {
+            NormalizeEmptyReplies = normalizeEmptyReplies;
+            return (T)this;
+        }
+        #endregion
+
+        #region Response Metadata
+        /// <summary>
+        /// Return the replies of custom commands as <see cref="ResponseWithMetadata" />, with the kind and the address of the node which
+        /// served the reply, and the RESP3 attributes the server sent with it, e.g. about the encoding of the value.<br />
+        /// Typed commands still return the reply only. Batches don't return metadata.
+        /// If not set, no metadata is collected.
+        /// </summary>
+        public bool ResponseMetadata
+        {
+            set => Config.ResponseMetadata = value;
+        }
+
+        /// <inheritdoc cref="ResponseMetadata"/>
+        public T WithResponseMetadata(bool responseMetadata)
+        {
+            ResponseMetadata = responseMetadata;
+            return (T)this;
+        }
+        #endregion
+
+        #region Error Message Format
+        /// <summary>
+        /// Format of the messages of <see cref="RequestException" />s, e.g. <see cref="ErrorMessageFormat.Json" /> for structured logging.
+        /// If not set, messages are human readable.
+        /// </summary>
+        public ErrorMessageFormat ErrorMessageFormat
+        {
+            set => Config.ErrorMessageFormat = value;
+        }
+
+        /// <inheritdoc cref="ErrorMessageFormat"/>
+        public T WithErrorMessageFormat(ErrorMessageFormat errorMessageFormat)
+        {
+            ErrorMessageFormat = errorMessageFormat;
+            return (T)this;
+        }
+        #endregion
+
+        #region Response Pool Capacity
+        /// <summary>
+        /// The number of freed responses kept for reuse, which reduces allocations under high throughput.<br />
+        /// The pool is shared by all clients and holds up to the largest capacity configured by any client.
+        /// If not set, responses are not pooled.
+        /// </summary>
+        public uint ResponsePoolCapacity
+        {
+            set => Config.ResponsePoolCapacity = value;
+        }
+
+        /// <inheritdoc cref="ResponsePoolCapacity"/>
+        public T WithResponsePoolCapacity(uint responsePoolCapacity)
+        {
+            ResponsePoolCapacity = responsePoolCapacity;
+            return (T)this;
+        }
+        #endregion
+
+        #region Log Non-UTF-8 Arguments
+        /// <summary>
+        /// Log a warning for every command argument which isn't valid UTF-8.<br />
+        /// The arguments are still sent, since binary arguments are legal. This is a diagnostic aid for catching
+        /// encoding bugs, like strings which were encoded twice.
+        /// </summary>
+        public bool LogNonUtf8Arguments
+        {
+            set => Config.LogNonUtf8Arguments = value;
+        }
+
+        /// <inheritdoc cref="LogNonUtf8Arguments"/>
+        public T WithLogNonUtf8Arguments(bool logNonUtf8Arguments)
+        {
+            LogNonUtf8Arguments = logNonUtf8Arguments;
+            return (T)this;
+        }
+        #endregion
+
+        #region Reauthenticate On NOAUTH
+        /// <summary>
+        /// When a command fails because its connection lost its authentication (<c>NOAUTH</c>), e.g. after a server restart,
+        /// re-authenticate the connection with the configured credentials and retry the command once, instead of failing it.<br />
+        /// If re-authenticating fails, the command fails with <see cref="RequestErrorType.ReauthenticationFailed" />.
+        /// </summary>
+        public bool ReauthenticateOnNoAuth
+        {
+            set => Config.ReauthenticateOnNoAuth = value;
+        }
+
+        /// <inheritdoc cref="ReauthenticateOnNoAuth"/>
+        public T WithReauthenticateOnNoAuth(bool reauthenticateOnNoAuth)
+        {
+            ReauthenticateOnNoAuth = reauthenticateOnNoAuth;
+            return (T)this;
+        }
+        #endregion
+
+        #region Deduplicate Reads
+        /// <summary>
+        /// Coalesce concurrent identical reads (the same command and arguments) into a single round trip to the server,
+        /// whose result is passed to all of them, e.g. to relieve the server from a burst of reads of a key on a cache miss.<br />
+        /// Only reads without a route are coalesced, and results aren't cached beyond the round trip.
+        /// </summary>
+        public bool DeduplicateReads
+        {
+            set => Config.DeduplicateReads = value;
+        }
+
+        /// <inheritdoc cref="DeduplicateReads"/>
+        public T WithDeduplicateReads(bool deduplicateReads)
+        {
+            DeduplicateReads = deduplicateReads;
+            return (T)this;
+        }
+        #endregion
+
+        #region Order Commands Per Key
+        /// <summary>
+        /// Dispatch the commands on the same key one at a time, in the order they were submitted, e.g. so <c>LRANGE</c> observes
+        /// a preceding <c>LPUSH</c> of the same key, while commands on different keys remain concurrent.<br />
+        /// A command is dispatched once the previous command on its key completed, so the throughput of a single hot key is limited
+        /// to one round trip at a time. Commands on multiple keys, e.g. <c>MGET</c>, and batches are not ordered.
+        /// </summary>
+        public bool OrderCommandsPerKey
+        {
+            set => Config.OrderCommandsPerKey = value;
+        }
+
+        /// <inheritdoc cref="OrderCommandsPerKey"/>
+        public T WithOrderCommandsPerKey(bool orderCommandsPerKey)
+        {
+            OrderCommandsPerKey = orderCommandsPerKey;
+            return (T)this;
+        }
+        #endregion
+
+        #region Client Keepalive
+        /// <summary>
+        /// Interval in seconds of a <c>PING</c> on idle connections, which keeps them alive at the application layer, e.g. behind proxies
+        /// which close connections without traffic regardless of TCP keepalive, so the first command after an idle period doesn't fail.<br />
+        /// The interval should be shorter than the idle timeout of the proxy. If not set, idle connections aren't pinged.
+        /// </summary>
+        public uint ClientKeepaliveIntervalSecs
+        {
+            set => Config.ClientKeepaliveIntervalSecs = value;
+        }
+
+        /// <inheritdoc cref="ClientKeepaliveIntervalSecs"/>
+        public T WithClientKeepaliveIntervalSecs(uint clientKeepaliveIntervalSecs)
+        {
+            ClientKeepaliveIntervalSecs = clientKeepaliveIntervalSecs;
+            return (T)this;
+        }
+        #endregion
+
+        #region TCP No Delay
+        /// <summary>
+        /// Disable Nagle's algorithm (<c>TCP_NODELAY</c>) on the sockets of all connections, including reconnections,
+        /// so small commands are sent immediately instead of being delayed until previous writes are acknowledged.<br />
+        /// Enabled by default, which lowers the latency of request/response workloads. Disabling it may save packets
+        /// when sending many small commands at high throughput, at the cost of latency.
+        /// </summary>
+        public bool TcpNoDelay
+        {
+            set => Config.TcpNoDelay = value;
+        }
+
+        /// <inheritdoc cref="TcpNoDelay"/>
+        public T WithTcpNoDelay(bool tcpNoDelay)
+        {
+            TcpNoDelay = tcpNoDelay;
+            return (T)this;
+        }
+        #endregion
+
+        #region Address Family
+        /// <summary>
+        /// IP version of the addresses the hosts are resolved to and connected by, including the nodes discovered in cluster mode
+        /// and reconnections, e.g. <see cref="AddressFamily.IPv4" /> when IPv6 routes are blocked, so connections don't wait for
+        /// unreachable addresses. Hosts without an address of the family fail to connect.<br />
+        /// If not set, <see cref="AddressFamily.Auto" /> connects by any address of a host.
+        /// </summary>
+        public AddressFamily AddressFamily
+        {
+            set => Config.AddressFamily = value;
+        }
+
+        /// <inheritdoc cref="AddressFamily"/>
+        public T WithAddressFamily(AddressFamily addressFamily)
+        {
+            AddressFamily = addressFamily;
+            return (T)this;
+        }
+        #endregion
+
+        #region Fallback To Primary On Replica Error
+        /// <summary>
+        /// Retry a read once on the primary of its slot, if it was served by a replica which failed with a
+        /// <see cref="RequestErrorType.Disconnect" /> or <see cref="RequestErrorType.Unavailable" /> error, e.g. because the replica is down.
+        /// Other errors, e.g. <c>WRONGTYPE</c>, are reported as is.<br />
+        /// Applies to reads routed to a replica, and to reads served by replicas according to the read from strategy. Only in cluster mode.
+        /// </summary>
+        public bool FallbackToPrimaryOnReplicaError
+        {
+            set => Config.FallbackToPrimaryOnReplicaError = value;
+        }
+
+        /// <inheritdoc cref="FallbackToPrimaryOnReplicaError"/>
+        public T WithFallbackToPrimaryOnReplicaError(bool fallbackToPrimaryOnReplicaError)
+        {
+            FallbackToPrimaryOnReplicaError = fallbackToPrimaryOnReplicaError;
+            return (T)this;
+        }
+        #endregion
+
+        #region Key Prefix
+        /// <summary>
+        /// Prefix the keys of all commands with the given prefix, e.g. to share a server between tenants.<br />
+        /// The keys of a command are found by the key specifications the server reports for it, so custom commands are prefixed too,
+        /// and the prefix is included when calculating the slot of a key. The patterns of <c>KEYS</c> and <c>SCAN</c> are prefixed as well.<br />
+        /// Commands of batches are not prefixed.
+        /// </summary>
+        public string? KeyPrefix
+        {
+            set => Config.KeyPrefix = value;
+        }
+
+        /// <inheritdoc cref="KeyPrefix"/>
+        public T WithKeyPrefix(string? keyPrefix)
+        {
+            KeyPrefix = keyPrefix;
+            return (T)this;
+        }
+
+        /// <summary>
+        /// Remove the <see cref="KeyPrefix" /> from the keys returned by <c>KEYS</c>, <c>SCAN</c> and <c>RANDOMKEY</c>.
+        /// </summary>
+        public bool StripKeyPrefix
+        {
+            set => Config.StripKeyPrefix = value;
+        }
+
+        /// <inheritdoc cref="StripKeyPrefix"/>
+        public T WithStripKeyPrefix(bool stripKeyPrefix)
+        {
+            StripKeyPrefix = stripKeyPrefix;
+            return (T)this;
+        }
+        #endregion
+
+        #region Default Hash Tag
+        /// <summary>
+        /// Wrap the keys of all commands in the given hash tag, as <c>{tag}key</c>, so all keys map to the same slot,
+        /// e.g. for multi-key commands in cluster mode. The tag must not contain <c>}</c>.<br />
+        /// The tag follows the <see cref="KeyPrefix" />, if one is set. Only the first hash tag of a key determines its slot,
+        /// so hash tags already included in the keys have no effect, and a hash tag in the <see cref="KeyPrefix" /> takes precedence.
+        /// </summary>
+        public string? DefaultHashTag
+        {
+            set => Config.DefaultHashTag = value;
+        }
+
+        /// <inheritdoc cref="DefaultHashTag"/>
+        public T WithDefaultHashTag(string? defaultHashTag)
+        {
+            DefaultHashTag = defaultHashTag;
+            return (T)this;
+        }
+        #endregion
+
+        #region Value Transform
+        /// <summary>
+        /// Transform the values of commands by native callbacks, e.g. to compress or encrypt values before they leave the process.<br />
+        /// The encoder is applied to the values of <c>SET</c>, <c>SETNX</c>, <c>GETSET</c>, <c>SETEX</c>, <c>PSETEX</c>, <c>MSET</c>, <c>MSETNX</c>,
+        /// <c>HSET</c>, <c>HMSET</c> and <c>HSETNX</c>, never to their keys or hash fields, and the decoder to the values returned by
+        /// <c>GET</c>, <c>GETDEL</c>, <c>GETEX</c>, <c>GETSET</c>, <c>SET</c> with <c>GET</c>, <c>MGET</c>, <c>HGET</c>, <c>HMGET</c>, <c>HVALS</c> and <c>HGETALL</c>.
+        /// Commands of batches are not transformed.<br />
+        /// Both are unmanaged functions <c>byte* (byte* value, uint length, uint* transformedLength)</c>, which return the transformed value,
+        /// or <c>null</c> to fail the command. The returned value must stay valid until the callback is called again by the same thread.
+        /// The callbacks must stay valid for the lifetime of the client, and they should be cheap and never block.
+        /// </summary>
+        /// <param name="encoder">Function pointer of the encoder, applied before values are sent.</param>
+        /// <param name="decoder">Function pointer of the decoder, applied once values are received.</param>
+        public T WithValueTransform(IntPtr encoder, IntPtr decoder)
+        {
+            Config.ValueEncoder = encoder;
+            Config.ValueDecoder = decoder;
+            return (T)this;
+        }
+        #endregion
+
+        #region Credential Provider
+        /// <summary>
+        /// Pull the password of the client from a native callback, e.g. to authenticate by short-lived IAM tokens without pushing them to the client.<br />
+        /// The callback is an unmanaged function <c>byte* ()</c>, which returns a fresh password as a null-terminated UTF-8 string, or <c>null</c>
+        /// if none is available. The password is copied before the callback returns, so it must stay valid only until the callback is called again.<br />
+        /// A password is pulled when the client is created, replacing the password given to <see cref="WithAuthentication(string?, string)" />,
+        /// and once a connection is lost or fails to connect, so the connection is established again with a fresh password.
+        /// The first attempt to reconnect may still use the previous password. The callback must stay valid for the lifetime of the client,
+        /// and it should be cheap and never block.
+        /// </summary>
+        /// <param name="credentialProvider">Function pointer of the credential provider.</param>
+        public T WithCredentialProvider(IntPtr credentialProvider)
+        {
+            Config.CredentialProvider = credentialProvider;
+            return (T)this;
+        }
+        #endregion
+
+        #region Callbacks
+        /// <summary>
+        /// Log a warning with the callback index of a request whose success or failure callback took longer than the given time in milliseconds.<br />
+        /// Callbacks are called by the threads of the client, so a callback which blocks delays the results of other requests.
+        /// If not set, callbacks aren't timed.
+        /// </summary>
+        public uint SlowCallbackThresholdMs
+        {
+            set => Config.SlowCallbackThresholdMs = value;
+        }
+
+        /// <inheritdoc cref="SlowCallbackThresholdMs"/>
+        public T WithSlowCallbackThresholdMs(uint slowCallbackThresholdMs)
+        {
+            SlowCallbackThresholdMs = slowCallbackThresholdMs;
+            return (T)this;
+        }
+
+        /// <summary>
+        /// Call the success and failure callbacks on a dedicated blocking thread pool, so a slow callback can't starve the threads
+        /// which run the requests.<br />
+        /// Callbacks are then no longer called in the order the requests completed, and callbacks of different requests may run concurrently.
+        /// Failures of requests rejected before they were submitted are reported asynchronously too.
+        /// </summary>
+        public bool OffloadCallbacks
+        {
+            set => Config.OffloadCallbacks = value;
+        }
+
+        /// <inheritdoc cref="OffloadCallbacks"/>
+        public T WithOffloadCallbacks(bool offloadCallbacks)
+        {
+            OffloadCallbacks = offloadCallbacks;
+            return (T)this;
+        }
+        #endregion
+
+        #region Max Response Bytes
+        /// <summary>
+        /// Maximal size in bytes of a response, <c>0</c> means no limit. Larger responses, e.g. of <c>KEYS *</c> on a huge database,
+        /// fail with <see cref="RequestErrorType.ResponseTooLarge" /> instead of being passed to the caller, as a safety valve
+        /// against running out of memory.
+        /// </summary>
+        public ulong MaxResponseBytes
+        {
+            set => Config.MaxResponseBytes = value;
+        }
+
+        /// <inheritdoc cref="MaxResponseBytes"/>
+        public T WithMaxResponseBytes(ulong maxResponseBytes)
+        {
+            MaxResponseBytes = maxResponseBytes;
+            return (T)this;
+        }
+        #endregion
+
+        #region Max Command Argument Bytes
+        /// <summary>
+        /// Maximal size in bytes of an argument of a command, <c>0</c> means no limit. Commands with a larger argument, e.g. a bug
+        /// serializing a whole dataset into a single value, fail with <see cref="RequestErrorType.ArgumentTooLarge" /> without being sent,
+        /// as a client-side guardrail complementing the <c>proto-max-bulk-len</c> of the server.
+        /// </summary>
+        public uint MaxCommandArgBytes
+        {
+            set => Config.MaxCommandArgBytes = value;
+        }
+
+        /// <inheritdoc cref="MaxCommandArgBytes"/>
+        public T WithMaxCommandArgBytes(uint maxCommandArgBytes)
+        {
+            MaxCommandArgBytes = maxCommandArgBytes;
+            return (T)this;
+        }
+        #endregion
+
+        #region Command Trace
+        /// <summary>
+        /// Number of the last commands recorded in memory, retrievable by <see cref="BaseClient.GetCommandTrace" /> to reproduce issues,
+        /// <c>0</c> disables recording. Commands are recorded once they complete, with their name, route, node, latency and result type.<br />
+        /// If <paramref name="fullArgs" /> is set, the full arguments of the commands are recorded instead of a hash of them.
+        /// The arguments could contain sensitive data, and copying them adds to the overhead of recording.
+        /// </summary>
+        /// <param name="capacity">Number of the last commands to record.</param>
+        /// <param name="fullArgs">Whether to record the full arguments.</param>
+        public T WithCommandTrace(uint capacity, bool fullArgs = false)
+        {
+            Config.CommandTraceCapacity = capacity;
+            Config.CommandTraceFullArgs = fullArgs;
+            return (T)this;
+        }
+        #endregion
+
+        #region Command Filter
+        private BaseClient.RequestType[] _commandFilter = [];
+
+        /// <summary>
+        /// Allow only the given commands, other commands fail with <see cref="RequestErrorType.CommandNotAllowed" />
+        /// without reaching the server. Commands are matched by name, so custom commands are filtered too.<br />
+        /// This is enforced on the client side as defense in depth, alongside the ACLs of the server.
+        /// Replaces commands denied by <see cref="WithDeniedCommands" />.
+        /// </summary>
+        public T WithAllowedCommands(params BaseClient.RequestType[] commands)
+        {
+            Config.CommandFilterMode = CommandFilterMode.Allow;
+            _commandFilter = commands;
+            return (T)this;
+        }
+
+        /// <summary>
+        /// Deny the given commands, which fail with <see cref="RequestErrorType.CommandNotAllowed" /> without reaching the server,
+        /// e.g. <c>FLUSHALL</c> or <c>KEYS</c>. Commands are matched by name, so custom commands are filtered too.<br />
+        /// This is enforced on the client side as defense in depth, alongside the ACLs of the server.
+        /// Replaces commands allowed by <see cref="WithAllowedCommands" />.
+        /// </summary>
+        public T WithDeniedCommands(params BaseClient.RequestType[] commands)
+        {
+            Config.CommandFilterMode = CommandFilterMode.Deny;
+            _commandFilter = commands;
+            return (T)this;
+        }
+        #endregion
 
         public void Dispose() => Clean();
 
@@ -462,6 +1113,11 @@ public abstract class ConnectionConfiguration
                 Marshal.FreeHGlobal(Config.Addresses);
                 Config.Addresses = IntPtr.Zero;
             }
+            if (Config.CommandFilter != IntPtr.Zero)
+            {
+                Marshal.FreeHGlobal(Config.CommandFilter);
+                Config.CommandFilter = IntPtr.Zero;
+            }
         }
 
         internal ConnectionRequest Build()
@@ -474,6 +1130,9 @@ public abstract class ConnectionConfiguration
             {
                 Marshal.StructureToPtr(_addresses[i], Config.Addresses + (i * addressSize), false);
             }
+            Config.CommandFilterCount = (uint)_commandFilter.Length;
+            Config.CommandFilter = Marshal.AllocHGlobal(sizeof(int) * _commandFilter.Length);
+            Marshal.Copy(Array.ConvertAll(_commandFilter, command => (int)command), 0, Config.CommandFilter, _commandFilter.Length);
             return Config;
         }
     }
@@ -545,5 +1204,156 @@ public abstract class ConnectionConfiguration
         /// Complete the configuration with given settings.
         /// </summary>
         public new ClusterClientConfiguration Build() => new() { Request = base.Build() };
+
+        #region Fail Fast On Degraded
+        /// <summary>
+        /// Fail commands immediately with <see cref="RequestErrorType.Unavailable"/> error when they are routed to a node
+        /// which is considered unhealthy, instead of waiting for the client to reconnect or for the topology to settle.<br />
+        /// A node is considered unhealthy after a command sent to it failed with a connection or a cluster-down error,
+        /// until the next topology refresh. This trades availability for latency - commands which could succeed after
+        /// a short failover fail instead.
+        /// </summary>
+        public bool FailFastOnDegraded
+        {
+            set => Config.FailFastOnDegraded = value;
+        }
+
+        /// <inheritdoc cref="FailFastOnDegraded"/>
+        public ClusterClientConfigurationBuilder WithFailFastOnDegraded(bool failFastOnDegraded)
+        {
+            FailFastOnDegraded = failFastOnDegraded;
+            return this;
+        }
+        #endregion
+
+        #region Default Route
+        /// <summary>
+        /// Route of commands submitted without an explicit route.<br />
+        /// The precedence is: explicit per-command route > default route > command-type default.
+        /// The default route only applies to commands which may be served by a replica:
+        /// <list type="bullet">
+        /// <item>Commands with keys are routed to the slot of their keys, with the <see cref="Route.SlotType"/> of the default route,
+        /// if it is a <see cref="Route.SlotIdRoute"/> or a <see cref="Route.SlotKeyRoute"/>. Other routes don't apply to them.</item>
+        /// <item>Read-only commands without keys, which are otherwise routed to a random node, are routed by the default route.</item>
+        /// </list>
+        /// Commands which must be routed to a primary, like writes, and commands routed to multiple nodes are routed as usual.
+        /// </summary>
+        public Route? DefaultRoute
+        {
+            set
+            {
+                Config.HasDefaultRoute = value != null;
+                Config.DefaultRoute = value?.ToFfi() ?? default;
+            }
+        }
+
+        /// <inheritdoc cref="DefaultRoute"/>
+        public ClusterClientConfigurationBuilder WithDefaultRoute(Route? defaultRoute)
+        {
+            DefaultRoute = defaultRoute;
+            return this;
+        }
+        #endregion
+
+        #region Periodic Topology Refresh
+        /// <summary>
+        /// Interval in seconds of a proactive refresh of the cluster slot map, which catches slot migrations and failovers
+        /// before commands are redirected. A <c>TopologyChanged</c> event is reported when the slot map changes.<br />
+        /// If not set, the default periodic checks of the client are used.
+        /// </summary>
+        public uint PeriodicTopologyRefreshSecs
+        {
+            set => Config.PeriodicTopologyRefreshSecs = value;
+        }
+
+        /// <inheritdoc cref="PeriodicTopologyRefreshSecs"/>
+        public ClusterClientConfigurationBuilder WithPeriodicTopologyRefreshSecs(uint periodicTopologyRefreshSecs)
+        {
+            PeriodicTopologyRefreshSecs = periodicTopologyRefreshSecs;
+            return this;
+        }
+        #endregion
+
+        #region Read Your Writes
+        /// <summary>
+        /// Window in milliseconds after a write to a slot, in which reads of the slot are routed to the primary,
+        /// so they observe the write even if the replicas haven't applied it yet. Reads with an explicit route aren't affected.<br />
+        /// Replication is asynchronous, so reads served by replicas may be stale. This trades the load of the replicas for consistency:
+        /// reads of recently written keys are served by the primary, and reads after the window may still be stale if replication lags behind.<br />
+        /// If not set, reads are routed according to <see cref="ReadFrom" />.
+        /// </summary>
+        public uint ReadYourWritesWindowMs
+        {
+            set => Config.ReadYourWritesWindowMs = value;
+        }
+
+        /// <inheritdoc cref="ReadYourWritesWindowMs"/>
+        public ClusterClientConfigurationBuilder WithReadYourWritesWindowMs(uint readYourWritesWindowMs)
+        {
+            ReadYourWritesWindowMs = readYourWritesWindowMs;
+            return this;
+        }
+        #endregion
+
+        #region Redirection Event Threshold
+        /// <summary>
+        /// Rate of <c>MOVED</c> and <c>ASK</c> redirections per second above which a <c>Redirection</c> event is reported,
+        /// with the slot and the target node of the redirection. Frequent redirections indicate topology churn, e.g. rebalancing,
+        /// which helps to correlate latency spikes.<br />
+        /// If not set, no event is reported. The redirections are counted regardless.
+        /// </summary>
+        public uint RedirectionEventThreshold
+        {
+            set => Config.RedirectionEventThreshold = value;
+        }
+
+        /// <inheritdoc cref="RedirectionEventThreshold"/>
+        public ClusterClientConfigurationBuilder WithRedirectionEventThreshold(uint redirectionEventThreshold)
+        {
+            RedirectionEventThreshold = redirectionEventThreshold;
+            return this;
+        }
+        #endregion
+
+        #region Circuit Breaker
+        /// <summary>
+        /// Number of consecutive commands failing because the cluster is down (<c>CLUSTERDOWN</c>) after which the circuit breaker opens.<br />
+        /// While the breaker is open, commands fail fast with <see cref="RequestErrorType.ClusterDown"/> error instead of being
+        /// retried against the recovering cluster. A <c>CircuitOpen</c> event is reported once it opens, and a <c>CircuitClosed</c> event
+        /// once the topology is refreshed successfully after a backoff, which starts at 500 milliseconds and doubles while the cluster
+        /// keeps failing, up to 10 seconds.<br />
+        /// If not set, the breaker is disabled.
+        /// </summary>
+        public uint CircuitBreakerThreshold
+        {
+            set => Config.CircuitBreakerThreshold = value;
+        }
+
+        /// <inheritdoc cref="CircuitBreakerThreshold"/>
+        public ClusterClientConfigurationBuilder WithCircuitBreakerThreshold(uint circuitBreakerThreshold)
+        {
+            CircuitBreakerThreshold = circuitBreakerThreshold;
+            return this;
+        }
+        #endregion
+
+        #region Retry Writes On READONLY
+        /// <summary>
+        /// Retry a write once on the primary of its slot if it reached a replica, which rejected it with <c>READONLY</c>,
+        /// e.g. by a stale topology during a failover. The topology is refreshed first, and the write is sent to the primary it reports.<br />
+        /// Writes which fail again, writes routed to multiple nodes or by address, and reads fail with <see cref="RequestErrorType.ReadOnly"/>.
+        /// </summary>
+        public bool RetryWritesOnReadOnly
+        {
+            set => Config.RetryWritesOnReadOnly = value;
+        }
+
+        /// <inheritdoc cref="RetryWritesOnReadOnly"/>
+        public ClusterClientConfigurationBuilder WithRetryWritesOnReadOnly(bool retryWritesOnReadOnly)
+        {
+            RetryWritesOnReadOnly = retryWritesOnReadOnly;
+            return this;
+        }
+        #endregion
     }
 }
@@ -0,0 +1,105 @@
+﻿// Copyright Valkey GLIDE Project Contributors - SPDX Identifier: Apache-2.0
+
+namespace Valkey.Glide;
+
+/// <summary>
+/// Type of a request error, reported by GLIDE.
+/// </summary>
+public enum RequestErrorType : uint
+{
+    Unspecified = 0,
+    ExecAbort = 1,
+    Timeout = 2,
+    Disconnect = 3,
+    /// <summary>
+    /// The server rejected the command because <c>maxmemory</c> was reached.
+    /// </summary>
+    OutOfMemory = 4,
+    /// <summary>
+    /// The server rejected the write command because it can't persist data.
+    /// </summary>
+    Misconfiguration = 5,
+    /// <summary>
+    /// The node serving the request is unavailable, e.g. a primary is mid-failover, or the node is considered unhealthy
+    /// with <c>FailFastOnDegraded</c>.
+    /// </summary>
+    Unavailable = 6,
+    /// <summary>
+    /// The user has no permissions to run the command or to access the keys or channels.
+    /// </summary>
+    NoPermission = 7,
+    /// <summary>
+    /// The request was submitted after the client was closed.
+    /// </summary>
+    ClientClosed = 8,
+    /// <summary>
+    /// The requested statistic isn't tracked under the configured <c>maxmemory-policy</c>,
+    /// e.g. <c>OBJECT FREQ</c> without an LFU policy, or <c>OBJECT IDLETIME</c> with an LFU policy.
+    /// </summary>
+    MaxMemoryPolicyMismatch = 9,
+    /// <summary>
+    /// The server rejected the credentials of a connection.
+    /// </summary>
+    AuthenticationFailed = 10,
+    /// <summary>
+    /// The command isn't allowed by the command filter of the client, so it wasn't sent to the server.
+    /// </summary>
+    CommandNotAllowed = 11,
+    /// <summary>
+    /// The server doesn't support the command or some of its options, e.g. conditions of <c>EXPIRE</c> before 7.0.
+    /// </summary>
+    UnsupportedByServer = 12,
+    /// <summary>
+    /// A connection lost its authentication and re-authenticating it with the credentials of the client failed.
+    /// </summary>
+    ReauthenticationFailed = 13,
+    /// <summary>
+    /// The response exceeded the maximal size configured by <c>MaxResponseBytes</c>, so it wasn't passed to the caller.
+    /// </summary>
+    ResponseTooLarge = 14,
+    /// <summary>
+    /// The client reached the maximal number of pending commands configured by <c>MaxPendingCommands</c>,
+    /// or the node reached the maximal number of in-flight requests configured by <c>MaxInflightRequestsPerNode</c>,
+    /// so the command wasn't sent.
+    /// </summary>
+    Backpressure = 15,
+    /// <summary>
+    /// The blocking command was cancelled by its cancellation handle.
+    /// </summary>
+    Cancelled = 16,
+    /// <summary>
+    /// An argument of the command exceeded the maximal size configured by <c>MaxCommandArgBytes</c>, so the command wasn't sent.
+    /// </summary>
+    ArgumentTooLarge = 17,
+    /// <summary>
+    /// The configuration of the client is invalid, e.g. inconsistent settings, so the client wasn't created.
+    /// The message names the invalid setting.
+    /// </summary>
+    InvalidConfig = 18,
+    /// <summary>
+    /// The cluster is down, e.g. not enough primaries are reachable, or the circuit breaker configured by
+    /// <c>CircuitBreakerThreshold</c> is open, so the command wasn't sent.
+    /// </summary>
+    ClusterDown = 19,
+    /// <summary>
+    /// A write was sent to a replica (<c>READONLY</c>), e.g. by a stale topology during a failover,
+    /// and it wasn't retried on the primary, see <c>RetryWritesOnReadOnly</c>.
+    /// </summary>
+    ReadOnly = 20,
+    /// <summary>
+    /// The slot of a command routed by a topology snapshot moved to another node since the snapshot was taken
+    /// (<c>MOVED</c> or <c>ASK</c>), so the command wasn't redirected.
+    /// </summary>
+    SlotMoved = 21,
+}
+
+/// <summary>
+/// An error returned by GLIDE for a failed request.
+/// </summary>
+public class RequestException(RequestErrorType errorType, string message) : Exception(message)
+{
+    /// <summary>
+    /// The type of the error.
+    /// </summary>
+    public RequestErrorType ErrorType { get; } = errorType;
+}
@@ -1,6 +1,9 @@
 ﻿// Copyright Valkey GLIDE Project Contributors - SPDX Identifier: Apache-2.0
 
+using System.Globalization;
+using System.Numerics;
 using System.Runtime.InteropServices;
+using System.Text;
 
 namespace Valkey.Glide.Internals;
 
@@ -26,6 +29,10 @@ internal class ResponseHandler
         Set = 7,
         BulkString = 8,
         OK = 9,
+        VerbatimString = 10,
+        Error = 11,
+        WithMetadata = 12,
+        BigNumber = 13,
     }
 
     public static object? HandleResponse(IntPtr valuePtr)
@@ -41,6 +48,13 @@ internal class ResponseHandler
         return new GlideString(bytes);
     }
 
+    private static VerbatimString CreateVerbatimString(GlideValue value)
+    {
+        // The value is stored as `<format>:<text>`, where the format is 3 bytes long.
+        byte[] bytes = CreateString(value).Bytes;
+        return new VerbatimString(Encoding.ASCII.GetString(bytes, 0, 3), new GlideString(bytes[4..]));
+    }
+
     private static object?[] CreateArray(GlideValue value)
     {
         object?[] values = new object?[value.Size];
@@ -65,17 +79,35 @@ internal class ResponseHandler
         return res;
     }
 
+    private static RequestException CreateError(GlideValue value)
+    {
+        // The error is stored as an array of its type and its message.
+        object?[] values = CreateArray(value);
+        return new RequestException((RequestErrorType)(long)values[0]!, values[1]!.ToString()!);
+    }
+
+    private static ResponseWithMetadata CreateWithMetadata(GlideValue value)
+    {
+        // The reply is stored as an array of the reply and a map of its metadata.
+        object?[] values = CreateArray(value);
+        return new ResponseWithMetadata(values[0], (Dictionary<GlideString, object?>)values[1]!);
+    }
+
     private static object? TraverseValue(GlideValue value) => value.Type switch
     {
         ValueType.Null => null,
         ValueType.Int => (long)value.Value,
-        ValueType.Float => (double)value.Value,
+        ValueType.Float => BitConverter.Int64BitsToDouble((long)value.Value),
         ValueType.Bool => value.Value != 0,
         ValueType.BulkString or ValueType.String => CreateString(value),
         ValueType.Array => CreateArray(value),
         ValueType.Map => CreateMap(value),
         ValueType.Set => CreateArray(value).ToHashSet(),
         ValueType.OK => new GlideString("OK"),
+        ValueType.VerbatimString => CreateVerbatimString(value),
+        ValueType.Error => CreateError(value),
+        ValueType.WithMetadata => CreateWithMetadata(value),
+        ValueType.BigNumber => BigInteger.Parse(CreateString(value).GetString(), CultureInfo.InvariantCulture),
         _ => throw new NotImplementedException(),
     };
 }
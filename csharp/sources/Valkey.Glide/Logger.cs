@@ -75,6 +75,10 @@ public class Logger
         byte[]? buffer = filename is null ? null : Encoding.UTF8.GetBytes(filename);
         s_loggerLevel = InitInternalLogger(Convert.ToInt32(level), buffer);
     }
+
+    // flush the logs written so far to the console or to the log file, e.g. to keep the last error logs before the process exits.
+    // closing a client flushes the logs as well. flushing can be repeated, and logging keeps working after all clients are closed.
+    public static void FlushLogs() => FlushInternalLogger();
     #endregion public methods
 
     #region FFI function declaration
@@ -84,5 +88,8 @@ public class Logger
     [DllImport("libglide_rs", CallingConvention = CallingConvention.Cdecl, EntryPoint = "init")]
     private static extern Level InitInternalLogger(int level, byte[]? filename);
 
+    [DllImport("libglide_rs", CallingConvention = CallingConvention.Cdecl, EntryPoint = "flush_logs")]
+    private static extern void FlushInternalLogger();
+
     #endregion
 }
@@ -0,0 +1,23 @@
+﻿// Copyright Valkey GLIDE Project Contributors - SPDX Identifier: Apache-2.0
+
+namespace Valkey.Glide;
+
+/// <summary>
+/// A reply with its metadata, returned by custom commands if <c>ResponseMetadata</c> is enabled in the configuration of the client.
+/// </summary>
+/// <param name="Value">The reply.</param>
+/// <param name="Metadata">A map of <c>source</c> - the kind of node which served the reply (<c>primary</c>, <c>replica</c> or <c>unknown</c>),
+/// <c>node</c> - the address of the node if it is known, and <c>attributes</c> - the RESP3 attributes the server sent with the reply, if any.</param>
+public sealed record ResponseWithMetadata(object? Value, Dictionary<GlideString, object?> Metadata)
+{
+    /// <summary>
+    /// The kind of node which served the reply: <c>primary</c>, <c>replica</c>, or <c>unknown</c> if the node was chosen by the
+    /// <c>ReadFrom</c> strategy or the command was sent to multiple nodes.
+    /// </summary>
+    public string Source => Metadata[new GlideString("source")]!.ToString()!;
+
+    /// <summary>
+    /// The address of the node which served the reply, if it is known.
+    /// </summary>
+    public string? Node => Metadata[new GlideString("node")]?.ToString();
+}
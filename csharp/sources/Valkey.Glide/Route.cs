@@ -10,11 +10,14 @@ namespace Valkey.Glide;
 /// Basic class. Please use one of the following implementations:
 /// <list type="bullet">
 /// <item><see cref="RandomRoute"/></item>
+/// <item><see cref="RandomReplicaRoute"/></item>
 /// <item><see cref="AllNodesRoute"/></item>
 /// <item><see cref="AllPrimariesRoute"/></item>
 /// <item><see cref="SlotIdRoute"/></item>
 /// <item><see cref="SlotKeyRoute"/></item>
 /// <item><see cref="ByAddressRoute"/></item>
+/// <item><see cref="PrimaryPreferredRoute"/></item>
+/// <item><see cref="BySlotKeyRoute"/></item>
 /// </list>
 /// </summary>
 public abstract class Route
@@ -51,8 +54,20 @@ public abstract class Route
         internal override RouteInfo ToFfi() => ToFfi(RouteType.AllPrimaries);
     }
 
+    /// <summary>
+    /// Route request to a replica of a random shard, even if the <see cref="ReadFromStrategy"/> is <see cref="ReadFromStrategy.Primary"/>.<br />
+    /// Only read-only commands can be routed to a replica, other commands fail without being sent.
+    /// To route a command with a key to a replica of the key's shard, use <see cref="SlotKeyRoute"/> with <see cref="SlotType.Replica"/>.
+    /// </summary>
+    public sealed class RandomReplicaRoute : Route, ISingleNodeRoute, ISimpleRoute
+    {
+        internal override RouteInfo ToFfi() => ToFfi(RouteType.RandomReplica);
+    }
+
     /// <inheritdoc cref="RandomRoute"/>
     public static readonly RandomRoute Random = new();
+    /// <inheritdoc cref="RandomReplicaRoute"/>
+    public static readonly RandomReplicaRoute RandomReplica = new();
     /// <inheritdoc cref="AllNodesRoute"/>
     public static readonly AllNodesRoute AllNodes = new();
     /// <inheritdoc cref="AllPrimariesRoute"/>
@@ -99,7 +114,7 @@ public abstract class Route
         public readonly string SlotKey = slotKey;
         public new readonly SlotType SlotType = slotType;
 
-        internal override RouteInfo ToFfi() => ToFfi(RouteType.SlotId, slotKeyInfo: (SlotKey, SlotType));
+        internal override RouteInfo ToFfi() => ToFfi(RouteType.SlotKey, slotKeyInfo: (SlotKey, SlotType));
     }
 
     /// <summary>
@@ -139,7 +154,38 @@ public abstract class Route
             Port = int.Parse(parts[1]);
         }
 
-        internal override RouteInfo ToFfi() => ToFfi(RouteType.SlotId, address: (Host, Port));
+        internal override RouteInfo ToFfi() => ToFfi(RouteType.ByAddress, address: (Host, Port));
+    }
+
+    /// <summary>
+    /// Route a request to the primary of a slot, for fresh reads, and fall back to a replica of the slot if the primary is overloaded
+    /// (<see cref="RequestErrorType.Backpressure"/>) or unavailable (<see cref="RequestErrorType.Disconnect"/> or <see cref="RequestErrorType.Unavailable"/>).
+    /// Other errors, e.g. <c>WRONGTYPE</c>, are reported as is. Only read-only commands fall back, and only once.<br />
+    /// This is the inverse of <c>FallbackToPrimaryOnReplicaError</c>, which doesn't apply to requests routed by this route.
+    /// Only in cluster mode.
+    /// </summary>
+    /// <param name="slotKey">The request will be sent to nodes managing this key. If not set, the request is routed by the keys of the command,
+    /// and commands without keys are routed by their default route.</param>
+    public class PrimaryPreferredRoute(string? slotKey = null) : Route, ISingleNodeRoute
+    {
+        public readonly string? SlotKey = slotKey;
+
+        internal override RouteInfo ToFfi() => ToFfi(RouteType.PrimaryPreferred, slotKeyInfo: SlotKey is null ? null : (SlotKey, SlotType.Primary));
+    }
+
+    /// <summary>
+    /// Route a request to the node owning the slot of an arbitrary routing key, which isn't a key of the command, e.g. a keyless
+    /// administrative command to the node holding the keys of the hash tag <c>{tenant123}</c>.<br />
+    /// Unlike <see cref="SlotKeyRoute"/>, the routing key isn't prefixed by the key prefix of the client.
+    /// </summary>
+    /// <param name="routingKey">The request will be sent to nodes owning the slot of this key.</param>
+    /// <param name="slotType">Defines type of the node being addressed.</param>
+    public class BySlotKeyRoute(string routingKey, SlotType slotType = SlotType.Primary) : Route, ISingleNodeRoute
+    {
+        public readonly string RoutingKey = routingKey;
+        public new readonly SlotType SlotType = slotType;
+
+        internal override RouteInfo ToFfi() => ToFfi(RouteType.BySlotKey, slotKeyInfo: (RoutingKey, SlotType));
     }
 
     internal Route() { }
@@ -162,8 +208,11 @@ public abstract class Route
         AllNodes,
         AllPrimaries,
         SlotId,
-        SlotType,
+        SlotKey,
         ByAddress,
+        RandomReplica,
+        PrimaryPreferred,
+        BySlotKey,
     }
 
     [StructLayout(LayoutKind.Sequential, CharSet = CharSet.Ansi)]
@@ -0,0 +1,18 @@
+﻿// Copyright Valkey GLIDE Project Contributors - SPDX Identifier: Apache-2.0
+
+namespace Valkey.Glide;
+
+/// <summary>
+/// A RESP3 verbatim string, returned for example by <c>INFO</c> or <c>CLIENT INFO</c>, with a hint of its format.
+/// </summary>
+/// <param name="Format">The format of the text - <c>txt</c> for plain text or <c>mkd</c> for markdown.</param>
+/// <param name="Text">The text.</param>
+public sealed record VerbatimString(string Format, GlideString Text)
+{
+    /// <summary>
+    /// Whether the text is markdown.
+    /// </summary>
+    public bool IsMarkdown => Format == "mkd";
+
+    public override string ToString() => Text.ToString();
+}
@@ -14,7 +14,7 @@ public class ClusterClientTests
         SortedSet<string> ports = [];
         foreach (int i in Enumerable.Range(0, 100))
         {
-            string res = ((await client.CustomCommand(["info", "server"], Route.Random))! as GlideString)!;
+            string res = (await client.CustomCommand(["info", "server"], Route.Random))!.ToString();
             foreach (string line in res!.Split("\r\n"))
             {
                 if (line.Contains("tcp_port"))
@@ -36,22 +36,44 @@ public class ClusterClientTests
     {
         GlideClusterClient client = TestConfiguration.DefaultClusterClient();
 
-        string res = (await client.CustomCommand(["info", "replication"], new SlotKeyRoute("abc", SlotType.Primary))! as GlideString)!;
+        string res = (await client.CustomCommand(["info", "replication"], new SlotKeyRoute("abc", SlotType.Primary)))!.ToString();
         Assert.Contains("role:master", res);
 
-        res = (await client.CustomCommand(["info", "replication"], new SlotKeyRoute("abc", SlotType.Replica))! as GlideString)!;
+        res = (await client.CustomCommand(["info", "replication"], new SlotKeyRoute("abc", SlotType.Replica)))!.ToString();
         Assert.Contains("role:slave", res);
 
-        res = (await client.CustomCommand(["info", "replication"], new SlotIdRoute(42, SlotType.Primary))! as GlideString)!;
+        res = (await client.CustomCommand(["info", "replication"], new SlotIdRoute(42, SlotType.Primary)))!.ToString();
         Assert.Contains("role:master", res);
 
-        res = (await client.CustomCommand(["info", "replication"], new SlotIdRoute(42, SlotType.Replica))! as GlideString)!;
+        res = (await client.CustomCommand(["info", "replication"], new SlotIdRoute(42, SlotType.Replica)))!.ToString();
         Assert.Contains("role:slave", res);
 
-        res = (await client.CustomCommand(["info", "replication"], new ByAddressRoute(TestConfiguration.CLUSTER_HOSTS[0].host, TestConfiguration.CLUSTER_HOSTS[0].port))! as GlideString)!;
+        res = (await client.CustomCommand(["info", "replication"], new ByAddressRoute(TestConfiguration.CLUSTER_HOSTS[0].host, TestConfiguration.CLUSTER_HOSTS[0].port)))!.ToString();
         Assert.Contains("# Replication", res);
     }
 
+    [Theory]
+    [InlineData("sintercard", "2", "{0}", "{1}")]
+    [InlineData("lmpop", "2", "{0}", "{1}", "left")]
+    [InlineData("zmpop", "2", "{0}", "{1}", "min")]
+    [InlineData("blmpop", "0.1", "2", "{0}", "{1}", "left")]
+    [InlineData("bzmpop", "0.1", "2", "{0}", "{1}", "min")]
+    [InlineData("zdiff", "2", "{0}", "{1}")]
+    public async Task MultiKeyCommandsWithKeyCountAreRoutedByKeys(params string[] command)
+    {
+        Assert.SkipWhen(TestConfiguration.SERVER_VERSION < new Version("7.0.0"), "Commands were added in version 7.0.0");
+        GlideClusterClient client = TestConfiguration.DefaultClusterClient();
+
+        string tag = Guid.NewGuid().ToString();
+        GlideString[] sameSlot = [.. command.Select(arg => string.Format(arg, $"{{{tag}}}a", $"{{{tag}}}b"))];
+        // Keys from the same slot are routed to the node which owns the slot
+        _ = await client.CustomCommand(sameSlot);
+
+        GlideString[] crossSlot = [.. command.Select(arg => string.Format(arg, "abc", "klm"))];
+        RequestException exception = await Assert.ThrowsAsync<RequestException>(async () => await client.CustomCommand(crossSlot));
+        Assert.Contains("CROSSSLOT", exception.Message);
+    }
+
     [Fact]
     public async Task CustomCommandWithMultiNodeRoute()
     {
@@ -63,4 +85,66 @@ public class ClusterClientTests
         long res = (long)(await client.CustomCommand(["dbsize"], AllPrimaries))!;
         Assert.True(res >= 3);
     }
+
+    [Fact]
+    public async Task DefaultRouteAppliesToReadsOnly()
+    {
+        GlideClusterClient client = new(TestConfiguration.DefaultClusterClientConfig()
+            .WithDefaultRoute(new SlotIdRoute(0, SlotType.Replica))
+            .Build());
+
+        // keyless reads are routed by the default route, unless a route is given
+        object?[] role = (await client.CustomCommand(["role"]) as object?[])!;
+        Assert.Equal("slave", role[0]!.ToString());
+        role = (await client.CustomCommand(["role"], new SlotIdRoute(0, SlotType.Primary)) as object?[])!;
+        Assert.Equal("master", role[0]!.ToString());
+
+        // writes are still routed to the primary, reads with keys are routed to a replica of their slot
+        string key = Guid.NewGuid().ToString();
+        Assert.Equal("OK", (await client.CustomCommand(["set", key, "value"]))!.ToString());
+        _ = await client.CustomCommand(["wait", "1", "1000"], AllPrimaries);
+        Assert.Equal("value", (await client.CustomCommand(["get", key]))!.ToString());
+    }
+
+    [Fact]
+    public async Task ClientKillByMaxAgeOnSpecificNode()
+    {
+        Assert.SkipWhen(TestConfiguration.SERVER_VERSION < new Version("7.4.0"), "MAXAGE filter was added in version 7.4.0");
+        GlideClusterClient client = TestConfiguration.DefaultClusterClient();
+        ByAddressRoute route = new(TestConfiguration.CLUSTER_HOSTS[0].host, TestConfiguration.CLUSTER_HOSTS[0].port);
+
+        // no client is that old, so none is killed
+        long killed = (long)(await client.CustomCommand(["client", "kill", "maxage", "1000000", "skipme", "yes"], route))!;
+        Assert.Equal(0, killed);
+    }
+
+    [Fact]
+    public async Task RandomReplicaRouteServesReadsOnly()
+    {
+        GlideClusterClient client = TestConfiguration.DefaultClusterClient();
+        string key = Guid.NewGuid().ToString();
+
+        string res = (await client.CustomCommand(["info", "replication"], Route.RandomReplica))!.ToString();
+        Assert.Contains("role:slave", res);
+
+        RequestException exception = await Assert.ThrowsAsync<RequestException>(async () => await client.CustomCommand(["set", key, "value"], Route.RandomReplica));
+        Assert.Contains("read-only", exception.Message);
+        _ = await Assert.ThrowsAsync<RequestException>(async () => await client.CustomCommand(["set", key, "value"], new SlotKeyRoute(key, SlotType.Replica)));
+    }
+
+    [Fact]
+    public async Task PrimaryPreferredRouteServesReadsByPrimary()
+    {
+        GlideClusterClient client = TestConfiguration.DefaultClusterClient();
+        string key = Guid.NewGuid().ToString();
+
+        object?[] role = (await client.CustomCommand(["role"], new PrimaryPreferredRoute(key)) as object?[])!;
+        Assert.Equal("master", role[0]!.ToString());
+        Assert.Equal("OK", (await client.CustomCommand(["set", key, "value"], new PrimaryPreferredRoute()))!.ToString());
+        Assert.Equal("value", (await client.CustomCommand(["get", key], new PrimaryPreferredRoute()))!.ToString());
+
+        // logical errors don't fall back to a replica
+        RequestException exception = await Assert.ThrowsAsync<RequestException>(async () => await client.CustomCommand(["hget", key, "field"], new PrimaryPreferredRoute()));
+        Assert.Contains("WRONGTYPE", exception.Message);
+    }
 }
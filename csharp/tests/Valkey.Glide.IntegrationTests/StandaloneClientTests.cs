@@ -17,6 +17,17 @@ public class StandaloneClientTests
         );
     }
 
+    [Fact]
+    public async Task VerbatimStringKeepsFormat()
+    {
+        GlideClient client = TestConfiguration.DefaultStandaloneClient();
+        // INFO is a verbatim string in RESP3, LOLWUT isn't used since glide-core converts it to a plain string
+        VerbatimString info = Assert.IsType<VerbatimString>(await client.CustomCommand(["INFO", "SERVER"]));
+        Assert.Equal("txt", info.Format);
+        Assert.False(info.IsMarkdown);
+        Assert.Contains("# Server", info.Text.ToString());
+    }
+
     [Fact]
     public async Task CustomCommandWithBinary()
     {
@@ -37,6 +48,287 @@ public class StandaloneClientTests
         Assert.Equal(dump, await client.Get(key3));
     }
 
+    [Theory]
+    [InlineData(ConnectionConfiguration.Protocol.RESP2)]
+    [InlineData(ConnectionConfiguration.Protocol.RESP3)]
+    public async Task HashWithBinaryFields(ConnectionConfiguration.Protocol protocol)
+    {
+        GlideClient client = new(TestConfiguration.DefaultClientConfig().WithProtocolVersion(protocol).Build());
+        string key = Guid.NewGuid().ToString();
+        gs field = new byte[] { 0xff, 0x00 };
+        gs value = new byte[] { 0x00, 0xff };
+
+        Assert.Equal(1L, await client.CustomCommand(["hset", key, field, value]));
+        Assert.Equal(value, await client.CustomCommand(["hget", key, field]));
+
+        // HGETALL is converted to a map by glide-core regardless of the protocol, with binary-safe fields and values
+        Dictionary<gs, object?> hash = Assert.IsType<Dictionary<gs, object?>>(await client.CustomCommand(["hgetall", key]));
+        (gs actualField, object? actualValue) = Assert.Single(hash);
+        Assert.Equal(field.Bytes, actualField.Bytes);
+        Assert.Equal(value.Bytes, Assert.IsType<gs>(actualValue).Bytes);
+    }
+
+    [Fact]
+    public async Task ConfigGetIsMapForBothProtocols()
+    {
+        GlideClient resp2 = new(TestConfiguration.DefaultClientConfig().WithProtocolVersion(ConnectionConfiguration.Protocol.RESP2).Build());
+        GlideClient resp3 = new(TestConfiguration.DefaultClientConfig().WithProtocolVersion(ConnectionConfiguration.Protocol.RESP3).Build());
+
+        // RESP2 replies with a flat array, which is converted to a map like the RESP3 reply
+        Dictionary<gs, object?> config2 = Assert.IsType<Dictionary<gs, object?>>(await resp2.CustomCommand(["config", "get", "maxmemory*"]));
+        Dictionary<gs, object?> config3 = Assert.IsType<Dictionary<gs, object?>>(await resp3.CustomCommand(["config", "get", "maxmemory*"]));
+        Assert.True(config2.Count > 1);
+        Assert.Equal(config2.Keys.Select(k => k.ToString()).OrderBy(k => k), config3.Keys.Select(k => k.ToString()).OrderBy(k => k));
+        Assert.Contains(config2.Keys, k => k.ToString() == "maxmemory-policy");
+    }
+
+    [Fact]
+    public async Task KeyPrefixIsAppliedToKeysAndPatterns()
+    {
+        string prefix = $"{Guid.NewGuid()}:";
+        GlideClient client = new(TestConfiguration.DefaultClientConfig().WithKeyPrefix(prefix).WithStripKeyPrefix(true).Build());
+        GlideClient plain = TestConfiguration.DefaultStandaloneClient();
+
+        Assert.Equal("OK", await client.Set("key1", "value1"));
+        Assert.Equal("OK".ToGlideString(), await client.CustomCommand(["mset", "key2", "value2", "key3", "value3"]));
+        Assert.Equal("value1", (await plain.Get(prefix + "key1"))!);
+        Assert.Equal("value3", (await plain.Get(prefix + "key3"))!);
+        Assert.Equal("value2", (await client.Get("key2"))!);
+
+        // the pattern is prefixed, and the prefix is stripped from the keys returned
+        object?[] keys = Assert.IsType<object?[]>(await client.CustomCommand(["keys", "key*"]));
+        Assert.Equal(["key1", "key2", "key3"], keys.Select(k => k!.ToString()).OrderBy(k => k));
+    }
+
+    [Fact]
+    public async Task EmptyRepliesAreNormalizedToNull()
+    {
+        GlideClient client = new(TestConfiguration.DefaultClientConfig().WithNormalizeEmptyReplies(true).Build());
+        GlideClient plain = TestConfiguration.DefaultStandaloneClient();
+        string key = Guid.NewGuid().ToString();
+
+        Assert.Empty(Assert.IsType<object?[]>(await plain.CustomCommand(["lrange", key, "0", "-1"])));
+        Assert.Null(await client.CustomCommand(["lrange", key, "0", "-1"]));
+        Assert.Null(await client.CustomCommand(["hgetall", key]));
+
+        // nested empty collections are kept
+        object?[] scan = Assert.IsType<object?[]>(await client.CustomCommand(["scan", "0", "match", key]));
+        Assert.Empty(Assert.IsType<object?[]>(scan[1]));
+    }
+
+    [Fact]
+    public async Task ResponseMetadataReportsServingNode()
+    {
+        GlideClient client = new(TestConfiguration.DefaultClientConfig().WithResponseMetadata(true).Build());
+        string key = Guid.NewGuid().ToString();
+
+        Assert.Equal("OK", await client.Set(key, "value"));
+        ResponseWithMetadata response = Assert.IsType<ResponseWithMetadata>(await client.CustomCommand(["get", key]));
+        Assert.Equal("value", response.Value!.ToString());
+        Assert.Equal("primary", response.Source);
+        Assert.NotNull(response.Node);
+
+        // typed commands return the reply only
+        Assert.Equal("value", (await client.Get(key))!);
+    }
+
+    [Fact]
+    public async Task ErrorMessagesAreFormattedAsJson()
+    {
+        GlideClient client = new(TestConfiguration.DefaultClientConfig().WithErrorMessageFormat(ConnectionConfiguration.ErrorMessageFormat.Json).Build());
+        string key = Guid.NewGuid().ToString();
+        Assert.Equal("OK", await client.Set(key, "value"));
+
+        RequestException exception = await Assert.ThrowsAsync<RequestException>(async () => await client.CustomCommand(["lpush", key, "element"]));
+        using System.Text.Json.JsonDocument message = System.Text.Json.JsonDocument.Parse(exception.Message);
+        Assert.Equal("Unspecified", message.RootElement.GetProperty("type").GetString());
+        Assert.Equal("WRONGTYPE", message.RootElement.GetProperty("code").GetString());
+        Assert.Contains("wrong kind of value", message.RootElement.GetProperty("detail").GetString());
+    }
+
+    [Fact]
+    public void EffectiveConfigAppliesDefaults()
+    {
+        GlideClient client = new(TestConfiguration.DefaultClientConfig().WithRequestTimeout(500).Build());
+        Dictionary<string, object?> config = client.GetEffectiveConfig().ToDictionary(kv => kv.Key.ToString(), kv => kv.Value);
+
+        Assert.Equal(500L, config["request_timeout_ms"]);
+        Assert.Equal(250L, config["connection_timeout_ms"]);
+        Assert.Equal("Primary", config["read_from"]!.ToString());
+        Assert.Equal(false, config["cluster_mode"]);
+        Assert.Null(config["password"]);
+    }
+
+    [Fact]
+    public async Task TlsSniHostnameIsIgnoredWithoutTls()
+    {
+        GlideClient client = new(TestConfiguration.DefaultClientConfig().WithTlsSniHostname("valkey.example.com").Build());
+        Dictionary<string, object?> config = client.GetEffectiveConfig().ToDictionary(kv => kv.Key.ToString(), kv => kv.Value);
+
+        Assert.Equal("valkey.example.com", config["tls_sni_hostname"]!.ToString());
+        Assert.Equal("PONG", (await client.CustomCommand(["ping"]))!.ToString());
+    }
+
+    [Fact]
+    public async Task DumpAndRestoreHashWithManyFields()
+    {
+        GlideClient client = TestConfiguration.DefaultStandaloneClient();
+        string key1 = Guid.NewGuid().ToString();
+        string key2 = Guid.NewGuid().ToString();
+        List<gs> args = ["hset", key1];
+        for (int i = 0; i < 1000; i++)
+        {
+            args.Add($"field{i}");
+            args.Add(Guid.NewGuid().ToString());
+        }
+        Assert.Equal(1000, (long)(await client.CustomCommand([.. args]))!);
+
+        gs dump = (await client.CustomCommand(["DUMP", key1]) as gs)!;
+        Assert.Equal("OK".ToGlideString(), await client.CustomCommand(["RESTORE", key2, "0", dump, "REPLACE", "IDLETIME", "100"]));
+
+        Assert.Equal(await client.CustomCommand(["hgetall", key1]), await client.CustomCommand(["hgetall", key2]));
+        Assert.Equal(dump, await client.CustomCommand(["DUMP", key2]));
+    }
+
+    [Fact]
+    public async Task AclUserWithRestrictedPermissions()
+    {
+        GlideClient client = TestConfiguration.DefaultStandaloneClient();
+        string username = Guid.NewGuid().ToString();
+        string password = Guid.NewGuid().ToString();
+        _ = await client.CustomCommand(["acl", "setuser", username, "on", $">{password}", "~*", "+get"]);
+
+        try
+        {
+            GlideClient restricted = new(TestConfiguration.DefaultClientConfig().WithAuthentication(username, password).Build());
+            Assert.Null(await restricted.Get(Guid.NewGuid().ToString()));
+
+            RequestException exception = await Assert.ThrowsAsync<RequestException>(async () => await restricted.Set(Guid.NewGuid().ToString(), "value"));
+            Assert.Equal(RequestErrorType.NoPermission, exception.ErrorType);
+        }
+        finally
+        {
+            _ = await client.CustomCommand(["acl", "deluser", username]);
+        }
+    }
+
+    [Fact]
+    public async Task DeniedCommandDoesNotReachServer()
+    {
+        GlideClient client = new(TestConfiguration.DefaultClientConfig()
+            .WithDeniedCommands(BaseClient.RequestType.FlushAll, BaseClient.RequestType.Keys).Build());
+        string key = Guid.NewGuid().ToString();
+        _ = await client.Set(key, "value");
+
+        RequestException exception = await Assert.ThrowsAsync<RequestException>(async () => await client.CustomCommand(["flushall"]));
+        Assert.Equal(RequestErrorType.CommandNotAllowed, exception.ErrorType);
+        exception = await Assert.ThrowsAsync<RequestException>(async () => await client.CustomCommand(["keys", "*"]));
+        Assert.Equal(RequestErrorType.CommandNotAllowed, exception.ErrorType);
+
+        Assert.Equal("value", (await client.Get(key))!.ToString());
+    }
+
+    [Fact]
+    public async Task ResponseLargerThanMaxSizeFails()
+    {
+        GlideClient client = new(TestConfiguration.DefaultClientConfig().WithMaxResponseBytes(1024).Build());
+        string key = Guid.NewGuid().ToString();
+        Assert.Equal("OK", await client.Set(key, new string('a', 2048)));
+
+        RequestException exception = await Assert.ThrowsAsync<RequestException>(async () => await client.Get(key));
+        Assert.Equal(RequestErrorType.ResponseTooLarge, exception.ErrorType);
+        Assert.Equal(2048L, await client.CustomCommand(["strlen", key]));
+    }
+
+    [Fact]
+    public async Task ArgumentLargerThanMaxSizeFails()
+    {
+        GlideClient client = new(TestConfiguration.DefaultClientConfig().WithMaxCommandArgBytes(1024).Build());
+        string key = Guid.NewGuid().ToString();
+
+        RequestException exception = await Assert.ThrowsAsync<RequestException>(async () => await client.Set(key, new string('a', 2048)));
+        Assert.Equal(RequestErrorType.ArgumentTooLarge, exception.ErrorType);
+        Assert.Null(await client.Get(key));
+        Assert.Equal("OK", await client.Set(key, new string('a', 1024)));
+    }
+
+    [Fact]
+    public async Task CommandTraceKeepsLastCommands()
+    {
+        GlideClient client = new(TestConfiguration.DefaultClientConfig().WithCommandTrace(2, fullArgs: true).Build());
+        string key = Guid.NewGuid().ToString();
+        _ = await client.Set(key, "value");
+        _ = await client.Get(key);
+        _ = await client.Get(key);
+
+        object?[] trace = client.GetCommandTrace();
+        Assert.Equal(2, trace.Length);
+        Dictionary<GlideString, object?> last = (Dictionary<GlideString, object?>)trace[1]!;
+        Assert.Equal("GET", last["command"]!.ToString());
+        Assert.Equal(key, ((object?[])last["args"]!)[0]!.ToString());
+        Assert.Equal("Ok", last["result"]!.ToString());
+
+        GlideClient untraced = new(TestConfiguration.DefaultClientConfig().Build());
+        Assert.Empty(untraced.GetCommandTrace());
+    }
+
+    [Fact]
+    public async Task ConnectionSetupIsReappliedAfterReconnect()
+    {
+        GlideClient client = new(TestConfiguration.DefaultClientConfig()
+            .WithDataBaseId(1)
+            .WithProtocolVersion(ConnectionConfiguration.Protocol.RESP3)
+            .WithClientName("setup-test")
+            .Build());
+        long id = (long)(await client.CustomCommand(["client", "id"]))!;
+        GlideClient killer = TestConfiguration.DefaultStandaloneClient();
+        _ = await killer.CustomCommand(["client", "kill", "id", id.ToString()]);
+
+        // The first commands could fail until the client reconnects
+        string? info = null;
+        for (int attempt = 0; info is null && attempt < 10; attempt++)
+        {
+            try
+            {
+                info = (await client.CustomCommand(["client", "info"]))!.ToString();
+            }
+            catch (RequestException)
+            {
+                await Task.Delay(100);
+            }
+        }
+        Assert.NotNull(info);
+        Assert.DoesNotContain($"id={id} ", info);
+        Assert.Contains(" name=setup-test ", info);
+        Assert.Contains(" db=1 ", info);
+        Assert.Contains(" resp=3", info);
+    }
+
+    [Fact]
+    public void InvalidConfigFailsBeforeConnecting()
+    {
+        RequestException exception = Assert.Throws<RequestException>(() => new GlideClient(new ConnectionConfiguration.StandaloneClientConfigurationBuilder()
+            .WithAddress("localhost", 0)
+            .Build()));
+        Assert.Equal(RequestErrorType.InvalidConfig, exception.ErrorType);
+        Assert.Contains("addresses[0].port", exception.Message);
+    }
+
+    [Fact]
+    public async Task ObjectStatisticsDependOnMaxMemoryPolicy()
+    {
+        GlideClient client = TestConfiguration.DefaultStandaloneClient();
+        Dictionary<gs, object?> policy = (await client.CustomCommand(["config", "get", "maxmemory-policy"]) as Dictionary<gs, object?>)!;
+        Assert.SkipWhen(policy.Values.First()!.ToString()!.Contains("lfu"), "The test expects a non-LFU maxmemory-policy");
+
+        string key = Guid.NewGuid().ToString();
+        _ = await client.Set(key, "value");
+        Assert.IsType<long>(await client.CustomCommand(["object", "idletime", key]));
+
+        RequestException exception = await Assert.ThrowsAsync<RequestException>(async () => await client.CustomCommand(["object", "freq", key]));
+        Assert.Equal(RequestErrorType.MaxMemoryPolicyMismatch, exception.ErrorType);
+    }
+
     [Fact]
     public void CanConnectWithDifferentParameters()
     {
@@ -68,6 +360,28 @@ public class StandaloneClientTests
             .WithReadFrom(new ConnectionConfiguration.ReadFrom(ConnectionConfiguration.ReadFromStrategy.Primary)).Build());
     }
 
+    [Theory]
+    [InlineData(ConnectionConfiguration.Protocol.RESP2)]
+    [InlineData(ConnectionConfiguration.Protocol.RESP3)]
+    public async Task ResponseTypesDependOnProtocol(ConnectionConfiguration.Protocol protocol)
+    {
+        GlideClient client = new(TestConfiguration.DefaultClientConfig().WithProtocolVersion(protocol).Build());
+
+        // CONFIG GET is converted to a map by glide-core regardless of the protocol
+        _ = Assert.IsType<Dictionary<gs, object?>>(await client.CustomCommand(["config", "get", "maxmemory"]));
+
+        // CLIENT TRACKINGINFO isn't converted, so it is a map in RESP3 and a flat array in RESP2
+        object? trackingInfo = await client.CustomCommand(["client", "trackinginfo"]);
+        if (protocol == ConnectionConfiguration.Protocol.RESP3)
+        {
+            _ = Assert.IsType<Dictionary<gs, object?>>(trackingInfo);
+        }
+        else
+        {
+            _ = Assert.IsType<object?[]>(trackingInfo);
+        }
+    }
+
     [Fact]
     // Verify that client can handle complex return types, not just strings
     // TODO: remove this test once we add tests with these commands
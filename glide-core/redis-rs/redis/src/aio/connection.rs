@@ -11,7 +11,7 @@ use crate::connection::{
 use crate::parser::ValueCodec;
 use crate::pipeline::PipelineRetryStrategy;
 use crate::types::{ErrorKind, FromRedisValue, RedisError, RedisFuture, RedisResult, Value};
-use crate::{from_owned_redis_value, ProtocolVersion, ToRedisArgs};
+use crate::{from_owned_redis_value, AddressFamily, ProtocolVersion, ToRedisArgs};
 use ::tokio::io::{AsyncRead, AsyncWrite, AsyncWriteExt};
 #[cfg(feature = "tokio-comp")]
 use ::tokio::net::lookup_host;
@@ -388,16 +388,25 @@ where
     }
 }
 
+/// Resolve the addresses of a host, only of the given IP version unless it is [`AddressFamily::Auto`].
 pub(crate) async fn get_socket_addrs(
     host: &str,
     port: u16,
+    address_family: AddressFamily,
 ) -> RedisResult<impl Iterator<Item = SocketAddr> + Send + '_> {
     #[cfg(feature = "tokio-comp")]
     let socket_addrs = lookup_host((host, port)).await?;
 
-    let mut socket_addrs = socket_addrs.peekable();
+    let mut socket_addrs = socket_addrs
+        .filter(move |socket_addr| address_family.allows(socket_addr))
+        .peekable();
     match socket_addrs.peek() {
         Some(_) => Ok(socket_addrs),
+        None if address_family != AddressFamily::Auto => Err(RedisError::from((
+            ErrorKind::InvalidClientConfig,
+            "No address of the configured address family found for host",
+            format!("{host} has no {address_family:?} address"),
+        ))),
         None => Err(RedisError::from((
             ErrorKind::InvalidClientConfig,
             "No address found for host",
@@ -419,21 +428,24 @@ where
 pub(crate) async fn connect_simple<T: RedisRuntime>(
     connection_info: &ConnectionInfo,
     _socket_addr: Option<SocketAddr>,
+    tcp_nodelay: bool,
+    tls_sni_hostname: Option<&str>,
+    address_family: AddressFamily,
 ) -> RedisResult<(T, Option<IpAddr>)> {
     Ok(match connection_info.addr {
         ConnectionAddr::Tcp(ref host, port) => {
-            if let Some(socket_addr) = _socket_addr {
+            if let Some(socket_addr) = _socket_addr.filter(|addr| address_family.allows(addr)) {
                 return Ok::<_, RedisError>((
-                    <T>::connect_tcp(socket_addr).await?,
+                    <T>::connect_tcp(socket_addr, tcp_nodelay).await?,
                     Some(socket_addr.ip()),
                 ));
             }
-            let socket_addrs = get_socket_addrs(host, port).await?;
+            let socket_addrs = get_socket_addrs(host, port, address_family).await?;
             select_ok(socket_addrs.map(|socket_addr| {
                 log_conn_creation("TCP", format!("{host}:{port}"), Some(socket_addr.ip()));
                 Box::pin(async move {
                     Ok::<_, RedisError>((
-                        <T>::connect_tcp(socket_addr).await?,
+                        <T>::connect_tcp(socket_addr, tcp_nodelay).await?,
                         Some(socket_addr.ip()),
                     ))
                 })
@@ -449,13 +461,22 @@ pub(crate) async fn connect_simple<T: RedisRuntime>(
             insecure,
             ref tls_params,
         } => {
-            if let Some(socket_addr) = _socket_addr {
+            // The server name is used for SNI and for validating the certificate, the host only for resolving the address.
+            let server_name = tls_sni_hostname.unwrap_or(host);
+            if let Some(socket_addr) = _socket_addr.filter(|addr| address_family.allows(addr)) {
                 return Ok::<_, RedisError>((
-                    <T>::connect_tcp_tls(host, socket_addr, insecure, tls_params).await?,
+                    <T>::connect_tcp_tls(
+                        server_name,
+                        socket_addr,
+                        insecure,
+                        tls_params,
+                        tcp_nodelay,
+                    )
+                    .await?,
                     Some(socket_addr.ip()),
                 ));
             }
-            let socket_addrs = get_socket_addrs(host, port).await?;
+            let socket_addrs = get_socket_addrs(host, port, address_family).await?;
             select_ok(socket_addrs.map(|socket_addr| {
                 log_conn_creation(
                     "TCP with TLS",
@@ -464,7 +485,14 @@ pub(crate) async fn connect_simple<T: RedisRuntime>(
                 );
                 Box::pin(async move {
                     Ok::<_, RedisError>((
-                        <T>::connect_tcp_tls(host, socket_addr, insecure, tls_params).await?,
+                        <T>::connect_tcp_tls(
+                            server_name,
+                            socket_addr,
+                            insecure,
+                            tls_params,
+                            tcp_nodelay,
+                        )
+                        .await?,
                         Some(socket_addr.ip()),
                     ))
                 })
@@ -32,8 +32,8 @@ pub mod tokio;
 /// Represents the ability of connecting via TCP or via Unix socket
 #[async_trait]
 pub(crate) trait RedisRuntime: AsyncStream + Send + Sync + Sized + 'static {
-    /// Performs a TCP connection
-    async fn connect_tcp(socket_addr: SocketAddr) -> RedisResult<Self>;
+    /// Performs a TCP connection, with Nagle's algorithm disabled if `tcp_nodelay` is set
+    async fn connect_tcp(socket_addr: SocketAddr, tcp_nodelay: bool) -> RedisResult<Self>;
 
     // Performs a TCP TLS connection
     #[cfg(any(feature = "tls-native-tls", feature = "tls-rustls"))]
@@ -42,6 +42,7 @@ pub(crate) trait RedisRuntime: AsyncStream + Send + Sync + Sized + 'static {
         socket_addr: SocketAddr,
         insecure: bool,
         tls_params: &Option<TlsConnParams>,
+        tcp_nodelay: bool,
     ) -> RedisResult<Self>;
 
     /// Performs a UNIX connection
@@ -144,22 +145,24 @@ where
     }
 }
 
-// Initial setup for every connection.
+// Initial setup for every connection. Returns the reply of `HELLO`, `None` with RESP2.
 async fn setup_connection<C>(
     connection_info: &RedisConnectionInfo,
     con: &mut C,
     // This parameter is set to 'true' if ReadFromReplica strategy is set to AZAffinity or AZAffinityReplicasAndPrimary.
     // An INFO command will be triggered in the connection's setup to update the 'availability_zone' property.
     discover_az: bool,
-) -> RedisResult<()>
+) -> RedisResult<Option<Value>>
 where
     C: ConnectionLike,
 {
+    let mut hello = None;
     if connection_info.protocol != ProtocolVersion::RESP2 {
         let hello_cmd = resp3_hello(connection_info);
         let val: RedisResult<Value> = hello_cmd.query_async(con).await;
-        if let Err(err) = val {
-            return Err(get_resp3_hello_command_error(err));
+        match val {
+            Ok(val) => hello = Some(val),
+            Err(err) => return Err(get_resp3_hello_command_error(err)),
         }
     } else if let Some(password) = &connection_info.password {
         let mut command = cmd("AUTH");
@@ -239,7 +242,7 @@ where
 
     // resubscribe
     if connection_info.protocol != ProtocolVersion::RESP3 {
-        return Ok(());
+        return Ok(hello);
     }
     static KIND_TO_COMMAND: [(PubSubSubscriptionKind, &str); 3] = [
         (PubSubSubscriptionKind::Exact, "SUBSCRIBE"),
@@ -248,7 +251,7 @@ where
     ];
 
     if connection_info.pubsub_subscriptions.is_none() {
-        return Ok(());
+        return Ok(hello);
     }
 
     for (subscription_kind, channels_patterns) in
@@ -313,7 +316,7 @@ where
         }
     }
 
-    Ok(())
+    Ok(hello)
 }
 
 mod connection;
@@ -512,7 +512,11 @@ impl MultiplexedConnection {
 
             match futures_util::future::select(auth, driver).await {
                 futures_util::future::Either::Left((result, driver)) => {
-                    result?;
+                    if let (Some(observer), Some(hello)) =
+                        (&glide_connection_options.connection_observer, result?)
+                    {
+                        observer.server_hello(&connection_info.addr.to_string(), &hello);
+                    }
                     driver
                 }
                 futures_util::future::Either::Right(((), _)) => {
@@ -38,10 +38,9 @@ use crate::connection::TlsConnParams;
 use super::Path;
 
 #[inline(always)]
-async fn connect_tcp(addr: &SocketAddr) -> io::Result<TcpStreamTokio> {
+async fn connect_tcp(addr: &SocketAddr, tcp_nodelay: bool) -> io::Result<TcpStreamTokio> {
     let socket = TcpStreamTokio::connect(addr).await?;
-    #[cfg(feature = "tcp_nodelay")]
-    socket.set_nodelay(true)?;
+    socket.set_nodelay(tcp_nodelay)?;
     #[cfg(feature = "keep-alive")]
     {
         //For now rely on system defaults
@@ -131,8 +130,10 @@ impl AsyncRead for Tokio {
 
 #[async_trait]
 impl RedisRuntime for Tokio {
-    async fn connect_tcp(socket_addr: SocketAddr) -> RedisResult<Self> {
-        Ok(connect_tcp(&socket_addr).await.map(Tokio::Tcp)?)
+    async fn connect_tcp(socket_addr: SocketAddr, tcp_nodelay: bool) -> RedisResult<Self> {
+        Ok(connect_tcp(&socket_addr, tcp_nodelay)
+            .await
+            .map(Tokio::Tcp)?)
     }
 
     #[cfg(all(feature = "tls-native-tls", not(feature = "tls-rustls")))]
@@ -141,6 +142,7 @@ impl RedisRuntime for Tokio {
         socket_addr: SocketAddr,
         insecure: bool,
         _: &Option<TlsConnParams>,
+        tcp_nodelay: bool,
     ) -> RedisResult<Self> {
         let tls_connector: tokio_native_tls::TlsConnector = if insecure {
             TlsConnector::builder()
@@ -153,7 +155,7 @@ impl RedisRuntime for Tokio {
         }
         .into();
         Ok(tls_connector
-            .connect(hostname, connect_tcp(&socket_addr).await?)
+            .connect(hostname, connect_tcp(&socket_addr, tcp_nodelay).await?)
             .await
             .map(|con| Tokio::TcpTls(Box::new(con)))?)
     }
@@ -164,6 +166,7 @@ impl RedisRuntime for Tokio {
         socket_addr: SocketAddr,
         insecure: bool,
         tls_params: &Option<TlsConnParams>,
+        tcp_nodelay: bool,
     ) -> RedisResult<Self> {
         let config = create_rustls_config(insecure, tls_params.clone())?;
         let tls_connector = TlsConnector::from(Arc::new(config));
@@ -171,7 +174,7 @@ impl RedisRuntime for Tokio {
         Ok(tls_connector
             .connect(
                 rustls_pki_types::ServerName::try_from(hostname)?.to_owned(),
-                connect_tcp(&socket_addr).await?,
+                connect_tcp(&socket_addr, tcp_nodelay).await?,
             )
             .await
             .map(|con| Tokio::TcpTls(Box::new(con)))?)
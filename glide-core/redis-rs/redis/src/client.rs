@@ -1,4 +1,4 @@
-use std::time::Duration;
+use std::{sync::Arc, time::Duration};
 
 #[cfg(feature = "aio")]
 use crate::aio::DisconnectNotifier;
@@ -78,8 +78,49 @@ impl Client {
     }
 }
 
+/// Observer of the connections created by a client, e.g. to measure how long connecting to every node takes.
+pub trait ConnectionObserver: Send + Sync {
+    /// Called once a connection to `address` was established and set up, with the time it took since connecting started.
+    fn connected(&self, address: &str, latency: Duration);
+
+    /// Called once connecting to `address` failed. A connection attempt which is cancelled, e.g. by a connection timeout,
+    /// isn't reported.
+    fn connection_failed(&self, address: &str);
+
+    /// Called once a connection to `address`, which was reported by [`ConnectionObserver::connected`], was closed.
+    fn disconnected(&self, address: &str);
+
+    /// Called with the reply of `HELLO` during the setup of a connection to `address`, before it is reported
+    /// by [`ConnectionObserver::connected`]. Not called for RESP2 connections, which are set up without `HELLO`.
+    fn server_hello(&self, _address: &str, _hello: &Value) {}
+}
+
+/// IP version of the addresses which hosts are resolved to and connected by.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[repr(C)]
+pub enum AddressFamily {
+    /// Connect by any address of the host, IPv4 or IPv6, trying all of them concurrently.
+    #[default]
+    Auto = 0,
+    /// Connect only by the IPv4 addresses of the host.
+    IPv4 = 1,
+    /// Connect only by the IPv6 addresses of the host.
+    IPv6 = 2,
+}
+
+impl AddressFamily {
+    /// Whether a resolved address is of this family.
+    pub fn allows(self, addr: &std::net::SocketAddr) -> bool {
+        match self {
+            AddressFamily::Auto => true,
+            AddressFamily::IPv4 => addr.is_ipv4(),
+            AddressFamily::IPv6 => addr.is_ipv6(),
+        }
+    }
+}
+
 /// Glide-specific connection options
-#[derive(Clone, Default)]
+#[derive(Clone)]
 pub struct GlideConnectionOptions {
     /// Queue for RESP3 notifications
     pub push_sender: Option<mpsc::UnboundedSender<PushInfo>>,
@@ -94,6 +135,33 @@ pub struct GlideConnectionOptions {
     /// This optional field sets the maximum duration to wait when attempting to establish
     /// a connection. If `None`, the connection will use `DEFAULT_CONNECTION_TIMEOUT`.
     pub connection_timeout: Option<Duration>,
+    /// Disable Nagle's algorithm (`TCP_NODELAY`) on the TCP socket of the connection, so small requests are sent
+    /// immediately instead of being buffered until previous writes are acknowledged.
+    /// Defaults to whether the `tcp_nodelay` feature is enabled.
+    pub tcp_nodelay: bool,
+    /// Server name sent by SNI during the TLS handshake, and validated against the certificate of the server,
+    /// instead of the host of the connection. Used when connecting by IP to a server whose certificate is issued for a hostname.
+    pub tls_sni_hostname: Option<String>,
+    /// IP version of the addresses the host of the connection is resolved to, e.g. IPv4 only if IPv6 routes are blocked.
+    pub address_family: AddressFamily,
+    /// Observer of the connection, notified once it is established and once it is closed.
+    pub connection_observer: Option<Arc<dyn ConnectionObserver>>,
+}
+
+impl Default for GlideConnectionOptions {
+    fn default() -> Self {
+        GlideConnectionOptions {
+            push_sender: None,
+            #[cfg(feature = "aio")]
+            disconnect_notifier: None,
+            discover_az: false,
+            connection_timeout: None,
+            tcp_nodelay: cfg!(feature = "tcp_nodelay"),
+            tls_sni_hostname: None,
+            address_family: AddressFamily::default(),
+            connection_observer: None,
+        }
+    }
 }
 
 /// To enable async support you need to enable the feature: `tokio-comp`
@@ -113,8 +181,13 @@ impl Client {
         let (con, _ip) = match Runtime::locate() {
             #[cfg(feature = "tokio-comp")]
             Runtime::Tokio => {
-                self.get_simple_async_connection::<crate::aio::tokio::Tokio>(None)
-                    .await?
+                self.get_simple_async_connection::<crate::aio::tokio::Tokio>(
+                    None,
+                    cfg!(feature = "tcp_nodelay"),
+                    None,
+                    AddressFamily::Auto,
+                )
+                .await?
             }
         };
 
@@ -401,15 +474,41 @@ impl Client {
     where
         T: crate::aio::RedisRuntime,
     {
-        let (connection, driver, ip) = self
+        let Some(observer) = glide_connection_options.connection_observer.clone() else {
+            let (connection, driver, ip) = self
+                .create_multiplexed_async_connection_inner::<T>(
+                    response_timeout,
+                    socket_addr,
+                    glide_connection_options,
+                )
+                .await?;
+            T::spawn(driver);
+            return Ok((connection, ip));
+        };
+        let address = self.connection_info.addr.to_string();
+        let started = std::time::Instant::now();
+        match self
             .create_multiplexed_async_connection_inner::<T>(
                 response_timeout,
                 socket_addr,
                 glide_connection_options,
             )
-            .await?;
-        T::spawn(driver);
-        Ok((connection, ip))
+            .await
+        {
+            Ok((connection, driver, ip)) => {
+                observer.connected(&address, started.elapsed());
+                // The driver completes once the connection is closed
+                T::spawn(async move {
+                    driver.await;
+                    observer.disconnected(&address);
+                });
+                Ok((connection, ip))
+            }
+            Err(err) => {
+                observer.connection_failed(&address);
+                Err(err)
+            }
+        }
     }
 
     async fn create_multiplexed_async_connection_inner<T>(
@@ -425,7 +524,14 @@ impl Client {
     where
         T: crate::aio::RedisRuntime,
     {
-        let (con, ip) = self.get_simple_async_connection::<T>(socket_addr).await?;
+        let (con, ip) = self
+            .get_simple_async_connection::<T>(
+                socket_addr,
+                glide_connection_options.tcp_nodelay,
+                glide_connection_options.tls_sni_hostname.as_deref(),
+                glide_connection_options.address_family,
+            )
+            .await?;
         crate::aio::MultiplexedConnection::new_with_response_timeout(
             &self.connection_info,
             con,
@@ -439,6 +545,9 @@ impl Client {
     async fn get_simple_async_connection<T>(
         &self,
         socket_addr: Option<SocketAddr>,
+        tcp_nodelay: bool,
+        tls_sni_hostname: Option<&str>,
+        address_family: AddressFamily,
     ) -> RedisResult<(
         Pin<Box<dyn crate::aio::AsyncStream + Send + Sync>>,
         Option<IpAddr>,
@@ -446,8 +555,14 @@ impl Client {
     where
         T: crate::aio::RedisRuntime,
     {
-        let (conn, ip) =
-            crate::aio::connect_simple::<T>(&self.connection_info, socket_addr).await?;
+        let (conn, ip) = crate::aio::connect_simple::<T>(
+            &self.connection_info,
+            socket_addr,
+            tcp_nodelay,
+            tls_sni_hostname,
+            address_family,
+        )
+        .await?;
         Ok((conn.boxed(), ip))
     }
 
@@ -617,4 +732,13 @@ mod test {
     fn regression_293_parse_ipv6_with_interface() {
         assert!(Client::open(("fe80::cafe:beef%eno1", 6379)).is_ok());
     }
+
+    #[test]
+    fn address_family_allows_only_its_addresses() {
+        let ipv4: std::net::SocketAddr = "127.0.0.1:6379".parse().unwrap();
+        let ipv6: std::net::SocketAddr = "[::1]:6379".parse().unwrap();
+        assert!(AddressFamily::Auto.allows(&ipv4) && AddressFamily::Auto.allows(&ipv6));
+        assert!(AddressFamily::IPv4.allows(&ipv4) && !AddressFamily::IPv4.allows(&ipv6));
+        assert!(!AddressFamily::IPv6.allows(&ipv4) && AddressFamily::IPv6.allows(&ipv6));
+    }
 }
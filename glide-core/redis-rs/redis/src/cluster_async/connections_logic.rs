@@ -195,6 +195,10 @@ where
             disconnect_notifier,
             discover_az,
             connection_timeout: Some(params.connection_timeout),
+            tcp_nodelay: params.tcp_nodelay,
+            tls_sni_hostname: params.tls_sni_hostname.clone(),
+            address_family: params.address_family,
+            connection_observer: None,
         },
     )
     .await
@@ -70,6 +70,7 @@ use telemetrylib::Telemetry;
 
 use crate::{
     aio::{get_socket_addrs, ConnectionLike, MultiplexedConnection, Runtime},
+    client::AddressFamily,
     cluster::slot_cmd,
     cluster_async::connections_logic::{
         get_host_and_port_from_addr, get_or_create_conn, ConnectionFuture, RefreshConnectionType,
@@ -334,6 +335,12 @@ where
         self.route_operation_request(Operation::GetUsername).await
     }
 
+    /// Get the slot map the commands are currently routed by, in the format of the reply of `CLUSTER SLOTS`,
+    /// without sending a command to the servers.
+    pub async fn get_slot_map(&mut self) -> RedisResult<Value> {
+        self.route_operation_request(Operation::GetSlotMap).await
+    }
+
     /// Routes an operation request to the appropriate handler.
     async fn route_operation_request(
         &mut self,
@@ -363,6 +370,21 @@ where
     }
 }
 
+/// The kind of a redirection reply of a cluster node.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RedirectKind {
+    /// The slot was migrated to another node, the slot map is updated (`MOVED`).
+    Moved,
+    /// The slot is being migrated, the request is sent to the importing node once (`ASK`).
+    Ask,
+}
+
+/// Observes the redirections handled internally by a cluster connection, e.g. to count them.
+pub trait RedirectionObserver: Send + Sync {
+    /// Called when a node redirected a request for `slot` to `address`, before the request is retried.
+    fn on_redirect(&self, kind: RedirectKind, slot: u16, address: &str);
+}
+
 #[cfg(feature = "tokio-comp")]
 #[derive(Clone)]
 struct TokioDisconnectNotifier {
@@ -653,6 +675,7 @@ enum CmdArg<C> {
 enum Operation {
     UpdateConnectionPassword(Option<String>),
     GetUsername,
+    GetSlotMap,
 }
 
 fn boxed_sleep(duration: Duration) -> BoxFuture<'static, ()> {
@@ -734,6 +757,10 @@ struct RequestInfo<C> {
 }
 
 impl<C> RequestInfo<C> {
+    fn is_no_retry(&self) -> bool {
+        matches!(&self.cmd, CmdArg::Cmd { cmd, .. } if cmd.is_no_retry())
+    }
+
     fn set_redirect(&mut self, redirect: Option<Redirect>) {
         if let Some(redirect) = redirect {
             match &mut self.cmd {
@@ -840,6 +867,7 @@ struct PendingRequest<C> {
 pin_project! {
     struct Request<C> {
         retry_params: RetryParams,
+        redirection_observer: Option<Arc<dyn RedirectionObserver>>,
         request: Option<PendingRequest<C>>,
         #[pin]
         future: RequestState<BoxFuture<'static, OperationResult>>,
@@ -919,7 +947,9 @@ impl<C> Future for Request<C> {
             Err((target, err)) => {
                 let request = this.request.as_mut().unwrap();
                 // TODO - would be nice if we didn't need to repeat this code twice, with & without retries.
-                if request.retry >= this.retry_params.number_of_retries {
+                if request.retry >= this.retry_params.number_of_retries
+                    || request.info.is_no_retry()
+                {
                     let retry_method = err.retry_method();
                     let next = if err.kind() == ErrorKind::AllConnectionsUnavailable {
                         Next::ReconnectToInitialNodes { request: None }.into()
@@ -992,6 +1022,7 @@ impl<C> Future for Request<C> {
 
                 match err.retry_method() {
                     RetryMethod::AskRedirect => {
+                        notify_redirect(this.redirection_observer, RedirectKind::Ask, &err);
                         let mut request = this.request.take().unwrap();
                         request.info.set_redirect(
                             err.redirect_node()
@@ -1000,6 +1031,7 @@ impl<C> Future for Request<C> {
                         Next::Retry { request }.into()
                     }
                     RetryMethod::MovedRedirect => {
+                        notify_redirect(this.redirection_observer, RedirectKind::Moved, &err);
                         let mut request = this.request.take().unwrap();
                         let redirect_node = err.redirect_node();
                         request.info.set_redirect(
@@ -1055,6 +1087,16 @@ impl<C> Future for Request<C> {
     }
 }
 
+fn notify_redirect(
+    observer: &Option<Arc<dyn RedirectionObserver>>,
+    kind: RedirectKind,
+    err: &RedisError,
+) {
+    if let (Some(observer), Some((address, slot))) = (observer, err.redirect_node()) {
+        observer.on_redirect(kind, slot, address);
+    }
+}
+
 impl<C> Request<C> {
     fn respond(self: Pin<&mut Self>, msg: RedisResult<Response>) {
         // If `send` errors the receiver has dropped and thus does not care about the message
@@ -1103,6 +1145,10 @@ where
             disconnect_notifier,
             discover_az,
             connection_timeout: Some(cluster_params.connection_timeout),
+            tcp_nodelay: cluster_params.tcp_nodelay,
+            tls_sni_hostname: cluster_params.tls_sni_hostname.clone(),
+            address_family: cluster_params.address_family,
+            connection_observer: cluster_params.connection_observer.clone(),
         };
 
         let connections = Self::create_initial_connections(
@@ -1179,6 +1225,7 @@ where
     /// Returns a vector of tuples, each containing a node's address (including the hostname) and its corresponding SocketAddr if retrieved.
     pub(crate) async fn try_to_expand_initial_nodes(
         initial_nodes: &[ConnectionInfo],
+        address_family: AddressFamily,
     ) -> Vec<(String, Option<SocketAddr>)> {
         stream::iter(initial_nodes)
             .fold(
@@ -1198,7 +1245,7 @@ where
                             return acc;
                         }
                     };
-                    match get_socket_addrs(host, *port).await {
+                    match get_socket_addrs(host, *port, address_family).await {
                         Ok(socket_addrs) => {
                             for addr in socket_addrs {
                                 acc.push((info.addr.to_string(), Some(addr)));
@@ -1221,7 +1268,7 @@ where
         glide_connection_options: GlideConnectionOptions,
     ) -> RedisResult<ConnectionMap<C>> {
         let initial_nodes: Vec<(String, Option<SocketAddr>)> =
-            Self::try_to_expand_initial_nodes(initial_nodes).await;
+            Self::try_to_expand_initial_nodes(initial_nodes, params.address_family).await;
         let connections = stream::iter(initial_nodes.iter().cloned())
             .map(|(node_addr, socket_addr)| {
                 let mut params: ClusterParams = params.clone();
@@ -1954,7 +2001,10 @@ where
                             addrs_and_conns.push((addr, None));
                             return addrs_and_conns;
                         };
-                        let conn = get_socket_addrs(host, port)
+                        let address_family = inner
+                            .get_cluster_param(|params| params.address_family)
+                            .unwrap_or_default();
+                        let conn = get_socket_addrs(host, port, address_family)
                             .await
                             .ok()
                             .map(|mut socket_addresses| {
@@ -2311,6 +2361,15 @@ where
                     };
                     Ok(Response::Single(username))
                 }
+                Operation::GetSlotMap => {
+                    let slot_map = core
+                        .conn_lock
+                        .read()
+                        .expect(MUTEX_READ_ERR)
+                        .slot_map
+                        .to_cluster_slots_value();
+                    Ok(Response::Single(slot_map))
+                }
             },
         }
     }
@@ -2467,6 +2526,7 @@ where
         cmd: Option<Arc<Cmd>>,
     ) -> RedisResult<(String, C)> {
         let mut asking = false;
+        let no_retry = cmd.as_ref().is_some_and(|cmd| cmd.is_no_retry());
 
         let conn_check = match routing {
             InternalSingleNodeRouting::Redirect {
@@ -2515,9 +2575,12 @@ where
                     //   (e.g., sending management command to a different node than the user asked for); instead, raise the error.
                     let mut conn_check = ConnectionCheck::RandomConnection;
 
+                    // - For commands which aren't retried, raise the error too, rather than waiting for a reconnect
+                    //   or being redirected.
                     let routable_cmd = cmd.and_then(|cmd| Routable::command(&*cmd));
-                    if routable_cmd.is_some()
-                        && !RoutingInfo::is_key_routing_command(&routable_cmd.unwrap())
+                    if no_retry
+                        || (routable_cmd.is_some()
+                            && !RoutingInfo::is_key_routing_command(&routable_cmd.unwrap()))
                     {
                         return Err((
                             ErrorKind::ConnectionNotFoundForRoute,
@@ -2754,6 +2817,10 @@ where
             .inner
             .get_cluster_param(|params| params.retry_params.clone())
             .expect(MUTEX_READ_ERR);
+        let redirection_observer = self
+            .inner
+            .get_cluster_param(|params| params.redirection_observer.clone())
+            .expect(MUTEX_READ_ERR);
         let mut poll_flush_action = PollFlushAction::None;
         let mut pending_requests_guard = self.inner.pending_requests.lock().unwrap();
         if !pending_requests_guard.is_empty() {
@@ -2769,6 +2836,7 @@ where
                 let future = Self::try_request(request.info.clone(), self.inner.clone()).boxed();
                 self.in_flight_requests.push(Box::pin(Request {
                     retry_params: retry_params.clone(),
+                    redirection_observer: redirection_observer.clone(),
                     request: Some(request),
                     future: RequestState::Future { future },
                 }));
@@ -2789,6 +2857,7 @@ where
                     let future = Self::try_request(request.info.clone(), self.inner.clone());
                     self.in_flight_requests.push(Box::pin(Request {
                         retry_params: retry_params.clone(),
+                        redirection_observer: redirection_observer.clone(),
                         request: Some(request),
                         future: RequestState::Future {
                             future: Box::pin(future),
@@ -2806,6 +2875,7 @@ where
                     );
                     self.in_flight_requests.push(Box::pin(Request {
                         retry_params: retry_params.clone(),
+                        redirection_observer: redirection_observer.clone(),
                         request: Some(request),
                         future: RequestState::Future {
                             future: Box::pin(future),
@@ -2847,6 +2917,7 @@ where
                     if let Some(future) = future {
                         self.in_flight_requests.push(Box::pin(Request {
                             retry_params,
+                            redirection_observer: redirection_observer.clone(),
                             request,
                             future,
                         }));
@@ -6,10 +6,11 @@ use crate::cluster_topology::{
 use crate::connection::{ConnectionAddr, ConnectionInfo, IntoConnectionInfo};
 use crate::types::{ErrorKind, ProtocolVersion, RedisError, RedisResult};
 use crate::{cluster, cluster::TlsMode};
-use crate::{PubSubSubscriptionInfo, PushInfo};
+use crate::{AddressFamily, ConnectionObserver, PubSubSubscriptionInfo, PushInfo};
 use rand::Rng;
 #[cfg(feature = "cluster-async")]
 use std::ops::Add;
+use std::sync::Arc;
 use std::time::Duration;
 use telemetrylib::GlideOpenTelemetryConfig;
 
@@ -40,12 +41,18 @@ struct BuilderParams {
     certs: Option<TlsCertificates>,
     retries_configuration: RetryParams,
     connection_timeout: Option<Duration>,
+    tcp_nodelay: bool,
+    tls_sni_hostname: Option<String>,
+    address_family: AddressFamily,
+    connection_observer: Option<Arc<dyn ConnectionObserver>>,
     #[cfg(feature = "cluster-async")]
     topology_checks_interval: Option<Duration>,
     #[cfg(feature = "cluster-async")]
     connections_validation_interval: Option<Duration>,
     #[cfg(feature = "cluster-async")]
     slots_refresh_rate_limit: SlotsRefreshRateLimit,
+    #[cfg(feature = "cluster-async")]
+    redirection_observer: Option<Arc<dyn cluster_async::RedirectionObserver>>,
     client_name: Option<String>,
     response_timeout: Option<Duration>,
     protocol: ProtocolVersion,
@@ -144,9 +151,15 @@ pub struct ClusterParams {
     pub(crate) slots_refresh_rate_limit: SlotsRefreshRateLimit,
     #[cfg(feature = "cluster-async")]
     pub(crate) connections_validation_interval: Option<Duration>,
+    #[cfg(feature = "cluster-async")]
+    pub(crate) redirection_observer: Option<Arc<dyn cluster_async::RedirectionObserver>>,
     pub(crate) tls_params: Option<TlsConnParams>,
     pub(crate) client_name: Option<String>,
     pub(crate) connection_timeout: Duration,
+    pub(crate) tcp_nodelay: bool,
+    pub(crate) tls_sni_hostname: Option<String>,
+    pub(crate) address_family: AddressFamily,
+    pub(crate) connection_observer: Option<Arc<dyn ConnectionObserver>>,
     pub(crate) response_timeout: Duration,
     pub(crate) protocol: ProtocolVersion,
     pub(crate) pubsub_subscriptions: Option<PubSubSubscriptionInfo>,
@@ -171,11 +184,17 @@ impl ClusterParams {
             tls: value.tls,
             retry_params: value.retries_configuration,
             connection_timeout: value.connection_timeout.unwrap_or(Duration::MAX),
+            tcp_nodelay: value.tcp_nodelay,
+            tls_sni_hostname: value.tls_sni_hostname,
+            address_family: value.address_family,
+            connection_observer: value.connection_observer,
             #[cfg(feature = "cluster-async")]
             topology_checks_interval: value.topology_checks_interval,
             #[cfg(feature = "cluster-async")]
             slots_refresh_rate_limit: value.slots_refresh_rate_limit,
             #[cfg(feature = "cluster-async")]
+            redirection_observer: value.redirection_observer,
+            #[cfg(feature = "cluster-async")]
             connections_validation_interval: value.connections_validation_interval,
             tls_params,
             client_name: value.client_name,
@@ -204,7 +223,10 @@ impl ClusterClientBuilder {
                 .into_iter()
                 .map(|x| x.into_connection_info())
                 .collect(),
-            builder_params: Default::default(),
+            builder_params: BuilderParams {
+                tcp_nodelay: cfg!(feature = "tcp_nodelay"),
+                ..Default::default()
+            },
         }
     }
 
@@ -478,6 +500,17 @@ impl ClusterClientBuilder {
         self
     }
 
+    /// Sets an observer of the `MOVED` and `ASK` redirections, which are handled internally by the connection.
+    /// The observer is called for every redirection before the request is retried, so it must not block.
+    #[cfg(feature = "cluster-async")]
+    pub fn redirection_observer(
+        mut self,
+        observer: Arc<dyn cluster_async::RedirectionObserver>,
+    ) -> ClusterClientBuilder {
+        self.builder_params.redirection_observer = Some(observer);
+        self
+    }
+
     /// Enables timing out on slow connection time.
     ///
     /// If enabled, the cluster will only wait the given time on each connection attempt to each node.
@@ -486,6 +519,37 @@ impl ClusterClientBuilder {
         self
     }
 
+    /// Disables Nagle's algorithm (`TCP_NODELAY`) on the sockets of all connections, including reconnections.
+    /// Defaults to whether the `tcp_nodelay` feature is enabled.
+    pub fn tcp_nodelay(mut self, tcp_nodelay: bool) -> ClusterClientBuilder {
+        self.builder_params.tcp_nodelay = tcp_nodelay;
+        self
+    }
+
+    /// Sets the server name sent by SNI during the TLS handshake to all nodes, and validated against their certificates,
+    /// instead of the hosts of the nodes, e.g. when the nodes are connected by IP but their certificates are issued for a hostname.
+    pub fn tls_sni_hostname(mut self, tls_sni_hostname: Option<String>) -> ClusterClientBuilder {
+        self.builder_params.tls_sni_hostname = tls_sni_hostname;
+        self
+    }
+
+    /// Sets the IP version of the addresses the nodes are resolved to and connected by, including the initial nodes
+    /// and reconnections, e.g. IPv4 only if IPv6 routes are blocked.
+    pub fn address_family(mut self, address_family: AddressFamily) -> ClusterClientBuilder {
+        self.builder_params.address_family = address_family;
+        self
+    }
+
+    /// Sets the observer of the connections to all nodes, notified once a connection is established and once it is closed.
+    /// Management connections aren't observed.
+    pub fn connection_observer(
+        mut self,
+        observer: Arc<dyn ConnectionObserver>,
+    ) -> ClusterClientBuilder {
+        self.builder_params.connection_observer = Some(observer);
+        self
+    }
+
     /// Enables timing out on slow responses.
     ///
     /// If enabled, the cluster will only wait the given time to each response from each node.
@@ -859,6 +859,28 @@ impl RoutingInfo {
         }
     }
 
+    /// Returns the key by which `r` is routed to the slot of the key, `None` if it isn't routed by a single key,
+    /// e.g. commands without keys, or commands which are split by slot, like `MGET`.
+    pub fn routing_key<R>(r: &R) -> Option<&[u8]>
+    where
+        R: Routable + ?Sized,
+    {
+        let key_count = |idx| {
+            r.arg_idx(idx)
+                .and_then(|x| std::str::from_utf8(x).ok())
+                .and_then(|x| x.parse::<u64>().ok())
+                .filter(|key_count| *key_count > 0)
+        };
+        match base_routing(&r.command()?[..]) {
+            RouteBy::ThirdArgAfterKeyCount => key_count(2).and_then(|_| r.arg_idx(3)),
+            RouteBy::SecondArg => r.arg_idx(2),
+            RouteBy::SecondArgAfterKeyCount => key_count(1).and_then(|_| r.arg_idx(2)),
+            RouteBy::StreamsIndex => r.arg_idx(r.position(b"STREAMS")? + 1),
+            RouteBy::FirstKey => r.arg_idx(1),
+            _ => None,
+        }
+    }
+
     fn for_key(cmd: &[u8], key: &[u8]) -> RoutingInfo {
         RoutingInfo::SingleNode(SingleNodeRoutingInfo::SpecificNode(get_route(
             is_readonly_cmd(cmd),
@@ -1660,6 +1682,55 @@ mod tests_routing {
         }
     }
 
+    #[test]
+    fn test_routing_info_for_multi_key_commands_with_key_count() {
+        let expected = Some(RoutingInfo::SingleNode(
+            SingleNodeRoutingInfo::SpecificNode(Route::new(slot(b"{tag}a"), SlotAddr::Master)),
+        ));
+        for cmd in [
+            cmd("LMPOP").arg(2).arg("{tag}a").arg("{tag}b").arg("LEFT"),
+            cmd("ZMPOP").arg(2).arg("{tag}a").arg("{tag}b").arg("MIN"),
+            cmd("BLMPOP")
+                .arg(0.1)
+                .arg(2)
+                .arg("{tag}a")
+                .arg("{tag}b")
+                .arg("LEFT"),
+            cmd("BZMPOP")
+                .arg(0.1)
+                .arg(2)
+                .arg("{tag}a")
+                .arg("{tag}b")
+                .arg("MIN"),
+        ] {
+            assert_eq!(
+                RoutingInfo::for_routable(cmd),
+                expected,
+                "{}",
+                std::str::from_utf8(cmd.arg_idx(0).unwrap()).unwrap()
+            );
+        }
+
+        // Read-only commands may be routed to replicas
+        let expected = Some(RoutingInfo::SingleNode(
+            SingleNodeRoutingInfo::SpecificNode(Route::new(
+                slot(b"{tag}a"),
+                SlotAddr::ReplicaOptional,
+            )),
+        ));
+        for cmd in [
+            cmd("SINTERCARD").arg(2).arg("{tag}a").arg("{tag}b"),
+            cmd("ZDIFF").arg(2).arg("{tag}a").arg("{tag}b"),
+        ] {
+            assert_eq!(
+                RoutingInfo::for_routable(cmd),
+                expected,
+                "{}",
+                std::str::from_utf8(cmd.arg_idx(0).unwrap()).unwrap()
+            );
+        }
+    }
+
     #[test]
     fn test_slot_for_packed_cmd() {
         assert!(matches!(RoutingInfo::for_routable(&parse_redis_value(&[
@@ -1682,6 +1753,35 @@ mod tests_routing {
             ]).unwrap()), Some(RoutingInfo::SingleNode(SingleNodeRoutingInfo::SpecificNode(Route(slot, SlotAddr::Master)))) if slot == 5210));
     }
 
+    #[test]
+    fn test_routing_key() {
+        for (cmd, expected) in [
+            (cmd("GET").arg("foo"), Some(&b"foo"[..])),
+            (cmd("XINFO").arg("GROUPS").arg("foo"), Some(b"foo")),
+            (cmd("EVAL").arg("script").arg(1).arg("foo"), Some(b"foo")),
+            (cmd("EVAL").arg("script").arg(0), None),
+            (cmd("ZDIFF").arg(2).arg("foo").arg("bar"), Some(b"foo")),
+            (
+                cmd("XREAD")
+                    .arg("COUNT")
+                    .arg(2)
+                    .arg("STREAMS")
+                    .arg("foo")
+                    .arg(0),
+                Some(b"foo"),
+            ),
+            (cmd("MGET").arg("foo").arg("bar"), None),
+            (cmd("PING").arg("foo"), None),
+        ] {
+            assert_eq!(
+                RoutingInfo::routing_key(cmd),
+                expected,
+                "{}",
+                std::str::from_utf8(cmd.arg_idx(0).unwrap()).unwrap()
+            );
+        }
+    }
+
     #[test]
     fn test_multi_shard_keys_only() {
         let mut cmd = cmd("DEL");
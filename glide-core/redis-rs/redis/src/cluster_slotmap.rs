@@ -11,6 +11,7 @@ use crate::cluster_routing::{Route, ShardAddrs, Slot, SlotAddr};
 use crate::ErrorKind;
 use crate::RedisError;
 use crate::RedisResult;
+use crate::Value;
 pub(crate) type NodesMap = DashMap<Arc<String>, Arc<ShardAddrs>>;
 
 #[derive(Debug)]
@@ -206,6 +207,33 @@ impl SlotMap {
             .collect()
     }
 
+    /// Returns the slot ranges and the addresses of their primary and replicas, in the format of the reply of `CLUSTER SLOTS`:
+    /// `[start, end, [host, port], [replica host, replica port], ...]` per range, in the order of the slots.
+    pub(crate) fn to_cluster_slots_value(&self) -> Value {
+        fn node(address: &str) -> Value {
+            let (host, port) = address.rsplit_once(':').unwrap_or((address, "0"));
+            Value::Array(vec![
+                Value::BulkString(host.as_bytes().to_vec()),
+                Value::Int(port.parse().unwrap_or_default()),
+            ])
+        }
+        Value::Array(
+            self.slots
+                .iter()
+                .map(|(end, slot_value)| {
+                    let addrs = &slot_value.addrs;
+                    let mut range = vec![
+                        Value::Int(slot_value.start.into()),
+                        Value::Int((*end).into()),
+                        node(&addrs.primary()),
+                    ];
+                    range.extend(addrs.replicas().iter().map(|replica| node(replica)));
+                    Value::Array(range)
+                })
+                .collect(),
+        )
+    }
+
     pub(crate) fn node_address_for_slot(
         &self,
         slot: u16,
@@ -493,6 +521,40 @@ mod tests_cluster_slotmap {
             .collect()
     }
 
+    #[test]
+    fn test_slot_map_to_cluster_slots_value() {
+        let slot_map = SlotMap::new(
+            vec![
+                Slot::new(
+                    1002,
+                    2000,
+                    "node2:6379".to_owned(),
+                    vec!["replica2:6380".to_owned()],
+                ),
+                Slot::new(1, 1000, "node1:6379".to_owned(), vec![]),
+            ],
+            ReadFromReplicaStrategy::AlwaysFromPrimary,
+        );
+        let node = |host: &str, port| {
+            Value::Array(vec![
+                Value::BulkString(host.as_bytes().to_vec()),
+                Value::Int(port),
+            ])
+        };
+        assert_eq!(
+            slot_map.to_cluster_slots_value(),
+            Value::Array(vec![
+                Value::Array(vec![Value::Int(1), Value::Int(1000), node("node1", 6379)]),
+                Value::Array(vec![
+                    Value::Int(1002),
+                    Value::Int(2000),
+                    node("node2", 6379),
+                    node("replica2", 6380),
+                ]),
+            ])
+        );
+    }
+
     #[test]
     fn test_slot_map_retrieve_routes() {
         let slot_map = SlotMap::new(
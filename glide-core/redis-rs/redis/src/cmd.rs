@@ -31,6 +31,8 @@ pub struct Cmd {
     cursor: Option<u64>,
     // If it's true command's response won't be read from socket. Useful for Pub/Sub.
     no_response: bool,
+    // If it's true the command isn't retried, and doesn't wait for connections which are being established.
+    no_retry: bool,
     /// The span associated with this command
     span: Option<GlideSpan>,
 }
@@ -325,6 +327,7 @@ impl Cmd {
             args: vec![],
             cursor: None,
             no_response: false,
+            no_retry: false,
             span: None,
         }
     }
@@ -336,6 +339,7 @@ impl Cmd {
             args: Vec::with_capacity(arg_count),
             cursor: None,
             no_response: false,
+            no_retry: false,
             span: None,
         }
     }
@@ -599,6 +603,20 @@ impl Cmd {
         self.no_response
     }
 
+    /// Client won't retry the command or wait for a connection to be established for it, but return the first error.
+    /// Used for probes, which should reflect the state of the connection rather than recover from it.
+    #[inline]
+    pub fn set_no_retry(&mut self, no_retry: bool) -> &mut Cmd {
+        self.no_retry = no_retry;
+        self
+    }
+
+    /// Check whether the command is retried on errors.
+    #[inline]
+    pub fn is_no_retry(&self) -> bool {
+        self.no_retry
+    }
+
     /// Return this command span
     #[inline]
     pub fn span(&self) -> Option<GlideSpan> {
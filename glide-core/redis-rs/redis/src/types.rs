@@ -1190,6 +1190,8 @@ impl RedisError {
     }
 }
 
+/// Make an error of the given extension error code, e.g. for errors of the server which aren't known to the client,
+/// or for custom errors of applications built on this crate.
 pub fn make_extension_error(code: String, detail: Option<String>) -> RedisError {
     RedisError {
         repr: ErrorRepr::ExtensionError(
@@ -683,7 +683,8 @@ pub fn build_keys_and_certs_for_tls(tempdir: &TempDir) -> TlsFilePaths {
         b"keyUsage = digitalSignature, keyEncipherment\n\
     subjectAltName = @alt_names\n\
     [alt_names]\n\
-    IP.1 = 127.0.0.1\n",
+    IP.1 = 127.0.0.1\n\
+    DNS.1 = localhost\n",
     )
     .expect("failed to create x509v3 extensions file");
 
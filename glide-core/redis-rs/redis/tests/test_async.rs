@@ -1042,6 +1042,31 @@ mod basic_async {
                 }
             }
         }
+
+        #[test]
+        fn test_sni_hostname_is_validated_against_certificate() {
+            let ctx = TestContext::new_with_mtls();
+            let mut connection_info = ctx.server.connection_info();
+            // The certificate is only validated in secure mode, and only TLS servers have one.
+            let redis::ConnectionAddr::TcpTls { insecure, .. } = &mut connection_info.addr else {
+                return;
+            };
+            *insecure = false;
+            let client = build_single_client(connection_info, &ctx.server.tls_paths, true).unwrap();
+            let ping = |tls_sni_hostname: &str| {
+                let connect = client.get_multiplexed_async_connection(GlideConnectionOptions {
+                    tls_sni_hostname: Some(tls_sni_hostname.to_string()),
+                    ..Default::default()
+                });
+                block_on_all(connect.and_then(|mut con| async move {
+                    redis::cmd("PING").query_async::<_, String>(&mut con).await
+                }))
+            };
+
+            // The connection is made to 127.0.0.1, while the certificate is validated against the SNI hostname.
+            assert_eq!(ping("localhost").unwrap(), "PONG");
+            assert!(ping("other.example.com").is_err());
+        }
     }
 
     #[test]
@@ -16,7 +16,7 @@ use redis::{
     ClusterScanArgs, Cmd, ErrorKind, FromRedisValue, PipelineRetryStrategy, PushInfo, RedisError,
     RedisResult, ScanStateRC, Value,
 };
-pub use standalone_client::StandaloneClient;
+pub use standalone_client::{StandaloneClient, StandaloneClientConnectionError};
 use std::io;
 use std::str::FromStr;
 use std::sync::atomic::{AtomicIsize, Ordering};
@@ -396,7 +396,11 @@ impl Client {
                     .cmd_iter()
                     .map(|cmd| expected_type_for_cmd(cmd.as_ref())),
             )
-            .map(|(value, expected_type)| convert_to_expected_type(value?, expected_type))
+            .map(|(value, expected_type)| match value? {
+                // Errors returned as values are kept as is, rather than failing the conversion of the whole batch.
+                value @ Value::ServerError(_) => Ok(value),
+                value => convert_to_expected_type(value, expected_type),
+            })
             .try_fold(
                 Vec::with_capacity(command_count),
                 |mut acc, result| -> RedisResult<_> {
@@ -669,6 +673,15 @@ impl Client {
             ClientWrapper::Standalone(client) => Ok(client.get_username()),
         }
     }
+
+    /// Returns the slot map a cluster client routes the commands by, in the format of the reply of `CLUSTER SLOTS`,
+    /// without sending a command to the servers. Returns None in standalone mode.
+    pub async fn get_slot_map(&mut self) -> RedisResult<Option<Value>> {
+        match &mut self.internal_client {
+            ClientWrapper::Cluster { client } => client.get_slot_map().await.map(Some),
+            ClientWrapper::Standalone(_) => Ok(None),
+        }
+    }
 }
 
 fn load_cmd(code: &[u8]) -> Cmd {
@@ -716,6 +729,9 @@ async fn create_cluster_client(
     let connection_timeout = to_duration(request.connection_timeout, DEFAULT_CONNECTION_TIMEOUT);
     let mut builder = redis::cluster::ClusterClientBuilder::new(initial_nodes)
         .connection_timeout(connection_timeout)
+        .tcp_nodelay(request.tcp_nodelay)
+        .tls_sni_hostname(request.tls_sni_hostname.clone())
+        .address_family(request.address_family)
         .retries(DEFAULT_RETRIES);
     let read_from_strategy = request.read_from.unwrap_or_default();
     builder = builder.read_from(match read_from_strategy {
@@ -744,6 +760,12 @@ async fn create_cluster_client(
     if let Some(pubsub_subscriptions) = redis_connection_info.pubsub_subscriptions.clone() {
         builder = builder.pubsub_subscriptions(pubsub_subscriptions);
     }
+    if let Some(redirection_observer) = request.redirection_observer {
+        builder = builder.redirection_observer(redirection_observer);
+    }
+    if let Some(connection_observer) = request.connection_observer {
+        builder = builder.connection_observer(connection_observer);
+    }
 
     // Always use with Glide
     builder = builder.periodic_connections_checks(Some(CONNECTION_CHECKS_INTERVAL));
@@ -6,7 +6,10 @@ use async_trait::async_trait;
 use futures_intrusive::sync::ManualResetEvent;
 use logger_core::{log_debug, log_error, log_trace, log_warn};
 use redis::aio::{DisconnectNotifier, MultiplexedConnection};
-use redis::{GlideConnectionOptions, PushInfo, RedisConnectionInfo, RedisError, RedisResult};
+use redis::{
+    AddressFamily, ConnectionObserver, GlideConnectionOptions, PushInfo, RedisConnectionInfo,
+    RedisError, RedisResult,
+};
 use std::fmt;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
@@ -116,12 +119,17 @@ impl TokioDisconnectNotifier {
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 async fn create_connection(
     connection_backend: ConnectionBackend,
     retry_strategy: RetryStrategy,
     push_sender: Option<mpsc::UnboundedSender<PushInfo>>,
     discover_az: bool,
     connection_timeout: Duration,
+    tcp_nodelay: bool,
+    tls_sni_hostname: Option<String>,
+    address_family: AddressFamily,
+    connection_observer: Option<Arc<dyn ConnectionObserver>>,
 ) -> Result<ReconnectingConnection, (ReconnectingConnection, RedisError)> {
     let client = {
         let guard = connection_backend
@@ -138,6 +146,10 @@ async fn create_connection(
         )),
         discover_az,
         connection_timeout: Some(connection_timeout),
+        tcp_nodelay,
+        tls_sni_hostname,
+        address_family,
+        connection_observer,
     };
 
     let action = || async {
@@ -224,6 +236,7 @@ impl ConnectionBackend {
 }
 
 impl ReconnectingConnection {
+    #[allow(clippy::too_many_arguments)]
     pub(super) async fn new(
         address: &NodeAddress,
         connection_retry_strategy: RetryStrategy,
@@ -232,6 +245,10 @@ impl ReconnectingConnection {
         push_sender: Option<mpsc::UnboundedSender<PushInfo>>,
         discover_az: bool,
         connection_timeout: Duration,
+        tcp_nodelay: bool,
+        tls_sni_hostname: Option<String>,
+        address_family: AddressFamily,
+        connection_observer: Option<Arc<dyn ConnectionObserver>>,
     ) -> Result<ReconnectingConnection, (ReconnectingConnection, RedisError)> {
         log_debug(
             "connection creation",
@@ -250,6 +267,10 @@ impl ReconnectingConnection {
             push_sender,
             discover_az,
             connection_timeout,
+            tcp_nodelay,
+            tls_sni_hostname,
+            address_family,
+            connection_observer,
         )
         .await
     }
@@ -6,13 +6,13 @@ use super::{to_duration, DEFAULT_CONNECTION_TIMEOUT};
 use super::{ConnectionRequest, NodeAddress, TlsMode};
 use crate::client::types::ReadFrom as ClientReadFrom;
 use crate::retry_strategies::RetryStrategy;
-use futures::{future, stream, StreamExt};
+use futures::{future, stream::FuturesUnordered, StreamExt};
 use logger_core::log_debug;
 use logger_core::log_warn;
 use rand::Rng;
 use redis::aio::ConnectionLike;
 use redis::cluster_routing::{self, is_readonly_cmd, ResponsePolicy, Routable, RoutingInfo};
-use redis::{PushInfo, RedisError, RedisResult, Value};
+use redis::{AddressFamily, ConnectionObserver, PushInfo, RedisError, RedisResult, Value};
 use std::sync::atomic::AtomicUsize;
 use std::sync::atomic::Ordering;
 use std::sync::Arc;
@@ -142,7 +142,11 @@ impl StandaloneClient {
             DEFAULT_CONNECTION_TIMEOUT,
         );
 
-        let mut stream = stream::iter(connection_request.addresses.iter())
+        // The futures are collected before they're polled, so the closure isn't held across an await,
+        // which would make this future `Send` only for a specific lifetime and prevent spawning it.
+        let mut stream = connection_request
+            .addresses
+            .iter()
             .map(|address| async {
                 get_connection_and_replication_info(
                     address,
@@ -156,11 +160,15 @@ impl StandaloneClient {
                     &push_sender,
                     discover_az,
                     connection_timeout,
+                    connection_request.tcp_nodelay,
+                    connection_request.tls_sni_hostname.clone(),
+                    connection_request.address_family,
+                    connection_request.connection_observer.clone(),
                 )
                 .await
                 .map_err(|err| (format!("{}:{}", address.host, address.port), err))
             })
-            .buffer_unordered(node_count);
+            .collect::<FuturesUnordered<_>>();
 
         let mut nodes = Vec::with_capacity(node_count);
         let mut addresses_and_errors = Vec::with_capacity(node_count);
@@ -399,7 +407,20 @@ impl StandaloneClient {
         cmd: &redis::Cmd,
         reconnecting_connection: &ReconnectingConnection,
     ) -> RedisResult<Value> {
-        let mut connection = reconnecting_connection.get_connection().await?;
+        let mut connection = if cmd.is_no_retry() {
+            // Commands which aren't retried don't wait for the connection to be established again.
+            reconnecting_connection
+                .try_get_connection()
+                .await
+                .ok_or_else(|| {
+                    RedisError::from((
+                        redis::ErrorKind::IoError,
+                        "The connection is being established",
+                    ))
+                })?
+        } else {
+            reconnecting_connection.get_connection().await?
+        };
         let result = connection.send_packed_command(cmd).await;
         match result {
             Err(err) if err.is_unrecoverable_error() => {
@@ -624,6 +645,7 @@ impl StandaloneClient {
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 async fn get_connection_and_replication_info(
     address: &NodeAddress,
     retry_strategy: &RetryStrategy,
@@ -632,6 +654,10 @@ async fn get_connection_and_replication_info(
     push_sender: &Option<mpsc::UnboundedSender<PushInfo>>,
     discover_az: bool,
     connection_timeout: Duration,
+    tcp_nodelay: bool,
+    tls_sni_hostname: Option<String>,
+    address_family: AddressFamily,
+    connection_observer: Option<Arc<dyn ConnectionObserver>>,
 ) -> Result<(ReconnectingConnection, Value), (ReconnectingConnection, RedisError)> {
     let result = ReconnectingConnection::new(
         address,
@@ -641,6 +667,10 @@ async fn get_connection_and_replication_info(
         push_sender.clone(),
         discover_az,
         connection_timeout,
+        tcp_nodelay,
+        tls_sni_hostname,
+        address_family,
+        connection_observer,
     )
     .await;
     let reconnecting_connection = match result {
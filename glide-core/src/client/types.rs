@@ -4,12 +4,13 @@
 use logger_core::log_warn;
 #[allow(unused_imports)]
 use std::collections::HashSet;
+use std::sync::Arc;
 use std::time::Duration;
 
 #[cfg(feature = "proto")]
 use crate::connection_request as protobuf;
 
-#[derive(Default)]
+#[derive(Default, Clone)]
 pub struct ConnectionRequest {
     pub read_from: Option<ReadFrom>,
     pub client_name: Option<String>,
@@ -21,20 +22,34 @@ pub struct ConnectionRequest {
     pub cluster_mode_enabled: bool,
     pub request_timeout: Option<u32>,
     pub connection_timeout: Option<u32>,
+    /// Disable Nagle's algorithm (`TCP_NODELAY`) on the sockets of all connections, including reconnections.
+    pub tcp_nodelay: bool,
+    /// Server name sent by SNI during the TLS handshake, and validated against the certificates of the servers, instead of
+    /// the hosts of the addresses. Used when connecting by IP to servers whose certificates are issued for a hostname.
+    pub tls_sni_hostname: Option<String>,
+    /// IP version of the addresses the hosts are resolved to and connected by, for the initial addresses, the discovered
+    /// nodes and reconnections. `Auto` connects by any address of a host.
+    pub address_family: redis::AddressFamily,
     pub connection_retry_strategy: Option<ConnectionRetryStrategy>,
     pub periodic_checks: Option<PeriodicCheck>,
     pub pubsub_subscriptions: Option<redis::PubSubSubscriptionInfo>,
     pub inflight_requests_limit: Option<u32>,
     pub otel_endpoint: Option<String>,
     pub otel_span_flush_interval_ms: Option<u64>,
+    /// Observer of the `MOVED` and `ASK` redirections handled by the client, only used in cluster mode.
+    pub redirection_observer: Option<Arc<dyn redis::cluster_async::RedirectionObserver>>,
+    /// Observer of the connections to the nodes, notified once a connection is established and once it is closed,
+    /// including reconnections.
+    pub connection_observer: Option<Arc<dyn redis::ConnectionObserver>>,
 }
 
+#[derive(Clone)]
 pub struct AuthenticationInfo {
     pub username: Option<String>,
     pub password: Option<String>,
 }
 
-#[derive(Default, Debug)]
+#[derive(Default, Debug, Clone)]
 pub enum PeriodicCheck {
     #[default]
     Enabled,
@@ -42,7 +57,7 @@ pub enum PeriodicCheck {
     ManualInterval(Duration),
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct NodeAddress {
     pub host: String,
     pub port: u16,
@@ -240,12 +255,17 @@ impl From<protobuf::ConnectionRequest> for ConnectionRequest {
             cluster_mode_enabled,
             request_timeout,
             connection_timeout,
+            tcp_nodelay: false,
+            tls_sni_hostname: None,
+            address_family: redis::AddressFamily::Auto,
             connection_retry_strategy,
             periodic_checks,
             pubsub_subscriptions,
             inflight_requests_limit,
             otel_endpoint,
             otel_span_flush_interval_ms,
+            redirection_observer: None,
+            connection_observer: None,
         }
     }
 }
@@ -3141,6 +3141,23 @@ mod tests {
         .is_err());
     }
 
+    #[test]
+    fn integer_replies_are_not_converted() {
+        for cmd in [
+            redis::cmd("DEL").arg("key1").arg("key2").clone(),
+            redis::cmd("SETNX").arg("key").arg("value").clone(),
+            redis::cmd("STRLEN").arg("key").clone(),
+            redis::cmd("LLEN").arg("key").clone(),
+        ] {
+            let expected_type = expected_type_for_cmd(&cmd);
+            assert!(expected_type.is_none());
+            assert_eq!(
+                convert_to_expected_type(Value::Int(1), expected_type),
+                Ok(Value::Int(1))
+            );
+        }
+    }
+
     #[test]
     fn convert_smove_to_bool() {
         assert!(matches!(
@@ -35,6 +35,17 @@ pub(crate) const EXPONENT_BASE: u32 = 2;
 pub(crate) const FACTOR: u32 = 100;
 pub(crate) const NUMBER_OF_RETRIES: u32 = 5;
 
+/// The strategy used to reconnect when none is configured.
+impl Default for ConnectionRetryStrategy {
+    fn default() -> Self {
+        ConnectionRetryStrategy {
+            exponent_base: EXPONENT_BASE,
+            factor: FACTOR,
+            number_of_retries: NUMBER_OF_RETRIES,
+        }
+    }
+}
+
 pub(crate) fn get_exponential_backoff(
     exponent_base: u32,
     factor: u32,
@@ -29,6 +29,15 @@ pub fn get_script(hash: &str) -> Option<Arc<BytesMut>> {
     CONTAINER.lock().unwrap().get(hash).cloned()
 }
 
+pub fn get_all_scripts() -> Vec<(String, Arc<BytesMut>)> {
+    CONTAINER
+        .lock()
+        .unwrap()
+        .iter()
+        .map(|(hash, script)| (hash.clone(), script.clone()))
+        .collect()
+}
+
 pub fn remove_script(hash: &str) {
     log_info(
         "script lifetime",
@@ -3,6 +3,7 @@
  */
 use once_cell::sync::OnceCell;
 use std::{
+    io::Write,
     path::{Path, PathBuf},
     sync::RwLock,
 };
@@ -80,6 +81,15 @@ impl LazyRollingFileAppender {
             filename_prefix: filename_prefix.as_ref().to_path_buf(),
         }
     }
+
+    /// Flush the log file, if it was opened.
+    fn flush(&self) -> std::io::Result<()> {
+        use tracing_subscriber::fmt::writer::MakeWriter;
+        match self.file_appender.get() {
+            Some(file_appender) => file_appender.make_writer().flush(),
+            None => Ok(()),
+        }
+    }
 }
 
 impl<'a> tracing_subscriber::fmt::writer::MakeWriter<'a> for LazyRollingFileAppender {
@@ -227,6 +237,20 @@ pub fn init(minimal_level: Option<Level>, file_name: Option<&str>) -> Level {
     level
 }
 
+/// Flush the logs written so far to their sink, the console or the log file, e.g. before the process exits.
+/// The logger is global and isn't torn down, so logging keeps working after the call. Flushing is idempotent,
+/// and it does nothing if the logger wasn't initialized.
+pub fn flush() {
+    if let Some(reloads) = INITIATE_ONCE.init_once.get() {
+        let _ = reloads
+            .file_reload
+            .read()
+            .expect("error reading file appender")
+            .with_current(|layer| layer.inner().writer().flush());
+    }
+    let _ = std::io::stdout().flush();
+}
+
 macro_rules! create_log {
     ($name:ident, $uppercase_level:tt) => {
         pub fn $name<Message: AsRef<str>, Identifier: AsRef<str>>(
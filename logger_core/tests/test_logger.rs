@@ -7,7 +7,7 @@ use test_env_helpers::*;
 #[after_all]
 #[before_all]
 mod tests {
-    use logger_core::{init, log_debug, log_trace};
+    use logger_core::{flush, init, log_debug, log_trace};
     use rand::{distributions::Alphanumeric, Rng};
     use std::{
         fs::{read_dir, read_to_string, remove_dir_all},
@@ -120,6 +120,20 @@ mod tests {
         assert!(!contents.contains("boo"), "Contents: {}", contents);
     }
 
+    #[test]
+    fn log_to_file_works_after_flush() {
+        let identifier = generate_random_string(10);
+        init(Some(logger_core::Level::Trace), Some(identifier.as_str()));
+        log_debug(identifier.clone(), "foo");
+        flush();
+        flush();
+        assert!(get_file_contents(identifier.as_str()).contains("foo"));
+        log_debug(identifier.clone(), "boo");
+        flush();
+        let contents = get_file_contents(identifier.as_str());
+        assert!(contents.contains("boo"), "Contents: {}", contents);
+    }
+
     fn clean() -> Result<(), std::io::Error> {
         remove_dir_all(FILE_DIRECTORY)
     }